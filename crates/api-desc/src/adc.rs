@@ -0,0 +1,51 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::*;
+
+pub(crate) fn new() -> Item {
+    let docs = docs! {
+        /// Analog-to-digital converter operations.
+    };
+    let name = "adc".into();
+    let items = vec![
+        item! {
+            /// Returns how many analog inputs are on the device.
+            fn count "ac" {} -> {
+                /// How many analog inputs are on the device.
+                cnt: usize,
+            }
+        },
+        item! {
+            /// Samples an analog input.
+            fn read "ar" {
+                /// Index of the analog input to sample.
+                adc: usize,
+            } -> {
+                /// The sample normalized to 16 bits, or a negative value on error.
+                ///
+                /// A sample of 0 is ground and 65535 is the reference voltage.
+                res: isize,
+            }
+        },
+        item! {
+            /// Measures the supply voltage.
+            fn read_vdd "av" {} -> {
+                /// The supply voltage in millivolts, or a negative value on error.
+                res: isize,
+            }
+        },
+    ];
+    Item::Mod(Mod { docs, name, items })
+}
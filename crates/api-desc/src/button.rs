@@ -29,6 +29,17 @@ pub(crate) fn new() -> Item {
                 Pressed,
             }
         },
+        item! {
+            /// Describes a button gesture.
+            enum Gesture {
+                /// The button was pressed and released once.
+                Single,
+                /// The button was held pressed for at least the long-press threshold.
+                Long,
+                /// The button was pressed twice within the double-press window.
+                Double,
+            }
+        },
         item! {
             /// Returns how many buttons are on the device.
             fn count "bc" {} -> {
@@ -45,12 +56,37 @@ pub(crate) fn new() -> Item {
                 /// Function called on button events.
                 ///
                 /// The function takes its opaque `data` and the new button `state` as arguments.
+                /// If gestures are configured for the button, the function is called with a
+                /// `Gesture` instead of a `State`.
                 handler_func: fn { data: *mut u8, state: usize },
 
                 /// The opaque data to use when calling the handler function.
                 handler_data: *mut u8,
             } -> {}
         },
+        item! {
+            /// Configures gesture recognition for a button.
+            ///
+            /// When configured, the button handler is called on gestures instead of state changes.
+            fn gesture "bg" {
+                /// Index of the button to configure.
+                button: usize,
+
+                /// Minimum duration in milliseconds of a long press.
+                ///
+                /// Zero disables gesture recognition for the button.
+                long: usize,
+
+                /// Maximum duration in milliseconds between a release and the next press for a
+                /// double press.
+                ///
+                /// Zero disables double presses such that single presses are reported on release.
+                double: usize,
+            } -> {
+                /// Zero on success, -1 if there are no timers available.
+                res: isize,
+            }
+        },
         item! {
             /// Unregister handlers for button events.
             fn unregister "bu" {
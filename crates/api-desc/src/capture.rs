@@ -0,0 +1,82 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::*;
+
+pub(crate) fn new() -> Item {
+    let docs = docs! {
+        /// Input capture operations.
+        ///
+        /// The GPIO must be configured as an input with the `gpio` module.
+    };
+    let name = "capture".into();
+    let items = vec![
+        item! {
+            /// Starts measuring the frequency and duty cycle of the signal on a GPIO.
+            ///
+            /// The measurement is executed by the board. Register a handler to be notified when
+            /// the measurement is done.
+            fn start "cs" {
+                /// Index of the GPIO.
+                gpio: usize,
+            } -> {
+                /// Zero on success, negative on error.
+                ///
+                /// It is an error to start a measurement while another one is running.
+                res: isize,
+            }
+        },
+        item! {
+            /// Reads the result of the done measurement of a GPIO.
+            fn result "cr" {
+                /// Index of the GPIO.
+                gpio: usize,
+
+                /// Pointer to the 8 bytes of the measurement.
+                ///
+                /// The period and the high time of the signal in nanoseconds as little-endian u32.
+                ptr: *mut u8,
+            } -> {
+                /// One if the signal was measured, zero if there is no signal, and negative on
+                /// error.
+                ///
+                /// It is an error to read the result of a measurement that is not done.
+                res: isize,
+            }
+        },
+        item! {
+            /// Register a handler for input capture events.
+            fn register "ce" {
+                /// Index of the GPIO to listen to.
+                gpio: usize,
+
+                /// Function called when a measurement is done.
+                ///
+                /// The function takes its opaque `data` as argument.
+                handler_func: fn { data: *mut u8 },
+
+                /// The opaque data to use when calling the handler function.
+                handler_data: *mut u8,
+            } -> {}
+        },
+        item! {
+            /// Unregister handlers for input capture events.
+            fn unregister "cd" {
+                /// Index of the GPIO to stop listening to.
+                gpio: usize,
+            } -> {}
+        },
+    ];
+    Item::Mod(Mod { docs, name, items })
+}
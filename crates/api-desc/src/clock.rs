@@ -77,6 +77,16 @@ pub(crate) fn new() -> Item {
                 id: usize,
             } -> {}
         },
+        item! {
+            /// Returns the time since the platform started.
+            fn uptime "tu" {
+                /// Pointer to the 8 bytes of the uptime in micro-seconds (little-endian).
+                ptr: *mut u8,
+            } -> {
+                /// Zero on success, negative on error.
+                res: isize,
+            }
+        },
     ];
     Item::Mod(Mod { docs, name, items })
 }
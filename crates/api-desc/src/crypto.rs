@@ -15,6 +15,8 @@
 use crate::*;
 
 mod ccm;
+mod crc;
+mod ecdh;
 mod gcm;
 mod hash;
 
@@ -35,6 +37,8 @@ pub(crate) fn new() -> Item {
             }
         },
         ccm::new(),
+        crc::new(),
+        ecdh::new(),
         gcm::new(),
         hash::new(),
     ];
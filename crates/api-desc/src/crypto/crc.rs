@@ -0,0 +1,70 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::*;
+
+pub(crate) fn new() -> Item {
+    let docs = docs! {
+        /// Cyclic redundancy checks.
+        ///
+        /// CRC-32 is the one used by Ethernet, zlib, and PNG (also known as CRC-32/ISO-HDLC).
+        /// CRC-16/CCITT has initial value 0xffff and no final xor (also known as CRC-16/IBM-3740
+        /// or CRC-16/CCITT-FALSE). Checksums are written in little-endian.
+    };
+    let name = "crc".into();
+    let items = vec![
+        item! {
+            /// Whether CRCs are supported.
+            fn is_supported "crs" {} -> {
+                /// 1 if supported, 0 otherwise.
+                supported: usize,
+            }
+        },
+        item! {
+            /// Computes the CRC-32 of some data.
+            fn crc32 "crw" {
+                /// The pointer to the data.
+                data: *const u8,
+
+                /// The length of the data.
+                length: usize,
+
+                /// The 4 bytes checksum.
+                crc: *mut u8,
+            } -> {
+                /// Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+                /// otherwise.
+                res: isize,
+            }
+        },
+        item! {
+            /// Computes the CRC-16/CCITT of some data.
+            fn crc16_ccitt "crh" {
+                /// The pointer to the data.
+                data: *const u8,
+
+                /// The length of the data.
+                length: usize,
+
+                /// The 2 bytes checksum.
+                crc: *mut u8,
+            } -> {
+                /// Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+                /// otherwise.
+                res: isize,
+            }
+        },
+    ];
+    Item::Mod(Mod { docs, name, items })
+}
@@ -0,0 +1,67 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::*;
+
+pub(crate) fn new() -> Item {
+    let docs = docs! {
+        /// ECDH over P-256.
+        ///
+        /// Private keys are 32 bytes big-endian scalars. Public keys are 64 bytes uncompressed
+        /// points (the big-endian X coordinate followed by the Y coordinate). Shared secrets are
+        /// the 32 bytes X coordinate of the product point (as specified by SEC1).
+    };
+    let name = "ecdh".into();
+    let items = vec![
+        item! {
+            /// Whether ECDH over P-256 is supported.
+            fn is_supported "ces" {} -> {
+                /// 1 if supported, 0 otherwise.
+                supported: usize,
+            }
+        },
+        item! {
+            /// Generates a random key pair.
+            fn generate_keypair "ceg" {
+                /// The 32 bytes private key.
+                private: *mut u8,
+
+                /// The 64 bytes public key.
+                public: *mut u8,
+            } -> {
+                /// Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+                /// otherwise.
+                res: isize,
+            }
+        },
+        item! {
+            /// Computes the shared secret of a private key and a peer public key.
+            fn agree "cea" {
+                /// The 32 bytes private key.
+                private: *const u8,
+
+                /// The 64 bytes public key of the peer.
+                public: *const u8,
+
+                /// The 32 bytes shared secret.
+                shared: *mut u8,
+            } -> {
+                /// Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+                /// otherwise.
+                res: isize,
+            }
+        },
+    ];
+    Item::Mod(Mod { docs, name, items })
+}
@@ -34,6 +34,18 @@ pub(crate) fn new() -> Item {
                 len: usize,
             } -> {}
         },
+        item! {
+            /// Reads the heap statistics of the platform.
+            fn heap_stats "dh" {
+                /// Pointer to the 12 bytes of statistics.
+                ///
+                /// The used, free, and high water bytes as little-endian u32.
+                ptr: *mut u8,
+            } -> {
+                /// Zero on success, negative on error.
+                res: isize,
+            }
+        },
         item! {
             /// Exits the platform with an error code.
             ///
@@ -44,6 +56,50 @@ pub(crate) fn new() -> Item {
                 code: usize,
             } -> {}
         },
+        item! {
+            /// Logging levels from the least to the most verbose.
+            enum Level {
+                Error,
+                Warn,
+                Info,
+                Debug,
+                Trace,
+            }
+        },
+        item! {
+            /// Sets the most verbose level of the platform logs.
+            ///
+            /// Levels disabled when the platform was compiled can't be enabled.
+            fn set_log_level "dl" {
+                /// The most verbose level to log.
+                ///
+                /// Valid values are defined by [`Level`](super::Level).
+                level: usize,
+            } -> {
+                /// Zero on success, negative on error.
+                res: isize,
+            }
+        },
+        item! {
+            /// Logs a message at a given level in the platform logs.
+            ///
+            /// The message interleaves with the platform logs and is subject to the same level
+            /// filter. If logging is disabled then this is a no-op.
+            fn log "dg" {
+                /// The level of the message.
+                ///
+                /// Valid values are defined by [`Level`](super::Level).
+                level: usize,
+
+                /// The message to log.
+                ///
+                /// Traps if the message is not valid UTF-8.
+                ptr: *const u8,
+
+                /// The length of the message in bytes.
+                len: usize,
+            } -> {}
+        },
     ];
     Item::Mod(Mod { docs, name, items })
 }
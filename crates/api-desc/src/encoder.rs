@@ -0,0 +1,77 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::*;
+
+pub(crate) fn new() -> Item {
+    let docs = docs! {
+        /// Quadrature encoder operations.
+    };
+    let name = "encoder".into();
+    let items = vec![
+        item! {
+            /// Returns how many encoders are on the device.
+            fn count "ec" {} -> {
+                /// How many encoders are on the device.
+                cnt: usize,
+            }
+        },
+        item! {
+            /// Returns the current position of an encoder.
+            fn position "ep" {
+                /// Index of the encoder.
+                encoder: usize,
+            } -> {
+                /// Current position in steps.
+                position: isize,
+            }
+        },
+        item! {
+            /// Starts homing an encoder.
+            ///
+            /// The position is zeroed at the next index pulse. The shaft must be rotated while
+            /// homing. Register a handler to be notified when homing is done.
+            fn home "eh" {
+                /// Index of the encoder.
+                encoder: usize,
+            } -> {
+                /// Error code: 0 on success, -1 on error
+                res: isize,
+            }
+        },
+        item! {
+            /// Register a handler for encoder events.
+            fn register "er" {
+                /// Index of the encoder to listen to.
+                encoder: usize,
+
+                /// Function called when homing is done.
+                ///
+                /// The function takes its opaque `data` as argument.
+                handler_func: fn { data: *mut u8 },
+
+                /// The opaque data to use when calling the handler function.
+                handler_data: *mut u8,
+            } -> {}
+        },
+        item! {
+            /// Unregister handlers for encoder events.
+            fn unregister "eu" {
+                /// Index of the encoder to stop listening to.
+                encoder: usize,
+            } -> {}
+        },
+    ];
+    Item::Mod(Mod { docs, name, items })
+}
@@ -0,0 +1,80 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::*;
+
+pub(crate) fn new() -> Item {
+    let docs = docs! {
+        /// General-purpose input/output operations.
+    };
+    let name = "gpio".into();
+    let items = vec![
+        item! {
+            /// Returns how many GPIOs are on the device.
+            fn count "gc" {} -> {
+                /// How many GPIOs are on the device.
+                cnt: usize,
+            }
+        },
+        item! {
+            /// Describes the configuration of a GPIO.
+            enum Mode {
+                /// Input with an internal pull-up resistor.
+                InputPullUp,
+
+                /// Input with an internal pull-down resistor.
+                InputPullDown,
+
+                /// Output driving both levels.
+                OutputPushPull,
+
+                /// Output driving only the low level.
+                OutputOpenDrain,
+            }
+        },
+        item! {
+            /// Configures a GPIO.
+            fn configure "gf" {
+                /// Index of the GPIO to configure.
+                gpio: usize,
+
+                /// The new mode of the GPIO.
+                ///
+                /// Valid values are defined by [`Mode`](super::Mode).
+                mode: usize,
+            } -> {}
+        },
+        item! {
+            /// Reads the level of a configured GPIO.
+            fn read "gr" {
+                /// Index of the GPIO to read.
+                gpio: usize,
+            } -> {
+                /// 0 for low and 1 for high.
+                level: usize,
+            }
+        },
+        item! {
+            /// Writes the level of a GPIO configured as output.
+            fn write "gw" {
+                /// Index of the GPIO to write.
+                gpio: usize,
+
+                /// 0 for low and 1 for high.
+                level: usize,
+            } -> {}
+        },
+    ];
+    Item::Mod(Mod { docs, name, items })
+}
@@ -0,0 +1,100 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::*;
+
+pub(crate) fn new() -> Item {
+    let docs = docs! {
+        /// Software I2C operations.
+        ///
+        /// The bus is bit-banged on 2 GPIOs. It is slower than a hardware bus and the CPU is busy
+        /// for the whole transfer.
+    };
+    let name = "i2c_sw".into();
+    let items = vec![
+        item! {
+            /// Configures the bus.
+            ///
+            /// This must be called before any transfer.
+            fn configure "isc" {
+                /// Index of the GPIO of the data line.
+                sda: usize,
+
+                /// Index of the GPIO of the clock line.
+                scl: usize,
+
+                /// Clock rate in Hertz.
+                frequency: usize,
+            } -> {
+                /// Zero on success, negative on error.
+                res: isize,
+            }
+        },
+        item! {
+            /// Reads bytes from a device into a buffer.
+            fn read "isr" {
+                /// 7-bits address of the device.
+                addr: usize,
+
+                /// Address of the buffer.
+                ptr: *mut u8,
+
+                /// Length of the buffer in bytes.
+                len: usize,
+            } -> {
+                /// Zero on success, negative on error.
+                res: isize,
+            }
+        },
+        item! {
+            /// Writes bytes to a device from a buffer.
+            fn write "isw" {
+                /// 7-bits address of the device.
+                addr: usize,
+
+                /// Address of the buffer.
+                ptr: *const u8,
+
+                /// Length of the buffer in bytes.
+                len: usize,
+            } -> {
+                /// Zero on success, negative on error.
+                res: isize,
+            }
+        },
+        item! {
+            /// Writes then reads bytes from a device with a repeated start condition.
+            fn write_read "isx" {
+                /// 7-bits address of the device.
+                addr: usize,
+
+                /// Address of the buffer to write.
+                wptr: *const u8,
+
+                /// Length of the buffer to write in bytes.
+                wlen: usize,
+
+                /// Address of the buffer to read.
+                rptr: *mut u8,
+
+                /// Length of the buffer to read in bytes.
+                rlen: usize,
+            } -> {
+                /// Zero on success, negative on error.
+                res: isize,
+            }
+        },
+    ];
+    Item::Mod(Mod { docs, name, items })
+}
@@ -57,6 +57,18 @@ pub(crate) fn new() -> Item {
                 status: usize,
             } -> {}
         },
+        item! {
+            /// Sets a LED brightness.
+            ///
+            /// LEDs without brightness support are on if the brightness is at least 128.
+            fn set_brightness "lb" {
+                /// Index of the LED to set.
+                led: usize,
+
+                /// 0 for off and 255 for fully on.
+                brightness: usize,
+            } -> {}
+        },
     ];
     Item::Mod(Mod { docs, name, items })
 }
@@ -0,0 +1,68 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::*;
+
+pub(crate) fn new() -> Item {
+    let docs = docs! {
+        /// Addressable LED ring operations.
+        ///
+        /// Animations are executed by the board, such that the applet only needs to select them.
+    };
+    let name = "led_ring".into();
+    let items = vec![
+        item! {
+            /// Animation patterns.
+            enum Pattern {
+                /// A single lit pixel going around the ring.
+                Spinner,
+                /// An arc of lit pixels growing around the ring and restarting when full.
+                Progress,
+                /// A rainbow rotating around the ring (the color is ignored).
+                Rainbow,
+            }
+        },
+        item! {
+            /// Returns the number of pixels of the ring.
+            fn count "lrc" {} -> {
+                /// How many pixels are available (zero if there is no ring).
+                cnt: usize,
+            }
+        },
+        item! {
+            /// Starts an animation, replacing the current one if any.
+            fn animate "lra" {
+                /// The animation pattern.
+                pattern: usize,
+
+                /// The number of steps per second (zero freezes the first frame).
+                speed: usize,
+
+                /// The color of the lit pixels as 0xRRGGBB.
+                color: usize,
+            } -> {
+                /// Zero on success, negative on error.
+                res: isize,
+            }
+        },
+        item! {
+            /// Stops the current animation and turns all pixels off.
+            fn stop "lrs" {} -> {
+                /// Zero on success, negative on error.
+                res: isize,
+            }
+        },
+    ];
+    Item::Mod(Mod { docs, name, items })
+}
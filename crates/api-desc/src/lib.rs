@@ -18,16 +18,30 @@ use clap::ValueEnum;
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::{format_ident, quote};
 
+mod adc;
 mod button;
+mod capture;
 mod clock;
 mod crypto;
 mod debug;
+mod encoder;
+mod gpio;
+mod i2c_sw;
 mod id;
 mod led;
+mod led_ring;
 mod macros;
+mod magnetometer;
+mod platform;
+mod power;
+mod pwm;
 mod rng;
 mod scheduling;
+mod stepper;
 mod store;
+mod temp;
+mod typescript;
+mod uart;
 mod usb;
 
 pub use id::{Id, Name};
@@ -38,14 +52,27 @@ pub struct Api(Vec<Item>);
 impl Default for Api {
     fn default() -> Self {
         Api(vec![
+            adc::new(),
             button::new(),
+            capture::new(),
             clock::new(),
             crypto::new(),
             debug::new(),
+            encoder::new(),
+            gpio::new(),
+            i2c_sw::new(),
             led::new(),
+            led_ring::new(),
+            magnetometer::new(),
+            platform::new(),
+            power::new(),
+            pwm::new(),
             rng::new(),
             scheduling::new(),
+            stepper::new(),
             store::new(),
+            temp::new(),
+            uart::new(),
             usb::new(),
             item! {
                 /// Board-specific syscalls.
@@ -57,32 +84,102 @@ impl Default for Api {
     }
 }
 
+/// Version of the applet API.
+///
+/// This must be incremented when the interface between applets and platforms changes in an
+/// incompatible way.
+pub const API_VERSION: u32 = 1;
+
 #[derive(Copy, Clone, ValueEnum)]
 pub enum Lang {
     C,
     Assemblyscript,
+    Go,
+    TypescriptDecl,
 }
 
 impl Api {
     pub fn host(&self) -> TokenStream {
-        Mod::body(None, &self.0)
+        let hash = self.hash();
+        let body = Mod::body(None, &self.0);
+        quote! {
+            /// Hash of the applet API.
+            ///
+            /// This can be used to check compatibility between the platform and applets.
+            pub const API_HASH: u32 = #hash;
+            /// Version of the applet API.
+            ///
+            /// This is returned by [`platform::api_version`](platform::api_version::Sig).
+            pub const API_VERSION: u32 = #API_VERSION;
+            #body
+        }
+    }
+
+    /// Returns the FNV-1a hash of the applet API.
+    pub fn hash(&self) -> u32 {
+        let mut hash = 0x811c9dc5u32;
+        for byte in self.wasm_rust().to_string().bytes() {
+            hash = (hash ^ byte as u32).wrapping_mul(0x01000193);
+        }
+        hash
     }
 
     pub fn wasm(&self, output: &mut dyn Write, lang: Lang) -> std::io::Result<()> {
         match lang {
-            Lang::C => unimplemented!(),
+            Lang::C => self.wasm_c(output),
             Lang::Assemblyscript => self.wasm_assemblyscript(output),
+            Lang::Go => self.wasm_go(output),
+            Lang::TypescriptDecl => self.wasm_typescript_decl(output),
         }
     }
 
     pub fn wasm_rust(&self) -> TokenStream {
         let items: Vec<_> = self.0.iter().map(|x| x.wasm_rust()).collect();
-        quote!(#(#items)*)
+        quote! {
+            /// Version of the applet API.
+            ///
+            /// This can be compared with [`platform::api_version()`] to check compatibility.
+            pub const API_VERSION: u32 = #API_VERSION;
+            #(#items)*
+        }
     }
 
     pub fn wasm_assemblyscript(&self, output: &mut dyn Write) -> std::io::Result<()> {
+        writeln!(output, "// Version of the applet API.")?;
+        writeln!(output, "export const API_VERSION: u32 = {API_VERSION};")?;
+        writeln!(output)?;
         write_items(output, &self.0, |output, item| item.wasm_assemblyscript(output, &Path::Empty))
     }
+
+    pub fn wasm_c(&self, output: &mut dyn Write) -> std::io::Result<()> {
+        writeln!(output, "#ifndef WASEFIRE_API_H")?;
+        writeln!(output, "#define WASEFIRE_API_H")?;
+        writeln!(output)?;
+        writeln!(output, "#include <stdint.h>")?;
+        writeln!(output)?;
+        writeln!(output, "#ifdef __wasm__")?;
+        writeln!(output, "#define WASEFIRE_IMPORT(name) \\")?;
+        writeln!(output, "  __attribute__((import_module(\"env\"), import_name(name)))")?;
+        writeln!(output, "#else")?;
+        writeln!(output, "#define WASEFIRE_IMPORT(name)")?;
+        writeln!(output, "#endif")?;
+        writeln!(output)?;
+        writeln!(output, "// Version of the applet API.")?;
+        writeln!(output, "#define WASEFIRE_API_VERSION {API_VERSION}")?;
+        writeln!(output)?;
+        write_items(output, &self.0, |output, item| item.wasm_c(output, &Path::Empty))?;
+        writeln!(output)?;
+        writeln!(output, "#endif  // WASEFIRE_API_H")
+    }
+
+    pub fn wasm_go(&self, output: &mut dyn Write) -> std::io::Result<()> {
+        writeln!(output, "package api")?;
+        writeln!(output)?;
+        writeln!(output, "// Version of the applet API.")?;
+        writeln!(output, "const ApiVersion uint32 = {API_VERSION}")?;
+        writeln!(output)?;
+        write_items(output, &self.0, |output, item| item.wasm_go(output, &Path::Empty, &self.0))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -168,6 +265,23 @@ impl Item {
             Item::Mod(x) => x.wasm_assemblyscript(output, path),
         }
     }
+
+    fn wasm_c(&self, output: &mut dyn Write, path: &Path) -> std::io::Result<()> {
+        match self {
+            Item::Enum(x) => x.wasm_c(output, path),
+            Item::Fn(x) => x.wasm_c(output, path),
+            Item::Mod(x) => x.wasm_c(output, path),
+        }
+    }
+
+    /// Writes the Go bindings of an item given the items of its module.
+    fn wasm_go(&self, output: &mut dyn Write, path: &Path, items: &[Item]) -> std::io::Result<()> {
+        match self {
+            Item::Enum(x) => x.wasm_go(output, path),
+            Item::Fn(x) => x.wasm_go(output, path, items),
+            Item::Mod(x) => x.wasm_go(output, path),
+        }
+    }
 }
 
 impl Enum {
@@ -217,7 +331,7 @@ impl Enum {
             impl TryFrom<u32> for #name {
                 type Error = ();
 
-                fn try_from(x: u32) -> Result<Self, Self::Error> {
+                fn try_from(x: u32) -> Result<Self, ()> {
                     if x < #num_variants as u32 {
                         Ok(unsafe { core::mem::transmute(x) })
                     } else {
@@ -235,6 +349,36 @@ impl Enum {
         write_items(output, variants, |output, variant| variant.wasm_assemblyscript(output, path))?;
         writeln!(output, "{path:#}}}")
     }
+
+    fn wasm_c(&self, output: &mut dyn Write, path: &Path) -> std::io::Result<()> {
+        let Enum { docs, name, variants } = self;
+        let name = format!("{path}{}", snake(name));
+        write_line_docs(output, docs, "")?;
+        writeln!(output, "enum {name} {{")?;
+        for Variant { docs, name: variant } in variants {
+            write_line_docs(output, docs, "  ")?;
+            writeln!(output, "  {}_{},", name.to_uppercase(), snake(variant).to_uppercase())?;
+        }
+        writeln!(output, "}};")
+    }
+
+    fn wasm_go(&self, output: &mut dyn Write, path: &Path) -> std::io::Result<()> {
+        let Enum { docs, name, variants } = self;
+        let name = go_name(path, name);
+        write_line_docs(output, docs, "")?;
+        writeln!(output, "type {name} uint32")?;
+        writeln!(output)?;
+        writeln!(output, "const (")?;
+        for (i, Variant { docs, name: variant }) in variants.iter().enumerate() {
+            write_line_docs(output, docs, "\t")?;
+            write!(output, "\t{name}{variant}")?;
+            if i == 0 {
+                write!(output, " {name} = iota")?;
+            }
+            writeln!(output)?;
+        }
+        writeln!(output, ")")
+    }
 }
 
 impl Fn {
@@ -242,6 +386,10 @@ impl Fn {
         let Fn { docs, name, link, params, results } = self;
         let name = format_ident!("{}", name);
         let doc = format!("Module of [`{name}`]({name}::Sig).");
+        let error = match results.iter().position(|x| x.type_.is_signed()) {
+            Some(i) => quote!(Some(#i)),
+            None => quote!(None),
+        };
         let params: Vec<_> = params.iter().map(|x| x.host()).collect();
         let results: Vec<_> = results.iter().map(|x| x.host()).collect();
         quote! {
@@ -269,6 +417,7 @@ impl Fn {
                     const NAME: &'static str = #link;
                     type Params = Params;
                     type Results = Results;
+                    const ERROR: Option<usize> = #error;
                 }
             }
         }
@@ -327,6 +476,66 @@ impl Fn {
         }
         writeln!(output)
     }
+
+    fn wasm_c(&self, output: &mut dyn Write, path: &Path) -> std::io::Result<()> {
+        let Fn { docs, name, link, params, results } = self;
+        let name = format!("{path}{name}");
+        for (fields, kind, doc) in
+            [(params, "params", "Parameters"), (results, "results", "Results")]
+        {
+            if fields.is_empty() {
+                continue;
+            }
+            writeln!(output, "// {doc} of {name}().")?;
+            writeln!(output, "typedef struct {{")?;
+            for Field { docs, name, type_ } in fields {
+                write_line_docs(output, docs, "  ")?;
+                writeln!(output, "  {};", type_.wasm_c(&c_ident(name)))?;
+            }
+            writeln!(output, "}} {name}_{kind}_t;")?;
+            writeln!(output)?;
+        }
+        write_line_docs(output, docs, "")?;
+        writeln!(output, "WASEFIRE_IMPORT(\"{link}\")")?;
+        let result = match &results[..] {
+            [] => "void".to_string(),
+            [result] => result.type_.wasm_c(""),
+            _ => unimplemented!("multi-value is not supported in C"),
+        };
+        write!(output, "extern {} {name}(", result.trim_end())?;
+        if params.is_empty() {
+            write!(output, "void")?;
+        } else {
+            let params: Vec<_> = params.iter().map(|x| x.type_.wasm_c(&c_ident(&x.name))).collect();
+            write!(output, "{}", params.join(", "))?;
+        }
+        writeln!(output, ");")
+    }
+
+    fn wasm_go(&self, output: &mut dyn Write, path: &Path, items: &[Item]) -> std::io::Result<()> {
+        let Fn { docs, name, link, params, results } = self;
+        write_line_docs(output, docs, "")?;
+        if !docs.is_empty() {
+            writeln!(output, "//")?;
+        }
+        writeln!(output, "//go:wasmimport env {link}")?;
+        write!(output, "func {}(", go_fn_name(path, name, items))?;
+        if !params.is_empty() {
+            writeln!(output)?;
+            params.iter().try_for_each(|param| param.wasm_go(output))?;
+        }
+        write!(output, ")")?;
+        match &results[..] {
+            [] => (),
+            [result] => {
+                writeln!(output, " (")?;
+                result.wasm_go(output)?;
+                write!(output, ")")?;
+            }
+            _ => unimplemented!("multi-value is not supported in Go"),
+        }
+        writeln!(output)
+    }
 }
 
 impl Mod {
@@ -446,6 +655,28 @@ impl Mod {
         write_items(output, items, |output, item| item.wasm_assemblyscript(output, &inner_path))?;
         writeln!(output, "{path:#}// END OF MODULE {path}{name}")
     }
+
+    fn wasm_c(&self, output: &mut dyn Write, path: &Path) -> std::io::Result<()> {
+        let Mod { docs, name, items } = self;
+        writeln!(output, "// START OF MODULE {path}{name}")?;
+        write_line_docs(output, docs, "")?;
+        writeln!(output)?;
+        let inner_path = Path::Mod { name, prev: path };
+        write_items(output, items, |output, item| item.wasm_c(output, &inner_path))?;
+        writeln!(output)?;
+        writeln!(output, "// END OF MODULE {path}{name}")
+    }
+
+    fn wasm_go(&self, output: &mut dyn Write, path: &Path) -> std::io::Result<()> {
+        let Mod { docs, name, items } = self;
+        writeln!(output, "// START OF MODULE {path}{name}")?;
+        write_line_docs(output, docs, "")?;
+        writeln!(output)?;
+        let inner_path = Path::Mod { name, prev: path };
+        write_items(output, items, |output, item| item.wasm_go(output, &inner_path, items))?;
+        writeln!(output)?;
+        writeln!(output, "// END OF MODULE {path}{name}")
+    }
 }
 
 impl Field {
@@ -478,9 +709,21 @@ impl Field {
         type_.wasm_assemblyscript(output)?;
         writeln!(output, ",")
     }
+
+    fn wasm_go(&self, output: &mut dyn Write) -> std::io::Result<()> {
+        let Field { docs, name, type_ } = self;
+        write_line_docs(output, docs, "\t")?;
+        write!(output, "\t{name} ")?;
+        type_.wasm_go(output)?;
+        writeln!(output, ",")
+    }
 }
 
 impl Type {
+    fn is_signed(&self) -> bool {
+        matches!(self, Type::Integer { signed: true })
+    }
+
     fn wasm_rust(&self) -> TokenStream {
         match self {
             Type::Integer { signed: true } => quote!(isize),
@@ -510,6 +753,36 @@ impl Type {
             Type::Function { params: _ } => write!(output, "usize"),
         }
     }
+
+    /// Returns the C declaration of a variable of this type.
+    fn wasm_c(&self, name: &str) -> String {
+        match self {
+            Type::Integer { signed: true } => format!("int32_t {name}"),
+            Type::Integer { signed: false } => format!("uint32_t {name}"),
+            Type::Pointer { mutable, type_ } => {
+                let name = if *mutable { format!("*{name}") } else { format!("const *{name}") };
+                match type_ {
+                    None => format!("uint8_t {name}"),
+                    Some(x) => x.wasm_c(&name),
+                }
+            }
+            Type::Function { params } => {
+                let params: Vec<_> =
+                    params.iter().map(|x| x.type_.wasm_c(&c_ident(&x.name))).collect();
+                let params = if params.is_empty() { "void".to_string() } else { params.join(", ") };
+                format!("void (*{name})({params})")
+            }
+        }
+    }
+
+    fn wasm_go(&self, output: &mut dyn Write) -> std::io::Result<()> {
+        match self {
+            Type::Integer { signed: true } => write!(output, "int32"),
+            Type::Integer { signed: false } => write!(output, "uint32"),
+            Type::Pointer { mutable: _, type_: _ } => write!(output, "uintptr"),
+            Type::Function { params: _ } => write!(output, "uintptr"),
+        }
+    }
 }
 
 impl Variant {
@@ -571,6 +844,51 @@ fn write_docs(output: &mut dyn Write, docs: &[String], path: &Path) -> std::io::
     Ok(())
 }
 
+/// Escapes C keywords used as names.
+fn c_ident(name: &str) -> String {
+    match name {
+        "double" | "long" => format!("{name}_"),
+        _ => name.to_string(),
+    }
+}
+
+/// Converts a camel-case name to snake case.
+fn snake(input: &str) -> String {
+    let mut output = String::new();
+    for c in input.chars() {
+        if c.is_uppercase() && !output.is_empty() {
+            output.push('_');
+        }
+        output.extend(c.to_lowercase());
+    }
+    output
+}
+
+/// Writes line comments with the given indentation.
+fn write_line_docs(output: &mut dyn Write, docs: &[String], indent: &str) -> std::io::Result<()> {
+    for doc in docs {
+        writeln!(output, "{indent}//{doc}")?;
+    }
+    Ok(())
+}
+
+/// Returns the exported Go name of an item.
+fn go_name(path: &Path, name: &str) -> Ident {
+    camel(&format!("{path}{name}"))
+}
+
+/// Returns the exported Go name of a function given the items of its module.
+///
+/// Types and functions share the same namespace in Go. Functions named like an enum of their module
+/// (e.g. `button::gesture()` and `button::Gesture`) get a `Fn` suffix.
+fn go_fn_name(path: &Path, name: &str, items: &[Item]) -> Ident {
+    let result = go_name(path, name);
+    match items.iter().any(|x| matches!(x, Item::Enum(x) if go_name(path, &x.name) == result)) {
+        true => format_ident!("{result}Fn"),
+        false => result,
+    }
+}
+
 fn write_items<T>(
     output: &mut dyn Write, items: &[T],
     mut write: impl FnMut(&mut dyn Write, &T) -> std::io::Result<()>,
@@ -603,4 +921,32 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn go_names_are_unique() {
+        let mut output = Vec::new();
+        Api::default().wasm_go(&mut output).unwrap();
+        let mut seen = HashSet::new();
+        let mut in_const = false;
+        for line in String::from_utf8(output).unwrap().lines() {
+            let name = match line.split_once(' ') {
+                Some(("type" | "const", name)) if !name.starts_with('(') => name,
+                Some(("func", name)) => name,
+                _ if line == "const (" => {
+                    in_const = true;
+                    continue;
+                }
+                _ if line == ")" => {
+                    in_const = false;
+                    continue;
+                }
+                _ if in_const && !line.starts_with("\t//") => line.trim(),
+                _ => continue,
+            };
+            let name = name.split([' ', '(']).next().unwrap();
+            assert!(seen.insert(name.to_string()), "{name} is defined twice");
+        }
+        assert!(seen.contains("ButtonGesture"));
+        assert!(seen.contains("ButtonGestureFn"));
+    }
 }
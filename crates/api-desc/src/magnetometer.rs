@@ -0,0 +1,42 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::*;
+
+pub(crate) fn new() -> Item {
+    let docs = docs! {
+        /// Magnetometer operations.
+    };
+    let name = "magnetometer".into();
+    let items = vec![
+        item! {
+            /// Whether a magnetometer is available.
+            fn is_supported "fs" {} -> {
+                /// 1 if supported, 0 otherwise.
+                supported: usize,
+            }
+        },
+        item! {
+            /// Reads the magnetic field along the X, Y, and Z axes.
+            fn read "fr" {
+                /// Pointer to the 6 bytes of the raw field (X, Y, then Z as little-endian i16).
+                ptr: *mut u8,
+            } -> {
+                /// Zero on success, negative on error.
+                res: isize,
+            }
+        },
+    ];
+    Item::Mod(Mod { docs, name, items })
+}
@@ -0,0 +1,153 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::*;
+
+mod update;
+
+pub(crate) fn new() -> Item {
+    let docs = docs! {
+        /// Platform operations.
+    };
+    let name = "platform".into();
+    let items = vec![
+        item! {
+            /// Describes errors of platform operations.
+            enum Error {
+                /// The operation is not supported.
+                Unsupported,
+
+                /// A function pre-condition was broken.
+                InvalidArgument,
+            }
+        },
+        item! {
+            /// Board capabilities that applets may query.
+            ///
+            /// Each capability is a bit in the result of `capabilities()`: bit `1 << c` is set if
+            /// the board supports capability `c`.
+            enum Capability {
+                /// Analog-to-digital converters.
+                Adc,
+
+                /// AES-128-CCM.
+                Aes128Ccm,
+
+                /// AES-256-GCM.
+                Aes256Gcm,
+
+                /// Buttons.
+                Button,
+
+                /// CRC.
+                Crc,
+
+                /// ECDH.
+                Ecdh,
+
+                /// Rotary encoders.
+                Encoder,
+
+                /// GPIOs.
+                Gpio,
+
+                /// LEDs.
+                Led,
+
+                /// LED rings.
+                LedRing,
+
+                /// Magnetometer.
+                Magnetometer,
+
+                /// PWM outputs.
+                Pwm,
+
+                /// Random number generator.
+                Rng,
+
+                /// SHA-256.
+                Sha256,
+
+                /// Stepper motors.
+                Stepper,
+
+                /// Temperature sensor.
+                Temp,
+
+                /// Timers.
+                Timer,
+
+                /// UARTs.
+                Uart,
+
+                /// USB serial.
+                UsbSerial,
+            }
+        },
+        item! {
+            /// Reboots the platform.
+            ///
+            /// Pending storage operations complete before the platform resets.
+            fn reboot "pr" {} -> {
+                /// Complement of error number. Never returns on success.
+                res: isize,
+            }
+        },
+        item! {
+            /// Wipes the storage and reboots the platform.
+            ///
+            /// The whole storage is physically erased, including the store of all applets. The
+            /// applet manifest must grant the `factory-reset` permission and the applet must be
+            /// signed with the applet key of the runner.
+            fn factory_reset "pf" {} -> {
+                /// Complement of error number. Never returns on success.
+                res: isize,
+            }
+        },
+        item! {
+            /// Returns the capabilities supported by the board.
+            ///
+            /// Applets may use it to gracefully degrade on boards missing some capabilities.
+            fn capabilities "pc" {} -> {
+                /// Bit set of the supported capabilities.
+                capabilities: usize,
+            }
+        },
+        item! {
+            /// Returns the applet API version of the platform.
+            ///
+            /// Applets may compare it with the version they were compiled against to check
+            /// compatibility.
+            fn api_version "pv" {} -> {
+                /// The applet API version.
+                version: usize,
+            }
+        },
+        item! {
+            /// Reads the unique ID of the device.
+            ///
+            /// The ID is stable across resets but is not a secret.
+            fn device_id "pi" {
+                /// Pointer to the 8 bytes of the device ID.
+                ptr: *mut u8,
+            } -> {
+                /// Complement of error number.
+                res: isize,
+            }
+        },
+        update::new(),
+    ];
+    Item::Mod(Mod { docs, name, items })
+}
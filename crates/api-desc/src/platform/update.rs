@@ -0,0 +1,87 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::*;
+
+pub(crate) fn new() -> Item {
+    let docs = docs! {
+        /// Firmware updates.
+        ///
+        /// The new firmware is written to a staging bank, its Ed25519 signature is verified, and
+        /// it is swapped in on the next reboot. The firmware ends with its version as a 32-bit
+        /// little-endian integer, which must not be older than the last applied firmware.
+        ///
+        /// The applet manifest must grant the `update` permission and the applet must be signed
+        /// with the applet key of the runner.
+    };
+    let name = "update".into();
+    let items = vec![
+        item! {
+            /// Whether firmware updates are supported.
+            fn is_supported "pus" {} -> {
+                /// 1 if supported, 0 otherwise.
+                supported: usize,
+            }
+        },
+        item! {
+            /// Starts a firmware update.
+            ///
+            /// Any update in progress is discarded and the staging bank is erased.
+            fn start "pub" {} -> {
+                /// Zero on success, bitwise complement of [`Error`](crate::platform::Error)
+                /// otherwise.
+                res: isize,
+            }
+        },
+        item! {
+            /// Writes a chunk of the firmware at an offset of the staging bank.
+            fn write "puw" {
+                /// The offset in the staging bank.
+                offset: usize,
+
+                /// The chunk.
+                ptr: *const u8,
+
+                /// The length of the chunk.
+                len: usize,
+            } -> {
+                /// Zero on success, bitwise complement of [`Error`](crate::platform::Error)
+                /// otherwise.
+                res: isize,
+            }
+        },
+        item! {
+            /// Verifies the signature and version of the written firmware.
+            ///
+            /// The update must be started again if the signature is invalid or the version is
+            /// older than the last applied firmware.
+            fn finalize "puf" {
+                /// The 64 bytes Ed25519 signature of the firmware.
+                signature: *const u8,
+            } -> {
+                /// Zero on success, bitwise complement of [`Error`](crate::platform::Error)
+                /// otherwise.
+                res: isize,
+            }
+        },
+        item! {
+            /// Applies the finalized firmware update and reboots the platform.
+            fn apply "pua" {} -> {
+                /// Complement of error number. Never returns on success.
+                res: isize,
+            }
+        },
+    ];
+    Item::Mod(Mod { docs, name, items })
+}
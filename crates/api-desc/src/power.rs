@@ -0,0 +1,80 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::*;
+
+pub(crate) fn new() -> Item {
+    let docs = docs! {
+        /// Peripheral power operations.
+        ///
+        /// Peripherals are identified by their kind and an index (only used for timers, zero
+        /// otherwise).
+    };
+    let name = "power".into();
+    let items = vec![
+        item! {
+            /// Describes errors controlling peripheral power.
+            enum Error {
+                /// The peripheral is invalid or not supported.
+                Invalid,
+                /// The peripheral is in use.
+                Busy,
+            }
+        },
+        item! {
+            /// Kinds of peripherals.
+            enum Peripheral {
+                /// The analog-to-digital converters.
+                Adc,
+                /// The magnetometer.
+                Magnetometer,
+                /// The random number generator.
+                Rng,
+                /// A timer.
+                Timer,
+                /// The USB device.
+                Usb,
+            }
+        },
+        item! {
+            /// Disables a peripheral to save power.
+            ///
+            /// A disabled peripheral can't be used until enabled again.
+            fn disable_peripheral "pd" {
+                /// Kind of the peripheral.
+                peripheral: usize,
+
+                /// Index of the peripheral.
+                index: usize,
+            } -> {
+                /// Zero for success. Otherwise complement of error number.
+                res: isize,
+            }
+        },
+        item! {
+            /// Enables a peripheral.
+            fn enable_peripheral "pe" {
+                /// Kind of the peripheral.
+                peripheral: usize,
+
+                /// Index of the peripheral.
+                index: usize,
+            } -> {
+                /// Zero for success. Otherwise complement of error number.
+                res: isize,
+            }
+        },
+    ];
+    Item::Mod(Mod { docs, name, items })
+}
@@ -0,0 +1,62 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::*;
+
+pub(crate) fn new() -> Item {
+    let docs = docs! {
+        /// PWM output operations.
+    };
+    let name = "pwm".into();
+    let items = vec![
+        item! {
+            /// Returns how many PWM outputs are on the device.
+            fn count "wc" {} -> {
+                /// How many PWM outputs are on the device.
+                cnt: usize,
+            }
+        },
+        item! {
+            /// Configures and starts a PWM output.
+            fn configure "wg" {
+                /// Index of the PWM output to configure.
+                pwm: usize,
+
+                /// Frequency in Hz.
+                freq_hz: usize,
+
+                /// Duty cycle in percent (at most 100).
+                ///
+                /// A duty cycle of 0 (resp. 100) is a steady low (resp. high) output.
+                duty_pct: usize,
+            } -> {
+                /// Zero on success, negative on error.
+                res: isize,
+            }
+        },
+        item! {
+            /// Stops a PWM output.
+            ///
+            /// The output is steady low afterwards.
+            fn stop "ws" {
+                /// Index of the PWM output to stop.
+                pwm: usize,
+            } -> {
+                /// Zero on success, negative on error.
+                res: isize,
+            }
+        },
+    ];
+    Item::Mod(Mod { docs, name, items })
+}
@@ -24,6 +24,14 @@ pub(crate) fn new() -> Item {
             /// This can be used as power management, since the CPU will sleep while waiting.
             fn wait_for_callback "sw" {} -> {}
         },
+        item! {
+            /// Waits until the board produces an event.
+            ///
+            /// Unlike `wait_for_callback`, this returns after the next board event even if it
+            /// doesn't schedule a callback. Pending callbacks are executed before returning. The
+            /// CPU sleeps while waiting.
+            fn wait_for_event "se" {} -> {}
+        },
         item! {
             /// Returns how many callbacks are pending.
             fn num_pending_callbacks "sh" {} -> {
@@ -0,0 +1,88 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::*;
+
+pub(crate) fn new() -> Item {
+    let docs = docs! {
+        /// Stepper motor operations.
+    };
+    let name = "stepper".into();
+    let items = vec![
+        item! {
+            /// Returns how many stepper motors are on the device.
+            fn count "mc" {} -> {
+                /// How many stepper motors are on the device.
+                cnt: usize,
+            }
+        },
+        item! {
+            /// Starts moving a stepper motor to an absolute position.
+            ///
+            /// The move follows a trapezoidal acceleration profile and is executed by the board.
+            /// Register a handler to be notified when the move is done.
+            fn move_to "mm" {
+                /// Index of the stepper motor to move.
+                stepper: usize,
+
+                /// Target position in steps.
+                position: isize,
+
+                /// Maximum speed in steps per second.
+                max_speed: usize,
+
+                /// Acceleration in steps per second squared.
+                acceleration: usize,
+            } -> {
+                /// Error code: 0 on success, -1 on error
+                ///
+                /// It is an error to start a move while the motor is still moving.
+                res: isize,
+            }
+        },
+        item! {
+            /// Returns the current position of a stepper motor.
+            fn position "mp" {
+                /// Index of the stepper motor.
+                stepper: usize,
+            } -> {
+                /// Current position in steps.
+                position: isize,
+            }
+        },
+        item! {
+            /// Register a handler for stepper motor events.
+            fn register "mr" {
+                /// Index of the stepper motor to listen to.
+                stepper: usize,
+
+                /// Function called when a move is done.
+                ///
+                /// The function takes its opaque `data` as argument.
+                handler_func: fn { data: *mut u8 },
+
+                /// The opaque data to use when calling the handler function.
+                handler_data: *mut u8,
+            } -> {}
+        },
+        item! {
+            /// Unregister handlers for stepper motor events.
+            fn unregister "mu" {
+                /// Index of the stepper motor to stop listening to.
+                stepper: usize,
+            } -> {}
+        },
+    ];
+    Item::Mod(Mod { docs, name, items })
+}
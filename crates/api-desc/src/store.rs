@@ -14,6 +14,8 @@
 
 use crate::*;
 
+mod fragment;
+
 pub(crate) fn new() -> Item {
     let docs = docs! {
         /// Persistent storage operations.
@@ -46,7 +48,8 @@ pub(crate) fn new() -> Item {
             fn insert "si" {
                 /// Key of the entry.
                 ///
-                /// This must be smaller than 4096.
+                /// This must be smaller than 4032. Larger keys are reserved for the platform, but
+                /// entries inserted there by older platforms can still be found and removed.
                 key: usize,
 
                 /// Value of the entry.
@@ -104,6 +107,7 @@ pub(crate) fn new() -> Item {
                 res: isize,
             }
         },
+        fragment::new(),
     ];
     Item::Mod(Mod { docs, name, items })
 }
@@ -0,0 +1,95 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::*;
+
+pub(crate) fn new() -> Item {
+    let docs = docs! {
+        /// Support for fragmented entries.
+        ///
+        /// Fragmented entries span a range of keys and may thus be larger than the maximum value
+        /// length of a single entry. They are meant for large buffers (e.g. images or firmware).
+        ///
+        /// The functions of this module transfer the whole buffer in a single call: the runner
+        /// directly reads from (or writes to) the region of the applet linear memory described by
+        /// the pointer and length. The region is only accessed for the duration of the call and the
+        /// runner does not retain it afterwards. The applet must not assume anything about the
+        /// content of the region if the call fails.
+    };
+    let name = "fragment".into();
+    let items = vec![
+        item! {
+            /// Inserts a fragmented entry in the store.
+            ///
+            /// If an entry for that range of keys was already present, it is overwritten.
+            fn insert "sfi" {
+                /// Range of keys.
+                ///
+                /// The first key is the lower 16 bits and the one past the last key is the upper 16
+                /// bits. The range must be non-empty and the keys smaller than 4032.
+                keys: usize,
+
+                /// Value of the entry.
+                ptr: *const u8,
+
+                /// Length of the value.
+                ///
+                /// This must fit in the range of keys.
+                len: usize,
+            } -> {
+                /// Zero for success. Otherwise complement of error number.
+                res: isize,
+            }
+        },
+        item! {
+            /// Removes a fragmented entry from the store.
+            ///
+            /// This is not an error if no entry is present. This is simply a no-op in that case.
+            fn remove "sfr" {
+                /// Range of keys (same encoding as for `insert`).
+                keys: usize,
+            } -> {
+                /// Zero for success. Otherwise complement of error number.
+                res: isize,
+            }
+        },
+        item! {
+            /// Reads part of a fragmented entry from the store.
+            ///
+            /// The runner writes the bytes of the entry starting at `offset` directly into the
+            /// buffer. Large entries may thus be read in a single call, or in a few calls at
+            /// increasing offsets when the applet can't afford a buffer for the whole entry.
+            fn read "sfo" {
+                /// Range of keys (same encoding as for `insert`).
+                keys: usize,
+
+                /// Offset in the entry of the first byte to read.
+                offset: usize,
+
+                /// Where to write the bytes read.
+                ptr: *mut u8,
+
+                /// Length of the buffer.
+                len: usize,
+            } -> {
+                /// Number of bytes read. Otherwise complement of error number.
+                ///
+                /// This is smaller than the length of the buffer when the end of the entry is
+                /// reached. It is zero if the entry is not found or the offset is past its end.
+                res: isize,
+            }
+        },
+    ];
+    Item::Mod(Mod { docs, name, items })
+}
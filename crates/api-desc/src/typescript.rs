@@ -0,0 +1,119 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! TypeScript declarations (`.d.ts`) of the applet API.
+//!
+//! This only describes the signatures and documentation for editor tooling. The implementation is
+//! the AssemblyScript output.
+
+use std::io::Write;
+
+use crate::{write_items, Api, Enum, Field, Fn, Item, Mod, Path, Type, Variant, API_VERSION};
+
+impl Api {
+    pub fn wasm_typescript_decl(&self, output: &mut dyn Write) -> std::io::Result<()> {
+        write_jsdoc(output, "", &[format!(" Version of the applet API ({API_VERSION}).")], &[])?;
+        writeln!(output, "export declare const API_VERSION: number;")?;
+        writeln!(output)?;
+        write_items(output, &self.0, |output, item| item.typescript_decl(output, &Path::Empty))
+    }
+}
+
+impl Item {
+    fn typescript_decl(&self, output: &mut dyn Write, path: &Path) -> std::io::Result<()> {
+        match self {
+            Item::Enum(x) => x.typescript_decl(output, path),
+            Item::Fn(x) => x.typescript_decl(output, path),
+            Item::Mod(x) => x.typescript_decl(output, path),
+        }
+    }
+}
+
+impl Enum {
+    fn typescript_decl(&self, output: &mut dyn Write, path: &Path) -> std::io::Result<()> {
+        let Enum { docs, name, variants } = self;
+        write_jsdoc(output, "", docs, &[])?;
+        writeln!(output, "export declare enum {path}{name} {{")?;
+        for Variant { docs, name } in variants {
+            write_jsdoc(output, "  ", docs, &[])?;
+            writeln!(output, "  {name},")?;
+        }
+        writeln!(output, "}}")
+    }
+}
+
+impl Fn {
+    fn typescript_decl(&self, output: &mut dyn Write, path: &Path) -> std::io::Result<()> {
+        let Fn { docs, name, link: _, params, results } = self;
+        let mut tags = Vec::new();
+        for Field { docs, name, type_: _ } in params {
+            tags.push((format!("@param {name}"), docs));
+        }
+        if let Some(result) = results.get(0) {
+            tags.push(("@returns".to_string(), &result.docs));
+        }
+        write_jsdoc(output, "", docs, &tags)?;
+        let params: Vec<_> =
+            params.iter().map(|x| format!("{}: {}", x.name, x.type_.typescript_decl())).collect();
+        let result = match &results[..] {
+            [] => "void",
+            [result] => result.type_.typescript_decl(),
+            _ => unimplemented!("multi-value is not supported in TypeScript"),
+        };
+        writeln!(output, "export declare function {path}{name}({}): {result};", params.join(", "))
+    }
+}
+
+impl Mod {
+    fn typescript_decl(&self, output: &mut dyn Write, path: &Path) -> std::io::Result<()> {
+        let Mod { docs: _, name, items } = self;
+        let inner_path = Path::Mod { name, prev: path };
+        write_items(output, items, |output, item| item.typescript_decl(output, &inner_path))
+    }
+}
+
+impl Type {
+    fn typescript_decl(&self) -> &'static str {
+        // Integers, pointers, and functions are all 32-bits integers in WebAssembly.
+        "number"
+    }
+}
+
+/// Writes a JSDoc comment with the given indentation and block tags.
+fn write_jsdoc(
+    output: &mut dyn Write, indent: &str, docs: &[String], tags: &[(String, &Vec<String>)],
+) -> std::io::Result<()> {
+    if docs.is_empty() && tags.is_empty() {
+        return Ok(());
+    }
+    writeln!(output, "{indent}/**")?;
+    for doc in docs {
+        writeln!(output, "{indent} *{doc}")?;
+    }
+    if !docs.is_empty() && !tags.is_empty() {
+        writeln!(output, "{indent} *")?;
+    }
+    for (tag, docs) in tags {
+        match docs.split_first() {
+            None => writeln!(output, "{indent} * {tag}")?,
+            Some((first, rest)) => {
+                writeln!(output, "{indent} * {tag}{first}")?;
+                for doc in rest {
+                    writeln!(output, "{indent} *{doc}")?;
+                }
+            }
+        }
+    }
+    writeln!(output, "{indent} */")
+}
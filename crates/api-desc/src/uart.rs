@@ -0,0 +1,121 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::*;
+
+pub(crate) fn new() -> Item {
+    let docs = docs! {
+        /// UART operations.
+        ///
+        /// These are serial ports on physical pins, distinct from the USB serial. A board may have
+        /// multiple UARTs. They are identified by their index, starting at zero.
+    };
+    let name = "uart".into();
+    let items = vec![
+        item! {
+            /// Returns the number of UARTs.
+            fn count "uac" {} -> {
+                /// How many UARTs are available.
+                cnt: usize,
+            }
+        },
+        item! {
+            /// Sets the baud rate of a UART.
+            fn set_baudrate "uab" {
+                /// Index of the UART.
+                uart: usize,
+
+                /// Baud rate in bits per second.
+                baudrate: usize,
+            } -> {
+                /// Zero on success, -1 on error (e.g. unsupported baud rate).
+                res: isize,
+            }
+        },
+        item! {
+            /// Reads from a UART into a buffer.
+            fn read "uar" {
+                /// Index of the UART.
+                uart: usize,
+
+                /// Address of the buffer.
+                ptr: *mut u8,
+
+                /// Length of the buffer in bytes.
+                len: usize,
+            } -> {
+                /// Number of bytes read (or negative value for errors).
+                ///
+                /// This function does not block and may return zero.
+                len: isize,
+            }
+        },
+        item! {
+            /// Writes to a UART from a buffer.
+            fn write "uaw" {
+                /// Index of the UART.
+                uart: usize,
+
+                /// Address of the buffer.
+                ptr: *const u8,
+
+                /// Length of the buffer in bytes.
+                len: usize,
+            } -> {
+                /// Number of bytes written (or negative value for errors).
+                ///
+                /// This function does not block and may return zero.
+                len: isize,
+            }
+        },
+        item! {
+            /// Flushes a UART.
+            fn flush "uaf" {
+                /// Index of the UART.
+                uart: usize,
+            } -> {
+                /// Zero on success, -1 on error.
+                res: isize,
+            }
+        },
+        item! {
+            /// UART events.
+            enum Event {
+                /// Ready for read.
+                Read,
+                /// Ready for write.
+                Write,
+            }
+        },
+        item! {
+            /// Registers a callback when a UART is ready.
+            ///
+            /// It is possible that the callback is spuriously called.
+            fn register "uae" {
+                uart: usize,
+                event: usize,
+                handler_func: fn { data: *mut u8 },
+                handler_data: *mut u8,
+            } -> {}
+        },
+        item! {
+            /// Unregisters a callback.
+            fn unregister "uad" {
+                uart: usize,
+                event: usize,
+            } -> {}
+        },
+    ];
+    Item::Mod(Mod { docs, name, items })
+}
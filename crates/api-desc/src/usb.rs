@@ -14,6 +14,7 @@
 
 use crate::*;
 
+mod hid;
 mod serial;
 
 pub(crate) fn new() -> Item {
@@ -28,6 +29,7 @@ pub(crate) fn new() -> Item {
                 Unknown,
             }
         },
+        hid::new(),
         serial::new(),
     ];
     Item::Mod(Mod { docs, name, items })
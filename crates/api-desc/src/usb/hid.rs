@@ -0,0 +1,78 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::*;
+
+pub(crate) fn new() -> Item {
+    let docs = docs! {
+        /// USB HID operations.
+        ///
+        /// Reports are always 64 bytes long.
+    };
+    let name = "hid".into();
+    let items = vec![
+        item! {
+            /// Reads a report from USB HID into a 64-bytes buffer.
+            fn read "uhr" {
+                /// Address of the 64-bytes buffer.
+                ptr: *mut u8,
+            } -> {
+                /// One if a report was read, zero if there is nothing to read, and negative value
+                /// for errors.
+                ///
+                /// This function does not block.
+                res: isize,
+            }
+        },
+        item! {
+            /// Writes a report to USB HID from a 64-bytes buffer.
+            fn write "uhw" {
+                /// Address of the 64-bytes buffer.
+                ptr: *const u8,
+            } -> {
+                /// One if the report was written, zero if the other side is not ready, and
+                /// negative value for errors.
+                ///
+                /// This function does not block.
+                res: isize,
+            }
+        },
+        item! {
+            /// USB HID events.
+            enum Event {
+                /// Ready for read.
+                Read,
+                /// Ready for write.
+                Write,
+            }
+        },
+        item! {
+            /// Registers a callback when USB HID is ready.
+            ///
+            /// It is possible that the callback is spuriously called.
+            fn register "uhe" {
+                event: usize,
+                handler_func: fn { data: *mut u8 },
+                handler_data: *mut u8,
+            } -> {}
+        },
+        item! {
+            /// Unregisters a callback.
+            fn unregister "uhd" {
+                event: usize,
+            } -> {}
+        },
+    ];
+    Item::Mod(Mod { docs, name, items })
+}
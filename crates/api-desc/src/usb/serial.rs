@@ -15,12 +15,27 @@
 use crate::*;
 
 pub(crate) fn new() -> Item {
-    let docs = docs!();
+    let docs = docs! {
+        /// USB serial operations.
+        ///
+        /// A board may have multiple serial ports. They are identified by their index, starting at
+        /// zero.
+    };
     let name = "serial".into();
     let items = vec![
         item! {
-            /// Reads from USB serial into a buffer.
+            /// Returns the number of serial ports.
+            fn count "usc" {} -> {
+                /// How many serial ports are available.
+                cnt: usize,
+            }
+        },
+        item! {
+            /// Reads from a USB serial port into a buffer.
             fn read "usr" {
+                /// Index of the serial port.
+                port: usize,
+
                 /// Address of the buffer.
                 ptr: *mut u8,
 
@@ -34,8 +49,11 @@ pub(crate) fn new() -> Item {
             }
         },
         item! {
-            /// Writes to USB serial from a buffer.
+            /// Writes to a USB serial port from a buffer.
             fn write "usw" {
+                /// Index of the serial port.
+                port: usize,
+
                 /// Address of the buffer.
                 ptr: *const u8,
 
@@ -55,13 +73,36 @@ pub(crate) fn new() -> Item {
                 Read,
                 /// Ready for write.
                 Write,
+                /// Line state changed.
+                Control,
+                /// Line coding changed.
+                Coding,
+            }
+        },
+        item! {
+            /// Number of stop bits.
+            enum StopBits {
+                One,
+                OnePointFive,
+                Two,
             }
         },
         item! {
-            /// Registers a callback when USB serial is ready.
+            /// Parity type.
+            enum Parity {
+                None,
+                Odd,
+                Even,
+                Mark,
+                Space,
+            }
+        },
+        item! {
+            /// Registers a callback when a USB serial port is ready.
             ///
             /// It is possible that the callback is spuriously called.
             fn register "use" {
+                port: usize,
                 event: usize,
                 handler_func: fn { data: *mut u8 },
                 handler_data: *mut u8,
@@ -70,12 +111,55 @@ pub(crate) fn new() -> Item {
         item! {
             /// Unregisters a callback.
             fn unregister "usd" {
+                port: usize,
                 event: usize,
             } -> {}
         },
         item! {
-            /// Flushs the USB serial.
-            fn flush "usf" {} -> {
+            /// Flushs a USB serial port.
+            fn flush "usf" {
+                /// Index of the serial port.
+                port: usize,
+            } -> {
+                /// Zero on success, -1 on error.
+                res: isize,
+            }
+        },
+        item! {
+            /// Flushes a USB serial port, waiting at most a given duration.
+            fn flush_timeout "ust" {
+                /// Index of the serial port.
+                port: usize,
+
+                /// Maximum duration to wait in milliseconds.
+                timeout_ms: usize,
+            } -> {
+                /// Zero on success, -1 on error (including timeout).
+                res: isize,
+            }
+        },
+        item! {
+            /// Returns the line state of a USB serial port.
+            fn line_state "usl" {
+                /// Index of the serial port.
+                port: usize,
+            } -> {
+                /// Bit 0 is DTR and bit 1 is RTS (or -1 on error).
+                res: isize,
+            }
+        },
+        item! {
+            /// Returns the line coding of a USB serial port.
+            fn line_coding "usk" {
+                /// Index of the serial port.
+                port: usize,
+
+                /// Pointer to the 8 bytes of the line coding.
+                ///
+                /// The data rate in bits per second as little-endian u32, followed by the stop
+                /// bits, the parity, the number of data bits, and a zero byte.
+                ptr: *mut u8,
+            } -> {
                 /// Zero on success, -1 on error.
                 res: isize,
             }
@@ -30,6 +30,11 @@ pub trait Signature: Default + Debug {
     /// The type of results for this function.
     type Results: ArrayU32;
 
+    /// Index of the result holding the error code, if any.
+    ///
+    /// This is the first signed result.
+    const ERROR: Option<usize>;
+
     /// Returns the descriptor of this function.
     fn descriptor() -> Descriptor {
         Descriptor {
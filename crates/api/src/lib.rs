@@ -16,6 +16,7 @@
 #![cfg_attr(feature = "wasm", doc = include_str!("wasm.md"))]
 #![no_std]
 
+#[cfg(feature = "host")]
 extern crate alloc;
 
 #[cfg(feature = "host")]
@@ -32,4 +32,11 @@ impl crate::crypto::hash::Algorithm {
             crate::crypto::hash::Algorithm::Sha256 => 32,
         }
     }
+
+    /// Returns the length in bytes of the algorithm block.
+    pub fn block_len(self) -> usize {
+        match self {
+            crate::crypto::hash::Algorithm::Sha256 => 64,
+        }
+    }
 }
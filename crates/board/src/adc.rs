@@ -0,0 +1,62 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Analog-to-digital converter interface.
+//!
+//! A board exposes a fixed table of analog inputs. Samples are normalized to 16 bits such that 0 is
+//! ground and `u16::MAX` is the reference voltage, independently of the converter resolution.
+
+use crate::{Error, Unimplemented, Unsupported};
+
+pub trait Api {
+    /// Returns how many analog inputs are available.
+    ///
+    /// Analog inputs are identified by an integer smaller than this value.
+    fn count(&mut self) -> usize;
+
+    /// Samples an analog input.
+    fn read(&mut self, adc: usize) -> Result<u16, Error>;
+
+    /// Measures the supply voltage (VDD) in millivolts.
+    ///
+    /// This is useful to monitor the battery of portable boards. Since analog inputs may use VDD
+    /// as reference, this measurement must use an internal reference instead.
+    fn read_vdd_mv(&mut self) -> Result<u16, Error> {
+        Err(Error::User)
+    }
+}
+
+impl Api for Unimplemented {
+    fn count(&mut self) -> usize {
+        unreachable!()
+    }
+
+    fn read(&mut self, _: usize) -> Result<u16, Error> {
+        unreachable!()
+    }
+
+    fn read_vdd_mv(&mut self) -> Result<u16, Error> {
+        unreachable!()
+    }
+}
+
+impl Api for Unsupported {
+    fn count(&mut self) -> usize {
+        0
+    }
+
+    fn read(&mut self, _: usize) -> Result<u16, Error> {
+        Err(Error::User)
+    }
+}
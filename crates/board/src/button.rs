@@ -19,8 +19,12 @@
 
 use crate::{Error, Unimplemented, Unsupported};
 
+pub mod gesture;
+
+pub use self::gesture::Gesture;
+
 /// Button event.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Event {
     /// The button that triggered the event.
     pub button: usize,
@@ -35,6 +39,25 @@ impl From<Event> for crate::Event {
     }
 }
 
+/// Button gesture event.
+///
+/// Boards don't trigger those events. They are produced from button events by the scheduler for
+/// buttons with a gesture configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GestureEvent {
+    /// The button that triggered the event.
+    pub button: usize,
+
+    /// The recognized gesture.
+    pub gesture: Gesture,
+}
+
+impl From<GestureEvent> for crate::Event {
+    fn from(event: GestureEvent) -> Self {
+        crate::Event::Gesture(event)
+    }
+}
+
 /// Button interface.
 pub trait Api {
     /// Returns how many buttons are available.
@@ -47,6 +70,67 @@ pub trait Api {
 
     /// Disables events for a given button.
     fn disable(&mut self, button: usize) -> Result<(), Error>;
+
+    /// Sets the debounce interval of a given button in microseconds.
+    ///
+    /// Edges within this interval after the last reported event are ignored. Zero disables
+    /// debouncing.
+    fn set_debounce(&mut self, button: usize, duration_us: usize) -> Result<(), Error> {
+        let _ = (button, duration_us);
+        Err(Error::User)
+    }
+}
+
+/// Software debouncing of a button.
+///
+/// Edges are given with their timestamp in microseconds (typically the platform uptime) and the
+/// button state after the edge. If an edge is ignored within the interval, the final state may
+/// differ from the last reported event, so the platform must sample the button again at
+/// [`Debounce::resample_at()`] and give the state to [`Debounce::accept()`].
+#[derive(Debug, Clone)]
+pub struct Debounce {
+    interval_us: u64,
+    /// The last reported event, if any.
+    last: Option<(u64, bool)>,
+    /// Whether the button must be sampled at the end of the interval.
+    resample: bool,
+}
+
+impl Debounce {
+    /// Creates a debouncer with a given interval in microseconds.
+    pub fn new(interval_us: u64) -> Self {
+        Debounce { interval_us, last: None, resample: false }
+    }
+
+    /// Sets the interval in microseconds.
+    pub fn set_interval(&mut self, interval_us: u64) {
+        self.interval_us = interval_us;
+    }
+
+    /// Returns whether an edge (or a sample) should be reported.
+    ///
+    /// Edges are ignored if they are within the interval of the last reported event, or if they
+    /// don't change the state of the last reported event (e.g. the other edge was ignored).
+    pub fn accept(&mut self, now: u64, pressed: bool) -> bool {
+        if let Some((time, state)) = self.last {
+            if now.saturating_sub(time) < self.interval_us {
+                self.resample |= state != pressed;
+                return false;
+            }
+            self.resample = false;
+            if state == pressed {
+                return false;
+            }
+        }
+        self.last = Some((now, pressed));
+        true
+    }
+
+    /// Returns when the button must be sampled again, if an edge was ignored within the interval.
+    pub fn resample_at(&self) -> Option<u64> {
+        let (time, _) = self.last?;
+        self.resample.then(|| time.saturating_add(self.interval_us))
+    }
 }
 
 impl Api for Unimplemented {
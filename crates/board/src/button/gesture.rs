@@ -0,0 +1,191 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Gesture recognition for buttons.
+//!
+//! A [`Detector`] turns the press and release events of a button into gestures. It doesn't track
+//! time by itself: events are given with their timestamp in microseconds (typically the platform
+//! uptime), and when [`Detector::deadline()`] returns a timestamp, a timer should be armed to call
+//! [`Detector::timeout()`] at (or after) that time.
+
+/// Button gesture.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Gesture {
+    /// The button was pressed and released once.
+    Single,
+
+    /// The button was held pressed for at least the long-press threshold.
+    Long,
+
+    /// The button was pressed twice within the double-press window.
+    Double,
+}
+
+/// Gesture configuration of a button.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// Minimum duration in microseconds of a long press.
+    pub long_us: u64,
+
+    /// Maximum duration in microseconds between a release and the next press for a double press.
+    ///
+    /// Single presses are reported after this window. Zero disables double presses, such that
+    /// single presses are reported immediately on release.
+    pub double_us: u64,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Phase {
+    /// The button is released and no gesture is in progress.
+    Idle,
+
+    /// The button was pressed at the given time.
+    Pressed(u64),
+
+    /// The button was released at the given time and may be pressed again for a double press.
+    Released(u64),
+
+    /// The gesture was already reported and the button must be released before the next one.
+    Reported,
+}
+
+/// Gesture state machine of a button.
+#[derive(Debug, Clone)]
+pub struct Detector {
+    config: Config,
+    phase: Phase,
+}
+
+impl Detector {
+    /// Creates a detector for a released button.
+    pub fn new(config: Config) -> Self {
+        Detector { config, phase: Phase::Idle }
+    }
+
+    /// Returns the gesture configuration.
+    pub fn config(&self) -> Config {
+        self.config
+    }
+
+    /// Processes a press (or release) of the button at a given time.
+    pub fn update(&mut self, pressed: bool, now: u64) -> Option<Gesture> {
+        let (phase, gesture) = match (self.phase, pressed) {
+            (Phase::Idle, true) => (Phase::Pressed(now), None),
+            (Phase::Pressed(since), false) if self.config.long_us <= now - since => {
+                (Phase::Idle, Some(Gesture::Long))
+            }
+            (Phase::Pressed(_), false) if self.config.double_us == 0 => {
+                (Phase::Idle, Some(Gesture::Single))
+            }
+            (Phase::Pressed(_), false) => (Phase::Released(now), None),
+            (Phase::Released(since), true) if now - since <= self.config.double_us => {
+                (Phase::Reported, Some(Gesture::Double))
+            }
+            // The timeout was missed. We report the single press and start a new gesture.
+            (Phase::Released(_), true) => (Phase::Pressed(now), Some(Gesture::Single)),
+            (Phase::Reported, false) => (Phase::Idle, None),
+            // Repeated events (e.g. a missed release) are ignored.
+            (phase, _) => (phase, None),
+        };
+        self.phase = phase;
+        gesture
+    }
+
+    /// Returns when [`Self::timeout()`] should be called, if needed.
+    pub fn deadline(&self) -> Option<u64> {
+        match self.phase {
+            Phase::Pressed(since) => Some(since.saturating_add(self.config.long_us)),
+            Phase::Released(since) => Some(since.saturating_add(self.config.double_us)),
+            Phase::Idle | Phase::Reported => None,
+        }
+    }
+
+    /// Processes the passing of time.
+    ///
+    /// This may be called spuriously (before the deadline or without deadline).
+    pub fn timeout(&mut self, now: u64) -> Option<Gesture> {
+        if self.deadline().map_or(true, |deadline| now < deadline) {
+            return None;
+        }
+        let (phase, gesture) = match self.phase {
+            Phase::Pressed(_) => (Phase::Reported, Gesture::Long),
+            Phase::Released(_) => (Phase::Idle, Gesture::Single),
+            Phase::Idle | Phase::Reported => unreachable!(),
+        };
+        self.phase = phase;
+        Some(gesture)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: Config = Config { long_us: 1000, double_us: 300 };
+
+    #[test]
+    fn single() {
+        let mut detector = Detector::new(CONFIG);
+        assert_eq!(detector.update(true, 0), None);
+        assert_eq!(detector.deadline(), Some(1000));
+        assert_eq!(detector.update(false, 100), None);
+        assert_eq!(detector.deadline(), Some(400));
+        assert_eq!(detector.timeout(399), None);
+        assert_eq!(detector.timeout(400), Some(Gesture::Single));
+        assert_eq!(detector.deadline(), None);
+    }
+
+    #[test]
+    fn single_without_double() {
+        let mut detector = Detector::new(Config { double_us: 0, ..CONFIG });
+        assert_eq!(detector.update(true, 0), None);
+        assert_eq!(detector.update(false, 100), Some(Gesture::Single));
+        assert_eq!(detector.deadline(), None);
+    }
+
+    #[test]
+    fn long() {
+        let mut detector = Detector::new(CONFIG);
+        assert_eq!(detector.update(true, 0), None);
+        assert_eq!(detector.timeout(1000), Some(Gesture::Long));
+        assert_eq!(detector.deadline(), None);
+        assert_eq!(detector.update(false, 1500), None);
+        // Long press detected on release when the timeout is late.
+        assert_eq!(detector.update(true, 2000), None);
+        assert_eq!(detector.update(false, 3000), Some(Gesture::Long));
+    }
+
+    #[test]
+    fn double() {
+        let mut detector = Detector::new(CONFIG);
+        assert_eq!(detector.update(true, 0), None);
+        assert_eq!(detector.update(false, 100), None);
+        assert_eq!(detector.update(true, 400), Some(Gesture::Double));
+        // Holding the second press doesn't make it long.
+        assert_eq!(detector.timeout(2000), None);
+        assert_eq!(detector.update(false, 2000), None);
+        assert_eq!(detector.update(true, 2100), None);
+        assert_eq!(detector.deadline(), Some(3100));
+    }
+
+    #[test]
+    fn missed_timeout() {
+        let mut detector = Detector::new(CONFIG);
+        assert_eq!(detector.update(true, 0), None);
+        assert_eq!(detector.update(false, 100), None);
+        assert_eq!(detector.update(true, 500), Some(Gesture::Single));
+        assert_eq!(detector.update(false, 600), None);
+        assert_eq!(detector.timeout(900), Some(Gesture::Single));
+    }
+}
@@ -0,0 +1,165 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Input capture interface.
+//!
+//! This interface measures the frequency and duty cycle of a periodic digital signal on a GPIO
+//! (e.g. a PWM-encoded sensor or a tachometer). The GPIO must be configured as an input with the
+//! [GPIO interface](crate::gpio). Measurements run in the background: boards time the signal edges
+//! and may use [`analyze()`] to compute the measurement.
+
+use crate::{Error, Unimplemented, Unsupported};
+
+/// Measurement of a periodic signal.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Capture {
+    /// Duration of a period in nanoseconds.
+    pub period_ns: u32,
+
+    /// Duration of the high level within a period in nanoseconds.
+    pub high_ns: u32,
+}
+
+impl Capture {
+    /// Returns the frequency in Hertz.
+    pub fn frequency_hz(&self) -> f32 {
+        1e9 / self.period_ns as f32
+    }
+
+    /// Returns the ratio of time the signal is high (between 0 and 1).
+    pub fn duty(&self) -> f32 {
+        self.high_ns as f32 / self.period_ns as f32
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// The measurement of a GPIO is done.
+    Done {
+        /// The measured GPIO.
+        gpio: usize,
+    },
+}
+
+impl From<Event> for crate::Event {
+    fn from(event: Event) -> Self {
+        crate::Event::Capture(event)
+    }
+}
+
+pub trait Api {
+    /// Starts measuring the signal on a GPIO.
+    ///
+    /// The measurement runs in the background for at most a board-specific time window and a
+    /// [`Event::Done`] event triggers when it is done. Starting a measurement while another one is
+    /// running is a user error.
+    fn start(&mut self, gpio: usize) -> Result<(), Error>;
+
+    /// Takes the result of the done measurement of a GPIO.
+    ///
+    /// Returns `None` if there was no periodic signal. It is a user error if the measurement of the
+    /// GPIO is not done.
+    fn result(&mut self, gpio: usize) -> Result<Option<Capture>, Error>;
+}
+
+impl Api for Unimplemented {
+    fn start(&mut self, _: usize) -> Result<(), Error> {
+        unreachable!()
+    }
+
+    fn result(&mut self, _: usize) -> Result<Option<Capture>, Error> {
+        unreachable!()
+    }
+}
+
+impl Api for Unsupported {
+    fn start(&mut self, _: usize) -> Result<(), Error> {
+        Err(Error::User)
+    }
+
+    fn result(&mut self, _: usize) -> Result<Option<Capture>, Error> {
+        Err(Error::User)
+    }
+}
+
+/// Computes the measurement of a signal from its edges.
+///
+/// Each edge is the time in nanoseconds at which it occurred, and whether the signal is high after
+/// the edge. The period and high time are averaged over all complete periods, i.e. between the
+/// first and last rising edges. Returns `None` if there is no complete period.
+pub fn analyze(edges: &[(u64, bool)]) -> Option<Capture> {
+    let first = edges.iter().position(|&(_, high)| high)?;
+    let last = edges.iter().rposition(|&(_, high)| high)?;
+    let mut periods = 0;
+    let mut high_ns = 0;
+    let mut rise = edges[first].0;
+    for &(time, high) in &edges[first + 1 ..= last] {
+        match high {
+            true => {
+                periods += 1;
+                rise = time;
+            }
+            false => high_ns += time - rise,
+        }
+    }
+    if periods == 0 {
+        return None;
+    }
+    let period_ns = (edges[last].0 - edges[first].0) / periods;
+    let high_ns = high_ns / periods;
+    Some(Capture { period_ns: period_ns.try_into().ok()?, high_ns: high_ns.try_into().ok()? })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    /// Returns the edges of a signal starting at some phase.
+    fn signal(period_ns: u64, high_ns: u64, start_ns: u64, count: usize) -> Vec<(u64, bool)> {
+        let mut edges = Vec::new();
+        let mut time = start_ns;
+        while edges.len() < count {
+            let phase = time % period_ns;
+            let (next, high) = match phase < high_ns {
+                true => (time - phase + high_ns, false),
+                false => (time - phase + period_ns, true),
+            };
+            edges.push((next, high));
+            time = next;
+        }
+        edges
+    }
+
+    #[test]
+    fn analyze_1khz_25_percent() {
+        let capture = analyze(&signal(1_000_000, 250_000, 123_456, 9)).unwrap();
+        assert_eq!(capture, Capture { period_ns: 1_000_000, high_ns: 250_000 });
+        assert!((capture.frequency_hz() - 1000.).abs() < 0.01);
+        assert!((capture.duty() - 0.25).abs() < 0.0001);
+    }
+
+    #[test]
+    fn analyze_averages_jitter() {
+        let edges = [(0, true), (240, false), (1010, true), (1270, false), (2000, true)];
+        assert_eq!(analyze(&edges), Some(Capture { period_ns: 1000, high_ns: 250 }));
+    }
+
+    #[test]
+    fn analyze_no_period() {
+        assert_eq!(analyze(&[]), None);
+        assert_eq!(analyze(&[(10, false), (20, true), (30, false)]), None);
+    }
+}
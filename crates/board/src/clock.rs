@@ -0,0 +1,37 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Clock interface.
+
+use crate::{Error, Unimplemented, Unsupported};
+
+/// Clock interface.
+pub trait Api {
+    /// Returns the time in microseconds since the platform started.
+    ///
+    /// This clock is monotonic and does not wrap around.
+    fn uptime_us(&mut self) -> Result<u64, Error>;
+}
+
+impl Api for Unimplemented {
+    fn uptime_us(&mut self) -> Result<u64, Error> {
+        unreachable!()
+    }
+}
+
+impl Api for Unsupported {
+    fn uptime_us(&mut self) -> Result<u64, Error> {
+        Err(Error::User)
+    }
+}
@@ -18,6 +18,10 @@ use crate::{Unimplemented, Unsupported};
 
 pub mod aes128_ccm;
 pub mod aes256_gcm;
+pub mod crc;
+pub mod ecdh;
+pub mod ed25519;
+pub mod hkdf;
 pub mod sha256;
 
 /// Returns this [`Types`] given a [`crate::Types`].
@@ -38,6 +42,18 @@ pub trait Api<T: Types> {
     where Self: 'a;
     fn aes256_gcm(&mut self) -> Self::Aes256Gcm<'_>;
 
+    type Crc<'a>: crc::Api
+    where Self: 'a;
+    fn crc(&mut self) -> Self::Crc<'_>;
+
+    type Ecdh<'a>: ecdh::Api
+    where Self: 'a;
+    fn ecdh(&mut self) -> Self::Ecdh<'_>;
+
+    type Ed25519<'a>: ed25519::Api
+    where Self: 'a;
+    fn ed25519(&mut self) -> Self::Ed25519<'_>;
+
     type Sha256<'a>: sha256::Api<T::Sha256>
     where Self: 'a;
     fn sha256(&mut self) -> Self::Sha256<'_>;
@@ -58,6 +74,21 @@ impl Api<Unimplemented> for Unimplemented {
         unreachable!()
     }
 
+    type Crc<'a> = Unimplemented;
+    fn crc(&mut self) -> Self::Crc<'_> {
+        unreachable!()
+    }
+
+    type Ecdh<'a> = Unimplemented;
+    fn ecdh(&mut self) -> Self::Ecdh<'_> {
+        unreachable!()
+    }
+
+    type Ed25519<'a> = Unimplemented;
+    fn ed25519(&mut self) -> Self::Ed25519<'_> {
+        unreachable!()
+    }
+
     type Sha256<'a> = Unimplemented;
     fn sha256(&mut self) -> Self::Sha256<'_> {
         unreachable!()
@@ -79,6 +110,21 @@ impl Api<Unsupported> for Unsupported {
         Unsupported
     }
 
+    type Crc<'a> = Unsupported;
+    fn crc(&mut self) -> Self::Crc<'_> {
+        Unsupported
+    }
+
+    type Ecdh<'a> = Unsupported;
+    fn ecdh(&mut self) -> Self::Ecdh<'_> {
+        Unsupported
+    }
+
+    type Ed25519<'a> = Unsupported;
+    fn ed25519(&mut self) -> Self::Ed25519<'_> {
+        Unsupported
+    }
+
     type Sha256<'a> = Unsupported;
     fn sha256(&mut self) -> Self::Sha256<'_> {
         Unsupported
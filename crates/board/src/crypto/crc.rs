@@ -0,0 +1,106 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! CRC interface.
+//!
+//! CRC-32 is the one used by Ethernet, zlib, and PNG (also known as CRC-32/ISO-HDLC). CRC-16/CCITT
+//! has initial value 0xffff and no final xor (also known as CRC-16/IBM-3740 or CRC-16/CCITT-FALSE).
+
+use crate::{Error, Unimplemented, Unsupported};
+
+/// CRC interface.
+pub trait Api {
+    /// Whether CRCs are supported.
+    fn is_supported(&mut self) -> bool;
+
+    /// Returns the CRC-32 of some data.
+    fn crc32(&mut self, data: &[u8]) -> Result<u32, Error>;
+
+    /// Returns the CRC-16/CCITT of some data.
+    fn crc16_ccitt(&mut self, data: &[u8]) -> Result<u16, Error>;
+}
+
+impl Api for Unimplemented {
+    fn is_supported(&mut self) -> bool {
+        unreachable!()
+    }
+
+    fn crc32(&mut self, _: &[u8]) -> Result<u32, Error> {
+        unreachable!()
+    }
+
+    fn crc16_ccitt(&mut self, _: &[u8]) -> Result<u16, Error> {
+        unreachable!()
+    }
+}
+
+#[cfg(not(feature = "software-crypto-crc"))]
+mod unsupported {
+    use super::*;
+
+    impl Api for Unsupported {
+        fn is_supported(&mut self) -> bool {
+            false
+        }
+
+        fn crc32(&mut self, _: &[u8]) -> Result<u32, Error> {
+            Err(Error::User)
+        }
+
+        fn crc16_ccitt(&mut self, _: &[u8]) -> Result<u16, Error> {
+            Err(Error::User)
+        }
+    }
+}
+
+#[cfg(feature = "software-crypto-crc")]
+mod unsupported {
+    use crc::{Crc, CRC_16_IBM_3740, CRC_32_ISO_HDLC};
+
+    use super::*;
+
+    const CRC32: Crc<u32> = Crc::<u32>::new(&CRC_32_ISO_HDLC);
+    const CRC16_CCITT: Crc<u16> = Crc::<u16>::new(&CRC_16_IBM_3740);
+
+    impl Api for Unsupported {
+        fn is_supported(&mut self) -> bool {
+            true
+        }
+
+        fn crc32(&mut self, data: &[u8]) -> Result<u32, Error> {
+            Ok(CRC32.checksum(data))
+        }
+
+        fn crc16_ccitt(&mut self, data: &[u8]) -> Result<u16, Error> {
+            Ok(CRC16_CCITT.checksum(data))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn crc32_check_value() {
+            assert_eq!(Unsupported.crc32(b"").unwrap(), 0);
+            assert_eq!(Unsupported.crc32(b"123456789").unwrap(), 0xcbf43926);
+        }
+
+        #[test]
+        fn crc16_ccitt_check_value() {
+            assert_eq!(Unsupported.crc16_ccitt(b"").unwrap(), 0xffff);
+            assert_eq!(Unsupported.crc16_ccitt(b"123456789").unwrap(), 0x29b1);
+        }
+    }
+}
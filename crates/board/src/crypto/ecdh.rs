@@ -0,0 +1,146 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! ECDH over P-256.
+//!
+//! Private keys are 32-byte big-endian scalars. Public keys are 64-byte uncompressed points (the
+//! big-endian X coordinate followed by the Y coordinate, without the SEC1 tag). Shared secrets are
+//! the X coordinate of the product point as specified by SEC1.
+
+use crate::{Error, Unimplemented, Unsupported};
+
+/// ECDH interface.
+pub trait Api {
+    /// Whether ECDH over P-256 is supported.
+    fn is_supported(&mut self) -> bool;
+
+    /// Computes the public key of a private key.
+    ///
+    /// This is a user error if the private key is not a valid scalar (zero or not less than the
+    /// curve order). Key pairs are generated by calling this function with random private keys.
+    fn public_key(&mut self, private: &[u8; 32], public: &mut [u8; 64]) -> Result<(), Error>;
+
+    /// Computes the shared secret of a private key and a peer public key.
+    ///
+    /// This is a user error if the private key is invalid or the public key is not on the curve.
+    fn agree(
+        &mut self, private: &[u8; 32], public: &[u8; 64], shared: &mut [u8; 32],
+    ) -> Result<(), Error>;
+}
+
+impl Api for Unimplemented {
+    fn is_supported(&mut self) -> bool {
+        unreachable!()
+    }
+
+    fn public_key(&mut self, _: &[u8; 32], _: &mut [u8; 64]) -> Result<(), Error> {
+        unreachable!()
+    }
+
+    fn agree(&mut self, _: &[u8; 32], _: &[u8; 64], _: &mut [u8; 32]) -> Result<(), Error> {
+        unreachable!()
+    }
+}
+
+#[cfg(not(feature = "software-crypto-p256"))]
+mod unsupported {
+    use super::*;
+
+    impl Api for Unsupported {
+        fn is_supported(&mut self) -> bool {
+            false
+        }
+
+        fn public_key(&mut self, _: &[u8; 32], _: &mut [u8; 64]) -> Result<(), Error> {
+            Err(Error::User)
+        }
+
+        fn agree(&mut self, _: &[u8; 32], _: &[u8; 64], _: &mut [u8; 32]) -> Result<(), Error> {
+            Err(Error::User)
+        }
+    }
+}
+
+#[cfg(feature = "software-crypto-p256")]
+mod unsupported {
+    use p256::elliptic_curve::sec1::{FromEncodedPoint, ToEncodedPoint};
+    use p256::{EncodedPoint, PublicKey, SecretKey};
+
+    use super::*;
+
+    impl Api for Unsupported {
+        fn is_supported(&mut self) -> bool {
+            true
+        }
+
+        fn public_key(&mut self, private: &[u8; 32], public: &mut [u8; 64]) -> Result<(), Error> {
+            let private = secret_key(private)?;
+            encode_public(&private.public_key(), public);
+            Ok(())
+        }
+
+        fn agree(
+            &mut self, private: &[u8; 32], public: &[u8; 64], shared: &mut [u8; 32],
+        ) -> Result<(), Error> {
+            let private = secret_key(private)?;
+            let point = EncodedPoint::from_untagged_bytes(public.into());
+            let public: Option<PublicKey> = PublicKey::from_encoded_point(&point).into();
+            let public = public.ok_or(Error::User)?;
+            let secret =
+                p256::ecdh::diffie_hellman(private.to_nonzero_scalar(), public.as_affine());
+            shared.copy_from_slice(secret.raw_secret_bytes());
+            Ok(())
+        }
+    }
+
+    fn secret_key(private: &[u8; 32]) -> Result<SecretKey, Error> {
+        SecretKey::from_bytes(private.into()).map_err(|_| Error::User)
+    }
+
+    fn encode_public(public: &PublicKey, output: &mut [u8; 64]) {
+        let point = public.to_encoded_point(false);
+        output.copy_from_slice(&point.as_bytes()[1 ..]);
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn two_party_agreement() {
+            let alice = [0x11; 32];
+            let bob = [0x22; 32];
+            let mut alice_public = [0; 64];
+            let mut bob_public = [0; 64];
+            Unsupported.public_key(&alice, &mut alice_public).unwrap();
+            Unsupported.public_key(&bob, &mut bob_public).unwrap();
+            let mut alice_shared = [0; 32];
+            let mut bob_shared = [0; 32];
+            Unsupported.agree(&alice, &bob_public, &mut alice_shared).unwrap();
+            Unsupported.agree(&bob, &alice_public, &mut bob_shared).unwrap();
+            assert_eq!(alice_shared, bob_shared);
+            assert_ne!(alice_shared, [0; 32]);
+        }
+
+        #[test]
+        fn invalid_keys() {
+            let mut public = [0; 64];
+            assert!(matches!(Unsupported.public_key(&[0; 32], &mut public), Err(Error::User)));
+            assert!(matches!(Unsupported.public_key(&[0xff; 32], &mut public), Err(Error::User)));
+            let mut shared = [0; 32];
+            let result = Unsupported.agree(&[0x11; 32], &[0x42; 64], &mut shared);
+            assert!(matches!(result, Err(Error::User)));
+        }
+    }
+}
@@ -0,0 +1,113 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Ed25519 signature verification.
+//!
+//! Public keys are 32-byte compressed Edwards points and signatures are 64 bytes, as specified by
+//! RFC 8032.
+
+use crate::{Error, Unimplemented, Unsupported};
+
+/// Ed25519 interface.
+pub trait Api {
+    /// Whether Ed25519 is supported.
+    fn is_supported(&mut self) -> bool;
+
+    /// Verifies the signature of a message.
+    ///
+    /// Returns whether the signature is valid. This is a user error if the public key is not a
+    /// valid point.
+    fn verify(
+        &mut self, public: &[u8; 32], message: &[u8], signature: &[u8; 64],
+    ) -> Result<bool, Error>;
+}
+
+impl Api for Unimplemented {
+    fn is_supported(&mut self) -> bool {
+        unreachable!()
+    }
+
+    fn verify(&mut self, _: &[u8; 32], _: &[u8], _: &[u8; 64]) -> Result<bool, Error> {
+        unreachable!()
+    }
+}
+
+#[cfg(not(feature = "software-crypto-ed25519"))]
+mod unsupported {
+    use super::*;
+
+    impl Api for Unsupported {
+        fn is_supported(&mut self) -> bool {
+            false
+        }
+
+        fn verify(&mut self, _: &[u8; 32], _: &[u8], _: &[u8; 64]) -> Result<bool, Error> {
+            Err(Error::User)
+        }
+    }
+}
+
+#[cfg(feature = "software-crypto-ed25519")]
+mod unsupported {
+    use ed25519_dalek::{Signature, VerifyingKey};
+
+    use super::*;
+
+    impl Api for Unsupported {
+        fn is_supported(&mut self) -> bool {
+            true
+        }
+
+        fn verify(
+            &mut self, public: &[u8; 32], message: &[u8], signature: &[u8; 64],
+        ) -> Result<bool, Error> {
+            let public = VerifyingKey::from_bytes(public).map_err(|_| Error::User)?;
+            let signature = Signature::from_bytes(signature);
+            Ok(public.verify_strict(message, &signature).is_ok())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        // Test 2 of RFC 8032 section 7.1.
+        const PUBLIC: [u8; 32] = [
+            0x3d, 0x40, 0x17, 0xc3, 0xe8, 0x43, 0x89, 0x5a, 0x92, 0xb7, 0x0a, 0xa7, 0x4d, 0x1b,
+            0x7e, 0xbc, 0x9c, 0x98, 0x2c, 0xcf, 0x2e, 0xc4, 0x96, 0x8c, 0xc0, 0xcd, 0x55, 0xf1,
+            0x2a, 0xf4, 0x66, 0x0c,
+        ];
+        const MESSAGE: [u8; 1] = [0x72];
+        const SIGNATURE: [u8; 64] = [
+            0x92, 0xa0, 0x09, 0xa9, 0xf0, 0xd4, 0xca, 0xb8, 0x72, 0x0e, 0x82, 0x0b, 0x5f, 0x64,
+            0x25, 0x40, 0xa2, 0xb2, 0x7b, 0x54, 0x16, 0x50, 0x3f, 0x8f, 0xb3, 0x76, 0x22, 0x23,
+            0xeb, 0xdb, 0x69, 0xda, 0x08, 0x5a, 0xc1, 0xe4, 0x3e, 0x15, 0x99, 0x6e, 0x45, 0x8f,
+            0x36, 0x13, 0xd0, 0xf1, 0x1d, 0x8c, 0x38, 0x7b, 0x2e, 0xae, 0xb4, 0x30, 0x2a, 0xee,
+            0xb0, 0x0d, 0x29, 0x16, 0x12, 0xbb, 0x0c, 0x00,
+        ];
+
+        #[test]
+        fn verify_rfc8032() {
+            assert!(Unsupported.verify(&PUBLIC, &MESSAGE, &SIGNATURE).unwrap());
+        }
+
+        #[test]
+        fn verify_tampered() {
+            assert!(!Unsupported.verify(&PUBLIC, &[0x73], &SIGNATURE).unwrap());
+            let mut signature = SIGNATURE;
+            signature[0] ^= 1;
+            assert!(!Unsupported.verify(&PUBLIC, &MESSAGE, &signature).unwrap());
+        }
+    }
+}
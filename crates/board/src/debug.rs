@@ -14,18 +14,225 @@
 
 //! Debugging and testing interface.
 
-use crate::{Unimplemented, Unsupported};
+use alloc::vec::Vec;
+use core::ops::Range;
+
+pub use wasefire_logger::Level;
+
+use crate::usb::hid::Report;
+use crate::{gpio, led_ring, power, stepper, Error, Unimplemented, Unsupported};
+
+/// Debugging event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// A debugging shell line might be available.
+    ///
+    /// This event is handled by the scheduler and never reaches applets.
+    Shell,
+
+    /// The platform is asked to stop (e.g. Ctrl-C on the host).
+    ///
+    /// The scheduler finishes the running event handler, flushes the storage, and exits. This
+    /// event is handled by the scheduler and never reaches applets.
+    Shutdown,
+}
+
+impl From<Event> for crate::Event {
+    fn from(event: Event) -> Self {
+        crate::Event::Debug(event)
+    }
+}
+
+/// Side effect of an applet skipped in dry-run mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Effect {
+    /// Store entries would be written with a value.
+    ///
+    /// This is a single key for entries and a range of keys for fragmented entries.
+    StoreInsert { keys: Range<usize>, value: Vec<u8> },
+
+    /// Store entries would be removed.
+    StoreRemove { keys: Range<usize> },
+
+    /// A LED would be turned on or off.
+    LedSet { led: usize, on: bool },
+
+    /// The brightness of a LED would be set.
+    LedBrightness { led: usize, brightness: u8 },
+
+    /// Data would be written to a USB serial port.
+    UsbSerialWrite { port: usize, data: Vec<u8> },
+
+    /// A report would be written to the USB HID interface.
+    UsbHidWrite { report: Report },
+
+    /// A GPIO would be configured.
+    GpioConfigure { gpio: usize, mode: gpio::Mode },
+
+    /// A GPIO output would be driven to a level.
+    GpioWrite { gpio: usize, level: bool },
+
+    /// A stepper motor would move to a position.
+    StepperMove { stepper: usize, position: i32, profile: stepper::Profile },
+
+    /// The LED ring would start an animation.
+    LedRingAnimate { animation: led_ring::Animation },
+
+    /// The LED ring would stop animating and turn off.
+    LedRingStop,
+
+    /// A peripheral would be powered on.
+    PowerEnable { peripheral: power::Peripheral },
+
+    /// A peripheral would be powered off.
+    PowerDisable { peripheral: power::Peripheral },
+
+    /// A PWM output would be configured.
+    PwmConfigure { pwm: usize, freq_hz: u32, duty_pct: u8 },
+
+    /// A PWM output would be stopped.
+    PwmStop { pwm: usize },
+
+    /// The baudrate of a UART would be set.
+    UartSetBaudrate { uart: usize, baudrate: u32 },
+
+    /// Data would be written to a UART.
+    UartWrite { uart: usize, data: Vec<u8> },
+
+    /// The platform would reboot.
+    Reboot,
+
+    /// The storage would be wiped and the platform would reboot.
+    FactoryReset,
+
+    /// The staging bank would be erased to start a firmware update.
+    UpdateStart,
+
+    /// A chunk of firmware would be written at an offset of the staging bank.
+    UpdateWrite { offset: usize, data: Vec<u8> },
+
+    /// The staged firmware would be applied and the platform would reboot.
+    FirmwareUpdate,
+}
+
+/// Applet fault, reported before the platform stops.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fault<'a> {
+    /// Link name of the host function in which the applet trapped.
+    ///
+    /// This is `None` if the applet trapped in WebAssembly (e.g. `unreachable` after a panic).
+    pub function: Option<&'a str>,
+
+    /// The last line printed by the applet, if any.
+    ///
+    /// For panicking applets, this is the panic message.
+    pub message: Option<&'a str>,
+}
+
+impl core::fmt::Display for Fault<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.function {
+            Some(function) => write!(f, "Applet trapped in host function {function:?}.")?,
+            None => write!(f, "Applet trapped in wasm.")?,
+        }
+        if let Some(message) = self.message {
+            write!(f, " Last line: {message}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Statistics of the platform event queue.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct QueueStats {
+    /// Number of queued events.
+    pub depth: usize,
+
+    /// Number of events dropped because the queue was full.
+    pub dropped: usize,
+}
 
 /// Debugging and testing interface.
 pub trait Api {
     /// Exits the platform with a success/failure result.
     fn exit(&mut self, success: bool) -> !;
+
+    /// Reads a line of the debugging shell into a buffer.
+    ///
+    /// Returns the length of the line. It could be zero if there's nothing to read. The line is
+    /// truncated to the buffer length.
+    fn shell_read(&mut self, _line: &mut [u8]) -> Result<usize, Error> {
+        Ok(0)
+    }
+
+    /// Writes the output of the debugging shell.
+    fn shell_write(&mut self, _output: &[u8]) -> Result<(), Error> {
+        Err(Error::User)
+    }
+
+    /// Whether applets run in dry-run mode.
+    ///
+    /// In dry-run mode, the scheduler doesn't perform side-effecting operations (storage writes,
+    /// outputs, and reboots) but records them as [`Effect`] with [`Self::record()`]. Reads
+    /// still return real values. This is useful to vet untrusted applets.
+    fn dry_run(&mut self) -> bool {
+        false
+    }
+
+    /// Records a side effect skipped in dry-run mode.
+    fn record(&mut self, _effect: Effect) {}
+
+    /// Sets the most verbose level of the platform logs.
+    ///
+    /// Levels disabled at compile-time can't be enabled.
+    fn set_log_level(&mut self, _level: Level) -> Result<(), Error> {
+        Err(Error::User)
+    }
+
+    /// Reports an applet fault before the platform stops.
+    ///
+    /// The panic handler of the platform may not reach the outside world, so this is the place to
+    /// report the fault (e.g. over serial). By default, this does nothing.
+    fn on_applet_fault(&mut self, _fault: &Fault) {}
+
+    /// Returns the statistics of the platform event queue.
+    fn queue_stats(&mut self) -> Result<QueueStats, Error> {
+        Err(Error::User)
+    }
 }
 
 impl Api for Unimplemented {
     fn exit(&mut self, _: bool) -> ! {
         unreachable!()
     }
+
+    fn shell_read(&mut self, _: &mut [u8]) -> Result<usize, Error> {
+        unreachable!()
+    }
+
+    fn shell_write(&mut self, _: &[u8]) -> Result<(), Error> {
+        unreachable!()
+    }
+
+    fn dry_run(&mut self) -> bool {
+        unreachable!()
+    }
+
+    fn record(&mut self, _: Effect) {
+        unreachable!()
+    }
+
+    fn set_log_level(&mut self, _: Level) -> Result<(), Error> {
+        unreachable!()
+    }
+
+    fn on_applet_fault(&mut self, _: &Fault) {
+        unreachable!()
+    }
+
+    fn queue_stats(&mut self) -> Result<QueueStats, Error> {
+        unreachable!()
+    }
 }
 
 impl Api for Unsupported {
@@ -0,0 +1,143 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Quadrature encoder interface.
+//!
+//! A quadrature encoder counts the steps of a rotating shaft (e.g. of a motor for closed-loop
+//! motion). It may also have an index pin pulsing once per revolution at a fixed angle, which is
+//! used for homing: the position is zeroed at the next index pulse such that later positions are
+//! relative to the index angle. The shaft must be rotated while homing (e.g. with a
+//! [stepper motor](crate::stepper)).
+
+use crate::{Error, Unimplemented, Unsupported};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// The encoder position was zeroed at the index pulse.
+    Homed {
+        /// The encoder that completed homing.
+        encoder: usize,
+    },
+}
+
+impl From<Event> for crate::Event {
+    fn from(event: Event) -> Self {
+        crate::Event::Encoder(event)
+    }
+}
+
+pub trait Api {
+    /// Returns how many encoders are available.
+    ///
+    /// Encoders are identified by an integer smaller than this value.
+    fn count(&mut self) -> usize;
+
+    /// Returns the current position of an encoder in steps.
+    fn position(&mut self, encoder: usize) -> Result<i32, Error>;
+
+    /// Starts homing an encoder.
+    ///
+    /// The position is zeroed at the next index pulse and a [`Event::Homed`] event triggers.
+    /// Homing an encoder already homing is a no-op.
+    fn home(&mut self, encoder: usize) -> Result<(), Error>;
+}
+
+impl Api for Unimplemented {
+    fn count(&mut self) -> usize {
+        unreachable!()
+    }
+
+    fn position(&mut self, _: usize) -> Result<i32, Error> {
+        unreachable!()
+    }
+
+    fn home(&mut self, _: usize) -> Result<(), Error> {
+        unreachable!()
+    }
+}
+
+impl Api for Unsupported {
+    fn count(&mut self) -> usize {
+        0
+    }
+
+    fn position(&mut self, _: usize) -> Result<i32, Error> {
+        Err(Error::User)
+    }
+
+    fn home(&mut self, _: usize) -> Result<(), Error> {
+        Err(Error::User)
+    }
+}
+
+/// Helper tracking the position of an encoder.
+///
+/// Boards feed the counted steps and the index pulses.
+#[derive(Debug, Default, Clone)]
+pub struct Tracker {
+    position: i32,
+    homing: bool,
+}
+
+impl Tracker {
+    /// Returns the current position.
+    pub fn position(&self) -> i32 {
+        self.position
+    }
+
+    /// Returns whether homing is in progress.
+    pub fn is_homing(&self) -> bool {
+        self.homing
+    }
+
+    /// Starts homing.
+    pub fn home(&mut self) {
+        self.homing = true;
+    }
+
+    /// Accounts for steps counted since the last call.
+    pub fn count(&mut self, steps: i32) {
+        self.position = self.position.wrapping_add(steps);
+    }
+
+    /// Handles an index pulse and returns whether homing completed.
+    pub fn index(&mut self) -> bool {
+        if !self.homing {
+            return false;
+        }
+        self.homing = false;
+        self.position = 0;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracker_homing() {
+        let mut tracker = Tracker::default();
+        tracker.count(10);
+        assert!(!tracker.index());
+        assert_eq!(tracker.position(), 10);
+        tracker.home();
+        tracker.count(-3);
+        assert!(tracker.index());
+        assert_eq!(tracker.position(), 0);
+        tracker.count(-3);
+        assert!(!tracker.index());
+        assert_eq!(tracker.position(), -3);
+    }
+}
@@ -0,0 +1,96 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! General-purpose input/output interface.
+//!
+//! A board exposes a fixed table of GPIOs, usually the externally available header pins. They can
+//! be configured as input or output and are typically used to bit-bang protocols that dedicated
+//! peripherals don't cover.
+
+use crate::{Error, Unimplemented, Unsupported};
+
+/// GPIO configuration.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Mode {
+    /// Input with an internal pull-up resistor.
+    InputPullUp,
+
+    /// Input with an internal pull-down resistor.
+    InputPullDown,
+
+    /// Output driving both levels.
+    OutputPushPull,
+
+    /// Output driving only the low level (the high level is left floating).
+    OutputOpenDrain,
+}
+
+impl Mode {
+    /// Returns whether the mode is an output mode.
+    pub fn is_output(self) -> bool {
+        matches!(self, Mode::OutputPushPull | Mode::OutputOpenDrain)
+    }
+}
+
+pub trait Api {
+    /// Returns how many GPIOs are available.
+    ///
+    /// GPIOs are identified by an integer smaller than this value.
+    fn count(&mut self) -> usize;
+
+    /// Configures a GPIO.
+    fn configure(&mut self, gpio: usize, mode: Mode) -> Result<(), Error>;
+
+    /// Returns the level of a configured GPIO.
+    fn read(&mut self, gpio: usize) -> Result<bool, Error>;
+
+    /// Sets the level of a GPIO configured as output.
+    fn write(&mut self, gpio: usize, value: bool) -> Result<(), Error>;
+}
+
+impl Api for Unimplemented {
+    fn count(&mut self) -> usize {
+        unreachable!()
+    }
+
+    fn configure(&mut self, _: usize, _: Mode) -> Result<(), Error> {
+        unreachable!()
+    }
+
+    fn read(&mut self, _: usize) -> Result<bool, Error> {
+        unreachable!()
+    }
+
+    fn write(&mut self, _: usize, _: bool) -> Result<(), Error> {
+        unreachable!()
+    }
+}
+
+impl Api for Unsupported {
+    fn count(&mut self) -> usize {
+        0
+    }
+
+    fn configure(&mut self, _: usize, _: Mode) -> Result<(), Error> {
+        Err(Error::User)
+    }
+
+    fn read(&mut self, _: usize) -> Result<bool, Error> {
+        Err(Error::User)
+    }
+
+    fn write(&mut self, _: usize, _: bool) -> Result<(), Error> {
+        Err(Error::User)
+    }
+}
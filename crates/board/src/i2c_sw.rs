@@ -0,0 +1,299 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Software I2C interface.
+//!
+//! This interface provides an I2C controller bit-banged on 2 GPIOs, for when the hardware I2C
+//! peripherals are busy or not routed to the desired pins. It is much slower than hardware I2C and
+//! keeps the CPU busy for the whole transfer. Boards may implement it with [`Master`] over
+//! [`GpioLines`], in which case the GPIOs are configured as open-drain outputs and need external
+//! pull-up resistors.
+
+use crate::clock::Api as _;
+use crate::gpio::{Api as _, Mode};
+use crate::{Error, Unimplemented, Unsupported};
+
+/// Bus configuration.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// GPIO of the data line.
+    pub sda: usize,
+
+    /// GPIO of the clock line.
+    pub scl: usize,
+
+    /// Clock rate in Hertz.
+    pub frequency_hz: u32,
+}
+
+pub trait Api {
+    /// Configures the bus.
+    ///
+    /// This must be called before any transfer.
+    fn configure(&mut self, config: &Config) -> Result<(), Error>;
+
+    /// Reads bytes from a device.
+    fn read(&mut self, addr: u8, data: &mut [u8]) -> Result<(), Error>;
+
+    /// Writes bytes to a device.
+    fn write(&mut self, addr: u8, data: &[u8]) -> Result<(), Error>;
+
+    /// Writes then reads bytes from a device with a repeated start condition.
+    fn write_read(&mut self, addr: u8, write: &[u8], read: &mut [u8]) -> Result<(), Error>;
+}
+
+impl Api for Unimplemented {
+    fn configure(&mut self, _: &Config) -> Result<(), Error> {
+        unreachable!()
+    }
+
+    fn read(&mut self, _: u8, _: &mut [u8]) -> Result<(), Error> {
+        unreachable!()
+    }
+
+    fn write(&mut self, _: u8, _: &[u8]) -> Result<(), Error> {
+        unreachable!()
+    }
+
+    fn write_read(&mut self, _: u8, _: &[u8], _: &mut [u8]) -> Result<(), Error> {
+        unreachable!()
+    }
+}
+
+impl Api for Unsupported {
+    fn configure(&mut self, _: &Config) -> Result<(), Error> {
+        Err(Error::User)
+    }
+
+    fn read(&mut self, _: u8, _: &mut [u8]) -> Result<(), Error> {
+        Err(Error::User)
+    }
+
+    fn write(&mut self, _: u8, _: &[u8]) -> Result<(), Error> {
+        Err(Error::User)
+    }
+
+    fn write_read(&mut self, _: u8, _: &[u8], _: &mut [u8]) -> Result<(), Error> {
+        Err(Error::User)
+    }
+}
+
+/// Open-drain lines of an I2C bus.
+///
+/// Setting a line high releases it, such that devices may still pull it low.
+pub trait Lines {
+    /// Releases or pulls low the data line.
+    fn set_sda(&mut self, high: bool) -> Result<(), Error>;
+
+    /// Releases or pulls low the clock line.
+    fn set_scl(&mut self, high: bool) -> Result<(), Error>;
+
+    /// Returns the level of the data line.
+    fn get_sda(&mut self) -> Result<bool, Error>;
+
+    /// Returns the level of the clock line.
+    fn get_scl(&mut self) -> Result<bool, Error>;
+
+    /// Waits for half a clock period.
+    fn delay(&mut self);
+}
+
+/// Number of delays a device may stretch the clock before the transfer is aborted.
+const MAX_STRETCH: usize = 100;
+
+/// Bit-banged I2C controller.
+pub struct Master<L: Lines>(pub L);
+
+impl<L: Lines> Master<L> {
+    /// Reads bytes from a device.
+    pub fn read(&mut self, addr: u8, data: &mut [u8]) -> Result<(), Error> {
+        self.transfer(|x| {
+            x.address(addr, true)?;
+            x.read_bytes(data)
+        })
+    }
+
+    /// Writes bytes to a device.
+    pub fn write(&mut self, addr: u8, data: &[u8]) -> Result<(), Error> {
+        self.transfer(|x| {
+            x.address(addr, false)?;
+            x.write_bytes(data)
+        })
+    }
+
+    /// Writes then reads bytes from a device with a repeated start condition.
+    pub fn write_read(&mut self, addr: u8, write: &[u8], read: &mut [u8]) -> Result<(), Error> {
+        self.transfer(|x| {
+            x.address(addr, false)?;
+            x.write_bytes(write)?;
+            x.start()?;
+            x.address(addr, true)?;
+            x.read_bytes(read)
+        })
+    }
+
+    /// Runs a transfer between a start and a stop condition.
+    ///
+    /// The stop condition is sent even if the transfer fails, to leave the bus idle.
+    fn transfer(&mut self, f: impl FnOnce(&mut Self) -> Result<(), Error>) -> Result<(), Error> {
+        let result = self.start().and_then(|()| f(self));
+        self.stop()?;
+        result
+    }
+
+    /// Sends a start (or repeated start) condition.
+    fn start(&mut self) -> Result<(), Error> {
+        self.0.set_sda(true)?;
+        self.0.delay();
+        self.release_scl()?;
+        self.0.delay();
+        self.0.set_sda(false)?;
+        self.0.delay();
+        self.0.set_scl(false)
+    }
+
+    /// Sends a stop condition.
+    fn stop(&mut self) -> Result<(), Error> {
+        self.0.set_sda(false)?;
+        self.0.delay();
+        self.release_scl()?;
+        self.0.delay();
+        self.0.set_sda(true)?;
+        self.0.delay();
+        Ok(())
+    }
+
+    /// Releases the clock line and waits for devices to release it too.
+    fn release_scl(&mut self) -> Result<(), Error> {
+        self.0.set_scl(true)?;
+        for _ in 0 .. MAX_STRETCH {
+            if self.0.get_scl()? {
+                return Ok(());
+            }
+            self.0.delay();
+        }
+        Err(Error::World)
+    }
+
+    fn write_bit(&mut self, bit: bool) -> Result<(), Error> {
+        self.0.set_sda(bit)?;
+        self.0.delay();
+        self.release_scl()?;
+        self.0.delay();
+        self.0.set_scl(false)
+    }
+
+    fn read_bit(&mut self) -> Result<bool, Error> {
+        self.0.set_sda(true)?;
+        self.0.delay();
+        self.release_scl()?;
+        self.0.delay();
+        let bit = self.0.get_sda()?;
+        self.0.set_scl(false)?;
+        Ok(bit)
+    }
+
+    /// Writes a byte and fails if the device does not acknowledge it.
+    fn write_byte(&mut self, byte: u8) -> Result<(), Error> {
+        for i in (0 .. 8).rev() {
+            self.write_bit(byte >> i & 1 == 1)?;
+        }
+        match self.read_bit()? {
+            false => Ok(()),
+            true => Err(Error::World),
+        }
+    }
+
+    fn read_byte(&mut self, ack: bool) -> Result<u8, Error> {
+        let mut byte = 0;
+        for _ in 0 .. 8 {
+            byte = byte << 1 | self.read_bit()? as u8;
+        }
+        self.write_bit(!ack)?;
+        Ok(byte)
+    }
+
+    fn address(&mut self, addr: u8, read: bool) -> Result<(), Error> {
+        if 0x7f < addr {
+            return Err(Error::User);
+        }
+        self.write_byte(addr << 1 | read as u8)
+    }
+
+    fn write_bytes(&mut self, data: &[u8]) -> Result<(), Error> {
+        data.iter().try_for_each(|&byte| self.write_byte(byte))
+    }
+
+    /// Reads bytes and acknowledges all of them but the last.
+    fn read_bytes(&mut self, data: &mut [u8]) -> Result<(), Error> {
+        let len = data.len();
+        for (i, byte) in data.iter_mut().enumerate() {
+            *byte = self.read_byte(i + 1 < len)?;
+        }
+        Ok(())
+    }
+}
+
+/// Bus lines over the GPIO interface of a board.
+///
+/// Delays busy-wait on the clock interface, so the actual clock rate is bounded by its resolution.
+pub struct GpioLines<'a, B: crate::Api> {
+    board: &'a mut B,
+    sda: usize,
+    scl: usize,
+    half_period_us: u64,
+}
+
+impl<'a, B: crate::Api> GpioLines<'a, B> {
+    /// Configures the GPIOs as open-drain outputs and releases the lines.
+    pub fn new(board: &'a mut B, config: &Config) -> Result<Self, Error> {
+        let Config { sda, scl, frequency_hz } = *config;
+        if sda == scl || frequency_hz == 0 {
+            return Err(Error::User);
+        }
+        for gpio in [sda, scl] {
+            board.gpio().configure(gpio, Mode::OutputOpenDrain)?;
+            board.gpio().write(gpio, true)?;
+        }
+        let half_period_us = (500_000 / frequency_hz as u64).max(1);
+        Ok(GpioLines { board, sda, scl, half_period_us })
+    }
+}
+
+impl<'a, B: crate::Api> Lines for GpioLines<'a, B> {
+    fn set_sda(&mut self, high: bool) -> Result<(), Error> {
+        self.board.gpio().write(self.sda, high)
+    }
+
+    fn set_scl(&mut self, high: bool) -> Result<(), Error> {
+        self.board.gpio().write(self.scl, high)
+    }
+
+    fn get_sda(&mut self) -> Result<bool, Error> {
+        self.board.gpio().read(self.sda)
+    }
+
+    fn get_scl(&mut self) -> Result<bool, Error> {
+        self.board.gpio().read(self.scl)
+    }
+
+    fn delay(&mut self) {
+        let Ok(start) = self.board.clock().uptime_us() else { return };
+        while let Ok(now) = self.board.clock().uptime_us() {
+            if self.half_period_us <= now.wrapping_sub(start) {
+                break;
+            }
+        }
+    }
+}
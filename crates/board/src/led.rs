@@ -14,7 +14,7 @@
 
 //! LED interface.
 //!
-//! A LED is an output interface with 2 states: on and off.
+//! A LED is an output interface with 2 states: on and off. Some LEDs also support brightness.
 
 use crate::{Error, Unimplemented, Unsupported};
 
@@ -30,6 +30,14 @@ pub trait Api {
 
     /// Sets the state of a given LED.
     fn set(&mut self, led: usize, on: bool) -> Result<(), Error>;
+
+    /// Sets the brightness of a given LED.
+    ///
+    /// A brightness of 0 is off and 255 is fully on. LEDs without brightness support are on if the
+    /// brightness is at least 50%.
+    fn set_brightness(&mut self, led: usize, brightness: u8) -> Result<(), Error> {
+        self.set(led, 128 <= brightness)
+    }
 }
 
 impl Api for Unimplemented {
@@ -44,6 +52,10 @@ impl Api for Unimplemented {
     fn set(&mut self, _: usize, _: bool) -> Result<(), Error> {
         unreachable!()
     }
+
+    fn set_brightness(&mut self, _: usize, _: u8) -> Result<(), Error> {
+        unreachable!()
+    }
 }
 
 impl Api for Unsupported {
@@ -0,0 +1,203 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Addressable LED ring interface.
+//!
+//! A board exposes at most one ring of addressable RGB LEDs (e.g. NeoPixels). Instead of updating
+//! the pixels itself, an applet selects an [`Animation`] which the board runs autonomously from a
+//! timer. The [`frame()`] helper computes the pixels of an animation at a given time, such that
+//! boards only need to periodically write its output to the LEDs.
+
+use crate::{Error, Unimplemented, Unsupported};
+
+/// Color of a pixel as red, green, and blue components.
+pub type Color = [u8; 3];
+
+/// Animation patterns.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Pattern {
+    /// A single lit pixel going around the ring.
+    Spinner,
+
+    /// An arc of lit pixels growing around the ring and restarting when full.
+    Progress,
+
+    /// A rainbow rotating around the ring (the color is ignored).
+    Rainbow,
+}
+
+/// Animation of the LED ring.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Animation {
+    /// The pattern to animate.
+    pub pattern: Pattern,
+
+    /// The number of steps per second.
+    ///
+    /// A step moves the pattern by one pixel. A speed of zero freezes the first frame.
+    pub speed: u32,
+
+    /// The color of the lit pixels.
+    pub color: Color,
+}
+
+impl Animation {
+    /// Returns the step of the animation after some time.
+    ///
+    /// The step saturates instead of overflowing.
+    pub fn step(&self, elapsed_us: u64) -> u64 {
+        let speed = self.speed as u64;
+        // The whole seconds and the remaining microseconds are scaled separately such that only the
+        // former can overflow.
+        let steps = (elapsed_us / 1_000_000).saturating_mul(speed);
+        steps.saturating_add(elapsed_us % 1_000_000 * speed / 1_000_000)
+    }
+
+    /// Returns the duration between steps, or `None` if the animation is frozen.
+    pub fn period_us(&self) -> Option<u64> {
+        (self.speed != 0).then(|| (1_000_000 / self.speed as u64).max(1))
+    }
+}
+
+pub trait Api {
+    /// Returns the number of pixels of the ring.
+    ///
+    /// This is zero if there is no ring.
+    fn count(&mut self) -> usize;
+
+    /// Starts an animation, replacing the current one if any.
+    fn animate(&mut self, animation: &Animation) -> Result<(), Error>;
+
+    /// Stops the current animation and turns all pixels off.
+    fn stop(&mut self) -> Result<(), Error>;
+}
+
+impl Api for Unimplemented {
+    fn count(&mut self) -> usize {
+        unreachable!()
+    }
+
+    fn animate(&mut self, _: &Animation) -> Result<(), Error> {
+        unreachable!()
+    }
+
+    fn stop(&mut self) -> Result<(), Error> {
+        unreachable!()
+    }
+}
+
+impl Api for Unsupported {
+    fn count(&mut self) -> usize {
+        0
+    }
+
+    fn animate(&mut self, _: &Animation) -> Result<(), Error> {
+        Err(Error::User)
+    }
+
+    fn stop(&mut self) -> Result<(), Error> {
+        Err(Error::User)
+    }
+}
+
+/// Computes the pixels of an animation after some time.
+pub fn frame(animation: &Animation, elapsed_us: u64, pixels: &mut [Color]) {
+    let count = pixels.len() as u64;
+    if count == 0 {
+        return;
+    }
+    let step = animation.step(elapsed_us) % count;
+    for (i, pixel) in pixels.iter_mut().enumerate() {
+        let i = i as u64;
+        *pixel = match animation.pattern {
+            Pattern::Spinner if i == step => animation.color,
+            Pattern::Progress if i <= step => animation.color,
+            Pattern::Rainbow => wheel(((i + step) % count * 256 / count) as u8),
+            _ => [0; 3],
+        };
+    }
+}
+
+/// Returns the color at some position of the color wheel.
+fn wheel(pos: u8) -> Color {
+    let pos = pos as u16 * 3;
+    match pos {
+        0 ..= 254 => [(255 - pos) as u8, pos as u8, 0],
+        255 ..= 509 => [0, (510 - pos) as u8, (pos - 255) as u8],
+        _ => [(pos - 510) as u8, 0, (765 - pos) as u8],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    const RED: Color = [255, 0, 0];
+
+    fn animation(pattern: Pattern, speed: u32) -> Animation {
+        Animation { pattern, speed, color: RED }
+    }
+
+    fn lit(animation: &Animation, elapsed_us: u64) -> Vec<usize> {
+        let mut pixels = [[0; 3]; 4];
+        frame(animation, elapsed_us, &mut pixels);
+        (0 .. pixels.len()).filter(|&i| pixels[i] != [0; 3]).collect()
+    }
+
+    #[test]
+    fn spinner() {
+        let spinner = animation(Pattern::Spinner, 2);
+        assert_eq!(lit(&spinner, 0), [0]);
+        assert_eq!(lit(&spinner, 499_999), [0]);
+        assert_eq!(lit(&spinner, 500_000), [1]);
+        assert_eq!(lit(&spinner, 1_500_000), [3]);
+        assert_eq!(lit(&spinner, 2_000_000), [0]);
+        assert_eq!(spinner.period_us(), Some(500_000));
+    }
+
+    #[test]
+    fn progress() {
+        let progress = animation(Pattern::Progress, 1);
+        assert_eq!(lit(&progress, 0), [0]);
+        assert_eq!(lit(&progress, 2_000_000), [0, 1, 2]);
+        assert_eq!(lit(&progress, 4_000_000), [0]);
+    }
+
+    #[test]
+    fn frozen() {
+        let spinner = animation(Pattern::Spinner, 0);
+        assert_eq!(lit(&spinner, 10_000_000), [0]);
+        assert_eq!(spinner.period_us(), None);
+    }
+
+    #[test]
+    fn step_saturates() {
+        let spinner = animation(Pattern::Spinner, 3);
+        assert_eq!(spinner.step(1_500_000), 4);
+        assert_eq!(spinner.step(u64::MAX), u64::MAX / 1_000_000 * 3 + 1);
+        let spinner = animation(Pattern::Spinner, u32::MAX);
+        assert_eq!(spinner.step(u64::MAX), u64::MAX);
+        assert_eq!(lit(&spinner, u64::MAX), [3]);
+    }
+
+    #[test]
+    fn rainbow() {
+        let mut pixels = [[0; 3]; 3];
+        frame(&animation(Pattern::Rainbow, 1), 1_000_000, &mut pixels);
+        assert_eq!(pixels, [wheel(85), wheel(170), wheel(0)]);
+        assert_eq!(wheel(0), [255, 0, 0]);
+    }
+}
@@ -23,16 +23,33 @@ extern crate alloc;
 
 use core::fmt::Debug;
 
-use wasefire_store::Storage;
+use wasefire_store::{Storage, Store};
 
+pub mod adc;
 pub mod button;
+pub mod capture;
+pub mod clock;
 pub mod crypto;
 pub mod debug;
+pub mod encoder;
+pub mod gpio;
+pub mod i2c_sw;
 pub mod led;
+pub mod led_ring;
+pub mod magnetometer;
+pub mod platform;
+pub mod power;
+pub mod pwm;
 pub mod rng;
+pub mod stepper;
 pub mod storage;
+pub mod store;
+pub mod temp;
 pub mod timer;
+pub mod uart;
+pub mod update;
 pub mod usb;
+pub mod watchdog;
 
 // For consistency.
 type Get<B> = B;
@@ -65,15 +82,29 @@ pub trait Api: Types {
     /// Storage type.
     type Storage: Storage;
 
-    /// Takes the storage from the board.
+    /// Calls a function with the store of the board.
     ///
-    /// This function returns `Some` at most once and if it does, it does so on the first call.
-    fn take_storage(&mut self) -> Option<Self::Storage>;
+    /// The store is shared by the applets and the platform. The platform owns the keys in
+    /// [`store::RESERVED_KEYS`] (see [`store::Platform`]). The function must not call back into the
+    /// board store.
+    fn with_store<R>(&mut self, f: impl FnOnce(&mut Store<Self::Storage>) -> R) -> R;
+
+    type Adc<'a>: adc::Api
+    where Self: 'a;
+    fn adc(&mut self) -> Self::Adc<'_>;
 
     type Button<'a>: button::Api
     where Self: 'a;
     fn button(&mut self) -> Self::Button<'_>;
 
+    type Capture<'a>: capture::Api
+    where Self: 'a;
+    fn capture(&mut self) -> Self::Capture<'_>;
+
+    type Clock<'a>: clock::Api
+    where Self: 'a;
+    fn clock(&mut self) -> Self::Clock<'_>;
+
     type Crypto<'a>: crypto::Api<<Self as Types>::Crypto>
     where Self: 'a;
     fn crypto(&mut self) -> <Self as Api>::Crypto<'_>;
@@ -82,39 +113,141 @@ pub trait Api: Types {
     where Self: 'a;
     fn debug(&mut self) -> Self::Debug<'_>;
 
+    type Encoder<'a>: encoder::Api
+    where Self: 'a;
+    fn encoder(&mut self) -> Self::Encoder<'_>;
+
+    type Gpio<'a>: gpio::Api
+    where Self: 'a;
+    fn gpio(&mut self) -> Self::Gpio<'_>;
+
+    type I2cSw<'a>: i2c_sw::Api
+    where Self: 'a;
+    fn i2c_sw(&mut self) -> Self::I2cSw<'_>;
+
     type Led<'a>: led::Api
     where Self: 'a;
     fn led(&mut self) -> Self::Led<'_>;
 
+    type LedRing<'a>: led_ring::Api
+    where Self: 'a;
+    fn led_ring(&mut self) -> Self::LedRing<'_>;
+
+    type Magnetometer<'a>: magnetometer::Api
+    where Self: 'a;
+    fn magnetometer(&mut self) -> Self::Magnetometer<'_>;
+
+    type Platform<'a>: platform::Api
+    where Self: 'a;
+    fn platform(&mut self) -> Self::Platform<'_>;
+
+    type Power<'a>: power::Api
+    where Self: 'a;
+    fn power(&mut self) -> Self::Power<'_>;
+
+    type Pwm<'a>: pwm::Api
+    where Self: 'a;
+    fn pwm(&mut self) -> Self::Pwm<'_>;
+
     type Rng<'a>: rng::Api
     where Self: 'a;
     fn rng(&mut self) -> Self::Rng<'_>;
 
+    type Stepper<'a>: stepper::Api
+    where Self: 'a;
+    fn stepper(&mut self) -> Self::Stepper<'_>;
+
+    type Temp<'a>: temp::Api
+    where Self: 'a;
+    fn temp(&mut self) -> Self::Temp<'_>;
+
     type Timer<'a>: timer::Api
     where Self: 'a;
     fn timer(&mut self) -> Self::Timer<'_>;
 
+    type Uart<'a>: uart::Api
+    where Self: 'a;
+    fn uart(&mut self) -> Self::Uart<'_>;
+
+    type Update<'a>: update::Api
+    where Self: 'a;
+    fn update(&mut self) -> Self::Update<'_>;
+
     type Usb<'a>: usb::Api
     where Self: 'a;
     fn usb(&mut self) -> Self::Usb<'_>;
+
+    type Watchdog<'a>: watchdog::Api
+    where Self: 'a;
+    fn watchdog(&mut self) -> Self::Watchdog<'_>;
 }
 
 /// Events that interfaces may trigger.
 ///
 /// Events are de-duplicated if the previous one was not processed yet, because some events may
 /// trigger repeatedly.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Event {
     /// Button event.
     Button(button::Event),
 
+    /// Input capture event.
+    Capture(capture::Event),
+
+    /// Debugging event.
+    Debug(debug::Event),
+
+    /// Encoder event.
+    Encoder(encoder::Event),
+
+    /// Button gesture event.
+    Gesture(button::GestureEvent),
+
+    /// Stepper motor event.
+    Stepper(stepper::Event),
+
     /// Timer event.
     Timer(timer::Event),
 
+    /// UART event.
+    Uart(uart::Event),
+
     /// USB event.
     Usb(usb::Event),
 }
 
+impl Event {
+    /// Returns the dispatch priority of the event.
+    pub fn priority(&self) -> Priority {
+        match self {
+            Event::Usb(_) | Event::Uart(_) => Priority::High,
+            Event::Button(_)
+            | Event::Capture(_)
+            | Event::Debug(_)
+            | Event::Encoder(_)
+            | Event::Gesture(_)
+            | Event::Stepper(_) => Priority::Normal,
+            Event::Timer(_) => Priority::Low,
+        }
+    }
+}
+
+/// Dispatch priority of events.
+///
+/// Queued events of higher priority are dispatched first. Events of the same priority are
+/// dispatched in order.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    /// Events without latency needs, like periodic timers.
+    Low,
+
+    /// Events triggered by the user, like buttons.
+    Normal,
+
+    /// Events of communication interfaces, which may lose data if not handled soon.
+    High,
+}
+
 /// Errors that interfaces may return.
 ///
 /// Because a board interfaces between the user and the world, there's 2 types of errors: those due
@@ -163,7 +296,12 @@ mod tests {
             }
 
             type Storage = Unimplemented;
-            fn take_storage(&mut self) -> Option<Self::Storage> {
+            fn with_store<R>(&mut self, _: impl FnOnce(&mut Store<Self::Storage>) -> R) -> R {
+                todo!()
+            }
+
+            type Adc<'a> = Unimplemented;
+            fn adc(&mut self) -> Self::Adc<'_> {
                 todo!()
             }
 
@@ -172,6 +310,16 @@ mod tests {
                 todo!()
             }
 
+            type Capture<'a> = Unimplemented;
+            fn capture(&mut self) -> Self::Capture<'_> {
+                todo!()
+            }
+
+            type Clock<'a> = Unimplemented;
+            fn clock(&mut self) -> Self::Clock<'_> {
+                todo!()
+            }
+
             type Crypto<'a> = Unimplemented;
             fn crypto(&mut self) -> Unimplemented {
                 todo!()
@@ -182,25 +330,90 @@ mod tests {
                 todo!()
             }
 
+            type Encoder<'a> = Unimplemented;
+            fn encoder(&mut self) -> Self::Encoder<'_> {
+                todo!()
+            }
+
+            type Gpio<'a> = Unimplemented;
+            fn gpio(&mut self) -> Self::Gpio<'_> {
+                todo!()
+            }
+
+            type I2cSw<'a> = Unimplemented;
+            fn i2c_sw(&mut self) -> Self::I2cSw<'_> {
+                todo!()
+            }
+
             type Led<'a> = Unimplemented;
             fn led(&mut self) -> Self::Led<'_> {
                 todo!()
             }
 
+            type LedRing<'a> = Unimplemented;
+            fn led_ring(&mut self) -> Self::LedRing<'_> {
+                todo!()
+            }
+
+            type Magnetometer<'a> = Unimplemented;
+            fn magnetometer(&mut self) -> Self::Magnetometer<'_> {
+                todo!()
+            }
+
+            type Platform<'a> = Unimplemented;
+            fn platform(&mut self) -> Self::Platform<'_> {
+                todo!()
+            }
+
+            type Power<'a> = Unimplemented;
+            fn power(&mut self) -> Self::Power<'_> {
+                todo!()
+            }
+
+            type Pwm<'a> = Unimplemented;
+            fn pwm(&mut self) -> Self::Pwm<'_> {
+                todo!()
+            }
+
             type Rng<'a> = Unimplemented;
             fn rng(&mut self) -> Self::Rng<'_> {
                 todo!()
             }
 
+            type Stepper<'a> = Unimplemented;
+            fn stepper(&mut self) -> Self::Stepper<'_> {
+                todo!()
+            }
+
+            type Temp<'a> = Unimplemented;
+            fn temp(&mut self) -> Self::Temp<'_> {
+                todo!()
+            }
+
             type Timer<'a> = Unimplemented;
             fn timer(&mut self) -> Self::Timer<'_> {
                 todo!()
             }
 
+            type Uart<'a> = Unimplemented;
+            fn uart(&mut self) -> Self::Uart<'_> {
+                todo!()
+            }
+
+            type Update<'a> = Unimplemented;
+            fn update(&mut self) -> Self::Update<'_> {
+                todo!()
+            }
+
             type Usb<'a> = Unimplemented;
             fn usb(&mut self) -> Self::Usb<'_> {
                 todo!()
             }
+
+            type Watchdog<'a> = Unimplemented;
+            fn watchdog(&mut self) -> Self::Watchdog<'_> {
+                todo!()
+            }
         }
     }
 
@@ -220,8 +433,13 @@ mod tests {
             }
 
             type Storage = Unsupported;
-            fn take_storage(&mut self) -> Option<Self::Storage> {
-                None
+            fn with_store<R>(&mut self, _: impl FnOnce(&mut Store<Self::Storage>) -> R) -> R {
+                unreachable!()
+            }
+
+            type Adc<'a> = Unsupported;
+            fn adc(&mut self) -> Self::Adc<'_> {
+                Unsupported
             }
 
             type Button<'a> = Unsupported;
@@ -229,6 +447,16 @@ mod tests {
                 Unsupported
             }
 
+            type Capture<'a> = Unsupported;
+            fn capture(&mut self) -> Self::Capture<'_> {
+                Unsupported
+            }
+
+            type Clock<'a> = Unsupported;
+            fn clock(&mut self) -> Self::Clock<'_> {
+                Unsupported
+            }
+
             type Crypto<'a> = Unsupported;
             fn crypto(&mut self) -> Unsupported {
                 Unsupported
@@ -239,25 +467,90 @@ mod tests {
                 Unsupported
             }
 
+            type Encoder<'a> = Unsupported;
+            fn encoder(&mut self) -> Self::Encoder<'_> {
+                Unsupported
+            }
+
+            type Gpio<'a> = Unsupported;
+            fn gpio(&mut self) -> Self::Gpio<'_> {
+                Unsupported
+            }
+
+            type I2cSw<'a> = Unsupported;
+            fn i2c_sw(&mut self) -> Self::I2cSw<'_> {
+                Unsupported
+            }
+
             type Led<'a> = Unsupported;
             fn led(&mut self) -> Self::Led<'_> {
                 Unsupported
             }
 
+            type LedRing<'a> = Unsupported;
+            fn led_ring(&mut self) -> Self::LedRing<'_> {
+                Unsupported
+            }
+
+            type Magnetometer<'a> = Unsupported;
+            fn magnetometer(&mut self) -> Self::Magnetometer<'_> {
+                Unsupported
+            }
+
+            type Platform<'a> = Unsupported;
+            fn platform(&mut self) -> Self::Platform<'_> {
+                Unsupported
+            }
+
+            type Power<'a> = Unsupported;
+            fn power(&mut self) -> Self::Power<'_> {
+                Unsupported
+            }
+
+            type Pwm<'a> = Unsupported;
+            fn pwm(&mut self) -> Self::Pwm<'_> {
+                Unsupported
+            }
+
             type Rng<'a> = Unsupported;
             fn rng(&mut self) -> Self::Rng<'_> {
                 Unsupported
             }
 
+            type Stepper<'a> = Unsupported;
+            fn stepper(&mut self) -> Self::Stepper<'_> {
+                Unsupported
+            }
+
+            type Temp<'a> = Unsupported;
+            fn temp(&mut self) -> Self::Temp<'_> {
+                Unsupported
+            }
+
             type Timer<'a> = Unsupported;
             fn timer(&mut self) -> Self::Timer<'_> {
                 Unsupported
             }
 
+            type Uart<'a> = Unsupported;
+            fn uart(&mut self) -> Self::Uart<'_> {
+                Unsupported
+            }
+
+            type Update<'a> = Unsupported;
+            fn update(&mut self) -> Self::Update<'_> {
+                Unsupported
+            }
+
             type Usb<'a> = Unsupported;
             fn usb(&mut self) -> Self::Usb<'_> {
                 Unsupported
             }
+
+            type Watchdog<'a> = Unsupported;
+            fn watchdog(&mut self) -> Self::Watchdog<'_> {
+                Unsupported
+            }
         }
     }
 }
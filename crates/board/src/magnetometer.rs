@@ -0,0 +1,49 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Magnetometer interface.
+//!
+//! A board exposes at most one 3-axis magnetometer. Samples are the raw signed measurements of the
+//! sensor along its X, Y, and Z axes. Their scale depends on the sensor and its configuration, but
+//! is the same for all axes.
+
+use crate::{Error, Unimplemented, Unsupported};
+
+pub trait Api {
+    /// Returns whether a magnetometer is available.
+    fn is_supported(&mut self) -> bool;
+
+    /// Reads the magnetic field along the X, Y, and Z axes.
+    fn read(&mut self) -> Result<[i16; 3], Error>;
+}
+
+impl Api for Unimplemented {
+    fn is_supported(&mut self) -> bool {
+        unreachable!()
+    }
+
+    fn read(&mut self) -> Result<[i16; 3], Error> {
+        unreachable!()
+    }
+}
+
+impl Api for Unsupported {
+    fn is_supported(&mut self) -> bool {
+        false
+    }
+
+    fn read(&mut self) -> Result<[i16; 3], Error> {
+        Err(Error::User)
+    }
+}
@@ -0,0 +1,85 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Platform interface.
+
+use crate::{Error, Unimplemented, Unsupported};
+
+/// Heap statistics of the platform in bytes.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct HeapStats {
+    /// Bytes currently allocated.
+    pub used: usize,
+
+    /// Bytes currently available.
+    pub free: usize,
+
+    /// Maximum number of bytes allocated at once since boot.
+    pub high_water: usize,
+}
+
+pub trait Api {
+    /// Resets the platform.
+    ///
+    /// Implementations must wait for pending storage operations to complete before resetting, such
+    /// that the store is not corrupted. This function only returns on error.
+    fn reset(&mut self) -> Result<(), Error>;
+
+    /// Returns the heap statistics of the platform allocator.
+    fn heap_stats(&mut self) -> Result<HeapStats, Error>;
+
+    /// Returns the device-unique key to encrypt the store at rest.
+    ///
+    /// See [`crate::store::Encrypted`]. The key must not change across resets. It should be derived
+    /// from the device secrets with [`crate::crypto::hkdf`] rather than be a device secret itself.
+    fn storage_key(&mut self) -> Result<[u8; 32], Error> {
+        Err(Error::User)
+    }
+
+    /// Returns the unique ID of the device.
+    ///
+    /// The ID must not change across resets. It is not a secret: applets may use it to identify the
+    /// device (e.g. to provision per-device keys) but not as a key.
+    fn device_id(&mut self) -> Result<[u8; 8], Error> {
+        Err(Error::User)
+    }
+}
+
+impl Api for Unimplemented {
+    fn reset(&mut self) -> Result<(), Error> {
+        unreachable!()
+    }
+
+    fn heap_stats(&mut self) -> Result<HeapStats, Error> {
+        unreachable!()
+    }
+
+    fn storage_key(&mut self) -> Result<[u8; 32], Error> {
+        unreachable!()
+    }
+
+    fn device_id(&mut self) -> Result<[u8; 8], Error> {
+        unreachable!()
+    }
+}
+
+impl Api for Unsupported {
+    fn reset(&mut self) -> Result<(), Error> {
+        Err(Error::User)
+    }
+
+    fn heap_stats(&mut self) -> Result<HeapStats, Error> {
+        Err(Error::User)
+    }
+}
@@ -0,0 +1,85 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Peripheral power interface.
+//!
+//! This interface lets applets turn off the peripherals they don't use to save power. A peripheral
+//! in use (e.g. an armed timer) can't be disabled, and a disabled peripheral can't be used until
+//! enabled again.
+
+use crate::{Error, Unimplemented, Unsupported};
+
+/// Peripherals that may be disabled.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Peripheral {
+    /// The analog-to-digital converters.
+    Adc,
+
+    /// The magnetometer.
+    Magnetometer,
+
+    /// The random number generator.
+    Rng,
+
+    /// A timer identified by its index.
+    Timer(usize),
+
+    /// The USB device.
+    Usb,
+}
+
+pub trait Api {
+    /// Returns whether a peripheral is in use.
+    ///
+    /// A peripheral in use can't be disabled.
+    fn is_busy(&mut self, peripheral: Peripheral) -> Result<bool, Error>;
+
+    /// Disables a peripheral.
+    ///
+    /// This is a user error if the peripheral is in use or not supported.
+    fn disable_peripheral(&mut self, peripheral: Peripheral) -> Result<(), Error>;
+
+    /// Enables a peripheral.
+    ///
+    /// Peripherals are enabled by default. Enabling an enabled peripheral is a no-op.
+    fn enable_peripheral(&mut self, peripheral: Peripheral) -> Result<(), Error>;
+}
+
+impl Api for Unimplemented {
+    fn is_busy(&mut self, _: Peripheral) -> Result<bool, Error> {
+        unreachable!()
+    }
+
+    fn disable_peripheral(&mut self, _: Peripheral) -> Result<(), Error> {
+        unreachable!()
+    }
+
+    fn enable_peripheral(&mut self, _: Peripheral) -> Result<(), Error> {
+        unreachable!()
+    }
+}
+
+impl Api for Unsupported {
+    fn is_busy(&mut self, _: Peripheral) -> Result<bool, Error> {
+        Err(Error::User)
+    }
+
+    fn disable_peripheral(&mut self, _: Peripheral) -> Result<(), Error> {
+        Err(Error::User)
+    }
+
+    fn enable_peripheral(&mut self, _: Peripheral) -> Result<(), Error> {
+        Err(Error::User)
+    }
+}
@@ -0,0 +1,67 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! PWM output interface.
+//!
+//! A board exposes a fixed table of PWM outputs, for example to drive buzzers or servos. Each
+//! output has its own frequency. A duty cycle of 0% (resp. 100%) must be a steady low (resp. high)
+//! output, without glitches.
+
+use crate::{Error, Unimplemented, Unsupported};
+
+pub trait Api {
+    /// Returns how many PWM outputs are available.
+    ///
+    /// PWM outputs are identified by an integer smaller than this value.
+    fn count(&mut self) -> usize;
+
+    /// Configures and starts a PWM output.
+    ///
+    /// The duty cycle is in percent and at most 100. This is a user error if the frequency is not
+    /// supported by the board.
+    fn configure(&mut self, pwm: usize, freq_hz: u32, duty_pct: u8) -> Result<(), Error>;
+
+    /// Stops a PWM output.
+    ///
+    /// The output is steady low afterwards.
+    fn stop(&mut self, pwm: usize) -> Result<(), Error>;
+}
+
+impl Api for Unimplemented {
+    fn count(&mut self) -> usize {
+        unreachable!()
+    }
+
+    fn configure(&mut self, _: usize, _: u32, _: u8) -> Result<(), Error> {
+        unreachable!()
+    }
+
+    fn stop(&mut self, _: usize) -> Result<(), Error> {
+        unreachable!()
+    }
+}
+
+impl Api for Unsupported {
+    fn count(&mut self) -> usize {
+        0
+    }
+
+    fn configure(&mut self, _: usize, _: u32, _: u8) -> Result<(), Error> {
+        Err(Error::User)
+    }
+
+    fn stop(&mut self, _: usize) -> Result<(), Error> {
+        Err(Error::User)
+    }
+}
@@ -14,18 +14,57 @@
 
 //! Random number generator interface.
 
+#[cfg(feature = "rng-mixing")]
+use chacha20::cipher::{KeyIvInit, StreamCipher};
+
 use crate::{Error, Unimplemented, Unsupported};
 
+/// Health of a random number generator.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RngHealth {
+    /// The random number generator is not monitored.
+    Unknown,
+
+    /// The random number generator passed its health tests so far.
+    Healthy,
+
+    /// The random number generator failed a health test.
+    Failed,
+}
+
 /// Random number generator interface.
 pub trait Api {
     /// Fills a buffer with random bytes uniformly.
+    ///
+    /// Returns [`Error::World`] if the random number generator is not healthy.
     fn fill_bytes(&mut self, buffer: &mut [u8]) -> Result<(), Error>;
+
+    /// Returns the health of the random number generator.
+    fn health_check(&mut self) -> Result<RngHealth, Error> {
+        Ok(RngHealth::Unknown)
+    }
+
+    /// Mixes additional entropy into the random number generator.
+    ///
+    /// This is only meaningful for deterministic generators. Returns [`Error::User`] if the random
+    /// number generator can't be reseeded (for example because it is a true random source).
+    fn reseed(&mut self, _entropy: &[u8]) -> Result<(), Error> {
+        Err(Error::User)
+    }
 }
 
 impl Api for Unimplemented {
     fn fill_bytes(&mut self, _: &mut [u8]) -> Result<(), Error> {
         unreachable!()
     }
+
+    fn health_check(&mut self) -> Result<RngHealth, Error> {
+        unreachable!()
+    }
+
+    fn reseed(&mut self, _: &[u8]) -> Result<(), Error> {
+        unreachable!()
+    }
 }
 
 impl Api for Unsupported {
@@ -33,3 +72,182 @@ impl Api for Unsupported {
         Err(Error::User)
     }
 }
+
+/// Continuous health tests for byte-oriented noise sources.
+///
+/// This implements the repetition count test and the adaptive proportion test of NIST SP 800-90B
+/// (section 4.4) for a false positive probability of 2^-20. The repetition count test assumes a
+/// min-entropy of 2 bits per byte. The adaptive proportion test uses the cutoff of table 2 for a
+/// window of 512 samples, which is the one of a min-entropy of 0.5 bits per byte. Once a test
+/// fails, the health stays failed.
+#[derive(Debug, Default)]
+pub struct HealthTest {
+    samples: usize,
+    failed: bool,
+    /// Last sample and number of consecutive times it was seen.
+    repetition: (u8, usize),
+    /// First sample of the window and number of times it was seen in the window.
+    proportion: (u8, usize),
+}
+
+impl HealthTest {
+    /// Cutoff of the repetition count test.
+    const REPETITION_CUTOFF: usize = 11;
+
+    /// Window size of the adaptive proportion test.
+    const PROPORTION_WINDOW: usize = 512;
+
+    /// Cutoff of the adaptive proportion test.
+    const PROPORTION_CUTOFF: usize = 410;
+
+    /// Feeds the tests with a sample and returns whether the source is still healthy.
+    pub fn push(&mut self, sample: u8) -> bool {
+        if self.samples == 0 || self.repetition.0 != sample {
+            self.repetition = (sample, 0);
+        }
+        self.repetition.1 += 1;
+        if self.samples % Self::PROPORTION_WINDOW == 0 {
+            self.proportion = (sample, 0);
+        }
+        if self.proportion.0 == sample {
+            self.proportion.1 += 1;
+        }
+        self.samples = self.samples.wrapping_add(1);
+        if Self::REPETITION_CUTOFF <= self.repetition.1
+            || Self::PROPORTION_CUTOFF <= self.proportion.1
+        {
+            self.failed = true;
+        }
+        !self.failed
+    }
+
+    /// Returns the health of the source.
+    pub fn health(&self) -> RngHealth {
+        match (self.failed, self.samples) {
+            (true, _) => RngHealth::Failed,
+            (false, 0) => RngHealth::Unknown,
+            (false, _) => RngHealth::Healthy,
+        }
+    }
+}
+
+/// Mixes a random number generator with a ChaCha20 key stream.
+///
+/// The output of the random number generator is XORed with the key stream. When the seed comes
+/// from a source independent of the random number generator (e.g. clock jitter), a single biased
+/// source doesn't compromise the output.
+#[cfg(feature = "rng-mixing")]
+pub struct Mixer(chacha20::ChaCha20);
+
+#[cfg(feature = "rng-mixing")]
+impl Mixer {
+    /// Creates a mixer from a seed.
+    pub fn new(seed: &[u8; 32]) -> Self {
+        Mixer(chacha20::ChaCha20::new(seed.into(), &[0; 12].into()))
+    }
+
+    /// Mixes random bytes in place.
+    pub fn mix(&mut self, buffer: &mut [u8]) {
+        self.0.apply_keystream(buffer);
+    }
+
+    /// Mixes additional entropy into the seed.
+    ///
+    /// The new seed is derived from the key stream, such that previous outputs can't be recovered
+    /// from the new state.
+    pub fn reseed(&mut self, entropy: &[u8]) {
+        let mut seed = [0; 32];
+        self.0.apply_keystream(&mut seed);
+        for (i, x) in entropy.iter().enumerate() {
+            seed[i % 32] ^= x;
+        }
+        *self = Mixer::new(&seed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn health_test_healthy() {
+        let mut test = HealthTest::default();
+        assert_eq!(test.health(), RngHealth::Unknown);
+        for i in 0 .. 4096u32 {
+            // Simple LCG, good enough to pass the tests.
+            assert!(test.push((i.wrapping_mul(1103515245).wrapping_add(12345) >> 16) as u8));
+        }
+        assert_eq!(test.health(), RngHealth::Healthy);
+    }
+
+    #[test]
+    fn health_test_repetition() {
+        let mut test = HealthTest::default();
+        for _ in 0 .. HealthTest::REPETITION_CUTOFF - 1 {
+            assert!(test.push(0x42));
+        }
+        assert!(!test.push(0x42));
+        assert!(!test.push(0x43));
+        assert_eq!(test.health(), RngHealth::Failed);
+    }
+
+    #[test]
+    fn health_test_proportion() {
+        let mut test = HealthTest::default();
+        let mut healthy = true;
+        for i in 0 .. HealthTest::PROPORTION_WINDOW {
+            // Interleave other values to avoid failing the repetition count test.
+            healthy &= test.push(if i % 8 == 7 { i as u8 | 1 } else { 0 });
+        }
+        assert!(!healthy);
+        assert_eq!(test.health(), RngHealth::Failed);
+    }
+
+    /// Returns the chi-square statistic of the byte distribution against the uniform one.
+    #[cfg(feature = "rng-mixing")]
+    fn chi_square(data: &[u8]) -> f64 {
+        let mut counts = [0usize; 256];
+        data.iter().for_each(|&x| counts[x as usize] += 1);
+        let expected = data.len() as f64 / 256.;
+        counts.iter().map(|&x| (x as f64 - expected) * (x as f64 - expected) / expected).sum()
+    }
+
+    // Critical value of the chi-square distribution with 255 degrees of freedom at p = 0.001.
+    #[cfg(feature = "rng-mixing")]
+    const CHI_SQUARE_CUTOFF: f64 = 330.5;
+
+    #[cfg(feature = "rng-mixing")]
+    #[test]
+    fn mixer_chi_square() {
+        // A badly biased source only producing 16 values.
+        let mut state = 1u32;
+        let mut data: alloc::vec::Vec<u8> = (0 .. 256 * 1024)
+            .map(|_| {
+                state = state.wrapping_mul(1103515245).wrapping_add(12345);
+                (state >> 16) as u8 & 0x0f
+            })
+            .collect();
+        assert!(CHI_SQUARE_CUTOFF < chi_square(&data));
+        let mut mixer = Mixer::new(&[0x5c; 32]);
+        for chunk in data.chunks_mut(100) {
+            mixer.mix(chunk);
+        }
+        assert!(chi_square(&data) < CHI_SQUARE_CUTOFF);
+    }
+
+    #[cfg(feature = "rng-mixing")]
+    #[test]
+    fn mixer_reseed() {
+        let mut x = Mixer::new(&[0; 32]);
+        let mut y = Mixer::new(&[0; 32]);
+        let (mut a, mut b) = ([0; 64], [0; 64]);
+        x.mix(&mut a);
+        y.mix(&mut b);
+        assert_eq!(a, b);
+        x.reseed(b"jitter");
+        y.reseed(b"jitteR");
+        x.mix(&mut a);
+        y.mix(&mut b);
+        assert_ne!(a, b);
+    }
+}
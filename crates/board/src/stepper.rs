@@ -0,0 +1,203 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Stepper motor interface.
+//!
+//! A stepper motor is driven by a step pin and a direction pin. Moves are executed by the board
+//! (usually using a timer) following a trapezoidal acceleration profile, such that applets don't
+//! need to step in software.
+
+use crate::{Error, Unimplemented, Unsupported};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// The motor reached the target position of its last move.
+    Done {
+        /// The stepper motor that completed its move.
+        stepper: usize,
+    },
+}
+
+impl From<Event> for crate::Event {
+    fn from(event: Event) -> Self {
+        crate::Event::Stepper(event)
+    }
+}
+
+pub trait Api {
+    /// Returns how many stepper motors are available.
+    ///
+    /// Stepper motors are identified by an integer smaller than this value.
+    fn count(&mut self) -> usize;
+
+    /// Starts moving a stepper motor to an absolute position.
+    ///
+    /// The move is executed in the background and a [`Event::Done`] event triggers when the target
+    /// position is reached. Starting a move while the motor is still moving is a user error.
+    fn move_to(&mut self, stepper: usize, position: i32, profile: &Profile) -> Result<(), Error>;
+
+    /// Returns the current position of a stepper motor.
+    fn position(&mut self, stepper: usize) -> Result<i32, Error>;
+}
+
+impl Api for Unimplemented {
+    fn count(&mut self) -> usize {
+        unreachable!()
+    }
+
+    fn move_to(&mut self, _: usize, _: i32, _: &Profile) -> Result<(), Error> {
+        unreachable!()
+    }
+
+    fn position(&mut self, _: usize) -> Result<i32, Error> {
+        unreachable!()
+    }
+}
+
+impl Api for Unsupported {
+    fn count(&mut self) -> usize {
+        0
+    }
+
+    fn move_to(&mut self, _: usize, _: i32, _: &Profile) -> Result<(), Error> {
+        Err(Error::User)
+    }
+
+    fn position(&mut self, _: usize) -> Result<i32, Error> {
+        Err(Error::User)
+    }
+}
+
+/// Trapezoidal acceleration profile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    /// Maximum speed in steps per second.
+    pub max_speed: u32,
+
+    /// Acceleration (and deceleration) in steps per second squared.
+    pub acceleration: u32,
+}
+
+impl Profile {
+    /// Returns whether the profile can be executed.
+    pub fn is_valid(&self) -> bool {
+        0 < self.max_speed && 0 < self.acceleration
+    }
+}
+
+/// Planned move of a stepper motor.
+///
+/// This is a helper for boards to compute the delay between steps following a [`Profile`].
+#[derive(Debug, Clone)]
+pub struct Motion {
+    position: i32,
+    target: i32,
+    step: u32,
+    steps: u32,
+    profile: Profile,
+}
+
+impl Motion {
+    /// Plans a move from a position to a target following a valid profile.
+    pub fn new(position: i32, target: i32, profile: &Profile) -> Self {
+        debug_assert!(profile.is_valid());
+        let steps = position.abs_diff(target);
+        Motion { position, target, step: 0, steps, profile: profile.clone() }
+    }
+
+    /// Returns the current position.
+    pub fn position(&self) -> i32 {
+        self.position
+    }
+
+    /// Returns whether steps increment the position (as opposed to decrement).
+    pub fn forward(&self) -> bool {
+        self.position <= self.target
+    }
+
+    /// Returns the delay in microseconds before the next step, if any.
+    ///
+    /// The speed is the largest one satisfying the acceleration from the start position, the
+    /// deceleration to the target position, and the maximum speed.
+    pub fn delay_us(&self) -> Option<u32> {
+        if self.step == self.steps {
+            return None;
+        }
+        // On overflow, the speed is limited by the maximum speed (which is smaller) instead.
+        let acceleration = 2 * self.profile.acceleration as u64;
+        let limit = |steps: u32| match acceleration.checked_mul(steps as u64) {
+            Some(x) => isqrt(x),
+            None => u64::MAX,
+        };
+        let accel = limit(self.step + 1);
+        let decel = limit(self.steps - self.step);
+        let speed = accel.min(decel).min(self.profile.max_speed as u64).max(1);
+        Some((1_000_000 / speed) as u32)
+    }
+
+    /// Records that a step was executed.
+    pub fn step(&mut self) {
+        debug_assert!(self.step < self.steps);
+        self.step += 1;
+        match self.forward() {
+            true => self.position += 1,
+            false => self.position -= 1,
+        }
+    }
+}
+
+fn isqrt(x: u64) -> u64 {
+    let mut r = 0;
+    let mut b = 1 << 31;
+    while b > 0 {
+        let y = r | b;
+        if y * y <= x {
+            r = y;
+        }
+        b >>= 1;
+    }
+    r
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn motion_reaches_target_within_max_speed() {
+        let profile = Profile { max_speed: 1000, acceleration: 20000 };
+        for (position, target) in [(0, 200), (50, -150), (7, 7)] {
+            let mut motion = Motion::new(position, target, &profile);
+            let mut steps = 0;
+            while let Some(delay) = motion.delay_us() {
+                assert!(delay >= 1_000_000 / profile.max_speed);
+                motion.step();
+                steps += 1;
+            }
+            assert_eq!(motion.position(), target);
+            assert_eq!(steps, position.abs_diff(target));
+        }
+    }
+
+    #[test]
+    fn motion_large_profile() {
+        let profile = Profile { max_speed: u32::MAX, acceleration: u32::MAX };
+        let mut motion = Motion::new(i32::MIN, i32::MAX, &profile);
+        assert_eq!(motion.delay_us(), Some(10));
+        motion.step();
+        assert_eq!(motion.position(), i32::MIN + 1);
+        let profile = Profile { max_speed: 1000, acceleration: u32::MAX };
+        assert_eq!(Motion::new(i32::MIN, i32::MAX, &profile).delay_us(), Some(1000));
+    }
+}
@@ -0,0 +1,790 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Key-value store interface.
+//!
+//! This interface provides byte-string keys on top of the [`wasefire_store`] format, which only
+//! supports small integer keys. Each entry is stored under one integer key of a dedicated range as
+//! the length of its key (one byte), followed by the key, followed by the value. The store crate
+//! takes care of atomicity and wear leveling.
+//!
+//! Values may optionally be encrypted at rest with a [`Cipher`] (see [`Encrypted`]). Keys are
+//! stored in clear but authenticated with their value. The scheduler uses [`Encrypted`] for the
+//! applet store with its `encrypted-store` feature.
+//!
+//! The platform has its own key-value store in the [`RESERVED_KEYS`] of the board store (see
+//! [`Platform`]). Applets can't write those keys. Applet entries written there before the keys were
+//! reserved are moved to the platform key-value store by [`migrate()`].
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+pub use wasefire_store::WearStats;
+use wasefire_store::{Storage, Store, StoreError, StoreHandle};
+
+use crate::crypto::aes256_gcm::Api as _;
+use crate::crypto::Api as _;
+use crate::platform::Api as _;
+use crate::rng::Api as _;
+use crate::Error;
+
+/// Integer keys reserved for the platform key-value store.
+///
+/// The scheduler rejects applet writes to those keys.
+pub const RESERVED_KEYS: Range<usize> = 4032 .. 4096;
+
+/// Platform key-value store key marking that [`migrate()`] completed.
+const MIGRATED: &[u8] = b"migrated";
+
+/// Platform key-value store key recording whether values are encrypted (see [`Platform`]).
+const ENCRYPTED: &[u8] = b"encrypted";
+
+/// Returns the platform key-value store key of a migrated applet entry.
+///
+/// See [`migrate()`].
+pub fn legacy_key(key: usize) -> Vec<u8> {
+    alloc::format!("applet/{key}").into_bytes()
+}
+
+/// Migrates the applet entries written in the [`RESERVED_KEYS`] before they were reserved.
+///
+/// Each such entry is wrapped in place as a platform key-value store entry under its
+/// [`legacy_key()`], such that the scheduler can still let applets read and remove it. The
+/// scheduler calls this function when starting, before the platform key-value store is used.
+///
+/// This is idempotent and resumes after a power loss. Returns [`Error::User`] if an entry is too
+/// long to be wrapped or the platform key-value store is full.
+pub fn migrate<S: Storage>(store: &mut Store<S>) -> Result<(), Error> {
+    if let Ok(Some(_)) = KeyValue::new(store, RESERVED_KEYS).get(MIGRATED) {
+        return Ok(());
+    }
+    let mut entries = Vec::new();
+    for handle in store.iter().map_err(convert)? {
+        let handle = handle.map_err(convert)?;
+        if RESERVED_KEYS.contains(&handle.get_key()) {
+            entries.push((handle.get_key(), handle.get_value(store).map_err(convert)?));
+        }
+    }
+    for (key, value) in entries {
+        let name = legacy_key(key);
+        // The entry was wrapped before a power loss.
+        if value.first() == Some(&(name.len() as u8)) && value[1 ..].starts_with(&name) {
+            continue;
+        }
+        let mut content = Vec::with_capacity(1 + name.len() + value.len());
+        content.push(name.len() as u8);
+        content.extend_from_slice(&name);
+        content.extend_from_slice(&value);
+        store.insert(key, &content).map_err(convert)?;
+    }
+    KeyValue::new(store, RESERVED_KEYS).insert(MIGRATED, &[])
+}
+
+pub trait Api {
+    /// Associates a value to a key, overwriting the previous value if any.
+    ///
+    /// Keys are at most 255 bytes long.
+    fn insert(&mut self, key: &[u8], value: &[u8]) -> Result<(), Error>;
+
+    /// Returns the value associated to a key, if any.
+    fn get(&mut self, key: &[u8]) -> Result<Option<Vec<u8>>, Error>;
+
+    /// Removes the value associated to a key, if any.
+    fn remove(&mut self, key: &[u8]) -> Result<(), Error>;
+
+    /// Returns the keys with an associated value.
+    fn keys(&mut self) -> Result<Vec<Vec<u8>>, Error>;
+
+    /// Physically erases the whole storage backing the store.
+    ///
+    /// This is a factory reset: entries outside the key-value store (e.g. from applets) are erased
+    /// too. The platform should be reset afterwards.
+    fn wipe(&mut self) -> Result<(), Error>;
+
+    /// Returns the wear statistics of the storage backing the store.
+    ///
+    /// This covers the whole storage, not only the key-value entries.
+    fn wear_stats(&mut self) -> Result<WearStats, Error>;
+}
+
+/// Protection of the values at rest.
+pub trait Cipher {
+    /// Protects a value before it is stored, binding it to its key.
+    fn seal(&mut self, key: &[u8], value: &[u8]) -> Result<Vec<u8>, Error>;
+
+    /// Recovers a value after it is loaded, checking it is bound to its key.
+    ///
+    /// Returns [`Error::World`] if the stored value was tampered with.
+    fn open(&mut self, key: &[u8], sealed: &[u8]) -> Result<Vec<u8>, Error>;
+}
+
+/// Values are stored in clear.
+pub struct Clear;
+
+impl Cipher for Clear {
+    fn seal(&mut self, _: &[u8], value: &[u8]) -> Result<Vec<u8>, Error> {
+        Ok(value.to_vec())
+    }
+
+    fn open(&mut self, _: &[u8], sealed: &[u8]) -> Result<Vec<u8>, Error> {
+        Ok(sealed.to_vec())
+    }
+}
+
+/// Values are encrypted with AES-256-GCM using the board [storage
+/// key](crate::platform::Api::storage_key).
+///
+/// Sealed values are the random IV (12 bytes), followed by the tag (16 bytes), followed by the
+/// cipher text. The key of the entry is the associated data.
+pub struct Encrypted<'a, B: crate::Api> {
+    board: &'a mut B,
+    key: [u8; 32],
+}
+
+impl<'a, B: crate::Api> Encrypted<'a, B> {
+    /// Creates an AES-256-GCM cipher using the board storage key.
+    ///
+    /// Returns [`Error::User`] if the board doesn't support AES-256-GCM or a storage key.
+    pub fn new(board: &'a mut B) -> Result<Self, Error> {
+        if !board.crypto().aes256_gcm().is_supported() {
+            return Err(Error::User);
+        }
+        let key = board.platform().storage_key()?;
+        Ok(Encrypted { board, key })
+    }
+}
+
+impl<B: crate::Api> Cipher for Encrypted<'_, B> {
+    fn seal(&mut self, key: &[u8], value: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut sealed = vec![0; 28 + value.len()];
+        let (iv, rest) = sealed.split_at_mut(12);
+        let (tag, cipher) = rest.split_at_mut(16);
+        self.board.rng().fill_bytes(iv)?;
+        let iv = <&[u8; 12]>::try_from(&*iv).unwrap();
+        let tag = <&mut [u8; 16]>::try_from(tag).unwrap();
+        self.board.crypto().aes256_gcm().encrypt(&self.key, iv, key, value, cipher, tag)?;
+        Ok(sealed)
+    }
+
+    fn open(&mut self, key: &[u8], sealed: &[u8]) -> Result<Vec<u8>, Error> {
+        if sealed.len() < 28 {
+            return Err(Error::World);
+        }
+        let (iv, rest) = sealed.split_at(12);
+        let (tag, cipher) = rest.split_at(16);
+        let iv = <&[u8; 12]>::try_from(iv).unwrap();
+        let tag = <&[u8; 16]>::try_from(tag).unwrap();
+        let mut value = vec![0; cipher.len()];
+        let result =
+            self.board.crypto().aes256_gcm().decrypt(&self.key, iv, key, tag, cipher, &mut value);
+        // Decryption only fails if the storage was tampered with.
+        result.map_err(|_| Error::World)?;
+        Ok(value)
+    }
+}
+
+/// Key-value store within a range of keys of a store.
+pub struct KeyValue<'a, S: Storage, C: Cipher = Clear> {
+    store: &'a mut Store<S>,
+    keys: Range<usize>,
+    cipher: C,
+}
+
+/// Entry of the key-value store.
+struct Entry {
+    /// The handle of the entry in the store.
+    handle: StoreHandle,
+
+    /// The content of the entry.
+    content: Vec<u8>,
+}
+
+impl Entry {
+    fn key(&self) -> &[u8] {
+        let len = self.content[0] as usize;
+        &self.content[1 ..][.. len]
+    }
+
+    fn value(&self) -> &[u8] {
+        let len = self.content[0] as usize;
+        &self.content[1 + len ..]
+    }
+}
+
+impl<'a, S: Storage> KeyValue<'a, S> {
+    /// Creates a key-value store using a range of keys of a store.
+    pub fn new(store: &'a mut Store<S>, keys: Range<usize>) -> Self {
+        KeyValue::with_cipher(store, keys, Clear)
+    }
+}
+
+impl<'a, S: Storage, C: Cipher> KeyValue<'a, S, C> {
+    /// Creates a key-value store protecting its values with a cipher.
+    pub fn with_cipher(store: &'a mut Store<S>, keys: Range<usize>, cipher: C) -> Self {
+        KeyValue { store, keys, cipher }
+    }
+
+    fn entries(&self) -> Result<Vec<Entry>, Error> {
+        let mut entries = Vec::new();
+        for handle in self.store.iter().map_err(convert)? {
+            let handle = handle.map_err(convert)?;
+            if !self.keys.contains(&handle.get_key()) {
+                continue;
+            }
+            let content = handle.get_value(self.store).map_err(convert)?;
+            if content.first().map_or(true, |&len| content.len() <= len as usize) {
+                // This entry was not written by us.
+                return Err(Error::World);
+            }
+            entries.push(Entry { handle, content });
+        }
+        Ok(entries)
+    }
+
+    fn find(&self, key: &[u8]) -> Result<Option<Entry>, Error> {
+        Ok(self.entries()?.into_iter().find(|x| x.key() == key))
+    }
+}
+
+impl<S: Storage, C: Cipher> Api for KeyValue<'_, S, C> {
+    fn insert(&mut self, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        let len = u8::try_from(key.len()).map_err(|_| Error::User)?;
+        let entries = self.entries()?;
+        let slot = match entries.iter().find(|x| x.key() == key) {
+            Some(entry) => entry.handle.get_key(),
+            None => {
+                let used = |k: &usize| entries.iter().any(|x| x.handle.get_key() == *k);
+                self.keys.clone().find(|k| !used(k)).ok_or(Error::User)?
+            }
+        };
+        let value = self.cipher.seal(key, value)?;
+        let mut content = Vec::with_capacity(1 + key.len() + value.len());
+        content.push(len);
+        content.extend_from_slice(key);
+        content.extend_from_slice(&value);
+        self.store.insert(slot, &content).map_err(convert)
+    }
+
+    fn get(&mut self, key: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        match self.find(key)? {
+            Some(entry) => Ok(Some(self.cipher.open(entry.key(), entry.value())?)),
+            None => Ok(None),
+        }
+    }
+
+    fn remove(&mut self, key: &[u8]) -> Result<(), Error> {
+        match self.find(key)? {
+            Some(entry) => self.store.remove_handle(&entry.handle).map_err(convert),
+            None => Ok(()),
+        }
+    }
+
+    fn keys(&mut self) -> Result<Vec<Vec<u8>>, Error> {
+        Ok(self.entries()?.iter().map(|x| x.key().to_vec()).collect())
+    }
+
+    fn wipe(&mut self) -> Result<(), Error> {
+        self.store.wipe().map_err(convert)
+    }
+
+    fn wear_stats(&mut self) -> Result<WearStats, Error> {
+        self.store.wear_stats().map_err(convert)
+    }
+}
+
+/// Key-value store of the platform.
+///
+/// Entries are stored in the [`RESERVED_KEYS`] of the [board store](crate::Api::with_store). Values
+/// are [encrypted](Encrypted) if the board supports it. Keys starting with `applet/` are used by
+/// [`migrate()`].
+///
+/// Whether values are encrypted is recorded in the store when it is first used. If the board
+/// support changes afterwards, operations on values fail with [`Error::World`] until the store is
+/// wiped, such that encrypted values are never returned as clear and new values are never stored
+/// in clear in an encrypted store.
+pub struct Platform<'a, B: crate::Api> {
+    board: &'a mut B,
+}
+
+impl<'a, B: crate::Api> Platform<'a, B> {
+    /// Creates the platform key-value store of a board.
+    pub fn new(board: &'a mut B) -> Self {
+        Platform { board }
+    }
+
+    /// Returns whether values are encrypted.
+    ///
+    /// Returns [`Error::World`] if the board support changed since the mode was recorded.
+    pub fn is_encrypted(&mut self) -> Result<bool, Error> {
+        let supported = match Encrypted::new(&mut *self.board) {
+            Ok(_) => true,
+            Err(Error::User) => false,
+            Err(error) => return Err(error),
+        };
+        match self.with(|mut x| x.get(ENCRYPTED))? {
+            None => self.with(|mut x| x.insert(ENCRYPTED, &[supported as u8]))?,
+            Some(mode) if mode == [supported as u8] => (),
+            Some(_) => return Err(Error::World),
+        }
+        Ok(supported)
+    }
+
+    fn with<R>(&mut self, f: impl FnOnce(KeyValue<'_, B::Storage>) -> R) -> R {
+        self.board.with_store(|store| f(KeyValue::new(store, RESERVED_KEYS)))
+    }
+
+    // The key-value store borrows the store while the cipher borrows the board, so values are
+    // sealed and opened outside the key-value store (which stores them as is).
+    fn seal(&mut self, key: &[u8], value: &[u8]) -> Result<Vec<u8>, Error> {
+        match self.is_encrypted()? {
+            true => Encrypted::new(&mut *self.board)?.seal(key, value),
+            false => Clear.seal(key, value),
+        }
+    }
+
+    fn open(&mut self, key: &[u8], sealed: &[u8]) -> Result<Vec<u8>, Error> {
+        match self.is_encrypted()? {
+            true => Encrypted::new(&mut *self.board)?.open(key, sealed),
+            false => Clear.open(key, sealed),
+        }
+    }
+}
+
+impl<B: crate::Api> Api for Platform<'_, B> {
+    fn insert(&mut self, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        let value = self.seal(key, value)?;
+        self.with(|mut x| x.insert(key, &value))
+    }
+
+    fn get(&mut self, key: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        match self.with(|mut x| x.get(key))? {
+            Some(sealed) => Ok(Some(self.open(key, &sealed)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn remove(&mut self, key: &[u8]) -> Result<(), Error> {
+        self.with(|mut x| x.remove(key))
+    }
+
+    fn keys(&mut self) -> Result<Vec<Vec<u8>>, Error> {
+        self.with(|mut x| x.keys())
+    }
+
+    fn wipe(&mut self) -> Result<(), Error> {
+        self.with(|mut x| x.wipe())
+    }
+
+    fn wear_stats(&mut self) -> Result<WearStats, Error> {
+        self.with(|mut x| x.wear_stats())
+    }
+}
+
+fn convert(error: StoreError) -> Error {
+    match error {
+        StoreError::InvalidArgument | StoreError::NoCapacity => Error::User,
+        _ => Error::World,
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use wasefire_store::{BufferOptions, BufferStorage};
+
+    use super::*;
+
+    fn new_storage() -> BufferStorage {
+        let options = BufferOptions {
+            word_size: 4,
+            page_size: 0x1000,
+            max_word_writes: 2,
+            max_page_erases: 10000,
+            strict_mode: true,
+        };
+        BufferStorage::new(vec![0xff; 0x1000 * 8].into_boxed_slice(), options)
+    }
+
+    #[test]
+    fn insert_get_remove() {
+        let mut store = Store::new(new_storage()).ok().unwrap();
+        let mut kv = KeyValue::new(&mut store, 10 .. 13);
+        kv.insert(b"name", b"alice").unwrap();
+        kv.insert(b"", b"empty key").unwrap();
+        kv.insert(b"name", b"bob").unwrap();
+        assert_eq!(kv.get(b"name").unwrap().as_deref(), Some(&b"bob"[..]));
+        assert_eq!(kv.get(b"").unwrap().as_deref(), Some(&b"empty key"[..]));
+        assert_eq!(kv.get(b"other").unwrap(), None);
+        kv.insert(b"a", b"").unwrap();
+        assert!(matches!(kv.insert(b"full", b""), Err(Error::User)));
+        kv.remove(b"name").unwrap();
+        kv.remove(b"name").unwrap();
+        assert_eq!(kv.get(b"name").unwrap(), None);
+        let mut keys = kv.keys().unwrap();
+        keys.sort();
+        assert_eq!(keys, [b"".to_vec(), b"a".to_vec()]);
+        assert!(matches!(kv.insert(&[0; 256], b""), Err(Error::User)));
+    }
+
+    #[test]
+    fn survives_power_cycle() {
+        let mut store = Store::new(new_storage()).ok().unwrap();
+        KeyValue::new(&mut store, RESERVED_KEYS).insert(b"counter", &[42]).unwrap();
+        store.insert(RESERVED_KEYS.start - 1, b"other user").unwrap();
+        // Simulate a power cycle by dropping the store and reopening its storage.
+        let storage = store.extract_storage();
+        let mut store = Store::new(storage).ok().unwrap();
+        let mut kv = KeyValue::new(&mut store, RESERVED_KEYS);
+        assert_eq!(kv.get(b"counter").unwrap(), Some(vec![42]));
+        assert_eq!(kv.keys().unwrap(), [b"counter".to_vec()]);
+    }
+
+    #[test]
+    fn wipe_erases_everything() {
+        let mut store = Store::new(new_storage()).ok().unwrap();
+        let mut kv = KeyValue::new(&mut store, RESERVED_KEYS);
+        kv.insert(b"name", b"alice").unwrap();
+        kv.insert(b"counter", &[42]).unwrap();
+        store.insert(RESERVED_KEYS.start - 1, b"other user").unwrap();
+        let mut kv = KeyValue::new(&mut store, RESERVED_KEYS);
+        kv.wipe().unwrap();
+        assert_eq!(kv.get(b"name").unwrap(), None);
+        assert_eq!(kv.get(b"counter").unwrap(), None);
+        assert_eq!(kv.keys().unwrap(), Vec::<Vec<u8>>::new());
+        assert_eq!(store.find(RESERVED_KEYS.start - 1), Ok(None));
+    }
+
+    #[test]
+    fn migrate_applet_entries() {
+        let mut store = Store::new(new_storage()).ok().unwrap();
+        store.insert(RESERVED_KEYS.start - 1, b"applet").unwrap();
+        store.insert(RESERVED_KEYS.start, b"legacy").unwrap();
+        store.insert(RESERVED_KEYS.start + 2, &[3, 1, 2]).unwrap();
+        migrate(&mut store).unwrap();
+        migrate(&mut store).unwrap();
+        assert_eq!(store.find(RESERVED_KEYS.start - 1).unwrap(), Some(b"applet".to_vec()));
+        let mut kv = KeyValue::new(&mut store, RESERVED_KEYS);
+        let value = kv.get(&legacy_key(RESERVED_KEYS.start)).unwrap();
+        assert_eq!(value, Some(b"legacy".to_vec()));
+        let value = kv.get(&legacy_key(RESERVED_KEYS.start + 2)).unwrap();
+        assert_eq!(value, Some(vec![3, 1, 2]));
+        assert_eq!(kv.keys().unwrap().len(), 3);
+        kv.insert(b"counter", &[42]).unwrap();
+        assert_eq!(kv.get(b"counter").unwrap(), Some(vec![42]));
+    }
+
+    #[test]
+    fn migrate_resumes() {
+        let mut store = Store::new(new_storage()).ok().unwrap();
+        store.insert(RESERVED_KEYS.start, b"legacy").unwrap();
+        store.insert(RESERVED_KEYS.start + 1, b"other").unwrap();
+        // Simulate a power loss after wrapping the first entry.
+        let name = legacy_key(RESERVED_KEYS.start);
+        let mut content = vec![name.len() as u8];
+        content.extend_from_slice(&name);
+        content.extend_from_slice(b"legacy");
+        store.insert(RESERVED_KEYS.start, &content).unwrap();
+        migrate(&mut store).unwrap();
+        let mut kv = KeyValue::new(&mut store, RESERVED_KEYS);
+        assert_eq!(kv.get(&name).unwrap(), Some(b"legacy".to_vec()));
+        let value = kv.get(&legacy_key(RESERVED_KEYS.start + 1)).unwrap();
+        assert_eq!(value, Some(b"other".to_vec()));
+    }
+
+    #[test]
+    fn wear_stats_count_erases() {
+        let mut store = Store::new(new_storage()).ok().unwrap();
+        let mut kv = KeyValue::new(&mut store, RESERVED_KEYS);
+        let stats = kv.wear_stats().unwrap();
+        assert_eq!(stats.erase_count_per_page, [0; 8]);
+        assert_eq!(stats.max_page_erases, 10000);
+        // Overwrite the same entry until garbage collection erases the first page.
+        let mut writes = stats.total_writes;
+        let mut count = 0u32;
+        while kv.wear_stats().unwrap().erase_count_per_page[0] == 0 {
+            kv.insert(b"counter", &count.to_le_bytes()).unwrap();
+            let stats = kv.wear_stats().unwrap();
+            assert!(writes < stats.total_writes);
+            writes = stats.total_writes;
+            count += 1;
+        }
+        assert_eq!(kv.wear_stats().unwrap().erase_count_per_page[0], 1);
+        assert_eq!(kv.get(b"counter").unwrap(), Some((count - 1).to_le_bytes().to_vec()));
+    }
+
+    /// Toy cipher flipping the bits of the value and appending the key length as tag.
+    struct Flip;
+
+    impl Cipher for Flip {
+        fn seal(&mut self, key: &[u8], value: &[u8]) -> Result<Vec<u8>, Error> {
+            Ok(value.iter().map(|x| !x).chain([key.len() as u8]).collect())
+        }
+
+        fn open(&mut self, key: &[u8], sealed: &[u8]) -> Result<Vec<u8>, Error> {
+            match sealed.split_last() {
+                Some((&tag, value)) if tag as usize == key.len() => {
+                    Ok(value.iter().map(|x| !x).collect())
+                }
+                _ => Err(Error::World),
+            }
+        }
+    }
+
+    #[test]
+    fn cipher_protects_values() {
+        let mut store = Store::new(new_storage()).ok().unwrap();
+        let mut kv = KeyValue::with_cipher(&mut store, 10 .. 12, Flip);
+        kv.insert(b"name", b"alice").unwrap();
+        assert_eq!(kv.get(b"name").unwrap(), Some(b"alice".to_vec()));
+        // The value is not stored in clear.
+        let mut content = store.find(10).unwrap().unwrap();
+        assert!(!content.windows(5).any(|x| x == b"alice"));
+        // Tampering with the storage is detected.
+        *content.last_mut().unwrap() ^= 1;
+        store.insert(10, &content).unwrap();
+        let mut kv = KeyValue::with_cipher(&mut store, 10 .. 12, Flip);
+        assert_eq!(kv.keys().unwrap(), [b"name".to_vec()]);
+        assert!(matches!(kv.get(b"name"), Err(Error::World)));
+    }
+
+    #[cfg(feature = "software-crypto-aes256-gcm")]
+    mod encrypted {
+        use super::*;
+        use crate::platform::HeapStats;
+        use crate::{Types, Unsupported};
+
+        /// Board with a store, a storage key, and a deterministic random number generator.
+        struct Test {
+            store: Store<BufferStorage>,
+            counter: u8,
+        }
+
+        impl Test {
+            fn new() -> Self {
+                Test { store: Store::new(new_storage()).ok().unwrap(), counter: 0 }
+            }
+        }
+
+        impl crate::platform::Api for &mut Test {
+            fn reset(&mut self) -> Result<(), Error> {
+                unreachable!()
+            }
+
+            fn heap_stats(&mut self) -> Result<HeapStats, Error> {
+                unreachable!()
+            }
+
+            fn storage_key(&mut self) -> Result<[u8; 32], Error> {
+                Ok([0x42; 32])
+            }
+        }
+
+        impl crate::rng::Api for &mut Test {
+            fn fill_bytes(&mut self, buffer: &mut [u8]) -> Result<(), Error> {
+                for byte in buffer {
+                    self.counter = self.counter.wrapping_add(1);
+                    *byte = self.counter;
+                }
+                Ok(())
+            }
+        }
+
+        impl Types for Test {
+            type Crypto = Unsupported;
+        }
+        impl crate::Api for Test {
+            fn try_event(&mut self) -> Option<crate::Event> {
+                unreachable!()
+            }
+
+            fn wait_event(&mut self) -> crate::Event {
+                unreachable!()
+            }
+
+            type Storage = BufferStorage;
+            fn with_store<R>(&mut self, f: impl FnOnce(&mut Store<Self::Storage>) -> R) -> R {
+                f(&mut self.store)
+            }
+
+            type Adc<'a> = Unsupported;
+            fn adc(&mut self) -> Self::Adc<'_> {
+                Unsupported
+            }
+
+            type Button<'a> = Unsupported;
+            fn button(&mut self) -> Self::Button<'_> {
+                Unsupported
+            }
+
+            type Capture<'a> = Unsupported;
+            fn capture(&mut self) -> Self::Capture<'_> {
+                Unsupported
+            }
+
+            type Clock<'a> = Unsupported;
+            fn clock(&mut self) -> Self::Clock<'_> {
+                Unsupported
+            }
+
+            type Crypto<'a> = Unsupported;
+            fn crypto(&mut self) -> Unsupported {
+                Unsupported
+            }
+
+            type Debug<'a> = Unsupported;
+            fn debug(&mut self) -> Self::Debug<'_> {
+                Unsupported
+            }
+
+            type Encoder<'a> = Unsupported;
+            fn encoder(&mut self) -> Self::Encoder<'_> {
+                Unsupported
+            }
+
+            type Gpio<'a> = Unsupported;
+            fn gpio(&mut self) -> Self::Gpio<'_> {
+                Unsupported
+            }
+
+            type I2cSw<'a> = Unsupported;
+            fn i2c_sw(&mut self) -> Self::I2cSw<'_> {
+                Unsupported
+            }
+
+            type Led<'a> = Unsupported;
+            fn led(&mut self) -> Self::Led<'_> {
+                Unsupported
+            }
+
+            type LedRing<'a> = Unsupported;
+            fn led_ring(&mut self) -> Self::LedRing<'_> {
+                Unsupported
+            }
+
+            type Magnetometer<'a> = Unsupported;
+            fn magnetometer(&mut self) -> Self::Magnetometer<'_> {
+                Unsupported
+            }
+
+            type Platform<'a> = &'a mut Test;
+            fn platform(&mut self) -> Self::Platform<'_> {
+                self
+            }
+
+            type Power<'a> = Unsupported;
+            fn power(&mut self) -> Self::Power<'_> {
+                Unsupported
+            }
+
+            type Pwm<'a> = Unsupported;
+            fn pwm(&mut self) -> Self::Pwm<'_> {
+                Unsupported
+            }
+
+            type Rng<'a> = &'a mut Test;
+            fn rng(&mut self) -> Self::Rng<'_> {
+                self
+            }
+
+            type Stepper<'a> = Unsupported;
+            fn stepper(&mut self) -> Self::Stepper<'_> {
+                Unsupported
+            }
+
+            type Temp<'a> = Unsupported;
+            fn temp(&mut self) -> Self::Temp<'_> {
+                Unsupported
+            }
+
+            type Timer<'a> = Unsupported;
+            fn timer(&mut self) -> Self::Timer<'_> {
+                Unsupported
+            }
+
+            type Uart<'a> = Unsupported;
+            fn uart(&mut self) -> Self::Uart<'_> {
+                Unsupported
+            }
+
+            type Update<'a> = Unsupported;
+            fn update(&mut self) -> Self::Update<'_> {
+                Unsupported
+            }
+
+            type Usb<'a> = Unsupported;
+            fn usb(&mut self) -> Self::Usb<'_> {
+                Unsupported
+            }
+
+            type Watchdog<'a> = Unsupported;
+            fn watchdog(&mut self) -> Self::Watchdog<'_> {
+                Unsupported
+            }
+        }
+
+        #[test]
+        fn round_trip() {
+            let mut board = Test::new();
+            let mut cipher = Encrypted::new(&mut board).unwrap();
+            let sealed = cipher.seal(b"name", b"alice").unwrap();
+            assert_eq!(sealed.len(), 28 + 5);
+            assert!(!sealed.windows(5).any(|x| x == b"alice"));
+            assert_eq!(cipher.open(b"name", &sealed).unwrap(), b"alice");
+            // The IV is random, so sealing the same value twice differs.
+            assert_ne!(cipher.seal(b"name", b"alice").unwrap(), sealed);
+        }
+
+        #[test]
+        fn tamper() {
+            let mut board = Test::new();
+            let mut cipher = Encrypted::new(&mut board).unwrap();
+            let sealed = cipher.seal(b"name", b"alice").unwrap();
+            for i in 0 .. sealed.len() {
+                let mut tampered = sealed.clone();
+                tampered[i] ^= 1;
+                assert!(matches!(cipher.open(b"name", &tampered), Err(Error::World)));
+            }
+            assert!(matches!(cipher.open(b"other", &sealed), Err(Error::World)));
+            assert!(matches!(cipher.open(b"name", &sealed[.. 27]), Err(Error::World)));
+        }
+
+        #[test]
+        fn platform_encrypts() {
+            let mut board = Test::new();
+            Platform::new(&mut board).insert(b"name", b"alice").unwrap();
+            // The first key records the mode.
+            let key = RESERVED_KEYS.start + 1;
+            let mut content = board.store.find(key).unwrap().unwrap();
+            assert!(!content.windows(5).any(|x| x == b"alice"));
+            assert_eq!(Platform::new(&mut board).get(b"name").unwrap(), Some(b"alice".to_vec()));
+            *content.last_mut().unwrap() ^= 1;
+            board.store.insert(key, &content).unwrap();
+            assert!(matches!(Platform::new(&mut board).get(b"name"), Err(Error::World)));
+        }
+
+        #[test]
+        fn platform_mode_is_recorded() {
+            let mut board = Test::new();
+            assert!(Platform::new(&mut board).is_encrypted().unwrap());
+            Platform::new(&mut board).insert(b"name", b"alice").unwrap();
+            // The board lost encryption support since the mode was recorded.
+            KeyValue::new(&mut board.store, RESERVED_KEYS).insert(ENCRYPTED, &[0]).unwrap();
+            assert!(matches!(Platform::new(&mut board).is_encrypted(), Err(Error::World)));
+            assert!(matches!(Platform::new(&mut board).get(b"name"), Err(Error::World)));
+            assert!(matches!(Platform::new(&mut board).insert(b"name", b"bob"), Err(Error::World)));
+            Platform::new(&mut board).wipe().unwrap();
+            assert!(Platform::new(&mut board).is_encrypted().unwrap());
+        }
+    }
+}
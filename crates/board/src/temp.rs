@@ -0,0 +1,49 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Temperature sensor interface.
+//!
+//! A board exposes at most one temperature sensor. This is typically the die temperature of the
+//! chip, which is useful to put other measurements in context (like the random number generator
+//! health) or to throttle heavy computations.
+
+use crate::{Error, Unimplemented, Unsupported};
+
+pub trait Api {
+    /// Returns whether a temperature sensor is available.
+    fn is_supported(&mut self) -> bool;
+
+    /// Reads the temperature in millidegrees Celsius.
+    fn read(&mut self) -> Result<i32, Error>;
+}
+
+impl Api for Unimplemented {
+    fn is_supported(&mut self) -> bool {
+        unreachable!()
+    }
+
+    fn read(&mut self) -> Result<i32, Error> {
+        unreachable!()
+    }
+}
+
+impl Api for Unsupported {
+    fn is_supported(&mut self) -> bool {
+        false
+    }
+
+    fn read(&mut self) -> Result<i32, Error> {
+        Err(Error::User)
+    }
+}
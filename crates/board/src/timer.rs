@@ -19,7 +19,7 @@
 use crate::{Error, Unimplemented, Unsupported};
 
 /// Timer event.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Event {
     /// The timer that triggered the event.
     pub timer: usize,
@@ -38,8 +38,11 @@ pub trait Api {
     /// Timers are identified by an integer smaller than this value.
     fn count(&mut self) -> usize;
 
-    /// Arms a timer to trigger according to a command.
-    fn arm(&mut self, timer: usize, command: &Command) -> Result<(), Error>;
+    /// Arms a timer to trigger after a duration in microseconds.
+    ///
+    /// A one-shot timer disarms itself after triggering once. A periodic timer triggers every
+    /// duration until disarmed.
+    fn arm(&mut self, timer: usize, duration_us: usize, mode: Mode) -> Result<(), Error>;
 
     /// Disarms a timer regardless of whether it already triggered.
     ///
@@ -52,7 +55,7 @@ impl Api for Unimplemented {
         unreachable!()
     }
 
-    fn arm(&mut self, _: usize, _: &Command) -> Result<(), Error> {
+    fn arm(&mut self, _: usize, _: usize, _: Mode) -> Result<(), Error> {
         unreachable!()
     }
 
@@ -66,7 +69,7 @@ impl Api for Unsupported {
         0
     }
 
-    fn arm(&mut self, _: usize, _: &Command) -> Result<(), Error> {
+    fn arm(&mut self, _: usize, _: usize, _: Mode) -> Result<(), Error> {
         Err(Error::User)
     }
 
@@ -75,11 +78,12 @@ impl Api for Unsupported {
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct Command {
-    /// Whether the timer should periodically trigger.
-    pub periodic: bool,
+/// Whether a timer triggers once or periodically.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Mode {
+    /// The timer triggers once and disarms itself.
+    OneShot,
 
-    /// Duration in milliseconds after which the timer should trigger.
-    pub duration_ms: usize,
+    /// The timer triggers periodically until disarmed.
+    Periodic,
 }
@@ -0,0 +1,139 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! UART interface.
+//!
+//! These are serial ports on physical pins (e.g. to talk to GPS or Wi-Fi modules), as opposed to
+//! the [USB serial](crate::usb::serial). A board may have multiple UARTs, identified by their
+//! index, starting at zero.
+
+use crate::{Error, Unimplemented, Unsupported};
+
+/// UART event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    /// The UART that triggered the event.
+    pub uart: usize,
+
+    /// Whether the UART is ready for read or write.
+    pub direction: Direction,
+}
+
+/// Direction of a UART event.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Direction {
+    /// There might be data to read.
+    Read,
+
+    /// It might be possible to write data.
+    Write,
+}
+
+impl From<Event> for crate::Event {
+    fn from(event: Event) -> Self {
+        crate::Event::Uart(event)
+    }
+}
+
+/// UART interface.
+pub trait Api {
+    /// Returns the number of UARTs.
+    fn count(&mut self) -> usize;
+
+    /// Sets the baud rate of a UART.
+    ///
+    /// This is a user error if the baud rate is not supported by the board.
+    fn set_baudrate(&mut self, uart: usize, baudrate: u32) -> Result<(), Error>;
+
+    /// Reads from a UART into a buffer.
+    ///
+    /// Returns the number of bytes read. It could be zero if there's nothing to read.
+    fn read(&mut self, uart: usize, output: &mut [u8]) -> Result<usize, Error>;
+
+    /// Writes from a buffer to a UART.
+    ///
+    /// Returns the number of bytes written. It could be zero if the UART is busy.
+    fn write(&mut self, uart: usize, input: &[u8]) -> Result<usize, Error>;
+
+    /// Flushes a UART.
+    ///
+    /// This doesn't block: it returns an error if the UART can't be flushed immediately.
+    fn flush(&mut self, uart: usize) -> Result<(), Error>;
+
+    /// Enables a given event to be triggered on a UART.
+    fn enable(&mut self, uart: usize, direction: Direction) -> Result<(), Error>;
+
+    /// Disables a given event from being triggered on a UART.
+    fn disable(&mut self, uart: usize, direction: Direction) -> Result<(), Error>;
+}
+
+impl Api for Unimplemented {
+    fn count(&mut self) -> usize {
+        unreachable!()
+    }
+
+    fn set_baudrate(&mut self, _: usize, _: u32) -> Result<(), Error> {
+        unreachable!()
+    }
+
+    fn read(&mut self, _: usize, _: &mut [u8]) -> Result<usize, Error> {
+        unreachable!()
+    }
+
+    fn write(&mut self, _: usize, _: &[u8]) -> Result<usize, Error> {
+        unreachable!()
+    }
+
+    fn flush(&mut self, _: usize) -> Result<(), Error> {
+        unreachable!()
+    }
+
+    fn enable(&mut self, _: usize, _: Direction) -> Result<(), Error> {
+        unreachable!()
+    }
+
+    fn disable(&mut self, _: usize, _: Direction) -> Result<(), Error> {
+        unreachable!()
+    }
+}
+
+impl Api for Unsupported {
+    fn count(&mut self) -> usize {
+        0
+    }
+
+    fn set_baudrate(&mut self, _: usize, _: u32) -> Result<(), Error> {
+        Err(Error::User)
+    }
+
+    fn read(&mut self, _: usize, _: &mut [u8]) -> Result<usize, Error> {
+        Err(Error::User)
+    }
+
+    fn write(&mut self, _: usize, _: &[u8]) -> Result<usize, Error> {
+        Err(Error::User)
+    }
+
+    fn flush(&mut self, _: usize) -> Result<(), Error> {
+        Err(Error::User)
+    }
+
+    fn enable(&mut self, _: usize, _: Direction) -> Result<(), Error> {
+        Err(Error::User)
+    }
+
+    fn disable(&mut self, _: usize, _: Direction) -> Result<(), Error> {
+        Err(Error::User)
+    }
+}
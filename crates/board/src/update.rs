@@ -0,0 +1,338 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Firmware update interface.
+//!
+//! A firmware update writes a new firmware image to a staging bank and swaps it in on the next
+//! reboot:
+//! 1. [`Api::start()`] erases the staging bank.
+//! 2. [`Api::write()`] writes the image by chunks.
+//! 3. [`Api::finalize()`] verifies the Ed25519 signature and the version of the image.
+//! 4. [`Api::apply()`] marks the staging bank active and resets the platform.
+//!
+//! The image ends with its version as a 32-bit little-endian integer. To prevent downgrades, a
+//! rollback counter holds the version of the last applied image and older images are rejected.
+//!
+//! Boards with a staging [`Bank`] may implement this interface with [`Staged`].
+
+use wasefire_logger as logger;
+
+use crate::crypto::ed25519;
+use crate::{Error, Unimplemented, Unsupported};
+
+pub trait Api {
+    /// Whether firmware updates are supported.
+    fn is_supported(&mut self) -> bool;
+
+    /// Returns the minimum version of accepted images.
+    ///
+    /// This is the rollback counter, i.e. the version of the last applied image.
+    fn min_version(&mut self) -> u32;
+
+    /// Starts a firmware update.
+    ///
+    /// Any update in progress is discarded and the staging bank is erased.
+    fn start(&mut self) -> Result<(), Error>;
+
+    /// Writes a chunk of the firmware image at an offset of the staging bank.
+    ///
+    /// This is a user error if no update is in progress or the chunk doesn't fit the bank.
+    fn write(&mut self, offset: usize, chunk: &[u8]) -> Result<(), Error>;
+
+    /// Verifies the signature and version of the firmware image.
+    ///
+    /// The image spans from the start of the staging bank to the end of the furthest written
+    /// chunk. This is a user error if the signature is invalid or the version is smaller than
+    /// [`Self::min_version()`], in which case the update must be started again.
+    fn finalize(&mut self, signature: &[u8; 64]) -> Result<(), Error>;
+
+    /// Marks the staging bank active and resets the platform.
+    ///
+    /// This is a user error if the update was not finalized. This function only returns on error.
+    fn apply(&mut self) -> Result<(), Error>;
+}
+
+impl Api for Unimplemented {
+    fn is_supported(&mut self) -> bool {
+        unreachable!()
+    }
+
+    fn min_version(&mut self) -> u32 {
+        unreachable!()
+    }
+
+    fn start(&mut self) -> Result<(), Error> {
+        unreachable!()
+    }
+
+    fn write(&mut self, _: usize, _: &[u8]) -> Result<(), Error> {
+        unreachable!()
+    }
+
+    fn finalize(&mut self, _: &[u8; 64]) -> Result<(), Error> {
+        unreachable!()
+    }
+
+    fn apply(&mut self) -> Result<(), Error> {
+        unreachable!()
+    }
+}
+
+impl Api for Unsupported {
+    fn is_supported(&mut self) -> bool {
+        false
+    }
+
+    fn min_version(&mut self) -> u32 {
+        0
+    }
+
+    fn start(&mut self) -> Result<(), Error> {
+        Err(Error::User)
+    }
+
+    fn write(&mut self, _: usize, _: &[u8]) -> Result<(), Error> {
+        Err(Error::User)
+    }
+
+    fn finalize(&mut self, _: &[u8; 64]) -> Result<(), Error> {
+        Err(Error::User)
+    }
+
+    fn apply(&mut self) -> Result<(), Error> {
+        Err(Error::User)
+    }
+}
+
+/// Staging bank of firmware updates.
+pub trait Bank {
+    /// Returns the content of the bank.
+    fn read(&self) -> &[u8];
+
+    /// Returns the rollback counter.
+    ///
+    /// This must persist across reboots and is 0 if no image was ever activated.
+    fn version(&self) -> u32;
+
+    /// Erases the bank.
+    fn erase(&mut self) -> Result<(), Error>;
+
+    /// Writes a chunk at an offset of the bank.
+    ///
+    /// The chunk is within the bounds of the bank.
+    fn write(&mut self, offset: usize, chunk: &[u8]) -> Result<(), Error>;
+
+    /// Marks the bank active with an image of the given length and version.
+    ///
+    /// The bank is swapped in on the next reboot and the rollback counter is set to the version,
+    /// which is not smaller than the current counter. This function doesn't reset the platform.
+    fn activate(&mut self, length: usize, version: u32) -> Result<(), Error>;
+}
+
+/// Firmware update protocol over a staging bank.
+///
+/// The image signature is verified against a fixed public key and the image version is checked
+/// against the rollback counter before the bank may be activated.
+pub struct Staged<T: Bank> {
+    bank: T,
+    public: [u8; 32],
+    state: State,
+}
+
+#[derive(Debug, Copy, Clone)]
+enum State {
+    Idle,
+    Writing { length: usize },
+    Verified { length: usize, version: u32 },
+}
+
+impl<T: Bank> Staged<T> {
+    /// Creates a firmware update protocol for a bank and an Ed25519 public key.
+    pub fn new(bank: T, public: [u8; 32]) -> Self {
+        Staged { bank, public, state: State::Idle }
+    }
+
+    /// Implements [`Api::min_version()`].
+    pub fn min_version(&self) -> u32 {
+        self.bank.version()
+    }
+
+    /// Implements [`Api::start()`].
+    pub fn start(&mut self) -> Result<(), Error> {
+        self.state = State::Idle;
+        self.bank.erase()?;
+        self.state = State::Writing { length: 0 };
+        Ok(())
+    }
+
+    /// Implements [`Api::write()`].
+    pub fn write(&mut self, offset: usize, chunk: &[u8]) -> Result<(), Error> {
+        let State::Writing { length } = &mut self.state else { return Err(Error::User) };
+        let end = offset.checked_add(chunk.len()).ok_or(Error::User)?;
+        if self.bank.read().len() < end {
+            return Err(Error::User);
+        }
+        self.bank.write(offset, chunk)?;
+        *length = core::cmp::max(*length, end);
+        Ok(())
+    }
+
+    /// Implements [`Api::finalize()`] given an Ed25519 implementation.
+    pub fn finalize(
+        &mut self, ed25519: &mut impl ed25519::Api, signature: &[u8; 64],
+    ) -> Result<(), Error> {
+        let State::Writing { length } = self.state else { return Err(Error::User) };
+        self.state = State::Idle;
+        let image = &self.bank.read()[.. length];
+        if !ed25519.verify(&self.public, image, signature)? {
+            return Err(Error::User);
+        }
+        let version = match image.len().checked_sub(4) {
+            Some(i) => u32::from_le_bytes(image[i ..].try_into().unwrap()),
+            None => return Err(Error::User),
+        };
+        let min_version = self.bank.version();
+        if version < min_version {
+            logger::warn!("Rejected firmware version {} older than {}.", version, min_version);
+            return Err(Error::User);
+        }
+        self.state = State::Verified { length, version };
+        Ok(())
+    }
+
+    /// Implements [`Api::apply()`] without resetting the platform.
+    pub fn apply(&mut self) -> Result<(), Error> {
+        let State::Verified { length, version } = self.state else { return Err(Error::User) };
+        self.bank.activate(length, version)
+    }
+}
+
+#[cfg(all(test, feature = "software-crypto-ed25519"))]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use ed25519_dalek::{Signer, SigningKey};
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Memory {
+        data: Vec<u8>,
+        version: u32,
+        active: Option<usize>,
+    }
+
+    impl Bank for Memory {
+        fn read(&self) -> &[u8] {
+            &self.data
+        }
+
+        fn version(&self) -> u32 {
+            self.version
+        }
+
+        fn erase(&mut self) -> Result<(), Error> {
+            self.data = vec![0xff; 64];
+            Ok(())
+        }
+
+        fn write(&mut self, offset: usize, chunk: &[u8]) -> Result<(), Error> {
+            self.data[offset ..][.. chunk.len()].copy_from_slice(chunk);
+            Ok(())
+        }
+
+        fn activate(&mut self, length: usize, version: u32) -> Result<(), Error> {
+            self.version = version;
+            self.active = Some(length);
+            Ok(())
+        }
+    }
+
+    fn staged() -> (Staged<Memory>, SigningKey) {
+        let key = SigningKey::from_bytes(&[0x42; 32]);
+        (Staged::new(Memory::default(), key.verifying_key().to_bytes()), key)
+    }
+
+    fn image(version: u32) -> Vec<u8> {
+        let mut image = b"firmware".to_vec();
+        image.extend_from_slice(&version.to_le_bytes());
+        image
+    }
+
+    fn update(staged: &mut Staged<Memory>, key: &SigningKey, version: u32) -> Result<(), Error> {
+        let image = image(version);
+        staged.start()?;
+        staged.write(0, &image)?;
+        staged.finalize(&mut Unsupported, &key.sign(&image).to_bytes())?;
+        staged.apply()
+    }
+
+    #[test]
+    fn update_ok() {
+        let (mut staged, key) = staged();
+        let image = image(1);
+        staged.start().unwrap();
+        staged.write(4, &image[4 ..]).unwrap();
+        staged.write(0, &image[.. 4]).unwrap();
+        let signature = key.sign(&image).to_bytes();
+        staged.finalize(&mut Unsupported, &signature).unwrap();
+        staged.apply().unwrap();
+        assert_eq!(staged.bank.active, Some(12));
+        assert_eq!(staged.min_version(), 1);
+    }
+
+    #[test]
+    fn update_invalid_signature() {
+        let (mut staged, key) = staged();
+        staged.start().unwrap();
+        staged.write(0, &image(1)).unwrap();
+        let signature = key.sign(&image(2)).to_bytes();
+        assert!(matches!(staged.finalize(&mut Unsupported, &signature), Err(Error::User)));
+        assert!(matches!(staged.apply(), Err(Error::User)));
+        assert_eq!(staged.bank.active, None);
+    }
+
+    #[test]
+    fn update_out_of_order() {
+        let (mut staged, key) = staged();
+        let signature = key.sign(b"").to_bytes();
+        assert!(matches!(staged.write(0, b"firmware"), Err(Error::User)));
+        assert!(matches!(staged.finalize(&mut Unsupported, &signature), Err(Error::User)));
+        staged.start().unwrap();
+        assert!(matches!(staged.apply(), Err(Error::User)));
+        assert!(matches!(staged.write(60, b"firmware"), Err(Error::User)));
+        assert!(matches!(staged.write(usize::MAX, b"firmware"), Err(Error::User)));
+    }
+
+    #[test]
+    fn update_rollback() {
+        let (mut staged, key) = staged();
+        update(&mut staged, &key, 3).unwrap();
+        assert!(matches!(update(&mut staged, &key, 2), Err(Error::User)));
+        assert_eq!(staged.min_version(), 3);
+        update(&mut staged, &key, 3).unwrap();
+        update(&mut staged, &key, 4).unwrap();
+        assert_eq!(staged.min_version(), 4);
+    }
+
+    #[test]
+    fn update_missing_version() {
+        let (mut staged, key) = staged();
+        staged.start().unwrap();
+        staged.write(0, b"fw").unwrap();
+        let signature = key.sign(b"fw").to_bytes();
+        assert!(matches!(staged.finalize(&mut Unsupported, &signature), Err(Error::User)));
+    }
+}
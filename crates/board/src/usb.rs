@@ -16,13 +16,18 @@
 
 use crate::{Unimplemented, Unsupported};
 
+pub mod hid;
 pub mod serial;
+pub mod version;
 
 /// USB event.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Event {
-    /// Serial event.
-    Serial(serial::Event),
+    /// HID event.
+    Hid(hid::Event),
+
+    /// Serial event on a given port.
+    Serial { port: usize, event: serial::Event },
 }
 
 impl From<Event> for crate::Event {
@@ -33,12 +38,21 @@ impl From<Event> for crate::Event {
 
 /// USB interface.
 pub trait Api {
+    type Hid<'a>: hid::Api
+    where Self: 'a;
+    fn hid(&mut self) -> Self::Hid<'_>;
+
     type Serial<'a>: serial::Api
     where Self: 'a;
     fn serial(&mut self) -> Self::Serial<'_>;
 }
 
 impl Api for Unimplemented {
+    type Hid<'a> = Unimplemented;
+    fn hid(&mut self) -> Self::Hid<'_> {
+        unreachable!()
+    }
+
     type Serial<'a> = Unimplemented;
     fn serial(&mut self) -> Self::Serial<'_> {
         unreachable!()
@@ -46,6 +60,11 @@ impl Api for Unimplemented {
 }
 
 impl Api for Unsupported {
+    type Hid<'a> = Unsupported;
+    fn hid(&mut self) -> Self::Hid<'_> {
+        Unsupported
+    }
+
     type Serial<'a> = Unsupported;
     fn serial(&mut self) -> Self::Serial<'_> {
         Unsupported
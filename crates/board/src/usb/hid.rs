@@ -0,0 +1,293 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! USB HID interface.
+//!
+//! This interface exchanges fixed-size reports with the host through a FIDO HID interface (usage
+//! page 0xF1D0) as used by CTAP. Each report is exactly [`REPORT_LEN`] bytes.
+
+use usb_device::class_prelude::{
+    ControlIn, ControlOut, DescriptorWriter, EndpointIn, EndpointOut, InterfaceNumber, UsbBus,
+    UsbBusAllocator, UsbClass,
+};
+use usb_device::control::{Recipient, Request, RequestType};
+use usb_device::UsbError;
+use wasefire_logger as logger;
+
+use crate::{Error, Unimplemented, Unsupported};
+
+/// Length of a HID report in bytes.
+pub const REPORT_LEN: usize = 64;
+
+/// HID report.
+pub type Report = [u8; REPORT_LEN];
+
+/// USB HID event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// There might be a report to read.
+    Read,
+
+    /// It might be possible to write a report.
+    Write,
+}
+
+impl From<Event> for crate::Event {
+    fn from(event: Event) -> Self {
+        super::Event::Hid(event).into()
+    }
+}
+
+/// USB HID interface.
+pub trait Api {
+    /// Reads a report from the USB HID.
+    ///
+    /// Returns whether a report was read. It could be false if there's nothing to read.
+    fn read(&mut self, output: &mut Report) -> Result<bool, Error>;
+
+    /// Writes a report to the USB HID.
+    ///
+    /// Returns whether the report was written. It could be false if the other side is not ready.
+    fn write(&mut self, input: &Report) -> Result<bool, Error>;
+
+    /// Enables a given event to be triggered.
+    fn enable(&mut self, event: &Event) -> Result<(), Error>;
+
+    /// Disables a given event from being triggered.
+    fn disable(&mut self, event: &Event) -> Result<(), Error>;
+}
+
+impl Api for Unimplemented {
+    fn read(&mut self, _: &mut Report) -> Result<bool, Error> {
+        unreachable!()
+    }
+
+    fn write(&mut self, _: &Report) -> Result<bool, Error> {
+        unreachable!()
+    }
+
+    fn enable(&mut self, _: &Event) -> Result<(), Error> {
+        unreachable!()
+    }
+
+    fn disable(&mut self, _: &Event) -> Result<(), Error> {
+        unreachable!()
+    }
+}
+
+impl Api for Unsupported {
+    fn read(&mut self, _: &mut Report) -> Result<bool, Error> {
+        Err(Error::User)
+    }
+
+    fn write(&mut self, _: &Report) -> Result<bool, Error> {
+        Err(Error::User)
+    }
+
+    fn enable(&mut self, _: &Event) -> Result<(), Error> {
+        Err(Error::User)
+    }
+
+    fn disable(&mut self, _: &Event) -> Result<(), Error> {
+        Err(Error::User)
+    }
+}
+
+/// Report descriptor of the FIDO HID interface.
+pub const REPORT_DESCRIPTOR: &[u8] = &[
+    0x06, 0xd0, 0xf1, // Usage Page (FIDO Alliance)
+    0x09, 0x01, // Usage (CTAPHID)
+    0xa1, 0x01, // Collection (Application)
+    0x09, 0x20, //   Usage (Input Report Data)
+    0x15, 0x00, //   Logical Minimum (0)
+    0x26, 0xff, 0x00, //   Logical Maximum (255)
+    0x75, 0x08, //   Report Size (8)
+    0x95, 0x40, //   Report Count (64)
+    0x81, 0x02, //   Input (Data, Variable, Absolute)
+    0x09, 0x21, //   Usage (Output Report Data)
+    0x15, 0x00, //   Logical Minimum (0)
+    0x26, 0xff, 0x00, //   Logical Maximum (255)
+    0x75, 0x08, //   Report Size (8)
+    0x95, 0x40, //   Report Count (64)
+    0x91, 0x02, //   Output (Data, Variable, Absolute)
+    0xc0, // End Collection
+];
+
+const CLASS_HID: u8 = 0x03;
+const DESCRIPTOR_HID: u8 = 0x21;
+const DESCRIPTOR_REPORT: u8 = 0x22;
+const REQUEST_SET_IDLE: u8 = 0x0a;
+
+/// FIDO HID class for boards using the `usb_device` crate.
+pub struct HidClass<'a, T: UsbBus> {
+    interface: InterfaceNumber,
+    read_ep: EndpointOut<'a, T>,
+    write_ep: EndpointIn<'a, T>,
+}
+
+impl<'a, T: UsbBus> HidClass<'a, T> {
+    /// Allocates the interface and its interrupt endpoints.
+    pub fn new(alloc: &'a UsbBusAllocator<T>) -> Self {
+        HidClass {
+            interface: alloc.interface(),
+            read_ep: alloc.interrupt(REPORT_LEN as u16, 5),
+            write_ep: alloc.interrupt(REPORT_LEN as u16, 5),
+        }
+    }
+
+    /// Reads a report from the host.
+    ///
+    /// Returns [`UsbError::WouldBlock`] if there is no report to read.
+    pub fn read(&self, output: &mut Report) -> Result<(), UsbError> {
+        match self.read_ep.read(output)? {
+            REPORT_LEN => Ok(()),
+            _ => Err(UsbError::ParseError),
+        }
+    }
+
+    /// Writes a report to the host.
+    ///
+    /// Returns [`UsbError::WouldBlock`] if the previous report was not yet sent.
+    pub fn write(&self, input: &Report) -> Result<(), UsbError> {
+        match self.write_ep.write(input)? {
+            REPORT_LEN => Ok(()),
+            _ => Err(UsbError::BufferOverflow),
+        }
+    }
+
+    fn is_interface(&self, request: &Request) -> bool {
+        request.recipient == Recipient::Interface
+            && request.index == u8::from(self.interface) as u16
+    }
+}
+
+impl<T: UsbBus> UsbClass<T> for HidClass<'_, T> {
+    fn get_configuration_descriptors(
+        &self, writer: &mut DescriptorWriter,
+    ) -> usb_device::Result<()> {
+        writer.interface(self.interface, CLASS_HID, 0, 0)?;
+        let [len_lo, len_hi] = (REPORT_DESCRIPTOR.len() as u16).to_le_bytes();
+        writer
+            .write(DESCRIPTOR_HID, &[0x11, 0x01, 0x00, 0x01, DESCRIPTOR_REPORT, len_lo, len_hi])?;
+        writer.endpoint(&self.write_ep)?;
+        writer.endpoint(&self.read_ep)?;
+        Ok(())
+    }
+
+    fn control_in(&mut self, xfer: ControlIn<T>) {
+        let request = *xfer.request();
+        if request.request_type == RequestType::Standard
+            && self.is_interface(&request)
+            && request.request == Request::GET_DESCRIPTOR
+            && (request.value >> 8) as u8 == DESCRIPTOR_REPORT
+        {
+            let _ = xfer.accept_with_static(REPORT_DESCRIPTOR);
+        }
+    }
+
+    fn control_out(&mut self, xfer: ControlOut<T>) {
+        let request = *xfer.request();
+        if request.request_type == RequestType::Class
+            && self.is_interface(&request)
+            && request.request == REQUEST_SET_IDLE
+        {
+            let _ = xfer.accept();
+        }
+    }
+}
+
+/// Helper trait for boards using the `usb_device` crate.
+pub trait HasHid {
+    type UsbBus: UsbBus;
+
+    fn with_hid<R>(&mut self, f: impl FnOnce(&mut Hid<Self::UsbBus>) -> R) -> R;
+}
+
+/// Wrapper type for boards using the `usb_device` crate.
+#[repr(transparent)]
+pub struct WithHid<T: HasHid>(pub T);
+
+/// Helper struct for boards using the `usb_device` crate.
+pub struct Hid<'a, T: UsbBus> {
+    class: HidClass<'a, T>,
+    read_enabled: bool,
+    write_enabled: bool,
+}
+
+impl<'a, T: UsbBus> Hid<'a, T> {
+    pub fn new(class: HidClass<'a, T>) -> Self {
+        Self { class, read_enabled: false, write_enabled: false }
+    }
+
+    pub fn class(&mut self) -> &mut HidClass<'a, T> {
+        &mut self.class
+    }
+
+    /// Pushes events based on whether the USB HID was polled.
+    pub fn tick(&mut self, polled: bool, mut push: impl FnMut(Event)) {
+        if self.read_enabled && polled {
+            push(Event::Read);
+        }
+        if self.write_enabled && polled {
+            push(Event::Write);
+        }
+    }
+
+    fn set(&mut self, event: &Event, enabled: bool) {
+        match event {
+            Event::Read => self.read_enabled = enabled,
+            Event::Write => self.write_enabled = enabled,
+        }
+    }
+}
+
+impl<T: HasHid> Api for WithHid<T> {
+    fn read(&mut self, output: &mut Report) -> Result<bool, Error> {
+        match self.0.with_hid(|hid| hid.class.read(output)) {
+            Ok(()) => {
+                logger::trace!("{:?} = read()", &output[..]);
+                Ok(true)
+            }
+            Err(UsbError::WouldBlock) => Ok(false),
+            Err(e) => {
+                logger::debug!("{} = read()", logger::Debug2Format(&e));
+                Err(Error::World)
+            }
+        }
+    }
+
+    fn write(&mut self, input: &Report) -> Result<bool, Error> {
+        match self.0.with_hid(|hid| hid.class.write(input)) {
+            Ok(()) => {
+                logger::trace!("write({:?})", &input[..]);
+                Ok(true)
+            }
+            Err(UsbError::WouldBlock) => Ok(false),
+            Err(e) => {
+                logger::debug!("{} = write({:?})", logger::Debug2Format(&e), &input[..]);
+                Err(Error::World)
+            }
+        }
+    }
+
+    fn enable(&mut self, event: &Event) -> Result<(), Error> {
+        self.0.with_hid(|hid| hid.set(event, true));
+        Ok(())
+    }
+
+    fn disable(&mut self, event: &Event) -> Result<(), Error> {
+        self.0.with_hid(|hid| hid.set(event, false));
+        Ok(())
+    }
+}
@@ -13,97 +13,252 @@
 // limitations under the License.
 
 //! USB serial interface.
+//!
+//! A board may expose multiple serial ports on the same USB device (e.g. a data channel and a
+//! debug console). Ports are identified by their index, starting at zero.
+
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
 
 use usb_device::class_prelude::UsbBus;
 use usb_device::UsbError;
 use usbd_serial::SerialPort;
 use wasefire_logger as logger;
 
-use crate::{Error, Unimplemented, Unsupported};
+use crate::{clock, Error, Unimplemented, Unsupported};
 
 /// USB serial event.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Event {
     /// There might be data to read.
     Read,
 
     /// It might be possible to write data.
     Write,
+
+    /// The line state changed (e.g. a terminal connected or disconnected).
+    Control,
+
+    /// The line coding changed (e.g. the host changed the baud rate).
+    Coding,
 }
 
-impl From<Event> for crate::Event {
-    fn from(event: Event) -> Self {
-        super::Event::Serial(event).into()
+impl Event {
+    /// Returns the board event for this event on a given port.
+    pub fn on(self, port: usize) -> crate::Event {
+        super::Event::Serial { port, event: self }.into()
+    }
+}
+
+/// USB serial line state, as set by the host.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct LineState {
+    /// Data terminal ready (DTR).
+    pub dtr: bool,
+
+    /// Request to send (RTS).
+    pub rts: bool,
+}
+
+/// USB serial line coding, as requested by the host.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LineCoding {
+    /// Data rate in bits per second (i.e. the baud rate).
+    pub data_rate: u32,
+
+    /// Number of stop bits.
+    pub stop_bits: StopBits,
+
+    /// Parity type.
+    pub parity: Parity,
+
+    /// Number of data bits (5, 6, 7, 8, or 16).
+    pub data_bits: u8,
+}
+
+/// Number of stop bits.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StopBits {
+    /// 1 stop bit.
+    One,
+
+    /// 1.5 stop bits.
+    OnePointFive,
+
+    /// 2 stop bits.
+    Two,
+}
+
+/// Parity type.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Parity {
+    /// No parity bit.
+    None,
+
+    /// Odd parity.
+    Odd,
+
+    /// Even parity.
+    Even,
+
+    /// Parity bit always set.
+    Mark,
+
+    /// Parity bit always cleared.
+    Space,
+}
+
+impl From<&usbd_serial::LineCoding> for LineCoding {
+    fn from(coding: &usbd_serial::LineCoding) -> Self {
+        let stop_bits = match coding.stop_bits() {
+            usbd_serial::StopBits::One => StopBits::One,
+            usbd_serial::StopBits::OnePointFive => StopBits::OnePointFive,
+            usbd_serial::StopBits::Two => StopBits::Two,
+        };
+        let parity = match coding.parity_type() {
+            usbd_serial::ParityType::None => Parity::None,
+            usbd_serial::ParityType::Odd => Parity::Odd,
+            usbd_serial::ParityType::Event => Parity::Even,
+            usbd_serial::ParityType::Mark => Parity::Mark,
+            usbd_serial::ParityType::Space => Parity::Space,
+        };
+        let data_rate = coding.data_rate();
+        LineCoding { data_rate, stop_bits, parity, data_bits: coding.data_bits() }
     }
 }
 
 /// USB serial interface.
 pub trait Api {
-    /// Reads from the USB serial into a buffer.
+    /// Returns the number of serial ports.
+    fn count(&mut self) -> usize;
+
+    /// Reads from a USB serial port into a buffer.
     ///
     /// Returns the number of bytes read. It could be zero if there's nothing to read.
-    fn read(&mut self, output: &mut [u8]) -> Result<usize, Error>;
+    fn read(&mut self, port: usize, output: &mut [u8]) -> Result<usize, Error>;
 
-    /// Writes from a buffer to the USB serial.
+    /// Reads from a USB serial port into multiple buffers, in order.
+    ///
+    /// Returns the total number of bytes read. It could be zero if there's nothing to read. A
+    /// buffer is only written if the previous ones are full.
+    fn read_into(&mut self, port: usize, outputs: &mut [&mut [u8]]) -> Result<usize, Error> {
+        let mut total = 0;
+        for output in outputs.iter_mut() {
+            let len = match self.read(port, output) {
+                Ok(len) => len,
+                Err(_) if total > 0 => break,
+                Err(e) => return Err(e),
+            };
+            total += len;
+            if len < output.len() {
+                break;
+            }
+        }
+        Ok(total)
+    }
+
+    /// Writes from a buffer to a USB serial port.
     ///
     /// Returns the number of bytes written. It could be zero if the other side is not ready.
-    fn write(&mut self, input: &[u8]) -> Result<usize, Error>;
+    fn write(&mut self, port: usize, input: &[u8]) -> Result<usize, Error>;
+
+    /// Flushes a USB serial port.
+    ///
+    /// This doesn't block: it returns an error if the port can't be flushed immediately.
+    fn flush(&mut self, port: usize) -> Result<(), Error>;
+
+    /// Flushes a USB serial port, waiting at most a given duration in microseconds.
+    ///
+    /// Returns [`Error::World`] if the port couldn't be flushed in time (e.g. the other side is not
+    /// reading). By default, this doesn't wait.
+    fn flush_timeout(&mut self, port: usize, _duration_us: u64) -> Result<(), Error> {
+        self.flush(port)
+    }
 
-    /// Flushes the USB serial.
-    fn flush(&mut self) -> Result<(), Error>;
+    /// Returns the line state of a port.
+    fn line_state(&mut self, _port: usize) -> Result<LineState, Error> {
+        Err(Error::User)
+    }
+
+    /// Returns the line coding of a port.
+    fn line_coding(&mut self, _port: usize) -> Result<LineCoding, Error> {
+        Err(Error::User)
+    }
 
-    /// Enables a given event to be triggered.
-    fn enable(&mut self, event: &Event) -> Result<(), Error>;
+    /// Enables a given event to be triggered on a port.
+    fn enable(&mut self, port: usize, event: &Event) -> Result<(), Error>;
 
-    /// Disables a given event from being triggered.
-    fn disable(&mut self, event: &Event) -> Result<(), Error>;
+    /// Disables a given event from being triggered on a port.
+    fn disable(&mut self, port: usize, event: &Event) -> Result<(), Error>;
 }
 
 impl Api for Unimplemented {
-    fn read(&mut self, _: &mut [u8]) -> Result<usize, Error> {
+    fn count(&mut self) -> usize {
+        unreachable!()
+    }
+
+    fn read(&mut self, _: usize, _: &mut [u8]) -> Result<usize, Error> {
         unreachable!()
     }
 
-    fn write(&mut self, _: &[u8]) -> Result<usize, Error> {
+    fn write(&mut self, _: usize, _: &[u8]) -> Result<usize, Error> {
         unreachable!()
     }
 
-    fn flush(&mut self) -> Result<(), Error> {
+    fn flush(&mut self, _: usize) -> Result<(), Error> {
         unreachable!()
     }
 
-    fn enable(&mut self, _: &Event) -> Result<(), Error> {
+    fn flush_timeout(&mut self, _: usize, _: u64) -> Result<(), Error> {
         unreachable!()
     }
 
-    fn disable(&mut self, _: &Event) -> Result<(), Error> {
+    fn line_state(&mut self, _: usize) -> Result<LineState, Error> {
+        unreachable!()
+    }
+
+    fn line_coding(&mut self, _: usize) -> Result<LineCoding, Error> {
+        unreachable!()
+    }
+
+    fn enable(&mut self, _: usize, _: &Event) -> Result<(), Error> {
+        unreachable!()
+    }
+
+    fn disable(&mut self, _: usize, _: &Event) -> Result<(), Error> {
         unreachable!()
     }
 }
 
 impl Api for Unsupported {
-    fn read(&mut self, _: &mut [u8]) -> Result<usize, Error> {
+    fn count(&mut self) -> usize {
+        0
+    }
+
+    fn read(&mut self, _: usize, _: &mut [u8]) -> Result<usize, Error> {
         Err(Error::User)
     }
 
-    fn write(&mut self, _: &[u8]) -> Result<usize, Error> {
+    fn write(&mut self, _: usize, _: &[u8]) -> Result<usize, Error> {
         Err(Error::User)
     }
 
-    fn flush(&mut self) -> Result<(), Error> {
+    fn flush(&mut self, _: usize) -> Result<(), Error> {
         Err(Error::User)
     }
 
-    fn enable(&mut self, _: &Event) -> Result<(), Error> {
+    fn enable(&mut self, _: usize, _: &Event) -> Result<(), Error> {
         Err(Error::User)
     }
 
-    fn disable(&mut self, _: &Event) -> Result<(), Error> {
+    fn disable(&mut self, _: usize, _: &Event) -> Result<(), Error> {
         Err(Error::User)
     }
 }
 
 /// Helper trait for boards using the `usbd_serial` crate.
+///
+/// The board must also implement the clock interface to bound [`Api::flush_timeout()`].
 pub trait HasSerial {
     type UsbBus: UsbBus;
 
@@ -116,91 +271,306 @@ pub struct WithSerial<T: HasSerial>(pub T);
 
 /// Helper struct for boards using the `usbd_serial` crate.
 pub struct Serial<'a, T: UsbBus> {
-    port: SerialPort<'a, T>,
-    read_enabled: bool,
-    write_enabled: bool,
+    ports: Vec<SerialPort<'a, T>>,
+    // Whether the read, write, control, and coding events are enabled, for each port.
+    enabled: Vec<[bool; 4]>,
+    // Last line state and coding seen by tick, for each port.
+    lines: Vec<(LineState, LineCoding)>,
+    // Bytes accepted but not yet written, for each port.
+    buffers: Vec<VecDeque<u8>>,
+    // Maximum number of bytes in each buffer.
+    capacity: usize,
 }
 
 impl<'a, T: UsbBus> Serial<'a, T> {
+    /// Creates a helper for a single port.
     pub fn new(port: SerialPort<'a, T>) -> Self {
-        Self { port, read_enabled: false, write_enabled: false }
+        Self::with_ports([port])
     }
 
-    pub fn port(&mut self) -> &mut SerialPort<'a, T> {
-        &mut self.port
+    /// Creates a helper for multiple ports.
+    ///
+    /// The ports are indexed in iteration order.
+    pub fn with_ports(ports: impl IntoIterator<Item = SerialPort<'a, T>>) -> Self {
+        let ports: Vec<_> = ports.into_iter().collect();
+        let enabled = ports.iter().map(|_| [false; 4]).collect();
+        let lines =
+            ports.iter().map(|port| (LineState::default(), port.line_coding().into())).collect();
+        let buffers = ports.iter().map(|_| VecDeque::new()).collect();
+        Self { ports, enabled, lines, buffers, capacity: 0 }
+    }
+
+    /// Buffers up to `capacity` bytes per port for [`Self::write_buffered()`].
+    ///
+    /// Without a buffer (the default), writes only accept what the port accepts immediately.
+    pub fn with_write_buffer(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Returns the ports in index order.
+    ///
+    /// This is useful to poll all ports in the same `UsbDevice::poll()` call.
+    pub fn ports(&mut self) -> &mut [SerialPort<'a, T>] {
+        &mut self.ports
+    }
+
+    /// Writes to a port, buffering what the port doesn't accept immediately.
+    ///
+    /// Pending bytes are written first. Returns how many bytes of the input were accepted, which is
+    /// less than its length only when the buffer is full. The buffer is drained by [`Self::tick()`].
+    pub fn write_buffered(&mut self, port: usize, input: &[u8]) -> Result<usize, Error> {
+        let capacity = self.capacity;
+        let (serial, buffer) = self.port_buffer(port)?;
+        write_buffered(serial, buffer, capacity, input)
     }
 
     /// Pushes events based on whether the USB serial was polled.
-    pub fn tick(&mut self, polled: bool, mut push: impl FnMut(Event)) {
-        if self.read_enabled && polled {
-            push(Event::Read);
+    ///
+    /// This also drains the write buffers and detects line state and coding changes.
+    pub fn tick(&mut self, polled: bool, mut push: impl FnMut(crate::Event)) {
+        let ports = self.ports.iter_mut().zip(&mut self.buffers).zip(&mut self.lines);
+        for (port, (((serial, buffer), (state, coding)), [read, write, control, code])) in
+            ports.zip(&self.enabled).enumerate()
+        {
+            if *read && polled {
+                push(Event::Read.on(port));
+            }
+            let current = LineState { dtr: serial.dtr(), rts: serial.rts() };
+            if core::mem::replace(state, current) != current && *control {
+                push(Event::Control.on(port));
+            }
+            let current = LineCoding::from(serial.line_coding());
+            if core::mem::replace(coding, current) != current && *code {
+                push(Event::Coding.on(port));
+            }
+            if !serial.dtr() {
+                continue;
+            }
+            if let Err(e) = drain(serial, buffer) {
+                logger::debug!("{} = drain({})", logger::Debug2Format(&e), port);
+            }
+            let full = self.capacity != 0 && buffer.len() == self.capacity;
+            if *write && !full {
+                push(Event::Write.on(port));
+            }
         }
-        if self.write_enabled && self.port.dtr() {
-            push(Event::Write);
+    }
+
+    fn port(&mut self, port: usize) -> Result<&mut SerialPort<'a, T>, Error> {
+        self.ports.get_mut(port).ok_or(Error::User)
+    }
+
+    fn port_buffer(
+        &mut self, port: usize,
+    ) -> Result<(&mut SerialPort<'a, T>, &mut VecDeque<u8>), Error> {
+        let serial = self.ports.get_mut(port).ok_or(Error::User)?;
+        Ok((serial, &mut self.buffers[port]))
+    }
+
+    fn flush_port(&mut self, port: usize) -> Result<Result<(), UsbError>, Error> {
+        let (serial, buffer) = self.port_buffer(port)?;
+        if !drain(serial, buffer)? {
+            return Ok(Err(UsbError::WouldBlock));
         }
+        Ok(serial.flush())
     }
 
-    fn set(&mut self, event: &Event, enabled: bool) {
+    fn set(&mut self, port: usize, event: &Event, enabled: bool) -> Result<(), Error> {
+        let [read, write, control, coding] = self.enabled.get_mut(port).ok_or(Error::User)?;
         match event {
-            Event::Read => self.read_enabled = enabled,
-            Event::Write => self.write_enabled = enabled,
+            Event::Read => *read = enabled,
+            Event::Write => *write = enabled,
+            Event::Control => *control = enabled,
+            Event::Coding => *coding = enabled,
         }
+        Ok(())
     }
 }
 
-impl<T: HasSerial> Api for WithSerial<T> {
-    fn read(&mut self, output: &mut [u8]) -> Result<usize, Error> {
-        match self.0.with_serial(|serial| serial.port.read(output)) {
+/// Serial port as seen by the write buffer.
+trait Port {
+    fn write(&mut self, data: &[u8]) -> Result<usize, UsbError>;
+}
+
+impl<T: UsbBus> Port for SerialPort<'_, T> {
+    fn write(&mut self, data: &[u8]) -> Result<usize, UsbError> {
+        SerialPort::write(self, data)
+    }
+}
+
+/// Writes to a port after its pending bytes and buffers what the port doesn't accept.
+fn write_buffered(
+    serial: &mut impl Port, buffer: &mut VecDeque<u8>, capacity: usize, input: &[u8],
+) -> Result<usize, Error> {
+    let mut len = 0;
+    if drain(serial, buffer)? {
+        len = match serial.write(input) {
+            Ok(len) => len,
+            Err(UsbError::WouldBlock) => 0,
+            Err(e) => return Err(world(e)),
+        };
+    }
+    let count = core::cmp::min(input.len() - len, capacity - buffer.len());
+    buffer.extend(&input[len .. len + count]);
+    Ok(len + count)
+}
+
+/// Writes pending bytes to a port and returns whether the buffer is empty.
+fn drain(serial: &mut impl Port, buffer: &mut VecDeque<u8>) -> Result<bool, Error> {
+    while !buffer.is_empty() {
+        match serial.write(buffer.as_slices().0) {
+            Ok(0) | Err(UsbError::WouldBlock) => return Ok(false),
+            Ok(len) => drop(buffer.drain(.. len)),
+            Err(e) => return Err(world(e)),
+        }
+    }
+    Ok(true)
+}
+
+fn world(e: UsbError) -> Error {
+    logger::debug!("{}", logger::Debug2Format(&e));
+    Error::World
+}
+
+impl<T: HasSerial + clock::Api> Api for WithSerial<T> {
+    fn count(&mut self) -> usize {
+        self.0.with_serial(|serial| serial.ports.len())
+    }
+
+    fn read(&mut self, port: usize, output: &mut [u8]) -> Result<usize, Error> {
+        match self.0.with_serial(|serial| Ok(serial.port(port)?.read(output)))? {
             Ok(len) => {
-                logger::trace!("{}{:?} = read({})", len, &output[.. len], output.len());
+                logger::trace!("{}{:?} = read({}, {})", len, &output[.. len], port, output.len());
                 Ok(len)
             }
             Err(UsbError::WouldBlock) => Ok(0),
             Err(e) => {
-                logger::debug!("{} = read({})", logger::Debug2Format(&e), output.len());
+                logger::debug!("{} = read({}, {})", logger::Debug2Format(&e), port, output.len());
                 Err(Error::World)
             }
         }
     }
 
-    fn write(&mut self, input: &[u8]) -> Result<usize, Error> {
-        if !self.0.with_serial(|serial| serial.port.dtr()) {
+    fn write(&mut self, port: usize, input: &[u8]) -> Result<usize, Error> {
+        if !self.0.with_serial(|serial| Ok::<_, Error>(serial.port(port)?.dtr()))? {
             // Data terminal is not ready.
             return Ok(0);
         }
-        match self.0.with_serial(|serial| serial.port.write(input)) {
-            Ok(len) => {
-                logger::trace!("{} = write({}{:?})", len, input.len(), input);
-                Ok(len)
-            }
-            Err(UsbError::WouldBlock) => Ok(0),
-            Err(e) => {
-                logger::debug!("{} = write({}{:?})", logger::Debug2Format(&e), input.len(), input);
-                Err(Error::World)
-            }
-        }
+        let len = self.0.with_serial(|serial| serial.write_buffered(port, input))?;
+        logger::trace!("{} = write({}, {}{:?})", len, port, input.len(), input);
+        Ok(len)
     }
 
-    fn flush(&mut self) -> Result<(), Error> {
-        match self.0.with_serial(|serial| serial.port.flush()) {
+    fn flush(&mut self, port: usize) -> Result<(), Error> {
+        match self.0.with_serial(|serial| serial.flush_port(port))? {
             Ok(()) => {
-                logger::trace!("flush()");
+                logger::trace!("flush({})", port);
                 Ok(())
             }
             Err(e) => {
-                logger::debug!("{} = flush()", logger::Debug2Format(&e));
+                logger::debug!("{} = flush({})", logger::Debug2Format(&e), port);
                 Err(Error::World)
             }
         }
     }
 
-    fn enable(&mut self, event: &Event) -> Result<(), Error> {
-        self.0.with_serial(|serial| serial.set(event, true));
-        Ok(())
+    fn flush_timeout(&mut self, port: usize, duration_us: u64) -> Result<(), Error> {
+        // The deadline is only computed if the first attempt doesn't succeed.
+        let mut deadline = None;
+        loop {
+            // The USB device is polled (by interrupts or another thread) between attempts.
+            let e = match self.0.with_serial(|serial| serial.flush_port(port))? {
+                Ok(()) => {
+                    logger::trace!("flush_timeout({}, {})", port, duration_us);
+                    return Ok(());
+                }
+                Err(UsbError::WouldBlock) => {
+                    let now = self.0.uptime_us()?;
+                    if now < *deadline.get_or_insert(now.saturating_add(duration_us)) {
+                        continue;
+                    }
+                    UsbError::WouldBlock
+                }
+                Err(e) => e,
+            };
+            let e = logger::Debug2Format(&e);
+            logger::debug!("{} = flush_timeout({}, {})", e, port, duration_us);
+            return Err(Error::World);
+        }
     }
 
-    fn disable(&mut self, event: &Event) -> Result<(), Error> {
-        self.0.with_serial(|serial| serial.set(event, false));
-        Ok(())
+    fn line_state(&mut self, port: usize) -> Result<LineState, Error> {
+        self.0.with_serial(|serial| {
+            let serial = serial.port(port)?;
+            Ok(LineState { dtr: serial.dtr(), rts: serial.rts() })
+        })
+    }
+
+    fn line_coding(&mut self, port: usize) -> Result<LineCoding, Error> {
+        self.0.with_serial(|serial| Ok(serial.port(port)?.line_coding().into()))
+    }
+
+    fn enable(&mut self, port: usize, event: &Event) -> Result<(), Error> {
+        self.0.with_serial(|serial| serial.set(port, event, true))
+    }
+
+    fn disable(&mut self, port: usize, event: &Event) -> Result<(), Error> {
+        self.0.with_serial(|serial| serial.set(port, event, false))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Port accepting a given number of bytes, at most 4 per write.
+    #[derive(Default)]
+    struct Mock {
+        room: usize,
+        written: Vec<u8>,
+    }
+
+    impl Port for Mock {
+        fn write(&mut self, data: &[u8]) -> Result<usize, UsbError> {
+            let len = core::cmp::min(core::cmp::min(data.len(), self.room), 4);
+            if len == 0 && !data.is_empty() {
+                return Err(UsbError::WouldBlock);
+            }
+            self.room -= len;
+            self.written.extend_from_slice(&data[.. len]);
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn drain_partial() {
+        let mut port = Mock { room: 6, ..Default::default() };
+        let mut buffer: VecDeque<u8> = (0 .. 10).collect();
+        assert!(!drain(&mut port, &mut buffer).unwrap());
+        assert_eq!(port.written, [0, 1, 2, 3, 4, 5]);
+        assert_eq!(buffer, [6, 7, 8, 9]);
+        port.room = 10;
+        assert!(drain(&mut port, &mut buffer).unwrap());
+        assert_eq!(port.written, (0 .. 10).collect::<Vec<_>>());
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn write_buffered_full() {
+        let mut port = Mock::default();
+        let mut buffer = VecDeque::new();
+        let mut write =
+            |port: &mut Mock, input: &[u8]| write_buffered(port, &mut buffer, 4, input).unwrap();
+        // The port doesn't accept anything, so the input is buffered up to the capacity.
+        assert_eq!(write(&mut port, &[0, 1, 2, 3, 4, 5]), 4);
+        assert_eq!(write(&mut port, &[4, 5]), 0);
+        // Pending bytes are written first and the rest of the input is buffered.
+        port.room = 6;
+        assert_eq!(write(&mut port, &[4, 5, 6, 7, 8, 9]), 6);
+        assert_eq!(port.written, [0, 1, 2, 3, 4, 5]);
+        port.room = 10;
+        assert_eq!(write(&mut port, &[]), 0);
+        assert_eq!(port.written, (0 .. 10).collect::<Vec<_>>());
     }
 }
@@ -0,0 +1,119 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! USB firmware version helper.
+//!
+//! Host tooling can identify the firmware without opening a serial session by sending a
+//! vendor-specific control request to the device. The response is the 4 bytes of the applet API
+//! hash in big-endian followed by the runner version in UTF-8.
+
+use usb_device::class_prelude::{ControlIn, UsbBus, UsbClass};
+use usb_device::control::{Recipient, Request, RequestType};
+use usb_device::UsbDirection;
+
+/// Vendor-specific request code to get the firmware version.
+pub const REQUEST_VERSION: u8 = 0x01;
+
+/// Helper struct for boards using the `usb_device` crate.
+///
+/// This class answers the [`REQUEST_VERSION`] vendor request addressed to the device.
+pub struct Version {
+    runner: &'static str,
+    api_hash: u32,
+}
+
+impl Version {
+    /// Creates a version class from the runner version and the applet API hash.
+    pub fn new(runner: &'static str, api_hash: u32) -> Self {
+        Version { runner, api_hash }
+    }
+
+    /// Writes the response to a request if it is a version request.
+    ///
+    /// Returns the length of the response or `None` if the request is not a version request.
+    /// The response is truncated to the requested length and the output buffer.
+    pub fn respond(&self, request: &Request, output: &mut [u8]) -> Option<usize> {
+        if !is_version(request) {
+            return None;
+        }
+        let hash = self.api_hash.to_be_bytes();
+        let data = hash.iter().chain(self.runner.as_bytes());
+        let len = output.len().min(request.length as usize);
+        Some(output[.. len].iter_mut().zip(data).map(|(x, y)| *x = *y).count())
+    }
+}
+
+impl<T: UsbBus> UsbClass<T> for Version {
+    fn control_in(&mut self, xfer: ControlIn<T>) {
+        let request = *xfer.request();
+        if !is_version(&request) {
+            // Other requests are handled by other classes or the device.
+            return;
+        }
+        let _ = xfer.accept(|output| Ok(self.respond(&request, output).unwrap_or(0)));
+    }
+}
+
+fn is_version(request: &Request) -> bool {
+    request.direction == UsbDirection::In
+        && request.request_type == RequestType::Vendor
+        && request.recipient == Recipient::Device
+        && request.request == REQUEST_VERSION
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(request_type: RequestType, request: u8, length: u16) -> Request {
+        Request {
+            direction: UsbDirection::In,
+            request_type,
+            recipient: Recipient::Device,
+            request,
+            value: 0,
+            index: 0,
+            length,
+        }
+    }
+
+    #[test]
+    fn respond_version() {
+        let version = Version::new("1.2.3", 0x01020304);
+        let mut output = [0; 64];
+        let request = request(RequestType::Vendor, REQUEST_VERSION, 64);
+        let len = version.respond(&request, &mut output).unwrap();
+        assert_eq!(&output[.. len], b"\x01\x02\x03\x041.2.3");
+    }
+
+    #[test]
+    fn respond_truncated() {
+        let version = Version::new("1.2.3", 0x01020304);
+        let mut output = [0; 64];
+        let request = request(RequestType::Vendor, REQUEST_VERSION, 6);
+        let len = version.respond(&request, &mut output).unwrap();
+        assert_eq!(&output[.. len], b"\x01\x02\x03\x041.");
+    }
+
+    #[test]
+    fn respond_other() {
+        let version = Version::new("1.2.3", 0x01020304);
+        let mut output = [0; 64];
+        assert_eq!(
+            version.respond(&request(RequestType::Class, REQUEST_VERSION, 64), &mut output),
+            None
+        );
+        assert_eq!(version.respond(&request(RequestType::Vendor, 0x02, 64), &mut output), None);
+    }
+}
@@ -0,0 +1,65 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Watchdog interface.
+//!
+//! The watchdog resets the platform if it is not fed in time. The scheduler starts it and feeds it
+//! between event dispatches, such that an applet stuck in a loop triggers a reset instead of
+//! bricking the device until a power cycle. Boards should pause the watchdog while waiting for
+//! events in [`crate::Api::wait_event()`], since idling is not hanging.
+
+use crate::{Error, Unimplemented, Unsupported};
+
+pub trait Api {
+    /// Starts the watchdog with a timeout in milliseconds.
+    ///
+    /// This is a user error if the watchdog is already started.
+    fn start(&mut self, timeout_ms: usize) -> Result<(), Error>;
+
+    /// Feeds the watchdog, restarting its timeout.
+    fn feed(&mut self) -> Result<(), Error>;
+
+    /// Stops the watchdog.
+    ///
+    /// Some watchdogs can't be stopped once started, in which case this is a user error.
+    fn stop(&mut self) -> Result<(), Error>;
+}
+
+impl Api for Unimplemented {
+    fn start(&mut self, _: usize) -> Result<(), Error> {
+        unreachable!()
+    }
+
+    fn feed(&mut self) -> Result<(), Error> {
+        unreachable!()
+    }
+
+    fn stop(&mut self) -> Result<(), Error> {
+        unreachable!()
+    }
+}
+
+impl Api for Unsupported {
+    fn start(&mut self, _: usize) -> Result<(), Error> {
+        Err(Error::User)
+    }
+
+    fn feed(&mut self) -> Result<(), Error> {
+        Err(Error::User)
+    }
+
+    fn stop(&mut self) -> Result<(), Error> {
+        Err(Error::User)
+    }
+}
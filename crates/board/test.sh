@@ -21,3 +21,5 @@ cargo check --target=thumbv7em-none-eabi --features=software-crypto
 cargo fmt -- --check
 cargo clippy --features=std -- --deny=warnings
 cargo test --features=std
+cargo test --features=std,software-crypto
+cargo test --features=std,rng-mixing
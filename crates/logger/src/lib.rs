@@ -13,6 +13,9 @@
 // limitations under the License.
 #![cfg_attr(not(any(test, feature = "log")), no_std)]
 
+use core::sync::atomic::AtomicU8;
+use core::sync::atomic::Ordering::Relaxed;
+
 #[cfg(not(feature = "defmt"))]
 mod no_defmt {
     use core::fmt::{Debug, Display, Formatter, Result};
@@ -42,26 +45,41 @@ mod custom {
 
     #[macro_export]
     macro_rules! trace {
+        ($($key: ident = $value: expr),+ ; $($args: expr),* $(,)?) => {
+            if false { $(let _ = $value;)+ $(let _ = $args;)* }
+        };
         ($($args: expr),*) => { if false { $(let _ = $args;)* } };
     }
 
     #[macro_export]
     macro_rules! debug {
+        ($($key: ident = $value: expr),+ ; $($args: expr),* $(,)?) => {
+            if false { $(let _ = $value;)+ $(let _ = $args;)* }
+        };
         ($($args: expr),*) => { if false { $(let _ = $args;)* } };
     }
 
     #[macro_export]
     macro_rules! info {
+        ($($key: ident = $value: expr),+ ; $($args: expr),* $(,)?) => {
+            if false { $(let _ = $value;)+ $(let _ = $args;)* }
+        };
         ($($args: expr),*) => { if false { $(let _ = $args;)* } };
     }
 
     #[macro_export]
     macro_rules! warn {
+        ($($key: ident = $value: expr),+ ; $($args: expr),* $(,)?) => {
+            if false { $(let _ = $value;)+ $(let _ = $args;)* }
+        };
         ($($args: expr),*) => { if false { $(let _ = $args;)* } };
     }
 
     #[macro_export]
     macro_rules! error {
+        ($($key: ident = $value: expr),+ ; $($args: expr),* $(,)?) => {
+            if false { $(let _ = $value;)+ $(let _ = $args;)* }
+        };
         ($($args: expr),*) => { if false { $(let _ = $args;)* } };
     }
 }
@@ -85,12 +103,133 @@ mod custom {
     }
 }
 
+/// Logging levels from the least to the most verbose.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum Level {
+    Error = 1,
+    Warn = 2,
+    Info = 3,
+    Debug = 4,
+    Trace = 5,
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(Level::Trace as u8);
+
+/// Sets the most verbose level of the logging macros at runtime.
+///
+/// This only filters messages further: levels disabled at compile-time stay disabled. By default,
+/// all levels enabled at compile-time are logged.
+pub fn set_level(level: Level) {
+    LEVEL.store(level as u8, Relaxed);
+}
+
+/// Returns whether a level is logged according to [`set_level()`].
+pub fn enabled(level: Level) -> bool {
+    level as u8 <= LEVEL.load(Relaxed)
+}
+
+#[cfg(any(feature = "log", feature = "defmt"))]
+mod levels {
+    #[macro_export]
+    macro_rules! trace {
+        ($($args: tt)*) => {
+            if $crate::enabled($crate::Level::Trace) {
+                $crate::__log!(trace, $($args)*)
+            }
+        };
+    }
+
+    #[macro_export]
+    macro_rules! debug {
+        ($($args: tt)*) => {
+            if $crate::enabled($crate::Level::Debug) {
+                $crate::__log!(debug, $($args)*)
+            }
+        };
+    }
+
+    #[macro_export]
+    macro_rules! info {
+        ($($args: tt)*) => {
+            if $crate::enabled($crate::Level::Info) {
+                $crate::__log!(info, $($args)*)
+            }
+        };
+    }
+
+    #[macro_export]
+    macro_rules! warn {
+        ($($args: tt)*) => {
+            if $crate::enabled($crate::Level::Warn) {
+                $crate::__log!(warn, $($args)*)
+            }
+        };
+    }
+
+    #[macro_export]
+    macro_rules! error {
+        ($($args: tt)*) => {
+            if $crate::enabled($crate::Level::Error) {
+                $crate::__log!(error, $($args)*)
+            }
+        };
+    }
+}
+
+/// Logs a message followed by its key-value fields as `key=value`.
+#[cfg(feature = "log")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log {
+    ($level: ident, $($key: ident = $value: expr),+ ; $fmt: literal $(, $args: expr)* $(,)?) => {
+        $crate::__backend::$level!(
+            concat!($fmt $(, " ", stringify!($key), "={}")+) $(, $args)* $(, $value)+
+        )
+    };
+    ($level: ident, $($args: tt)*) => { $crate::__backend::$level!($($args)*) };
+}
+
+/// Logs a message followed by its key-value fields, each encoded as separate arguments.
+#[cfg(feature = "defmt")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log {
+    ($level: ident, $($key: ident = $value: expr),+ ; $fmt: literal $(, $args: expr)* $(,)?) => {
+        $crate::__backend::$level!(
+            "{}",
+            $crate::Fields(|f: $crate::__backend::Formatter<'_>| {
+                $crate::__backend::write!(f, $fmt $(, $args)*);
+                $($crate::__backend::write!(f, " {=str}={}", stringify!($key), $value);)+
+            })
+        )
+    };
+    ($level: ident, $($args: tt)*) => { $crate::__backend::$level!($($args)*) };
+}
+
+/// Formats a message with its fields.
+#[cfg(feature = "defmt")]
+#[doc(hidden)]
+pub struct Fields<F: Fn(defmt::Formatter<'_>)>(pub F);
+
+#[cfg(feature = "defmt")]
+impl<F: Fn(defmt::Formatter<'_>)> defmt::Format for Fields<F> {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        (self.0)(f)
+    }
+}
+
 #[cfg(not(feature = "defmt"))]
 pub use custom::*;
 #[cfg(feature = "defmt")]
-pub use defmt::{debug, error, info, panic, println, trace, warn, Debug2Format, Display2Format};
+#[doc(hidden)]
+pub use defmt as __backend;
+#[cfg(feature = "defmt")]
+pub use defmt::{panic, println, Debug2Format, Display2Format};
 #[cfg(feature = "log")]
-pub use log::{debug, error, info, trace, warn};
+#[doc(hidden)]
+pub use log as __backend;
 #[cfg(not(feature = "defmt"))]
 pub use no_defmt::*;
 
@@ -0,0 +1,49 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides API for analog-to-digital converters.
+//!
+//! Analog inputs are abstracted with:
+//! - They are identified by an index less than [count()].
+//! - Their samples are normalized to 16 bits: 0 is ground and `u16::MAX` is the reference voltage.
+
+use wasefire_applet_api::adc as api;
+
+pub mod thermistor;
+
+/// Error sampling an analog input.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Error;
+
+/// Returns the number of available analog inputs on the board.
+pub fn count() -> usize {
+    let api::count::Results { cnt } = unsafe { api::count() };
+    cnt
+}
+
+/// Samples an analog input.
+///
+/// The `adc` argument is the index of the analog input. It must be less than [count()].
+pub fn read(adc: usize) -> Result<u16, Error> {
+    let api::read::Results { res } = unsafe { api::read(api::read::Params { adc }) };
+    u16::try_from(res).map_err(|_| Error)
+}
+
+/// Measures the supply voltage in millivolts.
+///
+/// This is useful to monitor the battery of portable boards.
+pub fn read_vdd_mv() -> Result<u16, Error> {
+    let api::read_vdd::Results { res } = unsafe { api::read_vdd() };
+    u16::try_from(res).map_err(|_| Error)
+}
@@ -0,0 +1,168 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides temperature readings from an NTC thermistor.
+//!
+//! The thermistor is part of a voltage divider with a series resistor between the reference
+//! voltage and ground. Its middle point is connected to an analog input. The resistance of the
+//! thermistor is converted to a temperature using the Steinhart-Hart equation:
+//!
+//! 1/T = A + B ln(R) + C ln(R)^3
+//!
+//! where T is in kelvin and R in ohms.
+
+use super::read;
+
+/// Errors reading a thermistor.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Error {
+    /// The configuration is invalid.
+    InvalidConfig,
+
+    /// The analog input could not be sampled.
+    Adc,
+
+    /// The sample is at the end of the range (the thermistor is shorted or disconnected).
+    OutOfRange,
+}
+
+/// Steinhart-Hart coefficients.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Coefficients {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+}
+
+impl Coefficients {
+    /// Typical coefficients of a 10kΩ NTC thermistor (B25/85 = 3950K).
+    pub const NTC_10K: Coefficients =
+        Coefficients { a: 1.009_249_5e-3, b: 2.378_405_4e-4, c: 2.019_202_7e-7 };
+}
+
+/// Position of the thermistor in the voltage divider.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Position {
+    /// The thermistor is between the analog input and ground.
+    Low,
+
+    /// The thermistor is between the reference voltage and the analog input.
+    High,
+}
+
+/// Thermistor configuration.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Config {
+    /// Steinhart-Hart coefficients of the thermistor.
+    pub coefficients: Coefficients,
+
+    /// Resistance of the series resistor in ohms.
+    pub series: f32,
+
+    /// Position of the thermistor in the voltage divider.
+    pub position: Position,
+}
+
+/// Thermistor connected to an analog input.
+#[derive(Debug)]
+pub struct Thermistor {
+    adc: usize,
+    config: Config,
+}
+
+impl Thermistor {
+    /// Creates a thermistor on an analog input.
+    ///
+    /// Returns [`Error::InvalidConfig`] if the coefficients are missing (all zero) or not finite,
+    /// or if the series resistance is not positive.
+    pub fn new(adc: usize, config: Config) -> Result<Self, Error> {
+        let Coefficients { a, b, c } = config.coefficients;
+        if ![a, b, c].iter().all(|x| x.is_finite()) || [a, b, c].iter().all(|&x| x == 0.) {
+            return Err(Error::InvalidConfig);
+        }
+        if !(config.series.is_finite() && 0. < config.series) {
+            return Err(Error::InvalidConfig);
+        }
+        Ok(Thermistor { adc, config })
+    }
+
+    /// Samples the thermistor and returns the temperature in degrees Celsius.
+    pub fn read(&self) -> Result<f32, Error> {
+        self.convert(read(self.adc).map_err(|_| Error::Adc)?)
+    }
+
+    /// Converts a sample to a temperature in degrees Celsius.
+    pub fn convert(&self, sample: u16) -> Result<f32, Error> {
+        if sample == 0 || sample == u16::MAX {
+            return Err(Error::OutOfRange);
+        }
+        // Ratio of the reference voltage at the analog input.
+        let x = sample as f32 / u16::MAX as f32;
+        let ratio = match self.config.position {
+            Position::Low => x / (1. - x),
+            Position::High => (1. - x) / x,
+        };
+        let ln_r = libm::logf(self.config.series * ratio);
+        let Coefficients { a, b, c } = self.config.coefficients;
+        let inverse = a + b * ln_r + c * ln_r * ln_r * ln_r;
+        if inverse.is_nan() || inverse <= 0. {
+            return Err(Error::OutOfRange);
+        }
+        Ok(1. / inverse - 273.15)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: Config =
+        Config { coefficients: Coefficients::NTC_10K, series: 10_000., position: Position::Low };
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 0.1, "{actual} is not close to {expected}");
+    }
+
+    #[test]
+    fn convert_ntc_10k() {
+        let thermistor = Thermistor::new(0, CONFIG).unwrap();
+        // 10kΩ (half the reference voltage).
+        assert_close(thermistor.convert(32768).unwrap(), 24.68);
+        // 3.3kΩ is about 55°C.
+        assert_close(thermistor.convert(16256).unwrap(), 55.43);
+        // The high position mirrors the sample.
+        let config = Config { position: Position::High, ..CONFIG };
+        let thermistor = Thermistor::new(0, config).unwrap();
+        assert_close(thermistor.convert(u16::MAX - 16256).unwrap(), 55.43);
+    }
+
+    #[test]
+    fn convert_out_of_range() {
+        let thermistor = Thermistor::new(0, CONFIG).unwrap();
+        assert_eq!(thermistor.convert(0), Err(Error::OutOfRange));
+        assert_eq!(thermistor.convert(u16::MAX), Err(Error::OutOfRange));
+    }
+
+    #[test]
+    fn new_invalid() {
+        let coefficients = Coefficients { a: 0., b: 0., c: 0. };
+        let config = Config { coefficients, ..CONFIG };
+        assert_eq!(Thermistor::new(0, config).unwrap_err(), Error::InvalidConfig);
+        let coefficients = Coefficients { a: f32::NAN, ..Coefficients::NTC_10K };
+        let config = Config { coefficients, ..CONFIG };
+        assert_eq!(Thermistor::new(0, config).unwrap_err(), Error::InvalidConfig);
+        let config = Config { series: 0., ..CONFIG };
+        assert_eq!(Thermistor::new(0, config).unwrap_err(), Error::InvalidConfig);
+    }
+}
@@ -17,13 +17,17 @@
 //! Button or touch sensors are abstracted with:
 //! - They have 2 states: `Pressed` or `Released`.
 //! - They can trigger a callback on any state change.
+//! - They can alternatively trigger a callback on gestures (single, long, or double press).
 
 use alloc::boxed::Box;
+use alloc::rc::Rc;
+use core::cell::Cell;
+use core::time::Duration;
 
 use wasefire_applet_api::button as api;
 
-pub use self::api::State;
 pub use self::api::State::*;
+pub use self::api::{Gesture, State};
 
 /// Returns the number of available buttons on the board.
 pub fn count() -> usize {
@@ -104,3 +108,119 @@ impl<H: Handler> Drop for Listener<H> {
         unsafe { Box::from_raw(self.handler) };
     }
 }
+
+/// Asynchronously waits until a button is pressed.
+///
+/// The `button` argument is the index of the button. It must be less than [count()].
+pub async fn wait_press(button: usize) {
+    let pressed = Rc::new(Cell::new(false));
+    let _listener = Listener::new(button, {
+        let pressed = pressed.clone();
+        move |state| pressed.set(pressed.get() || state == Pressed)
+    });
+    crate::executor::wait_until(|| pressed.get()).await;
+}
+
+/// Error configuring gestures.
+///
+/// This happens when all timers are already allocated or the board doesn't have a clock.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Error;
+
+/// Gesture configuration of a button.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct GestureConfig {
+    /// Minimum duration of a long press.
+    ///
+    /// This must be at least one millisecond.
+    pub long: Duration,
+
+    /// Maximum duration between a release and the next press for a double press.
+    ///
+    /// Single presses are reported after this duration. Zero disables double presses, such that
+    /// single presses are reported on release.
+    pub double: Duration,
+}
+
+impl Default for GestureConfig {
+    fn default() -> Self {
+        GestureConfig { long: Duration::from_millis(800), double: Duration::from_millis(300) }
+    }
+}
+
+/// Provides callback support for button gestures.
+pub trait GestureHandler: 'static {
+    /// Called when a gesture is recognized.
+    fn gesture(&self, gesture: Gesture);
+}
+
+impl<F: Fn(Gesture) + 'static> GestureHandler for F {
+    fn gesture(&self, gesture: Gesture) {
+        self(gesture)
+    }
+}
+
+/// Provides listening support for button gestures.
+///
+/// While listening for gestures, the button doesn't trigger state changes.
+#[must_use]
+pub struct GestureListener<H: GestureHandler> {
+    button: usize,
+    handler: *mut H,
+}
+
+impl<H: GestureHandler> GestureListener<H> {
+    /// Starts listening for button gestures.
+    ///
+    /// This is similar to [Listener::new()] but with a gesture configuration. Gesture recognition
+    /// uses one of the timers available to the applet.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// GestureListener::new(index, GestureConfig::default(), |gesture| debug!("{gesture:?}"))
+    /// ```
+    pub fn new(button: usize, config: GestureConfig, handler: H) -> Result<Self, Error> {
+        let long = core::cmp::max(config.long.as_millis() as usize, 1);
+        let double = config.double.as_millis() as usize;
+        let params = api::gesture::Params { button, long, double };
+        let api::gesture::Results { res } = unsafe { api::gesture(params) };
+        if res < 0 {
+            return Err(Error);
+        }
+        let handler_func = Self::call;
+        let handler = Box::into_raw(Box::new(handler));
+        let handler_data = handler as *mut u8;
+        unsafe { api::register(api::register::Params { button, handler_func, handler_data }) };
+        Ok(GestureListener { button, handler })
+    }
+
+    /// Stops listening.
+    ///
+    /// This is equivalent to calling `core::mem::drop()`.
+    pub fn stop(self) {
+        core::mem::drop(self);
+    }
+
+    /// Drops the listener but continues listening.
+    ///
+    /// This is equivalent to calling `core::mem::forget()`.
+    pub fn leak(self) {
+        core::mem::forget(self);
+    }
+
+    extern "C" fn call(data: *mut u8, gesture: usize) {
+        let handler = unsafe { &mut *(data as *mut H) };
+        handler.gesture(gesture.into());
+    }
+}
+
+impl<H: GestureHandler> Drop for GestureListener<H> {
+    fn drop(&mut self) {
+        let params = api::unregister::Params { button: self.button };
+        unsafe { api::unregister(params) };
+        let params = api::gesture::Params { button: self.button, long: 0, double: 0 };
+        unsafe { api::gesture(params) };
+        unsafe { Box::from_raw(self.handler) };
+    }
+}
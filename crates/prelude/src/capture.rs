@@ -0,0 +1,144 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides API for input capture.
+//!
+//! This measures the frequency and duty cycle of a periodic signal, for example to read PWM-encoded
+//! sensors or tachometers. The GPIO must first be configured as an input with [`crate::gpio`].
+//! Measurements run in the background and can trigger a callback when done.
+
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use core::cell::Cell;
+
+use wasefire_applet_api::capture as api;
+
+/// Errors measuring a signal.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Error {
+    /// The GPIO is invalid, not configured as an input, or busy.
+    Invalid,
+
+    /// No periodic signal was detected within the board time window.
+    Timeout,
+}
+
+/// Starts measuring the signal on a GPIO.
+///
+/// This function returns immediately, see [Listener] to be notified when the measurement is done
+/// and [result()] to read it.
+pub fn start(gpio: usize) -> Result<(), Error> {
+    let api::start::Results { res } = unsafe { api::start(api::start::Params { gpio }) };
+    match res {
+        0 => Ok(()),
+        _ => Err(Error::Invalid),
+    }
+}
+
+/// Reads the result of the done measurement on a GPIO.
+///
+/// Returns the frequency in Hertz and the duty cycle (between 0 and 1).
+pub fn result(gpio: usize) -> Result<(f32, f32), Error> {
+    let mut output = [0u8; 8];
+    let params = api::result::Params { gpio, ptr: output.as_mut_ptr() };
+    let api::result::Results { res } = unsafe { api::result(params) };
+    match res {
+        1 => (),
+        0 => return Err(Error::Timeout),
+        _ => return Err(Error::Invalid),
+    }
+    let period_ns = u32::from_le_bytes(output[.. 4].try_into().unwrap()) as f32;
+    let high_ns = u32::from_le_bytes(output[4 ..].try_into().unwrap()) as f32;
+    Ok((1e9 / period_ns, high_ns / period_ns))
+}
+
+/// Measures the signal on a GPIO and waits until the measurement is done.
+///
+/// Returns the frequency in Hertz and the duty cycle (between 0 and 1). This is a convenience
+/// function to avoid creating a listener and a callback.
+pub fn measure(gpio: usize) -> Result<(f32, f32), Error> {
+    let done = Rc::new(Cell::new(false));
+    let listener = Listener::new(gpio, {
+        let done = done.clone();
+        move || done.set(true)
+    });
+    start(gpio)?;
+    while !done.get() {
+        crate::scheduling::wait_for_callback();
+    }
+    listener.stop();
+    result(gpio)
+}
+
+/// Provides callback support for input capture events.
+pub trait Handler: 'static {
+    /// Called when a measurement is done.
+    fn event(&self);
+}
+
+impl<F: Fn() + 'static> Handler for F {
+    fn event(&self) {
+        self()
+    }
+}
+
+/// Provides listening support for input capture events.
+#[must_use]
+pub struct Listener<H: Handler> {
+    gpio: usize,
+    handler: *mut H,
+}
+
+impl<H: Handler> Listener<H> {
+    /// Starts listening for input capture events.
+    ///
+    /// The `gpio` argument is the index of the GPIO to listen events for. The `handler` argument is
+    /// the callback to be called when a measurement is done. Note that it may be an `Fn()` closure.
+    ///
+    /// The listener stops listening when dropped.
+    pub fn new(gpio: usize, handler: H) -> Self {
+        let handler_func = Self::call;
+        let handler = Box::into_raw(Box::new(handler));
+        let handler_data = handler as *mut u8;
+        unsafe { api::register(api::register::Params { gpio, handler_func, handler_data }) };
+        Listener { gpio, handler }
+    }
+
+    /// Stops listening.
+    ///
+    /// This is equivalent to calling `core::mem::drop()`.
+    pub fn stop(self) {
+        core::mem::drop(self);
+    }
+
+    /// Drops the listener but continues listening.
+    ///
+    /// This is equivalent to calling `core::mem::forget()`.
+    pub fn leak(self) {
+        core::mem::forget(self);
+    }
+
+    extern "C" fn call(data: *mut u8) {
+        let handler = unsafe { &mut *(data as *mut H) };
+        handler.event();
+    }
+}
+
+impl<H: Handler> Drop for Listener<H> {
+    fn drop(&mut self) {
+        let params = api::unregister::Params { gpio: self.gpio };
+        unsafe { api::unregister(params) };
+        unsafe { Box::from_raw(self.handler) };
+    }
+}
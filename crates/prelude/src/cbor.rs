@@ -0,0 +1,345 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides CBOR encoding and decoding.
+//!
+//! This wraps the [minicbor] crate, which is re-exported to implement [`Encode`] and [`Decode`].
+//!
+//! CTAP2 requires the canonical CBOR encoding form (section 3.9 of RFC 7049 with the CTAP2 key
+//! ordering):
+//! - Integers and lengths use the shortest encoding.
+//! - Strings, arrays, and maps have definite lengths.
+//! - Map keys are unique and sorted by major type, then length, then bytewise.
+//!
+//! The `_canonical` functions produce and accept only this form. Floating-point numbers are
+//! copied as is.
+
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use minicbor::data::Type;
+pub use minicbor::{self, Decode, Decoder, Encode, Encoder};
+
+/// Maximum nesting depth of arrays, maps, and tags when canonicalizing.
+///
+/// This bounds the stack usage on untrusted input. CTAP2 messages nest at most 4 levels.
+pub const MAX_DEPTH: usize = 16;
+
+/// CBOR errors.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The input is not valid CBOR or doesn't match the expected type.
+    Invalid,
+
+    /// The input is valid CBOR but not in canonical form.
+    NotCanonical,
+
+    /// The input is nested deeper than [`MAX_DEPTH`].
+    TooDeep,
+}
+
+impl From<minicbor::decode::Error> for Error {
+    fn from(_: minicbor::decode::Error) -> Self {
+        Error::Invalid
+    }
+}
+
+impl<E> From<minicbor::encode::Error<E>> for Error {
+    fn from(_: minicbor::encode::Error<E>) -> Self {
+        Error::Invalid
+    }
+}
+
+/// Encodes a value.
+pub fn encode<T: Encode<()>>(value: &T) -> Result<Vec<u8>, Error> {
+    Ok(minicbor::to_vec(value)?)
+}
+
+/// Decodes a value, which must span the whole input.
+pub fn decode<'a, T: Decode<'a, ()>>(input: &'a [u8]) -> Result<T, Error> {
+    let mut decoder = Decoder::new(input);
+    let value = decoder.decode()?;
+    match decoder.position() == input.len() {
+        true => Ok(value),
+        false => Err(Error::Invalid),
+    }
+}
+
+/// Encodes a value in canonical form.
+pub fn encode_canonical<T: Encode<()>>(value: &T) -> Result<Vec<u8>, Error> {
+    canonicalize(&encode(value)?)
+}
+
+/// Decodes a value, which must be in canonical form and span the whole input.
+pub fn decode_canonical<'a, T: Decode<'a, ()>>(input: &'a [u8]) -> Result<T, Error> {
+    if canonicalize(input)? != input {
+        return Err(Error::NotCanonical);
+    }
+    decode(input)
+}
+
+/// Returns whether the input is a single CBOR item in canonical form.
+pub fn is_canonical(input: &[u8]) -> bool {
+    canonicalize(input).is_ok_and(|x| x == input)
+}
+
+/// Converts a single CBOR item to canonical form.
+///
+/// Integers and lengths are shortened and map entries are sorted. Indefinite lengths and
+/// duplicate map keys return [`Error::NotCanonical`] because they have no canonical equivalent.
+pub fn canonicalize(input: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut decoder = Decoder::new(input);
+    let mut output = Vec::with_capacity(input.len());
+    canonical(&mut decoder, &mut output, 0)?;
+    if decoder.position() != input.len() {
+        return Err(Error::Invalid);
+    }
+    Ok(output)
+}
+
+/// Compares 2 encoded map keys according to the CTAP2 canonical ordering.
+pub fn compare_keys(x: &[u8], y: &[u8]) -> Ordering {
+    let major = |x: &[u8]| x.first().map(|x| x >> 5);
+    major(x).cmp(&major(y)).then(x.len().cmp(&y.len())).then(x.cmp(y))
+}
+
+fn canonical(decoder: &mut Decoder, output: &mut Vec<u8>, depth: usize) -> Result<(), Error> {
+    if MAX_DEPTH < depth {
+        return Err(Error::TooDeep);
+    }
+    let mut encoder = Encoder::new(&mut *output);
+    match decoder.datatype()? {
+        Type::U8
+        | Type::U16
+        | Type::U32
+        | Type::U64
+        | Type::I8
+        | Type::I16
+        | Type::I32
+        | Type::I64
+        | Type::Int => {
+            encoder.int(decoder.int()?)?;
+        }
+        Type::Bool => {
+            encoder.bool(decoder.bool()?)?;
+        }
+        Type::Null => {
+            decoder.null()?;
+            encoder.null()?;
+        }
+        Type::Undefined => {
+            decoder.undefined()?;
+            encoder.undefined()?;
+        }
+        Type::Simple => {
+            encoder.simple(decoder.simple()?)?;
+        }
+        Type::F16 | Type::F32 | Type::F64 => {
+            let start = decoder.position();
+            decoder.skip()?;
+            output.extend_from_slice(&decoder.input()[start .. decoder.position()]);
+        }
+        Type::Bytes => {
+            encoder.bytes(decoder.bytes()?)?;
+        }
+        Type::String => {
+            encoder.str(decoder.str()?)?;
+        }
+        Type::Tag => {
+            encoder.tag(decoder.tag()?)?;
+            canonical(decoder, output, depth + 1)?;
+        }
+        Type::Array => {
+            let len = decoder.array()?.ok_or(Error::NotCanonical)?;
+            encoder.array(len)?;
+            for _ in 0 .. len {
+                canonical(decoder, output, depth + 1)?;
+            }
+        }
+        Type::Map => {
+            let len = decoder.map()?.ok_or(Error::NotCanonical)?;
+            encoder.map(len)?;
+            let mut entries = Vec::new();
+            for _ in 0 .. len {
+                let mut key = Vec::new();
+                canonical(decoder, &mut key, depth + 1)?;
+                let mut value = Vec::new();
+                canonical(decoder, &mut value, depth + 1)?;
+                entries.push((key, value));
+            }
+            entries.sort_by(|x, y| compare_keys(&x.0, &y.0));
+            if entries.windows(2).any(|x| x[0].0 == x[1].0) {
+                return Err(Error::NotCanonical);
+            }
+            for (key, value) in entries {
+                output.extend_from_slice(&key);
+                output.extend_from_slice(&value);
+            }
+        }
+        Type::BytesIndef | Type::StringIndef | Type::ArrayIndef | Type::MapIndef => {
+            return Err(Error::NotCanonical)
+        }
+        Type::Break | Type::Unknown(_) => return Err(Error::Invalid),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::{String, ToString};
+    use alloc::vec;
+
+    use super::*;
+
+    /// Subset of the authenticatorGetInfo response of CTAP2.
+    #[derive(Debug, PartialEq, Eq)]
+    struct GetInfo {
+        versions: Vec<String>,
+        extensions: Vec<String>,
+        aaguid: [u8; 16],
+        options: Vec<(String, bool)>,
+        max_msg_size: u32,
+        pin_protocols: Vec<u32>,
+    }
+
+    impl<C> Encode<C> for GetInfo {
+        fn encode<W: minicbor::encode::Write>(
+            &self, e: &mut Encoder<W>, ctx: &mut C,
+        ) -> Result<(), minicbor::encode::Error<W::Error>> {
+            // Keys and options are in non-canonical order on purpose.
+            e.map(6)?;
+            e.u8(0x04)?.map(self.options.len() as u64)?;
+            for (key, value) in &self.options {
+                e.str(key)?.bool(*value)?;
+            }
+            e.u8(0x01)?.encode_with(&self.versions, ctx)?;
+            e.u8(0x02)?.encode_with(&self.extensions, ctx)?;
+            e.u8(0x03)?.bytes(&self.aaguid)?;
+            e.u8(0x05)?.u32(self.max_msg_size)?;
+            e.u8(0x06)?.encode_with(&self.pin_protocols, ctx)?;
+            Ok(())
+        }
+    }
+
+    impl<'b, C> Decode<'b, C> for GetInfo {
+        fn decode(d: &mut Decoder<'b>, ctx: &mut C) -> Result<Self, minicbor::decode::Error> {
+            let mut info = GetInfo {
+                versions: Vec::new(),
+                extensions: Vec::new(),
+                aaguid: [0; 16],
+                options: Vec::new(),
+                max_msg_size: 0,
+                pin_protocols: Vec::new(),
+            };
+            let len = d.map()?.ok_or(minicbor::decode::Error::message("indefinite map"))?;
+            for _ in 0 .. len {
+                match d.u8()? {
+                    0x01 => info.versions = d.decode_with(ctx)?,
+                    0x02 => info.extensions = d.decode_with(ctx)?,
+                    0x03 => {
+                        info.aaguid = d.bytes()?.try_into().map_err(|_| {
+                            minicbor::decode::Error::message("invalid aaguid length")
+                        })?
+                    }
+                    0x04 => {
+                        let len =
+                            d.map()?.ok_or(minicbor::decode::Error::message("indefinite map"))?;
+                        for _ in 0 .. len {
+                            info.options.push((d.str()?.to_string(), d.bool()?));
+                        }
+                    }
+                    0x05 => info.max_msg_size = d.u32()?,
+                    0x06 => info.pin_protocols = d.decode_with(ctx)?,
+                    _ => d.skip()?,
+                }
+            }
+            Ok(info)
+        }
+    }
+
+    fn get_info() -> GetInfo {
+        GetInfo {
+            versions: vec!["U2F_V2".to_string(), "FIDO_2_0".to_string()],
+            extensions: vec!["hmac-secret".to_string()],
+            aaguid: [0x42; 16],
+            options: vec![
+                ("plat".to_string(), false),
+                ("up".to_string(), true),
+                ("rk".to_string(), true),
+            ],
+            max_msg_size: 1200,
+            pin_protocols: vec![1],
+        }
+    }
+
+    #[test]
+    fn get_info_round_trip() {
+        let info = get_info();
+        let plain = encode(&info).unwrap();
+        assert!(!is_canonical(&plain));
+        assert_eq!(decode_canonical::<GetInfo>(&plain), Err(Error::NotCanonical));
+        let canonical = encode_canonical(&info).unwrap();
+        assert!(is_canonical(&canonical));
+        assert_eq!(canonical[.. 2], [0xa6, 0x01]);
+        let mut decoded = decode_canonical::<GetInfo>(&canonical).unwrap();
+        // Options are decoded in canonical order.
+        let keys: Vec<_> = decoded.options.iter().map(|x| x.0.as_str()).collect();
+        assert_eq!(keys, ["rk", "up", "plat"]);
+        let mut expected = get_info();
+        expected.options.sort();
+        decoded.options.sort();
+        assert_eq!(decoded, expected);
+        assert_eq!(decode::<GetInfo>(&plain).unwrap(), info);
+    }
+
+    #[test]
+    fn shortest_integers() {
+        assert!(is_canonical(&[0x17]));
+        assert!(!is_canonical(&[0x18, 0x17]));
+        assert_eq!(canonicalize(&[0x19, 0x00, 0x18]).unwrap(), [0x18, 0x18]);
+        assert_eq!(canonicalize(&[0x38, 0x00]).unwrap(), [0x20]);
+        // Lengths too.
+        assert_eq!(canonicalize(&[0x58, 0x01, 0xff]).unwrap(), [0x41, 0xff]);
+    }
+
+    #[test]
+    fn definite_lengths() {
+        // [_ 1, 2]
+        assert_eq!(canonicalize(&[0x9f, 0x01, 0x02, 0xff]), Err(Error::NotCanonical));
+        // (_ h'01')
+        assert_eq!(canonicalize(&[0x5f, 0x41, 0x01, 0xff]), Err(Error::NotCanonical));
+        // {_ 1: 2}
+        assert_eq!(canonicalize(&[0xbf, 0x01, 0x02, 0xff]), Err(Error::NotCanonical));
+    }
+
+    #[test]
+    fn sorted_keys() {
+        // {"a": 1, 10: 2, -1: 3, 1000: 4}
+        let map = [0xa4, 0x61, 0x61, 0x01, 0x0a, 0x02, 0x20, 0x03, 0x19, 0x03, 0xe8, 0x04];
+        // {10: 2, 1000: 4, -1: 3, "a": 1}
+        let sorted = [0xa4, 0x0a, 0x02, 0x19, 0x03, 0xe8, 0x04, 0x20, 0x03, 0x61, 0x61, 0x01];
+        assert_eq!(canonicalize(&map).unwrap(), sorted);
+        assert!(is_canonical(&sorted));
+        // Duplicate keys.
+        assert_eq!(canonicalize(&[0xa2, 0x01, 0x02, 0x01, 0x03]), Err(Error::NotCanonical));
+    }
+
+    #[test]
+    fn trailing_and_deep() {
+        assert_eq!(canonicalize(&[0x01, 0x02]), Err(Error::Invalid));
+        assert_eq!(decode::<u8>(&[0x01, 0x02]), Err(Error::Invalid));
+        let deep = [0x81; MAX_DEPTH + 2];
+        assert_eq!(canonicalize(&deep), Err(Error::TooDeep));
+    }
+}
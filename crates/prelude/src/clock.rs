@@ -13,8 +13,6 @@
 // limitations under the License.
 
 //! Provides API for clocks and timers.
-//!
-//! For now, only timers are provided.
 
 use alloc::boxed::Box;
 use alloc::rc::Rc;
@@ -23,9 +21,23 @@ use core::time::Duration;
 
 use wasefire_applet_api::clock as api;
 
+pub mod sun;
+
 pub use self::api::Mode;
 pub use self::api::Mode::*;
 
+/// Returns the time since the platform started.
+///
+/// The uptime is monotonic with micro-second resolution (but possibly lower precision). This panics
+/// if the platform doesn't support it.
+pub fn uptime() -> Duration {
+    let mut uptime = [0; 8];
+    let params = api::uptime::Params { ptr: uptime.as_mut_ptr() };
+    let api::uptime::Results { res } = unsafe { api::uptime(params) };
+    assert!(res == 0, "uptime is not supported");
+    Duration::from_micros(u64::from_le_bytes(uptime))
+}
+
 /// Provides callback support for timer events.
 pub trait Handler: 'static {
     /// Called when a timer fires.
@@ -139,3 +151,17 @@ pub fn sleep_ms(duration_ms: usize) {
 pub fn sleep(duration: Duration) {
     sleep_ms(duration.as_millis() as usize)
 }
+
+/// Asynchronously sleeps for a given duration.
+///
+/// This is the asynchronous version of [`sleep()`]. The time resolution is milli-seconds (rounded
+/// down).
+pub async fn sleep_async(duration: Duration) {
+    let done = Rc::new(Cell::new(false));
+    let timer = Timer::new({
+        let done = done.clone();
+        move || done.set(true)
+    });
+    timer.start(Oneshot, duration);
+    crate::executor::wait_until(|| done.get()).await;
+}
@@ -0,0 +1,163 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides sunrise and sunset times to schedule work relative to them.
+//!
+//! Times are computed with the [sunrise equation] and are accurate to about a minute (except near
+//! the poles). Note that the platform doesn't provide wall-clock time: the Unix time must be
+//! provided by the caller (for example from the host or a synchronized counter).
+//!
+//! [sunrise equation]: https://en.wikipedia.org/wiki/Sunrise_equation
+
+use libm::{acos, asin, cos, sin};
+
+use super::{Handler, Oneshot, Timer};
+
+/// Number of seconds in a day.
+const DAY: i64 = 86400;
+
+/// Unix time of the J2000 epoch (2000-01-01 at noon).
+const J2000: i64 = 946728000;
+
+/// Geographic location of the observer.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Location {
+    /// Latitude in degrees (positive north).
+    pub latitude: f64,
+
+    /// Longitude in degrees (positive east).
+    pub longitude: f64,
+}
+
+/// Solar events.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Event {
+    Sunrise,
+    Sunset,
+}
+
+impl Location {
+    /// Returns the sunrise and sunset of a day in Unix time (seconds).
+    ///
+    /// The day is the number of days since the Unix epoch, and the times are those around the solar
+    /// noon of that date at this location. Returns `None` if the sun doesn't rise or set (polar day
+    /// or night).
+    pub fn sun(&self, day: i64) -> Option<(i64, i64)> {
+        let days = (day * DAY - J2000 + DAY / 2) / DAY;
+        let noon = days as f64 - self.longitude / 360.;
+        let anomaly = (357.5291 + 0.98560028 * noon) % 360.;
+        let m = anomaly.to_radians();
+        let center = 1.9148 * sin(m) + 0.0200 * sin(2. * m) + 0.0003 * sin(3. * m);
+        let ecliptic = ((anomaly + center + 180. + 102.9372) % 360.).to_radians();
+        let transit = noon + 0.0053 * sin(m) - 0.0069 * sin(2. * ecliptic);
+        let sin_declination = sin(ecliptic) * sin(23.4397f64.to_radians());
+        let declination = asin(sin_declination);
+        let latitude = self.latitude.to_radians();
+        let cos_hour = (sin((-0.833f64).to_radians()) - sin(latitude) * sin_declination)
+            / (cos(latitude) * cos(declination));
+        if !(-1. ..= 1.).contains(&cos_hour) {
+            return None;
+        }
+        let hour = acos(cos_hour).to_degrees() / 360.;
+        let time = |x: f64| J2000 + (x * DAY as f64) as i64;
+        Some((time(transit - hour), time(transit + hour)))
+    }
+
+    /// Returns the time of an event of a day in Unix time (seconds).
+    ///
+    /// See [`Self::sun()`] for details.
+    pub fn event(&self, event: Event, day: i64) -> Option<i64> {
+        let (sunrise, sunset) = self.sun(day)?;
+        Some(match event {
+            Event::Sunrise => sunrise,
+            Event::Sunset => sunset,
+        })
+    }
+
+    /// Returns the next time after a Unix time when an event shifted by an offset occurs.
+    ///
+    /// The offset is in seconds and may be negative (e.g. -1800 for 30 minutes before). Returns
+    /// `None` if the event doesn't occur within a year (near the poles).
+    pub fn next(&self, event: Event, offset: i64, unix_time: u64) -> Option<u64> {
+        let now = i64::try_from(unix_time).ok()?;
+        let today = now.div_euclid(DAY);
+        (today - 1 ..= today + 366)
+            .filter_map(|day| self.event(event, day))
+            .map(|time| time + offset)
+            .find(|&time| now < time)
+            .and_then(|time| u64::try_from(time).ok())
+    }
+}
+
+/// Starts a one-shot timer firing at the next event shifted by an offset.
+///
+/// See [`Location::next()`] for the arguments. Returns the Unix time at which the timer fires, or
+/// `None` (without starting the timer) if there is no such event or it is too far for the timer.
+pub fn schedule<H: Handler>(
+    timer: &Timer<H>, location: &Location, event: Event, offset: i64, unix_time: u64,
+) -> Option<u64> {
+    let time = location.next(event, offset, unix_time)?;
+    let duration_ms = usize::try_from((time - unix_time).checked_mul(1000)?).ok()?;
+    timer.start_ms(Oneshot, duration_ms);
+    Some(time)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LONDON: Location = Location { latitude: 51.5074, longitude: -0.1278 };
+
+    /// Days since the Unix epoch of 2023-06-21.
+    const SOLSTICE: i64 = 19529;
+
+    fn assert_near(actual: i64, expected: i64) {
+        assert!((actual - expected).abs() < 60, "{actual} is not near {expected}");
+    }
+
+    #[test]
+    fn london_summer_solstice() {
+        // Reference times are 04:43 and 21:21 BST (03:43 and 20:21 UTC).
+        let (sunrise, sunset) = LONDON.sun(SOLSTICE).unwrap();
+        assert_near(sunrise, SOLSTICE * DAY + 3 * 3600 + 43 * 60);
+        assert_near(sunset, SOLSTICE * DAY + 20 * 3600 + 21 * 60);
+    }
+
+    #[test]
+    fn mountain_view_sunset_next_utc_day() {
+        // Reference time is 20:33 PDT (03:33 UTC the next day).
+        let location = Location { latitude: 37.386, longitude: -122.084 };
+        let sunset = location.event(Event::Sunset, SOLSTICE).unwrap();
+        assert_near(sunset, (SOLSTICE + 1) * DAY + 3 * 3600 + 33 * 60);
+    }
+
+    #[test]
+    fn polar_day() {
+        let location = Location { latitude: 78.2232, longitude: 15.6267 };
+        assert_eq!(location.sun(SOLSTICE), None);
+        // The sun doesn't set for months, but it does within a year.
+        assert!(location.next(Event::Sunset, 0, (SOLSTICE * DAY) as u64).is_some());
+    }
+
+    #[test]
+    fn next_before_sunset() {
+        let sunset = LONDON.event(Event::Sunset, SOLSTICE).unwrap();
+        let before = (sunset - 1800) as u64;
+        // Just before the event, it is today's.
+        assert_eq!(LONDON.next(Event::Sunset, -1800, before - 1), Some(before));
+        // Just after the event, it is tomorrow's.
+        let next = LONDON.next(Event::Sunset, -1800, before).unwrap();
+        assert_near(next as i64, before as i64 + DAY);
+    }
+}
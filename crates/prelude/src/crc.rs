@@ -0,0 +1,150 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides CRC checksums.
+//!
+//! The CRC-32 is the one used by Ethernet, zlib, and PNG (reflected polynomial 0xedb88320). It is
+//! computed by the applet, either at once with [`crc32()`] or incrementally, similarly to hashing,
+//! when data is streamed (e.g. over USB serial):
+//!
+//! ```ignore
+//! let mut crc = crc::init();
+//! crc.update(b"1234");
+//! crc.update(b"56789");
+//! assert_eq!(crc.finalize(), crc::crc32(b"123456789"));
+//! ```
+//!
+//! The CRC-16/CCITT is computed by the platform, possibly in hardware.
+
+use wasefire_applet_api::crypto::crc as api;
+
+pub use crate::crypto::Error;
+
+/// Incremental CRC-32 context.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Crc32 {
+    /// The current (inverted) remainder.
+    state: u32,
+}
+
+impl Default for Crc32 {
+    fn default() -> Self {
+        init()
+    }
+}
+
+impl Crc32 {
+    /// Updates the checksum with the provided data.
+    pub fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.state = TABLE[(self.state as u8 ^ byte) as usize] ^ (self.state >> 8);
+        }
+    }
+
+    /// Returns the checksum of the data provided so far.
+    pub fn finalize(self) -> u32 {
+        !self.state
+    }
+}
+
+/// Creates a new CRC-32 context.
+pub fn init() -> Crc32 {
+    Crc32 { state: u32::MAX }
+}
+
+/// Returns the CRC-32 of the provided data.
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = init();
+    crc.update(data);
+    crc.finalize()
+}
+
+/// Whether the CRC-16/CCITT is supported by the platform.
+pub fn is_supported() -> bool {
+    let api::is_supported::Results { supported } = unsafe { api::is_supported() };
+    supported != 0
+}
+
+/// Returns the CRC-16/CCITT of the provided data.
+///
+/// This is the CRC-16 with polynomial 0x1021, initial value 0xffff, and no final xor (also known
+/// as CRC-16/CCITT-FALSE).
+pub fn crc16_ccitt(data: &[u8]) -> Result<u16, Error> {
+    let mut crc = [0; 2];
+    let params =
+        api::crc16_ccitt::Params { data: data.as_ptr(), length: data.len(), crc: crc.as_mut_ptr() };
+    let api::crc16_ccitt::Results { res } = unsafe { api::crc16_ccitt(params) };
+    Error::to_result(res)?;
+    Ok(u16::from_le_bytes(crc))
+}
+
+const POLYNOMIAL: u32 = 0xedb88320;
+
+const TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut x = i as u32;
+        let mut j = 0;
+        while j < 8 {
+            x = if x & 1 == 1 { (x >> 1) ^ POLYNOMIAL } else { x >> 1 };
+            j += 1;
+        }
+        table[i] = x;
+        i += 1;
+    }
+    table
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_value() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xcbf43926);
+        assert_eq!(crc32(b"The quick brown fox jumps over the lazy dog"), 0x414fa339);
+    }
+
+    #[test]
+    fn table_matches_bitwise() {
+        let bitwise = |data: &[u8]| {
+            let mut crc = u32::MAX;
+            for &byte in data {
+                crc ^= byte as u32;
+                for _ in 0 .. 8 {
+                    crc = if crc & 1 == 1 { (crc >> 1) ^ POLYNOMIAL } else { crc >> 1 };
+                }
+            }
+            !crc
+        };
+        let data: alloc::vec::Vec<u8> = (0 ..= 255).collect();
+        for len in [0, 1, 2, 100, 256] {
+            assert_eq!(crc32(&data[.. len]), bitwise(&data[.. len]));
+        }
+    }
+
+    #[test]
+    fn incremental_matches_one_shot() {
+        let data: alloc::vec::Vec<u8> = (0 .. 1000).map(|x| (x * 7 + x / 13) as u8).collect();
+        for chunk_size in [1, 3, 64, 999, 1000] {
+            let mut crc = init();
+            for chunk in data.chunks(chunk_size) {
+                crc.update(chunk);
+            }
+            assert_eq!(crc.finalize(), crc32(&data));
+        }
+    }
+}
@@ -14,9 +14,61 @@
 
 //! Provides API for cryptography.
 
+#[cfg(not(feature = "no-alloc"))]
 pub mod ccm;
+#[cfg(not(feature = "no-alloc"))]
+pub mod channel;
+pub mod ecdh;
+#[cfg(not(feature = "no-alloc"))]
 pub mod gcm;
 pub mod hash;
+#[cfg(not(feature = "no-alloc"))]
+pub mod hkdf;
+#[cfg(not(feature = "no-alloc"))]
+pub mod hmac;
+#[cfg(not(feature = "no-alloc"))]
+pub mod otp;
+pub mod zeroize;
 
+#[cfg(not(feature = "no-alloc"))]
+pub use hkdf::hkdf_sha256;
 /// Errors returned by cryptographic operations.
 pub use wasefire_applet_api::crypto::Error;
+pub use zeroize::{zeroize, Zeroizing};
+
+/// Compares two byte slices in constant time.
+///
+/// This should be used instead of `==` to compare secrets (like MACs or PINs) to avoid leaking
+/// their content through timing. The comparison does not return early: its time only depends on
+/// the length of the slices. Slices of different lengths are not equal and the function returns
+/// immediately in that case.
+pub fn ct_eq(x: &[u8], y: &[u8]) -> bool {
+    if x.len() != y.len() {
+        return false;
+    }
+    let diff = x.iter().zip(y).fold(0, |diff, (x, y)| diff | (x ^ y));
+    core::hint::black_box(diff) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ct_eq_ok() {
+        assert!(ct_eq(b"", b""));
+        assert!(ct_eq(b"a", b"a"));
+        assert!(ct_eq(&[0x5a; 32], &[0x5a; 32]));
+        assert!(!ct_eq(b"a", b"b"));
+        assert!(!ct_eq(b"a", b""));
+        assert!(!ct_eq(b"ab", b"a"));
+        assert!(!ct_eq(&[0; 32], &[0xff; 32]));
+        for i in 0 .. 32 {
+            for bit in 0 .. 8 {
+                let mut y = [0x5a; 32];
+                y[i] ^= 1 << bit;
+                assert!(!ct_eq(&[0x5a; 32], &y));
+            }
+        }
+    }
+}
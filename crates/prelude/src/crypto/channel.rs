@@ -0,0 +1,160 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides an authenticated channel with replay protection.
+//!
+//! Messages are authenticated with HMAC-SHA-256 and carry a monotonic counter. A message is
+//! encoded as the counter (8 bytes in big-endian), followed by the payload, followed by the tag
+//! (32 bytes). The tag authenticates both the counter and the payload.
+//!
+//! # Counter window
+//!
+//! The sender increments its counter before sealing each message. The receiver only accepts a
+//! message if its counter is strictly greater than the counter of the last accepted message.
+//! Counters may skip values (for example when messages are lost), but a replayed message or a
+//! message older than the last accepted one is rejected. Counters are persisted before a message
+//! is returned (by [`Channel::seal()`] or [`Channel::open()`]) such that the window survives
+//! reboots.
+//!
+//! A channel is used in a single direction. Bidirectional communication needs one channel per
+//! direction, each with its own counter (and ideally its own key).
+
+use alloc::vec::Vec;
+
+use super::ct_eq;
+use super::hmac::hmac_sha256;
+use crate::store;
+
+const COUNTER_LEN: usize = 8;
+const TAG_LEN: usize = 32;
+
+/// Errors returned by channel operations.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// A cryptographic operation failed.
+    Crypto(super::Error),
+
+    /// A counter operation failed.
+    Counter(store::Error),
+
+    /// The message is too short or its tag is invalid.
+    Invalid,
+
+    /// The message counter is not greater than the last accepted counter.
+    Replayed,
+
+    /// The counter cannot be incremented anymore.
+    Exhausted,
+}
+
+impl From<super::Error> for Error {
+    fn from(error: super::Error) -> Self {
+        Error::Crypto(error)
+    }
+}
+
+impl From<store::Error> for Error {
+    fn from(error: store::Error) -> Self {
+        Error::Counter(error)
+    }
+}
+
+/// Non-volatile monotonic counter.
+pub trait Counter {
+    /// Returns the current value of the counter.
+    fn get(&mut self) -> Result<u64, store::Error>;
+
+    /// Persists a new value for the counter.
+    ///
+    /// The value is always greater than the current value.
+    fn set(&mut self, value: u64) -> Result<(), store::Error>;
+}
+
+/// Counter persisted in the store.
+///
+/// The counter is zero until the first time it is set.
+pub struct StoreCounter {
+    key: usize,
+}
+
+impl StoreCounter {
+    /// Creates a counter persisted under a store key.
+    ///
+    /// The key must not be used for anything else.
+    pub fn new(key: usize) -> Self {
+        StoreCounter { key }
+    }
+}
+
+impl Counter for StoreCounter {
+    fn get(&mut self) -> Result<u64, store::Error> {
+        match store::find(self.key)? {
+            None => Ok(0),
+            Some(value) => match <[u8; COUNTER_LEN]>::try_from(&value[..]) {
+                Ok(value) => Ok(u64::from_be_bytes(value)),
+                Err(_) => Err(store::Error::InvalidArgument),
+            },
+        }
+    }
+
+    fn set(&mut self, value: u64) -> Result<(), store::Error> {
+        store::insert(self.key, &value.to_be_bytes())
+    }
+}
+
+/// Authenticated channel with replay protection.
+pub struct Channel<C: Counter> {
+    key: Vec<u8>,
+    counter: C,
+}
+
+impl<C: Counter> Channel<C> {
+    /// Creates a channel from a shared key and a counter.
+    pub fn new(key: &[u8], counter: C) -> Self {
+        Channel { key: key.to_vec(), counter }
+    }
+
+    /// Authenticates a payload and returns the message to send.
+    pub fn seal(&mut self, payload: &[u8]) -> Result<Vec<u8>, Error> {
+        let counter = self.counter.get()?.checked_add(1).ok_or(Error::Exhausted)?;
+        self.counter.set(counter)?;
+        let mut message = Vec::with_capacity(COUNTER_LEN + payload.len() + TAG_LEN);
+        message.extend_from_slice(&counter.to_be_bytes());
+        message.extend_from_slice(payload);
+        let tag = hmac_sha256(&self.key, &message)?;
+        message.extend_from_slice(&tag);
+        Ok(message)
+    }
+
+    /// Verifies a received message and returns its payload.
+    ///
+    /// The message is rejected if its tag is invalid or if it was replayed (see the [module
+    /// documentation](self) for the counter window).
+    pub fn open<'a>(&mut self, message: &'a [u8]) -> Result<&'a [u8], Error> {
+        if message.len() < COUNTER_LEN + TAG_LEN {
+            return Err(Error::Invalid);
+        }
+        let (data, tag) = message.split_at(message.len() - TAG_LEN);
+        if !ct_eq(&hmac_sha256(&self.key, data)?, tag) {
+            return Err(Error::Invalid);
+        }
+        let (counter, payload) = data.split_at(COUNTER_LEN);
+        let counter = u64::from_be_bytes(counter.try_into().unwrap());
+        if counter <= self.counter.get()? {
+            return Err(Error::Replayed);
+        }
+        self.counter.set(counter)?;
+        Ok(payload)
+    }
+}
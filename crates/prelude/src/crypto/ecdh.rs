@@ -0,0 +1,63 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides ECDH over P-256.
+//!
+//! This is used for example to derive the shared secret of the CTAP PIN protocol.
+
+use wasefire_applet_api::crypto::ecdh as api;
+
+use super::Error;
+
+/// ECDH key pair.
+pub struct KeyPair {
+    /// The big-endian private scalar.
+    pub private: [u8; 32],
+
+    /// The uncompressed public point (big-endian X then Y coordinates).
+    pub public: [u8; 64],
+}
+
+/// Whether ECDH over P-256 is supported.
+pub fn is_supported() -> bool {
+    let api::is_supported::Results { supported } = unsafe { api::is_supported() };
+    supported != 0
+}
+
+/// Generates a random key pair.
+pub fn generate_keypair() -> Result<KeyPair, Error> {
+    let mut keypair = KeyPair { private: [0; 32], public: [0; 64] };
+    let params = api::generate_keypair::Params {
+        private: keypair.private.as_mut_ptr(),
+        public: keypair.public.as_mut_ptr(),
+    };
+    let api::generate_keypair::Results { res } = unsafe { api::generate_keypair(params) };
+    Error::to_result(res)?;
+    Ok(keypair)
+}
+
+/// Returns the shared secret of a private key and a peer public key.
+///
+/// The shared secret is the X coordinate of the product point.
+pub fn agree(private: &[u8; 32], public: &[u8; 64]) -> Result<[u8; 32], Error> {
+    let mut shared = [0; 32];
+    let params = api::agree::Params {
+        private: private.as_ptr(),
+        public: public.as_ptr(),
+        shared: shared.as_mut_ptr(),
+    };
+    let api::agree::Results { res } = unsafe { api::agree(params) };
+    Error::to_result(res)?;
+    Ok(shared)
+}
@@ -0,0 +1,131 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides HKDF over HMAC-SHA-256.
+//!
+//! This follows [RFC 5869] and is implemented on top of [HMAC](super::hmac).
+//!
+//! [RFC 5869]: https://www.rfc-editor.org/rfc/rfc5869
+
+use super::hash::Algorithm;
+use super::hmac::Hmac;
+use super::Error;
+
+/// Maximum output length of HKDF-SHA-256.
+pub const MAX_OKM_LEN: usize = 255 * 32;
+
+/// Derives key material from a shared secret with HKDF-SHA-256.
+///
+/// The salt may be empty, in which case it is a string of zeros. The output key material `okm` must
+/// not be longer than [`MAX_OKM_LEN`].
+pub fn hkdf_sha256(salt: &[u8], ikm: &[u8], info: &[u8], okm: &mut [u8]) -> Result<(), Error> {
+    hkdf(hmac_sha256, salt, ikm, info, okm)
+}
+
+/// Computes HMAC-SHA-256 over the concatenation of the given data.
+fn hmac_sha256(key: &[u8], data: &[&[u8]], mac: &mut [u8; 32]) -> Result<(), Error> {
+    let mut context = Hmac::new(Algorithm::Sha256, key)?;
+    data.iter().for_each(|x| context.update(x));
+    context.finalize(mac)
+}
+
+fn hkdf(
+    mut hmac: impl FnMut(&[u8], &[&[u8]], &mut [u8; 32]) -> Result<(), Error>, salt: &[u8],
+    ikm: &[u8], info: &[u8], okm: &mut [u8],
+) -> Result<(), Error> {
+    if okm.len() > MAX_OKM_LEN {
+        return Err(Error::InvalidArgument);
+    }
+    // Extract.
+    let mut prk = [0; 32];
+    hmac(if salt.is_empty() { &[0; 32] } else { salt }, &[ikm], &mut prk)?;
+    // Expand.
+    let mut block = [0; 32];
+    for (i, chunk) in okm.chunks_mut(32).enumerate() {
+        let previous: &[u8] = if i == 0 { &[] } else { &block };
+        let counter = [i as u8 + 1];
+        let mut next = [0; 32];
+        hmac(&prk, &[previous, info, &counter], &mut next)?;
+        block = next;
+        chunk.copy_from_slice(&block[.. chunk.len()]);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use hmac::Mac;
+
+    use super::*;
+
+    fn hex(x: &str) -> Vec<u8> {
+        (0 .. x.len()).step_by(2).map(|i| u8::from_str_radix(&x[i .. i + 2], 16).unwrap()).collect()
+    }
+
+    fn hmac_sha256(key: &[u8], data: &[&[u8]], mac: &mut [u8; 32]) -> Result<(), Error> {
+        let mut context = hmac::Hmac::<sha2::Sha256>::new_from_slice(key).unwrap();
+        data.iter().for_each(|x| context.update(x));
+        mac.copy_from_slice(&context.finalize().into_bytes());
+        Ok(())
+    }
+
+    #[test]
+    fn rfc5869() {
+        // Test cases 1 to 3 from RFC 5869 appendix A.
+        const VECTORS: &[(&str, &str, &str, &str)] = &[
+            (
+                "0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b",
+                "000102030405060708090a0b0c",
+                "f0f1f2f3f4f5f6f7f8f9",
+                "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865",
+            ),
+            (
+                "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f\
+                 202122232425262728292a2b2c2d2e2f303132333435363738393a3b3c3d3e3f\
+                 404142434445464748494a4b4c4d4e4f",
+                "606162636465666768696a6b6c6d6e6f707172737475767778797a7b7c7d7e7f\
+                 808182838485868788898a8b8c8d8e8f909192939495969798999a9b9c9d9e9f\
+                 a0a1a2a3a4a5a6a7a8a9aaabacadaeaf",
+                "b0b1b2b3b4b5b6b7b8b9babbbcbdbebfc0c1c2c3c4c5c6c7c8c9cacbcccdcecf\
+                 d0d1d2d3d4d5d6d7d8d9dadbdcdddedfe0e1e2e3e4e5e6e7e8e9eaebecedeeef\
+                 f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff",
+                "b11e398dc80327a1c8e7f78c596a49344f012eda2d4efad8a050cc4c19afa97c\
+                 59045a99cac7827271cb41c65e590e09da3275600c2f09b8367793a9aca3db71\
+                 cc30c58179ec3e87c14c01d5c1f3434f1d87",
+            ),
+            (
+                "0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b",
+                "",
+                "",
+                "8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d9d201395faa4b61a96c8",
+            ),
+        ];
+        for &(ikm, salt, info, expected) in VECTORS {
+            let expected = hex(expected);
+            let mut okm = vec![0; expected.len()];
+            hkdf(hmac_sha256, &hex(salt), &hex(ikm), &hex(info), &mut okm).unwrap();
+            assert_eq!(okm, expected);
+        }
+    }
+
+    #[test]
+    fn okm_too_long() {
+        let mut okm = vec![0; MAX_OKM_LEN + 1];
+        assert_eq!(hkdf(hmac_sha256, &[], &[], &[], &mut okm), Err(Error::InvalidArgument));
+        assert_eq!(hkdf(hmac_sha256, &[], &[], &[], &mut okm[.. MAX_OKM_LEN]), Ok(()));
+    }
+}
@@ -0,0 +1,81 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides HMAC over the supported hash functions.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::hash::{Algorithm, Digest};
+use super::Error;
+
+/// HMAC context.
+pub struct Hmac {
+    algorithm: Algorithm,
+
+    /// The inner hashing context (already updated with the inner padded key).
+    inner: Digest,
+
+    /// The outer padded key.
+    outer: Vec<u8>,
+}
+
+impl Hmac {
+    /// Creates a new HMAC context for the specified algorithm and key.
+    pub fn new(algorithm: Algorithm, key: &[u8]) -> Result<Self, Error> {
+        let mut block = vec![0; algorithm.block_len()];
+        if key.len() > block.len() {
+            Digest::digest(algorithm, key, &mut block[.. algorithm.digest_len()])?;
+        } else {
+            block[.. key.len()].copy_from_slice(key);
+        }
+        block.iter_mut().for_each(|x| *x ^= 0x36);
+        let mut inner = Digest::new(algorithm)?;
+        inner.update(&block);
+        block.iter_mut().for_each(|x| *x ^= 0x36 ^ 0x5c);
+        Ok(Self { algorithm, inner, outer: block })
+    }
+
+    /// Updates the HMAC context with the provided data.
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    /// Finalizes the HMAC context and writes the associated MAC.
+    ///
+    /// The MAC length must be the digest length of the algorithm.
+    pub fn finalize(self, mac: &mut [u8]) -> Result<(), Error> {
+        let Hmac { algorithm, inner, outer } = self;
+        let mut digest = vec![0; algorithm.digest_len()];
+        inner.finalize(&mut digest)?;
+        let mut outer_context = Digest::new(algorithm)?;
+        outer_context.update(&outer);
+        outer_context.update(&digest);
+        outer_context.finalize(mac)
+    }
+
+    /// Writes the HMAC of the data for the given algorithm and key in the MAC.
+    pub fn mac(algorithm: Algorithm, key: &[u8], data: &[u8], mac: &mut [u8]) -> Result<(), Error> {
+        let mut context = Self::new(algorithm, key)?;
+        context.update(data);
+        context.finalize(mac)
+    }
+}
+
+/// Returns the HMAC-SHA-256 of the provided data.
+pub fn hmac_sha256(key: &[u8], data: &[u8]) -> Result<[u8; 32], Error> {
+    let mut mac = [0; 32];
+    Hmac::mac(Algorithm::Sha256, key, data, &mut mac)?;
+    Ok(mac)
+}
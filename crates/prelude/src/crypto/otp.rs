@@ -0,0 +1,314 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides one-time passwords (HOTP and TOTP).
+//!
+//! Codes follow [RFC 4226] (HOTP) and [RFC 6238] (TOTP) over the supported HMAC algorithms. Note
+//! that the platform doesn't provide wall-clock time: the Unix time must be provided by the caller
+//! (for example from the host or a synchronized counter).
+//!
+//! [RFC 4226]: https://www.rfc-editor.org/rfc/rfc4226
+//! [RFC 6238]: https://www.rfc-editor.org/rfc/rfc6238
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::hmac::Hmac;
+use super::{hash, zeroize, Error};
+
+/// HMAC algorithms of one-time passwords.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Algorithm {
+    /// HMAC-SHA-1, the algorithm of most authenticators.
+    ///
+    /// The platform doesn't provide SHA-1, so it is computed by the applet.
+    #[default]
+    Sha1,
+
+    /// HMAC-SHA-256.
+    Sha256,
+}
+
+impl Algorithm {
+    /// Returns the MAC length in bytes.
+    pub fn mac_len(self) -> usize {
+        match self {
+            Algorithm::Sha1 => 20,
+            Algorithm::Sha256 => hash::Algorithm::Sha256.digest_len(),
+        }
+    }
+
+    fn mac(self, key: &[u8], data: &[u8], mac: &mut [u8]) -> Result<(), Error> {
+        match self {
+            Algorithm::Sha1 => hmac_sha1(key, data, mac),
+            Algorithm::Sha256 => Hmac::mac(hash::Algorithm::Sha256, key, data, mac),
+        }
+    }
+}
+
+/// One-time password parameters.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Otp {
+    /// The HMAC algorithm.
+    pub algorithm: Algorithm,
+
+    /// The number of digits of the codes.
+    ///
+    /// This must be between 6 and 8 (inclusive).
+    pub digits: u32,
+}
+
+impl Default for Otp {
+    fn default() -> Self {
+        Otp { algorithm: Algorithm::Sha1, digits: 6 }
+    }
+}
+
+impl Otp {
+    /// Returns the HOTP code of a secret for a given counter.
+    pub fn hotp(&self, secret: &[u8], counter: u64) -> Result<u32, Error> {
+        self.code(|data, mac| self.algorithm.mac(secret, data, mac), counter)
+    }
+
+    /// Returns the TOTP code of a secret for a given Unix time and time step (in seconds).
+    pub fn totp(&self, secret: &[u8], unix_time: u64, period: u64) -> Result<u32, Error> {
+        self.hotp(secret, time_step(unix_time, period)?)
+    }
+
+    /// Returns the HOTP code for a counter given the keyed HMAC function.
+    fn code(
+        &self, hmac: impl FnOnce(&[u8], &mut [u8]) -> Result<(), Error>, counter: u64,
+    ) -> Result<u32, Error> {
+        if !(6 ..= 8).contains(&self.digits) {
+            return Err(Error::InvalidArgument);
+        }
+        let mut mac = vec![0; self.algorithm.mac_len()];
+        hmac(&counter.to_be_bytes(), &mut mac)?;
+        Ok(truncate(&mac, self.digits))
+    }
+}
+
+/// Returns the 6-digit HOTP code of a secret for a given counter using HMAC-SHA-1.
+///
+/// See [`Otp::hotp()`] to configure the algorithm and number of digits.
+pub fn hotp(secret: &[u8], counter: u64) -> Result<u32, Error> {
+    Otp::default().hotp(secret, counter)
+}
+
+/// Returns the 6-digit TOTP code of a secret for a given Unix time and period using HMAC-SHA-1.
+///
+/// The period is the time step in seconds (usually 30). See [`Otp::totp()`] to configure the
+/// algorithm and number of digits.
+pub fn totp(secret: &[u8], unix_time: u64, period: u64) -> Result<u32, Error> {
+    Otp::default().totp(secret, unix_time, period)
+}
+
+/// Returns the TOTP counter of a Unix time for a given period in seconds.
+fn time_step(unix_time: u64, period: u64) -> Result<u64, Error> {
+    unix_time.checked_div(period).ok_or(Error::InvalidArgument)
+}
+
+/// Dynamically truncates a MAC to a code with a given number of digits.
+fn truncate(mac: &[u8], digits: u32) -> u32 {
+    let offset = (mac[mac.len() - 1] & 0xf) as usize;
+    let code = u32::from_be_bytes(mac[offset .. offset + 4].try_into().unwrap()) & 0x7fffffff;
+    code % 10u32.pow(digits)
+}
+
+/// Writes the HMAC-SHA-1 of the data for the given key in the MAC.
+fn hmac_sha1(key: &[u8], data: &[u8], mac: &mut [u8]) -> Result<(), Error> {
+    if mac.len() != 20 {
+        return Err(Error::InvalidArgument);
+    }
+    let mut block = [0; 64];
+    if key.len() > block.len() {
+        block[.. 20].copy_from_slice(&sha1(&[key]));
+    } else {
+        block[.. key.len()].copy_from_slice(key);
+    }
+    let mut pad = block.map(|x| x ^ 0x36);
+    let inner = sha1(&[&pad, data]);
+    pad = block.map(|x| x ^ 0x5c);
+    mac.copy_from_slice(&sha1(&[&pad, &inner]));
+    zeroize(&mut block);
+    zeroize(&mut pad);
+    Ok(())
+}
+
+/// Returns the SHA-1 of the concatenation of some data.
+///
+/// This is only meant for HMAC-SHA-1, which doesn't rely on the collision resistance of SHA-1.
+fn sha1(data: &[&[u8]]) -> [u8; 20] {
+    let mut message: Vec<u8> = data.concat();
+    let bit_len = message.len() as u64 * 8;
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&bit_len.to_be_bytes());
+    let mut state = [0x67452301u32, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+    for block in message.chunks(64) {
+        let mut w = [0u32; 80];
+        for (w, word) in w.iter_mut().zip(block.chunks(4)) {
+            *w = u32::from_be_bytes(word.try_into().unwrap());
+        }
+        for i in 16 .. 80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e] = state;
+        for (i, w) in w.into_iter().enumerate() {
+            let (f, k) = match i / 20 {
+                0 => ((b & c) | (!b & d), 0x5a827999),
+                1 => (b ^ c ^ d, 0x6ed9eba1),
+                2 => ((b & c) | (b & d) | (c & d), 0x8f1bbcdc),
+                _ => (b ^ c ^ d, 0xca62c1d6),
+            };
+            let t = a.rotate_left(5).wrapping_add(f).wrapping_add(e).wrapping_add(k);
+            (e, d, c, b, a) = (d, c, b.rotate_left(30), a, t.wrapping_add(w));
+        }
+        for (x, y) in state.iter_mut().zip([a, b, c, d, e]) {
+            *x = x.wrapping_add(y);
+        }
+    }
+    zeroize(&mut message);
+    let mut digest = [0; 20];
+    for (chunk, x) in digest.chunks_mut(4).zip(state) {
+        chunk.copy_from_slice(&x.to_be_bytes());
+    }
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(x: &str) -> alloc::vec::Vec<u8> {
+        (0 .. x.len()).step_by(2).map(|i| u8::from_str_radix(&x[i .. i + 2], 16).unwrap()).collect()
+    }
+
+    #[test]
+    fn truncate_rfc4226() {
+        // HMAC-SHA-1 values and codes from RFC 4226 appendix D.
+        const VECTORS: &[(&str, u32)] = &[
+            ("cc93cf18508d94934c64b65d8ba7667fb7cde4b0", 755224),
+            ("75a48a19d4cbe100644e8ac1397eea747a2d33ab", 287082),
+            ("0bacb7fa082fef30782211938bc1c5e70416ff44", 359152),
+            ("66c28227d03a2d5529262ff016a1e6ef76557ece", 969429),
+            ("a904c900a64b35909874b33e61c5938a8e15ed1c", 338314),
+            ("a37e783d7b7233c083d4f62926c7a25f238d0316", 254676),
+            ("bc9cd28561042c83f219324d3c607256c03272ae", 287922),
+            ("a4fb960c0bc06e1eabb804e5b397cdc4b45596fa", 162583),
+            ("1b3c89f65e6c9e883012052823443f048b4332db", 399871),
+            ("1637409809a679dc698207310c8c7fc07290d9e5", 520489),
+        ];
+        for &(mac, code) in VECTORS {
+            assert_eq!(truncate(&hex(mac), 6), code);
+        }
+        // The full truncated value of count 0 is 1284755224.
+        assert_eq!(truncate(&hex(VECTORS[0].0), 8), 84755224);
+    }
+
+    #[test]
+    fn sha1_fips180() {
+        // Examples from FIPS 180-2 appendix A, the last one needing an extra padding block.
+        const VECTORS: &[(&[u8], &str)] = &[
+            (b"", "da39a3ee5e6b4b0d3255bfef95601890afd80709"),
+            (b"abc", "a9993e364706816aba3e25717850c26c9cd0d89d"),
+            (
+                b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+                "84983e441c3bd26ebaae4aa1f95129e5e54670f1",
+            ),
+        ];
+        for &(data, digest) in VECTORS {
+            assert_eq!(sha1(&[data]).to_vec(), hex(digest));
+        }
+    }
+
+    #[test]
+    fn hotp_rfc4226() {
+        // HMAC-SHA-1 values and codes from RFC 4226 appendix D.
+        const SECRET: &[u8] = b"12345678901234567890";
+        const VECTORS: &[(&str, u32)] = &[
+            ("cc93cf18508d94934c64b65d8ba7667fb7cde4b0", 755224),
+            ("75a48a19d4cbe100644e8ac1397eea747a2d33ab", 287082),
+            ("0bacb7fa082fef30782211938bc1c5e70416ff44", 359152),
+            ("66c28227d03a2d5529262ff016a1e6ef76557ece", 969429),
+            ("a904c900a64b35909874b33e61c5938a8e15ed1c", 338314),
+            ("a37e783d7b7233c083d4f62926c7a25f238d0316", 254676),
+            ("bc9cd28561042c83f219324d3c607256c03272ae", 287922),
+            ("a4fb960c0bc06e1eabb804e5b397cdc4b45596fa", 162583),
+            ("1b3c89f65e6c9e883012052823443f048b4332db", 399871),
+            ("1637409809a679dc698207310c8c7fc07290d9e5", 520489),
+        ];
+        for (counter, &(expected, code)) in VECTORS.iter().enumerate() {
+            let mut mac = [0; 20];
+            hmac_sha1(SECRET, &(counter as u64).to_be_bytes(), &mut mac).unwrap();
+            assert_eq!(mac.to_vec(), hex(expected));
+            assert_eq!(hotp(SECRET, counter as u64), Ok(code));
+        }
+    }
+
+    #[test]
+    fn totp_rfc6238() {
+        use hmac::Mac;
+
+        // Times and 8-digit codes from RFC 6238 appendix B (SHA-1 and SHA-256).
+        const VECTORS: &[(u64, u32, u32)] = &[
+            (59, 94287082, 46119246),
+            (1111111109, 7081804, 68084774),
+            (1111111111, 14050471, 67062674),
+            (1234567890, 89005924, 91819424),
+            (2000000000, 69279037, 90698825),
+            (20000000000, 65353130, 77737706),
+        ];
+        const SECRET: &[u8] = b"12345678901234567890123456789012";
+        let sha1 = Otp { algorithm: Algorithm::Sha1, digits: 8 };
+        let sha256 = Otp { algorithm: Algorithm::Sha256, digits: 8 };
+        // The platform computes HMAC-SHA-256, so the test uses a native implementation.
+        let hmac_sha256 = |data: &[u8], mac: &mut [u8]| {
+            let mut context = hmac::Hmac::<sha2::Sha256>::new_from_slice(SECRET).unwrap();
+            context.update(data);
+            mac.copy_from_slice(&context.finalize().into_bytes());
+            Ok(())
+        };
+        for &(time, code_sha1, code_sha256) in VECTORS {
+            assert_eq!(sha1.totp(&SECRET[.. 20], time, 30), Ok(code_sha1));
+            let counter = time_step(time, 30).unwrap();
+            assert_eq!(sha256.code(hmac_sha256, counter), Ok(code_sha256));
+        }
+    }
+
+    #[test]
+    fn invalid_digits() {
+        let otp = Otp { digits: 9, ..Otp::default() };
+        assert_eq!(otp.hotp(b"secret", 0), Err(Error::InvalidArgument));
+    }
+
+    #[test]
+    fn time_step_rfc6238() {
+        // Times and counters from RFC 6238 appendix B.
+        const VECTORS: &[(u64, u64)] = &[
+            (59, 0x1),
+            (1111111109, 0x23523ec),
+            (1111111111, 0x23523ed),
+            (1234567890, 0x273ef07),
+            (2000000000, 0x3f940aa),
+            (20000000000, 0x27bc86aa),
+        ];
+        for &(time, counter) in VECTORS {
+            assert_eq!(time_step(time, 30), Ok(counter));
+        }
+        assert_eq!(time_step(59, 0), Err(Error::InvalidArgument));
+    }
+}
@@ -0,0 +1,74 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides zeroization of sensitive buffers.
+//!
+//! Dropping a buffer (like a `Box` or `Vec`) doesn't wipe its memory. Buffers holding secrets (like
+//! key material) should be zeroized before being dropped, either explicitly with [`zeroize()`] or
+//! automatically by wrapping them in [`Zeroizing`].
+
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{compiler_fence, Ordering};
+
+/// Zeroizes a buffer.
+///
+/// The writes are volatile such that they are not elided by the optimizer, even if the buffer is
+/// not read afterwards.
+pub fn zeroize(buf: &mut [u8]) {
+    for x in buf.iter_mut() {
+        // SAFETY: The pointer comes from a mutable reference and is thus valid and aligned.
+        unsafe { core::ptr::write_volatile(x, 0) };
+    }
+    // Make sure the writes are not reordered with subsequent operations (like deallocation).
+    compiler_fence(Ordering::SeqCst);
+}
+
+/// Wrapper zeroizing its buffer when dropped.
+///
+/// The wrapper dereferences to the buffer, so it can be used in place of the buffer.
+#[derive(Debug, Default)]
+pub struct Zeroizing<T: AsMut<[u8]>>(T);
+
+impl<T: AsMut<[u8]>> Zeroizing<T> {
+    /// Wraps a buffer to zeroize it when dropped.
+    pub fn new(buf: T) -> Self {
+        Zeroizing(buf)
+    }
+}
+
+impl<T: AsMut<[u8]>> From<T> for Zeroizing<T> {
+    fn from(buf: T) -> Self {
+        Zeroizing::new(buf)
+    }
+}
+
+impl<T: AsMut<[u8]>> Deref for Zeroizing<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: AsMut<[u8]>> DerefMut for Zeroizing<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: AsMut<[u8]>> Drop for Zeroizing<T> {
+    fn drop(&mut self) {
+        zeroize(self.0.as_mut());
+    }
+}
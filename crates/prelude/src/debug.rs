@@ -18,8 +18,12 @@
 // size (and other performance) doesn't matter. This permits to have a simple debugging that is
 // completely excluded from release applets.
 
+use core::fmt::Write;
+
 use wasefire_applet_api::debug as api;
 
+pub use self::api::Level;
+
 /// Prints a line to the debug output.
 pub fn println(msg: &str) {
     if ENABLED {
@@ -37,7 +41,7 @@ pub const ENABLED: bool = option_env!("FIRWASM_DEBUG").is_some();
 macro_rules! println {
     ($($args:tt)*) => {
         if $crate::debug::ENABLED {
-            $crate::debug::println(&alloc::format!($($args)*));
+            $crate::debug::println(&$crate::__format!($($args)*));
         }
     };
 }
@@ -49,11 +53,140 @@ macro_rules! println {
 macro_rules! debug {
     ($($args:tt)*) => {
         if $crate::debug::ENABLED {
-            $crate::debug::println(&alloc::format!($($args)*));
+            $crate::debug::println(&$crate::__format!($($args)*));
         }
     };
 }
 
+#[cfg(not(feature = "no-alloc"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __format {
+    ($($args:tt)*) => {
+        alloc::format!($($args)*)
+    };
+}
+
+#[cfg(feature = "no-alloc")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __format {
+    ($($args:tt)*) => {
+        $crate::debug::StackString::format(format_args!($($args)*))
+    };
+}
+
+/// Formatted string on the stack.
+///
+/// This is used by the formatting macros without allocator. The string is truncated (on a
+/// character boundary) to [`StackString::CAPACITY`] bytes.
+#[doc(hidden)]
+pub struct StackString {
+    buffer: [u8; Self::CAPACITY],
+    len: usize,
+}
+
+impl StackString {
+    pub const CAPACITY: usize = 256;
+
+    pub fn format(args: core::fmt::Arguments) -> Self {
+        let mut result = StackString { buffer: [0; Self::CAPACITY], len: 0 };
+        let _ = result.write_fmt(args);
+        result
+    }
+}
+
+impl Write for StackString {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let mut len = core::cmp::min(s.len(), Self::CAPACITY - self.len);
+        while !s.is_char_boundary(len) {
+            len -= 1;
+        }
+        self.buffer[self.len ..][.. len].copy_from_slice(&s.as_bytes()[.. len]);
+        self.len += len;
+        Ok(())
+    }
+}
+
+impl core::ops::Deref for StackString {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        core::str::from_utf8(&self.buffer[.. self.len]).unwrap()
+    }
+}
+
+/// Logs a message at a given level in the platform logs.
+///
+/// Contrary to [`println()`], the message interleaves with the platform logs and is filtered by the
+/// platform log level (e.g. `RUST_LOG` or `DEFMT_LOG`) instead of being excluded from release
+/// applets.
+pub fn log(level: Level, msg: &str) {
+    let params = api::log::Params { level: level as usize, ptr: msg.as_ptr(), len: msg.len() };
+    unsafe { api::log(params) };
+}
+
+/// Logs a line at a given level in the platform logs.
+///
+/// This is similar to [`debug!()`] and supports the same formatting, but see [`log()`].
+///
+/// ```ignore
+/// log!(Level::Warn, "battery at {percent}%");
+/// ```
+#[macro_export]
+macro_rules! log {
+    ($level:expr, $($args:tt)*) => {
+        $crate::debug::log($level, &$crate::__format!($($args)*))
+    };
+}
+
+/// Heap statistics of the platform in bytes.
+///
+/// This is the heap of the platform, not the one of the applet.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct HeapStats {
+    /// Bytes currently allocated.
+    pub used: usize,
+
+    /// Bytes currently available.
+    pub free: usize,
+
+    /// Maximum number of bytes allocated at once since boot.
+    pub high_water: usize,
+}
+
+/// Returns the heap statistics of the platform, if supported.
+///
+/// This is useful to log memory pressure, for example over USB serial.
+pub fn heap_stats() -> Option<HeapStats> {
+    let mut stats = [0u8; 12];
+    let params = api::heap_stats::Params { ptr: stats.as_mut_ptr() };
+    let api::heap_stats::Results { res } = unsafe { api::heap_stats(params) };
+    if res < 0 {
+        return None;
+    }
+    let stat =
+        |i: usize| u32::from_le_bytes(stats[4 * i .. 4 * i + 4].try_into().unwrap()) as usize;
+    Some(HeapStats { used: stat(0), free: stat(1), high_water: stat(2) })
+}
+
+/// The platform doesn't support changing its log level.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Error;
+
+/// Sets the most verbose level of the platform logs.
+///
+/// This is useful to debug the platform in the field. Levels disabled when the platform was
+/// compiled can't be enabled.
+pub fn set_log_level(level: Level) -> Result<(), Error> {
+    let params = api::set_log_level::Params { level: level as usize };
+    let api::set_log_level::Results { res } = unsafe { api::set_log_level(params) };
+    match res {
+        0 => Ok(()),
+        _ => Err(Error),
+    }
+}
+
 /// Exits the platform indicating success of failure.
 pub fn exit(success: bool) -> ! {
     let params = api::exit::Params { code: if success { 0 } else { 1 } };
@@ -78,3 +211,17 @@ pub fn assert_eq<T: ?Sized + Eq + core::fmt::Debug>(x: &T, y: &T) {
         exit(false);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stack_string_truncates() {
+        assert_eq!(&*StackString::format(format_args!("{} + {}", 1, 2)), "1 + 2");
+        let long = "é".repeat(StackString::CAPACITY);
+        let string = StackString::format(format_args!("x{long}"));
+        assert_eq!(string.len(), StackString::CAPACITY - 1);
+        assert!(string.starts_with("xé"));
+    }
+}
@@ -0,0 +1,144 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides API for quadrature encoders.
+//!
+//! Encoders are abstracted with:
+//! - They have a signed position in steps.
+//! - They can be homed: the position is zeroed at the next index pulse.
+//! - They can trigger a callback when homing is done.
+//!
+//! The shaft must be rotated while homing, for example with a [stepper motor](crate::stepper):
+//!
+//! ```ignore
+//! stepper::move_to(0, 2 * STEPS_PER_REVOLUTION, &profile)?;
+//! encoder::home_and_wait(0)?;
+//! ```
+
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use core::cell::Cell;
+
+use wasefire_applet_api::encoder as api;
+
+/// Error starting homing.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Error;
+
+/// Returns the number of available encoders on the board.
+pub fn count() -> usize {
+    let api::count::Results { cnt } = unsafe { api::count() };
+    cnt
+}
+
+/// Returns the current position of an encoder.
+///
+/// The `encoder` argument is the index of the encoder. It must be less than [count()].
+pub fn position(encoder: usize) -> isize {
+    let api::position::Results { position } =
+        unsafe { api::position(api::position::Params { encoder }) };
+    position
+}
+
+/// Starts homing an encoder.
+///
+/// The `encoder` argument is the index of the encoder. It must be less than [count()]. This
+/// function returns immediately, see [Listener] to be notified when homing is done.
+pub fn home(encoder: usize) -> Result<(), Error> {
+    let api::home::Results { res } = unsafe { api::home(api::home::Params { encoder }) };
+    match res {
+        0 => Ok(()),
+        _ => Err(Error),
+    }
+}
+
+/// Homes an encoder and waits until the position is zeroed at the index pulse.
+///
+/// This is a convenience function to avoid creating a listener and a callback.
+pub fn home_and_wait(encoder: usize) -> Result<(), Error> {
+    let done = Rc::new(Cell::new(false));
+    let listener = Listener::new(encoder, {
+        let done = done.clone();
+        move || done.set(true)
+    });
+    home(encoder)?;
+    while !done.get() {
+        crate::scheduling::wait_for_callback();
+    }
+    listener.stop();
+    Ok(())
+}
+
+/// Provides callback support for encoder events.
+pub trait Handler: 'static {
+    /// Called when homing is done.
+    fn event(&self);
+}
+
+impl<F: Fn() + 'static> Handler for F {
+    fn event(&self) {
+        self()
+    }
+}
+
+/// Provides listening support for encoder events.
+#[must_use]
+pub struct Listener<H: Handler> {
+    encoder: usize,
+    handler: *mut H,
+}
+
+impl<H: Handler> Listener<H> {
+    /// Starts listening for encoder events.
+    ///
+    /// The `encoder` argument is the index of the encoder to listen events for. It must be less
+    /// than [count()]. The `handler` argument is the callback to be called when homing is done.
+    /// Note that it may be an `Fn()` closure.
+    ///
+    /// The listener stops listening when dropped.
+    pub fn new(encoder: usize, handler: H) -> Self {
+        let handler_func = Self::call;
+        let handler = Box::into_raw(Box::new(handler));
+        let handler_data = handler as *mut u8;
+        unsafe { api::register(api::register::Params { encoder, handler_func, handler_data }) };
+        Listener { encoder, handler }
+    }
+
+    /// Stops listening.
+    ///
+    /// This is equivalent to calling `core::mem::drop()`.
+    pub fn stop(self) {
+        core::mem::drop(self);
+    }
+
+    /// Drops the listener but continues listening.
+    ///
+    /// This is equivalent to calling `core::mem::forget()`.
+    pub fn leak(self) {
+        core::mem::forget(self);
+    }
+
+    extern "C" fn call(data: *mut u8) {
+        let handler = unsafe { &mut *(data as *mut H) };
+        handler.event();
+    }
+}
+
+impl<H: Handler> Drop for Listener<H> {
+    fn drop(&mut self) {
+        let params = api::unregister::Params { encoder: self.encoder };
+        unsafe { api::unregister(params) };
+        unsafe { Box::from_raw(self.handler) };
+    }
+}
@@ -0,0 +1,146 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides the base64url encoding without padding.
+//!
+//! This is the URL and filename safe alphabet of RFC 4648 (section 5) as used by WebAuthn. The
+//! functions write to caller-provided buffers and don't allocate.
+
+/// Errors of the base64url functions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The output buffer is too small.
+    BufferTooSmall,
+
+    /// The input contains a character outside the alphabet (including padding).
+    InvalidCharacter,
+
+    /// The input length is not a valid encoded length.
+    InvalidLength,
+
+    /// The unused bits of the last character are not zero.
+    NonCanonical,
+}
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Returns the length of the encoding of an input of a given length.
+pub const fn encoded_len(len: usize) -> usize {
+    len / 3 * 4 + [0, 2, 3][len % 3]
+}
+
+/// Returns the length of the decoding of an input of a given length, if valid.
+pub const fn decoded_len(len: usize) -> Option<usize> {
+    match len % 4 {
+        1 => None,
+        r => Some(len / 4 * 3 + r * 3 / 4),
+    }
+}
+
+/// Encodes an input into an output buffer.
+///
+/// Returns the encoded prefix of the output buffer, which must be at least [`encoded_len()`] long.
+pub fn encode<'a>(input: &[u8], output: &'a mut [u8]) -> Result<&'a str, Error> {
+    let len = encoded_len(input.len());
+    let output = output.get_mut(.. len).ok_or(Error::BufferTooSmall)?;
+    for (input, output) in input.chunks(3).zip(output.chunks_mut(4)) {
+        let mut block = [0; 3];
+        block[.. input.len()].copy_from_slice(input);
+        let bits = u32::from_be_bytes([0, block[0], block[1], block[2]]);
+        for (i, output) in output.iter_mut().enumerate() {
+            *output = ALPHABET[(bits >> (18 - 6 * i) & 0x3f) as usize];
+        }
+    }
+    // The alphabet is ASCII.
+    Ok(core::str::from_utf8(output).unwrap())
+}
+
+/// Decodes an input into an output buffer.
+///
+/// Returns the decoded prefix of the output buffer, which must be at least [`decoded_len()`] long.
+/// Padding is rejected like any character outside the alphabet.
+pub fn decode<'a>(input: &[u8], output: &'a mut [u8]) -> Result<&'a [u8], Error> {
+    let len = decoded_len(input.len()).ok_or(Error::InvalidLength)?;
+    let output = output.get_mut(.. len).ok_or(Error::BufferTooSmall)?;
+    for (input, output) in input.chunks(4).zip(output.chunks_mut(3)) {
+        let mut bits = 0u32;
+        for (i, &x) in input.iter().enumerate() {
+            bits |= (value(x)? as u32) << (18 - 6 * i);
+        }
+        let block = bits.to_be_bytes();
+        if block[1 + output.len() ..].iter().any(|&x| x != 0) {
+            return Err(Error::NonCanonical);
+        }
+        output.copy_from_slice(&block[1 .. 1 + output.len()]);
+    }
+    Ok(output)
+}
+
+/// Returns the value of a character of the alphabet.
+fn value(x: u8) -> Result<u8, Error> {
+    Ok(match x {
+        b'A' ..= b'Z' => x - b'A',
+        b'a' ..= b'z' => x - b'a' + 26,
+        b'0' ..= b'9' => x - b'0' + 52,
+        b'-' => 62,
+        b'_' => 63,
+        _ => return Err(Error::InvalidCharacter),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test vectors of RFC 4648 (section 10) without padding.
+    const VECTORS: &[(&[u8], &str)] = &[
+        (b"", ""),
+        (b"f", "Zg"),
+        (b"fo", "Zm8"),
+        (b"foo", "Zm9v"),
+        (b"foob", "Zm9vYg"),
+        (b"fooba", "Zm9vYmE"),
+        (b"foobar", "Zm9vYmFy"),
+        // The URL-safe characters.
+        (&[0xfb, 0xff, 0xbf], "-_-_"),
+    ];
+
+    #[test]
+    fn vectors() {
+        for &(decoded, encoded) in VECTORS {
+            let mut buffer = [0; 8];
+            assert_eq!(encoded_len(decoded.len()), encoded.len());
+            assert_eq!(encode(decoded, &mut buffer), Ok(encoded));
+            assert_eq!(decoded_len(encoded.len()), Some(decoded.len()));
+            assert_eq!(decode(encoded.as_bytes(), &mut buffer), Ok(decoded));
+        }
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(encode(b"", &mut []), Ok(""));
+        assert_eq!(decode(b"", &mut []), Ok(&[][..]));
+    }
+
+    #[test]
+    fn invalid() {
+        let mut buffer = [0; 8];
+        assert_eq!(decode(b"Zg==", &mut buffer), Err(Error::InvalidCharacter));
+        assert_eq!(decode(b"Zm+v", &mut buffer), Err(Error::InvalidCharacter));
+        assert_eq!(decode(b"Zm9vY", &mut buffer), Err(Error::InvalidLength));
+        assert_eq!(decode(b"Zh", &mut buffer), Err(Error::NonCanonical));
+        assert_eq!(decode(b"Zm9v", &mut buffer[.. 2]), Err(Error::BufferTooSmall));
+        assert_eq!(encode(b"foo", &mut buffer[.. 3]), Err(Error::BufferTooSmall));
+    }
+}
@@ -0,0 +1,175 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides Consistent Overhead Byte Stuffing (COBS).
+//!
+//! The encoding doesn't contain zero bytes, such that a zero byte can delimit frames on a byte
+//! stream (e.g. USB serial). The delimiter is not part of the encoding: the caller appends it after
+//! [`encode()`] and strips it before [`decode()`]. The functions write to caller-provided buffers
+//! and don't allocate.
+
+/// Errors of the COBS functions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The output buffer is too small.
+    BufferTooSmall,
+
+    /// The input contains a zero byte.
+    InvalidZero,
+
+    /// The input ends in the middle of a block.
+    Truncated,
+}
+
+/// Returns the maximum length of the encoding of an input of a given length.
+pub const fn max_encoded_len(len: usize) -> usize {
+    len + len / 254 + 1
+}
+
+/// Encodes an input into an output buffer.
+///
+/// Returns the encoded prefix of the output buffer, which must be at least [`max_encoded_len()`]
+/// long.
+pub fn encode<'a>(input: &[u8], output: &'a mut [u8]) -> Result<&'a [u8], Error> {
+    let output = output.get_mut(.. max_encoded_len(input.len())).ok_or(Error::BufferTooSmall)?;
+    // Position of the code byte of the current block.
+    let mut code_pos = 0;
+    let mut code = 1u8;
+    let mut len = 1;
+    for &byte in input {
+        if byte != 0 {
+            output[len] = byte;
+            len += 1;
+            code += 1;
+        }
+        if byte == 0 || code == 0xff {
+            output[code_pos] = code;
+            code_pos = len;
+            code = 1;
+            len += 1;
+        }
+    }
+    output[code_pos] = code;
+    Ok(&output[.. len])
+}
+
+/// Decodes an input into an output buffer.
+///
+/// Returns the decoded prefix of the output buffer. The decoding is never longer than the input.
+pub fn decode<'a>(input: &[u8], output: &'a mut [u8]) -> Result<&'a [u8], Error> {
+    let mut pos = 0;
+    let mut len = 0;
+    while pos < input.len() {
+        let code = input[pos] as usize;
+        if code == 0 {
+            return Err(Error::InvalidZero);
+        }
+        let block = input.get(pos + 1 .. pos + code).ok_or(Error::Truncated)?;
+        if block.contains(&0) {
+            return Err(Error::InvalidZero);
+        }
+        let end = len + block.len();
+        output.get_mut(len .. end).ok_or(Error::BufferTooSmall)?.copy_from_slice(block);
+        len = end;
+        pos += code;
+        // Full blocks and the last block are not followed by a zero.
+        if code != 0xff && pos < input.len() {
+            *output.get_mut(len).ok_or(Error::BufferTooSmall)? = 0;
+            len += 1;
+        }
+    }
+    Ok(&output[.. len])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(decoded: &[u8]) {
+        let mut encoded = [0; 1024];
+        let encoded = encode(decoded, &mut encoded).unwrap();
+        assert!(!encoded.contains(&0));
+        assert!(encoded.len() <= max_encoded_len(decoded.len()));
+        let mut output = [0; 1024];
+        assert_eq!(decode(encoded, &mut output), Ok(decoded));
+    }
+
+    #[test]
+    fn zero_runs() {
+        const VECTORS: &[(&[u8], &[u8])] = &[
+            (&[], &[0x01]),
+            (&[0x00], &[0x01, 0x01]),
+            (&[0x00, 0x00], &[0x01, 0x01, 0x01]),
+            (&[0x00, 0x11, 0x00], &[0x01, 0x02, 0x11, 0x01]),
+            (&[0x11, 0x22, 0x00, 0x33], &[0x03, 0x11, 0x22, 0x02, 0x33]),
+            (&[0x11, 0x22, 0x33, 0x44], &[0x05, 0x11, 0x22, 0x33, 0x44]),
+            (&[0x11, 0x00, 0x00, 0x00], &[0x02, 0x11, 0x01, 0x01, 0x01]),
+        ];
+        for &(decoded, encoded) in VECTORS {
+            let mut buffer = [0; 8];
+            assert_eq!(encode(decoded, &mut buffer), Ok(encoded));
+            assert_eq!(decode(encoded, &mut buffer), Ok(decoded));
+        }
+        round_trip(&[0; 600]);
+    }
+
+    #[test]
+    fn long_blocks() {
+        let mut input = [0x42; 600];
+        round_trip(&input[.. 253]);
+        round_trip(&input[.. 254]);
+        round_trip(&input[.. 255]);
+        round_trip(&input);
+        input[254] = 0;
+        round_trip(&input);
+        let mut buffer = [0; 256];
+        let encoded = encode(&input[.. 254], &mut buffer).unwrap();
+        assert_eq!(encoded.len(), 256);
+        assert_eq!((encoded[0], encoded[255]), (0xff, 0x01));
+    }
+
+    #[test]
+    fn random_payloads() {
+        // Xorshift to generate payloads biased towards zeros.
+        let mut state = 0x2545f491u32;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state
+        };
+        let mut payload = [0; 600];
+        for _ in 0 .. 500 {
+            let len = next() as usize % payload.len();
+            for byte in &mut payload[.. len] {
+                let x = next();
+                *byte = if x % 4 == 0 { 0 } else { (x >> 8) as u8 };
+            }
+            round_trip(&payload[.. len]);
+        }
+    }
+
+    #[test]
+    fn invalid() {
+        let mut buffer = [0; 8];
+        assert_eq!(decode(&[0x02, 0x00], &mut buffer), Err(Error::InvalidZero));
+        assert_eq!(decode(&[0x00], &mut buffer), Err(Error::InvalidZero));
+        assert_eq!(decode(&[0x03, 0x11], &mut buffer), Err(Error::Truncated));
+        assert_eq!(
+            decode(&[0x03, 0x11, 0x22, 0x01], &mut buffer[.. 2]),
+            Err(Error::BufferTooSmall)
+        );
+        assert_eq!(encode(&[0x11], &mut buffer[.. 1]), Err(Error::BufferTooSmall));
+    }
+}
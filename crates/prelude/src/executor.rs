@@ -0,0 +1,101 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides a minimal single-threaded executor for asynchronous applets.
+//!
+//! The executor polls the future again after each board event (see
+//! [`crate::scheduling::wait_for_event()`]) and sleeps in between. Futures of this crate thus don't
+//! need to register wakers: they are simply polled until ready. A future woken by its waker is
+//! polled again without waiting.
+//!
+//! Asynchronous functions of this crate end with `_async` (e.g. [`crate::clock::sleep_async()`])
+//! and are implemented on top of the synchronous API, which stays available for applets that don't
+//! need an executor. Applets may use `async fn main()` with `wasefire::applet!(async)`.
+
+use core::future::Future;
+use core::pin::{pin, Pin};
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+use crate::scheduling;
+
+/// Whether the waker was called since the last poll.
+static WOKEN: AtomicBool = AtomicBool::new(false);
+
+/// Runs a future to completion.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = unsafe { Waker::from_raw(raw_waker()) };
+    let mut context = Context::from_waker(&waker);
+    loop {
+        WOKEN.store(false, Ordering::Relaxed);
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+        if !WOKEN.load(Ordering::Relaxed) {
+            scheduling::wait_for_event();
+        }
+    }
+}
+
+/// Returns a future resolving once a condition is satisfied.
+///
+/// This is the asynchronous version of [`crate::scheduling::wait_until()`].
+pub fn wait_until<C: FnMut() -> bool + Unpin>(cond: C) -> WaitUntil<C> {
+    WaitUntil(cond)
+}
+
+/// Future returned by [`wait_until()`].
+#[must_use = "futures do nothing unless polled"]
+pub struct WaitUntil<C>(C);
+
+impl<C: FnMut() -> bool + Unpin> Future for WaitUntil<C> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, _: &mut Context) -> Poll<()> {
+        match (self.0)() {
+            true => Poll::Ready(()),
+            false => Poll::Pending,
+        }
+    }
+}
+
+fn raw_waker() -> RawWaker {
+    const VTABLE: RawWakerVTable = RawWakerVTable::new(|_| raw_waker(), wake, wake, |_| ());
+    fn wake(_: *const ()) {
+        WOKEN.store(true, Ordering::Relaxed);
+    }
+    RawWaker::new(core::ptr::null(), &VTABLE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // This is a single test because the executor state is global.
+    #[test]
+    fn block_on_polls_until_ready() {
+        assert_eq!(block_on(async { 42 }), 42);
+        let mut polls = 0;
+        let future = core::future::poll_fn(|cx| {
+            polls += 1;
+            if polls == 3 {
+                return Poll::Ready(polls);
+            }
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        });
+        assert_eq!(block_on(future), 3);
+    }
+}
@@ -0,0 +1,52 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides API for general-purpose input/output.
+//!
+//! GPIOs are abstracted with:
+//! - They must be configured (as input or output) before use.
+//! - Their level can be read when configured and written when configured as output.
+
+use wasefire_applet_api::gpio as api;
+
+pub use self::api::Mode;
+pub use self::api::Mode::*;
+
+/// Returns the number of available GPIOs on the board.
+pub fn count() -> usize {
+    let api::count::Results { cnt } = unsafe { api::count() };
+    cnt
+}
+
+/// Configures a GPIO.
+///
+/// The `gpio` argument is the index of the GPIO. It must be less than [count()].
+pub fn configure(gpio: usize, mode: Mode) {
+    unsafe { api::configure(api::configure::Params { gpio, mode: mode as usize }) };
+}
+
+/// Reads the level of a configured GPIO.
+///
+/// Returns `true` for high and `false` for low.
+pub fn read(gpio: usize) -> bool {
+    let api::read::Results { level } = unsafe { api::read(api::read::Params { gpio }) };
+    level == 1
+}
+
+/// Writes the level of a GPIO configured as output.
+///
+/// The `level` argument is `true` for high and `false` for low.
+pub fn write(gpio: usize, level: bool) {
+    unsafe { api::write(api::write::Params { gpio, level: level as usize }) };
+}
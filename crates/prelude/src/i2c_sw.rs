@@ -0,0 +1,70 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides API for software I2C.
+//!
+//! The bus is bit-banged by the board on 2 GPIOs, for when no hardware I2C is available on the
+//! desired pins. It is much slower than hardware I2C and keeps the CPU busy during transfers. The
+//! lines need external pull-up resistors.
+
+use wasefire_applet_api::i2c_sw as api;
+
+/// Error using the bus.
+///
+/// This happens when the configuration is invalid or a device does not acknowledge.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Error;
+
+/// Configures the bus on the given GPIOs with the given clock rate in Hertz.
+pub fn configure(sda: usize, scl: usize, frequency_hz: u32) -> Result<(), Error> {
+    let params = api::configure::Params { sda, scl, frequency: frequency_hz as usize };
+    let api::configure::Results { res } = unsafe { api::configure(params) };
+    convert(res)
+}
+
+/// Reads bytes from a device.
+pub fn read(addr: u8, data: &mut [u8]) -> Result<(), Error> {
+    let params = api::read::Params { addr: addr as usize, ptr: data.as_mut_ptr(), len: data.len() };
+    let api::read::Results { res } = unsafe { api::read(params) };
+    convert(res)
+}
+
+/// Writes bytes to a device.
+pub fn write(addr: u8, data: &[u8]) -> Result<(), Error> {
+    let params = api::write::Params { addr: addr as usize, ptr: data.as_ptr(), len: data.len() };
+    let api::write::Results { res } = unsafe { api::write(params) };
+    convert(res)
+}
+
+/// Writes then reads bytes from a device with a repeated start condition.
+///
+/// This is typically used to read registers by first writing the register address.
+pub fn write_read(addr: u8, write: &[u8], read: &mut [u8]) -> Result<(), Error> {
+    let params = api::write_read::Params {
+        addr: addr as usize,
+        wptr: write.as_ptr(),
+        wlen: write.len(),
+        rptr: read.as_mut_ptr(),
+        rlen: read.len(),
+    };
+    let api::write_read::Results { res } = unsafe { api::write_read(params) };
+    convert(res)
+}
+
+fn convert(res: isize) -> Result<(), Error> {
+    match res {
+        0 => Ok(()),
+        _ => Err(Error),
+    }
+}
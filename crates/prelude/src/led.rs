@@ -17,6 +17,7 @@
 //! LEDs are abstracted with:
 //! - They have 2 states: `On` or `Off`.
 //! - Their state can be read and written.
+//! - Their brightness can be written (LEDs without brightness support round it to on or off).
 
 use wasefire_applet_api::led as api;
 
@@ -48,3 +49,13 @@ pub fn get(led: usize) -> api::Status {
 pub fn set(led: usize, status: api::Status) {
     unsafe { api::set(api::set::Params { led, status: status as usize }) };
 }
+
+/// Sets the brightness of a LED.
+///
+/// The `led` argument is the index of the LED. It must be less than [count()]. The `brightness`
+/// argument is 0 for off and 255 for fully on.
+pub fn set_brightness(led: usize, brightness: u8) {
+    unsafe {
+        api::set_brightness(api::set_brightness::Params { led, brightness: brightness as usize })
+    };
+}
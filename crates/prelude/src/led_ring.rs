@@ -0,0 +1,61 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides API for addressable LED rings.
+//!
+//! The board runs the animations autonomously, such that the applet only needs to select the
+//! pattern, its speed, and its color.
+
+use wasefire_applet_api::led_ring as api;
+
+pub use self::api::Pattern;
+
+/// Error controlling the LED ring.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Error;
+
+/// Returns the number of pixels of the ring (zero if there is no ring).
+pub fn count() -> usize {
+    let api::count::Results { cnt } = unsafe { api::count() };
+    cnt
+}
+
+/// Starts an animation, replacing the current one if any.
+///
+/// The `speed` is the number of steps per second, where a step moves the pattern by one pixel. A
+/// speed of zero freezes the first frame. The `color` is the red, green, and blue components of
+/// the lit pixels (it is ignored by [`Pattern::Rainbow`]).
+pub fn animate(pattern: Pattern, speed: u32, color: [u8; 3]) -> Result<(), Error> {
+    let [r, g, b] = color;
+    let params = api::animate::Params {
+        pattern: pattern as usize,
+        speed: speed as usize,
+        color: u32::from_be_bytes([0, r, g, b]) as usize,
+    };
+    let api::animate::Results { res } = unsafe { api::animate(params) };
+    convert(res)
+}
+
+/// Stops the current animation and turns all pixels off.
+pub fn stop() -> Result<(), Error> {
+    let api::stop::Results { res } = unsafe { api::stop() };
+    convert(res)
+}
+
+fn convert(res: isize) -> Result<(), Error> {
+    match res {
+        0 => Ok(()),
+        _ => Err(Error),
+    }
+}
@@ -19,6 +19,9 @@
 //! - A global allocator using the [rlsf] crate.
 //! - High-level wrappers around the applet API.
 //!
+//! The `no-alloc` feature removes the global allocator for the smallest footprint. Only the
+//! modules which don't need an allocator are then available.
+//!
 //! The high-level wrappers provide the following:
 //! - A safe API. (The applet API requires `unsafe`.)
 //! - Rust types like byte slices (instead of their internal representation).
@@ -32,20 +35,45 @@
 #![feature(negative_impls)]
 #![feature(vec_into_raw_parts)]
 
+#[cfg(not(feature = "no-alloc"))]
 extern crate alloc;
 
-#[cfg(not(feature = "test"))]
+pub mod adc;
+#[cfg(not(any(feature = "test", feature = "no-alloc")))]
 mod allocator;
+#[cfg(not(feature = "no-alloc"))]
 pub mod button;
 mod callback;
+pub mod capture;
+#[cfg(not(feature = "no-alloc"))]
+pub mod cbor;
+#[cfg(not(feature = "no-alloc"))]
 pub mod clock;
+pub mod crc;
 pub mod crypto;
 pub mod debug;
+#[cfg(not(feature = "no-alloc"))]
+pub mod encoder;
+pub mod encoding;
+pub mod executor;
+pub mod gpio;
+pub mod i2c_sw;
 pub mod led;
+pub mod led_ring;
+pub mod magnetometer;
+pub mod platform;
+pub mod power;
+pub mod pwm;
 pub mod rng;
 pub mod scheduling;
+#[cfg(not(feature = "no-alloc"))]
+pub mod stepper;
+#[cfg(not(feature = "no-alloc"))]
 pub mod store;
 pub mod sync;
+pub mod temp;
+#[cfg(not(feature = "no-alloc"))]
+pub mod uart;
 pub mod usb;
 
 /// Defines the entry point of an applet.
@@ -65,7 +93,32 @@ pub mod usb;
 ///     debug!("Hello world!");
 /// }
 /// ```
-#[cfg(not(feature = "test"))]
+///
+/// An asynchronous applet uses the `async` argument and runs `main()` with
+/// [`executor::block_on()`]:
+///
+/// ```ignore
+/// #![no_std]
+/// wasefire::applet!(async);
+///
+/// async fn main() {
+///     clock::sleep_async(core::time::Duration::from_secs(1)).await;
+///     debug!("Hello world!");
+/// }
+/// ```
+///
+/// An applet without allocator uses the `no_alloc` argument (possibly after `async`), which
+/// requires the `no-alloc` feature of this crate:
+///
+/// ```ignore
+/// #![no_std]
+/// wasefire::applet!(no_alloc);
+///
+/// fn main() {
+///     usb::serial::write_all(b"Hello world!\r\n").unwrap();
+/// }
+/// ```
+#[cfg(not(any(feature = "test", feature = "no-alloc")))]
 #[macro_export]
 macro_rules! applet {
     () => {
@@ -78,11 +131,44 @@ macro_rules! applet {
             main();
         }
     };
+    (async) => {
+        extern crate alloc;
+
+        use wasefire::*;
+
+        #[export_name = "main"]
+        extern "C" fn _main() {
+            executor::block_on(main());
+        }
+    };
+    ($(async,)? no_alloc) => {
+        compile_error!("alloc-free applets need the no-alloc feature of wasefire");
+    };
+}
+#[cfg(all(not(feature = "test"), feature = "no-alloc"))]
+#[macro_export]
+macro_rules! applet {
+    ($(no_alloc)?) => {
+        use wasefire::*;
+
+        #[export_name = "main"]
+        extern "C" fn _main() {
+            main();
+        }
+    };
+    (async $(, no_alloc)?) => {
+        use wasefire::*;
+
+        #[export_name = "main"]
+        extern "C" fn _main() {
+            executor::block_on(main());
+        }
+    };
 }
 #[cfg(feature = "test")]
 #[macro_export]
 macro_rules! applet {
-    () => {
+    ($(async)? $(,)? $(no_alloc)?) => {
         extern crate alloc;
 
         use wasefire::*;
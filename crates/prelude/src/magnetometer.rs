@@ -0,0 +1,251 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides API for magnetometers.
+//!
+//! The magnetometer axes are expected to be oriented with X pointing forward, Y pointing left, and
+//! Z pointing up. The [heading_degrees()] helper then returns the clockwise angle from magnetic
+//! north to the forward direction, assuming the board is held flat.
+//!
+//! Raw samples are distorted by nearby magnetic materials. This is compensated by a
+//! [`Calibration`] computed once with a [`Calibrator`] while rotating the board, and stored
+//! persistently with [`Calibration::save()`].
+
+use wasefire_applet_api::magnetometer as api;
+
+#[cfg(not(feature = "no-alloc"))]
+use crate::store;
+
+/// Error reading the magnetometer.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Error;
+
+/// Returns whether a magnetometer is available on the board.
+pub fn is_supported() -> bool {
+    let api::is_supported::Results { supported } = unsafe { api::is_supported() };
+    supported != 0
+}
+
+/// Reads the raw magnetic field along the X, Y, and Z axes.
+pub fn read() -> Result<[i16; 3], Error> {
+    let mut field = [0u8; 6];
+    let params = api::read::Params { ptr: field.as_mut_ptr() };
+    let api::read::Results { res } = unsafe { api::read(params) };
+    if res < 0 {
+        return Err(Error);
+    }
+    let axis = |i: usize| i16::from_le_bytes([field[2 * i], field[2 * i + 1]]);
+    Ok([axis(0), axis(1), axis(2)])
+}
+
+/// Hard-iron and soft-iron calibration.
+///
+/// Hard-iron distortion is an offset of the field, while soft-iron distortion is (approximated as)
+/// a different scale per axis. A calibrated axis is `(raw - offset) * scale`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Calibration {
+    pub offset: [f32; 3],
+    pub scale: [f32; 3],
+}
+
+impl Default for Calibration {
+    fn default() -> Self {
+        Calibration::IDENTITY
+    }
+}
+
+impl Calibration {
+    /// Calibration leaving raw samples unchanged.
+    pub const IDENTITY: Calibration = Calibration { offset: [0.; 3], scale: [1.; 3] };
+
+    /// Returns the calibrated field of a raw sample.
+    pub fn apply(&self, raw: [i16; 3]) -> [f32; 3] {
+        let mut field = [0.; 3];
+        for i in 0 .. 3 {
+            field[i] = (raw[i] as f32 - self.offset[i]) * self.scale[i];
+        }
+        field
+    }
+
+    /// Encodes the calibration for persistent storage.
+    pub fn to_bytes(&self) -> [u8; 24] {
+        let mut bytes = [0; 24];
+        let values = self.offset.iter().chain(self.scale.iter());
+        for (chunk, value) in bytes.chunks_exact_mut(4).zip(values) {
+            chunk.copy_from_slice(&value.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Decodes a calibration from persistent storage.
+    ///
+    /// Returns `None` if the bytes are not a valid encoding.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != 24 {
+            return None;
+        }
+        let value = |i: usize| f32::from_le_bytes(bytes[4 * i .. 4 * i + 4].try_into().unwrap());
+        let offset = [value(0), value(1), value(2)];
+        let scale = [value(3), value(4), value(5)];
+        if !offset.iter().chain(scale.iter()).all(|x| x.is_finite()) {
+            return None;
+        }
+        Some(Calibration { offset, scale })
+    }
+
+    /// Loads the calibration stored at a given key, if any.
+    #[cfg(not(feature = "no-alloc"))]
+    pub fn load(key: usize) -> Result<Option<Self>, store::Error> {
+        Ok(store::find(key)?.and_then(|bytes| Calibration::from_bytes(&bytes)))
+    }
+
+    /// Stores the calibration at a given key.
+    #[cfg(not(feature = "no-alloc"))]
+    pub fn save(&self, key: usize) -> Result<(), store::Error> {
+        store::insert(key, &self.to_bytes())
+    }
+}
+
+/// Computes a calibration from the extremes of the field over a rotation.
+///
+/// Samples should be [added](Calibrator::update) while slowly rotating the board a full turn in
+/// each direction. The calibration centers the extremes of each axis around zero and scales the
+/// axes to the same average radius.
+#[derive(Debug, Clone)]
+pub struct Calibrator {
+    min: [i16; 3],
+    max: [i16; 3],
+}
+
+impl Default for Calibrator {
+    fn default() -> Self {
+        Calibrator { min: [i16::MAX; 3], max: [i16::MIN; 3] }
+    }
+}
+
+impl Calibrator {
+    /// Creates a calibrator without samples.
+    pub fn new() -> Self {
+        Calibrator::default()
+    }
+
+    /// Adds a raw sample.
+    pub fn update(&mut self, raw: [i16; 3]) {
+        for (i, &x) in raw.iter().enumerate() {
+            self.min[i] = core::cmp::min(self.min[i], x);
+            self.max[i] = core::cmp::max(self.max[i], x);
+        }
+    }
+
+    /// Returns the calibration for the samples added so far.
+    ///
+    /// Returns `None` if the samples don't span the X and Y axes (the board was not rotated).
+    /// The Z axis is left unscaled if it was not spanned (the board was only rotated flat).
+    pub fn finish(&self) -> Option<Calibration> {
+        let mut offset = [0.; 3];
+        let mut radius = [0.; 3];
+        for i in 0 .. 3 {
+            if self.max[i] <= self.min[i] {
+                continue;
+            }
+            offset[i] = (self.max[i] as f32 + self.min[i] as f32) / 2.;
+            radius[i] = (self.max[i] as f32 - self.min[i] as f32) / 2.;
+        }
+        if radius[0] == 0. || radius[1] == 0. {
+            return None;
+        }
+        let spanned = radius.iter().filter(|&&r| r != 0.).count() as f32;
+        let average = radius.iter().sum::<f32>() / spanned;
+        let mut scale = [1.; 3];
+        for i in 0 .. 3 {
+            if radius[i] != 0. {
+                scale[i] = average / radius[i];
+            }
+        }
+        Some(Calibration { offset, scale })
+    }
+}
+
+/// Returns the heading in degrees of a raw sample given a calibration.
+///
+/// The heading is the clockwise angle from magnetic north to the X axis, in the range `[0, 360)`.
+pub fn heading_degrees(raw: [i16; 3], calibration: &Calibration) -> f32 {
+    let [x, y, _] = calibration.apply(raw);
+    let heading = libm::atan2f(y, x).to_degrees();
+    if heading < 0. {
+        heading + 360.
+    } else {
+        heading
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 0.5, "{actual} is not close to {expected}");
+    }
+
+    /// Returns the raw sample of a field with a given heading under a calibration.
+    fn raw(heading: f32, calibration: &Calibration) -> [i16; 3] {
+        let angle = heading.to_radians();
+        let field = [1000. * libm::cosf(angle), 1000. * libm::sinf(angle), -400.];
+        let mut raw = [0; 3];
+        for i in 0 .. 3 {
+            raw[i] = libm::roundf(field[i] / calibration.scale[i] + calibration.offset[i]) as i16;
+        }
+        raw
+    }
+
+    #[test]
+    fn heading_with_calibration() {
+        let calibration = Calibration { offset: [120., -80., 30.], scale: [1., 2., 1.] };
+        for heading in [0., 30., 90., 135., 180., 270., 345.] {
+            assert_close(heading_degrees(raw(heading, &calibration), &calibration), heading);
+        }
+        // Without calibration, the heading is off.
+        let actual = heading_degrees(raw(30., &calibration), &Calibration::IDENTITY);
+        assert!((actual - 30.).abs() > 5., "{actual} should not be close to 30");
+    }
+
+    #[test]
+    fn calibrator_over_rotation() {
+        let expected = Calibration { offset: [120., -80., 0.], scale: [1., 2., 1.] };
+        let mut calibrator = Calibrator::new();
+        assert_eq!(calibrator.finish(), None);
+        for heading in (0 .. 360).step_by(10) {
+            calibrator.update(raw(heading as f32, &expected));
+        }
+        let calibration = calibrator.finish().unwrap();
+        // The average radius is arbitrary, so only compare the scale ratio.
+        assert_close(calibration.offset[0], 120.);
+        assert_close(calibration.offset[1], -80.);
+        assert_close(calibration.scale[1] / calibration.scale[0], 2.);
+        assert_eq!(calibration.scale[2], 1.);
+        for heading in [0., 45., 200.] {
+            assert_close(heading_degrees(raw(heading, &expected), &calibration), heading);
+        }
+    }
+
+    #[test]
+    fn calibration_bytes() {
+        let calibration = Calibration { offset: [1.5, -2., 3.], scale: [0.5, 1., 2.] };
+        assert_eq!(Calibration::from_bytes(&calibration.to_bytes()), Some(calibration));
+        assert_eq!(Calibration::from_bytes(&[0; 23]), None);
+        let mut bytes = calibration.to_bytes();
+        bytes[20 ..].copy_from_slice(&f32::NAN.to_le_bytes());
+        assert_eq!(Calibration::from_bytes(&bytes), None);
+    }
+}
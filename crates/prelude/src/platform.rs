@@ -0,0 +1,92 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides API for the platform.
+
+pub mod update;
+
+use wasefire_applet_api::{platform as api, API_VERSION};
+
+pub use self::api::{Capability, Error};
+
+/// Set of board capabilities.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Capabilities(u32);
+
+impl Capabilities {
+    /// Returns whether a capability is in the set.
+    pub fn contains(self, capability: Capability) -> bool {
+        self.0 & (1 << capability as u32) != 0
+    }
+}
+
+/// Reboots the platform.
+///
+/// Pending storage operations complete before the platform resets. This function only returns if
+/// the platform doesn't support rebooting.
+pub fn reboot() -> Error {
+    let api::reboot::Results { res } = unsafe { api::reboot() };
+    match Error::to_result(res) {
+        Ok(_) => unreachable!(),
+        Err(e) => e,
+    }
+}
+
+/// Wipes the storage and reboots the platform.
+///
+/// The whole storage is physically erased, including the store of all applets. The applet manifest
+/// must grant the `factory-reset` permission (see the `permissions` array of the
+/// `[package.metadata.wasefire]` table) and the applet must be signed with the applet key of the
+/// runner. This function only returns if the platform doesn't support factory resets or the applet
+/// doesn't have the permission.
+pub fn factory_reset() -> Error {
+    let api::factory_reset::Results { res } = unsafe { api::factory_reset() };
+    match Error::to_result(res) {
+        Ok(_) => unreachable!(),
+        Err(e) => e,
+    }
+}
+
+/// Returns the capabilities supported by the board.
+///
+/// Applets may use it to gracefully degrade on boards missing some capabilities.
+pub fn capabilities() -> Capabilities {
+    let api::capabilities::Results { capabilities } = unsafe { api::capabilities() };
+    Capabilities(capabilities as u32)
+}
+
+/// Returns the applet API version of the platform.
+pub fn api_version() -> u32 {
+    let api::api_version::Results { version } = unsafe { api::api_version() };
+    version as u32
+}
+
+/// Returns the unique ID of the device.
+///
+/// The ID is stable across resets and may be used to identify the device, for example to
+/// provision per-device keys. It is not a secret and must not be used as a key.
+pub fn device_id() -> Result<[u8; 8], Error> {
+    let mut id = [0; 8];
+    let params = api::device_id::Params { ptr: id.as_mut_ptr() };
+    let api::device_id::Results { res } = unsafe { api::device_id(params) };
+    Error::to_result(res)?;
+    Ok(id)
+}
+
+/// Returns whether the platform implements the applet API this applet was compiled against.
+///
+/// Applets may check this at startup and refuse to run on a mismatched platform.
+pub fn is_api_compatible() -> bool {
+    api_version() == API_VERSION
+}
@@ -0,0 +1,73 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides firmware updates.
+//!
+//! This is meant for management applets. The new firmware is written by chunks to a staging bank,
+//! its Ed25519 signature is verified, and it is swapped in on the next reboot.
+//!
+//! The applet manifest must grant the `update` permission (see the `permissions` array of the
+//! `[package.metadata.wasefire]` table) and the applet must be signed with the applet key of the
+//! runner. Otherwise, the operations return
+//! [`Error::Unsupported`](super::Error::Unsupported).
+
+use wasefire_applet_api::platform::update as api;
+
+use super::Error;
+
+/// Whether firmware updates are supported.
+pub fn is_supported() -> bool {
+    let api::is_supported::Results { supported } = unsafe { api::is_supported() };
+    supported != 0
+}
+
+/// Starts a firmware update.
+///
+/// Any update in progress is discarded and the staging bank is erased.
+pub fn start() -> Result<(), Error> {
+    let api::start::Results { res } = unsafe { api::start() };
+    Error::to_result(res)?;
+    Ok(())
+}
+
+/// Writes a chunk of the firmware at an offset of the staging bank.
+pub fn write(offset: usize, chunk: &[u8]) -> Result<(), Error> {
+    let params = api::write::Params { offset, ptr: chunk.as_ptr(), len: chunk.len() };
+    let api::write::Results { res } = unsafe { api::write(params) };
+    Error::to_result(res)?;
+    Ok(())
+}
+
+/// Verifies the Ed25519 signature and the version of the written firmware.
+///
+/// The firmware ends with its version as a 32-bit little-endian integer. The update must be
+/// started again if the signature is invalid or the version is older than the last applied
+/// firmware.
+pub fn finalize(signature: &[u8; 64]) -> Result<(), Error> {
+    let params = api::finalize::Params { signature: signature.as_ptr() };
+    let api::finalize::Results { res } = unsafe { api::finalize(params) };
+    Error::to_result(res)?;
+    Ok(())
+}
+
+/// Applies the finalized firmware update and reboots the platform.
+///
+/// This function only returns on error.
+pub fn apply() -> Error {
+    let api::apply::Results { res } = unsafe { api::apply() };
+    match Error::to_result(res) {
+        Ok(_) => unreachable!(),
+        Err(e) => e,
+    }
+}
@@ -0,0 +1,44 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides API for peripheral power.
+//!
+//! Applets may disable the peripherals they don't use to save power, for example the USB device
+//! of a battery-powered applet. A peripheral in use (e.g. a running timer) can't be disabled, and a
+//! disabled peripheral can't be used until enabled again.
+
+use wasefire_applet_api::power as api;
+
+pub use self::api::{Error, Peripheral};
+
+/// Disables a peripheral.
+///
+/// The `index` argument is the index of the timer for [`Peripheral::Timer`] and must be zero
+/// otherwise.
+pub fn disable_peripheral(peripheral: Peripheral, index: usize) -> Result<(), Error> {
+    let params = api::disable_peripheral::Params { peripheral: peripheral as usize, index };
+    let api::disable_peripheral::Results { res } = unsafe { api::disable_peripheral(params) };
+    Error::to_result(res)?;
+    Ok(())
+}
+
+/// Enables a peripheral.
+///
+/// See [`disable_peripheral()`] for the arguments.
+pub fn enable_peripheral(peripheral: Peripheral, index: usize) -> Result<(), Error> {
+    let params = api::enable_peripheral::Params { peripheral: peripheral as usize, index };
+    let api::enable_peripheral::Results { res } = unsafe { api::enable_peripheral(params) };
+    Error::to_result(res)?;
+    Ok(())
+}
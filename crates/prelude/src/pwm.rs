@@ -0,0 +1,57 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides API for PWM outputs.
+//!
+//! PWM outputs are abstracted with:
+//! - They are identified by an index less than [count()].
+//! - Their duty cycle is in percent: 0 is steady low and 100 is steady high.
+
+use wasefire_applet_api::pwm as api;
+
+/// Error configuring a PWM output.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Error;
+
+/// Returns the number of available PWM outputs on the board.
+pub fn count() -> usize {
+    let api::count::Results { cnt } = unsafe { api::count() };
+    cnt
+}
+
+/// Configures and starts a PWM output.
+///
+/// The `pwm` argument is the index of the PWM output. It must be less than [count()]. The
+/// `duty_pct` argument must be at most 100.
+pub fn configure(pwm: usize, freq_hz: u32, duty_pct: u8) -> Result<(), Error> {
+    let params =
+        api::configure::Params { pwm, freq_hz: freq_hz as usize, duty_pct: duty_pct as usize };
+    let api::configure::Results { res } = unsafe { api::configure(params) };
+    convert(res)
+}
+
+/// Stops a PWM output.
+///
+/// The output is steady low afterwards.
+pub fn stop(pwm: usize) -> Result<(), Error> {
+    let api::stop::Results { res } = unsafe { api::stop(api::stop::Params { pwm }) };
+    convert(res)
+}
+
+fn convert(res: isize) -> Result<(), Error> {
+    match res {
+        0 => Ok(()),
+        _ => Err(Error),
+    }
+}
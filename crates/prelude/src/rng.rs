@@ -16,12 +16,47 @@
 
 use wasefire_applet_api::rng as api;
 
+pub use self::drbg::Drbg;
+#[cfg(not(feature = "no-alloc"))]
+use self::pool::Pool;
+
+pub mod drbg;
+#[cfg(not(feature = "no-alloc"))]
+mod pool;
+
 /// Error generating randomness.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Error;
 
+// SAFETY: Applets are single-threaded and the pool doesn't call back into applet code.
+#[cfg(not(feature = "no-alloc"))]
+static mut POOL: Pool = Pool::new();
+
+/// Fills a pool with random bytes from the platform.
+///
+/// The pool is refilled with the same number of bytes whenever [`fill_bytes()`] drains it. This is
+/// useful to gather randomness at startup instead of on first use. A length of zero disables the
+/// pool.
+#[cfg(not(feature = "no-alloc"))]
+pub fn prefill(len: usize) -> Result<(), Error> {
+    unsafe { POOL.prefill(len, platform_fill_bytes) }
+}
+
 /// Fills a slice with random bytes.
+///
+/// The bytes are drawn from the pool if primed with [`prefill()`].
+#[cfg(not(feature = "no-alloc"))]
 pub fn fill_bytes(buf: &mut [u8]) -> Result<(), Error> {
+    unsafe { POOL.fill_bytes(buf, platform_fill_bytes) }
+}
+
+/// Fills a slice with random bytes.
+#[cfg(feature = "no-alloc")]
+pub fn fill_bytes(buf: &mut [u8]) -> Result<(), Error> {
+    platform_fill_bytes(buf)
+}
+
+fn platform_fill_bytes(buf: &mut [u8]) -> Result<(), Error> {
     let params = api::fill_bytes::Params { ptr: buf.as_mut_ptr(), len: buf.len() };
     let api::fill_bytes::Results { res } = unsafe { api::fill_bytes(params) };
     match res {
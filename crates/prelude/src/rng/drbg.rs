@@ -0,0 +1,217 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides a deterministic random bit generator.
+//!
+//! This is the CTR_DRBG of NIST SP 800-90A (section 10.2) with AES-256 and without derivation
+//! function. The seed is 48 bytes of full-entropy input. Additional input and personalization
+//! strings are not supported.
+
+use aes::cipher::{BlockEncrypt, KeyInit};
+use aes::{Aes256, Block};
+
+use super::{fill_bytes, Error};
+
+const KEY_LEN: usize = 32;
+const BLOCK_LEN: usize = 16;
+
+/// Length of the seed in bytes.
+pub const SEED_LEN: usize = KEY_LEN + BLOCK_LEN;
+
+/// Number of generate requests after which a DRBG seeded by the platform reseeds.
+pub const RESEED_INTERVAL: u32 = 1 << 16;
+
+/// Deterministic random bit generator.
+///
+/// A DRBG created with [`Drbg::new()`] pulls its seed from the platform random number generator and
+/// periodically reseeds from it. A DRBG created with [`Drbg::from_seed()`] is fully deterministic
+/// and only reseeds when asked to, which is useful for reproducible tests.
+pub struct Drbg {
+    cipher: Aes256,
+    v: [u8; BLOCK_LEN],
+    /// Number of generate requests since the last (re)seed.
+    reseed_counter: u32,
+    /// Whether to periodically reseed from the platform.
+    platform: bool,
+}
+
+impl Drbg {
+    /// Creates a DRBG seeded from the platform random number generator.
+    pub fn new() -> Result<Self, Error> {
+        let mut drbg = Self::from_seed(&platform_seed()?);
+        drbg.platform = true;
+        Ok(drbg)
+    }
+
+    /// Creates a DRBG from a seed.
+    ///
+    /// The DRBG never reseeds automatically.
+    pub fn from_seed(seed: &[u8; SEED_LEN]) -> Self {
+        let cipher = Aes256::new(&[0; KEY_LEN].into());
+        let mut drbg = Drbg { cipher, v: [0; BLOCK_LEN], reseed_counter: 0, platform: false };
+        drbg.update(seed);
+        drbg.reseed_counter = 1;
+        drbg
+    }
+
+    /// Reseeds the DRBG from the platform random number generator.
+    pub fn reseed(&mut self) -> Result<(), Error> {
+        self.reseed_with(&platform_seed()?);
+        Ok(())
+    }
+
+    /// Reseeds the DRBG with the provided entropy.
+    pub fn reseed_with(&mut self, entropy: &[u8; SEED_LEN]) {
+        self.update(entropy);
+        self.reseed_counter = 1;
+    }
+
+    /// Fills a slice with random bytes.
+    ///
+    /// This only fails if the DRBG needs to reseed from the platform and the platform fails.
+    pub fn fill_bytes(&mut self, buf: &mut [u8]) -> Result<(), Error> {
+        if self.platform && RESEED_INTERVAL < self.reseed_counter {
+            self.reseed()?;
+        }
+        for chunk in buf.chunks_mut(BLOCK_LEN) {
+            let block = self.next_block();
+            chunk.copy_from_slice(&block[.. chunk.len()]);
+        }
+        self.update(&[0; SEED_LEN]);
+        self.reseed_counter = self.reseed_counter.saturating_add(1);
+        Ok(())
+    }
+
+    /// Increments V and returns its encryption.
+    fn next_block(&mut self) -> Block {
+        increment(&mut self.v);
+        let mut block = Block::from(self.v);
+        self.cipher.encrypt_block(&mut block);
+        block
+    }
+
+    /// Updates the internal state with the provided data.
+    fn update(&mut self, data: &[u8; SEED_LEN]) {
+        let mut temp = [0; SEED_LEN];
+        for chunk in temp.chunks_mut(BLOCK_LEN) {
+            chunk.copy_from_slice(&self.next_block());
+        }
+        for (x, y) in temp.iter_mut().zip(data.iter()) {
+            *x ^= y;
+        }
+        let (key, v) = temp.split_at(KEY_LEN);
+        self.cipher = Aes256::new(key.into());
+        self.v.copy_from_slice(v);
+        crate::crypto::zeroize(&mut temp);
+    }
+}
+
+impl Drop for Drbg {
+    fn drop(&mut self) {
+        // The round keys are zeroized when the cipher is dropped (or replaced in `update()`).
+        crate::crypto::zeroize(&mut self.v);
+    }
+}
+
+fn platform_seed() -> Result<[u8; SEED_LEN], Error> {
+    let mut seed = [0; SEED_LEN];
+    fill_bytes(&mut seed)?;
+    Ok(seed)
+}
+
+/// Increments a big-endian counter (modulo its size).
+fn increment(x: &mut [u8; BLOCK_LEN]) {
+    for byte in x.iter_mut().rev() {
+        *byte = byte.wrapping_add(1);
+        if *byte != 0 {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    fn hex(x: &str) -> Vec<u8> {
+        (0 .. x.len()).step_by(2).map(|i| u8::from_str_radix(&x[i .. i + 2], 16).unwrap()).collect()
+    }
+
+    #[test]
+    fn aes256_known_answers() {
+        // FIPS-197 appendix C.3 and SP 800-38A appendix F.1.5 (first block).
+        const VECTORS: &[(&str, &str, &str)] = &[
+            (
+                "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+                "00112233445566778899aabbccddeeff",
+                "8ea2b7ca516745bfeafc49904b496089",
+            ),
+            (
+                "603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4",
+                "6bc1bee22e409f96e93d7e117393172a",
+                "f3eed1bdb5d2a03c064b5a7e3db181f8",
+            ),
+        ];
+        for &(key, plaintext, ciphertext) in VECTORS {
+            let cipher = Aes256::new_from_slice(&hex(key)).unwrap();
+            let mut block = Block::clone_from_slice(&hex(plaintext));
+            cipher.encrypt_block(&mut block);
+            assert_eq!(block[..], hex(ciphertext));
+        }
+    }
+
+    fn stream(drbg: &mut Drbg) -> [u8; 100] {
+        let mut output = [0; 100];
+        drbg.fill_bytes(&mut output).unwrap();
+        output
+    }
+
+    #[test]
+    fn same_seed_same_stream() {
+        let mut x = Drbg::from_seed(&[0x42; SEED_LEN]);
+        let mut y = Drbg::from_seed(&[0x42; SEED_LEN]);
+        for _ in 0 .. 10 {
+            assert_eq!(stream(&mut x), stream(&mut y));
+        }
+        let mut z = Drbg::from_seed(&[0x43; SEED_LEN]);
+        assert_ne!(stream(&mut x), stream(&mut z));
+    }
+
+    #[test]
+    fn reseed_diverges() {
+        let mut x = Drbg::from_seed(&[0x42; SEED_LEN]);
+        let mut y = Drbg::from_seed(&[0x42; SEED_LEN]);
+        assert_eq!(stream(&mut x), stream(&mut y));
+        y.reseed_with(&[0x17; SEED_LEN]);
+        assert_ne!(stream(&mut x), stream(&mut y));
+    }
+
+    #[test]
+    fn consecutive_calls_differ() {
+        let mut x = Drbg::from_seed(&[0; SEED_LEN]);
+        assert_ne!(stream(&mut x), stream(&mut x));
+    }
+
+    #[test]
+    fn increment_carries() {
+        let mut x = [0xff; BLOCK_LEN];
+        x[0] = 0;
+        increment(&mut x);
+        let mut expected = [0; BLOCK_LEN];
+        expected[0] = 1;
+        assert_eq!(x, expected);
+    }
+}
@@ -0,0 +1,152 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides a pool of platform randomness.
+//!
+//! Priming the pool with [`prefill()`](super::prefill) at startup moves the latency of the platform
+//! random number generator out of time-sensitive code (e.g. in the middle of a protocol).
+
+use alloc::vec::Vec;
+
+use super::Error;
+use crate::crypto::zeroize;
+
+/// Buffer of random bytes refilled lazily once drained.
+pub(super) struct Pool {
+    /// Random bytes not yet used, consumed from the end.
+    buffer: Vec<u8>,
+
+    /// Number of bytes to refill once drained (zero if the pool is not used).
+    size: usize,
+}
+
+impl Pool {
+    pub(super) const fn new() -> Self {
+        Pool { buffer: Vec::new(), size: 0 }
+    }
+
+    /// Sets the size of the pool and fills it.
+    pub(super) fn prefill(
+        &mut self, size: usize, source: impl FnOnce(&mut [u8]) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        self.size = size;
+        self.discard();
+        self.refill(source)
+    }
+
+    /// Fills a slice with random bytes from the pool, refilling it when drained.
+    ///
+    /// Requests at least as large as the pool are served by the source directly.
+    pub(super) fn fill_bytes(
+        &mut self, mut buf: &mut [u8], mut source: impl FnMut(&mut [u8]) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        loop {
+            let len = core::cmp::min(buf.len(), self.buffer.len());
+            let start = self.buffer.len() - len;
+            let (head, tail) = core::mem::take(&mut buf).split_at_mut(len);
+            head.copy_from_slice(&self.buffer[start ..]);
+            self.truncate(start);
+            buf = tail;
+            if buf.is_empty() {
+                return Ok(());
+            }
+            if self.size <= buf.len() {
+                return source(buf);
+            }
+            self.refill(&mut source)?;
+        }
+    }
+
+    fn refill(&mut self, source: impl FnOnce(&mut [u8]) -> Result<(), Error>) -> Result<(), Error> {
+        debug_assert!(self.buffer.is_empty());
+        self.buffer.resize(self.size, 0);
+        let result = source(&mut self.buffer);
+        if result.is_err() {
+            self.discard();
+        }
+        result
+    }
+
+    fn discard(&mut self) {
+        self.truncate(0);
+    }
+
+    fn truncate(&mut self, len: usize) {
+        zeroize(&mut self.buffer[len ..]);
+        self.buffer.truncate(len);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a source counting its bytes and calls.
+    fn counter<'a>(
+        next: &'a mut u8, calls: &'a mut usize,
+    ) -> impl FnMut(&mut [u8]) -> Result<(), Error> + 'a {
+        move |buf| {
+            *calls += 1;
+            for x in buf {
+                *x = *next;
+                *next = next.wrapping_add(1);
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn refills_when_drained() {
+        let (mut next, mut calls) = (0, 0);
+        let mut pool = Pool::new();
+        pool.prefill(8, counter(&mut next, &mut calls)).unwrap();
+        let mut buf = [0; 6];
+        pool.fill_bytes(&mut buf, counter(&mut next, &mut calls)).unwrap();
+        assert_eq!(buf, [2, 3, 4, 5, 6, 7]);
+        assert_eq!(pool.buffer.len(), 2);
+        pool.fill_bytes(&mut buf, counter(&mut next, &mut calls)).unwrap();
+        assert_eq!(buf, [0, 1, 12, 13, 14, 15]);
+        assert_eq!(pool.buffer.len(), 4);
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    fn large_requests_bypass_the_pool() {
+        let (mut next, mut calls) = (0, 0);
+        let mut pool = Pool::new();
+        pool.prefill(4, counter(&mut next, &mut calls)).unwrap();
+        let mut buf = [0; 10];
+        pool.fill_bytes(&mut buf, counter(&mut next, &mut calls)).unwrap();
+        assert_eq!(buf, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert!(pool.buffer.is_empty());
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    fn unused_pool_forwards() {
+        let (mut next, mut calls) = (0, 0);
+        let mut pool = Pool::new();
+        let mut buf = [0; 3];
+        pool.fill_bytes(&mut buf, counter(&mut next, &mut calls)).unwrap();
+        assert_eq!(buf, [0, 1, 2]);
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn failed_refill_discards() {
+        let mut pool = Pool::new();
+        assert_eq!(pool.prefill(4, |_| Err(Error)), Err(Error));
+        assert!(pool.buffer.is_empty());
+    }
+}
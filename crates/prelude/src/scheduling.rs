@@ -27,6 +27,15 @@ pub fn wait_for_callback() {
     unsafe { api::wait_for_callback() };
 }
 
+/// Waits until the board produces an event.
+///
+/// This is like [`wait_for_callback()`] but also returns when the event doesn't schedule a
+/// callback. This lets applets that poll idle until something happens instead of spinning, and
+/// the CPU sleeps while waiting. Pending callbacks are executed before returning.
+pub fn wait_for_event() {
+    unsafe { api::wait_for_event() };
+}
+
 /// Returns how many callbacks are pending.
 pub fn num_pending_callbacks() -> usize {
     let api::num_pending_callbacks::Results { count } = unsafe { api::num_pending_callbacks() };
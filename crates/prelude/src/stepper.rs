@@ -0,0 +1,149 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides API for stepper motors.
+//!
+//! Stepper motors are abstracted with:
+//! - They have a signed position in steps.
+//! - They move to a target position following an acceleration profile.
+//! - They can trigger a callback when a move is done.
+
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use core::cell::Cell;
+
+use wasefire_applet_api::stepper as api;
+
+/// Error starting a move.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Error;
+
+/// Trapezoidal acceleration profile.
+#[derive(Debug, Copy, Clone)]
+pub struct Profile {
+    /// Maximum speed in steps per second.
+    pub max_speed: usize,
+
+    /// Acceleration (and deceleration) in steps per second squared.
+    pub acceleration: usize,
+}
+
+/// Returns the number of available stepper motors on the board.
+pub fn count() -> usize {
+    let api::count::Results { cnt } = unsafe { api::count() };
+    cnt
+}
+
+/// Starts moving a stepper motor to an absolute position.
+///
+/// The `stepper` argument is the index of the stepper motor. It must be less than [count()]. This
+/// function returns immediately, see [Listener] to be notified when the move is done.
+pub fn move_to(stepper: usize, position: isize, profile: &Profile) -> Result<(), Error> {
+    let Profile { max_speed, acceleration } = *profile;
+    let params = api::move_to::Params { stepper, position, max_speed, acceleration };
+    let api::move_to::Results { res } = unsafe { api::move_to(params) };
+    match res {
+        0 => Ok(()),
+        _ => Err(Error),
+    }
+}
+
+/// Moves a stepper motor to an absolute position and waits until the move is done.
+///
+/// This is a convenience function to avoid creating a listener and a callback.
+pub fn move_to_and_wait(stepper: usize, position: isize, profile: &Profile) -> Result<(), Error> {
+    let done = Rc::new(Cell::new(false));
+    let listener = Listener::new(stepper, {
+        let done = done.clone();
+        move || done.set(true)
+    });
+    move_to(stepper, position, profile)?;
+    while !done.get() {
+        crate::scheduling::wait_for_callback();
+    }
+    listener.stop();
+    Ok(())
+}
+
+/// Returns the current position of a stepper motor.
+///
+/// The `stepper` argument is the index of the stepper motor. It must be less than [count()].
+pub fn position(stepper: usize) -> isize {
+    let api::position::Results { position } =
+        unsafe { api::position(api::position::Params { stepper }) };
+    position
+}
+
+/// Provides callback support for stepper motor events.
+pub trait Handler: 'static {
+    /// Called when a move is done.
+    fn event(&self);
+}
+
+impl<F: Fn() + 'static> Handler for F {
+    fn event(&self) {
+        self()
+    }
+}
+
+/// Provides listening support for stepper motor events.
+#[must_use]
+pub struct Listener<H: Handler> {
+    stepper: usize,
+    handler: *mut H,
+}
+
+impl<H: Handler> Listener<H> {
+    /// Starts listening for stepper motor events.
+    ///
+    /// The `stepper` argument is the index of the stepper motor to listen events for. It must be
+    /// less than [count()]. The `handler` argument is the callback to be called when a move is
+    /// done. Note that it may be an `Fn()` closure.
+    ///
+    /// The listener stops listening when dropped.
+    pub fn new(stepper: usize, handler: H) -> Self {
+        let handler_func = Self::call;
+        let handler = Box::into_raw(Box::new(handler));
+        let handler_data = handler as *mut u8;
+        unsafe { api::register(api::register::Params { stepper, handler_func, handler_data }) };
+        Listener { stepper, handler }
+    }
+
+    /// Stops listening.
+    ///
+    /// This is equivalent to calling `core::mem::drop()`.
+    pub fn stop(self) {
+        core::mem::drop(self);
+    }
+
+    /// Drops the listener but continues listening.
+    ///
+    /// This is equivalent to calling `core::mem::forget()`.
+    pub fn leak(self) {
+        core::mem::forget(self);
+    }
+
+    extern "C" fn call(data: *mut u8) {
+        let handler = unsafe { &mut *(data as *mut H) };
+        handler.event();
+    }
+}
+
+impl<H: Handler> Drop for Listener<H> {
+    fn drop(&mut self) {
+        let params = api::unregister::Params { stepper: self.stepper };
+        unsafe { api::unregister(params) };
+        unsafe { Box::from_raw(self.handler) };
+    }
+}
@@ -18,14 +18,19 @@ use alloc::boxed::Box;
 
 use wasefire_applet_api::store as api;
 
+pub mod fragment;
+
 /// Errors returned by storage operations.
 pub use self::api::Error;
 
 /// Inserts an entry in the store.
 ///
-/// The `key` argument must be a small integer (currently less than 4096). The `value` argument is
+/// The `key` argument must be a small integer (currently less than 4032). The `value` argument is
 /// the slice to associate with this key. If there was already a value, it is overwritten.
 /// Overwritten values are zeroized from flash.
+///
+/// Keys from 4032 are reserved for the platform. Entries inserted there by older platforms can
+/// still be found and removed.
 pub fn insert(key: usize, value: &[u8]) -> Result<(), Error> {
     let params = api::insert::Params { key, ptr: value.as_ptr(), len: value.len() };
     let api::insert::Results { res } = unsafe { api::insert(params) };
@@ -0,0 +1,66 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides API for fragmented entries in persistent storage.
+//!
+//! Fragmented entries span a range of keys and may thus be larger than a single entry. Each
+//! function transfers the whole buffer in a single call: the runner directly accesses the slice
+//! given as argument for the duration of the call and doesn't retain it afterwards.
+
+use core::ops::Range;
+
+use wasefire_applet_api::store::fragment as api;
+
+use super::Error;
+
+/// Inserts a fragmented entry in the store.
+///
+/// The `keys` argument must be a non-empty range of small integers (currently less than 4032) and
+/// the `value` must fit in those keys. If there was already a value, it is overwritten.
+pub fn insert(keys: Range<usize>, value: &[u8]) -> Result<(), Error> {
+    let params =
+        api::insert::Params { keys: encode_keys(keys), ptr: value.as_ptr(), len: value.len() };
+    let api::insert::Results { res } = unsafe { api::insert(params) };
+    Error::to_result(res)?;
+    Ok(())
+}
+
+/// Removes a fragmented entry from the store.
+///
+/// If there was no value associated with the `keys` argument, this is a no-op.
+pub fn remove(keys: Range<usize>) -> Result<(), Error> {
+    let params = api::remove::Params { keys: encode_keys(keys) };
+    let api::remove::Results { res } = unsafe { api::remove(params) };
+    Error::to_result(res)?;
+    Ok(())
+}
+
+/// Reads part of a fragmented entry, starting at `offset`, into a buffer.
+///
+/// Returns how many bytes were read. This is less than the buffer length when the end of the entry
+/// is reached, and zero if there is no entry or `offset` is past its end.
+pub fn read(keys: Range<usize>, offset: usize, buf: &mut [u8]) -> Result<usize, Error> {
+    let params = api::read::Params {
+        keys: encode_keys(keys),
+        offset,
+        ptr: buf.as_mut_ptr(),
+        len: buf.len(),
+    };
+    let api::read::Results { res } = unsafe { api::read(params) };
+    Error::to_result(res)
+}
+
+fn encode_keys(keys: Range<usize>) -> usize {
+    keys.end << 16 | keys.start
+}
@@ -0,0 +1,38 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides API for temperature sensors.
+
+use wasefire_applet_api::temp as api;
+
+/// Error reading the temperature.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Error;
+
+/// Returns whether a temperature sensor is available on the board.
+pub fn is_supported() -> bool {
+    let api::is_supported::Results { supported } = unsafe { api::is_supported() };
+    supported != 0
+}
+
+/// Reads the temperature in millidegrees Celsius.
+pub fn read() -> Result<i32, Error> {
+    let mut temp = [0u8; 4];
+    let params = api::read::Params { ptr: temp.as_mut_ptr() };
+    let api::read::Results { res } = unsafe { api::read(params) };
+    if res < 0 {
+        return Err(Error);
+    }
+    Ok(i32::from_le_bytes(temp))
+}
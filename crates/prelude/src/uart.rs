@@ -0,0 +1,206 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides API for UARTs.
+//!
+//! UARTs are serial ports on physical pins (e.g. to talk to GPS or Wi-Fi modules), as opposed to
+//! the [USB serial](crate::usb::serial). They are identified by an index less than [count()].
+
+use alloc::boxed::Box;
+use core::cell::Cell;
+
+use wasefire_applet_api::uart as api;
+
+use crate::scheduling;
+
+/// Error using a UART.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Error;
+
+/// Returns the number of available UARTs on the board.
+pub fn count() -> usize {
+    let api::count::Results { cnt } = unsafe { api::count() };
+    cnt
+}
+
+/// Sets the baud rate of a UART.
+pub fn set_baudrate(uart: usize, baudrate: usize) -> Result<(), Error> {
+    let params = api::set_baudrate::Params { uart, baudrate };
+    let api::set_baudrate::Results { res } = unsafe { api::set_baudrate(params) };
+    convert(res).map(|_| ())
+}
+
+/// Reads from a UART into a buffer.
+///
+/// Returns the number of bytes read. This function does not block and may return zero.
+pub fn read(uart: usize, buf: &mut [u8]) -> Result<usize, Error> {
+    let params = api::read::Params { uart, ptr: buf.as_mut_ptr(), len: buf.len() };
+    let api::read::Results { len } = unsafe { api::read(params) };
+    convert(len)
+}
+
+/// Reads from a UART into a buffer.
+///
+/// Returns the number of bytes read. This function blocks until at least one byte is read.
+pub fn read_any(uart: usize, buf: &mut [u8]) -> Result<usize, Error> {
+    let mut listener = Listener::new(uart, Kind::Reader { buffer: buf });
+    scheduling::wait_until(|| !listener.is_empty());
+    listener.result()
+}
+
+/// Reads from a UART into a buffer.
+///
+/// This function blocks until the buffer is filled.
+pub fn read_all(uart: usize, buf: &mut [u8]) -> Result<(), Error> {
+    let mut listener = Listener::new(uart, Kind::Reader { buffer: buf });
+    scheduling::wait_until(|| listener.is_done());
+    listener.result()?;
+    Ok(())
+}
+
+/// Writes from a buffer to a UART.
+///
+/// Returns the number of bytes written. This function does not block and may return zero.
+pub fn write(uart: usize, buf: &[u8]) -> Result<usize, Error> {
+    let params = api::write::Params { uart, ptr: buf.as_ptr(), len: buf.len() };
+    let api::write::Results { len } = unsafe { api::write(params) };
+    convert(len)
+}
+
+/// Writes from a buffer to a UART.
+///
+/// This function blocks until the buffer is written.
+pub fn write_all(uart: usize, buf: &[u8]) -> Result<(), Error> {
+    let mut listener = Listener::new(uart, Kind::Writer { buffer: buf });
+    scheduling::wait_until(|| listener.is_done());
+    listener.result()?;
+    Ok(())
+}
+
+/// Flushes a UART.
+pub fn flush(uart: usize) -> Result<(), Error> {
+    let api::flush::Results { res } = unsafe { api::flush(api::flush::Params { uart }) };
+    convert(res).map(|_| ())
+}
+
+fn convert(len: isize) -> Result<usize, Error> {
+    if len < 0 {
+        return Err(Error);
+    }
+    Ok(len as usize)
+}
+
+struct Listener<'a> {
+    uart: usize,
+    kind: Kind<'a>,
+    // Whether the callback triggered since last operation.
+    ready: &'static Cell<bool>,
+    // The callback is registered as long as not done.
+    result: Result<usize, Error>,
+}
+
+impl<'a> Listener<'a> {
+    fn new(uart: usize, kind: Kind<'a>) -> Self {
+        let ready = Box::leak(Box::new(Cell::new(true)));
+        let mut listener = Listener { uart, kind, ready, result: Ok(0) };
+        if listener.is_registered() {
+            let event = listener.kind.event() as usize;
+            let handler_func = Self::call;
+            let handler_data = ready.as_ptr() as *mut u8;
+            let params = api::register::Params { uart, event, handler_func, handler_data };
+            unsafe { api::register(params) };
+        }
+        let _ = listener.update();
+        listener
+    }
+
+    fn is_empty(&mut self) -> bool {
+        matches!(self.update(), Ok(0))
+    }
+
+    fn is_done(&mut self) -> bool {
+        let _ = self.update();
+        !self.is_registered()
+    }
+
+    fn result(mut self) -> Result<usize, Error> {
+        self.update()
+    }
+
+    fn update(&mut self) -> Result<usize, Error> {
+        if !self.is_registered() || !self.ready.replace(false) {
+            return self.result;
+        }
+        let pos = self.result.as_mut().unwrap();
+        match self.kind.update(self.uart, *pos) {
+            Ok(len) => *pos += len,
+            err => self.result = err,
+        }
+        if !self.is_registered() {
+            self.unregister();
+        }
+        self.result
+    }
+
+    fn is_registered(&self) -> bool {
+        matches!(self.result, Ok(len) if len < self.kind.len())
+    }
+
+    fn unregister(&self) {
+        let params = api::unregister::Params { uart: self.uart, event: self.kind.event() as usize };
+        unsafe { api::unregister(params) };
+    }
+
+    extern "C" fn call(data: *mut u8) {
+        let ready = unsafe { &*(data as *mut Cell<bool>) };
+        ready.set(true);
+    }
+}
+
+impl<'a> Drop for Listener<'a> {
+    fn drop(&mut self) {
+        if self.is_registered() {
+            self.unregister();
+        }
+        unsafe { Box::from_raw(self.ready.as_ptr()) };
+    }
+}
+
+enum Kind<'a> {
+    Reader { buffer: &'a mut [u8] },
+    Writer { buffer: &'a [u8] },
+}
+
+impl<'a> Kind<'a> {
+    fn event(&self) -> api::Event {
+        match self {
+            Kind::Reader { .. } => api::Event::Read,
+            Kind::Writer { .. } => api::Event::Write,
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Kind::Reader { buffer } => buffer.len(),
+            Kind::Writer { buffer } => buffer.len(),
+        }
+    }
+
+    fn update(&mut self, uart: usize, pos: usize) -> Result<usize, Error> {
+        match self {
+            Kind::Reader { buffer } => read(uart, &mut buffer[pos ..]),
+            Kind::Writer { buffer } => write(uart, &buffer[pos ..]),
+        }
+    }
+}
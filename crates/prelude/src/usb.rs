@@ -14,9 +14,12 @@
 
 //! Provides API for USB.
 //!
-//! Only serial is currently supported. HID and its derivatives (e.g. CTAP) will be added in the
-//! future.
+//! Serial and HID are supported. The HID interface exchanges the fixed-size reports used by CTAP.
 
+#[cfg(not(feature = "no-alloc"))]
+pub mod ctaphid;
+#[cfg(not(feature = "no-alloc"))]
+pub mod hid;
 pub mod serial;
 
 /// Errors returned by USB operations.
@@ -0,0 +1,554 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides the CTAPHID transport of CTAP over USB HID.
+//!
+//! This implements the framing of section 11.2 of the CTAP 2.1 specification on top of [`hid`]:
+//! - Messages are fragmented into an initialization packet followed by continuation packets.
+//! - Each client allocates a channel (CID) with the INIT command on the broadcast channel.
+//! - PING, WINK, and errors are handled by the transport, while MSG and CBOR are returned to the
+//!   applet. The applet may send KEEPALIVE while processing them.
+//! - Messages not completed within [`TRANSACTION_TIMEOUT`] are aborted with
+//!   [`ErrorCode::MsgTimeout`].
+//!
+//! The packet-level types ([`Assembler`] and [`fragment()`]) don't depend on USB and may be used
+//! with other transports.
+
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::Cell;
+use core::time::Duration;
+
+use crate::clock::{Oneshot, Timer};
+use crate::scheduling;
+use crate::usb::hid::{self, Report, REPORT_LEN};
+use crate::usb::Error;
+
+/// The broadcast channel, used to allocate channels.
+pub const BROADCAST_CID: u32 = 0xffffffff;
+
+/// Number of payload bytes in an initialization packet.
+pub const INIT_DATA_LEN: usize = REPORT_LEN - 7;
+
+/// Number of payload bytes in a continuation packet.
+pub const CONT_DATA_LEN: usize = REPORT_LEN - 5;
+
+/// Maximum length of a message payload (one initialization and 128 continuation packets).
+pub const MAX_PAYLOAD_LEN: usize = INIT_DATA_LEN + 128 * CONT_DATA_LEN;
+
+/// Maximum duration to receive all the packets of a message.
+pub const TRANSACTION_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Version of the CTAPHID protocol.
+pub const PROTOCOL_VERSION: u8 = 2;
+
+/// CTAPHID commands.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Command {
+    Ping = 0x01,
+    Msg = 0x03,
+    Lock = 0x04,
+    Init = 0x06,
+    Wink = 0x08,
+    Cbor = 0x10,
+    Cancel = 0x11,
+    Keepalive = 0x3b,
+    Error = 0x3f,
+}
+
+impl TryFrom<u8> for Command {
+    type Error = ErrorCode;
+
+    fn try_from(x: u8) -> Result<Self, ErrorCode> {
+        Ok(match x {
+            0x01 => Command::Ping,
+            0x03 => Command::Msg,
+            0x04 => Command::Lock,
+            0x06 => Command::Init,
+            0x08 => Command::Wink,
+            0x10 => Command::Cbor,
+            0x11 => Command::Cancel,
+            0x3b => Command::Keepalive,
+            0x3f => Command::Error,
+            _ => return Err(ErrorCode::InvalidCmd),
+        })
+    }
+}
+
+/// Error codes of the ERROR command.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ErrorCode {
+    InvalidCmd = 0x01,
+    InvalidPar = 0x02,
+    InvalidLen = 0x03,
+    InvalidSeq = 0x04,
+    MsgTimeout = 0x05,
+    ChannelBusy = 0x06,
+    LockRequired = 0x0a,
+    InvalidChannel = 0x0b,
+    Other = 0x7f,
+}
+
+/// Status of the KEEPALIVE command.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u8)]
+pub enum KeepaliveStatus {
+    /// The authenticator is still processing the request.
+    Processing = 1,
+
+    /// The authenticator is waiting for user presence.
+    UpNeeded = 2,
+}
+
+/// Capability flags of the INIT response.
+pub mod capability {
+    /// The WINK command is supported.
+    pub const WINK: u8 = 0x01;
+
+    /// The CBOR command is supported.
+    pub const CBOR: u8 = 0x04;
+
+    /// The MSG command is not supported.
+    pub const NMSG: u8 = 0x08;
+}
+
+/// A complete CTAPHID message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    pub cid: u32,
+    pub cmd: Command,
+    pub payload: Vec<u8>,
+}
+
+/// A CTAPHID error to send on a channel.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ChannelError {
+    pub cid: u32,
+    pub code: ErrorCode,
+}
+
+/// Reassembles messages from packets.
+///
+/// Only one message is assembled at a time. Packets of other channels are rejected with
+/// [`ErrorCode::ChannelBusy`] until it completes or is aborted.
+#[derive(Debug, Default)]
+pub struct Assembler {
+    pending: Option<Pending>,
+}
+
+#[derive(Debug)]
+struct Pending {
+    cid: u32,
+    cmd: Command,
+    len: usize,
+    seq: u8,
+    payload: Vec<u8>,
+}
+
+impl Assembler {
+    /// Processes a packet and returns the message once complete.
+    pub fn process(&mut self, packet: &Report) -> Result<Option<Message>, ChannelError> {
+        let cid = u32::from_be_bytes(packet[.. 4].try_into().unwrap());
+        let error = |code| ChannelError { cid, code };
+        if packet[4] & 0x80 == 0 {
+            let seq = packet[4];
+            let pending = match &mut self.pending {
+                // Continuation packets of other channels (e.g. after an error) are ignored.
+                Some(x) if x.cid == cid => x,
+                _ => return Ok(None),
+            };
+            if seq != pending.seq {
+                self.pending = None;
+                return Err(error(ErrorCode::InvalidSeq));
+            }
+            pending.seq += 1;
+            let len = core::cmp::min(pending.len - pending.payload.len(), CONT_DATA_LEN);
+            pending.payload.extend_from_slice(&packet[5 .. 5 + len]);
+            return Ok(self.complete());
+        }
+        let cmd = Command::try_from(packet[4] & 0x7f).map_err(error)?;
+        match &self.pending {
+            Some(x) if x.cid != cid => return Err(error(ErrorCode::ChannelBusy)),
+            // INIT resynchronizes the channel.
+            Some(_) if cmd != Command::Init => {
+                self.pending = None;
+                return Err(error(ErrorCode::InvalidSeq));
+            }
+            _ => self.pending = None,
+        }
+        if cid == 0 || (cid == BROADCAST_CID && cmd != Command::Init) {
+            return Err(error(ErrorCode::InvalidChannel));
+        }
+        let len = u16::from_be_bytes([packet[5], packet[6]]) as usize;
+        if MAX_PAYLOAD_LEN < len {
+            return Err(error(ErrorCode::InvalidLen));
+        }
+        let mut payload = Vec::with_capacity(len);
+        payload.extend_from_slice(&packet[7 .. 7 + core::cmp::min(len, INIT_DATA_LEN)]);
+        self.pending = Some(Pending { cid, cmd, len, seq: 0, payload });
+        Ok(self.complete())
+    }
+
+    /// Aborts the message being assembled, if any, and returns its channel.
+    ///
+    /// This should be called when the next packet doesn't arrive in time, in which case
+    /// [`ErrorCode::MsgTimeout`] should be sent on the returned channel.
+    pub fn abort(&mut self) -> Option<u32> {
+        self.pending.take().map(|x| x.cid)
+    }
+
+    /// Returns whether a message is being assembled.
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    fn complete(&mut self) -> Option<Message> {
+        match &self.pending {
+            Some(x) if x.payload.len() == x.len => {
+                let Pending { cid, cmd, payload, .. } = self.pending.take().unwrap();
+                Some(Message { cid, cmd, payload })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Fragments a message into packets.
+///
+/// # Panics
+///
+/// Panics if the payload is longer than [`MAX_PAYLOAD_LEN`].
+pub fn fragment(cid: u32, cmd: Command, payload: &[u8]) -> Fragments<'_> {
+    assert!(payload.len() <= MAX_PAYLOAD_LEN);
+    Fragments { cid, cmd, payload, seq: None }
+}
+
+/// Iterator over the packets of a message.
+pub struct Fragments<'a> {
+    cid: u32,
+    cmd: Command,
+    /// The payload not yet fragmented.
+    payload: &'a [u8],
+    /// The sequence number of the last continuation packet (none before the initialization
+    /// packet).
+    seq: Option<u8>,
+}
+
+impl<'a> Iterator for Fragments<'a> {
+    type Item = Report;
+
+    fn next(&mut self) -> Option<Report> {
+        let mut packet = [0; REPORT_LEN];
+        packet[.. 4].copy_from_slice(&self.cid.to_be_bytes());
+        let data = match self.seq {
+            None => {
+                packet[4] = 0x80 | self.cmd as u8;
+                packet[5 .. 7].copy_from_slice(&(self.payload.len() as u16).to_be_bytes());
+                self.seq = Some(0);
+                &mut packet[7 ..]
+            }
+            Some(_) if self.payload.is_empty() => return None,
+            Some(seq) => {
+                packet[4] = seq;
+                self.seq = Some(seq + 1);
+                &mut packet[5 ..]
+            }
+        };
+        let len = core::cmp::min(data.len(), self.payload.len());
+        let (head, tail) = self.payload.split_at(len);
+        data[.. len].copy_from_slice(head);
+        self.payload = tail;
+        Some(packet)
+    }
+}
+
+/// Allocates channels.
+#[derive(Debug)]
+pub struct Channels {
+    /// The next channel to allocate.
+    next: u32,
+}
+
+impl Default for Channels {
+    fn default() -> Self {
+        Channels { next: 1 }
+    }
+}
+
+impl Channels {
+    /// Allocates a new channel.
+    ///
+    /// Channels are allocated in sequence and wrap around (skipping the reserved channels).
+    pub fn allocate(&mut self) -> u32 {
+        let cid = self.next;
+        self.next = match self.next.wrapping_add(1) {
+            0 | BROADCAST_CID => 1,
+            x => x,
+        };
+        cid
+    }
+}
+
+/// Returns the payload of the INIT response.
+pub fn init_response(nonce: &[u8; 8], cid: u32, version: [u8; 3], capabilities: u8) -> [u8; 17] {
+    let mut response = [0; 17];
+    response[.. 8].copy_from_slice(nonce);
+    response[8 .. 12].copy_from_slice(&cid.to_be_bytes());
+    response[12] = PROTOCOL_VERSION;
+    response[13 .. 16].copy_from_slice(&version);
+    response[16] = capabilities;
+    response
+}
+
+/// CTAPHID transport over USB HID.
+pub struct Transport {
+    assembler: Assembler,
+    channels: Channels,
+    version: [u8; 3],
+    capabilities: u8,
+}
+
+impl Transport {
+    /// Creates a transport for a device version (major, minor, build) and capabilities.
+    ///
+    /// See [`capability`] for the capability flags. The WINK command is answered (without
+    /// winking) only if advertised.
+    pub fn new(version: [u8; 3], capabilities: u8) -> Self {
+        let assembler = Assembler::default();
+        let channels = Channels::default();
+        Transport { assembler, channels, version, capabilities }
+    }
+
+    /// Receives the next MSG or CBOR message.
+    ///
+    /// This blocks and handles the other commands in the meantime. Messages whose packets don't
+    /// all arrive within [`TRANSACTION_TIMEOUT`] are aborted with [`ErrorCode::MsgTimeout`].
+    pub fn receive(&mut self) -> Result<Message, Error> {
+        let expired = Rc::new(Cell::new(false));
+        let timer = Timer::new({
+            let expired = expired.clone();
+            move || expired.set(true)
+        });
+        loop {
+            let mut packet = [0; REPORT_LEN];
+            // The timeout only applies while a message is being assembled.
+            let timeout = self.assembler.is_pending().then_some(&*expired);
+            if !read_report_until(&mut packet, timeout)? {
+                if let Some(cid) = self.assembler.abort() {
+                    self.send_error(cid, ErrorCode::MsgTimeout)?;
+                }
+                continue;
+            }
+            let result = self.assembler.process(&packet);
+            if result.is_ok() && packet[4] & 0x80 != 0 {
+                // A new message starts, so does its timeout.
+                timer.stop();
+                expired.set(false);
+            }
+            if self.assembler.is_pending() {
+                // This does nothing if the timer is already running.
+                timer.start(Oneshot, TRANSACTION_TIMEOUT);
+            }
+            let message = match result {
+                Ok(Some(x)) => x,
+                Ok(None) => continue,
+                Err(ChannelError { cid, code }) => {
+                    self.send_error(cid, code)?;
+                    continue;
+                }
+            };
+            if let Some(message) = self.handle(message)? {
+                return Ok(message);
+            }
+        }
+    }
+
+    /// Sends a message.
+    pub fn send(&self, cid: u32, cmd: Command, payload: &[u8]) -> Result<(), Error> {
+        fragment(cid, cmd, payload).try_for_each(|x| hid::write_report(&x))
+    }
+
+    /// Sends a KEEPALIVE while processing a message.
+    pub fn keepalive(&self, cid: u32, status: KeepaliveStatus) -> Result<(), Error> {
+        self.send(cid, Command::Keepalive, &[status as u8])
+    }
+
+    /// Sends an error on a channel.
+    pub fn send_error(&self, cid: u32, code: ErrorCode) -> Result<(), Error> {
+        self.send(cid, Command::Error, &[code as u8])
+    }
+
+    /// Handles the commands of the transport and returns the other messages.
+    fn handle(&mut self, message: Message) -> Result<Option<Message>, Error> {
+        let cid = message.cid;
+        match message.cmd {
+            Command::Init => {
+                let nonce = match <&[u8; 8]>::try_from(message.payload.as_slice()) {
+                    Ok(x) => x,
+                    Err(_) => return self.send_error(cid, ErrorCode::InvalidLen).map(|_| None),
+                };
+                let new_cid = match cid {
+                    BROADCAST_CID => self.channels.allocate(),
+                    x => x,
+                };
+                let response = init_response(nonce, new_cid, self.version, self.capabilities);
+                self.send(cid, Command::Init, &response)?;
+            }
+            Command::Ping => self.send(cid, Command::Ping, &message.payload)?,
+            Command::Wink if self.capabilities & capability::WINK != 0 => {
+                self.send(cid, Command::Wink, &[])?
+            }
+            Command::Msg if self.capabilities & capability::NMSG == 0 => return Ok(Some(message)),
+            Command::Cbor if self.capabilities & capability::CBOR != 0 => return Ok(Some(message)),
+            // There is nothing to cancel while not processing.
+            Command::Cancel => (),
+            _ => self.send_error(cid, ErrorCode::InvalidCmd)?,
+        }
+        Ok(None)
+    }
+}
+
+/// Reads a report, unless the timeout expires first.
+///
+/// Returns whether a report was read.
+fn read_report_until(report: &mut Report, expired: Option<&Cell<bool>>) -> Result<bool, Error> {
+    let listener = hid::Listener::new(hid::Event::Read);
+    let mut result = Ok(false);
+    scheduling::wait_until(|| {
+        result = match listener.take() {
+            true => hid::read(report),
+            false => Ok(false),
+        };
+        !matches!(result, Ok(false)) || expired.map_or(false, |x| x.get())
+    });
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+
+    fn packet(prefix: &[u8]) -> Report {
+        let mut packet = [0; REPORT_LEN];
+        packet[.. prefix.len()].copy_from_slice(prefix);
+        packet
+    }
+
+    #[test]
+    fn init_vector() {
+        // INIT request on the broadcast channel with nonce 0x0102030405060708.
+        let request = packet(&[
+            0xff, 0xff, 0xff, 0xff, 0x86, 0x00, 0x08, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07,
+            0x08,
+        ]);
+        let mut assembler = Assembler::default();
+        let message = assembler.process(&request).unwrap().unwrap();
+        assert_eq!(message.cid, BROADCAST_CID);
+        assert_eq!(message.cmd, Command::Init);
+        let nonce = <&[u8; 8]>::try_from(message.payload.as_slice()).unwrap();
+        let cid = Channels::default().allocate();
+        let response = init_response(nonce, cid, [1, 2, 3], capability::CBOR | capability::NMSG);
+        let packets: Vec<_> = fragment(BROADCAST_CID, Command::Init, &response).collect();
+        let expected = packet(&[
+            0xff, 0xff, 0xff, 0xff, 0x86, 0x00, 0x11, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07,
+            0x08, 0x00, 0x00, 0x00, 0x01, 0x02, 0x01, 0x02, 0x03, 0x0c,
+        ]);
+        assert_eq!(packets, [expected]);
+    }
+
+    #[test]
+    fn fragment_vector() {
+        let payload: Vec<u8> = (0 .. 100).collect();
+        let packets: Vec<_> = fragment(0x01020304, Command::Cbor, &payload).collect();
+        assert_eq!(packets.len(), 2);
+        assert_eq!(packets[0][.. 7], [0x01, 0x02, 0x03, 0x04, 0x90, 0x00, 0x64]);
+        assert_eq!(packets[0][7 ..], payload[.. INIT_DATA_LEN]);
+        assert_eq!(packets[1][.. 5], [0x01, 0x02, 0x03, 0x04, 0x00]);
+        assert_eq!(packets[1][5 .. 48], payload[INIT_DATA_LEN ..]);
+        assert!(packets[1][48 ..].iter().all(|&x| x == 0));
+        let packets: Vec<_> = fragment(0x01020304, Command::Ping, &[]).collect();
+        assert_eq!(packets, [packet(&[0x01, 0x02, 0x03, 0x04, 0x81, 0x00, 0x00])]);
+    }
+
+    #[test]
+    fn round_trip() {
+        for len in [0, 1, INIT_DATA_LEN, INIT_DATA_LEN + 1, 1000, MAX_PAYLOAD_LEN] {
+            let payload: Vec<u8> = (0 .. len).map(|x| x as u8).collect();
+            let mut assembler = Assembler::default();
+            let mut packets = fragment(7, Command::Msg, &payload).peekable();
+            let mut count = 0;
+            while let Some(packet) = packets.next() {
+                count += 1;
+                let result = assembler.process(&packet).unwrap();
+                assert_eq!(result.is_some(), packets.peek().is_none());
+                if let Some(message) = result {
+                    assert_eq!(
+                        message,
+                        Message { cid: 7, cmd: Command::Msg, payload: payload.clone() }
+                    );
+                }
+            }
+            if len == MAX_PAYLOAD_LEN {
+                assert_eq!(count, 129);
+            }
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let mut assembler = Assembler::default();
+        let error = |cid, code| Err(ChannelError { cid, code });
+        let payload = vec![0; 100];
+        let packets: Vec<_> = fragment(1, Command::Cbor, &payload).collect();
+        assert_eq!(assembler.process(&packets[0]), Ok(None));
+        // Other channels are busy.
+        let ping: Vec<_> = fragment(2, Command::Ping, &[]).collect();
+        assert_eq!(assembler.process(&ping[0]), error(2, ErrorCode::ChannelBusy));
+        // Wrong sequence numbers abort the message.
+        let mut wrong = packets[1];
+        wrong[4] = 1;
+        assert_eq!(assembler.process(&wrong), error(1, ErrorCode::InvalidSeq));
+        assert!(!assembler.is_pending());
+        // Continuation packets without initialization packet are ignored.
+        assert_eq!(assembler.process(&packets[1]), Ok(None));
+        // INIT resynchronizes.
+        assert_eq!(assembler.process(&packets[0]), Ok(None));
+        let init: Vec<_> = fragment(1, Command::Init, &[0; 8]).collect();
+        assert!(assembler.process(&init[0]).unwrap().is_some());
+        // Invalid packets.
+        let invalid = packet(&[0, 0, 0, 1, 0x80 | 0x22]);
+        assert_eq!(assembler.process(&invalid), error(1, ErrorCode::InvalidCmd));
+        let invalid = packet(&[0, 0, 0, 0, 0x81]);
+        assert_eq!(assembler.process(&invalid), error(0, ErrorCode::InvalidChannel));
+        let invalid = packet(&[0xff, 0xff, 0xff, 0xff, 0x90]);
+        assert_eq!(assembler.process(&invalid), error(BROADCAST_CID, ErrorCode::InvalidChannel));
+        let invalid = packet(&[0, 0, 0, 1, 0x90, 0x1d, 0xba]);
+        assert_eq!(assembler.process(&invalid), error(1, ErrorCode::InvalidLen));
+        // Timeouts abort.
+        assert_eq!(assembler.process(&packets[0]), Ok(None));
+        assert_eq!(assembler.abort(), Some(1));
+        assert_eq!(assembler.abort(), None);
+    }
+
+    #[test]
+    fn allocate_wraps() {
+        let mut channels = Channels { next: BROADCAST_CID - 1 };
+        assert_eq!(channels.allocate(), BROADCAST_CID - 1);
+        assert_eq!(channels.allocate(), 1);
+    }
+}
@@ -0,0 +1,128 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides API for USB HID.
+//!
+//! The interface is a FIDO HID interface exchanging 64-bytes reports, as used by CTAP.
+
+use alloc::boxed::Box;
+use core::cell::Cell;
+
+use wasefire_applet_api::usb::hid as api;
+
+pub use self::api::Event;
+use crate::scheduling;
+use crate::usb::{convert, Error};
+
+/// Length of a HID report in bytes.
+pub const REPORT_LEN: usize = 64;
+
+/// HID report.
+pub type Report = [u8; REPORT_LEN];
+
+/// Reads a report from USB HID without blocking.
+///
+/// Returns whether a report was read. This function does not block, so if there is no report
+/// available for read, false is returned.
+pub fn read(report: &mut Report) -> Result<bool, Error> {
+    let params = api::read::Params { ptr: report.as_mut_ptr() };
+    let api::read::Results { res } = unsafe { api::read(params) };
+    Ok(convert(res)? == 1)
+}
+
+/// Writes a report to USB HID without blocking.
+///
+/// Returns whether the report was written. This function does not block, so if the HID is not
+/// ready for write, false is returned.
+pub fn write(report: &Report) -> Result<bool, Error> {
+    let params = api::write::Params { ptr: report.as_ptr() };
+    let api::write::Results { res } = unsafe { api::write(params) };
+    Ok(convert(res)? == 1)
+}
+
+/// Synchronously reads a report from USB HID.
+///
+/// This function will block if necessary.
+pub fn read_report(report: &mut Report) -> Result<(), Error> {
+    let listener = Listener::new(Event::Read);
+    let mut result = Ok(false);
+    scheduling::wait_until(|| {
+        result = match listener.take() {
+            true => read(report),
+            false => Ok(false),
+        };
+        !matches!(result, Ok(false))
+    });
+    result.map(|_| ())
+}
+
+/// Synchronously writes a report to USB HID.
+///
+/// This function will block if necessary.
+pub fn write_report(report: &Report) -> Result<(), Error> {
+    let listener = Listener::new(Event::Write);
+    let mut result = Ok(false);
+    scheduling::wait_until(|| {
+        result = match listener.take() {
+            true => write(report),
+            false => Ok(false),
+        };
+        !matches!(result, Ok(false))
+    });
+    result.map(|_| ())
+}
+
+/// Provides a notification when USB HID is ready for an event.
+///
+/// The callback may be spuriously triggered. It is unregistered when the listener is dropped.
+#[must_use]
+pub struct Listener {
+    event: Event,
+    // Whether the callback triggered since last take.
+    ready: &'static Cell<bool>,
+}
+
+impl Listener {
+    /// Starts listening for the given event.
+    ///
+    /// The listener starts as ready, such that the first operation is always attempted.
+    pub fn new(event: Event) -> Self {
+        let ready = Box::leak(Box::new(Cell::new(true)));
+        let params = api::register::Params {
+            event: event as usize,
+            handler_func: Self::call,
+            handler_data: ready.as_ptr() as *mut u8,
+        };
+        unsafe { api::register(params) };
+        Listener { event, ready }
+    }
+
+    /// Returns whether the callback triggered since the last call.
+    pub fn take(&self) -> bool {
+        self.ready.replace(false)
+    }
+
+    extern "C" fn call(data: *mut u8) {
+        let ready = unsafe { &*(data as *mut Cell<bool>) };
+        ready.set(true);
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        let params = api::unregister::Params { event: self.event as usize };
+        unsafe { api::unregister(params) };
+        unsafe { Box::from_raw(self.ready.as_ptr()) };
+    }
+}
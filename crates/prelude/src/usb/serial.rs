@@ -13,30 +13,91 @@
 // limitations under the License.
 
 //! Provides API for USB serial.
-
+//!
+//! A board may have multiple serial ports (e.g. a data channel and a debug console), see
+//! [count()]. The functions without the `_on` suffix operate on the first port, while their `_on`
+//! variants take the index of the port as first argument.
+//!
+//! The synchronous and asynchronous helpers only use the buffers they are given and are thus
+//! available without allocator (see the `no-alloc` feature). The line state and coding listeners
+//! need an allocator.
+
+#[cfg(not(feature = "no-alloc"))]
 use alloc::boxed::Box;
+#[cfg(not(feature = "no-alloc"))]
 use core::cell::Cell;
+use core::fmt::Write as _;
+use core::time::Duration;
 
 use wasefire_applet_api::usb::serial as api;
 
+pub use self::api::{Parity, StopBits};
 use crate::scheduling;
 use crate::usb::{convert, Error};
 
+/// Returns the number of serial ports.
+pub fn count() -> usize {
+    let api::count::Results { cnt } = unsafe { api::count() };
+    cnt
+}
+
 /// Reads from USB serial into a buffer without blocking.
 ///
 /// Returns how many bytes were read (and thus written to the buffer). This function does not block,
 /// so if there are no data available for read, zero is returned.
 pub fn read(buf: &mut [u8]) -> Result<usize, Error> {
-    let params = api::read::Params { ptr: buf.as_mut_ptr(), len: buf.len() };
+    read_on(0, buf)
+}
+
+/// Reads from a USB serial port into a buffer without blocking.
+///
+/// See [read()] for more details.
+pub fn read_on(port: usize, buf: &mut [u8]) -> Result<usize, Error> {
+    let params = api::read::Params { port, ptr: buf.as_mut_ptr(), len: buf.len() };
     let api::read::Results { len } = unsafe { api::read(params) };
     convert(len)
 }
 
+/// Reads from USB serial into multiple buffers without blocking.
+///
+/// The buffers are filled in order, a buffer being only written if the previous ones are full.
+/// This is useful to read the header and payload of a frame in place. Returns the total number of
+/// bytes read, which is zero if there are no data available for read.
+pub fn read_into(bufs: &mut [&mut [u8]]) -> Result<usize, Error> {
+    read_into_on(0, bufs)
+}
+
+/// Reads from a USB serial port into multiple buffers without blocking.
+///
+/// See [read_into()] for more details.
+pub fn read_into_on(port: usize, bufs: &mut [&mut [u8]]) -> Result<usize, Error> {
+    let mut total = 0;
+    for buf in bufs.iter_mut() {
+        let len = match read_on(port, buf) {
+            Ok(len) => len,
+            Err(_) if total > 0 => break,
+            Err(e) => return Err(e),
+        };
+        total += len;
+        if len < buf.len() {
+            break;
+        }
+    }
+    Ok(total)
+}
+
 /// Synchronously reads at least one byte from USB serial into a buffer.
 ///
 /// This function will block if necessary.
 pub fn read_any(buf: &mut [u8]) -> Result<usize, Error> {
-    let mut reader = Reader::new(buf);
+    read_any_on(0, buf)
+}
+
+/// Synchronously reads at least one byte from a USB serial port into a buffer.
+///
+/// This function will block if necessary.
+pub fn read_any_on(port: usize, buf: &mut [u8]) -> Result<usize, Error> {
+    let mut reader = Reader::new_on(port, buf);
     scheduling::wait_until(|| !reader.is_empty());
     reader.result()
 }
@@ -45,7 +106,14 @@ pub fn read_any(buf: &mut [u8]) -> Result<usize, Error> {
 ///
 /// This function will block if necessary.
 pub fn read_all(buf: &mut [u8]) -> Result<(), Error> {
-    let mut reader = Reader::new(buf);
+    read_all_on(0, buf)
+}
+
+/// Synchronously reads from a USB serial port into a buffer until it is filled.
+///
+/// This function will block if necessary.
+pub fn read_all_on(port: usize, buf: &mut [u8]) -> Result<(), Error> {
+    let mut reader = Reader::new_on(port, buf);
     scheduling::wait_until(|| reader.is_done());
     reader.result()?;
     Ok(())
@@ -53,8 +121,13 @@ pub fn read_all(buf: &mut [u8]) -> Result<(), Error> {
 
 /// Synchronously reads exactly one byte.
 pub fn read_byte() -> Result<u8, Error> {
+    read_byte_on(0)
+}
+
+/// Synchronously reads exactly one byte from a USB serial port.
+pub fn read_byte_on(port: usize) -> Result<u8, Error> {
     let mut byte = 0;
-    read_any(core::slice::from_mut(&mut byte))?;
+    read_any_on(port, core::slice::from_mut(&mut byte))?;
     Ok(byte)
 }
 
@@ -63,7 +136,14 @@ pub fn read_byte() -> Result<u8, Error> {
 /// Returns how many bytes were written (and thus read from the buffer). This function does not
 /// block, so if the serial is not ready for write, zero is returned.
 pub fn write(buf: &[u8]) -> Result<usize, Error> {
-    let params = api::write::Params { ptr: buf.as_ptr(), len: buf.len() };
+    write_on(0, buf)
+}
+
+/// Writes from a buffer to a USB serial port.
+///
+/// See [write()] for more details.
+pub fn write_on(port: usize, buf: &[u8]) -> Result<usize, Error> {
+    let params = api::write::Params { port, ptr: buf.as_ptr(), len: buf.len() };
     let api::write::Results { len } = unsafe { api::write(params) };
     convert(len)
 }
@@ -72,7 +152,14 @@ pub fn write(buf: &[u8]) -> Result<usize, Error> {
 ///
 /// This function will block if necessary.
 pub fn write_any(buf: &[u8]) -> Result<usize, Error> {
-    let mut writer = Writer::new(buf);
+    write_any_on(0, buf)
+}
+
+/// Writes at least one byte from a buffer to a USB serial port.
+///
+/// This function will block if necessary.
+pub fn write_any_on(port: usize, buf: &[u8]) -> Result<usize, Error> {
+    let mut writer = Writer::new_on(port, buf);
     scheduling::wait_until(|| !writer.is_empty());
     writer.result()
 }
@@ -81,18 +168,295 @@ pub fn write_any(buf: &[u8]) -> Result<usize, Error> {
 ///
 /// This function will block if necessary.
 pub fn write_all(buf: &[u8]) -> Result<(), Error> {
-    let mut writer = Writer::new(buf);
+    write_all_on(0, buf)
+}
+
+/// Writes from a buffer to a USB serial port until everything has been written.
+///
+/// This function will block if necessary.
+pub fn write_all_on(port: usize, buf: &[u8]) -> Result<(), Error> {
+    let mut writer = Writer::new_on(port, buf);
     scheduling::wait_until(|| writer.is_done());
     writer.result()?;
     Ok(())
 }
 
+/// Writes formatted data to USB serial until everything has been written.
+///
+/// The data is written as it is formatted, so this function doesn't need a buffer. This function
+/// will block if necessary.
+///
+/// ```ignore
+/// usb::serial::write_fmt(format_args!("counter: {counter}\r\n"))?;
+/// ```
+pub fn write_fmt(args: core::fmt::Arguments) -> Result<(), Error> {
+    write_fmt_on(0, args)
+}
+
+/// Writes formatted data to a USB serial port until everything has been written.
+///
+/// See [write_fmt()] for more details.
+pub fn write_fmt_on(port: usize, args: core::fmt::Arguments) -> Result<(), Error> {
+    struct Port {
+        port: usize,
+        result: Result<(), Error>,
+    }
+    impl core::fmt::Write for Port {
+        fn write_str(&mut self, s: &str) -> core::fmt::Result {
+            self.result = write_all_on(self.port, s.as_bytes());
+            self.result.map_err(|_| core::fmt::Error)
+        }
+    }
+    let mut writer = Port { port, result: Ok(()) };
+    let _ = writer.write_fmt(args);
+    writer.result
+}
+
 /// Flushes the USB serial.
 pub fn flush() -> Result<(), Error> {
-    let api::flush::Results { res } = unsafe { api::flush() };
+    flush_on(0)
+}
+
+/// Flushes a USB serial port.
+pub fn flush_on(port: usize) -> Result<(), Error> {
+    let api::flush::Results { res } = unsafe { api::flush(api::flush::Params { port }) };
     convert(res).map(|_| ())
 }
 
+/// Flushes the USB serial, waiting at most the given duration.
+pub fn flush_timeout(timeout: Duration) -> Result<(), Error> {
+    flush_timeout_on(0, timeout)
+}
+
+/// Flushes a USB serial port, waiting at most the given duration.
+///
+/// Returns an error if the port couldn't be flushed in time (e.g. the host is not reading). The
+/// platform may wait less than the given duration (e.g. to avoid triggering the watchdog).
+pub fn flush_timeout_on(port: usize, timeout: Duration) -> Result<(), Error> {
+    let timeout_ms = usize::try_from(timeout.as_millis()).unwrap_or(usize::MAX);
+    let params = api::flush_timeout::Params { port, timeout_ms };
+    let api::flush_timeout::Results { res } = unsafe { api::flush_timeout(params) };
+    convert(res).map(|_| ())
+}
+
+/// USB serial line state, as set by the host.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct LineState {
+    /// Data terminal ready (DTR), usually set while a terminal is connected.
+    pub dtr: bool,
+
+    /// Request to send (RTS).
+    pub rts: bool,
+}
+
+/// Returns the line state of the USB serial.
+pub fn line_state() -> Result<LineState, Error> {
+    line_state_on(0)
+}
+
+/// Returns the line state of a USB serial port.
+pub fn line_state_on(port: usize) -> Result<LineState, Error> {
+    let params = api::line_state::Params { port };
+    let api::line_state::Results { res } = unsafe { api::line_state(params) };
+    let res = convert(res)?;
+    Ok(LineState { dtr: res & 1 != 0, rts: res & 2 != 0 })
+}
+
+/// Provides callback support for line state changes.
+pub trait Handler: 'static {
+    /// Called when the line state of the port changed.
+    ///
+    /// The `state` argument is the new line state of the port.
+    fn event(&self, state: LineState);
+}
+
+impl<F: Fn(LineState) + 'static> Handler for F {
+    fn event(&self, state: LineState) {
+        self(state)
+    }
+}
+
+/// Provides listening support for line state changes.
+///
+/// This is useful to reset a protocol state when a new terminal connects.
+#[cfg(not(feature = "no-alloc"))]
+#[must_use]
+pub struct ControlListener<H: Handler> {
+    port: usize,
+    handler: *mut (usize, H),
+}
+
+#[cfg(not(feature = "no-alloc"))]
+impl<H: Handler> ControlListener<H> {
+    /// Starts listening for line state changes on a port.
+    ///
+    /// The listener stops listening when dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// ControlListener::new(0, |state: LineState| debug!("DTR is {}", state.dtr))
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the port doesn't exist.
+    pub fn new(port: usize, handler: H) -> Self {
+        assert!(port < count(), "invalid USB serial port");
+        let handler_func = Self::call;
+        let handler = Box::into_raw(Box::new((port, handler)));
+        let handler_data = handler as *mut u8;
+        let event = api::Event::Control as usize;
+        unsafe { api::register(api::register::Params { port, event, handler_func, handler_data }) };
+        ControlListener { port, handler }
+    }
+
+    /// Stops listening.
+    ///
+    /// This is equivalent to calling `core::mem::drop()`.
+    pub fn stop(self) {
+        core::mem::drop(self);
+    }
+
+    /// Drops the listener but continues listening.
+    ///
+    /// This is equivalent to calling `core::mem::forget()`.
+    pub fn leak(self) {
+        core::mem::forget(self);
+    }
+
+    extern "C" fn call(data: *mut u8) {
+        let (port, handler) = unsafe { &*(data as *mut (usize, H)) };
+        if let Ok(state) = line_state_on(*port) {
+            handler.event(state);
+        }
+    }
+}
+
+#[cfg(not(feature = "no-alloc"))]
+impl<H: Handler> Drop for ControlListener<H> {
+    fn drop(&mut self) {
+        let event = api::Event::Control as usize;
+        unsafe { api::unregister(api::unregister::Params { port: self.port, event }) };
+        unsafe { Box::from_raw(self.handler) };
+    }
+}
+
+/// USB serial line coding, as requested by the host.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LineCoding {
+    /// Data rate in bits per second (i.e. the baud rate).
+    pub data_rate: u32,
+
+    /// Number of stop bits.
+    pub stop_bits: StopBits,
+
+    /// Parity type.
+    pub parity: Parity,
+
+    /// Number of data bits.
+    pub data_bits: u8,
+}
+
+/// Returns the line coding of the USB serial.
+pub fn line_coding() -> Result<LineCoding, Error> {
+    line_coding_on(0)
+}
+
+/// Returns the line coding of a USB serial port.
+pub fn line_coding_on(port: usize) -> Result<LineCoding, Error> {
+    let mut coding = [0u8; 8];
+    let params = api::line_coding::Params { port, ptr: coding.as_mut_ptr() };
+    let api::line_coding::Results { res } = unsafe { api::line_coding(params) };
+    convert(res)?;
+    Ok(LineCoding {
+        data_rate: u32::from_le_bytes(coding[.. 4].try_into().unwrap()),
+        stop_bits: (coding[4] as usize).into(),
+        parity: (coding[5] as usize).into(),
+        data_bits: coding[6],
+    })
+}
+
+/// Provides listening support for line coding changes.
+///
+/// This is useful to emulate devices that switch protocols based on the baud rate.
+#[cfg(not(feature = "no-alloc"))]
+#[must_use]
+pub struct CodingListener<F: Fn(LineCoding) + 'static> {
+    port: usize,
+    handler: *mut (usize, F),
+}
+
+#[cfg(not(feature = "no-alloc"))]
+impl<F: Fn(LineCoding) + 'static> CodingListener<F> {
+    /// Starts listening for line coding changes on a port.
+    ///
+    /// The `handler` is called with the new line coding. The listener stops listening when
+    /// dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the port doesn't exist.
+    pub fn new(port: usize, handler: F) -> Self {
+        assert!(port < count(), "invalid USB serial port");
+        let handler_func = Self::call;
+        let handler = Box::into_raw(Box::new((port, handler)));
+        let handler_data = handler as *mut u8;
+        let event = api::Event::Coding as usize;
+        unsafe { api::register(api::register::Params { port, event, handler_func, handler_data }) };
+        CodingListener { port, handler }
+    }
+
+    /// Stops listening.
+    ///
+    /// This is equivalent to calling `core::mem::drop()`.
+    pub fn stop(self) {
+        core::mem::drop(self);
+    }
+
+    /// Drops the listener but continues listening.
+    ///
+    /// This is equivalent to calling `core::mem::forget()`.
+    pub fn leak(self) {
+        core::mem::forget(self);
+    }
+
+    extern "C" fn call(data: *mut u8) {
+        let (port, handler) = unsafe { &*(data as *mut (usize, F)) };
+        if let Ok(coding) = line_coding_on(*port) {
+            handler(coding);
+        }
+    }
+}
+
+#[cfg(not(feature = "no-alloc"))]
+impl<F: Fn(LineCoding) + 'static> Drop for CodingListener<F> {
+    fn drop(&mut self) {
+        let event = api::Event::Coding as usize;
+        unsafe { api::unregister(api::unregister::Params { port: self.port, event }) };
+        unsafe { Box::from_raw(self.handler) };
+    }
+}
+
+/// Asynchronously reads from USB serial into a buffer.
+///
+/// Returns once at least one byte is read.
+pub async fn read_async(buf: &mut [u8]) -> Result<usize, Error> {
+    read_async_on(0, buf).await
+}
+
+/// Asynchronously reads from a USB serial port into a buffer.
+///
+/// Returns once at least one byte is read.
+pub async fn read_async_on(port: usize, buf: &mut [u8]) -> Result<usize, Error> {
+    if buf.is_empty() {
+        return Ok(0);
+    }
+    let mut reader = Reader::new_on(port, buf);
+    crate::executor::wait_until(|| !reader.is_empty()).await;
+    reader.result()
+}
+
 /// Provides asynchronous read support.
 #[must_use]
 pub struct Reader<'a>(Listener<'a>);
@@ -100,7 +464,12 @@ pub struct Reader<'a>(Listener<'a>);
 impl<'a> Reader<'a> {
     /// Asynchronously reads from USB serial into a buffer.
     pub fn new(buffer: &'a mut [u8]) -> Self {
-        Reader(Listener::new(Kind::Reader { buffer }))
+        Self::new_on(0, buffer)
+    }
+
+    /// Asynchronously reads from a USB serial port into a buffer.
+    pub fn new_on(port: usize, buffer: &'a mut [u8]) -> Self {
+        Reader(Listener::new(port, Kind::Reader { buffer }))
     }
 
     /// Returns whether anything has been read (or an error occurred).
@@ -126,7 +495,12 @@ pub struct Writer<'a>(Listener<'a>);
 impl<'a> Writer<'a> {
     /// Asynchronously writes from a buffer to USB serial.
     pub fn new(buffer: &'a [u8]) -> Self {
-        Writer(Listener::new(Kind::Writer { buffer }))
+        Self::new_on(0, buffer)
+    }
+
+    /// Asynchronously writes from a buffer to a USB serial port.
+    pub fn new_on(port: usize, buffer: &'a [u8]) -> Self {
+        Writer(Listener::new(port, Kind::Writer { buffer }))
     }
 
     /// Returns whether anything has been written (or an error occurred).
@@ -146,22 +520,24 @@ impl<'a> Writer<'a> {
 }
 
 struct Listener<'a> {
+    port: usize,
     kind: Kind<'a>,
     // Whether the callback triggered since last operation.
-    ready: &'static Cell<bool>,
+    ready: Ready,
     // The callback is registered as long as not done.
     result: Result<usize, Error>,
 }
 
 impl<'a> Listener<'a> {
-    fn new(kind: Kind<'a>) -> Self {
-        let ready = Box::leak(Box::new(Cell::new(true)));
-        let mut listener = Listener { kind, ready, result: Ok(0) };
+    fn new(port: usize, kind: Kind<'a>) -> Self {
+        let ready = Ready::new();
+        let handler_data = ready.data();
+        let mut listener = Listener { port, kind, ready, result: Ok(0) };
         if listener.is_registered() {
             let event = listener.kind.event() as usize;
-            let handler_func = Self::call;
-            let handler_data = ready.as_ptr() as *mut u8;
-            unsafe { api::register(api::register::Params { event, handler_func, handler_data }) };
+            let handler_func = Ready::call;
+            let params = api::register::Params { port, event, handler_func, handler_data };
+            unsafe { api::register(params) };
         }
         let _ = listener.update();
         listener
@@ -181,11 +557,11 @@ impl<'a> Listener<'a> {
     }
 
     fn update(&mut self) -> Result<usize, Error> {
-        if !self.is_registered() || !self.ready.replace(false) {
+        if !self.is_registered() || !self.ready.take() {
             return self.result;
         }
         let pos = self.result.as_mut().unwrap();
-        match self.kind.update(*pos) {
+        match self.kind.update(self.port, *pos) {
             Ok(len) => *pos += len,
             err => self.result = err,
         }
@@ -200,8 +576,37 @@ impl<'a> Listener<'a> {
     }
 
     fn unregister(&self) {
-        let event = self.kind.event() as usize;
-        unsafe { api::unregister(api::unregister::Params { event }) };
+        let params = api::unregister::Params { port: self.port, event: self.kind.event() as usize };
+        unsafe { api::unregister(params) };
+    }
+}
+
+impl<'a> Drop for Listener<'a> {
+    fn drop(&mut self) {
+        if self.is_registered() {
+            self.unregister();
+        }
+    }
+}
+
+/// Whether the callback of a listener triggered since last operation.
+///
+/// The flag is allocated because the callback needs a stable address while the listener moves.
+#[cfg(not(feature = "no-alloc"))]
+struct Ready(&'static Cell<bool>);
+
+#[cfg(not(feature = "no-alloc"))]
+impl Ready {
+    fn new() -> Self {
+        Ready(Box::leak(Box::new(Cell::new(true))))
+    }
+
+    fn data(&self) -> *mut u8 {
+        self.0.as_ptr() as *mut u8
+    }
+
+    fn take(&self) -> bool {
+        self.0.replace(false)
     }
 
     extern "C" fn call(data: *mut u8) {
@@ -210,15 +615,37 @@ impl<'a> Listener<'a> {
     }
 }
 
-impl<'a> Drop for Listener<'a> {
+#[cfg(not(feature = "no-alloc"))]
+impl Drop for Ready {
     fn drop(&mut self) {
-        if self.is_registered() {
-            self.unregister();
-        }
-        unsafe { Box::from_raw(self.ready.as_ptr()) };
+        unsafe { Box::from_raw(self.0.as_ptr()) };
     }
 }
 
+/// Whether the callback of a listener triggered since last operation.
+///
+/// Without allocator, there is no stable address for the flag. The callback is thus assumed to
+/// always have triggered, which only costs spurious non-blocking operations.
+#[cfg(feature = "no-alloc")]
+struct Ready;
+
+#[cfg(feature = "no-alloc")]
+impl Ready {
+    fn new() -> Self {
+        Ready
+    }
+
+    fn data(&self) -> *mut u8 {
+        core::ptr::null_mut()
+    }
+
+    fn take(&self) -> bool {
+        true
+    }
+
+    extern "C" fn call(_: *mut u8) {}
+}
+
 enum Kind<'a> {
     Reader { buffer: &'a mut [u8] },
     Writer { buffer: &'a [u8] },
@@ -239,10 +666,10 @@ impl<'a> Kind<'a> {
         }
     }
 
-    fn update(&mut self, pos: usize) -> Result<usize, Error> {
+    fn update(&mut self, port: usize, pos: usize) -> Result<usize, Error> {
         match self {
-            Kind::Reader { buffer } => read(&mut buffer[pos ..]),
-            Kind::Writer { buffer } => write(&buffer[pos ..]),
+            Kind::Reader { buffer } => read_on(port, &mut buffer[pos ..]),
+            Kind::Writer { buffer } => write_on(port, &buffer[pos ..]),
         }
     }
 }
@@ -16,7 +16,9 @@
 set -ex
 
 cargo check --target=wasm32-unknown-unknown
+cargo check --target=wasm32-unknown-unknown --features=no-alloc
 cargo check --features=test
 cargo fmt -- --check
 cargo clippy --target=wasm32-unknown-unknown -- --deny=warnings
+cargo clippy --target=wasm32-unknown-unknown --features=no-alloc -- --deny=warnings
 cargo test --features=test
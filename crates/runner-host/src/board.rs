@@ -12,34 +12,105 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod adc;
 pub mod button;
-mod debug;
-mod led;
-mod rng;
+pub mod capture;
+mod clock;
+pub mod debug;
+pub mod encoder;
+pub mod gpio;
+pub mod i2c_sw;
+pub mod led;
+pub mod led_ring;
+pub mod magnetometer;
+pub mod platform;
+pub mod power;
+pub mod pwm;
+pub mod record;
+pub mod rng;
+pub mod stepper;
+pub mod temp;
 pub mod timer;
-#[cfg(feature = "usb")]
+pub mod uart;
+pub mod update;
 pub mod usb;
+pub mod watchdog;
 
 use std::sync::{Arc, Mutex};
 
 use tokio::sync::mpsc::{Receiver, Sender};
+use tokio::time::Instant;
+use wasefire_board_api::debug::Effect;
+use wasefire_board_api::update::Staged;
 use wasefire_board_api::{Api, Event, Types, Unsupported};
-use wasefire_store::FileStorage;
-
+use wasefire_scheduler::Events;
+use wasefire_store::{FileStorage, Store};
+
+use self::adc::Adcs;
+use self::button::Button;
+use self::capture::Signals;
+use self::debug::Shell;
+use self::encoder::Encoders;
+use self::gpio::Gpios;
+use self::i2c_sw::I2cSw;
+use self::led::Leds;
+use self::led_ring::LedRing;
+use self::magnetometer::Magnetometer;
+use self::power::Power;
+use self::pwm::Pwms;
+use self::record::Recording;
+use self::rng::Rng;
+use self::stepper::Steppers;
+use self::temp::Temp;
 use self::timer::Timers;
+use self::uart::Uart;
+use self::update::FileBank;
+use self::usb::hid::Hid;
+use self::usb::tcp::SerialTcp;
+use self::watchdog::Watchdog;
 
 pub struct State {
     pub sender: Sender<Event>,
-    pub button: bool, // whether interrupts are enabled
-    pub led: bool,
+    pub adcs: Adcs,
+    pub button: Button,
+    /// The device ID override, if any.
+    pub device_id: Option<[u8; 8]>,
+    /// Effects collected in dry-run mode.
+    pub effects: Vec<Effect>,
+    pub encoders: Encoders,
+    pub gpios: Gpios,
+    pub hid: Hid,
+    pub i2c_sw: I2cSw,
+    pub leds: Leds,
+    pub led_ring: LedRing,
+    pub magnetometer: Magnetometer,
+    pub power: Power,
+    pub pwms: Pwms,
+    pub rng: Rng,
+    /// The USB serial bridged to a TCP socket, if enabled.
+    pub serial_tcp: Option<SerialTcp>,
+    /// When the platform started.
+    pub start: Instant,
+    /// The debugging shell.
+    pub shell: Shell,
+    pub signals: Signals,
+    pub steppers: Steppers,
+    pub temp: Temp,
     pub timers: Timers,
+    pub uart: Uart,
+    /// The firmware update protocol, if supported.
+    pub update: Option<Staged<FileBank>>,
     #[cfg(feature = "usb")]
     pub usb: usb::Usb,
-    pub storage: Option<FileStorage>,
+    pub store: Store<FileStorage>,
+    pub watchdog: Watchdog,
 }
 
 pub struct Board {
     pub receiver: Receiver<Event>,
+    /// Live events received but not yet dispatched.
+    pub events: Events,
+    pub recording: Recording,
     pub state: Arc<Mutex<State>>,
 }
 
@@ -49,16 +120,24 @@ impl Types for Board {
 
 impl Api for Board {
     fn try_event(&mut self) -> Option<Event> {
-        self.receiver.try_recv().ok()
+        self.next_event()
     }
 
     fn wait_event(&mut self) -> Event {
-        self.receiver.blocking_recv().unwrap()
+        self.state.lock().unwrap().watchdog.pause(true);
+        let event = self.wait_next_event();
+        self.state.lock().unwrap().watchdog.pause(false);
+        event
     }
 
     type Storage = FileStorage;
-    fn take_storage(&mut self) -> Option<Self::Storage> {
-        self.state.lock().unwrap().storage.take()
+    fn with_store<R>(&mut self, f: impl FnOnce(&mut Store<Self::Storage>) -> R) -> R {
+        f(&mut self.state.lock().unwrap().store)
+    }
+
+    type Adc<'a> = &'a mut Self;
+    fn adc(&mut self) -> Self::Adc<'_> {
+        self
     }
 
     type Button<'a> = &'a mut Self;
@@ -66,6 +145,16 @@ impl Api for Board {
         self
     }
 
+    type Capture<'a> = &'a mut Self;
+    fn capture(&mut self) -> Self::Capture<'_> {
+        self
+    }
+
+    type Clock<'a> = &'a mut Self;
+    fn clock(&mut self) -> Self::Clock<'_> {
+        self
+    }
+
     type Crypto<'a> = Unsupported;
     fn crypto(&mut self) -> Unsupported {
         Unsupported
@@ -76,31 +165,88 @@ impl Api for Board {
         self
     }
 
+    type Encoder<'a> = &'a mut Self;
+    fn encoder(&mut self) -> Self::Encoder<'_> {
+        self
+    }
+
+    type Gpio<'a> = &'a mut Self;
+    fn gpio(&mut self) -> Self::Gpio<'_> {
+        self
+    }
+
+    type I2cSw<'a> = &'a mut Self;
+    fn i2c_sw(&mut self) -> Self::I2cSw<'_> {
+        self
+    }
+
     type Led<'a> = &'a mut Self;
     fn led(&mut self) -> Self::Led<'_> {
         self
     }
 
+    type LedRing<'a> = &'a mut Self;
+    fn led_ring(&mut self) -> Self::LedRing<'_> {
+        self
+    }
+
+    type Magnetometer<'a> = &'a mut Self;
+    fn magnetometer(&mut self) -> Self::Magnetometer<'_> {
+        self
+    }
+
+    type Platform<'a> = &'a mut Self;
+    fn platform(&mut self) -> Self::Platform<'_> {
+        self
+    }
+
+    type Power<'a> = &'a mut Self;
+    fn power(&mut self) -> Self::Power<'_> {
+        self
+    }
+
+    type Pwm<'a> = &'a mut Self;
+    fn pwm(&mut self) -> Self::Pwm<'_> {
+        self
+    }
+
     type Rng<'a> = &'a mut Self;
     fn rng(&mut self) -> Self::Rng<'_> {
         self
     }
 
+    type Stepper<'a> = &'a mut Self;
+    fn stepper(&mut self) -> Self::Stepper<'_> {
+        self
+    }
+
+    type Temp<'a> = &'a mut Self;
+    fn temp(&mut self) -> Self::Temp<'_> {
+        self
+    }
+
     type Timer<'a> = &'a mut Self;
     fn timer(&mut self) -> Self::Timer<'_> {
         self
     }
 
-    #[cfg(feature = "usb")]
+    type Uart<'a> = &'a mut Self;
+    fn uart(&mut self) -> Self::Uart<'_> {
+        self
+    }
+
+    type Update<'a> = &'a mut Self;
+    fn update(&mut self) -> Self::Update<'_> {
+        self
+    }
+
     type Usb<'a> = &'a mut Self;
-    #[cfg(feature = "usb")]
     fn usb(&mut self) -> Self::Usb<'_> {
         self
     }
-    #[cfg(not(feature = "usb"))]
-    type Usb<'a> = wasefire_board_api::Unsupported;
-    #[cfg(not(feature = "usb"))]
-    fn usb(&mut self) -> Self::Usb<'_> {
-        wasefire_board_api::Unsupported
+
+    type Watchdog<'a> = &'a mut Self;
+    fn watchdog(&mut self) -> Self::Watchdog<'_> {
+        self
     }
 }
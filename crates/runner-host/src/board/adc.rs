@@ -0,0 +1,95 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use wasefire_board_api::adc::Api;
+use wasefire_board_api::Error;
+
+use crate::board::{Board, State};
+
+const NUM_ADCS: usize = 4;
+
+impl Api for &mut Board {
+    fn count(&mut self) -> usize {
+        NUM_ADCS
+    }
+
+    fn read(&mut self, adc: usize) -> Result<u16, Error> {
+        self.state.lock().unwrap().adcs.read(adc)
+    }
+
+    fn read_vdd_mv(&mut self) -> Result<u16, Error> {
+        Ok(self.state.lock().unwrap().adcs.vdd_mv)
+    }
+}
+
+/// Simulated analog inputs.
+///
+/// Samples are set from the command line with `adc <index> <sample>`. The supply voltage is 3.3V
+/// by default and is set from the command line with `adc vdd <millivolts>`.
+pub struct Adcs {
+    samples: Vec<u16>,
+    vdd_mv: u16,
+}
+
+impl Default for Adcs {
+    fn default() -> Self {
+        Adcs { samples: vec![0; NUM_ADCS], vdd_mv: 3300 }
+    }
+}
+
+impl Adcs {
+    fn read(&self, adc: usize) -> Result<u16, Error> {
+        self.samples.get(adc).copied().ok_or(Error::User)
+    }
+
+    fn set(&mut self, adc: usize, sample: u16) -> Result<(), Error> {
+        *self.samples.get_mut(adc).ok_or(Error::User)? = sample;
+        Ok(())
+    }
+}
+
+/// Handles the arguments of the `adc` command.
+pub fn command(state: &mut State, args: &str) {
+    if let Some(vdd_mv) = args.strip_prefix("vdd ") {
+        match vdd_mv.parse() {
+            Ok(vdd_mv) => state.adcs.vdd_mv = vdd_mv,
+            Err(_) => println!("Usage: adc vdd <millivolts less than 65536>"),
+        }
+        return;
+    }
+    let parsed = match args.split_once(' ') {
+        Some((adc, sample)) => adc.parse().ok().zip(sample.parse().ok()),
+        None => None,
+    };
+    match parsed.map(|(adc, sample)| state.adcs.set(adc, sample)) {
+        Some(Ok(())) => (),
+        _ => println!("Usage: adc <index less than {NUM_ADCS}> <sample less than 65536>"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_and_read() {
+        let mut adcs = Adcs::default();
+        assert_eq!(adcs.read(1).unwrap(), 0);
+        adcs.set(1, 1234).unwrap();
+        assert_eq!(adcs.read(1).unwrap(), 1234);
+        assert_eq!(adcs.read(0).unwrap(), 0);
+        assert!(matches!(adcs.set(NUM_ADCS, 0), Err(Error::User)));
+        assert!(matches!(adcs.read(NUM_ADCS), Err(Error::User)));
+    }
+}
@@ -12,8 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
 use wasefire_board_api as board;
-use wasefire_board_api::button::Api;
+use wasefire_board_api::button::gesture::{Config, Detector};
+use wasefire_board_api::button::{Api, Debounce, Gesture};
 use wasefire_board_api::Error;
 
 use crate::board::{Board, State};
@@ -27,7 +31,7 @@ impl Api for &mut Board {
         if button != 0 {
             return Err(Error::User);
         }
-        self.state.lock().unwrap().button = true;
+        self.state.lock().unwrap().button.enabled = true;
         Ok(())
     }
 
@@ -35,19 +39,215 @@ impl Api for &mut Board {
         if button != 0 {
             return Err(Error::User);
         }
-        self.state.lock().unwrap().button = false;
+        self.state.lock().unwrap().button.enabled = false;
+        Ok(())
+    }
+
+    fn set_debounce(&mut self, button: usize, duration_us: usize) -> Result<(), Error> {
+        if button != 0 {
+            return Err(Error::User);
+        }
+        self.state.lock().unwrap().button.debounce.set_interval(duration_us as u64);
         Ok(())
     }
 }
 
-pub fn event(state: &mut State, pressed: Option<bool>) {
-    if !state.button {
-        return;
+/// Simulated button.
+///
+/// Simulated edges don't bounce, so debouncing is disabled by default.
+pub struct Button {
+    /// Whether interrupts are enabled.
+    enabled: bool,
+    debounce: Debounce,
+    /// Whether the simulated button is pressed.
+    pressed: bool,
+}
+
+impl Default for Button {
+    fn default() -> Self {
+        Button { enabled: false, debounce: Debounce::new(0), pressed: false }
+    }
+}
+
+impl Button {
+    /// Returns the events of a button action at a given time in microseconds.
+    ///
+    /// The action is a press, a release, or both if `None`.
+    fn events(&mut self, now: u64, pressed: Option<bool>) -> Vec<board::button::Event> {
+        let mut events = Vec::new();
+        if !self.enabled {
+            return events;
+        }
+        let edges = match pressed {
+            Some(pressed) => vec![pressed],
+            None => vec![true, false],
+        };
+        for pressed in edges {
+            self.pressed = pressed;
+            if self.debounce.accept(now, pressed) {
+                events.push(board::button::Event { button: 0, pressed });
+            }
+        }
+        events
+    }
+
+    /// Returns the event of sampling the button at the end of the debounce interval, if any.
+    fn resample(&mut self, now: u64) -> Option<board::button::Event> {
+        let pressed = self.pressed;
+        (self.enabled && self.debounce.accept(now, pressed))
+            .then_some(board::button::Event { button: 0, pressed })
+    }
+}
+
+pub fn event(state: &Arc<Mutex<State>>, pressed: Option<bool>) {
+    let mut guard = state.lock().unwrap();
+    let now = guard.start.elapsed().as_micros() as u64;
+    for event in guard.button.events(now, pressed) {
+        let _ = guard.sender.try_send(event.into());
     }
-    if pressed.unwrap_or(true) {
-        let _ = state.sender.try_send(board::button::Event { button: 0, pressed: true }.into());
+    // The button is sampled again if an edge was ignored, since it might be the last one.
+    let Some(deadline) = guard.button.debounce.resample_at() else { return };
+    let state = state.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_micros(deadline.saturating_sub(now)));
+        let mut state = state.lock().unwrap();
+        let now = state.start.elapsed().as_micros() as u64;
+        if let Some(event) = state.button.resample(now) {
+            let _ = state.sender.try_send(event.into());
+        }
+    });
+}
+
+/// Replays a timestamped sequence of presses and releases through gesture recognition.
+///
+/// Timestamps are in microseconds and must be non-decreasing. Timeouts are simulated at their
+/// deadline, as if the timer triggered on time. Returns the recognized gestures with their time.
+pub fn replay(config: Config, events: &[(u64, bool)]) -> Vec<(u64, Gesture)> {
+    let mut detector = Detector::new(config);
+    let mut gestures = Vec::new();
+    let mut timeout = |detector: &mut Detector, now: Option<u64>| {
+        while let Some(deadline) = detector.deadline().filter(|&x| now.map_or(true, |now| x < now))
+        {
+            gestures.extend(detector.timeout(deadline).map(|x| (deadline, x)));
+        }
+    };
+    let mut result = Vec::new();
+    for &(now, pressed) in events {
+        timeout(&mut detector, Some(now));
+        result.extend(detector.update(pressed, now).map(|x| (now, x)));
+    }
+    timeout(&mut detector, None);
+    result.extend(gestures);
+    result.sort_by_key(|&(time, _)| time);
+    result
+}
+
+/// Handles the arguments of the `gesture` command.
+///
+/// The arguments are the long-press threshold and double-press window in milliseconds followed by
+/// a sequence of `<milliseconds>:press` and `<milliseconds>:release`.
+pub fn command(args: &str) {
+    let parse = || -> Option<(Config, Vec<(u64, bool)>)> {
+        let mut args = args.split_whitespace();
+        let long_us = args.next()?.parse::<u64>().ok()? * 1000;
+        let double_us = args.next()?.parse::<u64>().ok()? * 1000;
+        let mut events = Vec::new();
+        for event in args {
+            let (time, action) = event.split_once(':')?;
+            let time = time.parse::<u64>().ok()? * 1000;
+            let pressed = match action {
+                "press" => true,
+                "release" => false,
+                _ => return None,
+            };
+            events.push((time, pressed));
+        }
+        events
+            .windows(2)
+            .all(|x| x[0].0 <= x[1].0)
+            .then_some((Config { long_us, double_us }, events))
+    };
+    match parse() {
+        Some((config, events)) => {
+            for (time, gesture) in replay(config, &events) {
+                println!("{:.3}: {gesture:?}", time as f64 / 1e6);
+            }
+        }
+        None => println!("Usage: gesture <long ms> <double ms> [<ms>:press|<ms>:release]..."),
     }
-    if !pressed.unwrap_or(false) {
-        let _ = state.sender.try_send(board::button::Event { button: 0, pressed: false }.into());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: Config = Config { long_us: 800_000, double_us: 300_000 };
+
+    #[test]
+    fn replay_gestures() {
+        let events = [
+            // Single press.
+            (0, true),
+            (100_000, false),
+            // Double press.
+            (1_000_000, true),
+            (1_100_000, false),
+            (1_300_000, true),
+            (1_400_000, false),
+            // Long press.
+            (2_000_000, true),
+            (3_000_000, false),
+            // Single press reported at the end.
+            (4_000_000, true),
+            (4_050_000, false),
+        ];
+        let expected = [
+            (400_000, Gesture::Single),
+            (1_300_000, Gesture::Double),
+            (2_800_000, Gesture::Long),
+            (4_350_000, Gesture::Single),
+        ];
+        assert_eq!(replay(CONFIG, &events), expected);
+    }
+
+    #[test]
+    fn debounce_edges() {
+        let mut button = Button { enabled: true, debounce: Debounce::new(20_000), pressed: false };
+        // Two edges 5ms apart collapse to a single event.
+        assert_eq!(button.events(0, Some(true)).len(), 1);
+        assert_eq!(button.events(5_000, Some(false)).len(), 0);
+        // The bounce back is ignored because the state didn't change.
+        assert_eq!(button.events(30_000, Some(true)).len(), 0);
+        // Two edges 50ms apart produce two events.
+        assert_eq!(button.events(100_000, Some(false)).len(), 1);
+        assert_eq!(button.events(150_000, Some(true)).len(), 1);
+    }
+
+    #[test]
+    fn debounce_trailing_edge() {
+        let mut button = Button { enabled: true, debounce: Debounce::new(20_000), pressed: false };
+        assert_eq!(button.events(0, Some(true)).len(), 1);
+        assert_eq!(button.debounce.resample_at(), None);
+        // The release is ignored but the button is sampled at the end of the interval.
+        assert_eq!(button.events(5_000, Some(false)).len(), 0);
+        assert_eq!(button.debounce.resample_at(), Some(20_000));
+        assert_eq!(button.resample(10_000), None);
+        let release = board::button::Event { button: 0, pressed: false };
+        assert_eq!(button.resample(20_000), Some(release));
+        assert_eq!(button.debounce.resample_at(), None);
+        // Nothing is reported if the button bounced back to the reported state.
+        assert_eq!(button.events(100_000, Some(true)).len(), 1);
+        assert_eq!(button.events(105_000, Some(false)).len(), 0);
+        assert_eq!(button.events(110_000, Some(true)).len(), 0);
+        assert_eq!(button.resample(120_000), None);
+        assert_eq!(button.debounce.resample_at(), None);
+    }
+
+    #[test]
+    fn replay_without_double() {
+        let config = Config { double_us: 0, ..CONFIG };
+        let events = [(0, true), (100_000, false), (200_000, true), (300_000, false)];
+        let expected = [(100_000, Gesture::Single), (300_000, Gesture::Single)];
+        assert_eq!(replay(config, &events), expected);
     }
 }
@@ -0,0 +1,133 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use wasefire_board_api::capture::{analyze, Api, Capture, Event};
+use wasefire_board_api::Error;
+
+use crate::board::{Board, State};
+
+/// Number of simulated edges per measurement.
+const NUM_EDGES: usize = 9;
+
+impl Api for &mut Board {
+    fn start(&mut self, gpio: usize) -> Result<(), Error> {
+        let mut state = self.state.lock().unwrap();
+        if !state.gpios.is_input(gpio) {
+            return Err(Error::User);
+        }
+        let now = state.start.elapsed().as_nanos() as u64;
+        let capture = state.signals.measure(gpio, now);
+        state.signals.done.retain(|x| x.0 != gpio);
+        state.signals.done.push((gpio, capture));
+        let _ = state.sender.try_send(Event::Done { gpio }.into());
+        Ok(())
+    }
+
+    fn result(&mut self, gpio: usize) -> Result<Option<Capture>, Error> {
+        let done = &mut self.state.lock().unwrap().signals.done;
+        let i = done.iter().position(|x| x.0 == gpio).ok_or(Error::User)?;
+        Ok(done.swap_remove(i).1)
+    }
+}
+
+/// Simulated periodic signals on the GPIOs.
+///
+/// Signals are set from the command line with `signal <gpio> <frequency in Hz> <duty in percent>`
+/// and removed with `signal <gpio> off`.
+#[derive(Default)]
+pub struct Signals {
+    /// The period and high time in nanoseconds of the signal on each GPIO, if any.
+    signals: Vec<(usize, u64, u64)>,
+    /// The done measurements not yet read, by GPIO.
+    done: Vec<(usize, Option<Capture>)>,
+}
+
+impl Signals {
+    fn set(&mut self, gpio: usize, signal: Option<(u64, u64)>) {
+        self.signals.retain(|x| x.0 != gpio);
+        if let Some((period, high)) = signal {
+            self.signals.push((gpio, period, high));
+        }
+    }
+
+    /// Measures a signal from its edges starting at a given time.
+    fn measure(&self, gpio: usize, now: u64) -> Option<Capture> {
+        let &(_, period, high) = self.signals.iter().find(|x| x.0 == gpio)?;
+        analyze(&edges(period, high, now))
+    }
+}
+
+/// Returns the edges of a signal following a given time.
+fn edges(period: u64, high: u64, mut time: u64) -> Vec<(u64, bool)> {
+    let mut edges = Vec::with_capacity(NUM_EDGES);
+    while edges.len() < NUM_EDGES {
+        let phase = time % period;
+        let edge = match phase < high {
+            true => (time - phase + high, false),
+            false => (time - phase + period, true),
+        };
+        edges.push(edge);
+        time = edge.0;
+    }
+    edges
+}
+
+fn parse(args: &str) -> Option<(usize, Option<(u64, u64)>)> {
+    let args: Vec<_> = args.split_whitespace().collect();
+    match args[..] {
+        [gpio, "off"] => Some((gpio.parse().ok()?, None)),
+        [gpio, frequency, duty] => {
+            let frequency: u64 = frequency.parse().ok().filter(|&x| 0 < x && x <= 1_000_000)?;
+            let duty: u64 = duty.parse().ok().filter(|&x| 0 < x && x < 100)?;
+            let period = 1_000_000_000 / frequency;
+            Some((gpio.parse().ok()?, Some((period, period * duty / 100))))
+        }
+        _ => None,
+    }
+}
+
+/// Handles the arguments of the `signal` command.
+pub fn command(state: &mut State, args: &str) {
+    match parse(args) {
+        Some((gpio, signal)) => state.signals.set(gpio, signal),
+        None => println!("Usage: signal <gpio> (<frequency in Hz> <duty in percent> | off)"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measure_1khz_25_percent() {
+        let mut signals = Signals::default();
+        let (gpio, signal) = parse("3 1000 25").unwrap();
+        signals.set(gpio, signal);
+        for now in [0, 123_456_789, 250_000, 999_999] {
+            let capture = signals.measure(3, now).unwrap();
+            assert!((capture.frequency_hz() - 1000.).abs() < 1.);
+            assert!((capture.duty() - 0.25).abs() < 0.01);
+        }
+        assert_eq!(signals.measure(2, 0), None);
+        signals.set(3, parse("3 off").unwrap().1);
+        assert_eq!(signals.measure(3, 0), None);
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert_eq!(parse("3 1000"), None);
+        assert_eq!(parse("3 0 25"), None);
+        assert_eq!(parse("3 1000 100"), None);
+    }
+}
@@ -0,0 +1,45 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(feature = "test")]
+use std::time::Duration;
+
+use tokio::runtime::Handle;
+use wasefire_board_api::clock::Api;
+use wasefire_board_api::Error;
+
+use crate::board::Board;
+
+impl Api for &mut Board {
+    fn uptime_us(&mut self) -> Result<u64, Error> {
+        // This is the virtual time when the test feature is enabled.
+        Ok(self.state.lock().unwrap().start.elapsed().as_micros() as u64)
+    }
+}
+
+/// Advances the virtual clock, firing the timers that expire in the meantime.
+#[cfg(feature = "test")]
+pub fn command(runtime: &Handle, args: &str) {
+    match args.trim().parse() {
+        Ok(micros) => {
+            runtime.spawn(tokio::time::advance(Duration::from_micros(micros)));
+        }
+        Err(_) => println!("Usage: advance <microseconds> (unsigned 64-bit integer)"),
+    }
+}
+
+#[cfg(not(feature = "test"))]
+pub fn command(_: &Handle, _: &str) {
+    println!("The advance command needs the test feature (time is real otherwise).");
+}
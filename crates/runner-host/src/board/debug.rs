@@ -12,12 +12,222 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use wasefire_board_api as board;
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
 
-use crate::board::Board;
+use log::{LevelFilter, Log, Metadata, Record};
+use tokio::sync::mpsc::Sender;
+use wasefire_board_api::debug::{Effect, Fault, Level, QueueStats};
+use {wasefire_board_api as board, wasefire_logger as logger};
+
+use crate::board::{Board, State};
 
 impl board::debug::Api for &mut Board {
     fn exit(&mut self, success: bool) -> ! {
         std::process::exit(if success { 0 } else { 1 })
     }
+
+    fn shell_read(&mut self, line: &mut [u8]) -> Result<usize, board::Error> {
+        let shell = self.state.lock().unwrap().shell.clone();
+        Ok(shell.read(line))
+    }
+
+    fn shell_write(&mut self, output: &[u8]) -> Result<(), board::Error> {
+        // The state is not locked while writing, such that a slow client doesn't block the board.
+        let shell = self.state.lock().unwrap().shell.clone();
+        shell.write(output).map_err(|_| board::Error::World)
+    }
+
+    fn dry_run(&mut self) -> bool {
+        cfg!(feature = "dry-run")
+    }
+
+    fn record(&mut self, effect: Effect) {
+        self.state.lock().unwrap().effects.push(effect);
+    }
+
+    fn on_applet_fault(&mut self, fault: &Fault) {
+        eprintln!("{fault}");
+    }
+
+    fn set_log_level(&mut self, level: Level) -> Result<(), board::Error> {
+        let filter = match level {
+            Level::Error => LevelFilter::Error,
+            Level::Warn => LevelFilter::Warn,
+            Level::Info => LevelFilter::Info,
+            Level::Debug => LevelFilter::Debug,
+            Level::Trace => LevelFilter::Trace,
+        };
+        let reloadable = LOGGER.get().ok_or(board::Error::World)?;
+        // Module-specific directives of RUST_LOG are preserved.
+        let inner = env_logger::Builder::from_default_env().filter_level(filter).build();
+        log::set_max_level(inner.filter());
+        *reloadable.0.write().unwrap() = inner;
+        logger::set_level(level);
+        Ok(())
+    }
+    fn queue_stats(&mut self) -> Result<QueueStats, board::Error> {
+        Ok(QueueStats { depth: self.events.depth(), dropped: self.events.dropped() })
+    }
+}
+
+/// Logger configured by RUST_LOG whose filter can be replaced at runtime.
+struct Reloadable(RwLock<env_logger::Logger>);
+
+impl Log for Reloadable {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.0.read().unwrap().enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        self.0.read().unwrap().log(record)
+    }
+
+    fn flush(&self) {
+        self.0.read().unwrap().flush()
+    }
+}
+
+static LOGGER: OnceLock<Reloadable> = OnceLock::new();
+
+/// Installs the logger (similar to `env_logger::init()` but reloadable).
+pub fn init_logger() {
+    let inner = env_logger::Logger::from_default_env();
+    log::set_max_level(inner.filter());
+    log::set_logger(LOGGER.get_or_init(|| Reloadable(RwLock::new(inner)))).unwrap();
+}
+
+/// Prints the effects collected in dry-run mode.
+pub fn effects(state: &mut State) {
+    if !cfg!(feature = "dry-run") {
+        println!("Dry-run mode is disabled.");
+        return;
+    }
+    for effect in &state.effects {
+        println!("{effect:?}");
+    }
+    println!("{} effects", state.effects.len());
+}
+
+/// Asks the scheduler to shut down on Ctrl-C.
+///
+/// The scheduler finishes the running event handler and flushes the storage before exiting. A
+/// second Ctrl-C exits immediately.
+pub fn init_shutdown(state: &Arc<Mutex<State>>) {
+    let sender = state.lock().unwrap().sender.clone();
+    tokio::spawn(async move {
+        tokio::signal::ctrl_c().await.unwrap();
+        println!("Shutting down (press Ctrl-C again to force).");
+        let _ = sender.send(board::debug::Event::Shutdown.into()).await;
+        tokio::signal::ctrl_c().await.unwrap();
+        std::process::exit(1);
+    });
+}
+
+/// Debugging shell served on a TCP socket.
+///
+/// This is enabled with the `--shell-tcp <port>` option (requires the debug feature), such that the
+/// shell is used like the console serial of other boards, e.g. with `nc localhost <port>`. Only one
+/// connection is served at a time and the output is printed while there is no connection.
+#[derive(Clone, Default)]
+pub struct Shell(Arc<Mutex<ShellState>>);
+
+#[derive(Default)]
+struct ShellState {
+    /// Lines received and not yet read by the scheduler.
+    lines: VecDeque<String>,
+    /// The current connection, if any.
+    stream: Option<TcpStream>,
+}
+
+impl Shell {
+    /// Listens on a local TCP port and starts serving connections.
+    pub fn init(&self, sender: Sender<board::Event>, port: u16) -> std::io::Result<()> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+        println!("Shell is available at {}", listener.local_addr()?);
+        let shell = self.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                if let Err(e) = stream.and_then(|stream| shell.serve(stream, &sender)) {
+                    logger::warn!("Shell TCP connection error: {}", e);
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Forwards the lines received by a connection until it closes.
+    fn serve(&self, stream: TcpStream, sender: &Sender<board::Event>) -> std::io::Result<()> {
+        stream.set_nodelay(true)?;
+        self.0.lock().unwrap().stream = Some(stream.try_clone()?);
+        let mut result = Ok(());
+        for line in BufReader::new(stream).lines() {
+            match line {
+                Ok(line) => self.0.lock().unwrap().lines.push_back(line),
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            }
+            let _ = sender.try_send(board::debug::Event::Shell.into());
+        }
+        self.0.lock().unwrap().stream = None;
+        result
+    }
+
+    /// Reads the next pending line, truncated to the buffer length.
+    fn read(&self, line: &mut [u8]) -> usize {
+        let Some(x) = self.0.lock().unwrap().lines.pop_front() else { return 0 };
+        let x = x.trim_end_matches('\r');
+        let len = x.len().min(line.len());
+        line[.. len].copy_from_slice(&x.as_bytes()[.. len]);
+        len
+    }
+
+    /// Writes to the current connection, or prints if there is none.
+    fn write(&self, output: &[u8]) -> std::io::Result<()> {
+        let stream = self.0.lock().unwrap().stream.as_ref().map(|x| x.try_clone()).transpose()?;
+        match stream {
+            Some(mut stream) => stream.write_all(output),
+            None => {
+                print!("{}", String::from_utf8_lossy(output));
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc::channel;
+
+    use super::*;
+
+    #[test]
+    fn shell_round_trip() {
+        let shell = Shell::default();
+        let (sender, mut receiver) = channel(10);
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (stream, _) = listener.accept().unwrap();
+        let server = std::thread::spawn({
+            let shell = shell.clone();
+            move || shell.serve(stream, &sender)
+        });
+        client.write_all(b"leds\n").unwrap();
+        let event = receiver.blocking_recv().unwrap();
+        assert_eq!(event, board::debug::Event::Shell.into());
+        let mut line = [0; 16];
+        let len = shell.read(&mut line);
+        assert_eq!(&line[.. len], b"leds");
+        assert_eq!(shell.read(&mut line), 0);
+        shell.write(b"led 0: off\n").unwrap();
+        let mut reply = String::new();
+        BufReader::new(&client).read_line(&mut reply).unwrap();
+        assert_eq!(reply, "led 0: off\n");
+        drop(client);
+        server.join().unwrap().unwrap();
+    }
 }
@@ -0,0 +1,110 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use wasefire_board_api::encoder::{Api, Event, Tracker};
+use wasefire_board_api::Error;
+
+use crate::board::{Board, State};
+
+/// Number of steps per revolution of the simulated shafts.
+const STEPS_PER_REVOLUTION: i32 = 400;
+
+/// Angle in steps of the index pulse of the simulated shafts.
+const INDEX_ANGLE: i32 = 100;
+
+impl Api for &mut Board {
+    fn count(&mut self) -> usize {
+        self.state.lock().unwrap().encoders.0.len()
+    }
+
+    fn position(&mut self, encoder: usize) -> Result<i32, Error> {
+        let state = self.state.lock().unwrap();
+        let encoder = state.encoders.0.get(encoder).ok_or(Error::User)?;
+        Ok(encoder.tracker.position())
+    }
+
+    fn home(&mut self, encoder: usize) -> Result<(), Error> {
+        let mut state = self.state.lock().unwrap();
+        let encoder = state.encoders.0.get_mut(encoder).ok_or(Error::User)?;
+        encoder.tracker.home();
+        Ok(())
+    }
+}
+
+/// Simulated encoders.
+///
+/// Shafts are rotated from the command line with `encoder <encoder> <steps>`.
+#[derive(Default)]
+pub struct Encoders([Encoder; 1]);
+
+#[derive(Default)]
+struct Encoder {
+    /// The angle of the shaft in steps, in `0 .. STEPS_PER_REVOLUTION`.
+    angle: i32,
+    tracker: Tracker,
+}
+
+impl Encoder {
+    /// Rotates the shaft and returns whether homing completed.
+    fn rotate(&mut self, steps: i32) -> bool {
+        let mut homed = false;
+        for _ in 0 .. steps.unsigned_abs() {
+            self.angle = (self.angle + steps.signum()).rem_euclid(STEPS_PER_REVOLUTION);
+            self.tracker.count(steps.signum());
+            if self.angle == INDEX_ANGLE {
+                homed |= self.tracker.index();
+            }
+        }
+        homed
+    }
+}
+
+/// Handles the arguments of the `encoder` command.
+pub fn command(state: &mut State, args: &str) {
+    let args: Vec<_> = args.split_whitespace().collect();
+    let (i, steps) = match args[..] {
+        [i, steps] => match (i.parse::<usize>(), steps.parse::<i32>()) {
+            (Ok(i), Ok(steps)) => (i, steps),
+            _ => return usage(),
+        },
+        _ => return usage(),
+    };
+    let Some(encoder) = state.encoders.0.get_mut(i) else { return usage() };
+    if encoder.rotate(steps) {
+        let _ = state.sender.try_send(Event::Homed { encoder: i }.into());
+    }
+    println!("Encoder {i} is at position {}", encoder.tracker.position());
+}
+
+fn usage() {
+    println!("Usage: encoder <encoder> <steps>");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn homing_zeroes_at_index() {
+        let mut encoder = Encoder::default();
+        assert!(!encoder.rotate(150));
+        assert_eq!(encoder.tracker.position(), 150);
+        encoder.tracker.home();
+        assert!(!encoder.rotate(-30));
+        assert!(encoder.rotate(-30));
+        assert_eq!(encoder.tracker.position(), -10);
+        assert!(!encoder.rotate(STEPS_PER_REVOLUTION));
+        assert_eq!(encoder.tracker.position(), STEPS_PER_REVOLUTION - 10);
+    }
+}
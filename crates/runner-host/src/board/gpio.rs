@@ -0,0 +1,104 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use wasefire_board_api::gpio::{Api, Mode};
+use wasefire_board_api::Error;
+
+use crate::board::Board;
+
+const NUM_GPIOS: usize = 8;
+
+impl Api for &mut Board {
+    fn count(&mut self) -> usize {
+        NUM_GPIOS
+    }
+
+    fn configure(&mut self, gpio: usize, mode: Mode) -> Result<(), Error> {
+        self.state.lock().unwrap().gpios.configure(gpio, mode)
+    }
+
+    fn read(&mut self, gpio: usize) -> Result<bool, Error> {
+        self.state.lock().unwrap().gpios.read(gpio)
+    }
+
+    fn write(&mut self, gpio: usize, value: bool) -> Result<(), Error> {
+        self.state.lock().unwrap().gpios.write(gpio, value)
+    }
+}
+
+/// Simulated GPIOs.
+///
+/// Inputs read their pull resistor level and outputs read their last written level.
+pub struct Gpios {
+    modes: Vec<Option<Mode>>,
+    levels: Vec<bool>,
+}
+
+impl Default for Gpios {
+    fn default() -> Self {
+        Gpios { modes: vec![None; NUM_GPIOS], levels: vec![false; NUM_GPIOS] }
+    }
+}
+
+impl Gpios {
+    fn configure(&mut self, gpio: usize, mode: Mode) -> Result<(), Error> {
+        *self.modes.get_mut(gpio).ok_or(Error::User)? = Some(mode);
+        match mode {
+            Mode::InputPullUp => self.levels[gpio] = true,
+            Mode::InputPullDown => self.levels[gpio] = false,
+            Mode::OutputPushPull | Mode::OutputOpenDrain => (),
+        }
+        Ok(())
+    }
+
+    /// Returns whether a GPIO is configured as an input.
+    pub fn is_input(&self, gpio: usize) -> bool {
+        matches!(self.modes.get(gpio), Some(Some(mode)) if !mode.is_output())
+    }
+
+    fn read(&self, gpio: usize) -> Result<bool, Error> {
+        match self.modes.get(gpio) {
+            Some(Some(_)) => Ok(self.levels[gpio]),
+            _ => Err(Error::User),
+        }
+    }
+
+    fn write(&mut self, gpio: usize, value: bool) -> Result<(), Error> {
+        match self.modes.get(gpio) {
+            Some(Some(mode)) if mode.is_output() => self.levels[gpio] = value,
+            _ => return Err(Error::User),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_sequence() {
+        let mut gpios = Gpios::default();
+        assert!(gpios.write(2, true).is_err());
+        gpios.configure(2, Mode::OutputPushPull).unwrap();
+        for value in [true, false, false, true] {
+            gpios.write(2, value).unwrap();
+            assert_eq!(gpios.levels, [false, false, value, false, false, false, false, false]);
+        }
+        gpios.configure(2, Mode::InputPullUp).unwrap();
+        assert!(gpios.write(2, false).is_err());
+        assert!(matches!(gpios.read(2), Ok(true)));
+        assert!(gpios.read(NUM_GPIOS).is_err());
+    }
+}
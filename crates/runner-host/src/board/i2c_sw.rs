@@ -0,0 +1,286 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use wasefire_board_api::gpio::Api as _;
+use wasefire_board_api::i2c_sw::{Api, Config, Lines, Master};
+use wasefire_board_api::{Api as _, Error};
+
+use crate::board::{Board, State};
+
+impl Api for &mut Board {
+    fn configure(&mut self, config: &Config) -> Result<(), Error> {
+        let count = self.gpio().count();
+        let Config { sda, scl, frequency_hz } = *config;
+        if count <= sda || count <= scl || sda == scl || frequency_hz == 0 {
+            return Err(Error::User);
+        }
+        self.state.lock().unwrap().i2c_sw.config = Some(*config);
+        Ok(())
+    }
+
+    fn read(&mut self, addr: u8, data: &mut [u8]) -> Result<(), Error> {
+        self.state.lock().unwrap().i2c_sw.master()?.read(addr, data)
+    }
+
+    fn write(&mut self, addr: u8, data: &[u8]) -> Result<(), Error> {
+        self.state.lock().unwrap().i2c_sw.master()?.write(addr, data)
+    }
+
+    fn write_read(&mut self, addr: u8, write: &[u8], read: &mut [u8]) -> Result<(), Error> {
+        self.state.lock().unwrap().i2c_sw.master()?.write_read(addr, write, read)
+    }
+}
+
+/// Software I2C bus with simulated devices.
+///
+/// The host GPIOs are not electrically simulated, so the controller is bit-banged on simulated bus
+/// lines instead. Devices are added from the command line with `i2c <addr> <hex bytes>` where both
+/// the address and the bytes are hexadecimal. Devices behave like register-based memories: the
+/// first written byte sets the register address, subsequent bytes are written from there, and reads
+/// continue from the register address.
+#[derive(Default)]
+pub struct I2cSw {
+    config: Option<Config>,
+    bus: Bus,
+}
+
+impl I2cSw {
+    fn master(&mut self) -> Result<Master<&mut Bus>, Error> {
+        self.config.ok_or(Error::User)?;
+        Ok(Master(&mut self.bus))
+    }
+}
+
+#[derive(Default)]
+struct Device {
+    memory: Vec<u8>,
+    register: usize,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Phase {
+    /// Waiting for a start condition.
+    Idle,
+    /// Receiving the address byte.
+    Address,
+    /// Receiving bytes from the controller.
+    Receive { first: bool },
+    /// Sending bytes to the controller.
+    Send,
+}
+
+/// Simulated open-drain lines with the devices reacting to their edges.
+struct Bus {
+    devices: HashMap<u8, Device>,
+    /// Levels driven by the controller.
+    scl: bool,
+    sda: bool,
+    /// Level driven by the devices on the data line.
+    device_sda: bool,
+    phase: Phase,
+    /// Address of the selected device.
+    addr: u8,
+    /// Number of clock pulses in the current byte (including the acknowledge bit).
+    bits: u8,
+    byte: u8,
+    /// Whether the controller acknowledged the last sent byte.
+    ack: bool,
+}
+
+impl Default for Bus {
+    fn default() -> Self {
+        Bus {
+            devices: HashMap::new(),
+            scl: true,
+            sda: true,
+            device_sda: true,
+            phase: Phase::Idle,
+            addr: 0,
+            bits: 0,
+            byte: 0,
+            ack: false,
+        }
+    }
+}
+
+impl Bus {
+    fn line_sda(&self) -> bool {
+        self.sda && self.device_sda
+    }
+
+    fn device(&mut self) -> &mut Device {
+        self.devices.get_mut(&self.addr).unwrap()
+    }
+
+    fn rising(&mut self) {
+        self.bits += 1;
+        let bit = self.line_sda();
+        match self.phase {
+            Phase::Address | Phase::Receive { .. } if self.bits <= 8 => {
+                self.byte = self.byte << 1 | bit as u8
+            }
+            Phase::Send if self.bits == 9 => self.ack = !bit,
+            _ => (),
+        }
+    }
+
+    fn falling(&mut self) {
+        match (self.phase, self.bits) {
+            (Phase::Idle, _) => (),
+            (Phase::Address, 8) => {
+                self.addr = self.byte >> 1;
+                if !self.devices.contains_key(&self.addr) {
+                    self.phase = Phase::Idle;
+                    return;
+                }
+                self.phase = match self.byte & 1 {
+                    0 => Phase::Receive { first: true },
+                    _ => Phase::Send,
+                };
+                self.device_sda = false;
+            }
+            (Phase::Receive { first }, 8) => {
+                let byte = self.byte;
+                let device = self.device();
+                match first {
+                    true => device.register = byte as usize,
+                    false => {
+                        if let Some(x) = device.memory.get_mut(device.register) {
+                            *x = byte;
+                        }
+                        device.register += 1;
+                    }
+                }
+                self.phase = Phase::Receive { first: false };
+                self.device_sda = false;
+            }
+            (Phase::Send, 8) => self.device_sda = true,
+            (Phase::Send, 9) if !self.ack => self.phase = Phase::Idle,
+            (_, 9) => {
+                self.bits = 0;
+                self.device_sda = true;
+                if self.phase == Phase::Send {
+                    let device = self.device();
+                    let byte = device.memory.get(device.register).copied().unwrap_or(0xff);
+                    device.register += 1;
+                    self.byte = byte;
+                }
+            }
+            (Phase::Send, _) => (),
+            _ => return,
+        }
+        if self.phase == Phase::Send && self.bits < 8 {
+            self.device_sda = self.byte >> (7 - self.bits) & 1 == 1;
+        }
+    }
+}
+
+impl Lines for &mut Bus {
+    fn set_sda(&mut self, high: bool) -> Result<(), Error> {
+        let before = self.line_sda();
+        self.sda = high;
+        let after = self.line_sda();
+        if self.scl && before != after {
+            // Start and stop conditions.
+            self.phase = match after {
+                false => Phase::Address,
+                true => Phase::Idle,
+            };
+            self.bits = 0;
+            self.byte = 0;
+            self.device_sda = true;
+        }
+        Ok(())
+    }
+
+    fn set_scl(&mut self, high: bool) -> Result<(), Error> {
+        match (self.scl, high) {
+            (false, true) => self.rising(),
+            (true, false) => self.falling(),
+            _ => (),
+        }
+        self.scl = high;
+        Ok(())
+    }
+
+    fn get_sda(&mut self) -> Result<bool, Error> {
+        Ok(self.line_sda())
+    }
+
+    fn get_scl(&mut self) -> Result<bool, Error> {
+        Ok(self.scl)
+    }
+
+    fn delay(&mut self) {}
+}
+
+fn parse(args: &str) -> Option<(u8, Vec<u8>)> {
+    let (addr, data) = args.split_once(' ')?;
+    let addr = u8::from_str_radix(addr, 16).ok().filter(|&x| x < 0x80)?;
+    let data = data.trim();
+    if data.len() % 2 != 0 {
+        return None;
+    }
+    let data = (0 .. data.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(data.get(i .. i + 2)?, 16).ok())
+        .collect::<Option<_>>()?;
+    Some((addr, data))
+}
+
+/// Handles the arguments of the `i2c` command.
+pub fn command(state: &mut State, args: &str) {
+    match parse(args) {
+        Some((addr, memory)) => {
+            state.i2c_sw.bus.devices.insert(addr, Device { memory, register: 0 });
+        }
+        None => println!("Usage: i2c <hex addr> <hex bytes>"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bus() -> I2cSw {
+        let mut i2c = I2cSw::default();
+        let (addr, memory) = parse("50 0011223344556677").unwrap();
+        i2c.bus.devices.insert(addr, Device { memory, register: 0 });
+        i2c.config = Some(Config { sda: 0, scl: 1, frequency_hz: 100_000 });
+        i2c
+    }
+
+    #[test]
+    fn write_read_registers() {
+        let mut i2c = bus();
+        let mut data = [0; 3];
+        i2c.master().unwrap().write_read(0x50, &[2], &mut data).unwrap();
+        assert_eq!(data, [0x22, 0x33, 0x44]);
+        i2c.master().unwrap().write(0x50, &[6, 0xaa, 0xbb]).unwrap();
+        i2c.master().unwrap().read(0x50, &mut data).unwrap();
+        assert_eq!(data, [0xff, 0xff, 0xff]);
+        i2c.master().unwrap().write_read(0x50, &[5], &mut data).unwrap();
+        assert_eq!(data, [0x55, 0xaa, 0xbb]);
+    }
+
+    #[test]
+    fn missing_device() {
+        let mut i2c = bus();
+        assert!(matches!(i2c.master().unwrap().write(0x51, &[0]), Err(Error::World)));
+        i2c.config = None;
+        assert!(matches!(i2c.master(), Err(Error::User)));
+    }
+}
@@ -12,28 +12,64 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-const NUM_LEDS: usize = 1;
-
 use wasefire_board_api as board;
 
+const NUM_LEDS: usize = 1;
+
 impl board::led::Api for &mut crate::board::Board {
     fn count(&mut self) -> usize {
         NUM_LEDS
     }
 
     fn get(&mut self, led: usize) -> Result<bool, board::Error> {
-        if NUM_LEDS <= led {
-            return Err(board::Error::User);
-        }
-        Ok(self.state.lock().unwrap().led)
+        Ok(self.state.lock().unwrap().leds.brightness(led)? != 0)
     }
 
     fn set(&mut self, led: usize, on: bool) -> Result<(), board::Error> {
-        if NUM_LEDS <= led {
-            return Err(board::Error::User);
+        self.set_brightness(led, if on { u8::MAX } else { 0 })
+    }
+
+    fn set_brightness(&mut self, led: usize, brightness: u8) -> Result<(), board::Error> {
+        self.state.lock().unwrap().leds.set_brightness(led, brightness)?;
+        match brightness {
+            0 => println!("Led {led} is off"),
+            u8::MAX => println!("Led {led} is on"),
+            _ => println!("Led {led} is at {brightness}/255"),
         }
-        println!("Led {led} is {}", if on { "on" } else { "off" });
-        self.state.lock().unwrap().led = on;
         Ok(())
     }
 }
+
+/// Simulated LEDs.
+///
+/// The last brightness of each LED is recorded.
+#[derive(Default)]
+pub struct Leds {
+    brightness: [u8; NUM_LEDS],
+}
+
+impl Leds {
+    pub fn brightness(&self, led: usize) -> Result<u8, board::Error> {
+        self.brightness.get(led).copied().ok_or(board::Error::User)
+    }
+
+    fn set_brightness(&mut self, led: usize, brightness: u8) -> Result<(), board::Error> {
+        *self.brightness.get_mut(led).ok_or(board::Error::User)? = brightness;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_brightness() {
+        let mut leds = Leds::default();
+        assert_eq!(leds.brightness(0).unwrap(), 0);
+        leds.set_brightness(0, 42).unwrap();
+        assert_eq!(leds.brightness(0).unwrap(), 42);
+        assert!(matches!(leds.set_brightness(NUM_LEDS, 1), Err(board::Error::User)));
+        assert!(matches!(leds.brightness(NUM_LEDS), Err(board::Error::User)));
+    }
+}
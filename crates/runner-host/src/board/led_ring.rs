@@ -0,0 +1,136 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Simulated LED ring.
+//!
+//! The animation is run from a task like on a real board, and the frames are recorded instead of
+//! being written to pixels.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use wasefire_board_api::led_ring::{frame, Animation, Api, Color};
+use wasefire_board_api::Error;
+use wasefire_logger as logger;
+
+use crate::board::Board;
+
+/// Number of simulated pixels.
+const COUNT: usize = 12;
+
+/// Number of recorded frames before the oldest ones are dropped.
+const HISTORY: usize = 1024;
+
+impl Api for &mut Board {
+    fn count(&mut self) -> usize {
+        COUNT
+    }
+
+    fn animate(&mut self, animation: &Animation) -> Result<(), Error> {
+        let mut state = self.state.lock().unwrap();
+        let now = state.start.elapsed().as_micros() as u64;
+        state.led_ring.start(*animation, now);
+        if let Some(period) = animation.period_us() {
+            let state_ = self.state.clone();
+            state.led_ring.handle = Some(tokio::spawn(async move {
+                let mut interval = tokio::time::interval(Duration::from_micros(period));
+                interval.tick().await;
+                loop {
+                    interval.tick().await;
+                    let mut state = state_.lock().unwrap();
+                    let now = state.start.elapsed().as_micros() as u64;
+                    state.led_ring.tick(now);
+                }
+            }));
+        }
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<(), Error> {
+        self.state.lock().unwrap().led_ring.stop();
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct LedRing {
+    /// The current animation and when it started.
+    animation: Option<(Animation, u64)>,
+    handle: Option<JoinHandle<()>>,
+    /// The recorded frames, most recent last.
+    frames: VecDeque<[Color; COUNT]>,
+}
+
+impl LedRing {
+    fn start(&mut self, animation: Animation, now: u64) {
+        self.abort();
+        self.animation = Some((animation, now));
+        self.tick(now);
+    }
+
+    fn stop(&mut self) {
+        self.abort();
+        self.animation = None;
+        self.record([[0; 3]; COUNT]);
+    }
+
+    fn abort(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            handle.abort();
+        }
+    }
+
+    fn tick(&mut self, now: u64) {
+        let Some((animation, start)) = self.animation else { return };
+        let mut pixels = [[0; 3]; COUNT];
+        frame(&animation, now - start, &mut pixels);
+        self.record(pixels);
+    }
+
+    /// Records a frame if it differs from the previous one.
+    fn record(&mut self, pixels: [Color; COUNT]) {
+        if self.frames.back() == Some(&pixels) {
+            return;
+        }
+        logger::debug!("LED ring: {:?}", pixels);
+        if self.frames.len() == HISTORY {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(pixels);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wasefire_board_api::led_ring::Pattern;
+
+    use super::*;
+
+    #[test]
+    fn spinner_advances() {
+        let mut ring = LedRing::default();
+        let spinner = Animation { pattern: Pattern::Spinner, speed: 4, color: [0, 0, 255] };
+        ring.start(spinner, 1_000_000);
+        for ms in (1_000 .. 2_000).step_by(50) {
+            ring.tick(ms * 1000);
+        }
+        let lit: Vec<_> = ring.frames.iter().map(|x| x.iter().position(|x| x != &[0; 3])).collect();
+        assert_eq!(lit, [Some(0), Some(1), Some(2), Some(3)]);
+        ring.tick(3_000_000);
+        ring.stop();
+        assert_eq!(ring.frames[4][8], [0, 0, 255]);
+        assert_eq!(ring.frames[5], [[0; 3]; COUNT]);
+    }
+}
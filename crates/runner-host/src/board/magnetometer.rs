@@ -0,0 +1,65 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use wasefire_board_api::magnetometer::Api;
+use wasefire_board_api::Error;
+
+use crate::board::{Board, State};
+
+impl Api for &mut Board {
+    fn is_supported(&mut self) -> bool {
+        true
+    }
+
+    fn read(&mut self) -> Result<[i16; 3], Error> {
+        Ok(self.state.lock().unwrap().magnetometer.field)
+    }
+}
+
+/// Simulated magnetometer.
+///
+/// The field is set from the command line with `mag <x> <y> <z>`.
+#[derive(Default)]
+pub struct Magnetometer {
+    field: [i16; 3],
+}
+
+impl Magnetometer {
+    fn parse(args: &str) -> Option<[i16; 3]> {
+        let mut axes = args.split_whitespace().map(|x| x.parse().ok());
+        let field = [axes.next()??, axes.next()??, axes.next()??];
+        axes.next().is_none().then_some(field)
+    }
+}
+
+/// Handles the arguments of the `mag` command.
+pub fn command(state: &mut State, args: &str) {
+    match Magnetometer::parse(args) {
+        Some(field) => state.magnetometer.field = field,
+        None => println!("Usage: mag <x> <y> <z> (signed 16-bit integers)"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_field() {
+        assert_eq!(Magnetometer::parse("120 -80 -400"), Some([120, -80, -400]));
+        assert_eq!(Magnetometer::parse("1 2"), None);
+        assert_eq!(Magnetometer::parse("1 2 3 4"), None);
+        assert_eq!(Magnetometer::parse("1 2 40000"), None);
+    }
+}
@@ -0,0 +1,91 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{ensure, Result};
+use wasefire_board_api::platform::{Api, HeapStats};
+use wasefire_board_api::rng::Api as _;
+use wasefire_board_api::store::{self, Api as _};
+use wasefire_board_api::{Api as _, Error};
+
+use crate::board::Board;
+
+/// Environment variable overriding the device ID as 16 hexadecimal digits.
+const DEVICE_ID_VAR: &str = "WASEFIRE_DEVICE_ID";
+
+/// Key of the device ID in the platform key-value store.
+const DEVICE_ID_KEY: &[u8] = b"device-id";
+
+impl Api for &mut Board {
+    fn reset(&mut self) -> Result<(), Error> {
+        // Storage writes go directly to the file, so there's nothing to flush.
+        std::process::exit(0)
+    }
+
+    fn heap_stats(&mut self) -> Result<HeapStats, Error> {
+        // The host uses the system allocator which doesn't have a fixed heap.
+        Ok(HeapStats::default())
+    }
+
+    fn storage_key(&mut self) -> Result<[u8; 32], Error> {
+        // The host has no device secret, so the storage is only encrypted for testing.
+        Ok(*b"wasefire host storage test key!!")
+    }
+
+    fn device_id(&mut self) -> Result<[u8; 8], Error> {
+        if let Some(id) = self.state.lock().unwrap().device_id {
+            return Ok(id);
+        }
+        // The host has no unique ID, so we generate one on first use and persist it.
+        if let Some(id) = store::Platform::new(&mut **self).get(DEVICE_ID_KEY)? {
+            return id.try_into().map_err(|_| Error::World);
+        }
+        let mut id = [0; 8];
+        self.rng().fill_bytes(&mut id)?;
+        store::Platform::new(&mut **self).insert(DEVICE_ID_KEY, &id)?;
+        Ok(id)
+    }
+}
+
+/// Returns the device ID override from the environment, if any.
+pub fn device_id_from_env() -> Result<Option<[u8; 8]>> {
+    let Ok(id) = std::env::var(DEVICE_ID_VAR) else { return Ok(None) };
+    Ok(Some(parse_device_id(&id)?))
+}
+
+fn parse_device_id(id: &str) -> Result<[u8; 8]> {
+    let valid = id.len() == 16 && id.bytes().all(|x| x.is_ascii_hexdigit());
+    ensure!(valid, "{DEVICE_ID_VAR} must be 16 hexadecimal digits");
+    Ok(u64::from_str_radix(id, 16)?.to_be_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_device_id_valid() {
+        let id = parse_device_id("0123456789abcDEF").unwrap();
+        assert_eq!(id, [0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef]);
+    }
+
+    #[test]
+    fn parse_device_id_invalid() {
+        // Too short, too long, signed, and not hexadecimal.
+        const INVALID: &[&str] =
+            &["0123456789abcde", "0123456789abcdef0", "+123456789abcdef", "0123456789abcdeg"];
+        for id in INVALID {
+            assert!(parse_device_id(id).is_err(), "{id:?}");
+        }
+    }
+}
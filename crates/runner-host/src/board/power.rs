@@ -0,0 +1,98 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+
+use wasefire_board_api::power::{Api, Peripheral};
+use wasefire_board_api::Error;
+use wasefire_logger as logger;
+
+use crate::board::timer::Timers;
+use crate::board::Board;
+
+impl Api for &mut Board {
+    fn is_busy(&mut self, peripheral: Peripheral) -> Result<bool, Error> {
+        is_busy(&self.state.lock().unwrap().timers, peripheral)
+    }
+
+    fn disable_peripheral(&mut self, peripheral: Peripheral) -> Result<(), Error> {
+        let mut state = self.state.lock().unwrap();
+        let state = &mut *state;
+        state.power.disable(&state.timers, peripheral)
+    }
+
+    fn enable_peripheral(&mut self, peripheral: Peripheral) -> Result<(), Error> {
+        let mut state = self.state.lock().unwrap();
+        let state = &mut *state;
+        is_busy(&state.timers, peripheral)?;
+        state.power.enable(peripheral);
+        Ok(())
+    }
+}
+
+/// Simulated peripheral power.
+///
+/// Disabling a peripheral is only logged since the host doesn't save power.
+#[derive(Default)]
+pub struct Power {
+    disabled: HashSet<Peripheral>,
+}
+
+impl Power {
+    fn disable(&mut self, timers: &Timers, peripheral: Peripheral) -> Result<(), Error> {
+        if is_busy(timers, peripheral)? {
+            return Err(Error::User);
+        }
+        if self.disabled.insert(peripheral) {
+            logger::info!("Disabling {:?} (no-op on host).", peripheral);
+        }
+        Ok(())
+    }
+
+    fn enable(&mut self, peripheral: Peripheral) {
+        if self.disabled.remove(&peripheral) {
+            logger::info!("Enabling {:?} (no-op on host).", peripheral);
+        }
+    }
+}
+
+fn is_busy(timers: &Timers, peripheral: Peripheral) -> Result<bool, Error> {
+    match peripheral {
+        Peripheral::Timer(i) => timers.is_armed(i).ok_or(Error::User),
+        _ => Ok(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc::channel;
+    use wasefire_board_api::timer::Mode;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn disable_busy_timer() {
+        let (sender, _receiver) = channel(10);
+        let mut timers = Timers::default();
+        timers.arm(0, std::time::Duration::from_secs(10), Mode::OneShot, sender).unwrap();
+        let mut power = Power::default();
+        assert!(matches!(power.disable(&timers, Peripheral::Timer(0)), Err(Error::User)));
+        assert!(power.disable(&timers, Peripheral::Timer(1)).is_ok());
+        assert!(power.disable(&timers, Peripheral::Usb).is_ok());
+        assert!(matches!(power.disable(&timers, Peripheral::Timer(5)), Err(Error::User)));
+        assert_eq!(power.disabled.len(), 2);
+        power.enable(Peripheral::Timer(1));
+        assert_eq!(power.disabled.len(), 1);
+    }
+}
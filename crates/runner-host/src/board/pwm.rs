@@ -0,0 +1,79 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Simulated PWM outputs.
+//!
+//! The last configuration of each output is recorded instead of driving a pin.
+
+use wasefire_board_api::pwm::Api;
+use wasefire_board_api::Error;
+use wasefire_logger as logger;
+
+use crate::board::Board;
+
+impl Api for &mut Board {
+    fn count(&mut self) -> usize {
+        self.state.lock().unwrap().pwms.0.len()
+    }
+
+    fn configure(&mut self, pwm: usize, freq_hz: u32, duty_pct: u8) -> Result<(), Error> {
+        self.state.lock().unwrap().pwms.configure(pwm, freq_hz, duty_pct)
+    }
+
+    fn stop(&mut self, pwm: usize) -> Result<(), Error> {
+        self.state.lock().unwrap().pwms.stop(pwm)
+    }
+}
+
+/// The last frequency and duty cycle of each output, or `None` if stopped.
+#[derive(Default)]
+pub struct Pwms([Option<(u32, u8)>; 4]);
+
+impl Pwms {
+    fn configure(&mut self, pwm: usize, freq_hz: u32, duty_pct: u8) -> Result<(), Error> {
+        let output = self.0.get_mut(pwm).ok_or(Error::User)?;
+        if freq_hz == 0 || 100 < duty_pct {
+            return Err(Error::User);
+        }
+        logger::debug!("PWM {}: {} Hz at {}%", pwm, freq_hz, duty_pct);
+        *output = Some((freq_hz, duty_pct));
+        Ok(())
+    }
+
+    fn stop(&mut self, pwm: usize) -> Result<(), Error> {
+        let output = self.0.get_mut(pwm).ok_or(Error::User)?;
+        logger::debug!("PWM {}: stopped", pwm);
+        *output = None;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_last_configuration() {
+        let mut pwms = Pwms::default();
+        assert!(pwms.configure(1, 440, 50).is_ok());
+        assert!(pwms.configure(1, 50, 100).is_ok());
+        assert_eq!(pwms.0[1], Some((50, 100)));
+        assert!(pwms.configure(1, 0, 50).is_err());
+        assert!(pwms.configure(1, 440, 101).is_err());
+        assert!(pwms.configure(4, 440, 50).is_err());
+        assert_eq!(pwms.0[1], Some((50, 100)));
+        assert!(pwms.stop(1).is_ok());
+        assert_eq!(pwms.0, [None; 4]);
+    }
+}
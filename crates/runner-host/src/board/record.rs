@@ -0,0 +1,405 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Recording and replay of board events.
+//!
+//! With `--record <file>`, the button, timer, UART, and USB serial events are appended to a file
+//! (one JSON object per line) with their time in microseconds since startup. With `--replay
+//! <file>`, those events are fed back to the scheduler at the same times while live events are
+//! dropped. Once all recorded events are replayed, live events are processed again.
+//!
+//! The bytes read by the applet from the UART and USB serial are recorded too (as hexadecimal), such
+//! that read events are replayed with their payload. While replaying, reads return the recorded
+//! payloads in order instead of the live input.
+
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use wasefire_board_api::usb::serial;
+use wasefire_board_api::{button, timer, uart, usb, Error, Event};
+use wasefire_logger as logger;
+use wasefire_scheduler::{Events, OverflowPolicy};
+
+use crate::board::Board;
+
+#[derive(Default)]
+pub enum Recording {
+    /// Events are neither recorded nor replayed.
+    #[default]
+    Off,
+
+    /// Events are appended to a file.
+    Record(File),
+
+    /// Events are fed back from a file.
+    Replay(Replay),
+}
+
+impl Recording {
+    /// Records the events to a file (which is truncated).
+    pub fn record(path: &Path) -> Result<Self> {
+        let file = File::create(path).with_context(|| format!("creating {}", path.display()))?;
+        Ok(Recording::Record(file))
+    }
+
+    /// Replays the events recorded in a file.
+    pub fn replay(path: &Path) -> Result<Self> {
+        let content =
+            std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        let mut pending = VecDeque::new();
+        let mut reads = HashMap::<Source, VecDeque<Vec<u8>>>::new();
+        for (i, line) in content.lines().enumerate() {
+            let (time, entry) = decode(line)
+                .with_context(|| format!("invalid event at {}:{}", path.display(), i + 1))?;
+            match entry {
+                Entry::Event(event) => pending.push_back((time, event)),
+                Entry::Read(source, data) => reads.entry(source).or_default().push_back(data),
+            }
+        }
+        // Recorded events were already coalesced, so they are replayed as is.
+        let queue = Events::new(pending.len(), OverflowPolicy::DropNewest);
+        Ok(Recording::Replay(Replay { pending, queue, reads }))
+    }
+}
+
+/// Where the applet reads bytes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Source {
+    Uart(usize),
+    Serial(usize),
+}
+
+/// A recorded line.
+#[derive(Debug, PartialEq)]
+enum Entry {
+    Event(Event),
+    /// The bytes returned by a read.
+    Read(Source, Vec<u8>),
+}
+
+pub struct Replay {
+    /// Recorded events and their time, not yet due.
+    pending: VecDeque<(u64, Event)>,
+    /// Recorded events that are due.
+    queue: Events,
+    /// Recorded reads not yet replayed, in order.
+    reads: HashMap<Source, VecDeque<Vec<u8>>>,
+}
+
+impl Replay {
+    /// Returns when the next event is due, or `None` if all events were replayed.
+    fn next_time(&self) -> Option<u64> {
+        match self.queue.is_empty() {
+            true => self.pending.front().map(|x| x.0),
+            false => Some(0),
+        }
+    }
+
+    /// Returns the next event due at a given time.
+    fn pop(&mut self, now: u64) -> Option<Event> {
+        while self.pending.front().map_or(false, |x| x.0 <= now) {
+            self.queue.push(self.pending.pop_front().unwrap().1);
+        }
+        self.queue.pop()
+    }
+
+    /// Replays the next read from a source into some buffers.
+    ///
+    /// Returns `None` if live reads should be used instead, i.e. once everything was replayed.
+    fn read(&mut self, source: Source, outputs: &mut [&mut [u8]]) -> Option<usize> {
+        let Some(data) = self.reads.get_mut(&source).and_then(|x| x.pop_front()) else {
+            // Live input is dropped until all events are replayed.
+            return self.next_time().map(|_| 0);
+        };
+        let mut rest = &data[..];
+        for output in outputs.iter_mut() {
+            let len = std::cmp::min(output.len(), rest.len());
+            output[.. len].copy_from_slice(&rest[.. len]);
+            rest = &rest[len ..];
+        }
+        if !rest.is_empty() {
+            // The applet reads with smaller buffers than when recording.
+            self.reads.get_mut(&source).unwrap().push_front(rest.to_vec());
+        }
+        Some(data.len() - rest.len())
+    }
+}
+
+impl Board {
+    /// Returns the next event if any, recording or replaying it if enabled.
+    pub fn next_event(&mut self) -> Option<Event> {
+        let now = self.now_us();
+        if let Recording::Replay(replay) = &mut self.recording {
+            if replay.next_time().is_some() {
+                // Live events are dropped while replaying, except debugging ones (e.g. shutdown).
+                while let Ok(event) = self.receiver.try_recv() {
+                    if matches!(event, Event::Debug(_)) {
+                        return Some(event);
+                    }
+                }
+                return replay.pop(now);
+            }
+        }
+        while let Ok(event) = self.receiver.try_recv() {
+            self.events.push(event);
+        }
+        let event = self.events.pop()?;
+        self.record_event(&event);
+        Some(event)
+    }
+
+    /// Waits for the next event, recording or replaying it if enabled.
+    pub fn wait_next_event(&mut self) -> Event {
+        while let Recording::Replay(replay) = &self.recording {
+            let Some(time) = replay.next_time() else { break };
+            let now = self.now_us();
+            if now < time {
+                std::thread::sleep(Duration::from_micros(time - now));
+            }
+            if let Some(event) = self.next_event() {
+                return event;
+            }
+        }
+        if let Some(event) = self.next_event() {
+            return event;
+        }
+        let event = self.receiver.blocking_recv().unwrap();
+        self.record_event(&event);
+        event
+    }
+
+    /// Reads from a source, recording or replaying the payload if enabled.
+    ///
+    /// The closure does the live read into the buffers and returns the number of bytes read.
+    pub fn read_payload(
+        &mut self, source: Source, outputs: &mut [&mut [u8]],
+        read: impl FnOnce(&mut Self, &mut [&mut [u8]]) -> Result<usize, Error>,
+    ) -> Result<usize, Error> {
+        if let Recording::Replay(replay) = &mut self.recording {
+            if let Some(len) = replay.read(source, outputs) {
+                return Ok(len);
+            }
+        }
+        let len = read(self, outputs)?;
+        if matches!(self.recording, Recording::Record(_)) {
+            let data: Vec<u8> = outputs.iter().flat_map(|x| x.iter()).take(len).copied().collect();
+            self.record(encode_read(source, &data));
+        }
+        Ok(len)
+    }
+
+    fn record_event(&mut self, event: &Event) {
+        if let Some(event) = encode(event) {
+            self.record(event);
+        }
+    }
+
+    fn record(&mut self, fields: String) {
+        let time = self.now_us();
+        let Recording::Record(file) = &mut self.recording else { return };
+        if let Err(e) = writeln!(file, "{{\"time_us\":{time},{fields}}}") {
+            logger::warn!("Failed to record event: {}", e);
+        }
+    }
+
+    fn now_us(&self) -> u64 {
+        self.state.lock().unwrap().start.elapsed().as_micros() as u64
+    }
+}
+
+/// Encodes the fields of a recordable event.
+fn encode(event: &Event) -> Option<String> {
+    Some(match event {
+        Event::Button(button::Event { button, pressed }) => {
+            format!("\"event\":\"button\",\"button\":{button},\"pressed\":{pressed}")
+        }
+        Event::Timer(timer::Event { timer }) => format!("\"event\":\"timer\",\"timer\":{timer}"),
+        Event::Uart(uart::Event { uart, direction }) => {
+            let direction = match direction {
+                uart::Direction::Read => "read",
+                uart::Direction::Write => "write",
+            };
+            format!("\"event\":\"uart\",\"uart\":{uart},\"direction\":\"{direction}\"")
+        }
+        Event::Usb(usb::Event::Serial { port, event }) => {
+            let kind = match event {
+                serial::Event::Read => "read",
+                serial::Event::Write => "write",
+                serial::Event::Control => "control",
+                serial::Event::Coding => "coding",
+            };
+            format!("\"event\":\"serial\",\"port\":{port},\"kind\":\"{kind}\"")
+        }
+        _ => return None,
+    })
+}
+
+/// Encodes the fields of a recorded read.
+fn encode_read(source: Source, data: &[u8]) -> String {
+    let data: String = data.iter().map(|x| format!("{x:02x}")).collect();
+    match source {
+        Source::Uart(uart) => {
+            format!("\"event\":\"uart_read\",\"uart\":{uart},\"data\":\"{data}\"")
+        }
+        Source::Serial(port) => {
+            format!("\"event\":\"serial_read\",\"port\":{port},\"data\":\"{data}\"")
+        }
+    }
+}
+
+/// Decodes a recorded line and its time.
+///
+/// Only the flat objects produced by [`encode()`] and [`encode_read()`] are supported.
+fn decode(line: &str) -> Option<(u64, Entry)> {
+    let fields = line.trim().strip_prefix('{')?.strip_suffix('}')?;
+    let mut fields = fields
+        .split(',')
+        .map(|x| {
+            let (key, value) = x.split_once(':')?;
+            Some((key.trim().trim_matches('"'), value.trim().trim_matches('"')))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let mut get = |name: &str| {
+        let i = fields.iter().position(|x| x.0 == name)?;
+        Some(fields.swap_remove(i).1)
+    };
+    let time = get("time_us")?.parse().ok()?;
+    let event = match get("event")? {
+        "button" => {
+            let button = get("button")?.parse().ok()?;
+            let pressed = get("pressed")?.parse().ok()?;
+            button::Event { button, pressed }.into()
+        }
+        "timer" => timer::Event { timer: get("timer")?.parse().ok()? }.into(),
+        "uart" => {
+            let uart = get("uart")?.parse().ok()?;
+            let direction = match get("direction")? {
+                "read" => uart::Direction::Read,
+                "write" => uart::Direction::Write,
+                _ => return None,
+            };
+            uart::Event { uart, direction }.into()
+        }
+        "serial" => {
+            let port = get("port")?.parse().ok()?;
+            let event = match get("kind")? {
+                "read" => serial::Event::Read,
+                "write" => serial::Event::Write,
+                "control" => serial::Event::Control,
+                "coding" => serial::Event::Coding,
+                _ => return None,
+            };
+            event.on(port)
+        }
+        "uart_read" => {
+            let source = Source::Uart(get("uart")?.parse().ok()?);
+            return read_entry(time, source, get("data")?, fields.is_empty());
+        }
+        "serial_read" => {
+            let source = Source::Serial(get("port")?.parse().ok()?);
+            return read_entry(time, source, get("data")?, fields.is_empty());
+        }
+        _ => return None,
+    };
+    fields.is_empty().then_some((time, Entry::Event(event)))
+}
+
+fn read_entry(time: u64, source: Source, data: &str, done: bool) -> Option<(u64, Entry)> {
+    if !done || data.len() % 2 != 0 {
+        return None;
+    }
+    let data = (0 .. data.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(data.get(i .. i + 2)?, 16).ok())
+        .collect::<Option<_>>()?;
+    Some((time, Entry::Read(source, data)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_decode_roundtrip() {
+        let events: [Event; 4] = [
+            button::Event { button: 1, pressed: true }.into(),
+            timer::Event { timer: 3 }.into(),
+            uart::Event { uart: 0, direction: uart::Direction::Write }.into(),
+            serial::Event::Control.on(1),
+        ];
+        for (time, event) in events.into_iter().enumerate() {
+            let line = format!("{{\"time_us\":{time},{}}}", encode(&event).unwrap());
+            assert_eq!(decode(&line), Some((time as u64, Entry::Event(event))));
+        }
+    }
+
+    #[test]
+    fn encode_decode_read() {
+        for source in [Source::Uart(0), Source::Serial(1)] {
+            for data in [&[][..], &b"hello\r\n"[..]] {
+                let line = format!("{{\"time_us\":7,{}}}", encode_read(source, data));
+                assert_eq!(decode(&line), Some((7, Entry::Read(source, data.to_vec()))));
+            }
+        }
+        assert_eq!(decode(r#"{"time_us":5,"event":"uart_read","uart":0,"data":"123"}"#), None);
+        assert_eq!(decode(r#"{"time_us":5,"event":"uart_read","uart":0,"data":"zz"}"#), None);
+    }
+
+    #[test]
+    fn decode_rejects_unknown_fields() {
+        assert_eq!(decode(r#"{"time_us":5,"event":"timer","timer":2}"#).unwrap().0, 5);
+        assert_eq!(decode(r#"{"time_us":5,"event":"timer","timer":2,"x":1}"#), None);
+        assert_eq!(decode(r#"{"time_us":5,"event":"gesture"}"#), None);
+    }
+
+    #[test]
+    fn replay_pops_due_events() {
+        let pending = VecDeque::from([
+            (10, timer::Event { timer: 0 }.into()),
+            (10, timer::Event { timer: 1 }.into()),
+            (20, timer::Event { timer: 0 }.into()),
+        ]);
+        let queue = Events::new(3, OverflowPolicy::DropNewest);
+        let mut replay = Replay { queue, pending, reads: HashMap::new() };
+        assert_eq!(replay.next_time(), Some(10));
+        assert_eq!(replay.pop(5), None);
+        assert_eq!(replay.pop(15), Some(timer::Event { timer: 0 }.into()));
+        assert_eq!(replay.next_time(), Some(0));
+        assert_eq!(replay.pop(15), Some(timer::Event { timer: 1 }.into()));
+        assert_eq!(replay.pop(15), None);
+        assert_eq!(replay.pop(20), Some(timer::Event { timer: 0 }.into()));
+        assert_eq!(replay.next_time(), None);
+    }
+
+    #[test]
+    fn replay_reads_payloads() {
+        let pending = VecDeque::from([(10, serial::Event::Read.on(0))]);
+        let reads = HashMap::from([(Source::Serial(0), VecDeque::from([b"hello".to_vec()]))]);
+        let queue = Events::new(1, OverflowPolicy::DropNewest);
+        let mut replay = Replay { queue, pending, reads };
+        let mut buffer = [0; 3];
+        assert_eq!(replay.read(Source::Uart(0), &mut [&mut buffer]), Some(0));
+        assert_eq!(replay.read(Source::Serial(0), &mut [&mut buffer]), Some(3));
+        assert_eq!(&buffer, b"hel");
+        assert_eq!(replay.read(Source::Serial(0), &mut [&mut buffer]), Some(2));
+        assert_eq!(&buffer[.. 2], b"lo");
+        assert_eq!(replay.read(Source::Serial(0), &mut [&mut buffer]), Some(0));
+        assert_eq!(replay.pop(10), Some(serial::Event::Read.on(0)));
+        assert_eq!(replay.read(Source::Serial(0), &mut [&mut buffer]), None);
+    }
+}
@@ -12,12 +12,77 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use rand::RngCore;
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
 use wasefire_board_api as board;
+use wasefire_board_api::rng::RngHealth;
+
+/// Environment variable to seed the random number generator.
+const SEED_VAR: &str = "WASEFIRE_RNG_SEED";
+
+/// Random number generator.
+///
+/// By default, randomness comes from the thread-local generator of the host. If the
+/// `WASEFIRE_RNG_SEED` environment variable is set to an integer at startup, randomness comes from
+/// a generator seeded with it instead, such that runs are reproducible.
+#[derive(Default)]
+pub struct Rng {
+    seeded: Option<StdRng>,
+}
+
+impl Rng {
+    /// Creates a random number generator according to the environment.
+    pub fn from_env() -> Self {
+        match std::env::var(SEED_VAR) {
+            Ok(seed) => {
+                let seed = seed.parse().unwrap_or_else(|_| panic!("{SEED_VAR} must be a u64"));
+                println!("Using deterministic randomness with seed {seed}.");
+                Rng::seeded(seed)
+            }
+            Err(_) => Rng::default(),
+        }
+    }
+
+    /// Creates a deterministic random number generator.
+    pub fn seeded(seed: u64) -> Self {
+        Rng { seeded: Some(StdRng::seed_from_u64(seed)) }
+    }
+
+    fn fill_bytes(&mut self, buffer: &mut [u8]) {
+        match &mut self.seeded {
+            Some(rng) => rng.fill_bytes(buffer),
+            None => rand::thread_rng().fill_bytes(buffer),
+        }
+    }
+}
 
 impl board::rng::Api for &mut crate::board::Board {
     fn fill_bytes(&mut self, buffer: &mut [u8]) -> Result<(), board::Error> {
-        rand::thread_rng().fill_bytes(buffer);
+        self.state.lock().unwrap().rng.fill_bytes(buffer);
         Ok(())
     }
+
+    fn health_check(&mut self) -> Result<RngHealth, board::Error> {
+        Ok(RngHealth::Healthy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeded_is_reproducible() {
+        let mut x = Rng::seeded(42);
+        let mut y = Rng::seeded(42);
+        let mut z = Rng::seeded(43);
+        let mut output = [[0; 32]; 3];
+        for _ in 0 .. 3 {
+            x.fill_bytes(&mut output[0]);
+            y.fill_bytes(&mut output[1]);
+            z.fill_bytes(&mut output[2]);
+            assert_eq!(output[0], output[1]);
+            assert_ne!(output[0], output[2]);
+        }
+    }
 }
@@ -0,0 +1,99 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::ops::DerefMut;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::mpsc::Sender;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use wasefire_board_api::stepper::{Api, Event, Motion, Profile};
+use wasefire_board_api::{self as board, Error};
+
+use crate::board::Board;
+
+impl Api for &mut Board {
+    fn count(&mut self) -> usize {
+        self.state.lock().unwrap().steppers.0.len()
+    }
+
+    fn move_to(&mut self, i: usize, position: i32, profile: &Profile) -> Result<(), Error> {
+        let mut state = self.state.lock().unwrap();
+        let sender = state.sender.clone();
+        let state = state.deref_mut();
+        let stepper = state.steppers.0.get_mut(i).ok_or(Error::User)?;
+        if !profile.is_valid() || stepper.handle.as_ref().map_or(false, |x| !x.is_finished()) {
+            return Err(Error::User);
+        }
+        let motion = Motion::new(stepper.position.load(Ordering::SeqCst), position, profile);
+        let current = stepper.position.clone();
+        stepper.handle = Some(tokio::spawn(simulate(i, motion, current, sender)));
+        Ok(())
+    }
+
+    fn position(&mut self, i: usize) -> Result<i32, Error> {
+        let state = self.state.lock().unwrap();
+        let stepper = state.steppers.0.get(i).ok_or(Error::User)?;
+        Ok(stepper.position.load(Ordering::SeqCst))
+    }
+}
+
+#[derive(Default)]
+pub struct Steppers([Stepper; 1]);
+
+#[derive(Default)]
+pub struct Stepper {
+    position: Arc<AtomicI32>,
+    handle: Option<JoinHandle<()>>,
+}
+
+/// Simulates a move by advancing the position over time.
+async fn simulate(
+    stepper: usize, mut motion: Motion, position: Arc<AtomicI32>, sender: Sender<board::Event>,
+) {
+    // Sleeping has millisecond resolution, so we sleep until the deadline of each step to avoid
+    // accumulating rounding errors.
+    let mut deadline = Instant::now();
+    while let Some(delay) = motion.delay_us() {
+        deadline += Duration::from_micros(delay as u64);
+        tokio::time::sleep_until(deadline).await;
+        motion.step();
+        position.store(motion.position(), Ordering::SeqCst);
+    }
+    println!("Stepper {stepper} is at position {}", motion.position());
+    let _ = sender.try_send(Event::Done { stepper }.into());
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc::channel;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn move_reaches_target_and_emits_done() {
+        let (sender, mut receiver) = channel(10);
+        let position = Arc::new(AtomicI32::new(0));
+        let profile = Profile { max_speed: 2000, acceleration: 100000 };
+        let motion = Motion::new(0, 100, &profile);
+        let start = Instant::now();
+        simulate(0, motion, position.clone(), sender).await;
+        // Moving 100 steps at 2000 steps per second takes at least 50 milliseconds.
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        assert_eq!(position.load(Ordering::SeqCst), 100);
+        assert_eq!(receiver.try_recv().unwrap(), Event::Done { stepper: 0 }.into());
+    }
+}
@@ -0,0 +1,50 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use wasefire_board_api::temp::Api;
+use wasefire_board_api::Error;
+
+use crate::board::{Board, State};
+
+impl Api for &mut Board {
+    fn is_supported(&mut self) -> bool {
+        true
+    }
+
+    fn read(&mut self) -> Result<i32, Error> {
+        Ok(self.state.lock().unwrap().temp.millidegrees)
+    }
+}
+
+/// Simulated temperature sensor.
+///
+/// The temperature is 25°C by default and can be set from the command line with `temp
+/// <millidegrees>`.
+pub struct Temp {
+    millidegrees: i32,
+}
+
+impl Default for Temp {
+    fn default() -> Self {
+        Temp { millidegrees: 25000 }
+    }
+}
+
+/// Handles the arguments of the `temp` command.
+pub fn command(state: &mut State, args: &str) {
+    match args.trim().parse() {
+        Ok(millidegrees) => state.temp.millidegrees = millidegrees,
+        Err(_) => println!("Usage: temp <millidegrees> (signed 32-bit integer)"),
+    }
+}
@@ -15,9 +15,10 @@
 use std::ops::DerefMut;
 use std::time::Duration;
 
+use tokio::sync::mpsc::Sender;
 use tokio::task::JoinHandle;
-use wasefire_board_api::timer::{Api, Command, Event};
-use wasefire_board_api::Error;
+use wasefire_board_api::timer::{Api, Event, Mode};
+use wasefire_board_api::{self as board, Error};
 
 use crate::board::Board;
 
@@ -26,41 +27,20 @@ impl Api for &mut Board {
         self.state.lock().unwrap().timers.0.len()
     }
 
-    fn arm(&mut self, i: usize, command: &Command) -> Result<(), Error> {
+    fn arm(&mut self, i: usize, duration_us: usize, mode: Mode) -> Result<(), Error> {
         let mut state = self.state.lock().unwrap();
         let sender = state.sender.clone();
         let state = state.deref_mut();
-        let timer = state.timers.0.get_mut(i).ok_or(Error::User)?;
-        if timer.handle.is_some() {
-            return Err(Error::User);
-        }
-        let duration = Duration::from_millis(command.duration_ms as u64);
-        if command.periodic {
-            timer.handle = Some(tokio::spawn(async move {
-                let mut interval = tokio::time::interval(duration);
-                interval.tick().await;
-                loop {
-                    interval.tick().await;
-                    let _ = sender.try_send(Event { timer: i }.into());
-                }
-            }));
-        } else {
-            timer.handle = Some(tokio::spawn(async move {
-                tokio::time::sleep(duration).await;
-                let _ = sender.try_send(Event { timer: i }.into());
-            }));
-        }
-        Ok(())
+        state.timers.arm(i, Duration::from_micros(duration_us as u64), mode, sender)
     }
 
     fn disarm(&mut self, i: usize) -> Result<(), Error> {
         let mut state = self.state.lock().unwrap();
         let timer = state.timers.0.get_mut(i).ok_or(Error::User)?;
-        match &timer.handle {
+        match timer.handle.take() {
             Some(handle) => handle.abort(),
             None => return Err(Error::User),
         }
-        timer.handle = None;
         Ok(())
     }
 }
@@ -68,7 +48,107 @@ impl Api for &mut Board {
 #[derive(Default)]
 pub struct Timers([Timer; 5]);
 
+impl Timers {
+    /// Arms a timer.
+    pub fn arm(
+        &mut self, i: usize, duration: Duration, mode: Mode, sender: Sender<board::Event>,
+    ) -> Result<(), Error> {
+        self.0.get_mut(i).ok_or(Error::User)?.arm(i, duration, mode, sender)
+    }
+
+    /// Returns whether a timer is armed, or `None` if there is no such timer.
+    pub fn is_armed(&self, i: usize) -> Option<bool> {
+        let timer = self.0.get(i)?;
+        Some(timer.handle.as_ref().map_or(false, |x| !x.is_finished()))
+    }
+}
+
 #[derive(Default)]
 pub struct Timer {
     handle: Option<JoinHandle<()>>,
 }
+
+impl Timer {
+    fn arm(
+        &mut self, timer: usize, duration: Duration, mode: Mode, sender: Sender<board::Event>,
+    ) -> Result<(), Error> {
+        // A one-shot timer is disarmed once it triggered.
+        if self.handle.as_ref().map_or(false, |x| !x.is_finished()) {
+            return Err(Error::User);
+        }
+        self.handle = Some(match mode {
+            Mode::Periodic => tokio::spawn(async move {
+                let mut interval = tokio::time::interval(duration);
+                interval.tick().await;
+                loop {
+                    interval.tick().await;
+                    let _ = sender.try_send(Event { timer }.into());
+                }
+            }),
+            Mode::OneShot => tokio::spawn(async move {
+                tokio::time::sleep(duration).await;
+                let _ = sender.try_send(Event { timer }.into());
+            }),
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc::channel;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn one_shot_triggers_once() {
+        let (sender, mut receiver) = channel(10);
+        let mut timer = Timer::default();
+        timer.arm(3, Duration::from_millis(5), Mode::OneShot, sender).unwrap();
+        let event = receiver.recv().await.unwrap();
+        assert_eq!(event, Event { timer: 3 }.into());
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(receiver.try_recv().is_err());
+    }
+
+    /// Advances the virtual clock and lets the woken timers run.
+    #[cfg(feature = "test")]
+    async fn advance(millis: u64) {
+        tokio::time::advance(Duration::from_millis(millis)).await;
+        tokio::task::yield_now().await;
+    }
+
+    #[cfg(feature = "test")]
+    #[tokio::test(start_paused = true)]
+    async fn periodic_follows_virtual_time() {
+        let (sender, mut receiver) = channel(10);
+        let mut timer = Timer::default();
+        timer.arm(1, Duration::from_millis(10), Mode::Periodic, sender).unwrap();
+        tokio::task::yield_now().await;
+        for _ in 0 .. 3 {
+            advance(9).await;
+            assert!(receiver.try_recv().is_err());
+            advance(1).await;
+            assert_eq!(receiver.try_recv().unwrap(), Event { timer: 1 }.into());
+        }
+    }
+
+    #[cfg(feature = "test")]
+    #[tokio::test(start_paused = true)]
+    async fn timers_fire_in_deadline_order() {
+        let (sender, mut receiver) = channel(10);
+        let mut timers = Timers::default();
+        timers.arm(0, Duration::from_millis(30), Mode::OneShot, sender.clone()).unwrap();
+        timers.arm(1, Duration::from_millis(10), Mode::OneShot, sender.clone()).unwrap();
+        timers.arm(2, Duration::from_millis(20), Mode::OneShot, sender).unwrap();
+        tokio::task::yield_now().await;
+        for timer in [1, 2, 0] {
+            assert!(receiver.try_recv().is_err());
+            advance(10).await;
+            assert_eq!(receiver.try_recv().unwrap(), Event { timer }.into());
+            assert_eq!(timers.is_armed(timer), Some(false));
+        }
+        advance(100).await;
+        assert!(receiver.try_recv().is_err());
+    }
+}
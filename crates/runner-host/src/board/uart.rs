@@ -0,0 +1,196 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! UART bridged to a pseudo-terminal.
+//!
+//! The path of the terminal is printed at startup, such that `screen` or `minicom` can attach to
+//! it. The baud rate is ignored.
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{ErrorKind, Read, Write};
+use std::os::fd::{AsRawFd, FromRawFd};
+use std::sync::{Arc, Mutex};
+
+use nix::fcntl::{fcntl, FcntlArg, OFlag};
+use nix::poll::{poll, PollFd, PollFlags};
+use nix::pty::openpty;
+use nix::sys::termios::{cfmakeraw, tcgetattr, tcsetattr, SetArg};
+use nix::unistd::ttyname;
+use wasefire_board_api::uart::{Api, Direction, Event};
+use wasefire_board_api::Error;
+use wasefire_logger as logger;
+
+use crate::board::record::Source;
+use crate::board::{Board, State};
+
+impl Api for &mut Board {
+    fn count(&mut self) -> usize {
+        1
+    }
+
+    fn set_baudrate(&mut self, uart: usize, baudrate: u32) -> Result<(), Error> {
+        check(uart)?;
+        logger::debug!("UART baud rate is {}", baudrate);
+        Ok(())
+    }
+
+    fn read(&mut self, uart: usize, output: &mut [u8]) -> Result<usize, Error> {
+        check(uart)?;
+        self.read_payload(Source::Uart(uart), &mut [output], |board, outputs| {
+            Ok(board.state.lock().unwrap().uart.read(outputs[0]))
+        })
+    }
+
+    fn write(&mut self, uart: usize, input: &[u8]) -> Result<usize, Error> {
+        check(uart)?;
+        let mut state = self.state.lock().unwrap();
+        let Some(master) = &mut state.uart.master else { return Ok(input.len()) };
+        let len = match master.write(input) {
+            Ok(len) => len,
+            Err(e) if e.kind() == ErrorKind::WouldBlock => return Ok(0),
+            Err(_) => return Err(Error::World),
+        };
+        if state.uart.write_enabled {
+            let _ = state.sender.try_send(Event { uart, direction: Direction::Write }.into());
+        }
+        Ok(len)
+    }
+
+    fn flush(&mut self, uart: usize) -> Result<(), Error> {
+        check(uart)?;
+        match &mut self.state.lock().unwrap().uart.master {
+            Some(master) => master.flush().map_err(|_| Error::World),
+            None => Ok(()),
+        }
+    }
+
+    fn enable(&mut self, uart: usize, direction: Direction) -> Result<(), Error> {
+        check(uart)?;
+        let mut state = self.state.lock().unwrap();
+        state.uart.set(direction, true);
+        if direction == Direction::Write {
+            let _ = state.sender.try_send(Event { uart, direction }.into());
+        }
+        Ok(())
+    }
+
+    fn disable(&mut self, uart: usize, direction: Direction) -> Result<(), Error> {
+        check(uart)?;
+        self.state.lock().unwrap().uart.set(direction, false);
+        Ok(())
+    }
+}
+
+fn check(uart: usize) -> Result<(), Error> {
+    match uart {
+        0 => Ok(()),
+        _ => Err(Error::User),
+    }
+}
+
+#[derive(Default)]
+pub struct Uart {
+    read_enabled: bool,
+    write_enabled: bool,
+    /// Bytes received from the terminal and not yet read by the applet.
+    inbound: VecDeque<u8>,
+    /// The master side of the pseudo-terminal, if it could be opened.
+    ///
+    /// Without terminal, writes are dropped and there's nothing to read.
+    master: Option<File>,
+}
+
+impl Uart {
+    /// Opens the pseudo-terminal and starts forwarding what it receives.
+    pub fn init(state: Arc<Mutex<State>>) {
+        let master = match open() {
+            Ok(x) => x,
+            Err(e) => {
+                logger::warn!("Failed to open the UART pseudo-terminal: {}", e);
+                return;
+            }
+        };
+        state.lock().unwrap().uart.master = Some(master.try_clone().unwrap());
+        std::thread::spawn(move || {
+            if let Err(e) = forward(master, &state) {
+                logger::warn!("UART pseudo-terminal error: {}", e);
+            }
+        });
+    }
+
+    fn read(&mut self, output: &mut [u8]) -> usize {
+        let len = std::cmp::min(output.len(), self.inbound.len());
+        for (x, y) in output.iter_mut().zip(self.inbound.drain(.. len)) {
+            *x = y;
+        }
+        len
+    }
+
+    fn set(&mut self, direction: Direction, enabled: bool) {
+        match direction {
+            Direction::Read => self.read_enabled = enabled,
+            Direction::Write => self.write_enabled = enabled,
+        }
+    }
+}
+
+/// Opens a raw pseudo-terminal and returns its non-blocking master side.
+fn open() -> nix::Result<File> {
+    let pty = openpty(None, None)?;
+    let mut termios = tcgetattr(pty.slave)?;
+    cfmakeraw(&mut termios);
+    tcsetattr(pty.slave, SetArg::TCSANOW, &termios)?;
+    fcntl(pty.master, FcntlArg::F_SETFL(OFlag::O_NONBLOCK))?;
+    println!("UART is available at {}", ttyname(pty.slave)?.display());
+    // We leak the slave side such that the master side doesn't hang up while no terminal is
+    // attached.
+    Ok(unsafe { File::from_raw_fd(pty.master) })
+}
+
+/// Forwards the bytes received by the master side until it fails.
+fn forward(mut master: File, state: &Mutex<State>) -> std::io::Result<()> {
+    let mut buffer = [0; 256];
+    loop {
+        let mut fds = [PollFd::new(master.as_raw_fd(), PollFlags::POLLIN)];
+        poll(&mut fds, -1)?;
+        let len = match master.read(&mut buffer) {
+            Ok(len) => len,
+            Err(e) if e.kind() == ErrorKind::WouldBlock => continue,
+            Err(e) => return Err(e),
+        };
+        let mut state = state.lock().unwrap();
+        state.uart.inbound.extend(&buffer[.. len]);
+        if state.uart.read_enabled {
+            let _ = state.sender.try_send(Event { uart: 0, direction: Direction::Read }.into());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_drains_inbound() {
+        let mut uart = Uart::default();
+        uart.inbound.extend(b"hello");
+        let mut output = [0; 3];
+        assert_eq!(uart.read(&mut output), 3);
+        assert_eq!(&output, b"hel");
+        assert_eq!(uart.read(&mut output), 2);
+        assert_eq!(&output[.. 2], b"lo");
+        assert_eq!(uart.read(&mut output), 0);
+    }
+}
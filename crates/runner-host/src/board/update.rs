@@ -0,0 +1,140 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Simulated firmware updates.
+//!
+//! The host has no firmware to replace, so updates are simulated with files. The staging bank is
+//! `staging.bin` and the active firmware is `firmware.bin`. Applying an update marks the staging
+//! bank active and exits, and the next start swaps both banks. The rollback counter is stored in
+//! `firmware.version`.
+
+use std::os::unix::fs::FileExt;
+
+use anyhow::{Context, Result};
+use wasefire_board_api::platform::Api as _;
+use wasefire_board_api::update::{Api, Bank, Staged};
+use wasefire_board_api::{Api as _, Error, Unsupported};
+use wasefire_logger as logger;
+
+use crate::board::Board;
+
+const FIRMWARE: &str = "../../target/firmware.bin";
+const STAGING: &str = "../../target/staging.bin";
+/// Holds the image length when the staging bank is active.
+const ACTIVE: &str = "../../target/staging.active";
+/// Holds the rollback counter.
+const VERSION: &str = "../../target/firmware.version";
+const BANK_SIZE: usize = 0x40000;
+
+/// Environment variable holding the Ed25519 public key of firmware updates as 64 hexadecimal
+/// digits. Firmware updates are not supported when unset.
+const KEY_VAR: &str = "WASEFIRE_FIRMWARE_KEY";
+
+impl Api for &mut Board {
+    fn is_supported(&mut self) -> bool {
+        self.state.lock().unwrap().update.is_some()
+    }
+
+    fn min_version(&mut self) -> u32 {
+        staged(self, |x| Ok(x.min_version())).unwrap_or(0)
+    }
+
+    fn start(&mut self) -> Result<(), Error> {
+        staged(self, |x| x.start())
+    }
+
+    fn write(&mut self, offset: usize, chunk: &[u8]) -> Result<(), Error> {
+        staged(self, |x| x.write(offset, chunk))
+    }
+
+    fn finalize(&mut self, signature: &[u8; 64]) -> Result<(), Error> {
+        staged(self, |x| x.finalize(&mut Unsupported, signature))
+    }
+
+    fn apply(&mut self) -> Result<(), Error> {
+        staged(self, |x| x.apply())?;
+        self.platform().reset()
+    }
+}
+
+fn staged<T>(
+    board: &mut Board, f: impl FnOnce(&mut Staged<FileBank>) -> Result<T, Error>,
+) -> Result<T, Error> {
+    f(board.state.lock().unwrap().update.as_mut().ok_or(Error::User)?)
+}
+
+/// Returns the firmware update protocol if supported.
+pub fn new() -> Result<Option<Staged<FileBank>>> {
+    let Ok(key) = std::env::var(KEY_VAR) else { return Ok(None) };
+    let key: Option<Vec<u8>> = (0 .. key.len())
+        .step_by(2)
+        .map(|i| key.get(i .. i + 2).and_then(|x| u8::from_str_radix(x, 16).ok()))
+        .collect();
+    let key = key.and_then(|x| x.try_into().ok());
+    let key = key.with_context(|| format!("{KEY_VAR} must be 64 hexadecimal digits"))?;
+    let mut data = std::fs::read(STAGING).unwrap_or_default();
+    data.resize(BANK_SIZE, 0xff);
+    let version = match std::fs::read_to_string(VERSION) {
+        Ok(x) => x.parse().with_context(|| format!("invalid {VERSION}"))?,
+        Err(_) => 0,
+    };
+    Ok(Some(Staged::new(FileBank { data, version }, key)))
+}
+
+/// Swaps the staging bank in if it was applied before the last exit.
+pub fn swap() -> Result<()> {
+    let Ok(length) = std::fs::read_to_string(ACTIVE) else { return Ok(()) };
+    let length: usize = length.parse().with_context(|| format!("invalid {ACTIVE}"))?;
+    let mut staging = std::fs::read(STAGING)?;
+    staging.truncate(length);
+    let firmware = std::fs::read(FIRMWARE).unwrap_or_default();
+    std::fs::write(FIRMWARE, staging)?;
+    std::fs::write(STAGING, firmware)?;
+    std::fs::remove_file(ACTIVE)?;
+    logger::info!("Applied firmware update of {} bytes.", length);
+    Ok(())
+}
+
+/// Staging bank backed by a file.
+pub struct FileBank {
+    data: Vec<u8>,
+    version: u32,
+}
+
+impl Bank for FileBank {
+    fn read(&self) -> &[u8] {
+        &self.data
+    }
+
+    fn version(&self) -> u32 {
+        self.version
+    }
+
+    fn erase(&mut self) -> Result<(), Error> {
+        self.data.fill(0xff);
+        std::fs::write(STAGING, &self.data).map_err(|_| Error::World)
+    }
+
+    fn write(&mut self, offset: usize, chunk: &[u8]) -> Result<(), Error> {
+        self.data[offset ..][.. chunk.len()].copy_from_slice(chunk);
+        let file = std::fs::OpenOptions::new().write(true).open(STAGING);
+        file.and_then(|x| x.write_all_at(chunk, offset as u64)).map_err(|_| Error::World)
+    }
+
+    fn activate(&mut self, length: usize, version: u32) -> Result<(), Error> {
+        std::fs::write(VERSION, version.to_string()).map_err(|_| Error::World)?;
+        self.version = version;
+        std::fs::write(ACTIVE, length.to_string()).map_err(|_| Error::World)
+    }
+}
@@ -12,88 +12,126 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::process::{Child, Command};
-use std::sync::{Arc, Mutex};
-use std::time::Duration;
-
-use board::usb::serial::{HasSerial, WithSerial};
-use usb_device::class_prelude::UsbBusAllocator;
-use usb_device::prelude::{UsbDevice, UsbDeviceBuilder, UsbVidPid};
-use usb_device::UsbError;
-use usbd_serial::{SerialPort, USB_CLASS_CDC};
-use usbip_device::UsbIpBus;
 use wasefire_board_api as board;
-use wasefire_board_api::usb::serial::Serial;
+use wasefire_board_api::usb::serial::{Api as _, Event, LineCoding, LineState};
+use wasefire_board_api::Error;
 
-use crate::board::{Board, State};
+use crate::board::record::Source;
+use crate::board::Board;
+
+pub mod hid;
+pub mod tcp;
+#[cfg(feature = "usb")]
+mod usbip;
+
+#[cfg(feature = "usb")]
+pub use self::usbip::Usb;
 
 impl board::usb::Api for &mut Board {
-    type Serial<'a> = WithSerial<&'a mut Board>
+    type Hid<'a> = &'a mut Board
+    where Self: 'a;
+    fn hid(&mut self) -> Self::Hid<'_> {
+        self
+    }
+
+    type Serial<'a> = Serial<'a>
     where Self: 'a;
     fn serial(&mut self) -> Self::Serial<'_> {
-        WithSerial(self)
+        Serial::new(self)
     }
 }
 
-impl HasSerial for &mut Board {
-    type UsbBus = UsbIpBus;
+/// USB serial, either bridged to a TCP socket or over USB/IP.
+pub enum Serial<'a> {
+    /// Bridged to a TCP socket with the `--serial-tcp <port>` option.
+    Tcp(&'a mut Board),
+    #[cfg(feature = "usb")]
+    Usb(board::usb::serial::WithSerial<&'a mut Board>),
+    #[cfg(not(feature = "usb"))]
+    Unsupported(board::Unsupported),
+}
+
+impl<'a> Serial<'a> {
+    fn new(board: &'a mut Board) -> Self {
+        if board.state.lock().unwrap().serial_tcp.is_some() {
+            Serial::Tcp(board)
+        } else {
+            Self::usb(board)
+        }
+    }
+
+    #[cfg(feature = "usb")]
+    fn usb(board: &'a mut Board) -> Self {
+        Serial::Usb(board::usb::serial::WithSerial(board))
+    }
 
-    fn with_serial<R>(&mut self, f: impl FnOnce(&mut Serial<Self::UsbBus>) -> R) -> R {
-        f(&mut self.state.lock().unwrap().usb.serial)
+    #[cfg(not(feature = "usb"))]
+    fn usb(_: &'a mut Board) -> Self {
+        Serial::Unsupported(board::Unsupported)
     }
 }
 
-pub struct Usb {
-    pub serial: Serial<'static, UsbIpBus>,
-    pub usb_dev: UsbDevice<'static, UsbIpBus>,
+macro_rules! dispatch {
+    ($self:ident, $x:ident => $e:expr) => {
+        match $self {
+            Serial::Tcp($x) => $e,
+            #[cfg(feature = "usb")]
+            Serial::Usb($x) => $e,
+            #[cfg(not(feature = "usb"))]
+            Serial::Unsupported($x) => $e,
+        }
+    };
 }
 
-impl Default for Usb {
-    fn default() -> Self {
-        let usb_bus = Box::leak(Box::new(UsbBusAllocator::new(UsbIpBus::new())));
-        let serial = Serial::new(SerialPort::new(usb_bus));
-        let usb_dev = UsbDeviceBuilder::new(usb_bus, UsbVidPid(0x16c0, 0x27dd))
-            .product("Serial port")
-            .device_class(USB_CLASS_CDC)
-            .build();
-        Self { serial, usb_dev }
+impl board::usb::serial::Api for Serial<'_> {
+    fn count(&mut self) -> usize {
+        dispatch!(self, x => x.count())
+    }
+
+    fn read(&mut self, port: usize, output: &mut [u8]) -> Result<usize, Error> {
+        self.read_into(port, &mut [output])
     }
-}
 
-impl Usb {
-    pub fn init(state: Arc<Mutex<State>>) {
-        assert_eq!(spawn(&["sudo", "modprobe", "vhci-hcd"]).wait().unwrap().code().unwrap(), 0);
-        let mut usbip = spawn(&["sudo", "usbip", "attach", "-r", "localhost", "-b", "1-1"]);
-        loop {
-            state.lock().unwrap().usb.poll();
-            match usbip.try_wait().unwrap() {
-                None => continue,
-                Some(e) => assert_eq!(e.code().unwrap(), 0),
+    fn read_into(&mut self, port: usize, outputs: &mut [&mut [u8]]) -> Result<usize, Error> {
+        let source = Source::Serial(port);
+        match self {
+            Serial::Tcp(x) => {
+                x.read_payload(source, outputs, |mut x, outputs| x.read_into(port, outputs))
             }
-            break;
+            #[cfg(feature = "usb")]
+            Serial::Usb(x) => x.0.read_payload(source, outputs, |x, outputs| {
+                board::usb::serial::WithSerial(x).read_into(port, outputs)
+            }),
+            #[cfg(not(feature = "usb"))]
+            Serial::Unsupported(x) => x.read_into(port, outputs),
         }
-        tokio::spawn({
-            async move {
-                loop {
-                    tokio::time::sleep(Duration::from_millis(1)).await;
-                    let mut state = state.lock().unwrap();
-                    let polled = state.usb.poll()
-                        && !matches!(
-                            state.usb.serial.port().read(&mut []),
-                            Err(UsbError::WouldBlock)
-                        );
-                    let State { sender, usb: Usb { serial, .. }, .. } = &mut *state;
-                    serial.tick(polled, |event| drop(sender.try_send(event.into())));
-                }
-            }
-        });
     }
 
-    pub fn poll(&mut self) -> bool {
-        self.usb_dev.poll(&mut [self.serial.port()])
+    fn write(&mut self, port: usize, input: &[u8]) -> Result<usize, Error> {
+        dispatch!(self, x => x.write(port, input))
+    }
+
+    fn flush(&mut self, port: usize) -> Result<(), Error> {
+        dispatch!(self, x => x.flush(port))
+    }
+
+    fn flush_timeout(&mut self, port: usize, duration_us: u64) -> Result<(), Error> {
+        dispatch!(self, x => x.flush_timeout(port, duration_us))
     }
-}
 
-fn spawn(cmd: &[&str]) -> Child {
-    Command::new(cmd[0]).args(&cmd[1 ..]).spawn().unwrap()
+    fn line_state(&mut self, port: usize) -> Result<LineState, Error> {
+        dispatch!(self, x => x.line_state(port))
+    }
+
+    fn line_coding(&mut self, port: usize) -> Result<LineCoding, Error> {
+        dispatch!(self, x => x.line_coding(port))
+    }
+
+    fn enable(&mut self, port: usize, event: &Event) -> Result<(), Error> {
+        dispatch!(self, x => x.enable(port, event))
+    }
+
+    fn disable(&mut self, port: usize, event: &Event) -> Result<(), Error> {
+        dispatch!(self, x => x.disable(port, event))
+    }
 }
@@ -0,0 +1,187 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! USB HID bridged to a Unix socket.
+//!
+//! Integration tests can connect to the socket and exchange raw CTAP HID frames with the applet.
+//! Each frame is exactly 64 bytes in both directions. Only one client is served at a time. When
+//! the `usb` feature is enabled, the same reports are also available through the FIDO HID
+//! interface of the USB/IP device.
+
+use std::collections::VecDeque;
+use std::io::{ErrorKind, Read, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::mpsc::Sender;
+use wasefire_board_api::usb::hid::{Api, Event, Report, REPORT_LEN};
+use wasefire_board_api::Error;
+use wasefire_logger as logger;
+
+use crate::board::{Board, State};
+
+// TODO: Should be a flag controlled by xtask (like the storage path).
+const SOCKET: &str = "../../target/hid.sock";
+
+impl Api for &mut Board {
+    fn read(&mut self, output: &mut Report) -> Result<bool, Error> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(report) = state.hid.inbound.pop_front() {
+            *output = report;
+            return Ok(true);
+        }
+        #[cfg(feature = "usb")]
+        match state.usb.hid.read(output) {
+            Ok(()) => return Ok(true),
+            Err(usb_device::UsbError::WouldBlock) => (),
+            Err(_) => return Err(Error::World),
+        }
+        Ok(false)
+    }
+
+    fn write(&mut self, input: &Report) -> Result<bool, Error> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(stream) = &mut state.hid.stream {
+            stream.write_all(input).map_err(|_| Error::World)?;
+            return Ok(true);
+        }
+        #[cfg(feature = "usb")]
+        match state.usb.hid.write(input) {
+            Ok(()) => return Ok(true),
+            Err(usb_device::UsbError::WouldBlock) => (),
+            Err(_) => return Err(Error::World),
+        }
+        Ok(false)
+    }
+
+    fn enable(&mut self, event: &Event) -> Result<(), Error> {
+        self.state.lock().unwrap().hid.set(event, true);
+        Ok(())
+    }
+
+    fn disable(&mut self, event: &Event) -> Result<(), Error> {
+        self.state.lock().unwrap().hid.set(event, false);
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct Hid {
+    read_enabled: bool,
+    write_enabled: bool,
+    /// Reports received from the socket and not yet read by the applet.
+    inbound: VecDeque<Report>,
+    /// The connected client, if any.
+    stream: Option<UnixStream>,
+}
+
+impl Hid {
+    /// Starts accepting clients on the HID socket.
+    pub fn init(state: Arc<Mutex<State>>) {
+        let path = Path::new(SOCKET);
+        match std::fs::remove_file(path) {
+            Err(e) if e.kind() != ErrorKind::NotFound => panic!("{e}"),
+            _ => (),
+        }
+        let listener = UnixListener::bind(path).unwrap();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let stream = stream.unwrap();
+                serve(&state, stream);
+            }
+        });
+    }
+
+    /// Pushes events based on whether the USB device was polled.
+    pub fn tick(&mut self, polled: bool, sender: &Sender<wasefire_board_api::Event>) {
+        if self.read_enabled && polled {
+            let _ = sender.try_send(Event::Read.into());
+        }
+        if self.write_enabled && polled {
+            let _ = sender.try_send(Event::Write.into());
+        }
+    }
+
+    fn set(&mut self, event: &Event, enabled: bool) {
+        match event {
+            Event::Read => self.read_enabled = enabled,
+            Event::Write => self.write_enabled = enabled,
+        }
+    }
+}
+
+fn serve(state: &Mutex<State>, stream: UnixStream) {
+    {
+        let mut state = state.lock().unwrap();
+        state.hid.stream = Some(stream.try_clone().unwrap());
+        if state.hid.write_enabled {
+            let _ = state.sender.try_send(Event::Write.into());
+        }
+    }
+    let result = forward(stream, |report| {
+        let mut state = state.lock().unwrap();
+        state.hid.inbound.push_back(report);
+        if state.hid.read_enabled {
+            let _ = state.sender.try_send(Event::Read.into());
+        }
+    });
+    if let Err(e) = result {
+        logger::warn!("HID client error: {}", e);
+    }
+    state.lock().unwrap().hid.stream = None;
+}
+
+/// Forwards the frames of a stream until it is closed.
+///
+/// A partial frame at the end of the stream is an error.
+fn forward(mut stream: impl Read, mut push: impl FnMut(Report)) -> std::io::Result<()> {
+    loop {
+        let mut report = [0; REPORT_LEN];
+        let mut len = 0;
+        while len < REPORT_LEN {
+            match stream.read(&mut report[len ..])? {
+                0 if len == 0 => return Ok(()),
+                0 => return Err(ErrorKind::UnexpectedEof.into()),
+                n => len += n,
+            }
+        }
+        push(report);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frames(input: &[u8]) -> std::io::Result<Vec<Report>> {
+        let mut reports = Vec::new();
+        forward(input, |report| reports.push(report))?;
+        Ok(reports)
+    }
+
+    #[test]
+    fn forward_frames() {
+        let mut input = vec![0x11; REPORT_LEN];
+        input.extend_from_slice(&[0x22; REPORT_LEN]);
+        assert_eq!(frames(&input).unwrap(), [[0x11; REPORT_LEN], [0x22; REPORT_LEN]]);
+        assert_eq!(frames(&[]).unwrap(), [] as [Report; 0]);
+    }
+
+    #[test]
+    fn forward_partial() {
+        let input = [0x11; REPORT_LEN + 1];
+        assert_eq!(frames(&input).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+}
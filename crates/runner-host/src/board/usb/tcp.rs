@@ -0,0 +1,249 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! USB serial bridged to a TCP socket.
+//!
+//! This is enabled with the `--serial-tcp <port>` option, such that test processes can connect to
+//! the serial with `nc localhost <port>`. Only one connection is served at a time and the line is
+//! considered ready (DTR and RTS set) while a connection is established.
+//!
+//! Writes are queued to a writer thread per connection, such that a client not reading doesn't
+//! block the board (the write would block once the queue is full).
+
+use std::collections::VecDeque;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{Ipv4Addr, Shutdown, TcpListener, TcpStream};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+
+use wasefire_board_api::usb::serial::{Api, Event, LineState};
+use wasefire_board_api::Error;
+use wasefire_logger as logger;
+
+use crate::board::{Board, State};
+
+impl Api for &mut Board {
+    fn count(&mut self) -> usize {
+        1
+    }
+
+    fn read(&mut self, port: usize, output: &mut [u8]) -> Result<usize, Error> {
+        check(port)?;
+        Ok(self.state.lock().unwrap().serial_tcp.as_mut().unwrap().read(output))
+    }
+
+    fn write(&mut self, port: usize, input: &[u8]) -> Result<usize, Error> {
+        check(port)?;
+        let mut state = self.state.lock().unwrap();
+        let tcp = state.serial_tcp.as_mut().unwrap();
+        let len = tcp.write(input);
+        if 0 < len && tcp.write_enabled {
+            let _ = state.sender.try_send(Event::Write.on(port));
+        }
+        Ok(len)
+    }
+
+    fn flush(&mut self, port: usize) -> Result<(), Error> {
+        // Queued writes are sent as soon as possible by the writer thread.
+        check(port)
+    }
+
+    fn line_state(&mut self, port: usize) -> Result<LineState, Error> {
+        check(port)?;
+        let ready = self.state.lock().unwrap().serial_tcp.as_ref().unwrap().outbound.is_some();
+        Ok(LineState { dtr: ready, rts: ready })
+    }
+
+    fn enable(&mut self, port: usize, event: &Event) -> Result<(), Error> {
+        check(port)?;
+        let mut state = self.state.lock().unwrap();
+        let tcp = state.serial_tcp.as_mut().unwrap();
+        tcp.set(event, true)?;
+        if *event == Event::Write && tcp.outbound.is_some() {
+            let _ = state.sender.try_send(Event::Write.on(port));
+        }
+        Ok(())
+    }
+
+    fn disable(&mut self, port: usize, event: &Event) -> Result<(), Error> {
+        check(port)?;
+        self.state.lock().unwrap().serial_tcp.as_mut().unwrap().set(event, false)
+    }
+}
+
+fn check(port: usize) -> Result<(), Error> {
+    match port {
+        0 => Ok(()),
+        _ => Err(Error::User),
+    }
+}
+
+#[derive(Default)]
+pub struct SerialTcp {
+    read_enabled: bool,
+    write_enabled: bool,
+    control_enabled: bool,
+    /// Bytes received from the connection and not yet read by the applet.
+    inbound: VecDeque<u8>,
+    /// The write queue of the current connection, if any.
+    ///
+    /// Without connection, nothing is written and there's nothing to read.
+    outbound: Option<SyncSender<Vec<u8>>>,
+}
+
+/// Maximum number of queued writes per connection.
+const QUEUE_LEN: usize = 16;
+
+impl SerialTcp {
+    /// Listens on a local TCP port and starts serving connections.
+    pub fn init(state: Arc<Mutex<State>>, port: u16) -> std::io::Result<()> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+        println!("Serial is available at {}", listener.local_addr()?);
+        state.lock().unwrap().serial_tcp = Some(SerialTcp::default());
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let result = stream.and_then(|stream| serve(stream, &state));
+                if let Err(e) = result {
+                    logger::warn!("Serial TCP connection error: {}", e);
+                }
+            }
+        });
+        Ok(())
+    }
+
+    fn read(&mut self, output: &mut [u8]) -> usize {
+        let len = std::cmp::min(output.len(), self.inbound.len());
+        for (x, y) in output.iter_mut().zip(self.inbound.drain(.. len)) {
+            *x = y;
+        }
+        len
+    }
+
+    /// Queues a write and returns how many bytes were accepted (all or nothing).
+    fn write(&mut self, input: &[u8]) -> usize {
+        let Some(outbound) = &self.outbound else { return 0 };
+        match outbound.try_send(input.to_vec()) {
+            Ok(()) => input.len(),
+            // The queue is full or the connection is closing.
+            Err(TrySendError::Full(_) | TrySendError::Disconnected(_)) => 0,
+        }
+    }
+
+    fn set(&mut self, event: &Event, enabled: bool) -> Result<(), Error> {
+        match event {
+            Event::Read => self.read_enabled = enabled,
+            Event::Write => self.write_enabled = enabled,
+            Event::Control => self.control_enabled = enabled,
+            // The line coding never changes.
+            Event::Coding => (),
+        }
+        Ok(())
+    }
+
+    /// Updates the connection and returns the events to push.
+    fn connect(&mut self, outbound: Option<SyncSender<Vec<u8>>>) -> impl Iterator<Item = Event> {
+        self.outbound = outbound;
+        let connected = self.outbound.is_some();
+        let control = self.control_enabled.then_some(Event::Control);
+        let write = (connected && self.write_enabled).then_some(Event::Write);
+        control.into_iter().chain(write)
+    }
+}
+
+/// Forwards the bytes received by a connection until it closes.
+fn serve(mut stream: TcpStream, state: &Arc<Mutex<State>>) -> std::io::Result<()> {
+    stream.set_nodelay(true)?;
+    let (outbound, receiver) = sync_channel(QUEUE_LEN);
+    let writer = std::thread::spawn({
+        let stream = stream.try_clone()?;
+        let state = state.clone();
+        move || write_queue(stream, receiver, &state)
+    });
+    push(state, |tcp| tcp.connect(Some(outbound)));
+    let mut buffer = [0; 256];
+    let result = loop {
+        let len = match stream.read(&mut buffer) {
+            Ok(0) => break Ok(()),
+            Ok(len) => len,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => break Err(e),
+        };
+        push(state, |tcp| {
+            tcp.inbound.extend(&buffer[.. len]);
+            tcp.read_enabled.then_some(Event::Read)
+        });
+    };
+    // Dropping the queue and shutting the connection down stops the writer thread.
+    push(state, |tcp| tcp.connect(None));
+    let _ = stream.shutdown(Shutdown::Both);
+    let _ = writer.join();
+    result
+}
+
+/// Writes the queued bytes to a connection until the queue is dropped or the connection fails.
+fn write_queue(mut stream: TcpStream, receiver: Receiver<Vec<u8>>, state: &Mutex<State>) {
+    for chunk in receiver {
+        if let Err(e) = stream.write_all(&chunk) {
+            logger::warn!("Serial TCP write error: {}", e);
+            break;
+        }
+        // There is room in the queue again.
+        push(state, |tcp| (tcp.write_enabled && tcp.outbound.is_some()).then_some(Event::Write));
+    }
+}
+
+/// Updates the serial state and pushes the resulting events.
+fn push<I: IntoIterator<Item = Event>>(
+    state: &Mutex<State>, update: impl FnOnce(&mut SerialTcp) -> I,
+) {
+    let mut state = state.lock().unwrap();
+    for event in update(state.serial_tcp.as_mut().unwrap()) {
+        let _ = state.sender.try_send(event.on(0));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn connect_pushes_enabled_events() {
+        let mut tcp = SerialTcp::default();
+        assert_eq!(tcp.connect(None).count(), 0);
+        tcp.set(&Event::Control, true).unwrap();
+        tcp.set(&Event::Write, true).unwrap();
+        let (outbound, _receiver) = sync_channel(QUEUE_LEN);
+        let events: Vec<_> = tcp.connect(Some(outbound)).collect();
+        assert_eq!(events, [Event::Control, Event::Write]);
+        let events: Vec<_> = tcp.connect(None).collect();
+        assert_eq!(events, [Event::Control]);
+    }
+
+    #[test]
+    fn write_does_not_block() {
+        let mut tcp = SerialTcp::default();
+        assert_eq!(tcp.write(b"hello"), 0);
+        // Nothing reads the queue, like a client not reading the connection.
+        let (outbound, receiver) = sync_channel(QUEUE_LEN);
+        let _ = tcp.connect(Some(outbound)).count();
+        for _ in 0 .. QUEUE_LEN {
+            assert_eq!(tcp.write(b"hello"), 5);
+        }
+        assert_eq!(tcp.write(b"hello"), 0);
+        assert_eq!(receiver.recv().unwrap(), b"hello");
+        assert_eq!(tcp.write(b"world"), 5);
+        drop(receiver);
+        assert_eq!(tcp.write(b"hello"), 0);
+    }
+}
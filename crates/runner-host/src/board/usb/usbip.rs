@@ -0,0 +1,102 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::process::{Child, Command};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use board::usb::serial::HasSerial;
+use usb_device::class_prelude::UsbBusAllocator;
+use usb_device::prelude::{UsbDevice, UsbDeviceBuilder, UsbVidPid};
+use usb_device::UsbError;
+use usbd_serial::SerialPort;
+use usbip_device::UsbIpBus;
+use wasefire_board_api as board;
+use wasefire_board_api::usb::hid::HidClass;
+use wasefire_board_api::usb::serial::Serial;
+use wasefire_board_api::usb::version::Version;
+
+use crate::board::{Board, State};
+
+impl HasSerial for &mut Board {
+    type UsbBus = UsbIpBus;
+
+    fn with_serial<R>(&mut self, f: impl FnOnce(&mut Serial<Self::UsbBus>) -> R) -> R {
+        f(&mut self.state.lock().unwrap().usb.serial)
+    }
+}
+
+pub struct Usb {
+    pub serial: Serial<'static, UsbIpBus>,
+    pub hid: HidClass<'static, UsbIpBus>,
+    pub version: Version,
+    pub usb_dev: UsbDevice<'static, UsbIpBus>,
+}
+
+impl Default for Usb {
+    fn default() -> Self {
+        let usb_bus = Box::leak(Box::new(UsbBusAllocator::new(UsbIpBus::new())));
+        // The first port is for data and the second is a debug console.
+        let ports = [SerialPort::new(usb_bus), SerialPort::new(usb_bus)];
+        let serial = Serial::with_ports(ports).with_write_buffer(1024);
+        let hid = HidClass::new(usb_bus);
+        let version = Version::new(env!("CARGO_PKG_VERSION"), wasefire_applet_api::API_HASH);
+        let usb_dev = UsbDeviceBuilder::new(usb_bus, UsbVidPid(0x16c0, 0x27dd))
+            .product("Wasefire")
+            .composite_with_iads()
+            .build();
+        Self { serial, hid, version, usb_dev }
+    }
+}
+
+impl Usb {
+    pub fn init(state: Arc<Mutex<State>>) {
+        assert_eq!(spawn(&["sudo", "modprobe", "vhci-hcd"]).wait().unwrap().code().unwrap(), 0);
+        let mut usbip = spawn(&["sudo", "usbip", "attach", "-r", "localhost", "-b", "1-1"]);
+        loop {
+            state.lock().unwrap().usb.poll();
+            match usbip.try_wait().unwrap() {
+                None => continue,
+                Some(e) => assert_eq!(e.code().unwrap(), 0),
+            }
+            break;
+        }
+        tokio::spawn({
+            async move {
+                loop {
+                    tokio::time::sleep(Duration::from_millis(1)).await;
+                    let mut state = state.lock().unwrap();
+                    let polled = state.usb.poll();
+                    let serial_polled =
+                        polled
+                            && state.usb.serial.ports().iter_mut().any(|port| {
+                                !matches!(port.read(&mut []), Err(UsbError::WouldBlock))
+                            });
+                    let State { sender, hid, usb: Usb { serial, .. }, .. } = &mut *state;
+                    serial.tick(serial_polled, |event| drop(sender.try_send(event)));
+                    hid.tick(polled, sender);
+                }
+            }
+        });
+    }
+
+    pub fn poll(&mut self) -> bool {
+        let [data, console] = self.serial.ports() else { unreachable!() };
+        self.usb_dev.poll(&mut [data, console, &mut self.hid, &mut self.version])
+    }
+}
+
+fn spawn(cmd: &[&str]) -> Child {
+    Command::new(cmd[0]).args(&cmd[1 ..]).spawn().unwrap()
+}
@@ -0,0 +1,127 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::{Duration, Instant};
+
+use wasefire_board_api::watchdog::Api;
+use wasefire_board_api::Error;
+use wasefire_logger as logger;
+
+use crate::board::Board;
+
+impl Api for &mut Board {
+    fn start(&mut self, timeout_ms: usize) -> Result<(), Error> {
+        let mut state = self.state.lock().unwrap();
+        if state.watchdog.timeout.is_some() || timeout_ms == 0 {
+            return Err(Error::User);
+        }
+        let timeout = Duration::from_millis(timeout_ms as u64);
+        state.watchdog.timeout = Some(timeout);
+        state.watchdog.fed = Instant::now();
+        if !state.watchdog.running {
+            state.watchdog.running = true;
+            let state = self.state.clone();
+            std::thread::spawn(move || loop {
+                std::thread::sleep(timeout / 4);
+                let mut state = state.lock().unwrap();
+                if state.watchdog.is_expired(Instant::now()) {
+                    expire();
+                    state.watchdog.fed = Instant::now();
+                }
+            });
+        }
+        Ok(())
+    }
+
+    fn feed(&mut self) -> Result<(), Error> {
+        let mut state = self.state.lock().unwrap();
+        state.watchdog.timeout.ok_or(Error::User)?;
+        state.watchdog.fed = Instant::now();
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<(), Error> {
+        let mut state = self.state.lock().unwrap();
+        state.watchdog.timeout.take().ok_or(Error::User)?;
+        Ok(())
+    }
+}
+
+/// Simulated watchdog.
+///
+/// A background thread checks the watchdog. When it expires, the process aborts with the
+/// `watchdog` feature, and an error is only logged otherwise (to permit debugging). The watchdog
+/// is paused while the board waits for events.
+pub struct Watchdog {
+    /// The timeout if started.
+    timeout: Option<Duration>,
+    /// When the watchdog was last fed (or resumed).
+    fed: Instant,
+    /// Whether the watchdog is paused.
+    paused: bool,
+    /// Whether the background thread is running.
+    running: bool,
+}
+
+impl Default for Watchdog {
+    fn default() -> Self {
+        Watchdog { timeout: None, fed: Instant::now(), paused: false, running: false }
+    }
+}
+
+impl Watchdog {
+    /// Pauses or resumes the watchdog.
+    pub fn pause(&mut self, paused: bool) {
+        self.paused = paused;
+        self.fed = Instant::now();
+    }
+
+    fn is_expired(&self, now: Instant) -> bool {
+        match self.timeout {
+            Some(timeout) => !self.paused && self.fed + timeout <= now,
+            None => false,
+        }
+    }
+}
+
+#[cfg(feature = "watchdog")]
+fn expire() {
+    logger::error!("Watchdog expired, aborting.");
+    std::process::abort();
+}
+
+#[cfg(not(feature = "watchdog"))]
+fn expire() {
+    logger::error!("Watchdog expired (enable the watchdog feature to abort).");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expires_unless_fed_or_paused() {
+        let mut watchdog = Watchdog::default();
+        let start = watchdog.fed;
+        let ms = Duration::from_millis;
+        assert!(!watchdog.is_expired(start + ms(100)));
+        watchdog.timeout = Some(ms(50));
+        assert!(!watchdog.is_expired(start + ms(49)));
+        assert!(watchdog.is_expired(start + ms(50)));
+        watchdog.fed = start + ms(40);
+        assert!(!watchdog.is_expired(start + ms(60)));
+        watchdog.paused = true;
+        assert!(!watchdog.is_expired(start + ms(200)));
+    }
+}
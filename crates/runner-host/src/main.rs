@@ -16,43 +16,143 @@
 #![feature(try_blocks)]
 
 use std::io::BufRead;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use tokio::runtime::Handle;
 use tokio::sync::mpsc::channel;
-use wasefire_scheduler::Scheduler;
-use wasefire_store::{FileOptions, FileStorage};
+use tokio::time::Instant;
+use wasefire_scheduler::{Events, OverflowPolicy, Scheduler};
+use wasefire_store::{FileOptions, FileStorage, Store};
 
+use crate::board::adc::Adcs;
+use crate::board::button::Button;
+use crate::board::capture::Signals;
+use crate::board::encoder::Encoders;
+use crate::board::gpio::Gpios;
+use crate::board::i2c_sw::I2cSw;
+use crate::board::led::Leds;
+use crate::board::led_ring::LedRing;
+use crate::board::magnetometer::Magnetometer;
+use crate::board::power::Power;
+use crate::board::pwm::Pwms;
+use crate::board::record::Recording;
+use crate::board::rng::Rng;
+use crate::board::stepper::Steppers;
+use crate::board::temp::Temp;
 use crate::board::timer::Timers;
+use crate::board::uart::Uart;
+use crate::board::usb::hid::Hid;
+use crate::board::usb::tcp::SerialTcp;
+use crate::board::watchdog::Watchdog;
 
 mod board;
 
-#[tokio::main]
+/// Number of events queued before the overflow policy applies.
+const EVENT_CAPACITY: usize = 10;
+
+/// How events are dropped when the queue is full.
+const EVENT_POLICY: OverflowPolicy = OverflowPolicy::Coalesce;
+
+#[cfg_attr(not(feature = "test"), tokio::main)]
+#[cfg_attr(feature = "test", tokio::main(flavor = "current_thread", start_paused = true))]
 async fn main() -> Result<()> {
-    env_logger::init();
+    board::debug::init_logger();
+    let flags = Flags::parse()?;
+    board::update::swap()?;
     // TODO: Should be a flag controlled by xtask (value is duplicated there).
     const STORAGE: &str = "../../target/storage.bin";
     let options = FileOptions { word_size: 4, page_size: 4096, num_pages: 16 };
-    let storage = Some(FileStorage::new(Path::new(STORAGE), options).unwrap());
+    let storage = FileStorage::new(Path::new(STORAGE), options).unwrap();
+    let store = Store::new(storage).ok().context("failed to open the store")?;
     let (sender, receiver) = channel(10);
     let state = Arc::new(Mutex::new(board::State {
         sender,
-        button: false,
-        led: false,
+        adcs: Adcs::default(),
+        button: Button::default(),
+        device_id: board::platform::device_id_from_env()?,
+        effects: Vec::new(),
+        encoders: Encoders::default(),
+        gpios: Gpios::default(),
+        hid: Hid::default(),
+        i2c_sw: I2cSw::default(),
+        leds: Leds::default(),
+        led_ring: LedRing::default(),
+        magnetometer: Magnetometer::default(),
+        power: Power::default(),
+        pwms: Pwms::default(),
+        rng: Rng::from_env(),
+        serial_tcp: None,
+        start: Instant::now(),
+        shell: Default::default(),
+        signals: Signals::default(),
+        steppers: Steppers::default(),
+        temp: Temp::default(),
         timers: Timers::default(),
+        uart: Uart::default(),
+        update: board::update::new()?,
         #[cfg(feature = "usb")]
         usb: board::usb::Usb::default(),
-        storage,
+        store,
+        watchdog: Watchdog::default(),
     }));
+    board::debug::init_shutdown(&state);
+    if let Some(port) = flags.serial_tcp {
+        SerialTcp::init(state.clone(), port)?;
+    }
+    if let Some(port) = flags.shell_tcp {
+        let state = state.lock().unwrap();
+        state.shell.init(state.sender.clone(), port)?;
+    }
+    Hid::init(state.clone());
+    Uart::init(state.clone());
     #[cfg(feature = "usb")]
     board::usb::Usb::init(state.clone());
-    tokio::spawn({
+    // Reading stdin is blocking and would otherwise prevent timers from running.
+    std::thread::spawn({
         let state = state.clone();
-        async move {
+        let runtime = Handle::current();
+        move || {
             for line in std::io::stdin().lock().lines() {
-                let pressed = match line.unwrap().as_str() {
+                let line = line.unwrap();
+                if let Some(args) = line.strip_prefix("adc ") {
+                    board::adc::command(&mut state.lock().unwrap(), args);
+                    continue;
+                }
+                if let Some(args) = line.strip_prefix("gesture ") {
+                    board::button::command(args);
+                    continue;
+                }
+                if let Some(args) = line.strip_prefix("mag ") {
+                    board::magnetometer::command(&mut state.lock().unwrap(), args);
+                    continue;
+                }
+                if let Some(args) = line.strip_prefix("i2c ") {
+                    board::i2c_sw::command(&mut state.lock().unwrap(), args);
+                    continue;
+                }
+                if let Some(args) = line.strip_prefix("encoder ") {
+                    board::encoder::command(&mut state.lock().unwrap(), args);
+                    continue;
+                }
+                if let Some(args) = line.strip_prefix("signal ") {
+                    board::capture::command(&mut state.lock().unwrap(), args);
+                    continue;
+                }
+                if let Some(args) = line.strip_prefix("temp ") {
+                    board::temp::command(&mut state.lock().unwrap(), args);
+                    continue;
+                }
+                if let Some(args) = line.strip_prefix("advance ") {
+                    board::clock::command(&runtime, args);
+                    continue;
+                }
+                let pressed = match line.as_str() {
+                    "effects" => {
+                        board::debug::effects(&mut state.lock().unwrap());
+                        continue;
+                    }
                     "button" => None,
                     "press" => Some(true),
                     "release" => Some(false),
@@ -61,13 +161,73 @@ async fn main() -> Result<()> {
                         continue;
                     }
                 };
-                let mut state = state.lock().unwrap();
-                board::button::event(&mut state, pressed);
+                board::button::event(&state, pressed);
             }
         }
     });
     println!("Running.");
-    let board = board::Board { receiver, state };
+    let recording = flags.recording()?;
+    let events = Events::new(EVENT_CAPACITY, EVENT_POLICY);
+    let board = board::Board { receiver, events, recording, state };
     const WASM: &[u8] = include_bytes!("../../../target/applet.wasm");
-    Handle::current().spawn_blocking(|| Scheduler::run(board, WASM)).await?
+    let mut wasms = vec![WASM];
+    for path in &flags.applets {
+        let wasm = std::fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+        wasms.push(wasm.leak());
+    }
+    Handle::current().spawn_blocking(move || Scheduler::run_many(board, &wasms, None)).await?
+}
+
+/// Command-line options.
+#[derive(Default)]
+struct Flags {
+    /// Bridges the USB serial to a TCP socket on this port instead of USB/IP.
+    serial_tcp: Option<u16>,
+
+    /// Serves the debugging shell on a TCP socket on this port (requires the debug feature).
+    shell_tcp: Option<u16>,
+
+    /// Records the board events to this file.
+    record: Option<PathBuf>,
+
+    /// Replays the board events recorded in this file.
+    replay: Option<PathBuf>,
+
+    /// Runs these applets in addition to the built-in one.
+    applets: Vec<PathBuf>,
+}
+
+impl Flags {
+    fn parse() -> Result<Self> {
+        let mut flags = Flags::default();
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().with_context(|| format!("{arg} expects a value"));
+            match arg.as_str() {
+                "--serial-tcp" => flags.serial_tcp = Some(value()?.parse()?),
+                "--shell-tcp" => flags.shell_tcp = Some(value()?.parse()?),
+                "--record" => flags.record = Some(value()?.into()),
+                "--replay" => flags.replay = Some(value()?.into()),
+                "--applet" => flags.applets.push(value()?.into()),
+                _ => anyhow::bail!("Unknown argument {arg:?}"),
+            }
+        }
+        anyhow::ensure!(
+            flags.record.is_none() || flags.replay.is_none(),
+            "--record and --replay are exclusive"
+        );
+        anyhow::ensure!(
+            flags.shell_tcp.is_none() || cfg!(feature = "debug"),
+            "--shell-tcp requires the debug feature"
+        );
+        Ok(flags)
+    }
+
+    fn recording(&self) -> Result<Recording> {
+        match (&self.record, &self.replay) {
+            (Some(path), _) => Recording::record(path),
+            (None, Some(path)) => Recording::replay(path),
+            (None, None) => Ok(Recording::Off),
+        }
+    }
 }
@@ -25,3 +25,4 @@ cargo check --no-default-features --features=debug
 cargo fmt -- --check
 cargo clippy --features=debug -- --deny=warnings
 cargo test --features=debug
+cargo test --features=debug,test
@@ -15,11 +15,77 @@
 use std::env;
 use std::fs::File;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 fn main() {
+    // The update region holds the staging bank, the boot record page, and the 2 rollback counter
+    // pages (see `src/tasks/update.rs`), such that the staging bank is not larger than the
+    // application bank.
+    let (memory, update_size): (&[u8], u32) = if env::var_os("CARGO_FEATURE_NRF52833").is_some() {
+        (include_bytes!("memory-nrf52833.x"), 0x38000)
+    } else {
+        (include_bytes!("memory-nrf52840.x"), 0x78000)
+    };
+    let firmware_update = env::var_os("CARGO_FEATURE_FIRMWARE_UPDATE").is_some();
+    let update_size = if firmware_update { update_size } else { 0 };
     let out = &PathBuf::from(env::var_os("OUT_DIR").unwrap());
-    File::create(out.join("memory.x")).unwrap().write_all(include_bytes!("memory.x")).unwrap();
+    let mut file = File::create(out.join("memory.x")).unwrap();
+    writeln!(file, "__update_size = {update_size:#x};").unwrap();
+    file.write_all(memory).unwrap();
     println!("cargo:rustc-link-search={}", out.display());
-    println!("cargo:rerun-if-changed=memory.x");
+    println!("cargo:rerun-if-changed=memory-nrf52833.x");
+    println!("cargo:rerun-if-changed=memory-nrf52840.x");
+    usb_config(out);
+    if env::var_os("CARGO_FEATURE_SECURE_BOOT").is_some() {
+        public_key(out, "APPLET_KEY");
+    }
+    if firmware_update {
+        public_key(out, "FIRMWARE_KEY");
+    }
+}
+
+/// Generates the USB identity constants from the environment (see `src/usb_config.rs`).
+fn usb_config(out: &Path) {
+    let var = |name: &str| {
+        println!("cargo:rerun-if-env-changed={name}");
+        env::var(name).ok()
+    };
+    let id = |name: &str, default: u16| match var(name) {
+        None => default,
+        Some(x) => match x.strip_prefix("0x") {
+            Some(x) => u16::from_str_radix(x, 16),
+            None => x.parse(),
+        }
+        .unwrap_or_else(|_| panic!("{name} must be a 16-bit integer, got {x:?}")),
+    };
+    let vid = id("WASEFIRE_USB_VID", 0x16c0);
+    let pid = id("WASEFIRE_USB_PID", 0x27dd);
+    let manufacturer = var("WASEFIRE_USB_MANUFACTURER");
+    let product = var("WASEFIRE_USB_PRODUCT").unwrap_or_else(|| "Wasefire".to_string());
+    let serial_number = match var("WASEFIRE_USB_SERIAL").as_deref() {
+        None => "SerialNumber::None".to_string(),
+        Some("ficr") => "SerialNumber::Ficr".to_string(),
+        Some(x) => format!("SerialNumber::Fixed({x:?})"),
+    };
+    let mut file = File::create(out.join("usb_config.rs")).unwrap();
+    writeln!(file, "pub const VID: u16 = {vid:#06x};").unwrap();
+    writeln!(file, "pub const PID: u16 = {pid:#06x};").unwrap();
+    writeln!(file, "pub const MANUFACTURER: Option<&str> = {manufacturer:?};").unwrap();
+    writeln!(file, "pub const PRODUCT: &str = {product:?};").unwrap();
+    writeln!(file, "pub const SERIAL_NUMBER: SerialNumber = {serial_number};").unwrap();
+}
+
+/// Generates a public key constant from the `WASEFIRE_{name}` environment variable.
+fn public_key(out: &Path, name: &str) {
+    let var = format!("WASEFIRE_{name}");
+    println!("cargo:rerun-if-env-changed={var}");
+    let key = env::var(&var).unwrap_or_else(|_| panic!("{var} must be set"));
+    let key: Option<Vec<u8>> = (0 .. key.len())
+        .step_by(2)
+        .map(|i| key.get(i .. i + 2).and_then(|x| u8::from_str_radix(x, 16).ok()))
+        .collect();
+    let key = key.filter(|x| x.len() == 32);
+    let key = key.unwrap_or_else(|| panic!("{var} must be 64 hexadecimal digits"));
+    let mut file = File::create(out.join(format!("{}.rs", name.to_lowercase()))).unwrap();
+    writeln!(file, "const {name}: [u8; 32] = {key:?};").unwrap();
 }
@@ -12,10 +12,34 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use core::alloc::{GlobalAlloc, Layout};
+use core::sync::atomic::{AtomicUsize, Ordering};
+
 use embedded_alloc::Heap;
+use wasefire_board_api::platform::HeapStats;
+
+/// Heap tracking its high water mark.
+struct Allocator {
+    heap: Heap,
+    high_water: AtomicUsize,
+}
 
 #[global_allocator]
-static ALLOCATOR: Heap = Heap::empty();
+static ALLOCATOR: Allocator = Allocator { heap: Heap::empty(), high_water: AtomicUsize::new(0) };
+
+unsafe impl GlobalAlloc for Allocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.heap.alloc(layout);
+        if !ptr.is_null() {
+            self.high_water.fetch_max(self.heap.used(), Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.heap.dealloc(ptr, layout)
+    }
+}
 
 pub fn init() {
     extern "C" {
@@ -26,5 +50,12 @@ pub fn init() {
     let eheap = unsafe { &mut __eheap } as *mut u32 as usize;
     assert!(sheap < eheap);
     // Unsafe: Called only once before any allocation.
-    unsafe { ALLOCATOR.init(sheap, eheap - sheap) }
+    unsafe { ALLOCATOR.heap.init(sheap, eheap - sheap) }
+}
+
+pub fn stats() -> HeapStats {
+    let used = ALLOCATOR.heap.used();
+    let free = ALLOCATOR.heap.free();
+    let high_water = ALLOCATOR.high_water.load(Ordering::Relaxed);
+    HeapStats { used, free, high_water }
 }
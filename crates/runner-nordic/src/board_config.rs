@@ -0,0 +1,123 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Pin assignments of the supported development kits.
+//!
+//! The nRF52840-DK and nRF52833-DK share the same buttons, LEDs, analog inputs, and I2C pins.
+//! They differ for pins of the second port (the nRF52833 only has P1.00 to P1.09).
+
+use hal::gpio::p0::{self, P0_03, P0_04, P0_28, P0_29, P0_30, P0_31};
+use hal::gpio::{p1, Disconnected, Floating, Input, Level, Output, Pin, PullUp, PushPull};
+use hal::qdec;
+
+pub struct Pins {
+    pub buttons: [Pin<Input<PullUp>>; 4],
+    pub leds: [Pin<Output<PushPull>>; 4],
+    pub gpios: [Pin<Disconnected>; 8],
+    #[allow(clippy::type_complexity)]
+    pub adcs: (
+        P0_03<Disconnected>,
+        P0_04<Disconnected>,
+        P0_28<Disconnected>,
+        P0_29<Disconnected>,
+        P0_30<Disconnected>,
+        P0_31<Disconnected>,
+    ),
+    pub scl: Pin<Input<Floating>>,
+    pub sda: Pin<Input<Floating>>,
+    pub step: Pin<Output<PushPull>>,
+    pub dir: Pin<Output<PushPull>>,
+    pub pwms: [Pin<Output<PushPull>>; 2],
+    pub encoder: qdec::Pins,
+    pub encoder_index: Pin<Input<PullUp>>,
+    pub uart_tx: Pin<Output<PushPull>>,
+    pub uart_rx: Pin<Input<Floating>>,
+}
+
+impl Pins {
+    pub fn new(port0: p0::Parts, port1: p1::Parts) -> Self {
+        let buttons = [
+            port0.p0_11.into_pullup_input().degrade(),
+            port0.p0_12.into_pullup_input().degrade(),
+            port0.p0_24.into_pullup_input().degrade(),
+            port0.p0_25.into_pullup_input().degrade(),
+        ];
+        let leds = [
+            port0.p0_13.into_push_pull_output(Level::High).degrade(),
+            port0.p0_14.into_push_pull_output(Level::High).degrade(),
+            port0.p0_15.into_push_pull_output(Level::High).degrade(),
+            port0.p0_16.into_push_pull_output(Level::High).degrade(),
+        ];
+        // These are the pins D2 to D9 of the Arduino header.
+        #[cfg(feature = "nrf52840")]
+        let gpios = [
+            port1.p1_03.degrade(),
+            port1.p1_04.degrade(),
+            port1.p1_05.degrade(),
+            port1.p1_06.degrade(),
+            port1.p1_07.degrade(),
+            port1.p1_08.degrade(),
+            port1.p1_10.degrade(),
+            port1.p1_11.degrade(),
+        ];
+        // These are the pins P1.03 to P1.09 and P0.02.
+        #[cfg(feature = "nrf52833")]
+        let gpios = [
+            port1.p1_03.degrade(),
+            port1.p1_04.degrade(),
+            port1.p1_05.degrade(),
+            port1.p1_06.degrade(),
+            port1.p1_07.degrade(),
+            port1.p1_08.degrade(),
+            port1.p1_09.degrade(),
+            port0.p0_02.degrade(),
+        ];
+        let adcs = (port0.p0_03, port0.p0_04, port0.p0_28, port0.p0_29, port0.p0_30, port0.p0_31);
+        // These are the pins D10 to D12 of the Arduino header.
+        #[cfg(feature = "nrf52840")]
+        let (a, b, index) = (port1.p1_12, port1.p1_13, port1.p1_14);
+        // These are the pins P0.19 to P0.21.
+        #[cfg(feature = "nrf52833")]
+        let (a, b, index) = (port0.p0_19, port0.p0_20, port0.p0_21);
+        // These are the pin D13 of the Arduino header and P1.09.
+        #[cfg(feature = "nrf52840")]
+        let pwms = [port1.p1_15.degrade(), port1.p1_09.degrade()];
+        // These are the pins P0.22 and P0.23.
+        #[cfg(feature = "nrf52833")]
+        let pwms = [port0.p0_22.degrade(), port0.p0_23.degrade()];
+        let pwms = pwms.map(|x| x.into_push_pull_output(Level::Low));
+        let encoder = qdec::Pins {
+            a: a.into_pullup_input().degrade(),
+            b: b.into_pullup_input().degrade(),
+            led: None,
+        };
+        Pins {
+            buttons,
+            leds,
+            gpios,
+            adcs,
+            // These are the SCL and SDA pins of the Arduino header.
+            scl: port0.p0_27.into_floating_input().degrade(),
+            sda: port0.p0_26.into_floating_input().degrade(),
+            step: port1.p1_01.into_push_pull_output(Level::Low).degrade(),
+            dir: port1.p1_02.into_push_pull_output(Level::Low).degrade(),
+            pwms,
+            encoder,
+            encoder_index: index.into_pullup_input().degrade(),
+            // These are the pins of the virtual COM port of the on-board debugger.
+            uart_tx: port0.p0_06.into_push_pull_output(Level::High).degrade(),
+            uart_rx: port0.p0_08.into_floating_input().degrade(),
+        }
+    }
+}
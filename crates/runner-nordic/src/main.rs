@@ -17,12 +17,23 @@
 #![feature(try_blocks)]
 
 extern crate alloc;
+#[cfg(feature = "nrf52833")]
+extern crate nrf52833_hal as hal;
+#[cfg(feature = "nrf52840")]
+extern crate nrf52840_hal as hal;
+
+#[cfg(not(any(feature = "nrf52833", feature = "nrf52840")))]
+compile_error!("Exactly one of the nrf52833 and nrf52840 features must be enabled.");
+#[cfg(all(feature = "nrf52833", feature = "nrf52840"))]
+compile_error!("Exactly one of the nrf52833 and nrf52840 features must be enabled.");
 
 mod allocator;
+mod board_config;
 mod storage;
 #[cfg(feature = "debug")]
 mod systick;
 mod tasks;
+mod usb_config;
 
 use core::cell::{Cell, RefCell};
 use core::mem::MaybeUninit;
@@ -33,29 +44,44 @@ use cortex_m_rt::entry;
 use critical_section::Mutex;
 #[cfg(feature = "debug")]
 use defmt_rtt as _;
-use nrf52840_hal::ccm::{Ccm, DataRate};
-use nrf52840_hal::clocks::{self, ExternalOscillator, Internal, LfOscStopped};
-use nrf52840_hal::gpio;
-use nrf52840_hal::gpio::{Level, Output, Pin, PushPull};
-use nrf52840_hal::gpiote::Gpiote;
-use nrf52840_hal::pac::{interrupt, Interrupt};
-use nrf52840_hal::prelude::InputPin;
-use nrf52840_hal::rng::Rng;
-use nrf52840_hal::usbd::{UsbPeripheral, Usbd};
+use hal::ccm::{Ccm, DataRate};
+use hal::clocks::{self, ExternalOscillator, Internal, LfOscStarted};
+use hal::gpio;
+use hal::gpiote::Gpiote;
+use hal::pac::{interrupt, Interrupt};
+use hal::prelude::InputPin;
+use hal::usbd::{UsbPeripheral, Usbd};
 #[cfg(feature = "release")]
 use panic_abort as _;
 #[cfg(feature = "debug")]
 use panic_probe as _;
 use storage::Storage;
+use tasks::adc::Adcs;
 use tasks::button::{channel, Button};
-use tasks::clock::Timers;
+use tasks::capture::Capturer;
+use tasks::clock::{Timers, Uptime};
+use tasks::encoder::Encoder;
+use tasks::gpio::Gpio;
+use tasks::led::Leds;
+use tasks::magnetometer::Magnetometer;
+use tasks::power::Power;
+use tasks::pwm::Pwms;
+use tasks::rng::Rng;
+use tasks::stepper::Stepper;
+use tasks::temp::Temp;
+use tasks::uart::Uart;
 use tasks::usb::Usb;
+use tasks::watchdog::Watchdog;
 use tasks::Events;
 use usb_device::class_prelude::UsbBusAllocator;
-use usb_device::device::{UsbDevice, UsbDeviceBuilder, UsbVidPid};
-use usbd_serial::{SerialPort, USB_CLASS_CDC};
+use usb_device::device::UsbDevice;
+use usbd_serial::SerialPort;
+use wasefire_board_api::i2c_sw::Config as I2cConfig;
+use wasefire_board_api::usb::hid::{Hid, HidClass};
 use wasefire_board_api::usb::serial::Serial;
-use wasefire_scheduler::Scheduler;
+use wasefire_board_api::usb::version::Version;
+use wasefire_scheduler::{OverflowPolicy, Scheduler};
+use wasefire_store::Store;
 use {wasefire_board_api as board, wasefire_logger as logger};
 
 #[cfg(feature = "debug")]
@@ -64,19 +90,45 @@ fn panic() -> ! {
     panic_probe::hard_fault();
 }
 
-type Clocks = clocks::Clocks<ExternalOscillator, Internal, LfOscStopped>;
+/// Number of events queued before the overflow policy applies.
+const EVENT_CAPACITY: usize = 10;
+
+/// How events are dropped when the queue is full.
+const EVENT_POLICY: OverflowPolicy = OverflowPolicy::Coalesce;
+
+type Clocks = clocks::Clocks<ExternalOscillator, Internal, LfOscStarted>;
 
 struct State {
     events: Events,
+    adcs: Adcs,
     buttons: [Button; 4],
+    capturer: Capturer,
+    encoder: Encoder,
     gpiote: Gpiote,
+    gpios: [Gpio; 8],
+    hid: Hid<'static, Usb>,
+    i2c_sw: Option<I2cConfig>,
     serial: Serial<'static, Usb>,
+    #[cfg(feature = "debug")]
+    shell: tasks::debug::Shell,
+    version: Version,
     timers: Timers,
+    uptime: Uptime,
     ccm: Ccm,
-    leds: [Pin<Output<PushPull>>; 4],
+    leds: Leds,
+    magnetometer: Magnetometer,
+    power: Power,
+    pwms: Pwms,
     rng: Rng,
-    storage: Option<Storage>,
+    stepper: Stepper,
+    /// The store, taken while in use (see [`board::Api::with_store()`]).
+    store: Option<Store<Storage>>,
+    temp: Temp,
+    uart: Uart,
+    #[cfg(feature = "firmware-update")]
+    update: wasefire_board_api::update::Staged<tasks::update::Staging>,
     usb_dev: UsbDevice<'static, Usb>,
+    watchdog: Watchdog,
 }
 
 #[derive(Copy, Clone)]
@@ -94,53 +146,79 @@ fn main() -> ! {
     static mut USB_BUS: MaybeUninit<UsbBusAllocator<Usb>> = MaybeUninit::uninit();
     static mut STATE: MaybeUninit<Mutex<RefCell<State>>> = MaybeUninit::uninit();
 
+    #[cfg(feature = "firmware-update")]
+    tasks::update::install();
     #[cfg(feature = "debug")]
-    let c = nrf52840_hal::pac::CorePeripherals::take().unwrap();
+    let c = hal::pac::CorePeripherals::take().unwrap();
     #[cfg(feature = "debug")]
     systick::init(c.SYST);
     allocator::init();
     logger::debug!("Runner starts.");
-    let p = nrf52840_hal::pac::Peripherals::take().unwrap();
+    let p = hal::pac::Peripherals::take().unwrap();
     let port0 = gpio::p0::Parts::new(p.P0);
-    let buttons = [
-        Button::new(port0.p0_11.into_pullup_input().degrade()),
-        Button::new(port0.p0_12.into_pullup_input().degrade()),
-        Button::new(port0.p0_24.into_pullup_input().degrade()),
-        Button::new(port0.p0_25.into_pullup_input().degrade()),
-    ];
-    let leds = [
-        port0.p0_13.into_push_pull_output(Level::High).degrade(),
-        port0.p0_14.into_push_pull_output(Level::High).degrade(),
-        port0.p0_15.into_push_pull_output(Level::High).degrade(),
-        port0.p0_16.into_push_pull_output(Level::High).degrade(),
-    ];
-    let timers = Timers::new(p.TIMER0, p.TIMER1, p.TIMER2, p.TIMER3, p.TIMER4);
+    let port1 = gpio::p1::Parts::new(p.P1);
+    let pins = board_config::Pins::new(port0, port1);
+    let buttons = pins.buttons.map(Button::new);
+    let leds = Leds::new(p.PWM0, pins.leds);
+    let pwms = Pwms::new(p.PWM1, p.PWM2, pins.pwms);
+    let gpios = pins.gpios.map(Gpio::new);
+    let (a0, a1, a2, a3, a4, a5) = pins.adcs;
+    let adcs = Adcs::new(p.SAADC, a0, a1, a2, a3, a4, a5);
+    let magnetometer = Magnetometer::new(p.TWIM0, pins.scl, pins.sda);
+    let stepper = Stepper::new(pins.step, pins.dir, p.RTC1);
+    let encoder = Encoder::new(p.QDEC, pins.encoder, pins.encoder_index);
+    let uart = Uart::new(p.UARTE0, pins.uart_tx, pins.uart_rx);
+    let uptime = Uptime::new(p.RTC0);
+    let timers = Timers::new(p.TIMER0, p.TIMER1, p.TIMER2, p.TIMER3);
+    let ppi = hal::ppi::Parts::new(p.PPI);
+    let capturer = Capturer::new(p.TIMER4, ppi.ppi0);
     let gpiote = Gpiote::new(p.GPIOTE);
     // We enable all USB interrupts except STARTED and EPDATA which are feedback loops.
     p.USBD.inten.write(|w| unsafe { w.bits(0x00fffffd) });
-    let clocks = CLOCKS.write(clocks::Clocks::new(p.CLOCK).enable_ext_hfosc());
+    let clocks = CLOCKS.write(clocks::Clocks::new(p.CLOCK).enable_ext_hfosc().start_lfclk());
     let usb_bus = UsbBusAllocator::new(Usbd::new(UsbPeripheral::new(p.USBD, clocks)));
     let usb_bus = USB_BUS.write(usb_bus);
-    let serial = Serial::new(SerialPort::new(usb_bus));
-    let usb_dev = UsbDeviceBuilder::new(usb_bus, UsbVidPid(0x16c0, 0x27dd))
-        .product("Serial port")
-        .device_class(USB_CLASS_CDC)
-        .build();
+    // The first port is for data and the second is a debug console.
+    let ports = [SerialPort::new(usb_bus), SerialPort::new(usb_bus)];
+    let serial = Serial::with_ports(ports).with_write_buffer(256);
+    let hid = Hid::new(HidClass::new(usb_bus));
+    let version = Version::new(env!("CARGO_PKG_VERSION"), wasefire_applet_api::API_HASH);
+    let usb_dev = usb_config::build(usb_bus);
     let rng = Rng::new(p.RNG);
+    let temp = Temp::new(p.TEMP);
     let ccm = Ccm::init(p.CCM, p.AAR, DataRate::_1Mbit);
-    let storage = Some(Storage::new(p.NVMC));
-    let events = Events::default();
+    let store = Some(Store::new(Storage::new(p.NVMC)).ok().unwrap());
+    let events = Events::new(EVENT_CAPACITY, EVENT_POLICY);
     let state = STATE.write(Mutex::new(RefCell::new(State {
         events,
+        adcs,
         buttons,
+        capturer,
+        encoder,
         gpiote,
+        gpios,
+        hid,
+        i2c_sw: None,
         serial,
+        #[cfg(feature = "debug")]
+        shell: Default::default(),
+        version,
         timers,
+        uptime,
         ccm,
         leds,
+        magnetometer,
+        power: Power::default(),
+        pwms,
         rng,
-        storage,
+        stepper,
+        store,
+        temp,
+        uart,
+        #[cfg(feature = "firmware-update")]
+        update: tasks::update::new(),
         usb_dev,
+        watchdog: Watchdog::new(p.WDT),
     })));
     // We first set the board and then enable interrupts so that interrupts may assume the board is
     // always present.
@@ -150,6 +228,13 @@ fn main() -> ! {
     }
     logger::debug!("Runner is initialized.");
     const WASM: &[u8] = include_bytes!("../../../target/applet.wasm");
+    #[cfg(feature = "secure-boot")]
+    {
+        include!(concat!(env!("OUT_DIR"), "/applet_key.rs"));
+        wasefire_scheduler::signature::check(&mut Board(state), WASM, &APPLET_KEY);
+        Scheduler::run_many(Board(state), &[WASM], Some(APPLET_KEY))
+    }
+    #[cfg(not(feature = "secure-boot"))]
     Scheduler::run(Board(state), WASM)
 }
 
@@ -167,11 +252,15 @@ macro_rules! interrupts {
 
 interrupts! {
     GPIOTE = gpiote,
+    QDEC = qdec,
+    RTC0 = uptime,
+    RTC1 = stepper,
     TIMER0 = timer(0),
     TIMER1 = timer(1),
     TIMER2 = timer(2),
     TIMER3 = timer(3),
-    TIMER4 = timer(4),
+    TIMER4 = capture,
+    UARTE0_UART0 = uart,
     USBD = usbd,
 }
 
@@ -179,21 +268,74 @@ fn gpiote(board: Board) {
     critical_section::with(|cs| {
         let mut state = board.0.borrow_ref_mut(cs);
         let state = state.deref_mut();
+        let now = state.uptime.uptime_us();
         for (i, button) in state.buttons.iter_mut().enumerate() {
             if channel(&state.gpiote, i).is_event_triggered() {
                 let pressed = button.pin.is_low().unwrap();
-                state.events.push(board::button::Event { button: i, pressed }.into());
+                if button.debounce.accept(now, pressed) {
+                    state.events.push(board::button::Event { button: i, pressed }.into());
+                }
             }
         }
+        // Buttons whose edges were ignored are sampled at the end of their debounce interval.
+        tasks::button::resample(state);
+        if let Some(gpio) = state.capturer.edge(&state.gpiote) {
+            state.events.push(board::capture::Event::Done { gpio }.into());
+        }
+        if state.encoder.index(&state.gpiote) {
+            state.events.push(board::encoder::Event::Homed { encoder: 0 }.into());
+        }
         state.gpiote.reset_events();
     });
 }
 
+fn capture(board: Board) {
+    critical_section::with(|cs| {
+        let mut state = board.0.borrow_ref_mut(cs);
+        if let Some(gpio) = state.capturer.timeout() {
+            state.events.push(board::capture::Event::Done { gpio }.into());
+        }
+    })
+}
+
+fn qdec(board: Board) {
+    critical_section::with(|cs| board.0.borrow_ref_mut(cs).encoder.tick());
+}
+
+fn uptime(board: Board) {
+    critical_section::with(|cs| {
+        let mut state = board.0.borrow_ref_mut(cs);
+        let state = state.deref_mut();
+        state.uptime.tick();
+        if state.uptime.take_alarm() {
+            tasks::button::resample(state);
+        }
+    });
+}
+
+fn stepper(board: Board) {
+    critical_section::with(|cs| {
+        let mut state = board.0.borrow_ref_mut(cs);
+        if state.stepper.tick() {
+            state.events.push(board::stepper::Event::Done { stepper: 0 }.into());
+        }
+    })
+}
+
 fn timer(board: Board, timer: usize) {
     critical_section::with(|cs| {
         let mut state = board.0.borrow_ref_mut(cs);
-        state.events.push(board::timer::Event { timer }.into());
-        state.timers.tick(timer);
+        if state.timers.tick(timer) {
+            state.events.push(board::timer::Event { timer }.into());
+        }
+    })
+}
+
+fn uart(board: Board) {
+    critical_section::with(|cs| {
+        let mut state = board.0.borrow_ref_mut(cs);
+        let state = state.deref_mut();
+        state.uart.tick(|event| state.events.push(event));
     })
 }
 
@@ -221,7 +363,14 @@ fn usbd(board: Board) {
     critical_section::with(|cs| {
         let mut state = board.0.borrow_ref_mut(cs);
         let state = state.deref_mut();
-        let polled = state.usb_dev.poll(&mut [state.serial.port()]);
-        state.serial.tick(polled, |event| state.events.push(event.into()));
+        let [data, console] = state.serial.ports() else { unreachable!() };
+        let polled =
+            state.usb_dev.poll(&mut [data, console, state.hid.class(), &mut state.version]);
+        #[cfg(feature = "debug")]
+        if polled && state.shell.tick(console) {
+            state.events.push(board::debug::Event::Shell.into());
+        }
+        state.serial.tick(polled, |event| state.events.push(event));
+        state.hid.tick(polled, |event| state.events.push(event.into()));
     });
 }
@@ -20,8 +20,8 @@ use core::slice;
 use embedded_storage::nor_flash::{
     ErrorType, NorFlash, NorFlashError, NorFlashErrorKind, ReadNorFlash,
 };
-use nrf52840_hal::nvmc::Nvmc;
-use nrf52840_hal::pac::NVMC;
+use hal::nvmc::Nvmc;
+use hal::pac::NVMC;
 use wasefire_store::{self as store, StorageError, StorageIndex, StorageResult};
 
 const PAGE_SIZE: usize = <Nvmc<NVMC>>::ERASE_SIZE;
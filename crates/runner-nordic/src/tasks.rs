@@ -12,18 +12,40 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(feature = "debug")]
+use alloc::collections::VecDeque;
+
+use wasefire_board_api::debug::QueueStats;
 use wasefire_board_api::{self as board, Event};
+#[cfg(feature = "debug")]
+use wasefire_logger as logger;
 use wasefire_scheduler as scheduler;
+use wasefire_store::Store;
 
 use crate::Board;
 
+pub mod adc;
 pub mod button;
+pub mod capture;
 pub mod clock;
 mod crypto;
-mod debug;
-mod led;
-mod rng;
+pub mod debug;
+pub mod encoder;
+pub mod gpio;
+pub mod i2c_sw;
+pub mod led;
+pub mod magnetometer;
+pub mod platform;
+pub mod power;
+pub mod pwm;
+pub mod rng;
+pub mod stepper;
+pub mod temp;
+pub mod uart;
+#[cfg(feature = "firmware-update")]
+pub mod update;
 pub mod usb;
+pub mod watchdog;
 
 impl core::fmt::Debug for Board {
     fn fmt(&self, _: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
@@ -50,8 +72,18 @@ impl board::Api for Board {
     }
 
     type Storage = crate::storage::Storage;
-    fn take_storage(&mut self) -> Option<Self::Storage> {
-        critical_section::with(|cs| self.0.borrow_ref_mut(cs).storage.take())
+    fn with_store<R>(&mut self, f: impl FnOnce(&mut Store<Self::Storage>) -> R) -> R {
+        // The store is taken out of the state such that interrupts are not blocked while it is
+        // used. Interrupts don't use the store.
+        let mut store = critical_section::with(|cs| self.0.borrow_ref_mut(cs).store.take());
+        let result = f(store.as_mut().unwrap());
+        critical_section::with(|cs| self.0.borrow_ref_mut(cs).store = store);
+        result
+    }
+
+    type Adc<'a> = &'a mut Self;
+    fn adc(&mut self) -> Self::Adc<'_> {
+        self
     }
 
     type Button<'a> = &'a mut Self;
@@ -59,6 +91,16 @@ impl board::Api for Board {
         self
     }
 
+    type Capture<'a> = &'a mut Self;
+    fn capture(&mut self) -> Self::Capture<'_> {
+        self
+    }
+
+    type Clock<'a> = &'a mut Self;
+    fn clock(&mut self) -> Self::Clock<'_> {
+        self
+    }
+
     type Crypto<'a> = &'a mut Self;
     fn crypto(&mut self) -> &mut Self {
         self
@@ -69,38 +111,154 @@ impl board::Api for Board {
         self
     }
 
+    type Encoder<'a> = &'a mut Self;
+    fn encoder(&mut self) -> Self::Encoder<'_> {
+        self
+    }
+
+    type Gpio<'a> = &'a mut Self;
+    fn gpio(&mut self) -> Self::Gpio<'_> {
+        self
+    }
+
+    type I2cSw<'a> = &'a mut Self;
+    fn i2c_sw(&mut self) -> Self::I2cSw<'_> {
+        self
+    }
+
     type Led<'a> = &'a mut Self;
     fn led(&mut self) -> Self::Led<'_> {
         self
     }
 
+    // There is no addressable LED ring on the development kit.
+    type LedRing<'a> = board::Unsupported;
+    fn led_ring(&mut self) -> Self::LedRing<'_> {
+        board::Unsupported
+    }
+
+    type Magnetometer<'a> = &'a mut Self;
+    fn magnetometer(&mut self) -> Self::Magnetometer<'_> {
+        self
+    }
+
+    type Platform<'a> = &'a mut Self;
+    fn platform(&mut self) -> Self::Platform<'_> {
+        self
+    }
+
+    type Power<'a> = &'a mut Self;
+    fn power(&mut self) -> Self::Power<'_> {
+        self
+    }
+
+    type Pwm<'a> = &'a mut Self;
+    fn pwm(&mut self) -> Self::Pwm<'_> {
+        self
+    }
+
     type Rng<'a> = &'a mut Self;
     fn rng(&mut self) -> Self::Rng<'_> {
         self
     }
 
+    type Stepper<'a> = &'a mut Self;
+    fn stepper(&mut self) -> Self::Stepper<'_> {
+        self
+    }
+
+    type Temp<'a> = &'a mut Self;
+    fn temp(&mut self) -> Self::Temp<'_> {
+        self
+    }
+
     type Timer<'a> = &'a mut Self;
     fn timer(&mut self) -> Self::Timer<'_> {
         self
     }
 
+    type Uart<'a> = &'a mut Self;
+    fn uart(&mut self) -> Self::Uart<'_> {
+        self
+    }
+
+    #[cfg(feature = "firmware-update")]
+    type Update<'a> = &'a mut Self;
+    #[cfg(feature = "firmware-update")]
+    fn update(&mut self) -> Self::Update<'_> {
+        self
+    }
+
+    #[cfg(not(feature = "firmware-update"))]
+    type Update<'a> = board::Unsupported;
+    #[cfg(not(feature = "firmware-update"))]
+    fn update(&mut self) -> Self::Update<'_> {
+        board::Unsupported
+    }
+
     type Usb<'a> = &'a mut Self;
     fn usb(&mut self) -> Self::Usb<'_> {
         self
     }
+
+    type Watchdog<'a> = &'a mut Self;
+    fn watchdog(&mut self) -> Self::Watchdog<'_> {
+        self
+    }
 }
 
-#[derive(Default)]
-pub struct Events(scheduler::Events);
+pub struct Events {
+    queue: scheduler::Events,
+    /// When the queued events were pushed, to trace their dispatch latency.
+    #[cfg(feature = "debug")]
+    pushed: VecDeque<(Event, u32)>,
+}
 
 impl Events {
+    pub fn new(capacity: usize, policy: scheduler::OverflowPolicy) -> Self {
+        Events {
+            queue: scheduler::Events::new(capacity, policy),
+            #[cfg(feature = "debug")]
+            pushed: VecDeque::new(),
+        }
+    }
+
+    pub fn stats(&self) -> QueueStats {
+        QueueStats { depth: self.queue.depth(), dropped: self.queue.dropped() }
+    }
+
     pub fn push(&mut self, event: Event) {
-        self.0.push(event);
+        #[cfg(feature = "debug")]
+        let depth = self.queue.depth();
+        #[cfg(feature = "debug")]
+        let pushed = (event.clone(), clock::ticks());
+        let _evicted = self.queue.push(event);
+        // Merged and dropped events keep the time of the queued one (if any). Evicted events lose
+        // their time, which is the oldest one since events are popped in order.
+        #[cfg(feature = "debug")]
+        if let Some(evicted) = _evicted {
+            if let Some(i) = self.pushed.iter().position(|x| x.0 == evicted) {
+                self.pushed.remove(i);
+            }
+            self.pushed.push_back(pushed);
+        } else if depth < self.queue.depth() {
+            self.pushed.push_back(pushed);
+        }
         cortex_m::asm::sev();
     }
 
     fn pop(&mut self) -> Option<Event> {
-        self.0.pop()
+        let event = self.queue.pop()?;
+        #[cfg(feature = "debug")]
+        if let Some(i) = self.pushed.iter().position(|x| x.0 == event) {
+            let (_, start) = self.pushed.remove(i).unwrap();
+            logger::trace!(
+                "Dispatching {} after {}us",
+                logger::Debug2Format(&event),
+                clock::elapsed_us(start)
+            );
+        }
+        Some(event)
     }
 
     // May return even if there are no events.
@@ -0,0 +1,99 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use embedded_hal::adc::OneShot;
+use hal::gpio::p0::{P0_03, P0_04, P0_28, P0_29, P0_30, P0_31};
+use hal::gpio::Disconnected;
+use hal::pac::SAADC;
+use hal::saadc::{InternalVdd, Saadc, SaadcConfig};
+use wasefire_board_api::power::Peripheral;
+use wasefire_board_api::{self as board, Error};
+
+impl board::adc::Api for &mut crate::tasks::Board {
+    fn count(&mut self) -> usize {
+        6
+    }
+
+    fn read(&mut self, i: usize) -> Result<u16, Error> {
+        critical_section::with(|cs| {
+            let state = &mut *self.0.borrow_ref_mut(cs);
+            state.power.check(Peripheral::Adc)?;
+            state.adcs.read(i)
+        })
+    }
+
+    fn read_vdd_mv(&mut self) -> Result<u16, Error> {
+        critical_section::with(|cs| {
+            let state = &mut *self.0.borrow_ref_mut(cs);
+            state.power.check(Peripheral::Adc)?;
+            state.adcs.read_vdd_mv()
+        })
+    }
+}
+
+/// Analog inputs A0 to A5 of the Arduino header.
+pub struct Adcs {
+    saadc: Saadc,
+    a0: P0_03<Disconnected>,
+    a1: P0_04<Disconnected>,
+    a2: P0_28<Disconnected>,
+    a3: P0_29<Disconnected>,
+    a4: P0_30<Disconnected>,
+    a5: P0_31<Disconnected>,
+}
+
+impl Adcs {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        saadc: SAADC, a0: P0_03<Disconnected>, a1: P0_04<Disconnected>, a2: P0_28<Disconnected>,
+        a3: P0_29<Disconnected>, a4: P0_30<Disconnected>, a5: P0_31<Disconnected>,
+    ) -> Self {
+        // The default configuration uses VDD as reference with a 14-bit resolution.
+        let saadc = Saadc::new(saadc, SaadcConfig::default());
+        Adcs { saadc, a0, a1, a2, a3, a4, a5 }
+    }
+
+    fn read(&mut self, i: usize) -> Result<u16, Error> {
+        let sample = match i {
+            0 => self.saadc.read(&mut self.a0),
+            1 => self.saadc.read(&mut self.a1),
+            2 => self.saadc.read(&mut self.a2),
+            3 => self.saadc.read(&mut self.a3),
+            4 => self.saadc.read(&mut self.a4),
+            5 => self.saadc.read(&mut self.a5),
+            _ => return Err(Error::User),
+        }
+        .map_err(|_| Error::World)?;
+        // Samples may be slightly negative due to noise.
+        let sample = sample.clamp(0, 0x3fff) as u32;
+        Ok((sample * u16::MAX as u32 / 0x3fff) as u16)
+    }
+
+    fn read_vdd_mv(&mut self) -> Result<u16, Error> {
+        // The analog inputs use VDD/4 as reference with a gain of 1/4, so their range is VDD. To
+        // measure VDD itself, we temporarily use the internal 0.6V reference with a gain of 1/6,
+        // such that the range is 3.6V (the maximum VDD).
+        //
+        // SAFETY: The SAADC is only used through this struct and we restore the configuration.
+        let saadc = unsafe { &*SAADC::ptr() };
+        let config = saadc.ch[0].config.read().bits();
+        saadc.ch[0].config.modify(|_, w| w.refsel().internal().gain().gain1_6());
+        let sample = self.saadc.read(&mut InternalVdd);
+        saadc.ch[0].config.write(|w| unsafe { w.bits(config) });
+        let sample = sample.map_err(|_| Error::World)?;
+        // The 14-bit sample is VDD * (1/6) / 0.6V * 2^14.
+        let sample = sample.clamp(0, 0x3fff) as u32;
+        Ok((sample * 3600 / 0x4000) as u16)
+    }
+}
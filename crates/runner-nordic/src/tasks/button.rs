@@ -14,9 +14,13 @@
 
 use core::ops::DerefMut;
 
-use nrf52840_hal::gpio::{Input, Pin, PullUp};
-use nrf52840_hal::gpiote::{Gpiote, GpioteChannel};
+use hal::gpio::{Input, Pin, PullUp};
+use hal::gpiote::{Gpiote, GpioteChannel};
+use hal::prelude::InputPin;
 use wasefire_board_api as board;
+use wasefire_board_api::button::Debounce;
+
+use crate::State;
 
 impl board::button::Api for &mut crate::tasks::Board {
     fn count(&mut self) -> usize {
@@ -40,16 +44,50 @@ impl board::button::Api for &mut crate::tasks::Board {
             channel(&state.gpiote, i).input_pin(&button.pin).disable_interrupt();
         })
     }
+
+    fn set_debounce(&mut self, i: usize, duration_us: usize) -> Result<(), board::Error> {
+        critical_section::with(|cs| try {
+            let mut state = self.0.borrow_ref_mut(cs);
+            let button = state.buttons.get_mut(i).ok_or(board::Error::User)?;
+            button.debounce.set_interval(duration_us as u64);
+        })
+    }
 }
 
+/// Default debounce interval in microseconds.
+const DEBOUNCE_US: u64 = 20_000;
+
 // We map channels and buttons one to one.
 pub struct Button {
     pub pin: Pin<Input<PullUp>>,
+    pub debounce: Debounce,
 }
 
 impl Button {
     pub fn new(pin: Pin<Input<PullUp>>) -> Self {
-        Button { pin }
+        Button { pin, debounce: Debounce::new(DEBOUNCE_US) }
+    }
+}
+
+/// Samples the buttons whose debounce interval expired and pushes their events.
+///
+/// The uptime alarm is set for the next button to sample, if any.
+pub fn resample(state: &mut State) {
+    let now = state.uptime.uptime_us();
+    let mut next = None;
+    for (i, button) in state.buttons.iter_mut().enumerate() {
+        let Some(deadline) = button.debounce.resample_at() else { continue };
+        if now < deadline {
+            next = Some(core::cmp::min(next.unwrap_or(deadline), deadline));
+            continue;
+        }
+        let pressed = button.pin.is_low().unwrap();
+        if button.debounce.accept(now, pressed) {
+            state.events.push(board::button::Event { button: i, pressed }.into());
+        }
+    }
+    if let Some(deadline) = next {
+        state.uptime.set_alarm(deadline);
     }
 }
 
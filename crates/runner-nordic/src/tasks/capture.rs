@@ -0,0 +1,165 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use hal::gpiote::{Gpiote, GpioteInputPin};
+use hal::pac::TIMER4;
+use hal::ppi::{ConfigurablePpi, Ppi, Ppi0};
+use hal::prelude::InputPin;
+use wasefire_board_api::capture::{analyze, Api, Capture};
+use wasefire_board_api::Error;
+
+use crate::tasks::button::channel;
+use crate::tasks::gpio::Gpio;
+
+/// GPIOTE channel used for capture (the first ones are used by buttons).
+const CHANNEL: usize = 4;
+
+/// Maximum number of edges per measurement.
+const NUM_EDGES: usize = 9;
+
+/// Measurement window in timer ticks (100ms at 16MHz).
+const WINDOW: u32 = 1_600_000;
+
+/// Runs a capturer method with the input pin of a GPIO.
+macro_rules! with_pin {
+    ($board:expr, $gpio:expr, |$capturer:ident, $gpiote:ident, $pin:ident| $body:expr) => {
+        critical_section::with(|cs| {
+            let state = &mut *$board.0.borrow_ref_mut(cs);
+            let $capturer = &mut state.capturer;
+            let $gpiote = &state.gpiote;
+            match state.gpios.get($gpio).ok_or(Error::User)? {
+                Gpio::InputPullUp($pin) => $body,
+                Gpio::InputPullDown($pin) => $body,
+                _ => Err(Error::User),
+            }
+        })
+    };
+}
+
+impl Api for &mut crate::tasks::Board {
+    fn start(&mut self, gpio: usize) -> Result<(), Error> {
+        with_pin!(self, gpio, |capturer, gpiote, pin| capturer.start(gpiote, pin, gpio))
+    }
+
+    fn result(&mut self, gpio: usize) -> Result<Option<Capture>, Error> {
+        with_pin!(self, gpio, |capturer, gpiote, pin| capturer.finish(gpiote, pin, gpio))
+    }
+}
+
+/// Times signal edges with a dedicated timer.
+///
+/// Edges on the GPIO trigger a capture of the timer through PPI, which gives precise timestamps
+/// regardless of interrupt latency. The GPIOTE interrupt collects the timestamps and the timer
+/// interrupt ends the measurement after at most [`WINDOW`]. The interrupt ending the measurement
+/// triggers a capture event.
+pub struct Capturer {
+    timer: TIMER4,
+    ppi: Ppi0,
+    /// Edges of the current measurement.
+    edges: [(u64, bool); NUM_EDGES],
+    len: usize,
+    /// Whether the signal is high after the last edge.
+    high: bool,
+    running: bool,
+    /// The GPIO of the running or done measurement, until its result is read.
+    ///
+    /// Starting a measurement discards the unread result of the previous one.
+    gpio: Option<usize>,
+}
+
+impl Capturer {
+    pub fn new(timer: TIMER4, mut ppi: Ppi0) -> Self {
+        timer.mode.write(|w| w.mode().timer());
+        timer.bitmode.write(|w| w.bitmode()._32bit());
+        timer.prescaler.write(|w| unsafe { w.prescaler().bits(0) });
+        timer.cc[2].write(|w| unsafe { w.bits(WINDOW) });
+        timer.shorts.write(|w| w.compare2_stop().enabled());
+        timer.intenset.write(|w| w.compare2().set());
+        ppi.set_task_endpoint(&timer.tasks_capture[0]);
+        let edges = [(0, false); NUM_EDGES];
+        Capturer { timer, ppi, edges, len: 0, high: false, running: false, gpio: None }
+    }
+
+    /// Handles the GPIOTE interrupt.
+    ///
+    /// Returns the measured GPIO if the measurement is done.
+    pub fn edge(&mut self, gpiote: &Gpiote) -> Option<usize> {
+        let channel = channel(gpiote, CHANNEL);
+        if !self.running || !channel.is_event_triggered() {
+            return None;
+        }
+        channel.reset_events();
+        self.high = !self.high;
+        // Ticks are 62.5ns at 16MHz.
+        self.edges[self.len] = (self.timer.cc[0].read().bits() as u64 * 125 / 2, self.high);
+        self.len += 1;
+        if self.len < NUM_EDGES {
+            return None;
+        }
+        self.stop()
+    }
+
+    /// Handles the timer interrupt at the end of the window.
+    ///
+    /// Returns the measured GPIO if the measurement is done.
+    pub fn timeout(&mut self) -> Option<usize> {
+        self.timer.events_compare[2].write(|w| unsafe { w.bits(0) });
+        if !self.running {
+            return None;
+        }
+        self.stop()
+    }
+
+    fn start<P: GpioteInputPin + InputPin>(
+        &mut self, gpiote: &Gpiote, pin: &P, gpio: usize,
+    ) -> Result<(), Error> {
+        if self.running {
+            return Err(Error::User);
+        }
+        let channel = channel(gpiote, CHANNEL);
+        self.high = pin.is_high().map_err(|_| Error::World)?;
+        self.len = 0;
+        self.running = true;
+        self.gpio = Some(gpio);
+        channel.reset_events();
+        channel.input_pin(pin).toggle().enable_interrupt();
+        self.ppi.set_event_endpoint(channel.event());
+        self.ppi.enable();
+        self.timer.events_compare[2].write(|w| unsafe { w.bits(0) });
+        self.timer.tasks_clear.write(|w| unsafe { w.bits(1) });
+        self.timer.tasks_start.write(|w| unsafe { w.bits(1) });
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Option<usize> {
+        self.ppi.disable();
+        self.timer.tasks_stop.write(|w| unsafe { w.bits(1) });
+        self.running = false;
+        self.gpio
+    }
+
+    fn finish<P: GpioteInputPin>(
+        &mut self, gpiote: &Gpiote, pin: &P, gpio: usize,
+    ) -> Result<Option<Capture>, Error> {
+        if self.running || self.gpio != Some(gpio) {
+            return Err(Error::User);
+        }
+        self.gpio = None;
+        let channel = channel(gpiote, CHANNEL);
+        channel.input_pin(pin).disable_interrupt();
+        channel.input_pin(pin).none();
+        channel.reset_events();
+        Ok(analyze(&self.edges[.. self.len]))
+    }
+}
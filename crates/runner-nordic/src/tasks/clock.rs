@@ -16,25 +16,37 @@ use alloc::boxed::Box;
 
 use cortex_m::prelude::_embedded_hal_timer_CountDown;
 use embedded_hal::timer::Cancel;
-use nrf52840_hal::pac::{TIMER0, TIMER1, TIMER2, TIMER3, TIMER4};
-use nrf52840_hal::timer::{Instance, OneShot, Periodic};
-use nrf52840_hal::Timer;
+use hal::pac::{RTC0, TIMER0, TIMER1, TIMER2, TIMER3};
+use hal::rtc::{Rtc, RtcCompareReg, RtcInterrupt};
+use hal::timer::{Instance, OneShot, Periodic};
+use hal::Timer;
+use wasefire_board_api::power::Peripheral;
+use wasefire_board_api::timer::Mode;
 use {wasefire_board_api as board, wasefire_logger as logger};
 
+impl board::clock::Api for &mut crate::tasks::Board {
+    fn uptime_us(&mut self) -> Result<u64, board::Error> {
+        Ok(critical_section::with(|cs| self.0.borrow_ref_mut(cs).uptime.uptime_us()))
+    }
+}
+
 impl board::timer::Api for &mut crate::tasks::Board {
     fn count(&mut self) -> usize {
         critical_section::with(|cs| self.0.borrow_ref(cs).timers.0.len())
     }
 
-    fn arm(&mut self, i: usize, command: &board::timer::Command) -> Result<(), board::Error> {
+    fn arm(&mut self, i: usize, duration_us: usize, mode: Mode) -> Result<(), board::Error> {
         critical_section::with(|cs| try {
-            let timers = &mut self.0.borrow_ref_mut(cs).timers;
-            let timer = timers.0.get_mut(i).ok_or(board::Error::User)?;
-            match command.periodic {
-                true => timer.slot.set_periodic(),
-                false => timer.slot.set_oneshot(),
+            let state = &mut *self.0.borrow_ref_mut(cs);
+            state.power.check(Peripheral::Timer(i))?;
+            let timer = state.timers.0.get_mut(i).ok_or(board::Error::User)?;
+            match mode {
+                Mode::Periodic => timer.slot.set_periodic(),
+                Mode::OneShot => timer.slot.set_oneshot(),
             }
-            timer.slot.start(command.duration_ms as u32 * 1000);
+            // Timers run at 1MHz, so cycles are microseconds.
+            timer.slot.start(u32::try_from(duration_us).map_err(|_| board::Error::User)?);
+            timer.mode = Some(mode);
         })
     }
 
@@ -43,40 +55,138 @@ impl board::timer::Api for &mut crate::tasks::Board {
             let timers = &mut self.0.borrow_ref_mut(cs).timers;
             let timer = timers.0.get_mut(i).ok_or(board::Error::User)?;
             timer.slot.cancel();
+            timer.mode = None;
         })
     }
 }
 
-pub struct Timers([ErasedTimer; 5]);
+/// Free-running clock.
+///
+/// The RTC counter is 24 bits at 32768Hz and wraps around every 512 seconds. Overflows are
+/// accumulated in software to provide a 64 bits uptime. The first compare register is used as an
+/// alarm (e.g. to sample buttons at the end of their debounce interval).
+pub struct Uptime {
+    rtc: Rtc<RTC0>,
+    overflows: u64,
+}
+
+impl Uptime {
+    pub fn new(rtc: RTC0) -> Self {
+        let mut rtc = Rtc::new(rtc, 0).unwrap();
+        rtc.enable_event(RtcInterrupt::Overflow);
+        rtc.enable_interrupt(RtcInterrupt::Overflow, None);
+        rtc.enable_counter();
+        Uptime { rtc, overflows: 0 }
+    }
+
+    /// Handles the RTC interrupt.
+    pub fn tick(&mut self) {
+        if self.rtc.is_event_triggered(RtcInterrupt::Overflow) {
+            self.rtc.reset_event(RtcInterrupt::Overflow);
+            self.overflows += 1;
+        }
+    }
+
+    pub fn uptime_us(&mut self) -> u64 {
+        self.ticks() * 1_000_000 / 32768
+    }
+
+    /// Triggers the RTC interrupt at a given uptime in microseconds (or soon if in the past).
+    ///
+    /// This replaces the previous alarm. The alarm must be less than 512 seconds in the future.
+    pub fn set_alarm(&mut self, uptime_us: u64) {
+        // The RTC only triggers if the compare value is at least 2 ticks in the future.
+        let ticks = core::cmp::max((uptime_us * 32768 + 999_999) / 1_000_000, self.ticks() + 2);
+        self.rtc.set_compare(RtcCompareReg::Compare0, ticks as u32 & 0xffffff).unwrap();
+        self.rtc.reset_event(RtcInterrupt::Compare0);
+        self.rtc.enable_event(RtcInterrupt::Compare0);
+        self.rtc.enable_interrupt(RtcInterrupt::Compare0, None);
+    }
+
+    /// Returns whether the alarm triggered, in which case it is disabled.
+    pub fn take_alarm(&mut self) -> bool {
+        if !self.rtc.is_event_triggered(RtcInterrupt::Compare0) {
+            return false;
+        }
+        self.rtc.reset_event(RtcInterrupt::Compare0);
+        self.rtc.disable_interrupt(RtcInterrupt::Compare0, None);
+        self.rtc.disable_event(RtcInterrupt::Compare0);
+        true
+    }
+
+    fn ticks(&mut self) -> u64 {
+        let mut counter = self.rtc.get_counter();
+        // The interrupt may be pending while we are in a critical section, in which case the
+        // counter may have wrapped around.
+        if self.rtc.is_event_triggered(RtcInterrupt::Overflow) {
+            self.tick();
+            counter = self.rtc.get_counter();
+        }
+        self.overflows << 24 | counter as u64
+    }
+}
+
+/// Returns the RTC counter without borrowing the uptime.
+///
+/// This is only meant to measure short durations with [`elapsed_us()`] or to sample jitter.
+#[cfg(any(feature = "debug", feature = "rng-mixing"))]
+pub fn ticks() -> u32 {
+    // SAFETY: Reading the counter has no side effect.
+    unsafe { &*RTC0::ptr() }.counter.read().bits()
+}
+
+/// Returns the microseconds elapsed since some [`ticks()`], assuming it's less than 512 seconds.
+#[cfg(feature = "debug")]
+pub fn elapsed_us(start: u32) -> u64 {
+    let ticks = ticks().wrapping_sub(start) & 0xffffff;
+    ticks as u64 * 1_000_000 / 32768
+}
+
+pub struct Timers([ErasedTimer; 4]);
 
 impl Timers {
-    pub fn new(t0: TIMER0, t1: TIMER1, t2: TIMER2, t3: TIMER3, t4: TIMER4) -> Self {
+    pub fn new(t0: TIMER0, t1: TIMER1, t2: TIMER2, t3: TIMER3) -> Self {
         Timers([
             ErasedTimer::new(t0),
             ErasedTimer::new(t1),
             ErasedTimer::new(t2),
             ErasedTimer::new(t3),
-            ErasedTimer::new(t4),
         ])
     }
 
-    pub fn tick(&mut self, index: usize) {
-        self.0[index].tick();
+    /// Returns whether a timer is armed, or `None` if there is no such timer.
+    pub fn is_armed(&self, index: usize) -> Option<bool> {
+        Some(self.0.get(index)?.mode.is_some())
+    }
+
+    /// Handles a timer interrupt and returns whether an event should be pushed.
+    pub fn tick(&mut self, index: usize) -> bool {
+        self.0[index].tick()
     }
 }
 
 struct ErasedTimer {
     slot: Box<dyn ErasedSlot + Send>,
+    /// The mode of the timer if armed.
+    mode: Option<Mode>,
 }
 
 impl ErasedTimer {
     fn new<T: Instance + Send + 'static>(x: T) -> Self {
         x.enable_interrupt();
-        ErasedTimer { slot: Box::new(Slot::new(x)) }
+        ErasedTimer { slot: Box::new(Slot::new(x)), mode: None }
     }
 
-    fn tick(&mut self) {
+    fn tick(&mut self) -> bool {
         self.slot.wait();
+        match self.mode {
+            None => false,
+            Some(Mode::Periodic) => true,
+            Some(Mode::OneShot) => {
+                self.mode = None;
+                true
+            }
+        }
     }
 }
 
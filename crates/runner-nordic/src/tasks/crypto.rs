@@ -34,6 +34,22 @@ impl Api<Board> for &mut Board {
         Unsupported
     }
 
+    // The nRF52 chips don't have a CRC peripheral for arbitrary data, so we use the software
+    // implementation of the board API.
+    type Crc<'a> = Unsupported where Self: 'a;
+    fn crc(&mut self) -> Unsupported {
+        Unsupported
+    }
+
+    type Ecdh<'a> = Unsupported where Self: 'a;
+    fn ecdh(&mut self) -> Unsupported {
+        Unsupported
+    }
+    type Ed25519<'a> = Unsupported where Self: 'a;
+    fn ed25519(&mut self) -> Unsupported {
+        Unsupported
+    }
+
     type Sha256<'a> = Unsupported where Self: 'a;
     fn sha256(&mut self) -> Unsupported {
         Unsupported
@@ -14,7 +14,7 @@
 
 use alloc::vec;
 
-use nrf52840_hal::ccm::CcmData;
+use hal::ccm::CcmData;
 use wasefire_board_api as board;
 
 impl board::crypto::aes128_ccm::Api for &mut crate::tasks::Board {
@@ -12,10 +12,26 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use alloc::format;
+#[cfg(feature = "debug")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "debug")]
+use usbd_serial::SerialPort;
 use wasefire_board_api as board;
+use wasefire_board_api::debug::{Fault, Level, QueueStats};
+use wasefire_board_api::usb::serial::Api as _;
+use wasefire_board_api::usb::Api as _;
+use wasefire_board_api::Api as _;
+use wasefire_logger as logger;
 
+#[cfg(feature = "debug")]
+use crate::tasks::usb::Usb;
 use crate::tasks::Board;
 
+/// The second serial port is the debug console.
+const CONSOLE: usize = 1;
+
 impl board::debug::Api for &mut Board {
     fn exit(&mut self, success: bool) -> ! {
         if success {
@@ -29,4 +45,88 @@ impl board::debug::Api for &mut Board {
             panic!();
         }
     }
+
+    #[cfg(feature = "debug")]
+    fn shell_read(&mut self, line: &mut [u8]) -> Result<usize, board::Error> {
+        Ok(critical_section::with(|cs| self.0.borrow_ref_mut(cs).shell.read(line)))
+    }
+
+    #[cfg(feature = "debug")]
+    fn shell_write(&mut self, output: &[u8]) -> Result<(), board::Error> {
+        self.console_write(output);
+        Ok(())
+    }
+
+    fn set_log_level(&mut self, level: Level) -> Result<(), board::Error> {
+        // The defmt filter is compile-time, so this only filters further.
+        logger::set_level(level);
+        Ok(())
+    }
+
+    fn on_applet_fault(&mut self, fault: &Fault) {
+        self.console_write(format!("{fault}\r\n").as_bytes());
+    }
+
+    fn queue_stats(&mut self) -> Result<QueueStats, board::Error> {
+        Ok(critical_section::with(|cs| self.0.borrow_ref(cs).events.stats()))
+    }
+}
+
+impl Board {
+    /// Writes to the debug console, giving up if nobody is listening or the buffer is full.
+    fn console_write(&mut self, mut input: &[u8]) {
+        let mut serial = self.usb().serial();
+        while !input.is_empty() {
+            match serial.write(CONSOLE, input) {
+                Ok(len) if len > 0 => input = &input[len ..],
+                // Nobody is listening or the buffer is full, so we give up.
+                _ => break,
+            }
+        }
+        let _ = serial.flush_timeout(CONSOLE, 100_000);
+    }
+}
+
+/// Maximum length of a shell line.
+#[cfg(feature = "debug")]
+const MAX_LINE: usize = 256;
+
+/// Lines of the debugging shell received on the debug console.
+#[cfg(feature = "debug")]
+#[derive(Default)]
+pub struct Shell {
+    /// Bytes received and not yet read by the scheduler.
+    input: Vec<u8>,
+}
+
+#[cfg(feature = "debug")]
+impl Shell {
+    /// Reads the debug console and returns whether a line is complete.
+    ///
+    /// This must be called after polling the USB device.
+    pub fn tick(&mut self, console: &mut SerialPort<'static, Usb>) -> bool {
+        let mut complete = false;
+        let mut buffer = [0; 64];
+        while let Ok(len @ 1 ..) = console.read(&mut buffer) {
+            for &byte in &buffer[.. len] {
+                if self.input.len() == MAX_LINE {
+                    // The line is too long, so we drop it.
+                    self.input.clear();
+                }
+                self.input.push(byte);
+                complete |= byte == b'\n';
+            }
+        }
+        complete
+    }
+
+    /// Reads the next complete line, truncated to the buffer length.
+    fn read(&mut self, line: &mut [u8]) -> usize {
+        let Some(end) = self.input.iter().position(|&x| x == b'\n') else { return 0 };
+        let input: Vec<u8> = self.input.drain(..= end).collect();
+        let input = input[.. end].strip_suffix(b"\r").unwrap_or(&input[.. end]);
+        let len = core::cmp::min(input.len(), line.len());
+        line[.. len].copy_from_slice(&input[.. len]);
+        len
+    }
 }
@@ -0,0 +1,90 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use hal::gpio::{Input, Pin, PullUp};
+use hal::gpiote::Gpiote;
+use hal::pac::QDEC;
+use hal::qdec::{NumSamples, Pins, Qdec, SamplePeriod};
+use wasefire_board_api::encoder::{Api, Tracker};
+use wasefire_board_api::Error;
+
+use crate::tasks::button::channel;
+
+/// GPIOTE channel used for the index pulse (the first ones are used by buttons and capture).
+const CHANNEL: usize = 5;
+
+impl Api for &mut crate::tasks::Board {
+    fn count(&mut self) -> usize {
+        1
+    }
+
+    fn position(&mut self, encoder: usize) -> Result<i32, Error> {
+        if encoder != 0 {
+            return Err(Error::User);
+        }
+        critical_section::with(|cs| {
+            let encoder = &mut self.0.borrow_ref_mut(cs).encoder;
+            encoder.tick();
+            Ok(encoder.tracker.position())
+        })
+    }
+
+    fn home(&mut self, encoder: usize) -> Result<(), Error> {
+        if encoder != 0 {
+            return Err(Error::User);
+        }
+        critical_section::with(|cs| {
+            let state = &mut *self.0.borrow_ref_mut(cs);
+            let encoder = &mut state.encoder;
+            encoder.tracker.home();
+            channel(&state.gpiote, CHANNEL).input_pin(&encoder.index).lo_to_hi().enable_interrupt();
+            Ok(())
+        })
+    }
+}
+
+/// Quadrature encoder decoded by the QDEC peripheral with an index pin.
+///
+/// The QDEC accumulator only holds 11 bits, so it is regularly drained into the tracker.
+pub struct Encoder {
+    qdec: Qdec,
+    index: Pin<Input<PullUp>>,
+    tracker: Tracker,
+}
+
+impl Encoder {
+    pub fn new(qdec: QDEC, pins: Pins, index: Pin<Input<PullUp>>) -> Self {
+        let qdec = Qdec::new(qdec, pins, SamplePeriod::_128us);
+        qdec.debounce(true).enable_interrupt(NumSamples::_10smpl).enable();
+        Encoder { qdec, index, tracker: Tracker::default() }
+    }
+
+    /// Handles the QDEC interrupt.
+    pub fn tick(&mut self) {
+        self.qdec.reset_events();
+        self.tracker.count(self.qdec.read() as i32);
+    }
+
+    /// Handles the index pulse and returns whether homing completed.
+    pub fn index(&mut self, gpiote: &Gpiote) -> bool {
+        let channel = channel(gpiote, CHANNEL);
+        if !channel.is_event_triggered() {
+            return false;
+        }
+        channel.input_pin(&self.index).disable_interrupt();
+        // Steps counted before the index pulse must not offset the new origin.
+        self.tick();
+        self.tracker.index()
+    }
+}
@@ -0,0 +1,99 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use hal::gpio::{
+    Disconnected, Input, Level, OpenDrainConfig, OpenDrainIO, Output, Pin, PullDown, PullUp,
+    PushPull,
+};
+use hal::prelude::{InputPin, OutputPin, StatefulOutputPin};
+use wasefire_board_api::gpio::Mode;
+use wasefire_board_api::{self as board, Error};
+
+impl board::gpio::Api for &mut crate::tasks::Board {
+    fn count(&mut self) -> usize {
+        critical_section::with(|cs| self.0.borrow_ref(cs).gpios.len())
+    }
+
+    fn configure(&mut self, i: usize, mode: Mode) -> Result<(), Error> {
+        critical_section::with(|cs| {
+            let gpios = &mut self.0.borrow_ref_mut(cs).gpios;
+            gpios.get_mut(i).ok_or(Error::User)?.configure(mode);
+            Ok(())
+        })
+    }
+
+    fn read(&mut self, i: usize) -> Result<bool, Error> {
+        critical_section::with(|cs| {
+            let gpios = &mut self.0.borrow_ref_mut(cs).gpios;
+            match gpios.get_mut(i).ok_or(Error::User)? {
+                Gpio::Invalid => unreachable!(),
+                Gpio::Disconnected(_) => Err(Error::User),
+                Gpio::InputPullUp(x) => x.is_high().map_err(|_| Error::World),
+                Gpio::InputPullDown(x) => x.is_high().map_err(|_| Error::World),
+                Gpio::OutputPushPull(x) => x.is_set_high().map_err(|_| Error::World),
+                Gpio::OutputOpenDrain(x) => x.is_high().map_err(|_| Error::World),
+            }
+        })
+    }
+
+    fn write(&mut self, i: usize, value: bool) -> Result<(), Error> {
+        critical_section::with(|cs| {
+            let gpios = &mut self.0.borrow_ref_mut(cs).gpios;
+            match gpios.get_mut(i).ok_or(Error::User)? {
+                Gpio::Invalid => unreachable!(),
+                Gpio::OutputPushPull(x) => x.set_state(value.into()),
+                Gpio::OutputOpenDrain(x) => x.set_state(value.into()),
+                _ => return Err(Error::User),
+            }
+            .map_err(|_| Error::World)
+        })
+    }
+}
+
+/// Externally available header pin.
+pub enum Gpio {
+    Invalid,
+    Disconnected(Pin<Disconnected>),
+    InputPullUp(Pin<Input<PullUp>>),
+    InputPullDown(Pin<Input<PullDown>>),
+    OutputPushPull(Pin<Output<PushPull>>),
+    OutputOpenDrain(Pin<Output<OpenDrainIO>>),
+}
+
+impl Gpio {
+    pub fn new(pin: Pin<Disconnected>) -> Self {
+        Gpio::Disconnected(pin)
+    }
+
+    fn configure(&mut self, mode: Mode) {
+        let pin = match core::mem::replace(self, Gpio::Invalid) {
+            Gpio::Invalid => unreachable!(),
+            Gpio::Disconnected(x) => x,
+            Gpio::InputPullUp(x) => x.into_disconnected(),
+            Gpio::InputPullDown(x) => x.into_disconnected(),
+            Gpio::OutputPushPull(x) => x.into_disconnected(),
+            Gpio::OutputOpenDrain(x) => x.into_disconnected(),
+        };
+        *self =
+            match mode {
+                Mode::InputPullUp => Gpio::InputPullUp(pin.into_pullup_input()),
+                Mode::InputPullDown => Gpio::InputPullDown(pin.into_pulldown_input()),
+                Mode::OutputPushPull => Gpio::OutputPushPull(pin.into_push_pull_output(Level::Low)),
+                Mode::OutputOpenDrain => Gpio::OutputOpenDrain(pin.into_open_drain_input_output(
+                    OpenDrainConfig::Standard0Disconnect1,
+                    Level::High,
+                )),
+            };
+    }
+}
@@ -0,0 +1,46 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use wasefire_board_api::i2c_sw::{Api, Config, GpioLines, Master};
+use wasefire_board_api::Error;
+
+use crate::tasks::Board;
+
+// The uptime clock has a resolution of about 30us, which limits the clock rate to about 16kHz.
+impl Api for &mut Board {
+    fn configure(&mut self, config: &Config) -> Result<(), Error> {
+        GpioLines::new(&mut **self, config)?;
+        critical_section::with(|cs| self.0.borrow_ref_mut(cs).i2c_sw = Some(*config));
+        Ok(())
+    }
+
+    fn read(&mut self, addr: u8, data: &mut [u8]) -> Result<(), Error> {
+        let config = config(self)?;
+        Master(GpioLines::new(&mut **self, &config)?).read(addr, data)
+    }
+
+    fn write(&mut self, addr: u8, data: &[u8]) -> Result<(), Error> {
+        let config = config(self)?;
+        Master(GpioLines::new(&mut **self, &config)?).write(addr, data)
+    }
+
+    fn write_read(&mut self, addr: u8, write: &[u8], read: &mut [u8]) -> Result<(), Error> {
+        let config = config(self)?;
+        Master(GpioLines::new(&mut **self, &config)?).write_read(addr, write, read)
+    }
+}
+
+fn config(board: &Board) -> Result<Config, Error> {
+    critical_section::with(|cs| board.0.borrow_ref(cs).i2c_sw.ok_or(Error::User))
+}
@@ -12,31 +12,59 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use nrf52840_hal::prelude::{OutputPin, StatefulOutputPin};
+use hal::gpio::{Output, Pin, PushPull};
+use hal::pac::PWM0;
+use hal::pwm::{Channel, Pwm};
 use wasefire_board_api as board;
 
 impl board::led::Api for &mut crate::tasks::Board {
     fn count(&mut self) -> usize {
-        critical_section::with(|cs| self.0.borrow_ref(cs).leds.len())
+        critical_section::with(|cs| self.0.borrow_ref(cs).leds.brightness.len())
     }
 
     fn get(&mut self, i: usize) -> Result<bool, board::Error> {
         critical_section::with(|cs| {
-            let leds = &mut self.0.borrow_ref_mut(cs).leds;
-            let led = leds.get_mut(i).ok_or(board::Error::User)?;
-            led.is_set_low().map_err(|_| board::Error::World)
+            let leds = &self.0.borrow_ref(cs).leds;
+            Ok(*leds.brightness.get(i).ok_or(board::Error::User)? != 0)
         })
     }
 
     fn set(&mut self, i: usize, on: bool) -> Result<(), board::Error> {
-        critical_section::with(|cs| {
-            let leds = &mut self.0.borrow_ref_mut(cs).leds;
-            let led = leds.get_mut(i).ok_or(board::Error::User)?;
-            match on {
-                false => led.set_high(),
-                true => led.set_low(),
-            }
-            .map_err(|_| board::Error::World)
-        })
+        self.set_brightness(i, if on { u8::MAX } else { 0 })
+    }
+
+    fn set_brightness(&mut self, i: usize, brightness: u8) -> Result<(), board::Error> {
+        critical_section::with(|cs| self.0.borrow_ref_mut(cs).leds.set(i, brightness))
+    }
+}
+
+/// LEDs driven by the PWM peripheral.
+pub struct Leds {
+    pwm: Pwm<PWM0>,
+    brightness: [u8; 4],
+}
+
+const CHANNELS: [Channel; 4] = [Channel::C0, Channel::C1, Channel::C2, Channel::C3];
+
+impl Leds {
+    /// Creates LEDs from active-low pins.
+    pub fn new(pwm: PWM0, pins: [Pin<Output<PushPull>>; 4]) -> Self {
+        let pwm = Pwm::new(pwm);
+        for (channel, pin) in CHANNELS.into_iter().zip(pins) {
+            pwm.set_output_pin(channel, pin);
+        }
+        let mut leds = Leds { pwm, brightness: [0; 4] };
+        for i in 0 .. 4 {
+            leds.set(i, 0).unwrap();
+        }
+        leds
+    }
+
+    fn set(&mut self, i: usize, brightness: u8) -> Result<(), board::Error> {
+        *self.brightness.get_mut(i).ok_or(board::Error::User)? = brightness;
+        // The output is low (and thus the LED on) for the duty cycle.
+        let duty = brightness as u32 * self.pwm.max_duty() as u32 / u8::MAX as u32;
+        self.pwm.set_duty_on(CHANNELS[i], duty as u16);
+        Ok(())
     }
 }
@@ -0,0 +1,94 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use embedded_hal::blocking::i2c::{Write, WriteRead};
+use hal::gpio::{Floating, Input, Pin};
+use hal::pac::TWIM0;
+use hal::twim::{Frequency, Pins, Twim};
+use wasefire_board_api::power::Peripheral;
+use wasefire_board_api::{self as board, Error};
+
+impl board::magnetometer::Api for &mut crate::tasks::Board {
+    fn is_supported(&mut self) -> bool {
+        critical_section::with(|cs| self.0.borrow_ref(cs).magnetometer.address.is_some())
+    }
+
+    fn read(&mut self) -> Result<[i16; 3], Error> {
+        critical_section::with(|cs| {
+            let state = &mut *self.0.borrow_ref_mut(cs);
+            state.power.check(Peripheral::Magnetometer)?;
+            state.magnetometer.read()
+        })
+    }
+}
+
+/// LIS3MDL magnetometer on the I2C pins of the Arduino header.
+pub struct Magnetometer {
+    twim: Twim<TWIM0>,
+    /// The I2C address of the sensor, if present.
+    address: Option<u8>,
+}
+
+/// Possible I2C addresses depending on the SDO/SA1 pin.
+const ADDRESSES: [u8; 2] = [0x1c, 0x1e];
+const WHO_AM_I: u8 = 0x0f;
+const CTRL_REG1: u8 = 0x20;
+const OUT_X_L: u8 = 0x28;
+/// Register address bit to auto-increment the address on multi-byte reads.
+const AUTO_INCREMENT: u8 = 0x80;
+
+impl Magnetometer {
+    pub fn new(twim: TWIM0, scl: Pin<Input<Floating>>, sda: Pin<Input<Floating>>) -> Self {
+        let twim = Twim::new(twim, Pins { scl, sda }, Frequency::K400);
+        let mut result = Magnetometer { twim, address: None };
+        result.address = ADDRESSES.into_iter().find(|&x| result.probe(x).is_ok());
+        result
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        match enabled {
+            true => self.twim.enable(),
+            false => self.twim.disable(),
+        }
+    }
+
+    fn probe(&mut self, address: u8) -> Result<(), Error> {
+        // EasyDMA can't read from flash, so the register address is copied to the stack instead of
+        // using a promoted constant.
+        let register = [WHO_AM_I];
+        let mut id = [0];
+        self.twim.write_read(address, &register, &mut id).map_err(|_| Error::World)?;
+        if id[0] != 0x3d {
+            return Err(Error::World);
+        }
+        // Starting from CTRL_REG1:
+        // - Ultra-high performance on X and Y at 10Hz.
+        // - Full scale of 4 gauss.
+        // - Continuous conversion.
+        // - Ultra-high performance on Z.
+        let config = [CTRL_REG1 | AUTO_INCREMENT, 0x70, 0x00, 0x00, 0x0c];
+        // We use the trait method because it copies the buffer to RAM (for EasyDMA) if needed.
+        Write::write(&mut self.twim, address, &config).map_err(|_| Error::World)
+    }
+
+    fn read(&mut self) -> Result<[i16; 3], Error> {
+        let address = self.address.ok_or(Error::User)?;
+        // See probe() for why the register address is on the stack.
+        let register = [OUT_X_L | AUTO_INCREMENT];
+        let mut output = [0; 6];
+        self.twim.write_read(address, &register, &mut output).map_err(|_| Error::World)?;
+        let axis = |i: usize| i16::from_le_bytes([output[2 * i], output[2 * i + 1]]);
+        Ok([axis(0), axis(1), axis(2)])
+    }
+}
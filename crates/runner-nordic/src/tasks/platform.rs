@@ -0,0 +1,63 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use hal::pac::{FICR, NVMC};
+use wasefire_board_api::crypto::{hkdf, Api as _};
+use wasefire_board_api::platform::{Api, HeapStats};
+use wasefire_board_api::{Api as _, Error};
+
+use crate::tasks::Board;
+
+/// HKDF label of the storage key.
+const STORAGE_KEY_INFO: &[u8] = b"wasefire storage key";
+
+impl Api for &mut Board {
+    fn reset(&mut self) -> Result<(), Error> {
+        // SAFETY: We only read the READY register.
+        let nvmc = unsafe { &*NVMC::ptr() };
+        // Wait for any pending flash operation to complete.
+        while nvmc.ready.read().ready().is_busy() {}
+        cortex_m::peripheral::SCB::sys_reset()
+    }
+
+    fn heap_stats(&mut self) -> Result<HeapStats, Error> {
+        Ok(crate::allocator::stats())
+    }
+
+    fn storage_key(&mut self) -> Result<[u8; 32], Error> {
+        // SAFETY: We only read the FICR which is read-only.
+        let ficr = unsafe { &*FICR::ptr() };
+        // The encryption and identity roots are random and unique to each device.
+        let words =
+            ficr.er.iter().map(|x| x.read().bits()).chain(ficr.ir.iter().map(|x| x.read().bits()));
+        let mut root = [0; 32];
+        for (chunk, word) in root.chunks_exact_mut(4).zip(words) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        // The roots are also used by the radio, so we derive a dedicated key.
+        let mut key = [0; 32];
+        hkdf::sha256(&mut self.crypto().sha256(), &[], &root, STORAGE_KEY_INFO, &mut key)?;
+        Ok(key)
+    }
+
+    fn device_id(&mut self) -> Result<[u8; 8], Error> {
+        // SAFETY: We only read the FICR which is read-only.
+        let ficr = unsafe { &*FICR::ptr() };
+        let mut id = [0; 8];
+        for (chunk, word) in id.chunks_exact_mut(4).zip(ficr.deviceid.iter()) {
+            chunk.copy_from_slice(&word.read().bits().to_le_bytes());
+        }
+        Ok(id)
+    }
+}
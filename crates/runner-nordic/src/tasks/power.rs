@@ -0,0 +1,91 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use alloc::collections::BTreeSet;
+
+use hal::pac::SAADC;
+use wasefire_board_api::power::{Api, Peripheral};
+use wasefire_board_api::Error;
+
+use crate::tasks::Board;
+use crate::State;
+
+impl Api for &mut Board {
+    fn is_busy(&mut self, peripheral: Peripheral) -> Result<bool, Error> {
+        critical_section::with(|cs| is_busy(&self.0.borrow_ref(cs), peripheral))
+    }
+
+    fn disable_peripheral(&mut self, peripheral: Peripheral) -> Result<(), Error> {
+        critical_section::with(|cs| {
+            let state = &mut *self.0.borrow_ref_mut(cs);
+            if is_busy(state, peripheral)? {
+                return Err(Error::User);
+            }
+            if state.power.disabled.insert(peripheral) {
+                set_enabled(state, peripheral, false);
+            }
+            Ok(())
+        })
+    }
+
+    fn enable_peripheral(&mut self, peripheral: Peripheral) -> Result<(), Error> {
+        critical_section::with(|cs| {
+            let state = &mut *self.0.borrow_ref_mut(cs);
+            is_busy(state, peripheral)?;
+            if state.power.disabled.remove(&peripheral) {
+                set_enabled(state, peripheral, true);
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Disabled peripherals.
+#[derive(Default)]
+pub struct Power {
+    disabled: BTreeSet<Peripheral>,
+}
+
+impl Power {
+    /// Returns a user error if the peripheral is disabled.
+    pub fn check(&self, peripheral: Peripheral) -> Result<(), Error> {
+        match self.disabled.contains(&peripheral) {
+            true => Err(Error::User),
+            false => Ok(()),
+        }
+    }
+}
+
+fn is_busy(state: &State, peripheral: Peripheral) -> Result<bool, Error> {
+    match peripheral {
+        Peripheral::Timer(i) => state.timers.is_armed(i).ok_or(Error::User),
+        // The USB device is managed by the USB stack and suspended by the host when unused.
+        Peripheral::Usb => Err(Error::User),
+        Peripheral::Adc | Peripheral::Magnetometer | Peripheral::Rng => Ok(false),
+    }
+}
+
+fn set_enabled(state: &mut State, peripheral: Peripheral, enabled: bool) {
+    match peripheral {
+        Peripheral::Adc => {
+            // SAFETY: The ADC is only used while enabled.
+            let saadc = unsafe { &*SAADC::ptr() };
+            saadc.enable.write(|w| w.enable().bit(enabled));
+        }
+        Peripheral::Magnetometer => state.magnetometer.set_enabled(enabled),
+        // They only draw power while running.
+        Peripheral::Rng | Peripheral::Timer(_) => (),
+        Peripheral::Usb => unreachable!(),
+    }
+}
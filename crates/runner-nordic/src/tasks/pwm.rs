@@ -0,0 +1,115 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use hal::gpio::{Output, Pin, PushPull};
+use hal::pac::{PWM1, PWM2};
+use hal::prelude::OutputPin;
+use hal::pwm::{Channel, Instance, Pwm};
+use hal::time::Hertz;
+use wasefire_board_api::{self as board, Error};
+
+impl board::pwm::Api for &mut crate::tasks::Board {
+    fn count(&mut self) -> usize {
+        2
+    }
+
+    fn configure(&mut self, pwm: usize, freq_hz: u32, duty_pct: u8) -> Result<(), Error> {
+        critical_section::with(|cs| {
+            let pwms = &mut self.0.borrow_ref_mut(cs).pwms;
+            match pwm {
+                0 => pwms.0.configure(freq_hz, duty_pct),
+                1 => pwms.1.configure(freq_hz, duty_pct),
+                _ => Err(Error::User),
+            }
+        })
+    }
+
+    fn stop(&mut self, pwm: usize) -> Result<(), Error> {
+        critical_section::with(|cs| {
+            let pwms = &mut self.0.borrow_ref_mut(cs).pwms;
+            match pwm {
+                0 => pwms.0.steady(false),
+                1 => pwms.1.steady(false),
+                _ => Err(Error::User),
+            }
+        })
+    }
+}
+
+/// PWM outputs, each driven by its own peripheral since the frequency is per peripheral.
+pub struct Pwms(PwmOutput<PWM1>, PwmOutput<PWM2>);
+
+impl Pwms {
+    pub fn new(pwm1: PWM1, pwm2: PWM2, pins: [Pin<Output<PushPull>>; 2]) -> Self {
+        let [pin1, pin2] = pins;
+        Pwms(PwmOutput::new(pwm1, pin1), PwmOutput::new(pwm2, pin2))
+    }
+}
+
+/// The slowest frequency with a 16MHz clock, a prescaler of 128, and a 15-bit counter.
+const MIN_FREQ_HZ: u32 = 4;
+
+/// The fastest frequency with a 16MHz clock and a 1% duty cycle resolution.
+const MAX_FREQ_HZ: u32 = 160_000;
+
+struct PwmOutput<T: Instance> {
+    pwm: Pwm<T>,
+    /// The pin while it is driven as a GPIO (i.e. while the output is steady).
+    pin: Option<Pin<Output<PushPull>>>,
+}
+
+impl<T: Instance> PwmOutput<T> {
+    fn new(pwm: T, pin: Pin<Output<PushPull>>) -> Self {
+        let pwm = Pwm::new(pwm);
+        pwm.disable();
+        let mut output = PwmOutput { pwm, pin: Some(pin) };
+        output.steady(false).unwrap();
+        output
+    }
+
+    fn configure(&mut self, freq_hz: u32, duty_pct: u8) -> Result<(), Error> {
+        if !(MIN_FREQ_HZ ..= MAX_FREQ_HZ).contains(&freq_hz) || 100 < duty_pct {
+            return Err(Error::User);
+        }
+        // The PWM peripheral glitches at the extremes, so we drive the pin directly instead.
+        match duty_pct {
+            0 => return self.steady(false),
+            100 => return self.steady(true),
+            _ => (),
+        }
+        if let Some(pin) = self.pin.take() {
+            self.pwm.set_output_pin(Channel::C0, pin);
+        }
+        self.pwm.set_period(Hertz(freq_hz));
+        let duty = duty_pct as u32 * self.pwm.max_duty() as u32 / 100;
+        // The output is high for the duty cycle.
+        self.pwm.set_duty_off(Channel::C0, duty as u16);
+        self.pwm.enable();
+        Ok(())
+    }
+
+    /// Drives the pin to a steady level.
+    fn steady(&mut self, high: bool) -> Result<(), Error> {
+        if self.pin.is_none() {
+            self.pwm.disable();
+            self.pin = self.pwm.clear_output_pin(Channel::C0);
+        }
+        let pin = self.pin.as_mut().ok_or(Error::World)?;
+        match high {
+            true => pin.set_high(),
+            false => pin.set_low(),
+        }
+        .map_err(|_| Error::World)
+    }
+}
@@ -12,11 +12,91 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use wasefire_board_api as board;
+use hal::pac::RNG;
+use wasefire_board_api::power::Peripheral;
+#[cfg(feature = "rng-mixing")]
+use wasefire_board_api::rng::Mixer;
+use wasefire_board_api::rng::{HealthTest, RngHealth};
+use wasefire_board_api::{self as board, Error};
+
+#[cfg(feature = "rng-mixing")]
+use crate::tasks::clock::ticks;
 
 impl board::rng::Api for &mut crate::tasks::Board {
-    fn fill_bytes(&mut self, buffer: &mut [u8]) -> Result<(), board::Error> {
-        critical_section::with(|cs| self.0.borrow_ref_mut(cs).rng.random(buffer));
+    fn fill_bytes(&mut self, buffer: &mut [u8]) -> Result<(), Error> {
+        critical_section::with(|cs| {
+            let state = &mut *self.0.borrow_ref_mut(cs);
+            state.power.check(Peripheral::Rng)?;
+            state.rng.fill_bytes(buffer)
+        })
+    }
+
+    fn health_check(&mut self) -> Result<RngHealth, Error> {
+        Ok(critical_section::with(|cs| self.0.borrow_ref(cs).rng.health.health()))
+    }
+
+    #[cfg(feature = "rng-mixing")]
+    fn reseed(&mut self, entropy: &[u8]) -> Result<(), Error> {
+        critical_section::with(|cs| self.0.borrow_ref_mut(cs).rng.mixer.reseed(entropy));
+        Ok(())
+    }
+}
+
+/// Hardware random number generator with continuous health tests.
+///
+/// With the `rng-mixing` feature, the output is mixed with a ChaCha20 stream seeded from clock
+/// jitter at boot.
+pub struct Rng {
+    rng: hal::rng::Rng,
+    health: HealthTest,
+    #[cfg(feature = "rng-mixing")]
+    mixer: Mixer,
+}
+
+impl Rng {
+    pub fn new(rng: RNG) -> Self {
+        Rng {
+            rng: hal::rng::Rng::new(rng),
+            health: HealthTest::default(),
+            #[cfg(feature = "rng-mixing")]
+            mixer: Mixer::new(&jitter_seed()),
+        }
+    }
+
+    fn fill_bytes(&mut self, buffer: &mut [u8]) -> Result<(), Error> {
+        self.rng.random(buffer);
+        let mut healthy = true;
+        for &byte in buffer.iter() {
+            healthy &= self.health.push(byte);
+        }
+        if !healthy {
+            // Don't hand back possibly biased data.
+            buffer.fill(0);
+            return Err(Error::World);
+        }
+        #[cfg(feature = "rng-mixing")]
+        self.mixer.mix(buffer);
         Ok(())
     }
 }
+
+/// Collects a seed from the jitter between the CPU clock and the low-frequency clock.
+///
+/// Each bit of the seed is the parity of the number of loop iterations during a few RTC ticks. This
+/// takes about 30 milliseconds and requires the RTC to be running.
+#[cfg(feature = "rng-mixing")]
+fn jitter_seed() -> [u8; 32] {
+    const TICKS_PER_BIT: usize = 4;
+    let mut seed = [0u8; 32];
+    for bit in 0 .. 256 {
+        let mut count = 0u32;
+        for _ in 0 .. TICKS_PER_BIT {
+            let start = ticks();
+            while ticks() == start {
+                count = count.wrapping_add(1);
+            }
+        }
+        seed[bit / 8] |= (count as u8 & 1) << (bit % 8);
+    }
+    seed
+}
@@ -0,0 +1,121 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use hal::gpio::{Output, Pin, PushPull};
+use hal::pac::RTC1;
+use hal::prelude::OutputPin;
+use hal::rtc::{Rtc, RtcCompareReg, RtcInterrupt};
+use wasefire_board_api::stepper::{Motion, Profile};
+use wasefire_board_api::{self as board, Error};
+
+impl board::stepper::Api for &mut crate::tasks::Board {
+    fn count(&mut self) -> usize {
+        1
+    }
+
+    fn move_to(&mut self, i: usize, position: i32, profile: &Profile) -> Result<(), Error> {
+        critical_section::with(|cs| {
+            let stepper = &mut self.0.borrow_ref_mut(cs).stepper;
+            if i != 0 || !profile.is_valid() || stepper.motion.is_some() {
+                return Err(Error::User);
+            }
+            stepper.start(Motion::new(stepper.position, position, profile))
+        })
+    }
+
+    fn position(&mut self, i: usize) -> Result<i32, Error> {
+        if i != 0 {
+            return Err(Error::User);
+        }
+        critical_section::with(|cs| {
+            let stepper = &self.0.borrow_ref(cs).stepper;
+            Ok(stepper.motion.as_ref().map_or(stepper.position, |x| x.position()))
+        })
+    }
+}
+
+/// Stepper motor driven by a step and direction pin.
+///
+/// Steps are timed with RTC1 because the timers are used by applets (TIMER0 to TIMER3) and input
+/// capture (TIMER4).
+pub struct Stepper {
+    step: Pin<Output<PushPull>>,
+    dir: Pin<Output<PushPull>>,
+    rtc: Rtc<RTC1>,
+    position: i32,
+    motion: Option<Motion>,
+}
+
+impl Stepper {
+    pub fn new(step: Pin<Output<PushPull>>, dir: Pin<Output<PushPull>>, rtc: RTC1) -> Self {
+        let mut rtc = Rtc::new(rtc, 0).unwrap();
+        rtc.enable_event(RtcInterrupt::Compare0);
+        rtc.enable_interrupt(RtcInterrupt::Compare0, None);
+        Stepper { step, dir, rtc, position: 0, motion: None }
+    }
+
+    /// Handles the RTC interrupt and returns whether the move is done.
+    pub fn tick(&mut self) -> bool {
+        self.rtc.reset_event(RtcInterrupt::Compare0);
+        let motion = match &mut self.motion {
+            Some(x) => x,
+            None => return false,
+        };
+        if motion.delay_us().is_some() {
+            self.step.set_high().unwrap();
+            // The step pulse must be at least 1 microsecond (64 cycles at 64MHz).
+            cortex_m::asm::delay(64);
+            self.step.set_low().unwrap();
+            motion.step();
+        }
+        if self.schedule() {
+            return false;
+        }
+        self.position = self.motion.take().unwrap().position();
+        true
+    }
+
+    fn start(&mut self, motion: Motion) -> Result<(), Error> {
+        match motion.forward() {
+            true => self.dir.set_high(),
+            false => self.dir.set_low(),
+        }
+        .map_err(|_| Error::World)?;
+        self.motion = Some(motion);
+        if !self.schedule() {
+            // There is nothing to do, but we still want the done event.
+            self.rtc.clear_counter();
+            self.rtc.set_compare(RtcCompareReg::Compare0, 2).map_err(|_| Error::World)?;
+            self.rtc.enable_counter();
+        }
+        Ok(())
+    }
+
+    /// Schedules the next step and returns whether there is one.
+    fn schedule(&mut self) -> bool {
+        let delay_us = match self.motion.as_ref().and_then(|x| x.delay_us()) {
+            Some(x) => x,
+            None => {
+                self.rtc.disable_counter();
+                return false;
+            }
+        };
+        // The RTC runs at 32768Hz and the compare value must be at least 2 ticks ahead.
+        let ticks = (delay_us as u64 * 32768 / 1_000_000).max(2) as u32;
+        self.rtc.clear_counter();
+        self.rtc.set_compare(RtcCompareReg::Compare0, ticks).unwrap();
+        self.rtc.enable_counter();
+        true
+    }
+}
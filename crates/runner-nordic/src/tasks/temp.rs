@@ -0,0 +1,43 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use hal::pac::TEMP;
+use wasefire_board_api::{self as board, Error};
+
+impl board::temp::Api for &mut crate::tasks::Board {
+    fn is_supported(&mut self) -> bool {
+        true
+    }
+
+    fn read(&mut self) -> Result<i32, Error> {
+        Ok(critical_section::with(|cs| self.0.borrow_ref_mut(cs).temp.read()))
+    }
+}
+
+/// On-die temperature sensor.
+pub struct Temp {
+    temp: hal::temp::Temp,
+}
+
+impl Temp {
+    pub fn new(temp: TEMP) -> Self {
+        Temp { temp: hal::temp::Temp::new(temp) }
+    }
+
+    /// Returns the temperature in millidegrees Celsius.
+    fn read(&mut self) -> i32 {
+        // The measurement takes about 36us and has a resolution of 0.25°C.
+        self.temp.measure().to_bits() * 250
+    }
+}
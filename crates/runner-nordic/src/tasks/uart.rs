@@ -0,0 +1,280 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+
+use hal::gpio::{Floating, Input, Output, Pin, PushPull};
+use hal::pac::UARTE0;
+use hal::uarte::Baudrate;
+use wasefire_board_api::uart::{Api, Direction, Event};
+use wasefire_board_api::Error;
+use wasefire_logger as logger;
+
+impl Api for &mut crate::tasks::Board {
+    fn count(&mut self) -> usize {
+        1
+    }
+
+    fn set_baudrate(&mut self, uart: usize, baudrate: u32) -> Result<(), Error> {
+        let baudrate = convert_baudrate(baudrate).ok_or(Error::User)?;
+        with_uart(self, uart, |uart| {
+            uart.uarte.baudrate.write(|w| w.baudrate().variant(baudrate));
+            Ok(())
+        })
+    }
+
+    fn read(&mut self, uart: usize, output: &mut [u8]) -> Result<usize, Error> {
+        with_uart(self, uart, |uart| {
+            let len = core::cmp::min(output.len(), uart.inbound.len());
+            for (x, y) in output.iter_mut().zip(uart.inbound.drain(.. len)) {
+                *x = y;
+            }
+            if uart.inbound.is_empty() {
+                uart.flush_rx();
+            }
+            Ok(len)
+        })
+    }
+
+    fn write(&mut self, uart: usize, input: &[u8]) -> Result<usize, Error> {
+        with_uart(self, uart, |uart| Ok(uart.write(input)))
+    }
+
+    fn flush(&mut self, uart: usize) -> Result<(), Error> {
+        with_uart(self, uart, |uart| match uart.busy {
+            true => Err(Error::World),
+            false => Ok(()),
+        })
+    }
+
+    fn enable(&mut self, uart: usize, direction: Direction) -> Result<(), Error> {
+        with_uart(self, uart, |uart| {
+            uart.set(direction, true);
+            Ok(())
+        })
+    }
+
+    fn disable(&mut self, uart: usize, direction: Direction) -> Result<(), Error> {
+        with_uart(self, uart, |uart| {
+            uart.set(direction, false);
+            Ok(())
+        })
+    }
+}
+
+fn with_uart<R>(
+    board: &mut crate::tasks::Board, uart: usize, f: impl FnOnce(&mut Uart) -> Result<R, Error>,
+) -> Result<R, Error> {
+    if uart != 0 {
+        return Err(Error::User);
+    }
+    critical_section::with(|cs| f(&mut board.0.borrow_ref_mut(cs).uart))
+}
+
+/// Maximum number of bytes written at once.
+const TX_LEN: usize = 64;
+
+/// Size of each receive buffer.
+const RX_LEN: usize = 32;
+
+/// Maximum number of received bytes not yet read by the applet.
+const RX_CAPACITY: usize = 256;
+
+/// UART driven by the UARTE peripheral.
+///
+/// Reception is continuous and double-buffered: while the DMA fills one buffer, the pointer to the
+/// other is already programmed such that reception restarts as soon as a buffer ends. A partially
+/// filled buffer is flushed (by stopping and restarting reception) when the applet has read all
+/// bytes received so far. Writes are copied to a transmit buffer and sent in the background.
+pub struct Uart {
+    uarte: UARTE0,
+    read_enabled: bool,
+    write_enabled: bool,
+    /// Whether a transmission is in progress.
+    busy: bool,
+    /// Bytes received and not yet read by the applet.
+    inbound: VecDeque<u8>,
+    /// State of reception.
+    rx_state: RxState,
+    /// Whether the buffer being filled received bytes.
+    rx_pending: bool,
+    /// Index of the buffer being filled.
+    rx_active: usize,
+    // The DMA buffers must be in RAM and must not move.
+    rx: &'static mut [[u8; RX_LEN]; 2],
+    tx: &'static mut [u8; TX_LEN],
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum RxState {
+    /// Reception restarts with the other buffer when a buffer ends.
+    Running,
+    /// Reception was stopped to flush a partially filled buffer.
+    Stopping,
+    /// The receive FIFO is being flushed to the buffer being filled.
+    Flushing,
+}
+
+impl Uart {
+    pub fn new(uarte: UARTE0, tx: Pin<Output<PushPull>>, rx: Pin<Input<Floating>>) -> Self {
+        uarte.psel.txd.write(|w| unsafe { w.bits(tx.psel_bits()) });
+        uarte.psel.rxd.write(|w| unsafe { w.bits(rx.psel_bits()) });
+        uarte.baudrate.write(|w| w.baudrate().variant(Baudrate::BAUD115200));
+        uarte.config.write(|w| w.hwfc().disabled().parity().excluded());
+        uarte.enable.write(|w| w.enable().enabled());
+        let rx = Box::leak(Box::new([[0; RX_LEN]; 2]));
+        let tx = Box::leak(Box::new([0; TX_LEN]));
+        uarte.rxd.ptr.write(|w| unsafe { w.bits(rx[0].as_ptr() as u32) });
+        uarte.rxd.maxcnt.write(|w| unsafe { w.bits(RX_LEN as u32) });
+        uarte
+            .intenset
+            .write(|w| w.rxstarted().set().rxdrdy().set().endrx().set().rxto().set().endtx().set());
+        uarte.tasks_startrx.write(|w| unsafe { w.bits(1) });
+        let inbound = VecDeque::with_capacity(RX_CAPACITY);
+        Uart {
+            uarte,
+            read_enabled: false,
+            write_enabled: false,
+            busy: false,
+            inbound,
+            rx_state: RxState::Running,
+            rx_pending: false,
+            rx_active: 0,
+            rx,
+            tx,
+        }
+    }
+
+    /// Handles the UARTE interrupt and pushes the triggered events.
+    pub fn tick(&mut self, mut push: impl FnMut(wasefire_board_api::Event)) {
+        if self.uarte.events_rxstarted.read().bits() != 0 {
+            self.uarte.events_rxstarted.reset();
+            // The pointer is double-buffered: it is used when reception restarts.
+            let next = self.rx[1 - self.rx_active].as_ptr() as u32;
+            self.uarte.rxd.ptr.write(|w| unsafe { w.bits(next) });
+        }
+        if self.uarte.events_endrx.read().bits() != 0 {
+            self.uarte.events_endrx.reset();
+            let received = self.receive();
+            match self.rx_state {
+                RxState::Running => {
+                    self.rx_active = 1 - self.rx_active;
+                    self.uarte.tasks_startrx.write(|w| unsafe { w.bits(1) });
+                }
+                RxState::Stopping => self.rx_active = 1 - self.rx_active,
+                RxState::Flushing => {
+                    // The flushed bytes are in the buffer to be filled, which is already copied.
+                    self.rx_state = RxState::Running;
+                    self.uarte.tasks_startrx.write(|w| unsafe { w.bits(1) });
+                }
+            }
+            if received && self.read_enabled {
+                push(Event { uart: 0, direction: Direction::Read }.into());
+            }
+        }
+        if self.uarte.events_rxdrdy.read().bits() != 0 {
+            self.uarte.events_rxdrdy.reset();
+            // This is handled after the end of a buffer to not miss the first byte of the next one,
+            // and we only need to know about that first byte.
+            self.uarte.intenclr.write(|w| w.rxdrdy().clear());
+            self.rx_pending = true;
+            if self.read_enabled {
+                push(Event { uart: 0, direction: Direction::Read }.into());
+            }
+        }
+        if self.uarte.events_rxto.read().bits() != 0 {
+            self.uarte.events_rxto.reset();
+            self.rx_state = RxState::Flushing;
+            self.uarte.tasks_flushrx.write(|w| unsafe { w.bits(1) });
+        }
+        if self.uarte.events_endtx.read().bits() != 0 {
+            self.uarte.events_endtx.reset();
+            self.busy = false;
+            if self.write_enabled {
+                push(Event { uart: 0, direction: Direction::Write }.into());
+            }
+        }
+    }
+
+    /// Copies the bytes of the buffer being filled to the inbound queue.
+    ///
+    /// Returns whether some bytes were received.
+    fn receive(&mut self) -> bool {
+        let amount = self.uarte.rxd.amount.read().bits() as usize;
+        let buffer = &self.rx[self.rx_active][.. core::cmp::min(amount, RX_LEN)];
+        let len = core::cmp::min(buffer.len(), RX_CAPACITY - self.inbound.len());
+        if len < buffer.len() {
+            logger::warn!("UART dropped {} bytes.", buffer.len() - len);
+        }
+        self.inbound.extend(&buffer[.. len]);
+        // The next buffer is empty, so we want to know about its first byte. The event is not reset
+        // because it may already be for that byte: a stale event only results in an empty flush.
+        self.rx_pending = false;
+        self.uarte.intenset.write(|w| w.rxdrdy().set());
+        !buffer.is_empty()
+    }
+
+    /// Flushes the buffer being filled if it received bytes.
+    fn flush_rx(&mut self) {
+        if self.rx_pending && self.rx_state == RxState::Running {
+            self.rx_state = RxState::Stopping;
+            self.uarte.tasks_stoprx.write(|w| unsafe { w.bits(1) });
+        }
+    }
+
+    fn write(&mut self, input: &[u8]) -> usize {
+        if self.busy || input.is_empty() {
+            return 0;
+        }
+        let len = core::cmp::min(input.len(), TX_LEN);
+        self.tx[.. len].copy_from_slice(&input[.. len]);
+        self.uarte.txd.ptr.write(|w| unsafe { w.bits(self.tx.as_ptr() as u32) });
+        self.uarte.txd.maxcnt.write(|w| unsafe { w.bits(len as u32) });
+        self.uarte.tasks_starttx.write(|w| unsafe { w.bits(1) });
+        self.busy = true;
+        len
+    }
+
+    fn set(&mut self, direction: Direction, enabled: bool) {
+        match direction {
+            Direction::Read => self.read_enabled = enabled,
+            Direction::Write => self.write_enabled = enabled,
+        }
+    }
+}
+
+fn convert_baudrate(baudrate: u32) -> Option<Baudrate> {
+    Some(match baudrate {
+        1200 => Baudrate::BAUD1200,
+        2400 => Baudrate::BAUD2400,
+        4800 => Baudrate::BAUD4800,
+        9600 => Baudrate::BAUD9600,
+        14400 => Baudrate::BAUD14400,
+        19200 => Baudrate::BAUD19200,
+        28800 => Baudrate::BAUD28800,
+        31250 => Baudrate::BAUD31250,
+        38400 => Baudrate::BAUD38400,
+        56000 => Baudrate::BAUD56000,
+        57600 => Baudrate::BAUD57600,
+        76800 => Baudrate::BAUD76800,
+        115200 => Baudrate::BAUD115200,
+        230400 => Baudrate::BAUD230400,
+        250000 => Baudrate::BAUD250000,
+        460800 => Baudrate::BAUD460800,
+        921600 => Baudrate::BAUD921600,
+        1000000 => Baudrate::BAUD1M,
+        _ => return None,
+    })
+}
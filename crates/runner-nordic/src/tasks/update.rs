@@ -0,0 +1,315 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Firmware updates with a dual-bank scheme.
+//!
+//! The update region follows the store in flash (see `build.rs`) and contains:
+//! - the staging bank,
+//! - the boot record page, holding the magic and the image length of an applied update,
+//! - the 2 rollback counter pages.
+//!
+//! Applying an update writes the boot record and resets. On the next boot, [`install()`] runs
+//! before anything else: it copies the staging bank to the application bank from RAM, clears the
+//! magic, and resets into the new firmware. A power loss during the copy leaves the device without
+//! a valid firmware, which only a dedicated bootloader could prevent.
+//!
+//! The rollback counter pages are append-only logs of the complement of the version, such that
+//! erased words read as version 0. A page is only erased after the other page holds the current
+//! version, so a power loss never loses the counter.
+
+use core::arch::asm;
+use core::slice;
+
+use hal::pac::NVMC;
+use wasefire_board_api::platform::Api as _;
+use wasefire_board_api::update::{Api, Bank, Staged};
+use wasefire_board_api::{Api as _, Error, Unsupported};
+use wasefire_logger as logger;
+
+use crate::tasks::Board;
+
+include!(concat!(env!("OUT_DIR"), "/firmware_key.rs"));
+
+const PAGE_SIZE: usize = 4096;
+const WORD_SIZE: usize = 4;
+
+/// Marks a valid boot record ("WFUP").
+const BOOT_MAGIC: u32 = 0x5055_4657;
+
+impl Api for &mut Board {
+    fn is_supported(&mut self) -> bool {
+        true
+    }
+
+    fn min_version(&mut self) -> u32 {
+        critical_section::with(|cs| self.0.borrow_ref(cs).update.min_version())
+    }
+
+    fn start(&mut self) -> Result<(), Error> {
+        critical_section::with(|cs| self.0.borrow_ref_mut(cs).update.start())
+    }
+
+    fn write(&mut self, offset: usize, chunk: &[u8]) -> Result<(), Error> {
+        critical_section::with(|cs| self.0.borrow_ref_mut(cs).update.write(offset, chunk))
+    }
+
+    fn finalize(&mut self, signature: &[u8; 64]) -> Result<(), Error> {
+        critical_section::with(|cs| {
+            self.0.borrow_ref_mut(cs).update.finalize(&mut Unsupported, signature)
+        })
+    }
+
+    fn apply(&mut self) -> Result<(), Error> {
+        critical_section::with(|cs| self.0.borrow_ref_mut(cs).update.apply())?;
+        self.platform().reset()
+    }
+}
+
+pub fn new() -> Staged<Staging> {
+    Staged::new(Staging::new(), FIRMWARE_KEY)
+}
+
+/// Installs an applied update, if any.
+///
+/// This must be called first thing in `main()`. It only returns if there is no update to install.
+pub fn install() {
+    let staging = Staging::new();
+    let record = staging.record();
+    if staging.read_word(record) != BOOT_MAGIC {
+        return;
+    }
+    let length = staging.read_word(record + WORD_SIZE) as usize;
+    if length == 0 || staging.read().len() < length {
+        logger::error!("Invalid boot record of length {}.", length);
+        staging.write_word(record, 0);
+        return;
+    }
+    logger::info!("Installing firmware update of {} bytes.", length);
+    cortex_m::interrupt::disable();
+    let source = staging.flash.as_ptr() as usize;
+    let magic = staging.flash[record ..].as_ptr() as usize;
+    // SAFETY: Interrupts are disabled and the copy runs from RAM.
+    unsafe { copy_and_reset(source, length, magic) }
+}
+
+/// Staging bank in flash.
+///
+/// Pages are erased lazily when written, such that starting an update doesn't block for the whole
+/// bank. All pages before the last written one are erased.
+pub struct Staging {
+    /// The update region.
+    flash: &'static [u8],
+    /// Number of erased pages at the beginning of the staging bank.
+    erased: usize,
+}
+
+impl Staging {
+    fn new() -> Self {
+        extern "C" {
+            static __supdate: u32;
+            static __eupdate: u32;
+        }
+        // SAFETY: The linker script reserves this region for firmware updates.
+        let flash = unsafe {
+            let start = &__supdate as *const u32 as *const u8;
+            let length = &__eupdate as *const u32 as usize - start as usize;
+            assert_eq!(length % PAGE_SIZE, 0);
+            assert!(3 * PAGE_SIZE < length);
+            slice::from_raw_parts(start, length)
+        };
+        Staging { flash, erased: 0 }
+    }
+
+    fn record(&self) -> usize {
+        self.flash.len() - 3 * PAGE_SIZE
+    }
+
+    fn counter(&self, page: usize) -> usize {
+        self.flash.len() - (2 - page) * PAGE_SIZE
+    }
+
+    /// Returns the versions of a rollback counter page.
+    fn versions(&self, page: usize) -> impl Iterator<Item = u32> + '_ {
+        let page = self.counter(page);
+        (page .. page + PAGE_SIZE).step_by(WORD_SIZE).map(|x| !self.read_word(x))
+    }
+
+    /// Returns the first erased word of a rollback counter page.
+    fn free_word(&self, page: usize) -> Option<usize> {
+        let free = self.versions(page).position(|x| x == 0)?;
+        Some(self.counter(page) + free * WORD_SIZE)
+    }
+
+    fn read_word(&self, offset: usize) -> u32 {
+        u32::from_le_bytes(self.flash[offset ..][.. WORD_SIZE].try_into().unwrap())
+    }
+
+    fn erase_page(&self, offset: usize) {
+        let nvmc = nvmc();
+        nvmc.config.write(|w| w.wen().een());
+        let address = self.flash[offset ..].as_ptr() as u32;
+        nvmc.erasepage().write(|w| unsafe { w.bits(address) });
+        wait_ready();
+        nvmc.config.write(|w| w.wen().ren());
+    }
+
+    fn write_word(&self, offset: usize, word: u32) {
+        let nvmc = nvmc();
+        nvmc.config.write(|w| w.wen().wen());
+        let address = self.flash[offset ..].as_ptr() as *mut u32;
+        // SAFETY: The address is a word of the update region and writes are enabled.
+        unsafe { address.write_volatile(word) };
+        wait_ready();
+        nvmc.config.write(|w| w.wen().ren());
+    }
+
+    /// Appends a version to the rollback counter.
+    fn write_version(&self, version: u32) {
+        for page in 0 .. 2 {
+            let Some(offset) = self.free_word(page) else { continue };
+            self.write_word(offset, !version);
+            if offset + WORD_SIZE == self.counter(page) + PAGE_SIZE {
+                // The page is full and holds the version, so the other page can be erased.
+                self.erase_page(self.counter(1 - page));
+                self.write_word(self.counter(1 - page), !version);
+            }
+            return;
+        }
+        // Both pages are full after a power loss between filling a page and erasing the other. The
+        // page with the smaller maximum doesn't hold the current version.
+        let page = match self.versions(0).max() < self.versions(1).max() {
+            true => 0,
+            false => 1,
+        };
+        self.erase_page(self.counter(page));
+        self.write_word(self.counter(page), !version);
+    }
+}
+
+impl Bank for Staging {
+    fn read(&self) -> &[u8] {
+        &self.flash[.. self.record()]
+    }
+
+    fn version(&self) -> u32 {
+        self.versions(0).chain(self.versions(1)).max().unwrap()
+    }
+
+    fn erase(&mut self) -> Result<(), Error> {
+        // Invalidates a pending boot record without erasing the page.
+        self.write_word(self.record(), 0);
+        self.erased = 0;
+        Ok(())
+    }
+
+    fn write(&mut self, offset: usize, chunk: &[u8]) -> Result<(), Error> {
+        let end = offset + chunk.len();
+        while self.erased * PAGE_SIZE < end {
+            self.erase_page(self.erased * PAGE_SIZE);
+            self.erased += 1;
+        }
+        // Bytes outside the chunk are written as 0xff which leaves them unchanged.
+        let start = offset - offset % WORD_SIZE;
+        for word in (start .. end).step_by(WORD_SIZE) {
+            let mut bytes = [0xff; WORD_SIZE];
+            for (i, byte) in bytes.iter_mut().enumerate() {
+                if let Some(x) = (word + i).checked_sub(offset).and_then(|j| chunk.get(j)) {
+                    *byte = *x;
+                }
+            }
+            self.write_word(word, u32::from_le_bytes(bytes));
+        }
+        Ok(())
+    }
+
+    fn activate(&mut self, length: usize, version: u32) -> Result<(), Error> {
+        if self.version() < version {
+            self.write_version(version);
+        }
+        let record = self.record();
+        self.erase_page(record);
+        self.write_word(record + WORD_SIZE, length as u32);
+        // The magic is written last such that the record is only valid once complete.
+        self.write_word(record, BOOT_MAGIC);
+        Ok(())
+    }
+}
+
+fn nvmc() -> &'static hal::pac::nvmc::RegisterBlock {
+    // SAFETY: The store only uses the NVMC synchronously, so it is idle when we use it.
+    unsafe { &*NVMC::ptr() }
+}
+
+fn wait_ready() {
+    while nvmc().ready.read().ready().is_busy() {}
+}
+
+const NVMC_READY: usize = 0x4001_e400;
+const NVMC_CONFIG: usize = 0x4001_e504;
+const NVMC_ERASEPAGE: usize = 0x4001_e508;
+const WDT_RR0: usize = 0x4001_0600;
+const WDT_RELOAD: u32 = 0x6e52_4635;
+const SCB_AIRCR: usize = 0xe000_ed0c;
+const SCB_AIRCR_SYSRESETREQ: u32 = 0x05fa_0004;
+
+/// Copies the staging bank to the application bank, clears the magic, and resets.
+///
+/// The application bank is overwritten, so this function lives in RAM and only uses inline code.
+/// The watchdog is fed because it survives the reset that applied the update.
+///
+/// # Safety
+///
+/// Interrupts must be disabled and the arguments must describe the update region.
+#[inline(never)]
+#[link_section = ".data.install"]
+unsafe fn copy_and_reset(source: usize, length: usize, magic: usize) -> ! {
+    let mut offset = 0;
+    while offset < length {
+        if offset % PAGE_SIZE == 0 {
+            store(WDT_RR0, WDT_RELOAD);
+            store(NVMC_CONFIG, 2);
+            store(NVMC_ERASEPAGE, offset as u32);
+            wait_ready_inline();
+            store(NVMC_CONFIG, 1);
+        }
+        store(offset, load(source + offset));
+        wait_ready_inline();
+        offset += WORD_SIZE;
+    }
+    store(magic, 0);
+    wait_ready_inline();
+    store(NVMC_CONFIG, 0);
+    asm!("dsb");
+    store(SCB_AIRCR, SCB_AIRCR_SYSRESETREQ);
+    loop {
+        asm!("dsb");
+    }
+}
+
+#[inline(always)]
+unsafe fn wait_ready_inline() {
+    while load(NVMC_READY) == 0 {}
+}
+
+#[inline(always)]
+unsafe fn load(address: usize) -> u32 {
+    let value: u32;
+    asm!("ldr {}, [{}]", out(reg) value, in(reg) address, options(nostack, readonly));
+    value
+}
+
+#[inline(always)]
+unsafe fn store(address: usize, value: u32) {
+    asm!("str {}, [{}]", in(reg) value, in(reg) address, options(nostack));
+}
@@ -12,8 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use nrf52840_hal::usbd::{UsbPeripheral, Usbd};
+use hal::usbd::{UsbPeripheral, Usbd};
 use wasefire_board_api as board;
+use wasefire_board_api::usb::hid::{HasHid, Hid, WithHid};
 use wasefire_board_api::usb::serial::{HasSerial, Serial, WithSerial};
 
 use crate::tasks::Board;
@@ -21,12 +22,25 @@ use crate::tasks::Board;
 pub type Usb = Usbd<UsbPeripheral<'static>>;
 
 impl board::usb::Api for &mut Board {
+    type Hid<'a> = WithHid<&'a mut Board> where Self: 'a;
+    fn hid(&mut self) -> Self::Hid<'_> {
+        WithHid(self)
+    }
+
     type Serial<'a> = WithSerial<&'a mut Board> where Self: 'a;
     fn serial(&mut self) -> Self::Serial<'_> {
         WithSerial(self)
     }
 }
 
+impl HasHid for &mut Board {
+    type UsbBus = Usb;
+
+    fn with_hid<R>(&mut self, f: impl FnOnce(&mut Hid<Self::UsbBus>) -> R) -> R {
+        critical_section::with(|cs| f(&mut self.0.borrow_ref_mut(cs).hid))
+    }
+}
+
 impl HasSerial for &mut Board {
     type UsbBus = Usb;
 
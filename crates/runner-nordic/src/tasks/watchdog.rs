@@ -0,0 +1,80 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use hal::pac::WDT;
+use hal::wdt::handles::Hdl0;
+use hal::wdt::{self, count, Inactive, WatchdogHandle};
+use wasefire_board_api::watchdog::Api;
+use wasefire_board_api::Error;
+
+impl Api for &mut crate::tasks::Board {
+    fn start(&mut self, timeout_ms: usize) -> Result<(), Error> {
+        critical_section::with(|cs| self.0.borrow_ref_mut(cs).watchdog.start(timeout_ms))
+    }
+
+    fn feed(&mut self) -> Result<(), Error> {
+        critical_section::with(|cs| self.0.borrow_ref_mut(cs).watchdog.feed())
+    }
+
+    fn stop(&mut self) -> Result<(), Error> {
+        // The WDT can't be stopped once started (only a reset stops it).
+        Err(Error::User)
+    }
+}
+
+/// Hardware watchdog.
+///
+/// The watchdog is paused while the CPU sleeps, such that waiting for events doesn't need feeding.
+pub enum Watchdog {
+    Invalid,
+    Inactive(wdt::Watchdog<Inactive>),
+    Active(WatchdogHandle<Hdl0>),
+}
+
+impl Watchdog {
+    pub fn new(wdt: WDT) -> Self {
+        match wdt::Watchdog::try_new(wdt) {
+            Ok(x) => Watchdog::Inactive(x),
+            // The watchdog survives soft resets, in which case it keeps its configuration.
+            Err(wdt) => match wdt::Watchdog::try_recover::<count::One>(wdt) {
+                Ok(parts) => Watchdog::Active(parts.handles.0),
+                Err(_) => Watchdog::Invalid,
+            },
+        }
+    }
+
+    fn start(&mut self, timeout_ms: usize) -> Result<(), Error> {
+        let mut watchdog = match core::mem::replace(self, Watchdog::Invalid) {
+            Watchdog::Inactive(x) => x,
+            x => {
+                *self = x;
+                return Err(Error::User);
+            }
+        };
+        // The watchdog runs at 32768Hz.
+        let ticks = (timeout_ms as u64 * 32768 / 1000).try_into().unwrap_or(u32::MAX);
+        watchdog.set_lfosc_ticks(ticks);
+        watchdog.run_during_sleep(false);
+        *self = Watchdog::Active(watchdog.activate::<count::One>().handles.0);
+        Ok(())
+    }
+
+    fn feed(&mut self) -> Result<(), Error> {
+        match self {
+            Watchdog::Active(x) => x.pet(),
+            _ => return Err(Error::User),
+        }
+        Ok(())
+    }
+}
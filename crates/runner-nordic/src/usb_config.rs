@@ -0,0 +1,73 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! USB identity of the device.
+//!
+//! The identity is configured at build time with the following environment variables:
+//! - `WASEFIRE_USB_VID` and `WASEFIRE_USB_PID` are the vendor and product IDs (decimal or
+//!   hexadecimal with a `0x` prefix). They default to `0x16c0` and `0x27dd`.
+//! - `WASEFIRE_USB_MANUFACTURER` is the manufacturer string. There is none by default.
+//! - `WASEFIRE_USB_PRODUCT` is the product string. It defaults to `Wasefire`.
+//! - `WASEFIRE_USB_SERIAL` is the serial number string, or `ficr` to derive it from the device ID.
+//!   There is none by default.
+
+use alloc::boxed::Box;
+use alloc::format;
+
+use hal::pac::FICR;
+use usb_device::class_prelude::UsbBusAllocator;
+use usb_device::device::{UsbDevice, UsbDeviceBuilder, UsbVidPid};
+
+use crate::tasks::usb::Usb;
+
+/// Source of the serial number string.
+#[allow(dead_code)]
+pub enum SerialNumber {
+    /// There is no serial number.
+    None,
+
+    /// The serial number is a fixed string.
+    Fixed(&'static str),
+
+    /// The serial number is the device ID in hexadecimal.
+    Ficr,
+}
+
+include!(concat!(env!("OUT_DIR"), "/usb_config.rs"));
+
+/// Builds the USB device with the configured identity.
+pub fn build(usb_bus: &'static UsbBusAllocator<Usb>) -> UsbDevice<'static, Usb> {
+    let mut builder = UsbDeviceBuilder::new(usb_bus, UsbVidPid(VID, PID)).product(PRODUCT);
+    if let Some(manufacturer) = MANUFACTURER {
+        builder = builder.manufacturer(manufacturer);
+    }
+    if let Some(serial_number) = serial_number() {
+        builder = builder.serial_number(serial_number);
+    }
+    builder.composite_with_iads().build()
+}
+
+fn serial_number() -> Option<&'static str> {
+    match SERIAL_NUMBER {
+        SerialNumber::None => None,
+        SerialNumber::Fixed(x) => Some(x),
+        SerialNumber::Ficr => {
+            // SAFETY: We only read the FICR which is read-only.
+            let ficr = unsafe { &*FICR::ptr() };
+            let id = ficr.deviceid.iter().rev().fold(0, |id, x| id << 32 | x.read().bits() as u64);
+            // This is called once at boot and the USB device lives forever.
+            Some(Box::leak(format!("{id:016X}").into_boxed_str()))
+        }
+    }
+}
@@ -22,5 +22,14 @@ fi
 cargo check --target=thumbv7em-none-eabi --features=debug
 DEFMT_LOG=trace cargo check --target=thumbv7em-none-eabi --features=debug
 cargo check --target=thumbv7em-none-eabi --features=release
+cargo check --target=thumbv7em-none-eabi --features=debug,rng-mixing
+WASEFIRE_APPLET_KEY=$(printf '%064x' 0) \
+  cargo check --target=thumbv7em-none-eabi --features=debug,secure-boot
+WASEFIRE_FIRMWARE_KEY=$(printf '%064x' 0) \
+  cargo check --target=thumbv7em-none-eabi --features=debug,firmware-update
+WASEFIRE_USB_MANUFACTURER=Google WASEFIRE_USB_SERIAL=ficr \
+  cargo check --target=thumbv7em-none-eabi --features=debug
+cargo check --target=thumbv7em-none-eabi --no-default-features --features=debug,nrf52833
 cargo fmt -- --check
 cargo clippy --target=thumbv7em-none-eabi --features=debug -- --deny=warnings
+cargo clippy --target=thumbv7em-none-eabi --no-default-features --features=debug,nrf52833 -- --deny=warnings
@@ -17,27 +17,53 @@ use wasefire_board_api::Api as Board;
 
 use crate::DispatchSchedulerCall;
 
+mod adc;
 mod button;
+mod capture;
 mod clock;
 mod crypto;
 mod debug;
+mod encoder;
+mod gpio;
+mod i2c_sw;
 mod led;
+mod led_ring;
+mod magnetometer;
+pub mod platform;
+mod power;
+mod pwm;
 mod rng;
 mod scheduling;
+mod stepper;
 mod store;
+mod temp;
+mod uart;
 mod usb;
 
 pub fn process<B: Board>(call: Api<DispatchSchedulerCall<B>>) {
     match call {
+        Api::Adc(call) => adc::process(call),
         Api::Button(call) => button::process(call),
+        Api::Capture(call) => capture::process(call),
         Api::Clock(call) => clock::process(call),
         Api::Crypto(call) => crypto::process(call),
         Api::Debug(call) => debug::process(call),
+        Api::Encoder(call) => encoder::process(call),
+        Api::Gpio(call) => gpio::process(call),
+        Api::I2cSw(call) => i2c_sw::process(call),
         Api::Led(call) => led::process(call),
+        Api::LedRing(call) => led_ring::process(call),
+        Api::Magnetometer(call) => magnetometer::process(call),
+        Api::Platform(call) => platform::process(call),
+        Api::Power(call) => power::process(call),
+        Api::Pwm(call) => pwm::process(call),
         Api::Rng(call) => rng::process(call),
         Api::Scheduling(call) => scheduling::process(call),
+        Api::Stepper(call) => stepper::process(call),
         Api::Store(call) => store::process(call),
         Api::Syscall(_) => todo!(),
+        Api::Temp(call) => temp::process(call),
+        Api::Uart(call) => uart::process(call),
         Api::Usb(call) => usb::process(call),
     }
 }
@@ -0,0 +1,58 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use wasefire_applet_api::adc::{self as api, Api};
+use wasefire_board_api::adc::Api as _;
+use wasefire_board_api::Api as Board;
+
+use crate::{DispatchSchedulerCall, SchedulerCall, Trap};
+
+pub fn process<B: Board>(call: Api<DispatchSchedulerCall<B>>) {
+    match call {
+        Api::Count(call) => count(call),
+        Api::Read(call) => read(call),
+        Api::ReadVdd(call) => read_vdd(call),
+    }
+}
+
+fn count<B: Board>(mut call: SchedulerCall<B, api::count::Sig>) {
+    let api::count::Params {} = call.read();
+    let count = call.scheduler().board.adc().count() as u32;
+    call.reply(Ok(api::count::Results { cnt: count.into() }));
+}
+
+fn read<B: Board>(mut call: SchedulerCall<B, api::read::Sig>) {
+    let api::read::Params { adc } = call.read();
+    let adc = *adc as usize;
+    let results = try {
+        if call.scheduler().board.adc().count() <= adc {
+            Err(Trap)?;
+        }
+        let res = match call.scheduler().board.adc().read(adc) {
+            Ok(x) => (x as u32).into(),
+            Err(_) => u32::MAX.into(),
+        };
+        api::read::Results { res }
+    };
+    call.reply(results);
+}
+
+fn read_vdd<B: Board>(mut call: SchedulerCall<B, api::read_vdd::Sig>) {
+    let api::read_vdd::Params {} = call.read();
+    let res = match call.scheduler().board.adc().read_vdd_mv() {
+        Ok(x) => (x as u32).into(),
+        Err(_) => u32::MAX.into(),
+    };
+    call.reply(Ok(api::read_vdd::Results { res }));
+}
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use wasefire_applet_api::button::{self as api, Api};
+use wasefire_board_api::button::gesture::Config;
 use wasefire_board_api::button::Api as _;
 use wasefire_board_api::Api as Board;
 
@@ -24,6 +25,7 @@ pub fn process<B: Board>(call: Api<DispatchSchedulerCall<B>>) {
     match call {
         Api::Count(call) => count(call),
         Api::Register(call) => register(call),
+        Api::Gesture(call) => gesture(call),
         Api::Unregister(call) => unregister(call),
     }
 }
@@ -51,6 +53,26 @@ fn register<B: Board>(mut call: SchedulerCall<B, api::register::Sig>) {
     call.reply(results);
 }
 
+fn gesture<B: Board>(mut call: SchedulerCall<B, api::gesture::Sig>) {
+    let api::gesture::Params { button, long, double } = call.read();
+    let button = *button as usize;
+    let config = match *long {
+        0 => None,
+        long => Some(Config { long_us: long as u64 * 1000, double_us: *double as u64 * 1000 }),
+    };
+    let results = try {
+        if call.scheduler().board.button().count() <= button {
+            Err(Trap)?;
+        }
+        let res = match crate::event::button::configure(call.scheduler(), button, config) {
+            true => 0,
+            false => u32::MAX,
+        };
+        api::gesture::Results { res: res.into() }
+    };
+    call.reply(results);
+}
+
 fn unregister<B: Board>(mut call: SchedulerCall<B, api::unregister::Sig>) {
     let api::unregister::Params { button } = call.read();
     let button = *button as usize;
@@ -0,0 +1,89 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use wasefire_applet_api::capture::{self as api, Api};
+use wasefire_board_api::capture::Api as _;
+use wasefire_board_api::gpio::Api as _;
+use wasefire_board_api::Api as Board;
+
+use crate::event::capture::Key;
+use crate::event::Handler;
+use crate::{DispatchSchedulerCall, SchedulerCall, Trap};
+
+pub fn process<B: Board>(call: Api<DispatchSchedulerCall<B>>) {
+    match call {
+        Api::Start(call) => start(call),
+        Api::Result(call) => result(call),
+        Api::Register(call) => register(call),
+        Api::Unregister(call) => unregister(call),
+    }
+}
+
+fn start<B: Board>(mut call: SchedulerCall<B, api::start::Sig>) {
+    let api::start::Params { gpio } = call.read();
+    let res = match call.scheduler().board.capture().start(*gpio as usize) {
+        Ok(()) => 0,
+        Err(_) => u32::MAX,
+    };
+    call.reply(Ok(api::start::Results { res: res.into() }));
+}
+
+fn result<B: Board>(mut call: SchedulerCall<B, api::result::Sig>) {
+    let api::result::Params { gpio, ptr } = call.read();
+    let scheduler = call.scheduler();
+    let memory = scheduler.applet.memory();
+    let results = try {
+        let output = memory.get_array_mut::<8>(*ptr)?;
+        let res = match scheduler.board.capture().result(*gpio as usize) {
+            Ok(Some(capture)) => {
+                output[.. 4].copy_from_slice(&capture.period_ns.to_le_bytes());
+                output[4 ..].copy_from_slice(&capture.high_ns.to_le_bytes());
+                1
+            }
+            Ok(None) => 0,
+            Err(_) => u32::MAX,
+        };
+        api::result::Results { res: res.into() }
+    };
+    call.reply(results);
+}
+
+fn register<B: Board>(mut call: SchedulerCall<B, api::register::Sig>) {
+    let api::register::Params { gpio, handler_func, handler_data } = call.read();
+    let gpio = *gpio as usize;
+    let inst = call.inst();
+    let results = try {
+        if call.scheduler().board.gpio().count() <= gpio {
+            Err(Trap)?;
+        }
+        call.scheduler().applet.enable(Handler {
+            key: Key { gpio }.into(),
+            inst,
+            func: *handler_func,
+            data: *handler_data,
+        })?;
+        api::register::Results {}
+    };
+    call.reply(results);
+}
+
+fn unregister<B: Board>(mut call: SchedulerCall<B, api::unregister::Sig>) {
+    let api::unregister::Params { gpio } = call.read();
+    let gpio = *gpio as usize;
+    let results = try {
+        call.scheduler().disable_event(Key { gpio }.into())?;
+        api::unregister::Results {}
+    };
+    call.reply(results);
+}
@@ -13,7 +13,8 @@
 // limitations under the License.
 
 use wasefire_applet_api::clock::{self as api, Api};
-use wasefire_board_api::timer::{Api as _, Command};
+use wasefire_board_api::clock::Api as _;
+use wasefire_board_api::timer::{Api as _, Mode};
 use wasefire_board_api::Api as Board;
 
 use crate::event::timer::Key;
@@ -26,6 +27,7 @@ pub fn process<B: Board>(call: Api<DispatchSchedulerCall<B>>) {
         Api::Start(call) => start(call),
         Api::Stop(call) => stop(call),
         Api::Free(call) => free(call),
+        Api::Uptime(call) => uptime(call),
     }
 }
 
@@ -35,7 +37,7 @@ fn allocate<B: Board>(mut call: SchedulerCall<B, api::allocate::Sig>) {
     let results = try {
         let timers = &mut call.scheduler().timers;
         let timer = timers.iter().position(|x| x.is_none()).ok_or(Trap)?;
-        timers[timer] = Some(Timer {});
+        timers[timer] = Some(Timer { applet: inst.store_id() });
         call.scheduler().applet.enable(Handler {
             key: Key { timer }.into(),
             inst,
@@ -52,10 +54,12 @@ fn start<B: Board>(mut call: SchedulerCall<B, api::start::Sig>) {
     let timer = *id as usize;
     let results = try {
         get_timer(call.scheduler(), timer)?;
-        let periodic = matches!(api::Mode::try_from(*mode)?, api::Mode::Periodic);
-        let duration_ms = *duration_ms as usize;
-        let command = Command { periodic, duration_ms };
-        call.scheduler().board.timer().arm(timer, &command).map_err(|_| Trap)?;
+        let mode = match api::Mode::try_from(*mode)? {
+            api::Mode::Oneshot => Mode::OneShot,
+            api::Mode::Periodic => Mode::Periodic,
+        };
+        let duration_us = (*duration_ms as usize).checked_mul(1000).ok_or(Trap)?;
+        call.scheduler().board.timer().arm(timer, duration_us, mode).map_err(|_| Trap)?;
         api::start::Results {}
     };
     call.reply(results);
@@ -84,10 +88,29 @@ fn free<B: Board>(mut call: SchedulerCall<B, api::free::Sig>) {
     call.reply(results);
 }
 
-// TODO: Should also check that the timer belongs to the calling applet.
+/// Returns a timer of the running applet.
 fn get_timer<B: Board>(scheduler: &mut Scheduler<B>, timer: usize) -> Result<&mut Timer, Trap> {
+    let applet = scheduler.applet.store_mut().id();
     match scheduler.timers.get_mut(timer) {
-        Some(Some(x)) => Ok(x),
+        Some(Some(x)) if x.applet == applet => Ok(x),
         _ => Err(Trap),
     }
 }
+
+fn uptime<B: Board>(mut call: SchedulerCall<B, api::uptime::Sig>) {
+    let api::uptime::Params { ptr } = call.read();
+    let scheduler = call.scheduler();
+    let memory = scheduler.applet.memory();
+    let results = try {
+        let output = memory.get_array_mut::<8>(*ptr)?;
+        let res = match scheduler.board.clock().uptime_us() {
+            Ok(uptime) => {
+                *output = uptime.to_le_bytes();
+                0
+            }
+            Err(_) => u32::MAX,
+        };
+        api::uptime::Results { res: res.into() }
+    };
+    call.reply(results);
+}
@@ -13,6 +13,8 @@
 // limitations under the License.
 
 mod ccm;
+mod crc;
+mod ecdh;
 mod gcm;
 mod hash;
 
@@ -24,6 +26,8 @@ use crate::DispatchSchedulerCall;
 pub fn process<B: Board>(call: Api<DispatchSchedulerCall<B>>) {
     match call {
         Api::Ccm(call) => ccm::process(call),
+        Api::Crc(call) => crc::process(call),
+        Api::Ecdh(call) => ecdh::process(call),
         Api::Gcm(call) => gcm::process(call),
         Api::Hash(call) => hash::process(call),
     }
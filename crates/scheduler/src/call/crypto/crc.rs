@@ -0,0 +1,83 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use wasefire_applet_api::crypto as crypto_api;
+use wasefire_applet_api::crypto::crc::{self as api, Api};
+use wasefire_board_api::crypto::crc::Api as _;
+use wasefire_board_api::crypto::Api as _;
+use wasefire_board_api::Api as Board;
+
+use crate::{DispatchSchedulerCall, SchedulerCall};
+
+pub fn process<B: Board>(call: Api<DispatchSchedulerCall<B>>) {
+    match call {
+        Api::IsSupported(call) => is_supported(call),
+        Api::Crc32(call) => crc32(call),
+        Api::Crc16Ccitt(call) => crc16_ccitt(call),
+    }
+}
+
+fn is_supported<B: Board>(mut call: SchedulerCall<B, api::is_supported::Sig>) {
+    let api::is_supported::Params {} = call.read();
+    let supported = call.scheduler().board.crypto().crc().is_supported() as u32;
+    call.reply(Ok(api::is_supported::Results { supported: supported.into() }))
+}
+
+fn crc32<B: Board>(mut call: SchedulerCall<B, api::crc32::Sig>) {
+    let api::crc32::Params { data, length, crc } = call.read();
+    let scheduler = call.scheduler();
+    let memory = scheduler.applet.memory();
+    let results = try {
+        let data = memory.get(*data, *length)?;
+        let crc = memory.get_array_mut::<4>(*crc)?;
+        let mut crypto = scheduler.board.crypto();
+        let res = if !crypto.crc().is_supported() {
+            crypto_api::Error::Unsupported.into()
+        } else {
+            match crypto.crc().crc32(data) {
+                Ok(x) => {
+                    *crc = x.to_le_bytes();
+                    0u32.into()
+                }
+                Err(_) => crypto_api::Error::InvalidArgument.into(),
+            }
+        };
+        api::crc32::Results { res }
+    };
+    call.reply(results);
+}
+
+fn crc16_ccitt<B: Board>(mut call: SchedulerCall<B, api::crc16_ccitt::Sig>) {
+    let api::crc16_ccitt::Params { data, length, crc } = call.read();
+    let scheduler = call.scheduler();
+    let memory = scheduler.applet.memory();
+    let results = try {
+        let data = memory.get(*data, *length)?;
+        let crc = memory.get_array_mut::<2>(*crc)?;
+        let mut crypto = scheduler.board.crypto();
+        let res = if !crypto.crc().is_supported() {
+            crypto_api::Error::Unsupported.into()
+        } else {
+            match crypto.crc().crc16_ccitt(data) {
+                Ok(x) => {
+                    *crc = x.to_le_bytes();
+                    0u32.into()
+                }
+                Err(_) => crypto_api::Error::InvalidArgument.into(),
+            }
+        };
+        api::crc16_ccitt::Results { res }
+    };
+    call.reply(results);
+}
@@ -0,0 +1,83 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use wasefire_applet_api::crypto as crypto_api;
+use wasefire_applet_api::crypto::ecdh::{self as api, Api};
+use wasefire_board_api::crypto::ecdh::Api as _;
+use wasefire_board_api::crypto::Api as _;
+use wasefire_board_api::rng::Api as _;
+use wasefire_board_api::Api as Board;
+
+use crate::{DispatchSchedulerCall, SchedulerCall};
+
+pub fn process<B: Board>(call: Api<DispatchSchedulerCall<B>>) {
+    match call {
+        Api::IsSupported(call) => is_supported(call),
+        Api::GenerateKeypair(call) => generate_keypair(call),
+        Api::Agree(call) => agree(call),
+    }
+}
+
+fn is_supported<B: Board>(mut call: SchedulerCall<B, api::is_supported::Sig>) {
+    let api::is_supported::Params {} = call.read();
+    let supported = call.scheduler().board.crypto().ecdh().is_supported() as u32;
+    call.reply(Ok(api::is_supported::Results { supported: supported.into() }))
+}
+
+fn generate_keypair<B: Board>(mut call: SchedulerCall<B, api::generate_keypair::Sig>) {
+    let api::generate_keypair::Params { private, public } = call.read();
+    let scheduler = call.scheduler();
+    let memory = scheduler.applet.memory();
+    let results = try {
+        let private = memory.get_array_mut::<32>(*private)?;
+        let public = memory.get_array_mut::<64>(*public)?;
+        let res = if !scheduler.board.crypto().ecdh().is_supported() {
+            crypto_api::Error::Unsupported.into()
+        } else {
+            // A random private key is invalid with negligible probability (about 2^-32).
+            loop {
+                if scheduler.board.rng().fill_bytes(private).is_err() {
+                    break u32::MAX.into();
+                }
+                if scheduler.board.crypto().ecdh().public_key(private, public).is_ok() {
+                    break 0u32.into();
+                }
+            }
+        };
+        api::generate_keypair::Results { res }
+    };
+    call.reply(results);
+}
+
+fn agree<B: Board>(mut call: SchedulerCall<B, api::agree::Sig>) {
+    let api::agree::Params { private, public, shared } = call.read();
+    let scheduler = call.scheduler();
+    let memory = scheduler.applet.memory();
+    let results = try {
+        let private = memory.get_array::<32>(*private)?;
+        let public = memory.get_array::<64>(*public)?;
+        let shared = memory.get_array_mut::<32>(*shared)?;
+        let mut crypto = scheduler.board.crypto();
+        let res = if !crypto.ecdh().is_supported() {
+            crypto_api::Error::Unsupported.into()
+        } else {
+            match crypto.ecdh().agree(private, public, shared) {
+                Ok(()) => 0u32.into(),
+                Err(_) => crypto_api::Error::InvalidArgument.into(),
+            }
+        };
+        api::agree::Results { res }
+    };
+    call.reply(results);
+}
@@ -13,7 +13,8 @@
 // limitations under the License.
 
 use wasefire_applet_api::debug::{self as api, Api};
-use wasefire_board_api::debug::Api as _;
+use wasefire_board_api::debug::{Api as _, Level};
+use wasefire_board_api::platform::Api as _;
 use wasefire_board_api::Api as Board;
 use wasefire_logger as logger;
 
@@ -22,21 +23,83 @@ use crate::{DispatchSchedulerCall, SchedulerCall, Trap};
 pub fn process<B: Board>(call: Api<DispatchSchedulerCall<B>>) {
     match call {
         Api::Println(call) => println(call),
+        Api::HeapStats(call) => heap_stats(call),
         Api::Exit(call) => exit(call),
+        Api::SetLogLevel(call) => set_log_level(call),
+        Api::Log(call) => log(call),
     }
 }
 
 fn println<B: Board>(mut call: SchedulerCall<B, api::println::Sig>) {
     let api::println::Params { ptr, len } = call.read();
-    let memory = call.memory();
+    let scheduler = call.scheduler();
+    let memory = scheduler.applet.memory();
     let results = try {
-        logger::println!("{}", core::str::from_utf8(memory.get(*ptr, *len)?).map_err(|_| Trap)?);
+        let line = core::str::from_utf8(memory.get(*ptr, *len)?).map_err(|_| Trap)?;
+        scheduler.logs.push(None, line);
+        scheduler.last_line.push(line);
         api::println::Results {}
     };
     call.reply(results)
 }
 
+fn heap_stats<B: Board>(mut call: SchedulerCall<B, api::heap_stats::Sig>) {
+    let api::heap_stats::Params { ptr } = call.read();
+    let scheduler = call.scheduler();
+    let memory = scheduler.applet.memory();
+    let results = try {
+        let output = memory.get_array_mut::<12>(*ptr)?;
+        let res = match scheduler.board.platform().heap_stats() {
+            Ok(stats) => {
+                let stats = [stats.used, stats.free, stats.high_water];
+                for (output, stat) in output.chunks_exact_mut(4).zip(stats) {
+                    output.copy_from_slice(&(stat as u32).to_le_bytes());
+                }
+                0
+            }
+            Err(_) => u32::MAX,
+        };
+        api::heap_stats::Results { res: res.into() }
+    };
+    call.reply(results);
+}
+
 fn exit<B: Board>(mut call: SchedulerCall<B, api::exit::Sig>) {
     let api::exit::Params { code } = call.read();
+    call.scheduler().logs.flush();
+    #[cfg(feature = "metrics")]
+    logger::println!("Scheduler metrics: {}", logger::Display2Format(&call.scheduler().metrics()));
     call.scheduler().board.debug().exit(*code == 0);
 }
+
+fn set_log_level<B: Board>(mut call: SchedulerCall<B, api::set_log_level::Sig>) {
+    let api::set_log_level::Params { level } = call.read();
+    let results = try {
+        let level = match api::Level::try_from(*level)? {
+            api::Level::Error => Level::Error,
+            api::Level::Warn => Level::Warn,
+            api::Level::Info => Level::Info,
+            api::Level::Debug => Level::Debug,
+            api::Level::Trace => Level::Trace,
+        };
+        let res = match call.scheduler().board.debug().set_log_level(level) {
+            Ok(()) => 0,
+            Err(_) => u32::MAX,
+        };
+        api::set_log_level::Results { res: res.into() }
+    };
+    call.reply(results);
+}
+
+fn log<B: Board>(mut call: SchedulerCall<B, api::log::Sig>) {
+    let api::log::Params { level, ptr, len } = call.read();
+    let scheduler = call.scheduler();
+    let memory = scheduler.applet.memory();
+    let results = try {
+        let level = api::Level::try_from(*level)?;
+        let message = core::str::from_utf8(memory.get(*ptr, *len)?).map_err(|_| Trap)?;
+        scheduler.logs.push(Some(level), message);
+        api::log::Results {}
+    };
+    call.reply(results);
+}
@@ -0,0 +1,85 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use wasefire_applet_api::encoder::{self as api, Api};
+use wasefire_board_api::encoder::Api as _;
+use wasefire_board_api::Api as Board;
+
+use crate::event::encoder::Key;
+use crate::event::Handler;
+use crate::{DispatchSchedulerCall, SchedulerCall, Trap};
+
+pub fn process<B: Board>(call: Api<DispatchSchedulerCall<B>>) {
+    match call {
+        Api::Count(call) => count(call),
+        Api::Position(call) => position(call),
+        Api::Home(call) => home(call),
+        Api::Register(call) => register(call),
+        Api::Unregister(call) => unregister(call),
+    }
+}
+
+fn count<B: Board>(mut call: SchedulerCall<B, api::count::Sig>) {
+    let api::count::Params {} = call.read();
+    let count = call.scheduler().board.encoder().count() as u32;
+    call.reply(Ok(api::count::Results { cnt: count.into() }));
+}
+
+fn position<B: Board>(mut call: SchedulerCall<B, api::position::Sig>) {
+    let api::position::Params { encoder } = call.read();
+    let results = try {
+        let encoder = *encoder as usize;
+        let position = call.scheduler().board.encoder().position(encoder).map_err(|_| Trap)?;
+        api::position::Results { position: (position as u32).into() }
+    };
+    call.reply(results);
+}
+
+fn home<B: Board>(mut call: SchedulerCall<B, api::home::Sig>) {
+    let api::home::Params { encoder } = call.read();
+    let res = match call.scheduler().board.encoder().home(*encoder as usize) {
+        Ok(()) => 0,
+        Err(_) => u32::MAX,
+    };
+    call.reply(Ok(api::home::Results { res: res.into() }));
+}
+
+fn register<B: Board>(mut call: SchedulerCall<B, api::register::Sig>) {
+    let api::register::Params { encoder, handler_func, handler_data } = call.read();
+    let encoder = *encoder as usize;
+    let inst = call.inst();
+    let results = try {
+        if call.scheduler().board.encoder().count() <= encoder {
+            Err(Trap)?;
+        }
+        call.scheduler().applet.enable(Handler {
+            key: Key { encoder }.into(),
+            inst,
+            func: *handler_func,
+            data: *handler_data,
+        })?;
+        api::register::Results {}
+    };
+    call.reply(results);
+}
+
+fn unregister<B: Board>(mut call: SchedulerCall<B, api::unregister::Sig>) {
+    let api::unregister::Params { encoder } = call.read();
+    let encoder = *encoder as usize;
+    let results = try {
+        call.scheduler().disable_event(Key { encoder }.into())?;
+        api::unregister::Results {}
+    };
+    call.reply(results);
+}
@@ -0,0 +1,87 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use wasefire_applet_api::gpio::{self as api, Api};
+use wasefire_board_api::debug::Effect;
+use wasefire_board_api::gpio::{Api as _, Mode};
+use wasefire_board_api::Api as Board;
+
+use crate::{DispatchSchedulerCall, SchedulerCall, Trap};
+
+pub fn process<B: Board>(call: Api<DispatchSchedulerCall<B>>) {
+    match call {
+        Api::Count(call) => count(call),
+        Api::Configure(call) => configure(call),
+        Api::Read(call) => read(call),
+        Api::Write(call) => write(call),
+    }
+}
+
+fn count<B: Board>(mut call: SchedulerCall<B, api::count::Sig>) {
+    let api::count::Params {} = call.read();
+    let count = call.scheduler().board.gpio().count() as u32;
+    call.reply(Ok(api::count::Results { cnt: count.into() }));
+}
+
+fn configure<B: Board>(mut call: SchedulerCall<B, api::configure::Sig>) {
+    let api::configure::Params { gpio, mode } = call.read();
+    let results = try {
+        let mode = match api::Mode::try_from(*mode)? {
+            api::Mode::InputPullUp => Mode::InputPullUp,
+            api::Mode::InputPullDown => Mode::InputPullDown,
+            api::Mode::OutputPushPull => Mode::OutputPushPull,
+            api::Mode::OutputOpenDrain => Mode::OutputOpenDrain,
+        };
+        let scheduler = call.scheduler();
+        let gpio = *gpio as usize;
+        if scheduler.board.gpio().count() <= gpio {
+            Err(Trap)?;
+        }
+        if !scheduler.dry_run.skip(&mut scheduler.board, || Effect::GpioConfigure { gpio, mode }) {
+            scheduler.board.gpio().configure(gpio, mode).map_err(|_| Trap)?;
+        }
+        api::configure::Results {}
+    };
+    call.reply(results);
+}
+
+fn read<B: Board>(mut call: SchedulerCall<B, api::read::Sig>) {
+    let api::read::Params { gpio } = call.read();
+    let results = try {
+        let level = call.scheduler().board.gpio().read(*gpio as usize).map_err(|_| Trap)?;
+        api::read::Results { level: (level as u32).into() }
+    };
+    call.reply(results);
+}
+
+fn write<B: Board>(mut call: SchedulerCall<B, api::write::Sig>) {
+    let api::write::Params { gpio, level } = call.read();
+    let results = try {
+        let level = match *level {
+            0 => false,
+            1 => true,
+            _ => Err(Trap)?,
+        };
+        let scheduler = call.scheduler();
+        let gpio = *gpio as usize;
+        if scheduler.board.gpio().count() <= gpio {
+            Err(Trap)?;
+        }
+        if !scheduler.dry_run.skip(&mut scheduler.board, || Effect::GpioWrite { gpio, level }) {
+            scheduler.board.gpio().write(gpio, level).map_err(|_| Trap)?;
+        }
+        api::write::Results {}
+    };
+    call.reply(results);
+}
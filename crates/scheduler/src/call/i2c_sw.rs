@@ -0,0 +1,89 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use wasefire_applet_api::i2c_sw::{self as api, Api};
+use wasefire_applet_api::U32;
+use wasefire_board_api::i2c_sw::{Api as _, Config};
+use wasefire_board_api::{Api as Board, Error};
+
+use crate::{DispatchSchedulerCall, SchedulerCall};
+
+pub fn process<B: Board>(call: Api<DispatchSchedulerCall<B>>) {
+    match call {
+        Api::Configure(call) => configure(call),
+        Api::Read(call) => read(call),
+        Api::Write(call) => write(call),
+        Api::WriteRead(call) => write_read(call),
+    }
+}
+
+fn configure<B: Board>(mut call: SchedulerCall<B, api::configure::Sig>) {
+    let api::configure::Params { sda, scl, frequency } = call.read();
+    let config = Config { sda: *sda as usize, scl: *scl as usize, frequency_hz: *frequency };
+    let res = convert(call.scheduler().board.i2c_sw().configure(&config));
+    call.reply(Ok(api::configure::Results { res }));
+}
+
+fn read<B: Board>(mut call: SchedulerCall<B, api::read::Sig>) {
+    let api::read::Params { addr, ptr, len } = call.read();
+    let scheduler = call.scheduler();
+    let memory = scheduler.applet.memory();
+    let results = try {
+        let output = memory.get_mut(*ptr, *len)?;
+        let res = match u8::try_from(*addr) {
+            Ok(addr) => convert(scheduler.board.i2c_sw().read(addr, output)),
+            Err(_) => u32::MAX.into(),
+        };
+        api::read::Results { res }
+    };
+    call.reply(results);
+}
+
+fn write<B: Board>(mut call: SchedulerCall<B, api::write::Sig>) {
+    let api::write::Params { addr, ptr, len } = call.read();
+    let scheduler = call.scheduler();
+    let memory = scheduler.applet.memory();
+    let results = try {
+        let input = memory.get(*ptr, *len)?;
+        let res = match u8::try_from(*addr) {
+            Ok(addr) => convert(scheduler.board.i2c_sw().write(addr, input)),
+            Err(_) => u32::MAX.into(),
+        };
+        api::write::Results { res }
+    };
+    call.reply(results);
+}
+
+fn write_read<B: Board>(mut call: SchedulerCall<B, api::write_read::Sig>) {
+    let api::write_read::Params { addr, wptr, wlen, rptr, rlen } = call.read();
+    let scheduler = call.scheduler();
+    let memory = scheduler.applet.memory();
+    let results = try {
+        let input = memory.get(*wptr, *wlen)?;
+        let output = memory.get_mut(*rptr, *rlen)?;
+        let res = match u8::try_from(*addr) {
+            Ok(addr) => convert(scheduler.board.i2c_sw().write_read(addr, input, output)),
+            Err(_) => u32::MAX.into(),
+        };
+        api::write_read::Results { res }
+    };
+    call.reply(results);
+}
+
+fn convert(result: Result<(), Error>) -> U32<isize> {
+    match result {
+        Ok(()) => 0.into(),
+        Err(_) => u32::MAX.into(),
+    }
+}
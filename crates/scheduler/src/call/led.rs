@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use wasefire_applet_api::led::{self as api, Api};
+use wasefire_board_api::debug::Effect;
 use wasefire_board_api::led::Api as _;
 use wasefire_board_api::Api as Board;
 
@@ -23,6 +24,7 @@ pub fn process<B: Board>(call: Api<DispatchSchedulerCall<B>>) {
         Api::Count(call) => count(call),
         Api::Get(call) => get(call),
         Api::Set(call) => set(call),
+        Api::SetBrightness(call) => set_brightness(call),
     }
 }
 
@@ -48,8 +50,27 @@ fn set<B: Board>(mut call: SchedulerCall<B, api::set::Sig>) {
     let api::set::Params { led, status } = call.read();
     let results = try {
         let on = matches!(api::Status::try_from(*status)?, api::Status::On);
-        call.scheduler().board.led().set(*led as usize, on).map_err(|_| Trap)?;
+        let scheduler = call.scheduler();
+        let led = *led as usize;
+        if !scheduler.dry_run.skip(&mut scheduler.board, || Effect::LedSet { led, on }) {
+            scheduler.board.led().set(led, on).map_err(|_| Trap)?;
+        }
         api::set::Results {}
     };
     call.reply(results);
 }
+
+fn set_brightness<B: Board>(mut call: SchedulerCall<B, api::set_brightness::Sig>) {
+    let api::set_brightness::Params { led, brightness } = call.read();
+    let results = try {
+        let brightness = u8::try_from(*brightness).map_err(|_| Trap)?;
+        let scheduler = call.scheduler();
+        let led = *led as usize;
+        let effect = || Effect::LedBrightness { led, brightness };
+        if !scheduler.dry_run.skip(&mut scheduler.board, effect) {
+            scheduler.board.led().set_brightness(led, brightness).map_err(|_| Trap)?;
+        }
+        api::set_brightness::Results {}
+    };
+    call.reply(results);
+}
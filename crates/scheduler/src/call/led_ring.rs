@@ -0,0 +1,75 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use wasefire_applet_api::led_ring::{self as api, Api};
+use wasefire_board_api::debug::Effect;
+use wasefire_board_api::led_ring::{Animation, Api as _, Pattern};
+use wasefire_board_api::Api as Board;
+
+use crate::{DispatchSchedulerCall, SchedulerCall, Trap};
+
+pub fn process<B: Board>(call: Api<DispatchSchedulerCall<B>>) {
+    match call {
+        Api::Count(call) => count(call),
+        Api::Animate(call) => animate(call),
+        Api::Stop(call) => stop(call),
+    }
+}
+
+fn count<B: Board>(mut call: SchedulerCall<B, api::count::Sig>) {
+    let api::count::Params {} = call.read();
+    let count = call.scheduler().board.led_ring().count() as u32;
+    call.reply(Ok(api::count::Results { cnt: count.into() }));
+}
+
+fn animate<B: Board>(mut call: SchedulerCall<B, api::animate::Sig>) {
+    let api::animate::Params { pattern, speed, color } = call.read();
+    let scheduler = call.scheduler();
+    let results = try {
+        let pattern = convert_pattern(*pattern)?;
+        let [_, r, g, b] = color.to_be_bytes();
+        let animation = Animation { pattern, speed: *speed, color: [r, g, b] };
+        let effect = || Effect::LedRingAnimate { animation };
+        let res = match scheduler.dry_run.skip(&mut scheduler.board, effect) {
+            true => 0,
+            false => match scheduler.board.led_ring().animate(&animation) {
+                Ok(()) => 0,
+                Err(_) => u32::MAX,
+            },
+        };
+        api::animate::Results { res: res.into() }
+    };
+    call.reply(results);
+}
+
+fn stop<B: Board>(mut call: SchedulerCall<B, api::stop::Sig>) {
+    let api::stop::Params {} = call.read();
+    let scheduler = call.scheduler();
+    let res = match scheduler.dry_run.skip(&mut scheduler.board, || Effect::LedRingStop) {
+        true => 0,
+        false => match scheduler.board.led_ring().stop() {
+            Ok(()) => 0,
+            Err(_) => u32::MAX,
+        },
+    };
+    call.reply(Ok(api::stop::Results { res: res.into() }));
+}
+
+fn convert_pattern(pattern: u32) -> Result<Pattern, Trap> {
+    Ok(match api::Pattern::try_from(pattern)? {
+        api::Pattern::Spinner => Pattern::Spinner,
+        api::Pattern::Progress => Pattern::Progress,
+        api::Pattern::Rainbow => Pattern::Rainbow,
+    })
+}
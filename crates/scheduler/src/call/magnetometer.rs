@@ -0,0 +1,52 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use wasefire_applet_api::magnetometer::{self as api, Api};
+use wasefire_board_api::magnetometer::Api as _;
+use wasefire_board_api::Api as Board;
+
+use crate::{DispatchSchedulerCall, SchedulerCall};
+
+pub fn process<B: Board>(call: Api<DispatchSchedulerCall<B>>) {
+    match call {
+        Api::IsSupported(call) => is_supported(call),
+        Api::Read(call) => read(call),
+    }
+}
+
+fn is_supported<B: Board>(mut call: SchedulerCall<B, api::is_supported::Sig>) {
+    let api::is_supported::Params {} = call.read();
+    let supported = call.scheduler().board.magnetometer().is_supported() as u32;
+    call.reply(Ok(api::is_supported::Results { supported: supported.into() }));
+}
+
+fn read<B: Board>(mut call: SchedulerCall<B, api::read::Sig>) {
+    let api::read::Params { ptr } = call.read();
+    let scheduler = call.scheduler();
+    let memory = scheduler.applet.memory();
+    let results = try {
+        let output = memory.get_array_mut::<6>(*ptr)?;
+        let res = match scheduler.board.magnetometer().read() {
+            Ok(field) => {
+                for (output, axis) in output.chunks_exact_mut(2).zip(field) {
+                    output.copy_from_slice(&axis.to_le_bytes());
+                }
+                0
+            }
+            Err(_) => u32::MAX,
+        };
+        api::read::Results { res: res.into() }
+    };
+    call.reply(results);
+}
@@ -0,0 +1,112 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod update;
+
+use wasefire_applet_api::platform::{self as api, Api};
+use wasefire_board_api::debug::Effect;
+use wasefire_board_api::platform::Api as _;
+use wasefire_board_api::{self as board, Api as Board};
+use wasefire_logger as logger;
+
+use crate::{capability, DispatchSchedulerCall, Scheduler, SchedulerCall};
+
+pub fn process<B: Board>(call: Api<DispatchSchedulerCall<B>>) {
+    match call {
+        Api::Reboot(call) => reboot(call),
+        Api::FactoryReset(call) => factory_reset(call),
+        Api::Capabilities(call) => capabilities(call),
+        Api::ApiVersion(call) => api_version(call),
+        Api::DeviceId(call) => device_id(call),
+        Api::Update(call) => update::process(call),
+    }
+}
+
+fn reboot<B: Board>(mut call: SchedulerCall<B, api::reboot::Sig>) {
+    let scheduler = call.scheduler();
+    // In dry-run mode, the applet continues as if rebooting was not supported.
+    let res = match scheduler.dry_run.skip(&mut scheduler.board, || Effect::Reboot) {
+        true => api::Error::Unsupported.into(),
+        false => match reset(scheduler) {
+            Ok(()) => unreachable!(),
+            Err(_) => api::Error::Unsupported.into(),
+        },
+    };
+    call.reply(Ok(api::reboot::Results { res }));
+}
+
+fn factory_reset<B: Board>(mut call: SchedulerCall<B, api::factory_reset::Sig>) {
+    let scheduler = call.scheduler();
+    // Applets without the permission and applets in dry-run mode continue as if factory resets
+    // were not supported.
+    let res = if !scheduler.applet.permissions.factory_reset {
+        logger::warn!("Applet without factory-reset permission tried to wipe the storage.");
+        api::Error::Unsupported.into()
+    } else if scheduler.dry_run.skip(&mut scheduler.board, || Effect::FactoryReset) {
+        api::Error::Unsupported.into()
+    } else {
+        logger::info!("Wiping the storage.");
+        match scheduler.board.with_store(|x| x.wipe()) {
+            Ok(()) => match reset(scheduler) {
+                Ok(()) => unreachable!(),
+                Err(_) => api::Error::Unsupported.into(),
+            },
+            // The storage may be partially erased, so we can't continue.
+            Err(error) => {
+                logger::panic!("Failed to wipe the storage: {}", logger::Debug2Format(&error))
+            }
+        }
+    };
+    call.reply(Ok(api::factory_reset::Results { res }));
+}
+
+fn capabilities<B: Board>(mut call: SchedulerCall<B, api::capabilities::Sig>) {
+    let api::capabilities::Params {} = call.read();
+    let capabilities = capability::supported(&mut call.scheduler().board);
+    call.reply(Ok(api::capabilities::Results { capabilities: capabilities.into() }));
+}
+
+fn api_version<B: Board>(call: SchedulerCall<B, api::api_version::Sig>) {
+    let api::api_version::Params {} = call.read();
+    let version = wasefire_applet_api::API_VERSION;
+    call.reply(Ok(api::api_version::Results { version: version.into() }));
+}
+
+fn device_id<B: Board>(mut call: SchedulerCall<B, api::device_id::Sig>) {
+    let api::device_id::Params { ptr } = call.read();
+    let scheduler = call.scheduler();
+    let memory = scheduler.applet.memory();
+    let results = try {
+        let output = memory.get_array_mut::<8>(*ptr)?;
+        let res = match scheduler.board.platform().device_id() {
+            Ok(id) => {
+                output.copy_from_slice(&id);
+                0.into()
+            }
+            Err(_) => api::Error::Unsupported.into(),
+        };
+        api::device_id::Results { res }
+    };
+    call.reply(results);
+}
+
+/// Resets the platform.
+///
+/// This function only returns on error.
+pub fn reset<B: Board>(scheduler: &mut Scheduler<B>) -> Result<(), board::Error> {
+    // Store operations complete before returning to the applet, so the key-value log has no
+    // pending writes at this point. The board still waits for the flash to be ready.
+    logger::debug!("Resetting the platform.");
+    scheduler.board.platform().reset()
+}
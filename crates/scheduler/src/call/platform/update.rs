@@ -0,0 +1,122 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use wasefire_applet_api::platform::update::{self as api, Api};
+use wasefire_applet_api::{platform as platform_api, U32};
+use wasefire_board_api::debug::Effect;
+use wasefire_board_api::update::Api as _;
+use wasefire_board_api::{self as board, Api as Board};
+use wasefire_logger as logger;
+
+use crate::{DispatchSchedulerCall, Scheduler, SchedulerCall};
+
+pub fn process<B: Board>(call: Api<DispatchSchedulerCall<B>>) {
+    match call {
+        Api::IsSupported(call) => is_supported(call),
+        Api::Start(call) => start(call),
+        Api::Write(call) => write(call),
+        Api::Finalize(call) => finalize(call),
+        Api::Apply(call) => apply(call),
+    }
+}
+
+fn is_supported<B: Board>(mut call: SchedulerCall<B, api::is_supported::Sig>) {
+    let api::is_supported::Params {} = call.read();
+    let supported = call.scheduler().board.update().is_supported() as u32;
+    call.reply(Ok(api::is_supported::Results { supported: supported.into() }))
+}
+
+fn start<B: Board>(mut call: SchedulerCall<B, api::start::Sig>) {
+    let api::start::Params {} = call.read();
+    let scheduler = call.scheduler();
+    let res = match check(scheduler) {
+        Err(res) => res,
+        // In dry-run mode, the applet continues as if the staging bank was erased.
+        Ok(()) if scheduler.dry_run.skip(&mut scheduler.board, || Effect::UpdateStart) => 0.into(),
+        Ok(()) => convert(scheduler.board.update().start()),
+    };
+    call.reply(Ok(api::start::Results { res }))
+}
+
+fn write<B: Board>(mut call: SchedulerCall<B, api::write::Sig>) {
+    let api::write::Params { offset, ptr, len } = call.read();
+    let scheduler = call.scheduler();
+    let checked = check(scheduler);
+    let memory = scheduler.applet.memory();
+    let results = try {
+        let chunk = memory.get(*ptr, *len)?;
+        let offset = *offset as usize;
+        let effect = || Effect::UpdateWrite { offset, data: chunk.to_vec() };
+        let res = match checked {
+            Err(res) => res,
+            // In dry-run mode, the applet continues as if the chunk was written.
+            Ok(()) if scheduler.dry_run.skip(&mut scheduler.board, effect) => 0.into(),
+            Ok(()) => convert(scheduler.board.update().write(offset, chunk)),
+        };
+        api::write::Results { res }
+    };
+    call.reply(results);
+}
+
+fn finalize<B: Board>(mut call: SchedulerCall<B, api::finalize::Sig>) {
+    let api::finalize::Params { signature } = call.read();
+    let scheduler = call.scheduler();
+    let checked = check(scheduler);
+    let memory = scheduler.applet.memory();
+    let results = try {
+        let signature = memory.get_array::<64>(*signature)?;
+        let res = match checked {
+            Err(res) => res,
+            Ok(()) => convert(scheduler.board.update().finalize(signature)),
+        };
+        api::finalize::Results { res }
+    };
+    call.reply(results);
+}
+
+fn apply<B: Board>(mut call: SchedulerCall<B, api::apply::Sig>) {
+    let api::apply::Params {} = call.read();
+    let scheduler = call.scheduler();
+    let res = match check(scheduler) {
+        Err(res) => res,
+        // In dry-run mode, the applet continues as if the update was not finalized.
+        Ok(()) if scheduler.dry_run.skip(&mut scheduler.board, || Effect::FirmwareUpdate) => {
+            platform_api::Error::InvalidArgument.into()
+        }
+        Ok(()) => {
+            logger::info!("Applying the firmware update.");
+            convert(scheduler.board.update().apply())
+        }
+    };
+    call.reply(Ok(api::apply::Results { res }));
+}
+
+/// Checks that the board supports firmware updates and the applet has the `update` permission.
+fn check<B: Board>(scheduler: &mut Scheduler<B>) -> Result<(), U32<isize>> {
+    if !scheduler.board.update().is_supported() {
+        return Err(platform_api::Error::Unsupported.into());
+    }
+    if !scheduler.applet.permissions.update {
+        logger::warn!("Applet without update permission tried to update the firmware.");
+        return Err(platform_api::Error::Unsupported.into());
+    }
+    Ok(())
+}
+
+fn convert(result: Result<(), board::Error>) -> U32<isize> {
+    match result {
+        Ok(()) => 0.into(),
+        Err(_) => platform_api::Error::InvalidArgument.into(),
+    }
+}
@@ -0,0 +1,87 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use wasefire_applet_api::power::{self as api, Api};
+use wasefire_board_api::debug::Effect;
+use wasefire_board_api::power::{Api as _, Peripheral};
+use wasefire_board_api::Api as Board;
+
+use crate::{DispatchSchedulerCall, SchedulerCall, Trap};
+
+pub fn process<B: Board>(call: Api<DispatchSchedulerCall<B>>) {
+    match call {
+        Api::DisablePeripheral(call) => disable_peripheral(call),
+        Api::EnablePeripheral(call) => enable_peripheral(call),
+    }
+}
+
+fn disable_peripheral<B: Board>(mut call: SchedulerCall<B, api::disable_peripheral::Sig>) {
+    let api::disable_peripheral::Params { peripheral, index } = call.read();
+    let scheduler = call.scheduler();
+    let results = try {
+        let res = match convert_peripheral(*peripheral, *index)? {
+            None => api::Error::Invalid.into(),
+            Some(peripheral) => {
+                let busy = scheduler.board.power().is_busy(peripheral);
+                let effect = || Effect::PowerDisable { peripheral };
+                match busy {
+                    Ok(true) => api::Error::Busy.into(),
+                    Ok(false) if scheduler.dry_run.skip(&mut scheduler.board, effect) => 0.into(),
+                    Ok(false) => match scheduler.board.power().disable_peripheral(peripheral) {
+                        Ok(()) => 0.into(),
+                        Err(_) => api::Error::Invalid.into(),
+                    },
+                    Err(_) => api::Error::Invalid.into(),
+                }
+            }
+        };
+        api::disable_peripheral::Results { res }
+    };
+    call.reply(results);
+}
+
+fn enable_peripheral<B: Board>(mut call: SchedulerCall<B, api::enable_peripheral::Sig>) {
+    let api::enable_peripheral::Params { peripheral, index } = call.read();
+    let scheduler = call.scheduler();
+    let results = try {
+        let res = match convert_peripheral(*peripheral, *index)? {
+            None => api::Error::Invalid.into(),
+            Some(peripheral) => {
+                let effect = || Effect::PowerEnable { peripheral };
+                match scheduler.dry_run.skip(&mut scheduler.board, effect) {
+                    true => 0.into(),
+                    false => match scheduler.board.power().enable_peripheral(peripheral) {
+                        Ok(()) => 0.into(),
+                        Err(_) => api::Error::Invalid.into(),
+                    },
+                }
+            }
+        };
+        api::enable_peripheral::Results { res }
+    };
+    call.reply(results);
+}
+
+/// Returns the peripheral if the index is valid for its kind.
+fn convert_peripheral(peripheral: u32, index: u32) -> Result<Option<Peripheral>, Trap> {
+    let index = index as usize;
+    Ok(match api::Peripheral::try_from(peripheral)? {
+        api::Peripheral::Timer => Some(Peripheral::Timer(index)),
+        _ if index != 0 => None,
+        api::Peripheral::Adc => Some(Peripheral::Adc),
+        api::Peripheral::Magnetometer => Some(Peripheral::Magnetometer),
+        api::Peripheral::Rng => Some(Peripheral::Rng),
+        api::Peripheral::Usb => Some(Peripheral::Usb),
+    })
+}
@@ -0,0 +1,84 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use wasefire_applet_api::pwm::{self as api, Api};
+use wasefire_applet_api::U32;
+use wasefire_board_api::debug::Effect;
+use wasefire_board_api::pwm::Api as _;
+use wasefire_board_api::{Api as Board, Error};
+
+use crate::{DispatchSchedulerCall, SchedulerCall, Trap};
+
+pub fn process<B: Board>(call: Api<DispatchSchedulerCall<B>>) {
+    match call {
+        Api::Count(call) => count(call),
+        Api::Configure(call) => configure(call),
+        Api::Stop(call) => stop(call),
+    }
+}
+
+fn count<B: Board>(mut call: SchedulerCall<B, api::count::Sig>) {
+    let api::count::Params {} = call.read();
+    let count = call.scheduler().board.pwm().count() as u32;
+    call.reply(Ok(api::count::Results { cnt: count.into() }));
+}
+
+fn configure<B: Board>(mut call: SchedulerCall<B, api::configure::Sig>) {
+    let api::configure::Params { pwm, freq_hz, duty_pct } = call.read();
+    let pwm = *pwm as usize;
+    let freq_hz = *freq_hz;
+    let duty_pct = u8::try_from(*duty_pct).ok().filter(|&x| x <= 100);
+    let results = try {
+        if call.scheduler().board.pwm().count() <= pwm {
+            Err(Trap)?;
+        }
+        let scheduler = call.scheduler();
+        let res = match duty_pct {
+            Some(duty_pct) => {
+                let effect = || Effect::PwmConfigure { pwm, freq_hz, duty_pct };
+                match scheduler.dry_run.skip(&mut scheduler.board, effect) {
+                    true => Ok(()),
+                    false => scheduler.board.pwm().configure(pwm, freq_hz, duty_pct),
+                }
+            }
+            None => Err(Error::User),
+        };
+        api::configure::Results { res: convert(res) }
+    };
+    call.reply(results);
+}
+
+fn stop<B: Board>(mut call: SchedulerCall<B, api::stop::Sig>) {
+    let api::stop::Params { pwm } = call.read();
+    let pwm = *pwm as usize;
+    let results = try {
+        if call.scheduler().board.pwm().count() <= pwm {
+            Err(Trap)?;
+        }
+        let scheduler = call.scheduler();
+        let res = match scheduler.dry_run.skip(&mut scheduler.board, || Effect::PwmStop { pwm }) {
+            true => Ok(()),
+            false => scheduler.board.pwm().stop(pwm),
+        };
+        api::stop::Results { res: convert(res) }
+    };
+    call.reply(results);
+}
+
+fn convert(result: Result<(), Error>) -> U32<isize> {
+    match result {
+        Ok(()) => 0.into(),
+        Err(_) => u32::MAX.into(),
+    }
+}
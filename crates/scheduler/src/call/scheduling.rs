@@ -20,17 +20,25 @@ use crate::{DispatchSchedulerCall, SchedulerCall};
 pub fn process<B: Board>(call: Api<DispatchSchedulerCall<B>>) {
     match call {
         Api::WaitForCallback(call) => wait_for_callback(call),
+        Api::WaitForEvent(call) => wait_for_event(call),
         Api::NumPendingCallbacks(call) => num_pending_callbacks(call),
     }
 }
 
 fn wait_for_callback<B: Board>(mut call: SchedulerCall<B, api::wait_for_callback::Sig>) {
     let api::wait_for_callback::Params {} = call.read();
-    if call.scheduler().process_event() {
+    if call.scheduler().process_event(false) {
         call.reply(Ok(api::wait_for_callback::Results {}));
     }
 }
 
+fn wait_for_event<B: Board>(mut call: SchedulerCall<B, api::wait_for_event::Sig>) {
+    let api::wait_for_event::Params {} = call.read();
+    if call.scheduler().process_event(true) {
+        call.reply(Ok(api::wait_for_event::Results {}));
+    }
+}
+
 fn num_pending_callbacks<B: Board>(mut call: SchedulerCall<B, api::num_pending_callbacks::Sig>) {
     let api::num_pending_callbacks::Params {} = call.read();
     let count = (call.applet().len() as u32).into();
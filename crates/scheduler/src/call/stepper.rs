@@ -0,0 +1,96 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use wasefire_applet_api::stepper::{self as api, Api};
+use wasefire_board_api::debug::Effect;
+use wasefire_board_api::stepper::{Api as _, Profile};
+use wasefire_board_api::Api as Board;
+
+use crate::event::stepper::Key;
+use crate::event::Handler;
+use crate::{DispatchSchedulerCall, SchedulerCall, Trap};
+
+pub fn process<B: Board>(call: Api<DispatchSchedulerCall<B>>) {
+    match call {
+        Api::Count(call) => count(call),
+        Api::MoveTo(call) => move_to(call),
+        Api::Position(call) => position(call),
+        Api::Register(call) => register(call),
+        Api::Unregister(call) => unregister(call),
+    }
+}
+
+fn count<B: Board>(mut call: SchedulerCall<B, api::count::Sig>) {
+    let api::count::Params {} = call.read();
+    let count = call.scheduler().board.stepper().count() as u32;
+    call.reply(Ok(api::count::Results { cnt: count.into() }));
+}
+
+fn move_to<B: Board>(mut call: SchedulerCall<B, api::move_to::Sig>) {
+    let api::move_to::Params { stepper, position, max_speed, acceleration } = call.read();
+    let profile = Profile { max_speed: *max_speed, acceleration: *acceleration };
+    let stepper = *stepper as usize;
+    let position = *position as i32;
+    let scheduler = call.scheduler();
+    let effect = || Effect::StepperMove { stepper, position, profile: profile.clone() };
+    let res = match profile.is_valid() {
+        false => u32::MAX,
+        true if scheduler.board.stepper().count() <= stepper => u32::MAX,
+        true if scheduler.dry_run.skip(&mut scheduler.board, effect) => 0,
+        true => match scheduler.board.stepper().move_to(stepper, position, &profile) {
+            Ok(()) => 0,
+            Err(_) => u32::MAX,
+        },
+    };
+    call.reply(Ok(api::move_to::Results { res: res.into() }));
+}
+
+fn position<B: Board>(mut call: SchedulerCall<B, api::position::Sig>) {
+    let api::position::Params { stepper } = call.read();
+    let results = try {
+        let stepper = *stepper as usize;
+        let position = call.scheduler().board.stepper().position(stepper).map_err(|_| Trap)?;
+        api::position::Results { position: (position as u32).into() }
+    };
+    call.reply(results);
+}
+
+fn register<B: Board>(mut call: SchedulerCall<B, api::register::Sig>) {
+    let api::register::Params { stepper, handler_func, handler_data } = call.read();
+    let stepper = *stepper as usize;
+    let inst = call.inst();
+    let results = try {
+        if call.scheduler().board.stepper().count() <= stepper {
+            Err(Trap)?;
+        }
+        call.scheduler().applet.enable(Handler {
+            key: Key { stepper }.into(),
+            inst,
+            func: *handler_func,
+            data: *handler_data,
+        })?;
+        api::register::Results {}
+    };
+    call.reply(results);
+}
+
+fn unregister<B: Board>(mut call: SchedulerCall<B, api::unregister::Sig>) {
+    let api::unregister::Params { stepper } = call.read();
+    let stepper = *stepper as usize;
+    let results = try {
+        call.scheduler().disable_event(Key { stepper }.into())?;
+        api::unregister::Results {}
+    };
+    call.reply(results);
+}
@@ -12,10 +12,20 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod fragment;
+
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
 use wasefire_applet_api::store::{self as api, Api};
-use wasefire_board_api::Api as Board;
+use wasefire_board_api::debug::Effect;
+use wasefire_board_api::store::{self as board_store, Api as _, KeyValue, RESERVED_KEYS};
+#[cfg(feature = "encrypted-store")]
+use wasefire_board_api::store::{Cipher, Encrypted};
+use wasefire_board_api::{self as board, Api as Board};
 use wasefire_store::StoreError;
 
+use crate::dry_run::DryRun;
 use crate::{DispatchSchedulerCall, SchedulerCall, Trap};
 
 pub fn process<B: Board>(call: Api<DispatchSchedulerCall<B>>) {
@@ -23,6 +33,7 @@ pub fn process<B: Board>(call: Api<DispatchSchedulerCall<B>>) {
         Api::Insert(call) => insert(call),
         Api::Remove(call) => remove(call),
         Api::Find(call) => find(call),
+        Api::Fragment(call) => fragment::process(call),
     }
 }
 
@@ -32,7 +43,7 @@ fn insert<B: Board>(mut call: SchedulerCall<B, api::insert::Sig>) {
     let memory = scheduler.applet.memory();
     let results = try {
         let value = memory.get(*ptr, *len)?;
-        let res = match scheduler.store.insert(*key as usize, value) {
+        let res = match insert_entry(&scheduler.dry_run, &mut scheduler.board, *key, value) {
             Ok(()) => 0.into(),
             Err(e) => convert(e).into(),
         };
@@ -43,9 +54,15 @@ fn insert<B: Board>(mut call: SchedulerCall<B, api::insert::Sig>) {
 
 fn remove<B: Board>(mut call: SchedulerCall<B, api::remove::Sig>) {
     let api::remove::Params { key } = call.read();
-    let res = match call.scheduler().store.remove(*key as usize) {
-        Ok(()) => 0.into(),
-        Err(e) => convert(e).into(),
+    let scheduler = call.scheduler();
+    let key = *key as usize;
+    let effect = || Effect::StoreRemove { keys: key .. key + 1 };
+    let res = match scheduler.dry_run.skip(&mut scheduler.board, effect) {
+        true => 0.into(),
+        false => match remove_entry(&mut scheduler.board, key) {
+            Ok(()) => 0.into(),
+            Err(e) => convert(e).into(),
+        },
     };
     call.reply(Ok(api::remove::Results { res }));
 }
@@ -59,7 +76,7 @@ fn find<B: Board>(mut call: SchedulerCall<B, api::find::Sig>) {
     let mut memory = scheduler.applet.memory();
     let results = try {
         let mut results = api::find::Results::default();
-        match scheduler.store.find(*key as usize) {
+        match find_entry(&mut scheduler.board, *key) {
             Ok(None) => (),
             Ok(Some(value)) => {
                 let len = value.len() as u32;
@@ -91,6 +108,95 @@ fn find<B: Board>(mut call: SchedulerCall<B, api::find::Sig>) {
     call.reply(results);
 }
 
+/// Inserts an entry in the store, unless in dry-run mode.
+fn insert_entry<B: Board>(
+    dry_run: &DryRun, board: &mut B, key: u32, value: &[u8],
+) -> Result<(), StoreError> {
+    let key = key as usize;
+    check_key(key)?;
+    let effect = || Effect::StoreInsert { keys: key .. key + 1, value: value.to_vec() };
+    if dry_run.skip(board, effect) {
+        return Ok(());
+    }
+    let value = seal(board, key as u32, value)?;
+    board.with_store(|x| x.insert(key, &value))
+}
+
+/// Removes an entry from the store.
+///
+/// Migrated entries of the reserved keys are removed from the platform key-value store.
+fn remove_entry<B: Board>(board: &mut B, key: usize) -> Result<(), StoreError> {
+    match RESERVED_KEYS.contains(&key) {
+        true => board.with_store(|x| {
+            KeyValue::new(x, RESERVED_KEYS).remove(&board_store::legacy_key(key)).map_err(legacy)
+        }),
+        false => board.with_store(|x| x.remove(key)),
+    }
+}
+
+/// Finds an entry in the store.
+///
+/// Migrated entries of the reserved keys are found in the platform key-value store.
+fn find_entry<B: Board>(board: &mut B, key: u32) -> Result<Option<Vec<u8>>, StoreError> {
+    let sealed = match RESERVED_KEYS.contains(&(key as usize)) {
+        true => board.with_store(|x| {
+            let name = board_store::legacy_key(key as usize);
+            KeyValue::new(x, RESERVED_KEYS).get(&name).map_err(legacy)
+        })?,
+        false => board.with_store(|x| x.find(key as usize))?,
+    };
+    match sealed {
+        Some(sealed) => Ok(Some(open(board, key, sealed)?)),
+        None => Ok(None),
+    }
+}
+
+/// Converts errors of the platform key-value store holding migrated entries.
+fn legacy(error: board::Error) -> StoreError {
+    match error {
+        board::Error::User => StoreError::InvalidArgument,
+        board::Error::World => StoreError::InvalidStorage,
+    }
+}
+
+/// Rejects writes to the keys reserved for the platform.
+fn check_key(key: usize) -> Result<(), StoreError> {
+    match RESERVED_KEYS.contains(&key) {
+        true => Err(StoreError::InvalidArgument),
+        false => Ok(()),
+    }
+}
+
+/// Protects a value before it is stored.
+///
+/// The value is bound to its keys, as encoded in the applet API.
+#[cfg(feature = "encrypted-store")]
+fn seal<'a, B: Board>(
+    board: &mut B, keys: u32, value: &'a [u8],
+) -> Result<Cow<'a, [u8]>, StoreError> {
+    let mut cipher = Encrypted::new(board).map_err(|_| StoreError::StorageError)?;
+    let sealed = cipher.seal(&keys.to_le_bytes(), value).map_err(|_| StoreError::StorageError)?;
+    Ok(Cow::Owned(sealed))
+}
+
+#[cfg(not(feature = "encrypted-store"))]
+fn seal<'a, B: Board>(_: &mut B, _: u32, value: &'a [u8]) -> Result<Cow<'a, [u8]>, StoreError> {
+    Ok(Cow::Borrowed(value))
+}
+
+/// Recovers a value after it is loaded.
+#[cfg(feature = "encrypted-store")]
+fn open<B: Board>(board: &mut B, keys: u32, sealed: Vec<u8>) -> Result<Vec<u8>, StoreError> {
+    let mut cipher = Encrypted::new(board).map_err(|_| StoreError::StorageError)?;
+    // Opening only fails if the storage was tampered with.
+    cipher.open(&keys.to_le_bytes(), &sealed).map_err(|_| StoreError::InvalidStorage)
+}
+
+#[cfg(not(feature = "encrypted-store"))]
+fn open<B: Board>(_: &mut B, _: u32, sealed: Vec<u8>) -> Result<Vec<u8>, StoreError> {
+    Ok(sealed)
+}
+
 fn convert(err: StoreError) -> api::Error {
     match err {
         StoreError::InvalidArgument => api::Error::InvalidArgument,
@@ -100,3 +206,51 @@ fn convert(err: StoreError) -> api::Error {
         StoreError::InvalidStorage => api::Error::InvalidStorage,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+    use crate::testing::scheduler;
+
+    #[test]
+    fn insert_entry_dry_run() {
+        for dry_run in [false, true] {
+            let mut scheduler = scheduler(dry_run);
+            let s = &mut scheduler;
+            insert_entry(&s.dry_run, &mut s.board, 3, &[1, 2]).unwrap();
+            if dry_run {
+                assert_eq!(s.board.with_store(|x| x.find(3)).unwrap(), None);
+                let effect = Effect::StoreInsert { keys: 3 .. 4, value: vec![1, 2] };
+                assert_eq!(s.board.debug.effects, [effect]);
+            } else {
+                assert_eq!(s.board.with_store(|x| x.find(3)).unwrap(), Some(vec![1, 2]));
+                assert!(s.board.debug.effects.is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn insert_entry_reserved() {
+        let mut scheduler = scheduler(false);
+        let s = &mut scheduler;
+        let key = RESERVED_KEYS.start as u32;
+        let result = insert_entry(&s.dry_run, &mut s.board, key, &[1, 2]);
+        assert_eq!(result, Err(StoreError::InvalidArgument));
+        assert_eq!(find_entry(&mut s.board, key), Ok(None));
+        insert_entry(&s.dry_run, &mut s.board, key - 1, &[1, 2]).unwrap();
+    }
+
+    #[test]
+    fn find_remove_migrated_entry() {
+        let mut scheduler = scheduler(false);
+        let s = &mut scheduler;
+        let key = RESERVED_KEYS.start;
+        s.board.with_store(|x| x.insert(key, &[1, 2])).unwrap();
+        s.board.with_store(board_store::migrate).unwrap();
+        assert_eq!(find_entry(&mut s.board, key as u32), Ok(Some(vec![1, 2])));
+        remove_entry(&mut s.board, key).unwrap();
+        assert_eq!(find_entry(&mut s.board, key as u32), Ok(None));
+    }
+}
@@ -0,0 +1,138 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use wasefire_applet_api::store::fragment::{self as api, Api};
+use wasefire_board_api::debug::Effect;
+use wasefire_board_api::store::RESERVED_KEYS;
+use wasefire_board_api::Api as Board;
+use wasefire_store::{fragment, StoreError};
+
+#[cfg(feature = "encrypted-store")]
+use super::open;
+use super::{convert, seal};
+use crate::{DispatchSchedulerCall, SchedulerCall, Trap};
+
+pub fn process<B: Board>(call: Api<DispatchSchedulerCall<B>>) {
+    match call {
+        Api::Insert(call) => insert(call),
+        Api::Remove(call) => remove(call),
+        Api::Read(call) => read(call),
+    }
+}
+
+fn insert<B: Board>(mut call: SchedulerCall<B, api::insert::Sig>) {
+    let api::insert::Params { keys, ptr, len } = call.read();
+    let scheduler = call.scheduler();
+    let memory = scheduler.applet.memory();
+    let results = try {
+        let raw = *keys;
+        let keys = decode_keys(raw)?;
+        // Unless encrypted, the value is written to flash directly from the applet memory, without
+        // intermediate copy.
+        let value = memory.get(*ptr, *len)?;
+        let effect = || Effect::StoreInsert { keys: keys.clone(), value: value.to_vec() };
+        let res = match scheduler.dry_run.skip(&mut scheduler.board, effect) {
+            true => 0.into(),
+            false => match write(&mut scheduler.board, raw, &keys, value) {
+                Ok(()) => 0.into(),
+                Err(e) => convert(e).into(),
+            },
+        };
+        api::insert::Results { res }
+    };
+    call.reply(results);
+}
+
+fn remove<B: Board>(mut call: SchedulerCall<B, api::remove::Sig>) {
+    let api::remove::Params { keys } = call.read();
+    let scheduler = call.scheduler();
+    let results = try {
+        let keys = decode_keys(*keys)?;
+        let effect = || Effect::StoreRemove { keys: keys.clone() };
+        let res = match scheduler.dry_run.skip(&mut scheduler.board, effect) {
+            true => 0.into(),
+            false => match scheduler.board.with_store(|x| fragment::delete(x, &keys)) {
+                Ok(()) => 0.into(),
+                Err(e) => convert(e).into(),
+            },
+        };
+        api::remove::Results { res }
+    };
+    call.reply(results);
+}
+
+fn read<B: Board>(mut call: SchedulerCall<B, api::read::Sig>) {
+    let api::read::Params { keys, offset, ptr, len } = call.read();
+    let scheduler = call.scheduler();
+    let memory = scheduler.applet.memory();
+    let results = try {
+        let raw = *keys;
+        let keys = decode_keys(raw)?;
+        let output = memory.get_mut(*ptr, *len)?;
+        let start = *offset as usize;
+        let end = start.checked_add(output.len()).ok_or(Trap)?;
+        let res = match read_range(&mut scheduler.board, raw, &keys, start .. end) {
+            Ok(None) => 0.into(),
+            Ok(Some(value)) => {
+                output[.. value.len()].copy_from_slice(&value);
+                (value.len() as u32).into()
+            }
+            Err(e) => convert(e).into(),
+        };
+        api::read::Results { res }
+    };
+    call.reply(results);
+}
+
+fn write<B: Board>(
+    board: &mut B, raw: u32, keys: &Range<usize>, value: &[u8],
+) -> Result<(), StoreError> {
+    let value = seal(board, raw, value)?;
+    board.with_store(|x| fragment::write(x, keys, &value))
+}
+
+/// Reads a range of a value.
+///
+/// Encrypted values are read and opened as a whole.
+#[cfg(feature = "encrypted-store")]
+fn read_range<B: Board>(
+    board: &mut B, raw: u32, keys: &Range<usize>, range: Range<usize>,
+) -> Result<Option<Vec<u8>>, StoreError> {
+    let Some(sealed) = board.with_store(|x| fragment::read(x, keys))? else { return Ok(None) };
+    let value = open(board, raw, sealed)?;
+    let end = core::cmp::min(range.end, value.len());
+    let start = core::cmp::min(range.start, end);
+    Ok(Some(value[start .. end].to_vec()))
+}
+
+#[cfg(not(feature = "encrypted-store"))]
+fn read_range<B: Board>(
+    board: &mut B, _: u32, keys: &Range<usize>, range: Range<usize>,
+) -> Result<Option<Vec<u8>>, StoreError> {
+    board.with_store(|x| fragment::read_range(x, keys, range))
+}
+
+fn decode_keys(keys: u32) -> Result<Range<usize>, Trap> {
+    let start = (keys & 0xffff) as usize;
+    let end = (keys >> 16) as usize;
+    // The keys reserved for the platform are at the end of the key space.
+    if start < end && end <= RESERVED_KEYS.start {
+        Ok(start .. end)
+    } else {
+        Err(Trap)
+    }
+}
@@ -0,0 +1,143 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use wasefire_applet_api::uart::{self as api, Api};
+use wasefire_applet_api::U32;
+use wasefire_board_api::debug::Effect;
+use wasefire_board_api::uart::{Api as _, Direction};
+use wasefire_board_api::{Api as Board, Error};
+
+use crate::event::uart::Key;
+use crate::event::Handler;
+use crate::{DispatchSchedulerCall, SchedulerCall, Trap};
+
+pub fn process<B: Board>(call: Api<DispatchSchedulerCall<B>>) {
+    match call {
+        Api::Count(call) => count(call),
+        Api::SetBaudrate(call) => set_baudrate(call),
+        Api::Read(call) => read(call),
+        Api::Write(call) => write(call),
+        Api::Flush(call) => flush(call),
+        Api::Register(call) => register(call),
+        Api::Unregister(call) => unregister(call),
+    }
+}
+
+fn count<B: Board>(mut call: SchedulerCall<B, api::count::Sig>) {
+    let api::count::Params {} = call.read();
+    let count = call.scheduler().board.uart().count() as u32;
+    call.reply(Ok(api::count::Results { cnt: count.into() }));
+}
+
+fn set_baudrate<B: Board>(mut call: SchedulerCall<B, api::set_baudrate::Sig>) {
+    let api::set_baudrate::Params { uart, baudrate } = call.read();
+    let (uart, baudrate) = (*uart as usize, *baudrate);
+    let scheduler = call.scheduler();
+    let effect = || Effect::UartSetBaudrate { uart, baudrate };
+    let res = if scheduler.board.uart().count() <= uart {
+        Err(Error::User)
+    } else if scheduler.dry_run.skip(&mut scheduler.board, effect) {
+        Ok(())
+    } else {
+        scheduler.board.uart().set_baudrate(uart, baudrate)
+    };
+    let res = convert(res.map(|()| 0));
+    call.reply(Ok(api::set_baudrate::Results { res }));
+}
+
+fn read<B: Board>(mut call: SchedulerCall<B, api::read::Sig>) {
+    let api::read::Params { uart, ptr, len } = call.read();
+    let scheduler = call.scheduler();
+    let memory = scheduler.applet.memory();
+    let results = try {
+        let output = memory.get_mut(*ptr, *len)?;
+        let len = convert(scheduler.board.uart().read(*uart as usize, output));
+        api::read::Results { len }
+    };
+    call.reply(results);
+}
+
+fn write<B: Board>(mut call: SchedulerCall<B, api::write::Sig>) {
+    let api::write::Params { uart, ptr, len } = call.read();
+    let scheduler = call.scheduler();
+    let memory = scheduler.applet.memory();
+    let results = try {
+        let input = memory.get(*ptr, *len)?;
+        let uart = *uart as usize;
+        let effect = || Effect::UartWrite { uart, data: input.to_vec() };
+        let len = if scheduler.board.uart().count() <= uart {
+            Err(Error::User)
+        } else if scheduler.dry_run.skip(&mut scheduler.board, effect) {
+            Ok(input.len())
+        } else {
+            scheduler.board.uart().write(uart, input)
+        };
+        let len = convert(len);
+        api::write::Results { len }
+    };
+    call.reply(results);
+}
+
+fn flush<B: Board>(mut call: SchedulerCall<B, api::flush::Sig>) {
+    let api::flush::Params { uart } = call.read();
+    let uart = *uart as usize;
+    let res = convert(call.scheduler().board.uart().flush(uart).map(|()| 0));
+    call.reply(Ok(api::flush::Results { res }));
+}
+
+fn register<B: Board>(mut call: SchedulerCall<B, api::register::Sig>) {
+    let api::register::Params { uart, event, handler_func, handler_data } = call.read();
+    let inst = call.inst();
+    let scheduler = call.scheduler();
+    let results = try {
+        let uart = *uart as usize;
+        let direction = convert_event(*event)?;
+        scheduler.applet.enable(Handler {
+            key: Key::new(uart, direction).into(),
+            inst,
+            func: *handler_func,
+            data: *handler_data,
+        })?;
+        scheduler.board.uart().enable(uart, direction).map_err(|_| Trap)?;
+        api::register::Results {}
+    };
+    call.reply(results);
+}
+
+fn unregister<B: Board>(mut call: SchedulerCall<B, api::unregister::Sig>) {
+    let api::unregister::Params { uart, event } = call.read();
+    let scheduler = call.scheduler();
+    let results = try {
+        let uart = *uart as usize;
+        let direction = convert_event(*event)?;
+        scheduler.board.uart().disable(uart, direction).map_err(|_| Trap)?;
+        scheduler.disable_event(Key::new(uart, direction).into())?;
+        api::unregister::Results {}
+    };
+    call.reply(results);
+}
+
+fn convert(result: Result<usize, Error>) -> U32<isize> {
+    match result {
+        Ok(len) => (len as u32).into(),
+        Err(_) => u32::MAX.into(),
+    }
+}
+
+fn convert_event(event: u32) -> Result<Direction, Trap> {
+    Ok(match api::Event::try_from(event)? {
+        api::Event::Read => Direction::Read,
+        api::Event::Write => Direction::Write,
+    })
+}
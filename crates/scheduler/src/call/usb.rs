@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod hid;
 mod serial;
 
 use wasefire_applet_api::usb::Api;
@@ -21,6 +22,7 @@ use crate::DispatchSchedulerCall;
 
 pub fn process<B: Board>(call: Api<DispatchSchedulerCall<B>>) {
     match call {
+        Api::Hid(call) => hid::process(call),
         Api::Serial(call) => serial::process(call),
     }
 }
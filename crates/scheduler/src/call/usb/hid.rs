@@ -0,0 +1,103 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use wasefire_applet_api::usb::hid::{self as api, Api};
+use wasefire_board_api::debug::Effect;
+use wasefire_board_api::usb::hid::{Api as _, Event, REPORT_LEN};
+use wasefire_board_api::usb::Api as _;
+use wasefire_board_api::Api as Board;
+
+use crate::event::usb::hid::Key;
+use crate::event::Handler;
+use crate::{DispatchSchedulerCall, SchedulerCall, Trap};
+
+pub fn process<B: Board>(call: Api<DispatchSchedulerCall<B>>) {
+    match call {
+        Api::Read(call) => read(call),
+        Api::Write(call) => write(call),
+        Api::Register(call) => register(call),
+        Api::Unregister(call) => unregister(call),
+    }
+}
+
+fn read<B: Board>(mut call: SchedulerCall<B, api::read::Sig>) {
+    let api::read::Params { ptr } = call.read();
+    let scheduler = call.scheduler();
+    let memory = scheduler.applet.memory();
+    let results = try {
+        let output = memory.get_array_mut::<REPORT_LEN>(*ptr)?;
+        let res = match scheduler.board.usb().hid().read(output) {
+            Ok(read) => (read as u32).into(),
+            Err(_) => u32::MAX.into(),
+        };
+        api::read::Results { res }
+    };
+    call.reply(results);
+}
+
+fn write<B: Board>(mut call: SchedulerCall<B, api::write::Sig>) {
+    let api::write::Params { ptr } = call.read();
+    let scheduler = call.scheduler();
+    let memory = scheduler.applet.memory();
+    let results = try {
+        let input = memory.get_array::<REPORT_LEN>(*ptr)?;
+        let effect = || Effect::UsbHidWrite { report: *input };
+        let res = match scheduler.dry_run.skip(&mut scheduler.board, effect) {
+            true => (REPORT_LEN as u32).into(),
+            false => match scheduler.board.usb().hid().write(input) {
+                Ok(written) => (written as u32).into(),
+                Err(_) => u32::MAX.into(),
+            },
+        };
+        api::write::Results { res }
+    };
+    call.reply(results);
+}
+
+fn register<B: Board>(mut call: SchedulerCall<B, api::register::Sig>) {
+    let api::register::Params { event, handler_func, handler_data } = call.read();
+    let inst = call.inst();
+    let scheduler = call.scheduler();
+    let results = try {
+        let event = convert_event(*event)?;
+        scheduler.applet.enable(Handler {
+            key: Key::from(&event).into(),
+            inst,
+            func: *handler_func,
+            data: *handler_data,
+        })?;
+        scheduler.board.usb().hid().enable(&event).map_err(|_| Trap)?;
+        api::register::Results {}
+    };
+    call.reply(results);
+}
+
+fn unregister<B: Board>(mut call: SchedulerCall<B, api::unregister::Sig>) {
+    let api::unregister::Params { event } = call.read();
+    let scheduler = call.scheduler();
+    let results = try {
+        let event = convert_event(*event)?;
+        scheduler.board.usb().hid().disable(&event).map_err(|_| Trap)?;
+        scheduler.disable_event(Key::from(&event).into())?;
+        api::unregister::Results {}
+    };
+    call.reply(results);
+}
+
+fn convert_event(event: u32) -> Result<Event, Trap> {
+    Ok(match api::Event::try_from(event)? {
+        api::Event::Read => Event::Read,
+        api::Event::Write => Event::Write,
+    })
+}
@@ -13,31 +13,48 @@
 // limitations under the License.
 
 use wasefire_applet_api::usb::serial::{self as api, Api};
-use wasefire_board_api::usb::serial::{Api as _, Event};
+use wasefire_board_api::debug::Effect;
+use wasefire_board_api::usb::serial::{Api as _, Event, LineState, Parity, StopBits};
 use wasefire_board_api::usb::Api as _;
 use wasefire_board_api::Api as Board;
 
 use crate::event::usb::serial::Key;
 use crate::event::Handler;
-use crate::{DispatchSchedulerCall, SchedulerCall, Trap};
+use crate::{DispatchSchedulerCall, SchedulerCall, Trap, WATCHDOG_TIMEOUT_MS};
+
+/// Maximum duration in milliseconds to wait when flushing.
+///
+/// The applet doesn't return to the scheduler while flushing, so this must stay well below the
+/// watchdog timeout.
+const MAX_FLUSH_TIMEOUT_MS: usize = WATCHDOG_TIMEOUT_MS / 5;
 
 pub fn process<B: Board>(call: Api<DispatchSchedulerCall<B>>) {
     match call {
+        Api::Count(call) => count(call),
         Api::Read(call) => read(call),
         Api::Write(call) => write(call),
         Api::Register(call) => register(call),
         Api::Unregister(call) => unregister(call),
         Api::Flush(call) => flush(call),
+        Api::FlushTimeout(call) => flush_timeout(call),
+        Api::LineState(call) => line_state(call),
+        Api::LineCoding(call) => line_coding(call),
     }
 }
 
+fn count<B: Board>(mut call: SchedulerCall<B, api::count::Sig>) {
+    let api::count::Params {} = call.read();
+    let count = call.scheduler().board.usb().serial().count() as u32;
+    call.reply(Ok(api::count::Results { cnt: count.into() }));
+}
+
 fn read<B: Board>(mut call: SchedulerCall<B, api::read::Sig>) {
-    let api::read::Params { ptr, len } = call.read();
+    let api::read::Params { port, ptr, len } = call.read();
     let scheduler = call.scheduler();
     let memory = scheduler.applet.memory();
     let results = try {
         let output = memory.get_mut(*ptr, *len)?;
-        let len = match scheduler.board.usb().serial().read(output) {
+        let len = match scheduler.board.usb().serial().read(*port as usize, output) {
             Ok(len) => (len as u32).into(),
             Err(_) => u32::MAX.into(),
         };
@@ -47,14 +64,19 @@ fn read<B: Board>(mut call: SchedulerCall<B, api::read::Sig>) {
 }
 
 fn write<B: Board>(mut call: SchedulerCall<B, api::write::Sig>) {
-    let api::write::Params { ptr, len } = call.read();
+    let api::write::Params { port, ptr, len } = call.read();
     let scheduler = call.scheduler();
     let memory = scheduler.applet.memory();
     let results = try {
         let input = memory.get(*ptr, *len)?;
-        let len = match scheduler.board.usb().serial().write(input) {
-            Ok(len) => (len as u32).into(),
-            Err(_) => u32::MAX.into(),
+        let port = *port as usize;
+        let effect = || Effect::UsbSerialWrite { port, data: input.to_vec() };
+        let len = match scheduler.dry_run.skip(&mut scheduler.board, effect) {
+            true => (input.len() as u32).into(),
+            false => match scheduler.board.usb().serial().write(port, input) {
+                Ok(len) => (len as u32).into(),
+                Err(_) => u32::MAX.into(),
+            },
         };
         api::write::Results { len }
     };
@@ -62,40 +84,46 @@ fn write<B: Board>(mut call: SchedulerCall<B, api::write::Sig>) {
 }
 
 fn register<B: Board>(mut call: SchedulerCall<B, api::register::Sig>) {
-    let api::register::Params { event, handler_func, handler_data } = call.read();
+    let api::register::Params { port, event, handler_func, handler_data } = call.read();
     let inst = call.inst();
     let scheduler = call.scheduler();
     let results = try {
+        let port = *port as usize;
         let event = convert_event(*event)?;
+        // The handler must not be registered if the board can't enable the event.
+        if scheduler.board.usb().serial().count() <= port {
+            Err(Trap)?;
+        }
         scheduler.applet.enable(Handler {
-            key: Key::from(&event).into(),
+            key: Key::new(port, &event).into(),
             inst,
             func: *handler_func,
             data: *handler_data,
         })?;
-        scheduler.board.usb().serial().enable(&event).map_err(|_| Trap)?;
+        scheduler.board.usb().serial().enable(port, &event).map_err(|_| Trap)?;
         api::register::Results {}
     };
     call.reply(results);
 }
 
 fn unregister<B: Board>(mut call: SchedulerCall<B, api::unregister::Sig>) {
-    let api::unregister::Params { event } = call.read();
+    let api::unregister::Params { port, event } = call.read();
     let scheduler = call.scheduler();
     let results = try {
+        let port = *port as usize;
         let event = convert_event(*event)?;
-        scheduler.board.usb().serial().disable(&event).map_err(|_| Trap)?;
-        scheduler.disable_event(Key::from(&event).into())?;
+        scheduler.board.usb().serial().disable(port, &event).map_err(|_| Trap)?;
+        scheduler.disable_event(Key::new(port, &event).into())?;
         api::unregister::Results {}
     };
     call.reply(results);
 }
 
 fn flush<B: Board>(mut call: SchedulerCall<B, api::flush::Sig>) {
-    let api::flush::Params {} = call.read();
+    let api::flush::Params { port } = call.read();
     let scheduler = call.scheduler();
     let results = try {
-        let res = match scheduler.board.usb().serial().flush() {
+        let res = match scheduler.board.usb().serial().flush(*port as usize) {
             Ok(()) => 0.into(),
             Err(_) => u32::MAX.into(),
         };
@@ -104,9 +132,79 @@ fn flush<B: Board>(mut call: SchedulerCall<B, api::flush::Sig>) {
     call.reply(results);
 }
 
+fn flush_timeout<B: Board>(mut call: SchedulerCall<B, api::flush_timeout::Sig>) {
+    let api::flush_timeout::Params { port, timeout_ms } = call.read();
+    let scheduler = call.scheduler();
+    let results = try {
+        let timeout_ms = core::cmp::min(*timeout_ms as usize, MAX_FLUSH_TIMEOUT_MS);
+        let duration_us = timeout_ms as u64 * 1000;
+        let res = match scheduler.board.usb().serial().flush_timeout(*port as usize, duration_us) {
+            Ok(()) => 0.into(),
+            Err(_) => u32::MAX.into(),
+        };
+        api::flush_timeout::Results { res }
+    };
+    call.reply(results);
+}
+
+fn line_state<B: Board>(mut call: SchedulerCall<B, api::line_state::Sig>) {
+    let api::line_state::Params { port } = call.read();
+    let scheduler = call.scheduler();
+    let results = try {
+        let res = match scheduler.board.usb().serial().line_state(*port as usize) {
+            Ok(LineState { dtr, rts }) => (dtr as u32 | (rts as u32) << 1).into(),
+            Err(_) => u32::MAX.into(),
+        };
+        api::line_state::Results { res }
+    };
+    call.reply(results);
+}
+
+fn line_coding<B: Board>(mut call: SchedulerCall<B, api::line_coding::Sig>) {
+    let api::line_coding::Params { port, ptr } = call.read();
+    let scheduler = call.scheduler();
+    let memory = scheduler.applet.memory();
+    let results = try {
+        let output = memory.get_array_mut::<8>(*ptr)?;
+        let res = match scheduler.board.usb().serial().line_coding(*port as usize) {
+            Ok(coding) => {
+                output[.. 4].copy_from_slice(&coding.data_rate.to_le_bytes());
+                output[4] = convert_stop_bits(coding.stop_bits) as u8;
+                output[5] = convert_parity(coding.parity) as u8;
+                output[6] = coding.data_bits;
+                output[7] = 0;
+                0.into()
+            }
+            Err(_) => u32::MAX.into(),
+        };
+        api::line_coding::Results { res }
+    };
+    call.reply(results);
+}
+
+fn convert_stop_bits(stop_bits: StopBits) -> api::StopBits {
+    match stop_bits {
+        StopBits::One => api::StopBits::One,
+        StopBits::OnePointFive => api::StopBits::OnePointFive,
+        StopBits::Two => api::StopBits::Two,
+    }
+}
+
+fn convert_parity(parity: Parity) -> api::Parity {
+    match parity {
+        Parity::None => api::Parity::None,
+        Parity::Odd => api::Parity::Odd,
+        Parity::Even => api::Parity::Even,
+        Parity::Mark => api::Parity::Mark,
+        Parity::Space => api::Parity::Space,
+    }
+}
+
 fn convert_event(event: u32) -> Result<Event, Trap> {
     Ok(match api::Event::try_from(event)? {
         api::Event::Read => Event::Read,
         api::Event::Write => Event::Write,
+        api::Event::Control => Event::Control,
+        api::Event::Coding => Event::Coding,
     })
 }
@@ -0,0 +1,122 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Board capabilities.
+//!
+//! Capabilities are answered from the board interfaces, such that applets may query them at runtime
+//! or require them in their manifest.
+
+use wasefire_applet_api::platform::Capability;
+use wasefire_board_api::crypto::aes128_ccm::Api as _;
+use wasefire_board_api::crypto::aes256_gcm::Api as _;
+use wasefire_board_api::crypto::crc::Api as _;
+use wasefire_board_api::crypto::ecdh::Api as _;
+use wasefire_board_api::crypto::sha256::Api as _;
+use wasefire_board_api::crypto::Api as _;
+use wasefire_board_api::usb::serial::Api as _;
+use wasefire_board_api::usb::Api as _;
+use wasefire_board_api::{
+    adc, button, encoder, gpio, led, led_ring, magnetometer, pwm, rng, stepper, temp, timer, uart,
+    Api as Board,
+};
+
+/// Returns all the capabilities.
+pub fn all() -> impl Iterator<Item = Capability> {
+    (0 ..).map_while(|x| Capability::try_from(x).ok())
+}
+
+/// Returns the name of a capability, as used in applet manifests.
+pub fn name(capability: Capability) -> &'static str {
+    match capability {
+        Capability::Adc => "adc",
+        Capability::Aes128Ccm => "aes128_ccm",
+        Capability::Aes256Gcm => "aes256_gcm",
+        Capability::Button => "button",
+        Capability::Crc => "crc",
+        Capability::Ecdh => "ecdh",
+        Capability::Encoder => "encoder",
+        Capability::Gpio => "gpio",
+        Capability::Led => "led",
+        Capability::LedRing => "led_ring",
+        Capability::Magnetometer => "magnetometer",
+        Capability::Pwm => "pwm",
+        Capability::Rng => "rng",
+        Capability::Sha256 => "sha256",
+        Capability::Stepper => "stepper",
+        Capability::Temp => "temp",
+        Capability::Timer => "timer",
+        Capability::Uart => "uart",
+        Capability::UsbSerial => "usb_serial",
+    }
+}
+
+/// Returns the capability with a given name, if any.
+pub fn parse(name: &str) -> Option<Capability> {
+    all().find(|&x| self::name(x) == name)
+}
+
+/// Returns whether the board supports a capability.
+pub fn is_supported<B: Board>(board: &mut B, capability: Capability) -> bool {
+    match capability {
+        Capability::Adc => adc::Api::count(&mut board.adc()) > 0,
+        Capability::Aes128Ccm => board.crypto().aes128_ccm().is_supported(),
+        Capability::Aes256Gcm => board.crypto().aes256_gcm().is_supported(),
+        Capability::Button => button::Api::count(&mut board.button()) > 0,
+        Capability::Crc => board.crypto().crc().is_supported(),
+        Capability::Ecdh => board.crypto().ecdh().is_supported(),
+        Capability::Encoder => encoder::Api::count(&mut board.encoder()) > 0,
+        Capability::Gpio => gpio::Api::count(&mut board.gpio()) > 0,
+        Capability::Led => led::Api::count(&mut board.led()) > 0,
+        Capability::LedRing => led_ring::Api::count(&mut board.led_ring()) > 0,
+        Capability::Magnetometer => magnetometer::Api::is_supported(&mut board.magnetometer()),
+        Capability::Pwm => pwm::Api::count(&mut board.pwm()) > 0,
+        Capability::Rng => rng::Api::fill_bytes(&mut board.rng(), &mut []).is_ok(),
+        Capability::Sha256 => board.crypto().sha256().is_supported(),
+        Capability::Stepper => stepper::Api::count(&mut board.stepper()) > 0,
+        Capability::Temp => temp::Api::is_supported(&mut board.temp()),
+        Capability::Timer => timer::Api::count(&mut board.timer()) > 0,
+        Capability::Uart => uart::Api::count(&mut board.uart()) > 0,
+        Capability::UsbSerial => board.usb().serial().count() > 0,
+    }
+}
+
+/// Returns the set of capabilities supported by the board.
+///
+/// Bit `1 << c` is set if the board supports capability `c`.
+pub fn supported<B: Board>(board: &mut B) -> u32 {
+    all().filter(|&x| is_supported(board, x)).fold(0, |r, x| r | (1 << x as u32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::scheduler;
+
+    #[test]
+    fn names_round_trip() {
+        assert_eq!(all().count(), 19);
+        for capability in all() {
+            assert_eq!(parse(name(capability)), Some(capability));
+        }
+        assert_eq!(parse("teleport"), None);
+    }
+
+    #[test]
+    fn supported_capabilities() {
+        let board = &mut scheduler(false).board;
+        assert!(is_supported(board, Capability::Led));
+        assert!(!is_supported(board, Capability::Rng));
+        assert_eq!(supported(board), 1 << Capability::Led as u32);
+    }
+}
@@ -0,0 +1,49 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Dry-run mode to preview what an applet would do.
+//!
+//! In dry-run mode, side-effecting board operations are not performed but recorded with the board
+//! debug interface. Operations reading state are performed as usual.
+
+use wasefire_board_api::debug::{Api as _, Effect};
+use wasefire_board_api::Api as Board;
+use wasefire_logger::*;
+
+pub struct DryRun {
+    enabled: bool,
+}
+
+impl DryRun {
+    pub fn new<B: Board>(board: &mut B) -> Self {
+        let enabled = board.debug().dry_run();
+        if enabled {
+            info!("Running applets in dry-run mode.");
+        }
+        DryRun { enabled }
+    }
+
+    /// Returns whether an effect must be skipped, in which case it is recorded.
+    ///
+    /// The effect is only built in dry-run mode.
+    pub fn skip<B: Board>(&self, board: &mut B, effect: impl FnOnce() -> Effect) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        let effect = effect();
+        info!("Skipping {}", Debug2Format(&effect));
+        board.debug().record(effect);
+        true
+    }
+}
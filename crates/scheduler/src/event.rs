@@ -23,14 +23,22 @@ use wasefire_logger as logger;
 use crate::Scheduler;
 
 pub mod button;
+pub mod capture;
+pub mod encoder;
+pub mod stepper;
 pub mod timer;
+pub mod uart;
 pub mod usb;
 
 // TODO: This could be encoded into a u32 for performance/footprint.
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Key {
     Button(button::Key),
+    Capture(capture::Key),
+    Encoder(encoder::Key),
+    Stepper(stepper::Key),
     Timer(timer::Key),
+    Uart(uart::Key),
     Usb(usb::Key),
 }
 
@@ -38,7 +46,13 @@ impl<'a> From<&'a Event> for Key {
     fn from(event: &'a Event) -> Self {
         match event {
             Event::Button(event) => Key::Button(event.into()),
+            Event::Capture(event) => Key::Capture(event.into()),
+            Event::Debug(_) => unreachable!("debug events are handled by the scheduler"),
+            Event::Encoder(event) => Key::Encoder(event.into()),
+            Event::Gesture(event) => Key::Button(event.into()),
+            Event::Stepper(event) => Key::Stepper(event.into()),
             Event::Timer(event) => Key::Timer(event.into()),
+            Event::Uart(event) => Key::Uart(event.into()),
             Event::Usb(event) => Key::Usb(event.into()),
         }
     }
@@ -90,7 +104,13 @@ pub fn process<B: Board>(scheduler: &mut Scheduler<B>, event: Event) {
     let mut params = vec![*func, *data];
     match event {
         Event::Button(event) => button::process(event, &mut params),
+        Event::Capture(_) => capture::process(),
+        Event::Debug(_) => unreachable!(),
+        Event::Encoder(_) => encoder::process(),
+        Event::Gesture(event) => button::process_gesture(event, &mut params),
+        Event::Stepper(_) => stepper::process(),
         Event::Timer(_) => timer::process(),
+        Event::Uart(_) => uart::process(),
         Event::Usb(event) => usb::process(event),
     }
     let name = match params.len() - 2 {
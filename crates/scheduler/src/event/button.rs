@@ -12,9 +12,19 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use alloc::collections::BTreeMap;
 use alloc::vec::Vec;
 
-use wasefire_board_api::button::Event;
+use wasefire_applet_api::button as api;
+use wasefire_board_api::button::gesture::{Config, Detector};
+use wasefire_board_api::button::{Event, Gesture, GestureEvent};
+use wasefire_board_api::clock::Api as _;
+use wasefire_board_api::timer::{Api as _, Mode};
+use wasefire_board_api::Api as Board;
+use wasefire_logger as logger;
+
+use crate::stores::Applet;
+use crate::{Scheduler, Timer};
 
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Key {
@@ -33,6 +43,124 @@ impl<'a> From<&'a Event> for Key {
     }
 }
 
+impl<'a> From<&'a GestureEvent> for Key {
+    fn from(event: &'a GestureEvent) -> Self {
+        Key { button: event.button }
+    }
+}
+
 pub fn process(event: Event, params: &mut Vec<u32>) {
     params.push(event.pressed as u32);
 }
+
+pub fn process_gesture(event: GestureEvent, params: &mut Vec<u32>) {
+    let gesture = match event.gesture {
+        Gesture::Single => api::Gesture::Single,
+        Gesture::Long => api::Gesture::Long,
+        Gesture::Double => api::Gesture::Double,
+    };
+    params.push(gesture as u32);
+}
+
+/// Gesture recognition for the buttons that have a gesture configuration in an applet.
+///
+/// A single timer is reserved (among those available to applets) for the applet while at least one
+/// of its buttons is configured. It is armed for the earliest deadline of all those buttons.
+#[derive(Debug, Default)]
+pub struct Gestures {
+    timer: Option<usize>,
+    detectors: BTreeMap<usize, Detector>,
+}
+
+impl Gestures {
+    /// Returns whether the events of a button go through gesture recognition.
+    pub fn has_button(&self, button: usize) -> bool {
+        self.detectors.contains_key(&button)
+    }
+
+    /// Returns whether a timer is reserved for gesture recognition.
+    pub fn has_timer(&self, timer: usize) -> bool {
+        self.timer == Some(timer)
+    }
+}
+
+/// Configures gesture recognition for a button of the running applet.
+///
+/// Returns whether it succeeded, i.e. whether a timer and the uptime clock are available.
+pub fn configure<B: Board>(
+    scheduler: &mut Scheduler<B>, button: usize, config: Option<Config>,
+) -> bool {
+    let applet = scheduler.applet.store_mut().id();
+    let gestures = &mut scheduler.applet.gestures;
+    match config {
+        Some(config) => {
+            if scheduler.board.clock().uptime_us().is_err() {
+                return false;
+            }
+            if gestures.timer.is_none() {
+                match scheduler.timers.iter().position(|x| x.is_none()) {
+                    Some(timer) => {
+                        scheduler.timers[timer] = Some(Timer { applet });
+                        gestures.timer = Some(timer);
+                    }
+                    None => return false,
+                }
+            }
+            gestures.detectors.insert(button, Detector::new(config));
+        }
+        None => {
+            gestures.detectors.remove(&button);
+            if gestures.detectors.is_empty() {
+                if let Some(timer) = gestures.timer.take() {
+                    let _ = scheduler.board.timer().disarm(timer);
+                    scheduler.timers[timer] = None;
+                }
+            }
+        }
+    }
+    true
+}
+
+/// Processes a button event for a button with a gesture configuration in an applet.
+pub fn push_button<B: Board>(board: &mut B, applet: &mut Applet<B>, event: Event) {
+    let now = match board.clock().uptime_us() {
+        Ok(x) => x,
+        Err(_) => return logger::error!("Dropping button event without uptime."),
+    };
+    let detector = applet.gestures.detectors.get_mut(&event.button).unwrap();
+    if let Some(gesture) = detector.update(event.pressed, now) {
+        applet.push(GestureEvent { button: event.button, gesture }.into());
+    }
+    arm(board, &applet.gestures, now);
+}
+
+/// Processes the gesture timer event of an applet.
+pub fn push_timer<B: Board>(board: &mut B, applet: &mut Applet<B>) {
+    let now = match board.clock().uptime_us() {
+        Ok(x) => x,
+        Err(_) => return logger::error!("Dropping gesture timer event without uptime."),
+    };
+    let mut gestures = Vec::new();
+    for (&button, detector) in applet.gestures.detectors.iter_mut() {
+        if let Some(gesture) = detector.timeout(now) {
+            gestures.push(GestureEvent { button, gesture });
+        }
+    }
+    for gesture in gestures {
+        applet.push(gesture.into());
+    }
+    arm(board, &applet.gestures, now);
+}
+
+fn arm<B: Board>(board: &mut B, gestures: &Gestures, now: u64) {
+    let timer = gestures.timer.unwrap();
+    let deadline = gestures.detectors.values().filter_map(|x| x.deadline()).min();
+    // The timer may have already triggered, in which case it is already disarmed.
+    let _ = board.timer().disarm(timer);
+    if let Some(deadline) = deadline {
+        let duration = deadline.saturating_sub(now).clamp(1, usize::MAX as u64) as usize;
+        if board.timer().arm(timer, duration, Mode::OneShot).is_err() {
+            logger::error!("Failed to arm the gesture timer.");
+        }
+    }
+}
@@ -0,0 +1,44 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use wasefire_board_api::uart::{Direction, Event};
+
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Key {
+    Read { uart: usize },
+    Write { uart: usize },
+}
+
+impl From<Key> for crate::event::Key {
+    fn from(key: Key) -> Self {
+        crate::event::Key::Uart(key)
+    }
+}
+
+impl<'a> From<&'a Event> for Key {
+    fn from(event: &'a Event) -> Self {
+        Key::new(event.uart, event.direction)
+    }
+}
+
+impl Key {
+    pub fn new(uart: usize, direction: Direction) -> Self {
+        match direction {
+            Direction::Read => Key::Read { uart },
+            Direction::Write => Key::Write { uart },
+        }
+    }
+}
+
+pub fn process() {}
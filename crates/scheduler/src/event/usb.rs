@@ -12,12 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod hid;
 pub mod serial;
 
 use wasefire_board_api::usb::Event;
 
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Key {
+    Hid(hid::Key),
     Serial(serial::Key),
 }
 
@@ -30,13 +32,15 @@ impl From<Key> for crate::event::Key {
 impl<'a> From<&'a Event> for Key {
     fn from(event: &'a Event) -> Self {
         match event {
-            Event::Serial(event) => Key::Serial(event.into()),
+            Event::Hid(event) => Key::Hid(event.into()),
+            Event::Serial { port, event } => Key::Serial(serial::Key::new(*port, event)),
         }
     }
 }
 
 pub fn process(event: Event) {
     match event {
-        Event::Serial(_) => serial::process(),
+        Event::Hid(_) => hid::process(),
+        Event::Serial { .. } => serial::process(),
     }
 }
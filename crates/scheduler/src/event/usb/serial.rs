@@ -16,8 +16,10 @@ use wasefire_board_api::usb::serial::Event;
 
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Key {
-    Read,
-    Write,
+    Read { port: usize },
+    Write { port: usize },
+    Control { port: usize },
+    Coding { port: usize },
 }
 
 impl From<Key> for crate::event::Key {
@@ -26,11 +28,13 @@ impl From<Key> for crate::event::Key {
     }
 }
 
-impl<'a> From<&'a Event> for Key {
-    fn from(event: &'a Event) -> Self {
+impl Key {
+    pub fn new(port: usize, event: &Event) -> Self {
         match event {
-            Event::Read => Key::Read,
-            Event::Write => Key::Write,
+            Event::Read => Key::Read { port },
+            Event::Write => Key::Write { port },
+            Event::Control => Key::Control { port },
+            Event::Coding => Key::Coding { port },
         }
     }
 }
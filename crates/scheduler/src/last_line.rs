@@ -0,0 +1,113 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Last line printed by applets, reported if one faults.
+//!
+//! Printed lines are written to a ring buffer, such that printing doesn't allocate. Lines are
+//! separated by [`SEPARATOR`] which never occurs in UTF-8.
+
+/// Capacity in bytes of the ring buffer.
+///
+/// Lines longer than the buffer are truncated at the beginning.
+const CAPACITY: usize = 256;
+
+/// Byte written after each line.
+const SEPARATOR: u8 = 0xff;
+
+pub struct LastLine {
+    buffer: [u8; CAPACITY],
+    /// Index of the next byte to write.
+    end: usize,
+    /// Number of written bytes, at most the capacity.
+    len: usize,
+}
+
+impl Default for LastLine {
+    fn default() -> Self {
+        LastLine { buffer: [0; CAPACITY], end: 0, len: 0 }
+    }
+}
+
+impl LastLine {
+    /// Records a printed line, overwriting the oldest bytes.
+    pub fn push(&mut self, line: &str) {
+        let line = line.as_bytes();
+        for &byte in &line[line.len().saturating_sub(CAPACITY - 1) ..] {
+            self.write(byte);
+        }
+        self.write(SEPARATOR);
+    }
+
+    /// Forgets all lines.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Returns the last printed line, if any.
+    pub fn get(&mut self) -> Option<&str> {
+        if self.len == 0 {
+            return None;
+        }
+        // Make the lines contiguous.
+        self.buffer.rotate_left((self.end + CAPACITY - self.len) % CAPACITY);
+        self.end = self.len % CAPACITY;
+        let lines = &self.buffer[.. self.len - 1];
+        let start = lines.iter().rposition(|&x| x == SEPARATOR).map_or(0, |i| i + 1);
+        let mut line = &lines[start ..];
+        // Skip the rest of a character truncated by the beginning of the buffer.
+        while let [x, rest @ ..] = line {
+            if x & 0xc0 != 0x80 {
+                break;
+            }
+            line = rest;
+        }
+        Some(core::str::from_utf8(line).unwrap())
+    }
+
+    fn write(&mut self, byte: u8) {
+        self.buffer[self.end] = byte;
+        self.end = (self.end + 1) % CAPACITY;
+        self.len = core::cmp::min(self.len + 1, CAPACITY);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn last_of_many() {
+        let mut last_line = LastLine::default();
+        assert_eq!(last_line.get(), None);
+        for i in 0 .. 100 {
+            last_line.push(&alloc::format!("line {i}"));
+        }
+        assert_eq!(last_line.get(), Some("line 99"));
+        last_line.push("");
+        assert_eq!(last_line.get(), Some(""));
+        last_line.clear();
+        assert_eq!(last_line.get(), None);
+    }
+
+    #[test]
+    fn truncate_on_char_boundary() {
+        let mut last_line = LastLine::default();
+        last_line.push("first");
+        let line = alloc::format!("a{}", "é".repeat(CAPACITY));
+        last_line.push(&line);
+        let truncated = last_line.get().unwrap();
+        assert_eq!(truncated.len(), CAPACITY - 2);
+        assert!(line.ends_with(truncated));
+    }
+}
@@ -18,64 +18,186 @@
 
 extern crate alloc;
 
+use alloc::alloc::Layout;
 use alloc::collections::VecDeque;
 use alloc::vec;
 use alloc::vec::Vec;
 use core::cell::RefCell;
 use core::marker::PhantomData;
 use core::ops::Range;
+use core::ptr::addr_of_mut;
+use core::sync::atomic::{AtomicBool, Ordering};
 
 use event::Key;
 use stores::{Applet, EventAction};
 use wasefire_applet_api::{self as api, Api, ArrayU32, Dispatch, Id, Signature};
+#[cfg(feature = "metrics")]
+use wasefire_board_api::clock::Api as _;
+use wasefire_board_api::debug::{Api as _, Fault};
 use wasefire_board_api::timer::Api as _;
+use wasefire_board_api::watchdog::Api as _;
 use wasefire_board_api::{self as board, Api as Board};
 use wasefire_interpreter::{
-    self as interpreter, Call, Error, InstId, Module, RunAnswer, RunResult, Store, Val,
+    self as interpreter, Call, Error, InstId, Module, RunAnswer, RunResult, Store, StoreId, Val,
 };
 use wasefire_logger::{self as logger, *};
-use wasefire_store as store;
 
 mod call;
+mod capability;
+mod dry_run;
 mod event;
+mod last_line;
+mod logs;
+mod manifest;
+#[cfg(feature = "metrics")]
+mod metrics;
+#[cfg(feature = "shell")]
+mod shell;
+pub mod signature;
 mod stores;
+#[cfg(test)]
+mod testing;
 
-#[derive(Default)]
-pub struct Events(VecDeque<board::Event>);
+#[cfg(feature = "metrics")]
+pub use metrics::SchedulerMetrics;
+
+/// Time in milliseconds an applet may run without returning to the scheduler.
+const WATCHDOG_TIMEOUT_MS: usize = 5000;
+
+/// Size in bytes of the linear memory of each applet.
+const MEMORY_SIZE: usize = 0x10000;
+
+/// Policy when pushing an event to a full queue.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Drops the oldest event of the lowest queued priority to make room for the new one.
+    ///
+    /// The new event is dropped instead if its priority is lower than all queued events. Under
+    /// sustained traffic of higher priority, events of lower priority may thus starve.
+    DropOldest,
+
+    /// Drops the new event.
+    ///
+    /// If its priority is higher than some queued events, the newest event of the lowest queued
+    /// priority is dropped instead.
+    DropNewest,
+
+    /// Merges events identical to a queued one (even if the queue is not full) and drops new
+    /// events otherwise, like [`Self::DropNewest`].
+    #[default]
+    Coalesce,
+}
+
+/// Bounded queue of board events.
+///
+/// Events are popped by decreasing [priority](board::Event::priority()) and in order within a
+/// priority. Events of lower priority are only popped when no events of higher priority are queued,
+/// so they may starve under sustained traffic of higher priority.
+pub struct Events {
+    queue: VecDeque<board::Event>,
+    capacity: usize,
+    policy: OverflowPolicy,
+    dropped: usize,
+}
+
+impl Default for Events {
+    fn default() -> Self {
+        Events::new(10, OverflowPolicy::default())
+    }
+}
 
 impl Events {
+    /// Creates an empty queue with a given capacity and overflow policy.
+    pub fn new(capacity: usize, policy: OverflowPolicy) -> Self {
+        Events { queue: VecDeque::new(), capacity, policy, dropped: 0 }
+    }
+
     pub fn is_empty(&self) -> bool {
-        self.0.is_empty()
+        self.queue.is_empty()
+    }
+
+    /// Returns the number of queued events.
+    pub fn depth(&self) -> usize {
+        self.queue.len()
     }
 
-    pub fn push(&mut self, event: board::Event) {
-        const MAX_EVENTS: usize = 10;
-        if self.0.contains(&event) {
+    /// Returns the number of events dropped because the queue was full.
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    /// Pushes an event according to the overflow policy.
+    ///
+    /// Returns the queued event dropped to make room for the new one, if any.
+    pub fn push(&mut self, event: board::Event) -> Option<board::Event> {
+        if self.policy == OverflowPolicy::Coalesce && self.queue.contains(&event) {
             trace!("Merging {}", Debug2Format(&event));
-        } else if self.0.len() < MAX_EVENTS {
+            return None;
+        }
+        if self.queue.len() < self.capacity {
             debug!("Pushing {}", Debug2Format(&event));
-            self.0.push_back(event);
-        } else {
-            warn!("Dropping {}", Debug2Format(&event));
+            self.queue.push_back(event);
+            return None;
         }
+        self.dropped += 1;
+        let lowest = self.queue.iter().map(|x| x.priority()).min();
+        let is_lowest = |x: &board::Event| Some(x.priority()) == lowest;
+        let index = match self.policy {
+            OverflowPolicy::DropOldest if lowest <= Some(event.priority()) => {
+                self.queue.iter().position(is_lowest)
+            }
+            OverflowPolicy::DropNewest | OverflowPolicy::Coalesce
+                if lowest < Some(event.priority()) =>
+            {
+                self.queue.iter().rposition(is_lowest)
+            }
+            _ => None,
+        };
+        let Some(index) = index else {
+            warn!("Dropping {}", Debug2Format(&event));
+            return None;
+        };
+        let dropped = self.queue.remove(index);
+        warn!("Dropping {}", Debug2Format(&dropped));
+        self.queue.push_back(event);
+        dropped
     }
 
     pub fn pop(&mut self) -> Option<board::Event> {
-        self.0.pop_front().inspect(|event| debug!("Popping {}", Debug2Format(&event)))
+        let priority = self.queue.iter().map(|x| x.priority()).max()?;
+        let index = self.queue.iter().position(|x| x.priority() == priority).unwrap();
+        self.queue.remove(index).inspect(|event| debug!("Popping {}", Debug2Format(&event)))
     }
 }
 
 pub struct Scheduler<B: Board> {
     board: B,
-    store: store::Store<B::Storage>,
     host_funcs: Vec<Api<Id>>,
+    /// The running applet.
     applet: Applet<B>,
+    /// The other applets in round-robin order.
+    applets: VecDeque<Applet<B>>,
     timers: Vec<Option<Timer>>,
+    dry_run: dry_run::DryRun,
+    /// Public key of the signatures granting applets their manifest permissions.
+    applet_key: Option<[u8; 32]>,
+    /// The last line printed by an applet, reported if it faults.
+    last_line: last_line::LastLine,
+    /// Applet logs not yet sent to the platform logger.
+    logs: logs::Logs,
+    /// Whether the platform asked to stop once the running event handler returns.
+    shutdown: bool,
+    #[cfg(feature = "metrics")]
+    metrics: SchedulerMetrics,
+    /// Uptime in microseconds when the running event handler was called.
+    #[cfg(feature = "metrics")]
+    handler_start: Option<u64>,
 }
 
 #[derive(Clone)]
 struct Timer {
-    // TODO: Add AppletId.
+    /// The applet owning the timer.
+    applet: StoreId,
 }
 
 impl<B: Board> core::fmt::Debug for Scheduler<B> {
@@ -146,14 +268,17 @@ impl<'a, B: Board, T: Signature> SchedulerCall<'a, B, T> {
         self.erased.scheduler
     }
 
-    pub fn reply(mut self, results: Result<T::Results, Trap>) {
-        match results.map(convert_results::<T>) {
-            Ok(results) => {
-                let answer = self.call().resume(&results).map(|x| x.forget());
-                self.erased.scheduler.process_answer(answer);
+    pub fn reply(mut self, results: Result<T::Results, Failure>) {
+        let results = match results {
+            Ok(results) => convert_results::<T>(results),
+            Err(Failure::Trap) => return self.erased.scheduler.fault(Some(T::NAME)),
+            Err(Failure::Error(e)) => {
+                debug!("{} failed with {}.", T::NAME, Debug2Format(&e));
+                error_results::<T>()
             }
-            Err(Trap) => logger::panic!("Applet trapped in host."),
-        }
+        };
+        let answer = self.call().resume(&results).map(|x| x.forget());
+        self.erased.scheduler.process_answer(answer);
     }
 
     fn applet(&mut self) -> &mut Applet<B> {
@@ -171,10 +296,29 @@ impl<'a, B: Board, T: Signature> SchedulerCall<'a, B, T> {
 
 impl<B: Board> Scheduler<B> {
     pub fn run(board: B, wasm: &'static [u8]) -> ! {
+        Self::run_many(board, &[wasm], None)
+    }
+
+    /// Runs multiple applets, each in its own linear memory.
+    ///
+    /// Events are delivered to the applets that registered a handler for them. Applets run until
+    /// they wait for events, at which point the next applet with pending work runs. Timers and
+    /// button gestures belong to the applet that configured them. An applet that traps is stopped
+    /// while the others keep running.
+    ///
+    /// Applets are only granted the permissions of their manifest if they are signed with the applet
+    /// key. Without applet key, no applet is granted permissions.
+    pub fn run_many(board: B, wasms: &[&'static [u8]], applet_key: Option<[u8; 32]>) -> ! {
         let mut scheduler = Scheduler::new(board);
-        debug!("Loading applet.");
-        scheduler.load(wasm);
+        scheduler.applet_key = applet_key;
+        if scheduler.board.watchdog().start(WATCHDOG_TIMEOUT_MS).is_err() {
+            debug!("Watchdog is not supported.");
+        }
+        scheduler.load_many(wasms, memory);
         loop {
+            // The applet is stuck if it doesn't return to the scheduler in time.
+            let _ = scheduler.board.watchdog().feed();
+            scheduler.logs.flush_one();
             scheduler.flush_events();
             scheduler.process_applet();
         }
@@ -185,25 +329,103 @@ impl<B: Board> Scheduler<B> {
         Api::<Id>::iter(&mut host_funcs, |x| x);
         host_funcs.sort_by_key(|x| x.descriptor().name);
         assert!(host_funcs.windows(2).all(|x| x[0].descriptor().name != x[1].descriptor().name));
-        let mut applet = Applet::default();
-        let store = applet.store_mut();
-        for f in &host_funcs {
-            let d = f.descriptor();
-            store.link_func("env", d.name, d.params, d.results).unwrap();
-        }
+        let applet = new_applet(&host_funcs);
+        let applets = VecDeque::new();
         let timers = vec![None; board.timer().count()];
-        let store = store::Store::new(board.take_storage().unwrap()).ok().unwrap();
-        Self { board, store, host_funcs, applet, timers }
+        let dry_run = dry_run::DryRun::new(&mut board);
+        if let Err(error) = board.with_store(board::store::migrate) {
+            logger::error!("Failed to migrate the store: {}", Debug2Format(&error));
+        }
+        Self {
+            board,
+            host_funcs,
+            applet,
+            applets,
+            timers,
+            dry_run,
+            applet_key: None,
+            last_line: last_line::LastLine::default(),
+            logs: logs::Logs::default(),
+            shutdown: false,
+            #[cfg(feature = "metrics")]
+            metrics: SchedulerMetrics::default(),
+            #[cfg(feature = "metrics")]
+            handler_start: None,
+        }
     }
 
-    fn load(&mut self, wasm: &'static [u8]) {
-        #[repr(align(16))]
-        struct Memory([u8; 0x10000]);
-        static mut MEMORY: Memory = Memory([0; 0x10000]);
+    /// Returns statistics about the events dispatched so far.
+    #[cfg(feature = "metrics")]
+    pub fn metrics(&self) -> SchedulerMetrics {
+        self.metrics.clone()
+    }
+
+    /// Parks the running applet and runs a new empty applet.
+    fn add_applet(&mut self) {
+        let applet = core::mem::replace(&mut self.applet, new_applet(&self.host_funcs));
+        self.applets.push_back(applet);
+    }
+
+    /// Runs the next applet with pending work, if any, and parks the running applet.
+    ///
+    /// This is called when the running applet waits for events. Returns whether an applet was
+    /// switched to.
+    fn switch_applet(&mut self) -> bool {
+        let Some(i) = self.applets.iter().position(|x| x.is_runnable()) else { return false };
+        let mut applet = self.applets.remove(i).unwrap();
+        applet.resume();
+        let mut parked = core::mem::replace(&mut self.applet, applet);
+        parked.park();
+        self.applets.push_back(parked);
+        debug!("Switched applet.");
+        true
+    }
+
+    /// Loads applets in order, skipping those that can't run on the board.
+    ///
+    /// The last loaded applet is running and the others are parked.
+    fn load_many(
+        &mut self, wasms: &[&'static [u8]], mut memory: impl FnMut() -> &'static mut [u8],
+    ) {
+        let mut loaded = false;
+        for (i, &wasm) in wasms.iter().enumerate() {
+            debug!("Loading applet {}.", i);
+            if loaded {
+                self.add_applet();
+            }
+            // An applet that fails to load leaves the running applet empty for the next one.
+            loaded = match self.load(wasm, &mut memory) {
+                Ok(()) => true,
+                Err(error) => {
+                    error!("Skipping applet {}: {}", i, Display2Format(&error));
+                    false
+                }
+            };
+        }
+        // The last applet failed to load, so the last loaded applet runs instead of an empty one.
+        if !loaded {
+            if let Some(applet) = self.applets.pop_back() {
+                self.applet = applet;
+            }
+        }
+    }
+
+    /// Loads an applet in the running applet and runs its `main` function.
+    ///
+    /// Returns an error without changing the running applet if the applet can't run on the board.
+    /// The linear memory is only allocated if the applet can run.
+    fn load(
+        &mut self, wasm: &'static [u8], memory: impl FnOnce() -> &'static mut [u8],
+    ) -> Result<(), manifest::Error<'static>> {
+        let mut permissions = manifest::check(&mut self.board, wasm)?;
+        if permissions != manifest::Permissions::default() && !self.is_signed(wasm) {
+            logger::warn!("Ignoring the permissions of an applet without valid signature.");
+            permissions = manifest::Permissions::default();
+        }
+        self.applet.permissions = permissions;
         let module = Module::new(wasm).unwrap();
         let store = self.applet.store_mut();
-        // SAFETY: This function is called once in `run()`.
-        let inst = store.instantiate(module, unsafe { &mut MEMORY.0 }).unwrap();
+        let inst = store.instantiate(module, memory()).unwrap();
         match store.invoke(inst, "init", vec![]) {
             Ok(RunResult::Done(x)) => assert!(x.is_empty()),
             Ok(RunResult::Host { .. }) => logger::panic!("init called into host"),
@@ -211,23 +433,78 @@ impl<B: Board> Scheduler<B> {
             Err(e) => Err(e).unwrap(),
         }
         self.call(inst, "main", &[]);
+        Ok(())
+    }
+
+    /// Returns whether an applet is signed with the applet key.
+    fn is_signed(&mut self, wasm: &[u8]) -> bool {
+        let Some(public) = self.applet_key else { return false };
+        match signature::verify(&mut self.board, wasm, &public) {
+            Ok(()) => true,
+            Err(error) => {
+                logger::debug!("{}", Display2Format(&error));
+                false
+            }
+        }
     }
 
     fn flush_events(&mut self) {
         while let Some(event) = self.board.try_event() {
-            self.applet.push(event);
+            self.push_event(event);
+        }
+    }
+
+    fn push_event(&mut self, event: board::Event) {
+        match event {
+            board::Event::Debug(board::debug::Event::Shutdown) => self.shutdown = true,
+            #[cfg(feature = "shell")]
+            board::Event::Debug(board::debug::Event::Shell) => shell::process(self),
+            #[cfg(not(feature = "shell"))]
+            board::Event::Debug(_) => trace!("Ignoring debug event."),
+            event => {
+                for applet in &mut self.applets {
+                    push_applet(&mut self.board, applet, event.clone());
+                }
+                push_applet(&mut self.board, &mut self.applet, event);
+            }
         }
     }
 
     /// Returns whether execution should resume.
-    fn process_event(&mut self) -> bool {
+    ///
+    /// If `any_event` is set, execution also resumes after a board event that didn't schedule a
+    /// callback.
+    fn process_event(&mut self, any_event: bool) -> bool {
         let event = loop {
+            if self.shutdown {
+                self.shutdown();
+            }
             match self.applet.pop() {
                 EventAction::Handle(event) => break event,
-                EventAction::Wait => self.applet.push(self.board.wait_event()),
+                EventAction::Wait => {
+                    if self.switch_applet() {
+                        return false;
+                    }
+                    // Applets are waiting, so we have time to log.
+                    self.logs.flush();
+                    let event = self.board.wait_event();
+                    self.push_event(event);
+                    if any_event {
+                        // Other pending board events may schedule a callback.
+                        self.flush_events();
+                        if self.applet.is_idle() {
+                            return true;
+                        }
+                    }
+                }
                 EventAction::Reply => return true,
             }
         };
+        #[cfg(feature = "metrics")]
+        {
+            self.metrics.record(&event);
+            self.handler_start = self.board.clock().uptime_us().ok();
+        }
         event::process(self, event);
         false
     }
@@ -236,7 +513,7 @@ impl<B: Board> Scheduler<B> {
         let call = match self.applet.store_mut().last_call() {
             Some(x) => x,
             None => {
-                self.process_event();
+                self.process_event(false);
                 return;
             }
         };
@@ -256,6 +533,52 @@ impl<B: Board> Scheduler<B> {
         Ok(())
     }
 
+    /// Flushes the storage and exits the platform.
+    fn shutdown(&mut self) -> ! {
+        debug!("Shutting down.");
+        self.logs.flush();
+        let success = self.board.with_store(|x| x.flush()).is_ok();
+        self.board.debug().exit(success)
+    }
+
+    /// Reports an applet trap to the board and stops the applet.
+    ///
+    /// The next applet runs instead. The platform stops if there are no other applets.
+    fn fault(&mut self, function: Option<&str>) {
+        self.logs.flush();
+        let fault = Fault { function, message: self.last_line.get() };
+        self.board.debug().on_applet_fault(&fault);
+        if self.applets.is_empty() {
+            logger::panic!("{}", Display2Format(&fault));
+        }
+        logger::error!("{}", Display2Format(&fault));
+        self.stop_applet();
+    }
+
+    /// Stops the running applet and runs the next applet.
+    ///
+    /// The timers of the stopped applet are freed. There must be another applet.
+    fn stop_applet(&mut self) {
+        let id = self.applet.store_mut().id();
+        for (timer, owner) in self.timers.iter_mut().enumerate() {
+            if matches!(owner, Some(x) if x.applet == id) {
+                let _ = self.board.timer().disarm(timer);
+                *owner = None;
+            }
+        }
+        // An applet without pending work resumes waiting for events.
+        let i = self.applets.iter().position(|x| x.is_runnable()).unwrap_or(0);
+        let mut applet = self.applets.remove(i).unwrap();
+        applet.resume();
+        self.applet = applet;
+        self.last_line.clear();
+        #[cfg(feature = "metrics")]
+        {
+            self.handler_start = None;
+        }
+        debug!("Stopped applet.");
+    }
+
     fn call(&mut self, inst: InstId, name: &'static str, args: &[u32]) {
         debug!("Schedule thread {}{:?}.", name, args);
         let args = args.iter().map(|&x| Val::I32(x)).collect();
@@ -268,19 +591,83 @@ impl<B: Board> Scheduler<B> {
             Ok(RunAnswer::Done(x)) => {
                 debug!("Thread is done.");
                 debug_assert!(x.is_empty());
+                #[cfg(feature = "metrics")]
+                if let Some(start) = self.handler_start.take() {
+                    let end = self.board.clock().uptime_us().unwrap_or(start);
+                    self.metrics.handler_time_us += end.saturating_sub(start);
+                }
                 self.applet.done();
             }
             Ok(RunAnswer::Host) => (),
-            Err(Error::Trap) => logger::panic!("Applet trapped in wasm."),
+            Err(Error::Trap) => self.fault(None),
             Err(e) => Err(e).unwrap(),
         }
     }
 }
 
+/// Pushes an event to an applet, through gesture recognition if configured.
+fn push_applet<B: Board>(board: &mut B, applet: &mut Applet<B>, event: board::Event) {
+    match event {
+        board::Event::Button(event) if applet.gestures.has_button(event.button) => {
+            event::button::push_button(board, applet, event)
+        }
+        board::Event::Timer(event) if applet.gestures.has_timer(event.timer) => {
+            event::button::push_timer(board, applet)
+        }
+        event => applet.push(event),
+    }
+}
+
+/// Creates an applet with the host functions linked.
+fn new_applet<B: Board>(host_funcs: &[Api<Id>]) -> Applet<B> {
+    let mut applet = Applet::default();
+    let store = applet.store_mut();
+    for f in host_funcs {
+        let d = f.descriptor();
+        store.link_func("env", d.name, d.params, d.results).unwrap();
+    }
+    applet
+}
+
+/// Returns the linear memory of a new applet.
+///
+/// The first applet uses a static buffer. Other applets allocate their memory on the heap, which is
+/// never freed.
+fn memory() -> &'static mut [u8] {
+    #[repr(align(16))]
+    struct Memory([u8; MEMORY_SIZE]);
+    static mut MEMORY: Memory = Memory([0; MEMORY_SIZE]);
+    static TAKEN: AtomicBool = AtomicBool::new(false);
+    if !TAKEN.swap(true, Ordering::Relaxed) {
+        // SAFETY: The static buffer is only handed out once, so it is never aliased.
+        return unsafe { &mut (*addr_of_mut!(MEMORY)).0 };
+    }
+    let layout = Layout::new::<Memory>();
+    // SAFETY: The layout has a non-zero size.
+    let ptr = unsafe { alloc::alloc::alloc_zeroed(layout) };
+    if ptr.is_null() {
+        alloc::alloc::handle_alloc_error(layout);
+    }
+    // SAFETY: The allocation is valid, zeroed, and leaked.
+    unsafe { core::slice::from_raw_parts_mut(ptr, MEMORY_SIZE) }
+}
+
 fn convert_results<T: Signature>(results: T::Results) -> Vec<Val> {
     <T::Results as ArrayU32>::into(&results).iter().map(|&x| Val::I32(x)).collect()
 }
 
+/// Returns the results of a failed host function.
+///
+/// The first signed result is -1, which is the error code of the applet API. Other results are
+/// zero.
+fn error_results<T: Signature>() -> Vec<Val> {
+    let mut results = vec![Val::I32(0); <T::Results as ArrayU32>::LENGTH];
+    if let Some(i) = T::ERROR {
+        results[i] = Val::I32(u32::MAX);
+    }
+    results
+}
+
 pub struct Trap;
 
 impl From<()> for Trap {
@@ -289,8 +676,48 @@ impl From<()> for Trap {
     }
 }
 
+/// How a host function fails.
+pub enum Failure {
+    /// The applet broke a pre-condition and is stopped.
+    Trap,
+
+    /// The call failed and the applet gets an error code (see [`error_results()`]).
+    Error(board::Error),
+}
+
+impl From<Trap> for Failure {
+    fn from(Trap: Trap) -> Self {
+        Failure::Trap
+    }
+}
+
+impl From<board::Error> for Failure {
+    fn from(error: board::Error) -> Self {
+        Failure::Error(error)
+    }
+}
+
+/// Returns the range of a slice of the applet linear memory.
+///
+/// This is the only place where pointers and lengths from the applet are converted to a range of
+/// the linear memory. The slice must lie entirely within the linear memory of `size` bytes: it must
+/// not wrap around the address space nor extend past the end. Otherwise the call fails with
+/// [`board::Error::User`] and the memory is not accessed.
+fn range(ptr: u32, len: u32, size: usize) -> Result<Range<usize>, board::Error> {
+    // The end is computed in the 32-bits address space of the applet.
+    let end = ptr.checked_add(len).ok_or(board::Error::User)? as usize;
+    if size < end {
+        return Err(board::Error::User);
+    }
+    Ok(ptr as usize .. end)
+}
+
 // TODO: This could be a slice-cell crate. And should probably already be exposed in the
 // interpreter?
+/// Accesses the applet linear memory during a host function call.
+///
+/// Host functions must only access the applet memory through this type, which checks bounds with
+/// [`range()`] and prevents aliasing mutable accesses.
 pub struct Memory<'a> {
     store: *mut Store<'static>,
     lifetime: PhantomData<&'a ()>,
@@ -343,29 +770,26 @@ impl<'a> Memory<'a> {
         Ok(())
     }
 
-    pub fn get(&self, ptr: u32, len: u32) -> Result<&[u8], Trap> {
-        let ptr = ptr as usize;
-        let len = len as usize;
-        let range = ptr .. ptr.checked_add(len).ok_or(Trap)?;
+    pub fn get(&self, ptr: u32, len: u32) -> Result<&[u8], Failure> {
+        let data = unsafe { self.data() };
+        let range = range(ptr, len, data.len())?;
         self.borrow(range.clone())?;
-        <[u8]>::get(unsafe { self.data() }, range).ok_or(Trap)
+        Ok(&data[range])
     }
 
-    pub fn get_array<const LEN: usize>(&self, ptr: u32) -> Result<&[u8; LEN], Trap> {
+    pub fn get_array<const LEN: usize>(&self, ptr: u32) -> Result<&[u8; LEN], Failure> {
         self.get(ptr, LEN as u32).map(|x| x.try_into().unwrap())
     }
 
-    pub fn get_mut(&self, ptr: u32, len: u32) -> Result<&mut [u8], Trap> {
-        let ptr = ptr as usize;
-        let len = len as usize;
-        let range = ptr .. ptr.checked_add(len).ok_or(Trap)?;
-        self.borrow_mut(range.clone())?;
+    pub fn get_mut(&self, ptr: u32, len: u32) -> Result<&mut [u8], Failure> {
         let data = unsafe { self.data() };
+        let range = range(ptr, len, data.len())?;
+        self.borrow_mut(range.clone())?;
         let data = unsafe { core::slice::from_raw_parts_mut(data.as_ptr() as *mut u8, data.len()) };
-        <[u8]>::get_mut(data, range).ok_or(Trap)
+        Ok(&mut data[range])
     }
 
-    pub fn get_array_mut<const LEN: usize>(&self, ptr: u32) -> Result<&mut [u8; LEN], Trap> {
+    pub fn get_array_mut<const LEN: usize>(&self, ptr: u32) -> Result<&mut [u8; LEN], Failure> {
         self.get_mut(ptr, LEN as u32).map(|x| x.try_into().unwrap())
     }
 
@@ -386,3 +810,231 @@ impl<'a> Memory<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use wasefire_board_api::button;
+
+    use super::*;
+    use crate::event::Handler;
+    use crate::testing::scheduler;
+
+    fn timer(timer: usize) -> board::Event {
+        board::timer::Event { timer }.into()
+    }
+
+    fn flood(policy: OverflowPolicy) -> Events {
+        let mut events = Events::new(3, policy);
+        for i in [0, 1, 1, 2, 3, 4] {
+            events.push(timer(i));
+        }
+        events
+    }
+
+    fn drain(mut events: Events) -> Vec<board::Event> {
+        core::iter::from_fn(|| events.pop()).collect()
+    }
+
+    #[test]
+    fn error_results_only_set_error() {
+        let error = [Val::I32(u32::MAX)];
+        assert!(error_results::<api::store::insert::Sig>() == error);
+        assert!(error_results::<api::led::count::Sig>() == [Val::I32(0)]);
+        #[cfg(feature = "multivalue")]
+        assert!(error_results::<api::store::find::Sig>() == [Val::I32(0), Val::I32(u32::MAX)]);
+    }
+
+    #[test]
+    fn events_drop_oldest() {
+        let events = flood(OverflowPolicy::DropOldest);
+        assert_eq!(events.depth(), 3);
+        assert_eq!(events.dropped(), 3);
+        assert_eq!(drain(events), [timer(2), timer(3), timer(4)]);
+    }
+
+    #[test]
+    fn events_drop_oldest_priority() {
+        let usb = board::Event::from(board::usb::hid::Event::Read);
+        let mut events = Events::new(3, OverflowPolicy::DropOldest);
+        let high = || usb.clone();
+        for event in [high(), timer(0), timer(1), high(), timer(2), high(), timer(3)] {
+            events.push(event);
+        }
+        assert_eq!(events.dropped(), 4);
+        assert_eq!(drain(events), [high(), high(), high()]);
+    }
+
+    #[test]
+    fn events_drop_newest() {
+        let events = flood(OverflowPolicy::DropNewest);
+        assert_eq!(events.dropped(), 3);
+        assert_eq!(drain(events), [timer(0), timer(1), timer(1)]);
+    }
+
+    #[test]
+    fn events_drop_newest_priority() {
+        let usb = || board::Event::from(board::usb::hid::Event::Read);
+        let mut events = Events::new(3, OverflowPolicy::DropNewest);
+        for event in [timer(0), timer(1), timer(2), usb(), usb()] {
+            events.push(event);
+        }
+        assert_eq!(events.dropped(), 2);
+        assert_eq!(drain(events), [usb(), usb(), timer(0)]);
+        let mut events = Events::new(3, OverflowPolicy::Coalesce);
+        for event in [timer(0), timer(1), timer(2), usb(), usb()] {
+            events.push(event);
+        }
+        assert_eq!(events.dropped(), 1);
+        assert_eq!(drain(events), [usb(), timer(0), timer(1)]);
+    }
+
+    #[test]
+    fn events_coalesce() {
+        let events = flood(OverflowPolicy::Coalesce);
+        assert_eq!(events.dropped(), 2);
+        assert_eq!(drain(events), [timer(0), timer(1), timer(2)]);
+    }
+
+    #[test]
+    fn events_priority() {
+        let button = |button| board::Event::from(button::Event { button, pressed: true });
+        let usb = board::Event::from(board::usb::hid::Event::Read);
+        let mut events = Events::default();
+        for event in [timer(0), button(0), timer(1), usb.clone(), button(1)] {
+            events.push(event);
+        }
+        assert_eq!(drain(events), [usb, button(0), button(1), timer(0), timer(1)]);
+    }
+
+    #[test]
+    fn range_within_memory() {
+        assert_eq!(range(0, 0, 0).ok(), Some(0 .. 0));
+        assert_eq!(range(0, 16, 16).ok(), Some(0 .. 16));
+        assert_eq!(range(4, 8, 16).ok(), Some(4 .. 12));
+        assert_eq!(range(16, 0, 16).ok(), Some(16 .. 16));
+    }
+
+    fn is_user(result: Result<Range<usize>, board::Error>) -> bool {
+        matches!(result, Err(board::Error::User))
+    }
+
+    #[test]
+    fn range_out_of_bounds() {
+        assert!(is_user(range(0, 17, 16)));
+        assert!(is_user(range(12, 8, 16)));
+        assert!(is_user(range(17, 0, 16)));
+        assert!(is_user(range(u32::MAX, 1, 0x10000)));
+    }
+
+    #[test]
+    fn range_wrapping() {
+        // The end wraps around 32 bits, so only the wrapping check rejects those with a memory
+        // covering the whole host address space.
+        assert!(is_user(range(u32::MAX, 2, usize::MAX)));
+        assert!(is_user(range(0xfff0, u32::MAX - 0xfff, usize::MAX)));
+        assert!(is_user(range(1, u32::MAX, usize::MAX)));
+        assert_eq!(range(0, u32::MAX, usize::MAX).ok(), Some(0 .. u32::MAX as usize));
+    }
+
+    /// Module exporting an empty `main` function.
+    const MAIN: &[u8] = &[
+        0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // header
+        0x01, 0x04, 0x01, 0x60, 0x00, 0x00, // type section
+        0x03, 0x02, 0x01, 0x00, // function section
+        0x07, 0x08, 0x01, 0x04, b'm', b'a', b'i', b'n', 0x00, 0x00, // export section
+        0x0a, 0x04, 0x01, 0x02, 0x00, 0x0b, // code section
+    ];
+
+    #[test]
+    fn events_switch_to_registered_applet() {
+        let mut scheduler = scheduler(false);
+        scheduler.load(MAIN, || vec![0; MEMORY_SIZE].leak()).unwrap();
+        let first = scheduler.applet.store_mut().id();
+        scheduler.add_applet();
+        scheduler.load(MAIN, || vec![0; MEMORY_SIZE].leak()).unwrap();
+        assert_ne!(scheduler.applet.store_mut().id(), first);
+        // Only the first applet listens to the button.
+        let module = Module::new(MAIN).unwrap();
+        let applet = &mut scheduler.applets[0];
+        let inst = applet.store_mut().instantiate(module, &mut []).unwrap();
+        let key = Key::Button(event::button::Key { button: 0 });
+        applet.enable(Handler { key, inst, func: 0, data: 0 }).ok().unwrap();
+        scheduler.push_event(button::Event { button: 0, pressed: true }.into());
+        assert_eq!(scheduler.applets[0].len(), 1);
+        assert_eq!(scheduler.applet.len(), 0);
+        // The second applet returned from main and has nothing to do.
+        assert!(scheduler.process_event(false));
+        assert!(!scheduler.process_event(false));
+        assert_eq!(scheduler.applet.store_mut().id(), first);
+        assert_eq!(scheduler.applet.len(), 1);
+        assert!(!scheduler.applets[0].is_runnable());
+    }
+
+    #[test]
+    fn wait_for_any_event() {
+        let mut scheduler = scheduler(false);
+        scheduler.load(MAIN, || vec![0; MEMORY_SIZE].leak()).unwrap();
+        assert!(scheduler.process_event(true));
+        // The applet doesn't listen to the button, but resumes after the event.
+        scheduler.board.events.push_back(button::Event { button: 0, pressed: true }.into());
+        assert!(scheduler.process_event(true));
+        assert!(scheduler.board.events.is_empty());
+    }
+
+    /// Returns the [`MAIN`] module with a manifest.
+    fn with_manifest(manifest: &[u8]) -> &'static [u8] {
+        let mut wasm = MAIN[.. 8].to_vec();
+        wasm.extend_from_slice(&[0, (1 + manifest::SECTION.len() + manifest.len()) as u8]);
+        wasm.push(manifest::SECTION.len() as u8);
+        wasm.extend_from_slice(manifest::SECTION.as_bytes());
+        wasm.extend_from_slice(manifest);
+        wasm.extend_from_slice(&MAIN[8 ..]);
+        wasm.leak()
+    }
+
+    #[test]
+    fn unsigned_applet_has_no_permissions() {
+        let wasm = with_manifest(b"name=x\npermissions=factory-reset");
+        let mut scheduler = scheduler(false);
+        scheduler.load(wasm, || vec![0; MEMORY_SIZE].leak()).unwrap();
+        assert_eq!(scheduler.applet.permissions, manifest::Permissions::default());
+        scheduler.add_applet();
+        scheduler.applet_key = Some([0; 32]);
+        scheduler.load(wasm, || vec![0; MEMORY_SIZE].leak()).unwrap();
+        assert_eq!(scheduler.applet.permissions, manifest::Permissions::default());
+    }
+
+    #[test]
+    fn failed_load_is_not_running() {
+        let invalid = with_manifest(b"name");
+        let mut scheduler = scheduler(false);
+        let mut count = 0;
+        let memory = || {
+            count += 1;
+            vec![0; MEMORY_SIZE].leak()
+        };
+        scheduler.load_many(&[invalid, MAIN, invalid, MAIN, invalid], memory);
+        assert_eq!(count, 2);
+        assert_eq!(scheduler.applets.len(), 1);
+        let first = scheduler.applets[0].store_mut().id();
+        assert_ne!(scheduler.applet.store_mut().id(), first);
+        // The last applet ran main and isn't empty.
+        assert!(scheduler.process_event(false));
+    }
+
+    #[test]
+    fn fault_stops_only_faulting_applet() {
+        let mut scheduler = scheduler(false);
+        scheduler.load(MAIN, || vec![0; MEMORY_SIZE].leak()).unwrap();
+        let first = scheduler.applet.store_mut().id();
+        scheduler.add_applet();
+        scheduler.load(MAIN, || vec![0; MEMORY_SIZE].leak()).unwrap();
+        let second = scheduler.applet.store_mut().id();
+        scheduler.timers = vec![Some(Timer { applet: first }), Some(Timer { applet: second })];
+        scheduler.fault(None);
+        assert_eq!(scheduler.applet.store_mut().id(), first);
+        assert!(scheduler.applets.is_empty());
+        assert!(scheduler.timers[0].is_some());
+        assert!(scheduler.timers[1].is_none());
+    }
+}
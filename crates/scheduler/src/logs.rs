@@ -0,0 +1,177 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Buffer between the applet log syscalls and the platform logger.
+//!
+//! Applets may log faster than the platform can transmit (e.g. over RTT). Messages are buffered
+//! and logged when the scheduler has time. Messages that don't fit are dropped and counted.
+
+use wasefire_applet_api::debug::Level;
+use wasefire_logger as logger;
+
+/// Capacity in bytes of the applet log buffer.
+///
+/// This doesn't allocate. Each message takes its length plus [`HEADER`] bytes.
+const CAPACITY: usize = 1024;
+
+/// Size in bytes of the message header (level and little-endian u16 length).
+const HEADER: usize = 3;
+
+pub struct Logs {
+    buffer: [u8; CAPACITY],
+    /// Index of the first byte of the oldest message.
+    start: usize,
+    /// Number of bytes used by messages.
+    len: usize,
+    /// Number of messages dropped since the last report.
+    dropped: usize,
+}
+
+impl Default for Logs {
+    fn default() -> Self {
+        Logs { buffer: [0; CAPACITY], start: 0, len: 0, dropped: 0 }
+    }
+}
+
+impl Logs {
+    /// Buffers a message, or drops it if the buffer is full.
+    ///
+    /// Messages without level are printed. Messages too long for the buffer are truncated.
+    pub fn push(&mut self, level: Option<Level>, message: &str) {
+        let mut len = core::cmp::min(message.len(), CAPACITY - HEADER);
+        while !message.is_char_boundary(len) {
+            len -= 1;
+        }
+        if CAPACITY - self.len < HEADER + len {
+            self.dropped += 1;
+            return;
+        }
+        let tag = level.map_or(0, |x| x as u8 + 1);
+        self.write(&[tag]);
+        self.write(&(len as u16).to_le_bytes());
+        self.write(&message.as_bytes()[.. len]);
+    }
+
+    /// Removes the oldest message.
+    pub fn pop(&mut self) -> Option<(Option<Level>, &str)> {
+        if self.len == 0 {
+            return None;
+        }
+        let mut header = [0; HEADER];
+        self.read(&mut header);
+        let level = match header[0] {
+            0 => None,
+            x => Some(Level::try_from(x as u32 - 1).unwrap()),
+        };
+        let len = u16::from_le_bytes([header[1], header[2]]) as usize;
+        if CAPACITY < self.start + len {
+            // Make the message contiguous.
+            self.buffer.rotate_left(self.start);
+            self.start = 0;
+        }
+        let start = self.start;
+        self.start = (self.start + len) % CAPACITY;
+        self.len -= len;
+        Some((level, core::str::from_utf8(&self.buffer[start .. start + len]).unwrap()))
+    }
+
+    /// Logs the oldest message, and reports dropped messages once empty.
+    ///
+    /// Returns whether a message was logged.
+    pub fn flush_one(&mut self) -> bool {
+        match self.pop() {
+            Some((None, message)) => logger::println!("{}", message),
+            Some((Some(Level::Error), message)) => logger::error!("{}", message),
+            Some((Some(Level::Warn), message)) => logger::warn!("{}", message),
+            Some((Some(Level::Info), message)) => logger::info!("{}", message),
+            Some((Some(Level::Debug), message)) => logger::debug!("{}", message),
+            Some((Some(Level::Trace), message)) => logger::trace!("{}", message),
+            None => {
+                if 0 < self.dropped {
+                    logger::warn!("Dropped {} applet log messages.", self.dropped);
+                    self.dropped = 0;
+                }
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Logs all messages.
+    pub fn flush(&mut self) {
+        while self.flush_one() {}
+    }
+
+    fn write(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.buffer[(self.start + self.len) % CAPACITY] = byte;
+            self.len += 1;
+        }
+    }
+
+    fn read(&mut self, data: &mut [u8]) {
+        for byte in data {
+            *byte = self.buffer[self.start];
+            self.start = (self.start + 1) % CAPACITY;
+            self.len -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_pop() {
+        let mut logs = Logs::default();
+        logs.push(None, "hello");
+        logs.push(Some(Level::Warn), "world");
+        assert_eq!(logs.pop(), Some((None, "hello")));
+        assert_eq!(logs.pop(), Some((Some(Level::Warn), "world")));
+        assert_eq!(logs.pop(), None);
+    }
+
+    #[test]
+    fn wrap_around() {
+        let mut logs = Logs::default();
+        let message = "a".repeat(600);
+        logs.push(None, &message);
+        assert_eq!(logs.pop(), Some((None, message.as_str())));
+        logs.push(Some(Level::Info), &message);
+        assert_eq!(logs.pop(), Some((Some(Level::Info), message.as_str())));
+    }
+
+    #[test]
+    fn drop_when_full() {
+        let mut logs = Logs::default();
+        let message = "a".repeat(600);
+        logs.push(None, &message);
+        logs.push(None, &message);
+        assert_eq!(logs.dropped, 1);
+        assert_eq!(logs.pop(), Some((None, message.as_str())));
+        assert_eq!(logs.pop(), None);
+        assert!(!logs.flush_one());
+        assert_eq!(logs.dropped, 0);
+    }
+
+    #[test]
+    fn truncate_on_char_boundary() {
+        let mut logs = Logs::default();
+        let message = "é".repeat(CAPACITY);
+        logs.push(None, &message);
+        let (_, popped) = logs.pop().unwrap();
+        assert_eq!(popped.len(), CAPACITY - HEADER - 1);
+    }
+}
@@ -0,0 +1,270 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Applet manifest.
+//!
+//! The manifest is stored in the [`SECTION`] custom section of the applet module. Each line is a
+//! `key=value` pair with the following keys:
+//! - `name`: The name of the applet.
+//! - `version`: The version of the applet (semantic versioning).
+//! - `capabilities`: The comma-separated board capabilities required by the applet.
+//! - `permissions`: The comma-separated [permissions](Permissions) granted to the applet.
+//!
+//! Applets without manifest are assumed to require nothing and are granted no permissions. Applets
+//! without valid signature are granted no permissions either.
+
+use core::fmt::Display;
+
+use wasefire_board_api::Api as Board;
+use wasefire_logger as logger;
+
+use crate::capability;
+
+/// Name of the custom section holding the manifest.
+pub const SECTION: &str = "wasefire-manifest";
+
+/// Applet manifest.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Manifest<'a> {
+    /// The name of the applet.
+    pub name: &'a str,
+
+    /// The version of the applet.
+    pub version: &'a str,
+
+    /// The comma-separated board capabilities required by the applet.
+    capabilities: &'a str,
+
+    /// The comma-separated permissions granted to the applet.
+    permissions: &'a str,
+}
+
+impl<'a> Manifest<'a> {
+    /// Returns the manifest of an applet module, if any.
+    pub fn find(wasm: &'a [u8]) -> Result<Option<Self>, &'static str> {
+        match custom_section(wasm, SECTION)? {
+            Some(content) => Manifest::parse(content).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Parses the content of a manifest.
+    pub fn parse(content: &'a [u8]) -> Result<Self, &'static str> {
+        let content = core::str::from_utf8(content).map_err(|_| "manifest is not UTF-8")?;
+        let mut manifest = Manifest::default();
+        for line in content.lines() {
+            let (key, value) = line.split_once('=').ok_or("manifest line is not key=value")?;
+            match key {
+                "name" => manifest.name = value,
+                "version" => manifest.version = value,
+                "capabilities" => manifest.capabilities = value,
+                "permissions" => manifest.permissions = value,
+                _ => return Err("unknown manifest key"),
+            }
+        }
+        Ok(manifest)
+    }
+
+    /// Returns the board capabilities required by the applet.
+    pub fn capabilities(&self) -> impl Iterator<Item = &'a str> {
+        self.capabilities.split(',').filter(|x| !x.is_empty())
+    }
+
+    /// Returns the permissions granted to the applet.
+    pub fn permissions(&self) -> impl Iterator<Item = &'a str> {
+        self.permissions.split(',').filter(|x| !x.is_empty())
+    }
+}
+
+/// Permissions granted to an applet.
+///
+/// The manifest is covered by the applet [signature](crate::signature). Permissions are only granted
+/// to applets signed with the applet key of the runner, such that applets can't grant themselves
+/// permissions.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Permissions {
+    /// Whether the applet may wipe the device with `platform::factory_reset()`.
+    ///
+    /// This is the `factory-reset` permission.
+    pub factory_reset: bool,
+
+    /// Whether the applet may update the firmware with `platform::update`.
+    ///
+    /// This is the `update` permission.
+    pub update: bool,
+}
+
+/// Reasons an applet can't run on a board.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Error<'a> {
+    /// The manifest is invalid.
+    Invalid(&'static str),
+
+    /// The applet requires a capability not supported by the board.
+    Unsupported { applet: &'a str, capability: &'a str },
+
+    /// The applet requires an unknown capability.
+    Unknown { applet: &'a str, capability: &'a str },
+
+    /// The applet is granted an unknown permission.
+    UnknownPermission { applet: &'a str, permission: &'a str },
+}
+
+impl<'a> Display for Error<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::Invalid(error) => write!(f, "Invalid applet manifest: {error}"),
+            Error::Unsupported { applet, capability } => {
+                write!(f, "Applet {applet} requires unsupported capability {capability}")
+            }
+            Error::Unknown { applet, capability } => {
+                write!(f, "Applet {applet} requires unknown capability {capability}")
+            }
+            Error::UnknownPermission { applet, permission } => {
+                write!(f, "Applet {applet} is granted unknown permission {permission}")
+            }
+        }
+    }
+}
+
+/// Checks that the board supports the capabilities required by an applet.
+///
+/// Returns the permissions granted to the applet.
+pub fn check<'a, B: Board>(board: &mut B, wasm: &'a [u8]) -> Result<Permissions, Error<'a>> {
+    let manifest = match Manifest::find(wasm).map_err(Error::Invalid)? {
+        Some(x) => x,
+        None => return Ok(Permissions::default()),
+    };
+    logger::debug!("Applet {} version {}.", manifest.name, manifest.version);
+    for capability in manifest.capabilities() {
+        let applet = manifest.name;
+        match capability::parse(capability) {
+            Some(x) if capability::is_supported(board, x) => (),
+            Some(_) => return Err(Error::Unsupported { applet, capability }),
+            None => return Err(Error::Unknown { applet, capability }),
+        }
+    }
+    let mut permissions = Permissions::default();
+    for permission in manifest.permissions() {
+        match permission {
+            "factory-reset" => permissions.factory_reset = true,
+            "update" => permissions.update = true,
+            _ => return Err(Error::UnknownPermission { applet: manifest.name, permission }),
+        }
+    }
+    Ok(permissions)
+}
+
+/// Returns the content of a custom section of a module, if present.
+fn custom_section<'a>(wasm: &'a [u8], name: &str) -> Result<Option<&'a [u8]>, &'static str> {
+    // Skip the magic and version.
+    let mut sections = wasm.get(8 ..).ok_or("invalid module")?;
+    while let Some((&id, rest)) = sections.split_first() {
+        let (size, rest) = read_u32(rest)?;
+        let content = rest.get(.. size).ok_or("invalid module")?;
+        sections = &rest[size ..];
+        if id != 0 {
+            continue;
+        }
+        let (len, content) = read_u32(content)?;
+        if content.get(.. len).ok_or("invalid module")? == name.as_bytes() {
+            return Ok(Some(&content[len ..]));
+        }
+    }
+    Ok(None)
+}
+
+/// Reads an unsigned LEB128 number.
+pub(crate) fn read_u32(data: &[u8]) -> Result<(usize, &[u8]), &'static str> {
+    let mut result = 0;
+    for (i, &byte) in data.iter().enumerate().take(5) {
+        result |= ((byte & 0x7f) as usize) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok((result, &data[i + 1 ..]));
+        }
+    }
+    Err("invalid module")
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    fn module(manifest: &[u8]) -> Vec<u8> {
+        let mut wasm = b"\0asm\x01\0\0\0".to_vec();
+        let mut section = vec![SECTION.len() as u8];
+        section.extend_from_slice(SECTION.as_bytes());
+        section.extend_from_slice(manifest);
+        wasm.push(0);
+        wasm.push(section.len() as u8);
+        wasm.extend_from_slice(&section);
+        // Type section with no types after the manifest.
+        wasm.extend_from_slice(&[0x01, 0x01, 0x00]);
+        wasm
+    }
+
+    #[test]
+    fn find_manifest() {
+        let wasm = module(b"name=hello\nversion=1.2.3\ncapabilities=led,rng\n");
+        let manifest = Manifest::find(&wasm).unwrap().unwrap();
+        assert_eq!(manifest.name, "hello");
+        assert_eq!(manifest.version, "1.2.3");
+        assert_eq!(manifest.capabilities().collect::<Vec<_>>(), ["led", "rng"]);
+    }
+
+    #[test]
+    fn find_no_manifest() {
+        assert_eq!(Manifest::find(b"\0asm\x01\0\0\0\x01\x01\x00"), Ok(None));
+        assert!(Manifest::find(b"\0asm\x01\0\0\0\x01\x05\x00").is_err());
+    }
+
+    #[test]
+    fn check_capabilities() {
+        let mut board = crate::testing::scheduler(false).board;
+        let wasm = module(b"name=x\ncapabilities=led");
+        assert_eq!(check(&mut board, &wasm), Ok(Permissions::default()));
+        let wasm = module(b"name=x\ncapabilities=led,rng");
+        let error = Error::Unsupported { applet: "x", capability: "rng" };
+        assert_eq!(check(&mut board, &wasm), Err(error));
+        let wasm = module(b"name=x\ncapabilities=teleport");
+        let error = Error::Unknown { applet: "x", capability: "teleport" };
+        assert_eq!(check(&mut board, &wasm), Err(error));
+        assert!(matches!(check(&mut board, &module(b"name")), Err(Error::Invalid(_))));
+    }
+
+    #[test]
+    fn check_permissions() {
+        let mut board = crate::testing::scheduler(false).board;
+        assert_eq!(check(&mut board, &module(b"name=x")), Ok(Permissions::default()));
+        let wasm = module(b"name=x\npermissions=factory-reset");
+        let permissions = Permissions { factory_reset: true, ..Permissions::default() };
+        assert_eq!(check(&mut board, &wasm), Ok(permissions));
+        let wasm = module(b"name=x\npermissions=update,factory-reset");
+        let permissions = Permissions { factory_reset: true, update: true };
+        assert_eq!(check(&mut board, &wasm), Ok(permissions));
+        let wasm = module(b"name=x\npermissions=root");
+        let error = Error::UnknownPermission { applet: "x", permission: "root" };
+        assert_eq!(check(&mut board, &wasm), Err(error));
+    }
+
+    #[test]
+    fn parse_invalid_manifest() {
+        assert!(Manifest::parse(b"name").is_err());
+        assert!(Manifest::parse(b"author=me").is_err());
+        assert_eq!(Manifest::parse(b"capabilities=").unwrap().capabilities().count(), 0);
+    }
+}
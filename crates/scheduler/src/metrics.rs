@@ -0,0 +1,114 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use core::fmt::{Display, Formatter, Result};
+
+use wasefire_board_api::Event;
+
+/// Statistics about events dispatched to applet handlers.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SchedulerMetrics {
+    /// Number of dispatched button events.
+    pub button: usize,
+
+    /// Number of dispatched input capture events.
+    pub capture: usize,
+
+    /// Number of dispatched encoder events.
+    pub encoder: usize,
+
+    /// Number of dispatched button gesture events.
+    pub gesture: usize,
+
+    /// Number of dispatched stepper motor events.
+    pub stepper: usize,
+
+    /// Number of dispatched timer events.
+    pub timer: usize,
+
+    /// Number of dispatched UART events.
+    pub uart: usize,
+
+    /// Number of dispatched USB events.
+    pub usb: usize,
+
+    /// Total time in microseconds spent in event handlers.
+    ///
+    /// This includes the time spent in host functions called by the handlers. It stays zero if the
+    /// board doesn't support `clock::uptime_us()`.
+    pub handler_time_us: u64,
+}
+
+impl SchedulerMetrics {
+    /// Returns the total number of dispatched events.
+    pub fn events(&self) -> usize {
+        let Self { button, capture, encoder, gesture, stepper, timer, uart, usb, .. } = *self;
+        button + capture + encoder + gesture + stepper + timer + uart + usb
+    }
+
+    pub(crate) fn record(&mut self, event: &Event) {
+        let count = match event {
+            Event::Button(_) => &mut self.button,
+            Event::Capture(_) => &mut self.capture,
+            Event::Debug(_) => return,
+            Event::Encoder(_) => &mut self.encoder,
+            Event::Gesture(_) => &mut self.gesture,
+            Event::Stepper(_) => &mut self.stepper,
+            Event::Timer(_) => &mut self.timer,
+            Event::Uart(_) => &mut self.uart,
+            Event::Usb(_) => &mut self.usb,
+        };
+        *count += 1;
+    }
+}
+
+impl Display for SchedulerMetrics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{} events in {}us", self.events(), self.handler_time_us)?;
+        let counts = [
+            ("button", self.button),
+            ("capture", self.capture),
+            ("encoder", self.encoder),
+            ("gesture", self.gesture),
+            ("stepper", self.stepper),
+            ("timer", self.timer),
+            ("uart", self.uart),
+            ("usb", self.usb),
+        ];
+        for (name, count) in counts.into_iter().filter(|(_, x)| 0 < *x) {
+            write!(f, ", {name}: {count}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use wasefire_board_api::{button, timer};
+
+    use super::*;
+
+    #[test]
+    fn record_and_display() {
+        let mut metrics = SchedulerMetrics::default();
+        metrics.record(&button::Event { button: 0, pressed: true }.into());
+        metrics.record(&timer::Event { timer: 1 }.into());
+        metrics.record(&timer::Event { timer: 1 }.into());
+        metrics.handler_time_us = 42;
+        assert_eq!(metrics.events(), 3);
+        assert_eq!(metrics.to_string(), "3 events in 42us, button: 1, timer: 2");
+    }
+}
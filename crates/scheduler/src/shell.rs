@@ -0,0 +1,244 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Debugging shell.
+//!
+//! The shell runs in the scheduler independently of the applet. Lines are read from the debugging
+//! interface of the board when it triggers a shell event. They are split on whitespace, executed,
+//! and their output is written back to the debugging interface.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use wasefire_board_api::debug::{Api as _, Level};
+use wasefire_board_api::led::Api as _;
+use wasefire_board_api::store::{self, Api as _};
+use wasefire_board_api::Api as Board;
+use wasefire_logger as logger;
+
+use crate::Scheduler;
+
+const HELP: &str = "\
+help                    Prints this help.
+applets                 Lists the applets.
+metrics                 Shows scheduler metrics.
+log <level>             Sets the log level (error, warn, info, debug, or trace).
+leds                    Shows the LED states.
+led <index> [on|off]    Toggles or sets a LED.
+store get <key>         Reads a storage entry in hexadecimal.
+store set <key> <hex>   Writes a storage entry from hexadecimal.
+store remove <key>      Removes a storage entry.
+reboot                  Reboots the platform.
+";
+
+pub fn process<B: Board>(scheduler: &mut Scheduler<B>) {
+    let mut line = [0; 128];
+    loop {
+        let len = match scheduler.board.debug().shell_read(&mut line) {
+            Ok(0) => break,
+            Ok(x) => x,
+            Err(_) => {
+                logger::warn!("Failed to read shell line.");
+                break;
+            }
+        };
+        let mut output = String::new();
+        match core::str::from_utf8(&line[.. len]) {
+            Ok(line) => execute(scheduler, line, &mut output),
+            Err(_) => output.push_str("error: invalid UTF-8\n"),
+        }
+        if scheduler.board.debug().shell_write(output.as_bytes()).is_err() {
+            logger::warn!("Failed to write shell output.");
+        }
+    }
+}
+
+fn execute<B: Board>(scheduler: &mut Scheduler<B>, line: &str, output: &mut String) {
+    let args: Vec<&str> = line.split_whitespace().collect();
+    let result = match args[..] {
+        [] => Ok(()),
+        ["help"] => {
+            output.push_str(HELP);
+            Ok(())
+        }
+        ["applets"] => applets(scheduler, output),
+        ["metrics"] => metrics(scheduler, output),
+        ["log", level] => set_log_level(scheduler, level),
+        ["leds"] => leds(scheduler, output),
+        ["led", led] => set_led(scheduler, led, None),
+        ["led", led, "on"] => set_led(scheduler, led, Some(true)),
+        ["led", led, "off"] => set_led(scheduler, led, Some(false)),
+        ["store", "get", key] => store_get(scheduler, key, output),
+        ["store", "set", key, value] => store_set(scheduler, key, value),
+        ["store", "remove", key] => store_remove(scheduler, key),
+        ["reboot"] => crate::call::platform::reset(scheduler).map_err(|_| "reboot failed"),
+        _ => Err("unknown command (try help)"),
+    };
+    if let Err(error) = result {
+        writeln!(output, "error: {error}").unwrap();
+    }
+}
+
+type Result = core::result::Result<(), &'static str>;
+
+fn applets<B: Board>(scheduler: &mut Scheduler<B>, output: &mut String) -> Result {
+    // The scheduler only supports a single applet for now.
+    let state = if scheduler.applet.store_mut().last_call().is_some() { "host" } else { "wasm" };
+    writeln!(output, "0: running ({state})").unwrap();
+    Ok(())
+}
+
+fn metrics<B: Board>(scheduler: &mut Scheduler<B>, output: &mut String) -> Result {
+    let armed = scheduler.timers.iter().filter(|x| x.is_some()).count();
+    writeln!(output, "pending events: {}", scheduler.applet.len()).unwrap();
+    writeln!(output, "armed timers: {armed}/{}", scheduler.timers.len()).unwrap();
+    if let Ok(stats) = scheduler.board.debug().queue_stats() {
+        writeln!(output, "queued events: {} ({} dropped)", stats.depth, stats.dropped).unwrap();
+    }
+    let (capacity, lifetime) = scheduler.board.with_store(|x| (x.capacity(), x.lifetime()));
+    if let Ok(capacity) = capacity {
+        writeln!(output, "store capacity: {}/{}", capacity.used(), capacity.total()).unwrap();
+    }
+    if let Ok(lifetime) = lifetime {
+        writeln!(output, "store lifetime: {}/{}", lifetime.used(), lifetime.total()).unwrap();
+    }
+    if let Ok(wear) = store::Platform::new(&mut scheduler.board).wear_stats() {
+        let erases = wear.erase_count_per_page.iter().max().unwrap_or(&0);
+        writeln!(output, "store erases: {erases}/{}", wear.max_page_erases).unwrap();
+    }
+    Ok(())
+}
+
+fn set_log_level<B: Board>(scheduler: &mut Scheduler<B>, level: &str) -> Result {
+    let level = match level {
+        "error" => Level::Error,
+        "warn" => Level::Warn,
+        "info" => Level::Info,
+        "debug" => Level::Debug,
+        "trace" => Level::Trace,
+        _ => return Err("invalid level"),
+    };
+    scheduler.board.debug().set_log_level(level).map_err(|_| "failed to set log level")
+}
+
+fn leds<B: Board>(scheduler: &mut Scheduler<B>, output: &mut String) -> Result {
+    let count = scheduler.board.led().count();
+    for led in 0 .. count {
+        let on = scheduler.board.led().get(led).map_err(|_| "failed to read LED")?;
+        writeln!(output, "led {led}: {}", if on { "on" } else { "off" }).unwrap();
+    }
+    Ok(())
+}
+
+fn set_led<B: Board>(scheduler: &mut Scheduler<B>, led: &str, on: Option<bool>) -> Result {
+    let led = led.parse().map_err(|_| "invalid LED index")?;
+    let mut leds = scheduler.board.led();
+    let on = match on {
+        Some(x) => x,
+        None => !leds.get(led).map_err(|_| "failed to read LED")?,
+    };
+    leds.set(led, on).map_err(|_| "failed to write LED")
+}
+
+fn store_get<B: Board>(scheduler: &mut Scheduler<B>, key: &str, output: &mut String) -> Result {
+    let key = key.parse().map_err(|_| "invalid key")?;
+    match scheduler.board.with_store(|x| x.find(key)).map_err(|_| "failed to read store")? {
+        None => output.push_str("not found\n"),
+        Some(value) => {
+            value.iter().for_each(|x| write!(output, "{x:02x}").unwrap());
+            output.push('\n');
+        }
+    }
+    Ok(())
+}
+
+fn store_set<B: Board>(scheduler: &mut Scheduler<B>, key: &str, value: &str) -> Result {
+    let key = key.parse().map_err(|_| "invalid key")?;
+    if value.len() % 2 != 0 {
+        return Err("invalid hexadecimal");
+    }
+    let value = (0 .. value.len())
+        .step_by(2)
+        .map(|i| value.get(i .. i + 2).and_then(|x| u8::from_str_radix(x, 16).ok()))
+        .collect::<Option<Vec<u8>>>()
+        .ok_or("invalid hexadecimal")?;
+    scheduler.board.with_store(|x| x.insert(key, &value)).map_err(|_| "failed to write store")
+}
+
+fn store_remove<B: Board>(scheduler: &mut Scheduler<B>, key: &str) -> Result {
+    let key = key.parse().map_err(|_| "invalid key")?;
+    scheduler.board.with_store(|x| x.remove(key)).map_err(|_| "failed to write store")
+}
+
+#[cfg(test)]
+mod tests {
+    use wasefire_board_api::debug::QueueStats;
+
+    use super::*;
+    use crate::testing::{scheduler, Test};
+
+    fn run(scheduler: &mut Scheduler<Test>, line: &str) -> String {
+        let mut output = String::new();
+        execute(scheduler, line, &mut output);
+        output
+    }
+
+    #[test]
+    fn leds() {
+        let mut scheduler = scheduler(false);
+        assert_eq!(run(&mut scheduler, "leds"), "led 0: off\nled 1: on\n");
+        assert_eq!(run(&mut scheduler, "led 0"), "");
+        assert_eq!(run(&mut scheduler, "led 1 off"), "");
+        assert_eq!(run(&mut scheduler, "  leds  "), "led 0: on\nled 1: off\n");
+        assert_eq!(run(&mut scheduler, "led 2"), "error: failed to read LED\n");
+    }
+
+    #[test]
+    fn log() {
+        let mut scheduler = scheduler(false);
+        assert_eq!(run(&mut scheduler, "log debug"), "");
+        assert_eq!(scheduler.board.debug.log_level, Some(Level::Debug));
+        assert_eq!(run(&mut scheduler, "log verbose"), "error: invalid level\n");
+        assert_eq!(scheduler.board.debug.log_level, Some(Level::Debug));
+    }
+
+    #[test]
+    fn metrics() {
+        let mut scheduler = scheduler(false);
+        assert!(run(&mut scheduler, "metrics").ends_with("store erases: 0/10000\n"));
+        scheduler.board.with_store(|x| x.wipe()).unwrap();
+        assert!(run(&mut scheduler, "metrics").ends_with("store erases: 1/10000\n"));
+        scheduler.board.debug.queue_stats = QueueStats { depth: 3, dropped: 2 };
+        assert!(run(&mut scheduler, "metrics").contains("queued events: 3 (2 dropped)\n"));
+    }
+
+    #[test]
+    fn store() {
+        let mut scheduler = scheduler(false);
+        assert_eq!(run(&mut scheduler, "store get 3"), "not found\n");
+        assert_eq!(run(&mut scheduler, "store set 3 00c0ffee"), "");
+        assert_eq!(run(&mut scheduler, "store get 3"), "00c0ffee\n");
+        assert_eq!(run(&mut scheduler, "store set 3 0"), "error: invalid hexadecimal\n");
+        assert_eq!(run(&mut scheduler, "store remove 3"), "");
+        assert_eq!(run(&mut scheduler, "store get 3"), "not found\n");
+    }
+
+    #[test]
+    fn unknown() {
+        let mut scheduler = scheduler(false);
+        assert_eq!(run(&mut scheduler, "frobnicate"), "error: unknown command (try help)\n");
+        assert_eq!(run(&mut scheduler, ""), "");
+    }
+}
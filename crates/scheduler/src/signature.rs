@@ -0,0 +1,214 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Applet signature.
+//!
+//! The signature is stored in the [`SECTION`] custom section, which must be the last section of the
+//! applet module. Its content is the Ed25519 signature of the module bytes preceding the section.
+
+use core::fmt::Display;
+
+use wasefire_board_api::crypto::ed25519::Api as _;
+use wasefire_board_api::crypto::Api as _;
+use wasefire_board_api::Api as Board;
+use wasefire_logger as logger;
+
+use crate::manifest::read_u32;
+
+/// Name of the custom section holding the signature.
+pub const SECTION: &str = "wasefire-signature";
+
+/// Signed applet module.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Signed<'a> {
+    /// The module bytes preceding the signature section.
+    pub message: &'a [u8],
+
+    /// The Ed25519 signature of the message.
+    pub signature: &'a [u8; 64],
+}
+
+impl<'a> Signed<'a> {
+    /// Splits an applet module into its signed bytes and its signature, if signed.
+    pub fn find(wasm: &'a [u8]) -> Result<Option<Self>, &'static str> {
+        // Skip the magic and version.
+        let mut sections = wasm.get(8 ..).ok_or("invalid module")?;
+        let mut signed = None;
+        while let Some((&id, rest)) = sections.split_first() {
+            if signed.is_some() {
+                return Err("signature is not the last section");
+            }
+            let start = wasm.len() - sections.len();
+            let (size, rest) = read_u32(rest)?;
+            let content = rest.get(.. size).ok_or("invalid module")?;
+            sections = &rest[size ..];
+            if id != 0 {
+                continue;
+            }
+            let (len, content) = read_u32(content)?;
+            if content.get(.. len).ok_or("invalid module")? == SECTION.as_bytes() {
+                let signature =
+                    content[len ..].try_into().map_err(|_| "invalid signature length")?;
+                signed = Some(Signed { message: &wasm[.. start], signature });
+            }
+        }
+        Ok(signed)
+    }
+}
+
+/// Verifies the signature of an applet against a public key.
+///
+/// Panics with a diagnostic if the applet is unsigned or tampered.
+pub fn check<B: Board>(board: &mut B, wasm: &[u8], public: &[u8; 32]) {
+    match verify(board, wasm, public) {
+        Ok(()) => logger::debug!("Applet signature is valid."),
+        Err(error) => logger::panic!("{}", error),
+    }
+}
+
+/// Verifies the signature of an applet against a public key.
+///
+/// Returns a diagnostic if the applet is unsigned or tampered.
+pub fn verify<B: Board>(board: &mut B, wasm: &[u8], public: &[u8; 32]) -> Result<(), Error> {
+    let Signed { message, signature } = match Signed::find(wasm) {
+        Ok(Some(x)) => x,
+        Ok(None) => return Err(Error::Unsigned),
+        Err(error) => return Err(Error::Malformed(error)),
+    };
+    let mut crypto = board.crypto();
+    let mut ed25519 = crypto.ed25519();
+    if !ed25519.is_supported() {
+        return Err(Error::Unsupported);
+    }
+    match ed25519.verify(public, message, signature) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(Error::Invalid),
+        Err(_) => Err(Error::InvalidKey),
+    }
+}
+
+/// Reasons an applet signature can't be verified.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The applet is not signed.
+    Unsigned,
+
+    /// The signature section is malformed.
+    Malformed(&'static str),
+
+    /// Ed25519 is not supported by the board.
+    Unsupported,
+
+    /// The signature doesn't match the applet.
+    Invalid,
+
+    /// The public key is invalid.
+    InvalidKey,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::Unsigned => write!(f, "Applet is not signed."),
+            Error::Malformed(error) => write!(f, "Invalid applet signature: {error}"),
+            Error::Unsupported => write!(f, "Ed25519 is not supported by the board."),
+            Error::Invalid => write!(f, "Applet signature is invalid."),
+            Error::InvalidKey => write!(f, "Applet public key is invalid."),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::testing::scheduler;
+
+    /// Module with an empty type section, signed by `cargo xtask sign-applet` with the private key
+    /// `[0x42; 32]`.
+    const SIGNED: &[u8] = b"\0asm\x01\0\0\0\x01\x01\x00\
+        \x00\x53\x12wasefire-signature\
+        \xb4\xcb\xfd\x38\xbb\x91\xa0\x43\x43\x2b\xf0\x3d\x23\xb2\xc7\xf0\
+        \x35\xf7\x9b\xe6\x5e\xa1\xd5\x62\xb7\x32\xfe\x5d\xcb\x91\x1e\x27\
+        \xbf\x15\x71\x3c\x42\x2f\xef\x09\xe8\x8f\x10\x28\xe2\x09\xab\x94\
+        \x0f\x6b\xf6\x40\x5d\x3e\xe9\x8b\xea\x3c\xc9\xb4\xf2\x12\xaf\x09";
+
+    /// Public key of the private key `[0x42; 32]`.
+    const PUBLIC: [u8; 32] = [
+        0x21, 0x52, 0xf8, 0xd1, 0x9b, 0x79, 0x1d, 0x24, 0x45, 0x32, 0x42, 0xe1, 0x5f, 0x2e, 0xab,
+        0x6c, 0xb7, 0xcf, 0xfa, 0x7b, 0x6a, 0x5e, 0xd3, 0x00, 0x97, 0x96, 0x0e, 0x06, 0x98, 0x81,
+        0xdb, 0x12,
+    ];
+
+    fn custom(name: &str, content: &[u8]) -> Vec<u8> {
+        let mut section = vec![name.len() as u8];
+        section.extend_from_slice(name.as_bytes());
+        section.extend_from_slice(content);
+        let mut result = vec![0, section.len() as u8];
+        result.extend_from_slice(&section);
+        result
+    }
+
+    #[test]
+    fn find_signed() {
+        // Type section with no types before the signature.
+        let mut wasm = b"\0asm\x01\0\0\0\x01\x01\x00".to_vec();
+        wasm.extend_from_slice(&custom(SECTION, &[0x42; 64]));
+        let signed = Signed::find(&wasm).unwrap().unwrap();
+        assert_eq!(signed.message, b"\0asm\x01\0\0\0\x01\x01\x00");
+        assert_eq!(signed.signature, &[0x42; 64]);
+    }
+
+    #[test]
+    fn find_unsigned() {
+        assert_eq!(Signed::find(b"\0asm\x01\0\0\0\x01\x01\x00"), Ok(None));
+        let mut wasm = b"\0asm\x01\0\0\0".to_vec();
+        wasm.extend_from_slice(&custom("wasefire-manifest", b"name=hello"));
+        assert_eq!(Signed::find(&wasm), Ok(None));
+    }
+
+    #[test]
+    fn find_invalid() {
+        let mut wasm = b"\0asm\x01\0\0\0".to_vec();
+        wasm.extend_from_slice(&custom(SECTION, &[0x42; 63]));
+        assert!(Signed::find(&wasm).is_err());
+        let mut wasm = b"\0asm\x01\0\0\0".to_vec();
+        wasm.extend_from_slice(&custom(SECTION, &[0x42; 64]));
+        wasm.extend_from_slice(&[0x01, 0x01, 0x00]);
+        assert!(Signed::find(&wasm).is_err());
+    }
+
+    #[test]
+    fn check_xtask_signature() {
+        let signed = Signed::find(SIGNED).unwrap().unwrap();
+        assert_eq!(signed.message, b"\0asm\x01\0\0\0\x01\x01\x00");
+        check(&mut scheduler(false).board, SIGNED, &PUBLIC);
+    }
+
+    #[test]
+    fn verify_unsigned() {
+        let wasm = b"\0asm\x01\0\0\0\x01\x01\x00";
+        assert_eq!(verify(&mut scheduler(false).board, wasm, &PUBLIC), Err(Error::Unsigned));
+    }
+
+    #[test]
+    #[should_panic(expected = "Applet signature is invalid.")]
+    fn check_tampered_signature() {
+        let mut wasm = SIGNED.to_vec();
+        wasm[10] = 0x01;
+        check(&mut scheduler(false).board, &wasm, &PUBLIC);
+    }
+}
@@ -18,7 +18,9 @@ use wasefire_board_api::{self as board, Event};
 use wasefire_interpreter::Store;
 use wasefire_logger as log;
 
+use crate::event::button::Gestures;
 use crate::event::{Handler, Key};
+use crate::manifest::Permissions;
 use crate::{Memory, Trap};
 
 #[derive(Debug)]
@@ -31,8 +33,17 @@ pub struct Applet<B: board::Types> {
     /// Whether we returned from a callback.
     done: bool,
 
+    /// Whether the applet is parked while waiting for events.
+    waiting: bool,
+
     handlers: BTreeSet<Handler>,
 
+    /// Gesture recognition of the applet buttons.
+    pub gestures: Gestures,
+
+    /// Permissions granted by the applet manifest.
+    pub permissions: Permissions,
+
     pub hashes: AppletHashes<B>,
 }
 
@@ -43,7 +54,10 @@ impl<B: board::Types> Default for Applet<B> {
             store: Default::default(),
             events: Default::default(),
             done: Default::default(),
+            waiting: Default::default(),
             handlers: Default::default(),
+            gestures: Default::default(),
+            permissions: Default::default(),
             hashes: Default::default(),
         }
     }
@@ -126,6 +140,26 @@ impl<B: board::Types> Applet<B> {
         }
     }
 
+    /// Returns whether there are no pending events and no callback returned.
+    pub fn is_idle(&self) -> bool {
+        !self.done && self.events.is_empty()
+    }
+
+    /// Returns whether the applet has work to do if it were running.
+    pub fn is_runnable(&self) -> bool {
+        !self.waiting || !self.is_idle()
+    }
+
+    /// Marks the applet as parked while waiting for events.
+    pub fn park(&mut self) {
+        self.waiting = true;
+    }
+
+    /// Marks the applet as running.
+    pub fn resume(&mut self) {
+        self.waiting = false;
+    }
+
     pub fn done(&mut self) {
         self.done = true;
     }
@@ -0,0 +1,229 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test board for scheduler unit tests.
+
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+use wasefire_board_api::debug::{Effect, Level, QueueStats};
+use wasefire_board_api::{self as board, Api as Board, Event, Types, Unsupported};
+use wasefire_store::{BufferOptions, BufferStorage, Store};
+
+use crate::Scheduler;
+
+pub struct Leds(pub [bool; 2]);
+
+/// Effects recorded in dry-run mode.
+#[derive(Default)]
+pub struct Debug {
+    pub dry_run: bool,
+    pub effects: Vec<Effect>,
+    pub log_level: Option<Level>,
+    pub queue_stats: QueueStats,
+}
+
+pub struct Test {
+    /// Pending board events.
+    pub events: VecDeque<Event>,
+    pub leds: Leds,
+    pub debug: Debug,
+    pub store: Store<BufferStorage>,
+}
+
+impl Types for Test {
+    type Crypto = Unsupported;
+}
+
+impl Board for Test {
+    fn try_event(&mut self) -> Option<Event> {
+        self.events.pop_front()
+    }
+
+    fn wait_event(&mut self) -> Event {
+        self.events.pop_front().unwrap()
+    }
+
+    type Storage = BufferStorage;
+    fn with_store<R>(&mut self, f: impl FnOnce(&mut Store<Self::Storage>) -> R) -> R {
+        f(&mut self.store)
+    }
+
+    type Adc<'a> = Unsupported;
+    fn adc(&mut self) -> Self::Adc<'_> {
+        Unsupported
+    }
+
+    type Button<'a> = Unsupported;
+    fn button(&mut self) -> Self::Button<'_> {
+        Unsupported
+    }
+
+    type Capture<'a> = Unsupported;
+    fn capture(&mut self) -> Self::Capture<'_> {
+        Unsupported
+    }
+
+    type Clock<'a> = Unsupported;
+    fn clock(&mut self) -> Self::Clock<'_> {
+        Unsupported
+    }
+
+    type Crypto<'a> = Unsupported;
+    fn crypto(&mut self) -> Unsupported {
+        Unsupported
+    }
+
+    type Debug<'a> = &'a mut Debug;
+    fn debug(&mut self) -> Self::Debug<'_> {
+        &mut self.debug
+    }
+
+    type Encoder<'a> = Unsupported;
+    fn encoder(&mut self) -> Self::Encoder<'_> {
+        Unsupported
+    }
+
+    type Gpio<'a> = Unsupported;
+    fn gpio(&mut self) -> Self::Gpio<'_> {
+        Unsupported
+    }
+
+    type I2cSw<'a> = Unsupported;
+    fn i2c_sw(&mut self) -> Self::I2cSw<'_> {
+        Unsupported
+    }
+
+    type Led<'a> = &'a mut Leds;
+    fn led(&mut self) -> Self::Led<'_> {
+        &mut self.leds
+    }
+
+    type LedRing<'a> = Unsupported;
+    fn led_ring(&mut self) -> Self::LedRing<'_> {
+        Unsupported
+    }
+
+    type Magnetometer<'a> = Unsupported;
+    fn magnetometer(&mut self) -> Self::Magnetometer<'_> {
+        Unsupported
+    }
+
+    type Platform<'a> = Unsupported;
+    fn platform(&mut self) -> Self::Platform<'_> {
+        Unsupported
+    }
+
+    type Power<'a> = Unsupported;
+    fn power(&mut self) -> Self::Power<'_> {
+        Unsupported
+    }
+
+    type Pwm<'a> = Unsupported;
+    fn pwm(&mut self) -> Self::Pwm<'_> {
+        Unsupported
+    }
+
+    type Rng<'a> = Unsupported;
+    fn rng(&mut self) -> Self::Rng<'_> {
+        Unsupported
+    }
+
+    type Stepper<'a> = Unsupported;
+    fn stepper(&mut self) -> Self::Stepper<'_> {
+        Unsupported
+    }
+
+    type Temp<'a> = Unsupported;
+    fn temp(&mut self) -> Self::Temp<'_> {
+        Unsupported
+    }
+
+    type Timer<'a> = Unsupported;
+    fn timer(&mut self) -> Self::Timer<'_> {
+        Unsupported
+    }
+
+    type Uart<'a> = Unsupported;
+    fn uart(&mut self) -> Self::Uart<'_> {
+        Unsupported
+    }
+
+    type Update<'a> = Unsupported;
+    fn update(&mut self) -> Self::Update<'_> {
+        Unsupported
+    }
+
+    type Usb<'a> = Unsupported;
+    fn usb(&mut self) -> Self::Usb<'_> {
+        Unsupported
+    }
+
+    type Watchdog<'a> = Unsupported;
+    fn watchdog(&mut self) -> Self::Watchdog<'_> {
+        Unsupported
+    }
+}
+
+impl board::debug::Api for &mut Debug {
+    fn exit(&mut self, _: bool) -> ! {
+        unreachable!()
+    }
+
+    fn dry_run(&mut self) -> bool {
+        self.dry_run
+    }
+
+    fn record(&mut self, effect: Effect) {
+        self.effects.push(effect);
+    }
+
+    fn set_log_level(&mut self, level: Level) -> core::result::Result<(), board::Error> {
+        self.log_level = Some(level);
+        Ok(())
+    }
+
+    fn queue_stats(&mut self) -> core::result::Result<QueueStats, board::Error> {
+        Ok(self.queue_stats)
+    }
+}
+
+impl board::led::Api for &mut Leds {
+    fn count(&mut self) -> usize {
+        self.0.len()
+    }
+
+    fn get(&mut self, led: usize) -> core::result::Result<bool, board::Error> {
+        self.0.get(led).copied().ok_or(board::Error::User)
+    }
+
+    fn set(&mut self, led: usize, on: bool) -> core::result::Result<(), board::Error> {
+        *self.0.get_mut(led).ok_or(board::Error::User)? = on;
+        Ok(())
+    }
+}
+
+pub fn scheduler(dry_run: bool) -> Scheduler<Test> {
+    let options = BufferOptions {
+        word_size: 4,
+        page_size: 4096,
+        max_word_writes: 2,
+        max_page_erases: 10000,
+        strict_mode: true,
+    };
+    let storage = BufferStorage::new(vec![0xff; 4 * 4096].into_boxed_slice(), options);
+    let store = Store::new(storage).ok().unwrap();
+    let debug = Debug { dry_run, ..Default::default() };
+    Scheduler::new(Test { events: VecDeque::new(), leds: Leds([false, true]), debug, store })
+}
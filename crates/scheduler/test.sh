@@ -17,8 +17,12 @@ set -ex
 
 cargo check --features=std
 cargo check --features=std,log
+cargo check --features=std,shell
+cargo check --features=std,metrics
+cargo check --features=std,encrypted-store
 cargo check --target=thumbv7em-none-eabi
-cargo check --target=thumbv7em-none-eabi --features=defmt
+cargo check --target=thumbv7em-none-eabi --features=defmt,metrics
+cargo check --target=thumbv7em-none-eabi --features=shell
 cargo fmt -- --check
-cargo clippy --features=std -- --deny=warnings
-cargo test --features=std
+cargo clippy --features=std,shell,metrics -- --deny=warnings
+cargo test --features=std,shell,metrics
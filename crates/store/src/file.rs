@@ -124,6 +124,11 @@ impl Storage for FileStorage {
         file.write_all(&vec![0xff; self.page_size()][..])?;
         Ok(())
     }
+
+    fn flush(&mut self) -> StorageResult<()> {
+        self.file.get_mut().sync_all()?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
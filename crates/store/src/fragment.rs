@@ -18,6 +18,8 @@
 //! length](Store::max_value_length) by storing ordered consecutive fragments in a sequence of keys.
 //! The first keys hold fragments of maximal length, followed by a possibly partial fragment. The
 //! remaining keys are not used.
+//!
+//! Values can also be streamed with [`StoreWriter`] and [`StoreReader`].
 
 use alloc::vec::Vec;
 use core::ops::Range;
@@ -138,6 +140,107 @@ pub fn delete(store: &mut Store<impl Storage>, keys: &impl Keys) -> StoreResult<
     store.transaction(&updates)
 }
 
+/// Streams a value to a sequence of keys.
+///
+/// Only one fragment is buffered at a time: each fragment is written as soon as it is full, and
+/// the last (possibly partial) fragment is written by [`StoreWriter::finish`]. The old value is
+/// deleted when the writer starts, so this is not atomic like [`write`]: if the writer is dropped
+/// or the platform is interrupted before finishing, the keys hold a prefix of the new value.
+pub struct StoreWriter<'a, S: Storage, K: Keys> {
+    store: &'a mut Store<S>,
+    keys: &'a K,
+    /// Position of the fragment being filled.
+    pos: usize,
+    /// The fragment being filled, which is never full.
+    fragment: Vec<u8>,
+}
+
+impl<'a, S: Storage, K: Keys> StoreWriter<'a, S, K> {
+    /// Starts streaming a value to a sequence of keys.
+    ///
+    /// The old value is deleted.
+    pub fn new(store: &'a mut Store<S>, keys: &'a K) -> StoreResult<Self> {
+        delete(store, keys)?;
+        Ok(StoreWriter { store, keys, pos: 0, fragment: Vec::new() })
+    }
+
+    /// Appends a chunk to the value.
+    ///
+    /// Returns [`StoreError::InvalidArgument`] if the value would not fit in the sequence of keys,
+    /// in which case the chunk is not appended.
+    pub fn write_chunk(&mut self, mut chunk: &[u8]) -> StoreResult<()> {
+        let max_value_length = self.store.max_value_length();
+        let len = self.pos * max_value_length + self.fragment.len();
+        if self.keys.len() * max_value_length < len + chunk.len() {
+            return Err(StoreError::InvalidArgument);
+        }
+        while !chunk.is_empty() {
+            let len = core::cmp::min(max_value_length - self.fragment.len(), chunk.len());
+            self.fragment.extend_from_slice(&chunk[.. len]);
+            chunk = &chunk[len ..];
+            if self.fragment.len() == max_value_length {
+                self.store.insert(self.keys.key(self.pos), &self.fragment)?;
+                self.pos += 1;
+                self.fragment.clear();
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the last fragment of the value.
+    pub fn finish(self) -> StoreResult<()> {
+        if self.fragment.is_empty() {
+            return Ok(());
+        }
+        self.store.insert(self.keys.key(self.pos), &self.fragment)
+    }
+}
+
+/// Streams the value of a sequence of keys.
+///
+/// Only one fragment is read from storage at a time.
+pub struct StoreReader<'a, S: Storage> {
+    store: &'a Store<S>,
+    handles: Vec<StoreHandle>,
+    /// Position of the next fragment to read.
+    next: usize,
+    fragment: Vec<u8>,
+    /// Position of the next byte to read in the current fragment.
+    offset: usize,
+}
+
+impl<'a, S: Storage> StoreReader<'a, S> {
+    /// Starts streaming the value of a sequence of keys.
+    ///
+    /// Returns `None` if there is no value.
+    pub fn new(store: &'a Store<S>, keys: &impl Keys) -> StoreResult<Option<Self>> {
+        let handles = get_handles(store, keys)?;
+        if handles.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(StoreReader { store, handles, next: 0, fragment: Vec::new(), offset: 0 }))
+    }
+
+    /// Reads the next chunk of the value.
+    ///
+    /// Returns the number of bytes read, which is zero at the end of the value.
+    pub fn read_chunk(&mut self, output: &mut [u8]) -> StoreResult<usize> {
+        while self.offset == self.fragment.len() {
+            let handle = match self.handles.get(self.next) {
+                Some(x) => x,
+                None => return Ok(0),
+            };
+            self.fragment = handle.get_value(self.store)?;
+            self.next += 1;
+            self.offset = 0;
+        }
+        let len = core::cmp::min(output.len(), self.fragment.len() - self.offset);
+        output[.. len].copy_from_slice(&self.fragment[self.offset ..][.. len]);
+        self.offset += len;
+        Ok(len)
+    }
+}
+
 /// Returns the handles of a sequence of keys.
 ///
 /// The handles are truncated to the keys that are present.
@@ -321,4 +424,113 @@ mod tests {
         assert_eq!(store.find(2), Ok(None));
         assert_eq!(store.find(3), Ok(None));
     }
+
+    #[test]
+    fn stream_read_multiple_chunks() {
+        let mut store = MINIMAL.new_store();
+        let value: Vec<_> = (0 .. 60).collect();
+        assert_eq!(store.insert(0, &value[.. 52]), Ok(()));
+        assert_eq!(store.insert(1, &value[52 ..]), Ok(()));
+        let mut reader = StoreReader::new(&store, &(0 .. 4)).unwrap().unwrap();
+        let mut result = Vec::new();
+        let mut chunk = [0; 7];
+        loop {
+            match reader.read_chunk(&mut chunk) {
+                Ok(0) => break,
+                Ok(len) => result.extend_from_slice(&chunk[.. len]),
+                Err(error) => panic!("{error:?}"),
+            }
+        }
+        assert_eq!(result, value);
+    }
+
+    #[test]
+    fn stream_read_empty_entry() {
+        let store = MINIMAL.new_store();
+        assert!(matches!(StoreReader::new(&store, &(0 .. 4)), Ok(None)));
+    }
+
+    #[test]
+    fn stream_write_multiple_chunks() {
+        let mut store = MINIMAL.new_store();
+        let mut writer = StoreWriter::new(&mut store, &(0 .. 4)).unwrap();
+        for chunk in (0 .. 60).collect::<Vec<_>>().chunks(7) {
+            assert_eq!(writer.write_chunk(chunk), Ok(()));
+        }
+        assert_eq!(writer.finish(), Ok(()));
+        assert_eq!(store.find(0), Ok(Some((0 .. 52).collect())));
+        assert_eq!(store.find(1), Ok(Some((52 .. 60).collect())));
+        assert_eq!(store.find(2), Ok(None));
+    }
+
+    #[test]
+    fn stream_write_too_long() {
+        let mut store = MINIMAL.new_store();
+        let mut writer = StoreWriter::new(&mut store, &(0 .. 2)).unwrap();
+        assert_eq!(writer.write_chunk(&[0; 100]), Ok(()));
+        assert_eq!(writer.write_chunk(&[0; 5]), Err(StoreError::InvalidArgument));
+        assert_eq!(writer.write_chunk(&[0; 4]), Ok(()));
+        assert_eq!(writer.finish(), Ok(()));
+        assert_eq!(read(&store, &(0 .. 2)), Ok(Some(vec![0; 104])));
+    }
+
+    #[test]
+    fn stream_write_shorter() {
+        let mut store = MINIMAL.new_store();
+        let value: Vec<_> = (0 .. 60).collect();
+        assert_eq!(write(&mut store, &(0 .. 4), &value), Ok(()));
+        let mut writer = StoreWriter::new(&mut store, &(0 .. 4)).unwrap();
+        assert_eq!(writer.write_chunk(b"hello"), Ok(()));
+        assert_eq!(writer.finish(), Ok(()));
+        assert_eq!(read(&store, &(0 .. 4)), Ok(Some(b"hello".to_vec())));
+    }
+
+    #[test]
+    fn stream_write_dropped() {
+        let mut store = MINIMAL.new_store();
+        assert_eq!(write(&mut store, &(0 .. 4), b"hello"), Ok(()));
+        let value: Vec<_> = (0 .. 60).collect();
+        let mut writer = StoreWriter::new(&mut store, &(0 .. 4)).unwrap();
+        assert_eq!(writer.write_chunk(&value), Ok(()));
+        drop(writer);
+        // Only the full fragments are written.
+        assert_eq!(read(&store, &(0 .. 4)), Ok(Some((0 .. 52).collect())));
+    }
+
+    #[test]
+    fn stream_write_interrupted() {
+        let old: Vec<_> = (0 .. 20).collect();
+        let new: Vec<_> = (100 .. 160).collect();
+        let mut delay = 0;
+        loop {
+            let mut store = MINIMAL.new_store();
+            assert_eq!(write(&mut store, &(0 .. 4), &old), Ok(()));
+            store.storage_mut().arm_interruption(delay);
+            let result = (|| {
+                let mut writer = StoreWriter::new(&mut store, &(0 .. 4))?;
+                for chunk in new.chunks(13) {
+                    writer.write_chunk(chunk)?;
+                }
+                writer.finish()
+            })();
+            match result {
+                Ok(()) => {
+                    store.storage_mut().disarm_interruption();
+                    assert_eq!(read(&store, &(0 .. 4)), Ok(Some(new)));
+                    // Make sure we interrupted at least once.
+                    assert!(delay > 0);
+                    break;
+                }
+                Err(error) => assert_eq!(error, StoreError::StorageError),
+            }
+            let mut storage = store.extract_storage();
+            storage.reset_interruption();
+            let store = Store::new(storage).ok().unwrap();
+            // We either see the old value or a prefix of the new value.
+            if let Some(value) = read(&store, &(0 .. 4)).unwrap() {
+                assert!(value == old || new.starts_with(&value));
+            }
+            delay += 1;
+        }
+    }
 }
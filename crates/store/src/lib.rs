@@ -388,7 +388,7 @@ pub use self::file::{FileOptions, FileStorage};
 pub use self::model::{StoreModel, StoreOperation};
 pub use self::storage::{Storage, StorageError, StorageIndex, StorageResult};
 pub use self::store::{
-    Store, StoreError, StoreHandle, StoreIter, StoreRatio, StoreResult, StoreUpdate,
+    Store, StoreError, StoreHandle, StoreIter, StoreRatio, StoreResult, StoreUpdate, WearStats,
 };
 
 /// Internal representation of natural numbers.
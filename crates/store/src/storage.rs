@@ -88,6 +88,13 @@ pub trait Storage {
     /// The `page` must be in the storage, i.e. less than [`Storage::num_pages`]. And the page
     /// should not have been erased [too many](Self::max_page_erases) times.
     fn erase_page(&mut self, page: usize) -> StorageResult<()>;
+
+    /// Makes sure previous writes and erasures are persisted.
+    ///
+    /// Flash storages write through, so this does nothing by default.
+    fn flush(&mut self) -> StorageResult<()> {
+        Ok(())
+    }
 }
 
 impl StorageIndex {
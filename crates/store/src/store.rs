@@ -123,6 +123,26 @@ impl StoreRatio {
     }
 }
 
+/// Wear statistics of the storage.
+///
+/// This is used for the [`Store::wear_stats`] metric.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WearStats {
+    /// The number of times each page was erased.
+    ///
+    /// Erased pages count the erase cycle they will be initialized with. Wiping the store keeps the
+    /// counts (see [`Store::wipe`]).
+    pub erase_count_per_page: Vec<usize>,
+
+    /// The maximum number of times a page can be erased.
+    pub max_page_erases: usize,
+
+    /// The number of words written since the store was initialized.
+    ///
+    /// This is the used [lifetime](Store::lifetime).
+    pub total_writes: usize,
+}
+
 /// Safe pointer to an entry.
 ///
 /// A store handle stays valid at least until the next mutable operation. Store operations taking a
@@ -278,6 +298,26 @@ impl<S: Storage> Store<S> {
         Ok(StoreRatio { used, total })
     }
 
+    /// Returns the wear statistics of the storage.
+    ///
+    /// The erase counts are read from the init info of each page and thus persist with the storage.
+    pub fn wear_stats(&self) -> StoreResult<WearStats> {
+        let head = self.head()?;
+        let mut erase_count_per_page = Vec::new();
+        for page in 0 .. self.format.num_pages() {
+            erase_count_per_page.push(match self.parse_init(page)? {
+                WordState::Valid(InitInfo { cycle, .. }) => cycle as usize,
+                // Pages before the head are initialized in the next cycle.
+                WordState::Erased | WordState::Partial => {
+                    (head.cycle(&self.format) + (page < head.page(&self.format)) as Nat) as usize
+                }
+            });
+        }
+        let max_page_erases = self.format.max_page_erases() as usize;
+        let total_writes = self.lifetime()?.used();
+        Ok(WearStats { erase_count_per_page, max_page_erases, total_writes })
+    }
+
     /// Applies a sequence of updates as a single transaction.
     ///
     /// # Errors
@@ -371,6 +411,39 @@ impl<S: Storage> Store<S> {
         Ok(())
     }
 
+    /// Erases the storage and initializes an empty store.
+    ///
+    /// Contrary to [`Store::clear`], all pages are physically erased such that no previous value
+    /// (including deleted ones) remains in the storage. This should be reserved for factory resets.
+    /// An interrupted wipe should be retried.
+    ///
+    /// The erase cycles are kept. Because the store format uses the same cycle for all pages, the
+    /// store restarts at the largest cycle plus one (for the erase of the wipe).
+    pub fn wipe(&mut self) -> StoreResult<()> {
+        let stats = self.wear_stats()?;
+        let cycle = stats.erase_count_per_page.iter().max().map_or(0, |&x| x + 1);
+        let cycle = min(usize_to_nat(cycle), self.format.max_page_erases());
+        for page in 0 .. self.format.num_pages() {
+            self.storage_erase_page(page)?;
+        }
+        self.head = None;
+        self.entries = None;
+        let mut index = self.format.index_init(0);
+        let init_info = self.format.build_init(InitInfo { cycle, prefix: 0 })?;
+        self.storage_write_slice(index, &init_info)?;
+        // Pad the first word of the page, otherwise the store would look erased and be initialized
+        // with cycle 0 (see Store::init_with_cycle).
+        let word_size = self.format.word_size() as usize;
+        index.byte += 2 * word_size;
+        self.storage_write_slice(index, &alloc::vec![0; word_size])?;
+        self.recover()
+    }
+
+    /// Persists the storage, see [`Storage::flush`].
+    pub fn flush(&mut self) -> StoreResult<()> {
+        Ok(self.storage.flush()?)
+    }
+
     /// Recovers a possible interrupted operation.
     ///
     /// If the storage is completely erased, it is initialized.
@@ -1317,6 +1390,68 @@ mod tests {
         driver.check().unwrap();
     }
 
+    #[test]
+    fn wear_stats_ok() {
+        let mut store = MINIMAL.new_store();
+        let num_pages = store.format.num_pages() as usize;
+        let stats = store.wear_stats().unwrap();
+        assert_eq!(stats.erase_count_per_page, vec![0; num_pages]);
+        assert_eq!(stats.max_page_erases, store.format.max_page_erases() as usize);
+        // Overwrite the same entry until compaction erases the first page.
+        let mut writes = stats.total_writes;
+        while store.wear_stats().unwrap().erase_count_per_page[0] == 0 {
+            store.insert(0, &[0x5c; 6]).unwrap();
+            let stats = store.wear_stats().unwrap();
+            assert!(writes < stats.total_writes);
+            writes = stats.total_writes;
+        }
+        let stats = store.wear_stats().unwrap();
+        assert_eq!(stats.erase_count_per_page[0], 1);
+        assert!(stats.erase_count_per_page[1 ..].iter().all(|&x| x <= 1));
+    }
+
+    #[test]
+    fn wipe_ok() {
+        let mut store = MINIMAL.new_store();
+        store.insert(0, &[0x5c; 6]).unwrap();
+        store.insert(1, &[0x5c; 8]).unwrap();
+        store.remove(0).unwrap();
+        store.wipe().unwrap();
+        assert_eq!(store.find(1), Ok(None));
+        assert_eq!(store.iter().unwrap().count(), 0);
+        // Only the init info and a padding word of the first page are written.
+        let word_size = store.format.word_size() as usize;
+        assert!(is_erased(&store.read_page(0)[3 * word_size ..]));
+        for page in 1 .. store.format.num_pages() {
+            assert!(is_erased(&store.read_page(page)));
+        }
+        // The erase counts are kept.
+        let num_pages = store.format.num_pages() as usize;
+        assert_eq!(store.wear_stats().unwrap().erase_count_per_page, vec![1; num_pages]);
+        // The store is usable after a wipe.
+        store.insert(0, &[0x5c; 6]).unwrap();
+        assert_eq!(store.find(0), Ok(Some(vec![0x5c; 6])));
+    }
+
+    #[test]
+    fn wipe_keeps_erase_counts() {
+        let mut store = MINIMAL.new_store();
+        // Overwrite the same entry until compaction erases the first page.
+        while store.wear_stats().unwrap().erase_count_per_page[0] == 0 {
+            store.insert(0, &[0x5c; 6]).unwrap();
+        }
+        let before = store.wear_stats().unwrap();
+        store.wipe().unwrap();
+        let after = store.wear_stats().unwrap();
+        let max = before.erase_count_per_page.iter().max().unwrap();
+        assert!(after.erase_count_per_page.iter().all(|x| *x == max + 1));
+        assert!(before.total_writes < after.total_writes);
+        // Wiping again counts one more erase.
+        store.wipe().unwrap();
+        let again = store.wear_stats().unwrap();
+        assert!(again.erase_count_per_page.iter().all(|x| *x == max + 2));
+    }
+
     #[test]
     fn remove_ok() {
         let mut driver = MINIMAL.new_driver().power_on().unwrap();
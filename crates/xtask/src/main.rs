@@ -16,21 +16,32 @@ use std::cell::Cell;
 use std::cmp::Reverse;
 use std::collections::BinaryHeap;
 use std::fmt::Display;
+use std::net::TcpListener;
 use std::num::ParseIntError;
 use std::os::unix::prelude::CommandExt;
 use std::path::Path;
-use std::process::Command;
+use std::process::{Child, Command};
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Parser;
+use ed25519_dalek::{Signer, SigningKey, SIGNATURE_LENGTH};
 use lazy_static::lazy_static;
+use notify::{RecursiveMode, Watcher};
+use object::{Object, ObjectSection};
 use probe_rs::config::TargetSelector;
 use probe_rs::{flashing, Permissions, Session};
 use rustc_demangle::demangle;
 use sha2::{Digest, Sha256};
 use strum::{Display, EnumString};
 
+/// Port of the GDB server.
+const GDB_PORT: u16 = 2331;
+
 #[derive(Parser)]
 struct Flags {
     #[clap(flatten)]
@@ -43,6 +54,9 @@ struct Flags {
 #[derive(clap::Args)]
 struct MainOptions {
     /// (unstable) Compiles with multivalue support.
+    ///
+    /// This applies to both Rust applets and runners, which must agree. Other languages are not
+    /// supported.
     #[clap(long)]
     multivalue: bool,
 
@@ -53,6 +67,18 @@ struct MainOptions {
     /// Prints basic size information.
     #[clap(long)]
     size: bool,
+
+    /// Prints the commands instead of executing them.
+    ///
+    /// Files are not modified either, so the printed commands are those of a fresh build.
+    #[clap(long)]
+    dry_run: bool,
+
+    /// Maximum number of independent builds (e.g. runners) to run in parallel.
+    ///
+    /// Builds depending on each other (e.g. a runner embedding an applet) are still sequential.
+    #[clap(long, default_value_t = 1)]
+    jobs: usize,
     // TODO: Add a flag to add "-C link-arg=-Map=output.map" to get the map of why the linker
     // added/kept something.
 }
@@ -67,6 +93,16 @@ enum MainCommand {
 
     /// Updates the applet API for all languages.
     UpdateApis,
+
+    /// Checks that a runner builds reproducibly.
+    ///
+    /// The runner is built twice in clean target directories and both binaries are compared.
+    VerifyReproducible(VerifyReproducible),
+
+    /// Signs an applet with an Ed25519 private key.
+    ///
+    /// The signature is appended in a custom section and the public key is printed.
+    SignApplet(SignApplet),
 }
 
 #[derive(clap::Args)]
@@ -74,6 +110,12 @@ struct Applet {
     #[clap(flatten)]
     options: AppletOptions,
 
+    /// Rebuilds the applet (and reruns the command) when its sources change.
+    ///
+    /// The runner is only restarted if the applet actually changed.
+    #[clap(long)]
+    watch: bool,
+
     #[clap(subcommand)]
     command: Option<AppletCommand>,
 }
@@ -98,10 +140,22 @@ struct AppletOptions {
     #[clap(long, short = 'O', default_value_t)]
     opt_level: OptLevel,
 
+    /// WebAssembly target (wasm32-unknown-unknown, wasm32-wasi).
+    #[clap(long, default_value_t)]
+    wasm_target: WasmTarget,
+
     /// Stack size.
     #[clap(long, default_value_t)]
     stack_size: StackSize,
 
+    /// Fails if the applet is larger than this size in bytes.
+    #[clap(long)]
+    max_size: Option<u64>,
+
+    /// Signs the applet with the Ed25519 private key in this file (32 bytes).
+    #[clap(long)]
+    sign_key: Option<String>,
+
     /// Whether to call wasm-strip on the applet.
     #[clap(skip = Cell::new(true))]
     strip: Cell<bool>,
@@ -126,15 +180,46 @@ enum AppletCommand {
         #[clap(last = true)]
         args: Vec<String>,
     },
+
+    /// Runs the unit tests of the applet on the host (instead of compiling it).
+    ///
+    /// The applet must have a `test` feature enabling `wasefire/test`. Arguments are forwarded to
+    /// the test binary, e.g. `cargo xtask applet rust name test -- --nocapture`.
+    Test {
+        #[clap(last = true)]
+        args: Vec<String>,
+    },
 }
 
 #[derive(clap::Args)]
 struct Runner {
     #[clap(flatten)]
     options: RunnerOptions,
+
+    /// Other runners to compile with the same options.
+    others: Vec<String>,
 }
 
-#[derive(Default, clap::Args)]
+#[derive(clap::Args)]
+struct SignApplet {
+    /// File containing the Ed25519 private key (32 bytes).
+    ///
+    /// Defaults to the WASEFIRE_SIGN_KEY environment variable (64 hexadecimal digits).
+    #[clap(long)]
+    key: Option<String>,
+
+    /// Applet to sign.
+    #[clap(default_value = "target/applet.wasm")]
+    wasm: String,
+}
+
+#[derive(clap::Args)]
+struct VerifyReproducible {
+    #[clap(flatten)]
+    options: RunnerOptions,
+}
+
+#[derive(Default, Clone, clap::Args)]
 struct RunnerOptions {
     /// Runner name.
     name: String,
@@ -151,14 +236,40 @@ struct RunnerOptions {
     #[clap(long, short = 'O', default_value_t)]
     opt_level: OptLevel,
 
+    /// Overrides the optimization level of a crate (e.g. wasefire-scheduler=0).
+    ///
+    /// May be repeated. The other crates use the global optimization level.
+    #[clap(long, value_name = "CRATE=LEVEL")]
+    opt_level_override: Vec<OptLevelOverride>,
+
+    /// Chip name for probe-rs (defaults to the chip of the runner dev kit).
+    ///
+    /// The nordic runner supports nRF52840_xxAA and nRF52833_xxAA.
+    #[clap(long)]
+    chip: Option<String>,
+
     /// Erases all the flash first.
     #[clap(long)]
     erase_flash: bool,
 
+    /// Erases a flash region first (e.g. the storage) given as <start>:<len> in bytes.
+    ///
+    /// The region must be aligned to flash sectors. Numbers may be prefixed with 0x.
+    #[clap(long)]
+    erase_region: Option<FlashRegion>,
+
+    /// Reads back and verifies the flashed image.
+    #[clap(long)]
+    verify: bool,
+
     /// Prints the command lines to use GDB.
     #[clap(long)]
     gdb: bool,
 
+    /// Starts a GDB server and attaches GDB to it (instead of running).
+    #[clap(long)]
+    gdb_attach: bool,
+
     /// Defmt log filter.
     #[clap(long)]
     log: Option<String>,
@@ -175,6 +286,24 @@ struct RunnerOptions {
     /// Show the (top N) stack sizes of the firmware
     #[clap(long)]
     stack_sizes: Option<Option<usize>>,
+
+    /// Output format of the stack sizes (table, folded).
+    ///
+    /// The folded format can be piped into flamegraph.pl.
+    #[clap(long, default_value_t)]
+    stack_sizes_format: StackSizesFormat,
+
+    /// Whether to spawn the runner instead of replacing the process (when watching).
+    #[clap(skip)]
+    spawn: Cell<bool>,
+
+    /// The spawned runner, if any.
+    #[clap(skip)]
+    child: Arc<Mutex<Option<Child>>>,
+
+    /// Overrides the cargo target directory.
+    #[clap(skip)]
+    target_dir: Option<String>,
 }
 
 #[derive(Copy, Clone)]
@@ -200,6 +329,31 @@ impl FromStr for StackSize {
     }
 }
 
+#[derive(Copy, Clone)]
+struct FlashRegion {
+    start: u64,
+    len: u64,
+}
+
+impl FromStr for FlashRegion {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self> {
+        let parse = |x: &str| match x.strip_prefix("0x") {
+            Some(x) => u64::from_str_radix(x, 16),
+            None => u64::from_str(x),
+        };
+        let (start, len) = input.split_once(':').context("expected <start>:<len>")?;
+        Ok(FlashRegion { start: parse(start)?, len: parse(len)? })
+    }
+}
+
+impl Display for FlashRegion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:#x}:{:#x}", self.start, self.len)
+    }
+}
+
 #[derive(Default, Copy, Clone, EnumString, Display)]
 enum OptLevel {
     #[strum(serialize = "0")]
@@ -217,18 +371,72 @@ enum OptLevel {
     Oz,
 }
 
+impl OptLevel {
+    /// Returns the TOML value of the optimization level in a cargo profile.
+    fn to_toml(self) -> String {
+        match self {
+            OptLevel::Os | OptLevel::Oz => format!("\"{self}\""),
+            _ => self.to_string(),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct OptLevelOverride {
+    name: String,
+    level: OptLevel,
+}
+
+impl FromStr for OptLevelOverride {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self> {
+        let (name, level) = input.split_once('=').context("expected <crate>=<level>")?;
+        Ok(OptLevelOverride { name: name.to_string(), level: level.parse()? })
+    }
+}
+
+#[derive(Default, Copy, Clone, EnumString, Display)]
+enum WasmTarget {
+    #[strum(serialize = "wasm32-unknown-unknown")]
+    #[default]
+    Unknown,
+    #[strum(serialize = "wasm32-wasi")]
+    Wasi,
+}
+
+#[derive(Default, Copy, Clone, EnumString, Display)]
+#[strum(serialize_all = "kebab-case")]
+enum StackSizesFormat {
+    #[default]
+    Table,
+    Folded,
+}
+
 impl Flags {
     fn execute(self) -> Result<()> {
+        DRY_RUN.store(self.options.dry_run, Ordering::Relaxed);
         match self.command {
             MainCommand::Applet(applet) => applet.execute(&self.options)?,
             MainCommand::Runner(runner) => runner.execute(&self.options)?,
+            MainCommand::VerifyReproducible(verify) => verify.execute(&self.options)?,
+            MainCommand::SignApplet(sign) => {
+                write_signature(&sign.wasm, &read_signing_key(sign.key.as_deref())?)?
+            }
             MainCommand::UpdateApis => {
-                let (lang, ext) = ("assemblyscript", "ts");
-                let mut cargo = Command::new("cargo");
-                cargo.args(["run", "--manifest-path=crates/api-desc/Cargo.toml", "--"]);
-                cargo.arg(format!("--lang={lang}"));
-                cargo.arg(format!("--output=examples/{lang}/api.{ext}"));
-                execute_command(&mut cargo)?;
+                let apis = [
+                    ("assemblyscript", "assemblyscript", "api.ts"),
+                    ("typescript-decl", "assemblyscript", "api.d.ts"),
+                    ("c", "c", "api.h"),
+                    ("go", "go", "api/api.go"),
+                ];
+                for (lang, dir, file) in apis {
+                    let mut cargo = Command::new("cargo");
+                    cargo.args(["run", "--manifest-path=crates/api-desc/Cargo.toml", "--"]);
+                    cargo.arg(format!("--lang={lang}"));
+                    cargo.arg(format!("--output=examples/{dir}/{file}"));
+                    execute_command(&mut cargo)?;
+                }
             }
         }
         Ok(())
@@ -244,21 +452,73 @@ impl Applet {
             // use twiggy on the wasm-opt output.
             self.options.opt.set(false);
         }
+        if self.watch {
+            return self.watch(main);
+        }
+        if let Some(AppletCommand::Test { args }) = &self.command {
+            return self.options.execute_test(args);
+        }
         self.options.execute(main)?;
         if let Some(command) = &self.command {
             command.execute(main)?;
         }
         Ok(())
     }
+
+    fn watch(&self, main: &MainOptions) -> Result<()> {
+        anyhow::ensure!(self.options.lang == "rust", "--watch only supports Rust applets");
+        if let Some(AppletCommand::Runner(runner)) = &self.command {
+            runner.spawn.set(true);
+        }
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender)?;
+        watcher.watch(Path::new(&self.options.rust_dir()), RecursiveMode::Recursive)?;
+        let mut first = true;
+        loop {
+            if let Err(e) = self.rebuild(main, first) {
+                eprintln!("{e:#}");
+            }
+            first = false;
+            eprintln!("Waiting for changes...");
+            wait_for_changes(&receiver)?;
+        }
+    }
+
+    /// Rebuilds the applet and reruns the command if the applet changed.
+    fn rebuild(&self, main: &MainOptions, first: bool) -> Result<()> {
+        if let Some(AppletCommand::Test { args }) = &self.command {
+            return self.options.execute_test(args);
+        }
+        let hash = std::fs::read("target/applet.wasm.hash").ok();
+        self.options.execute(main)?;
+        if !first && std::fs::read("target/applet.wasm.hash").ok() == hash {
+            eprintln!("The applet did not change.");
+            return Ok(());
+        }
+        let Some(command) = &self.command else { return Ok(()) };
+        if let AppletCommand::Runner(runner) = command {
+            runner.stop()?;
+        }
+        command.execute(main)
+    }
 }
 
 impl AppletOptions {
     fn execute(&self, main: &MainOptions) -> Result<()> {
         match self.lang.as_str() {
-            "rust" => self.execute_rust(main),
-            "assemblyscript" => self.execute_assemblyscript(main),
+            "rust" => self.execute_rust(main)?,
+            "assemblyscript" => self.execute_assemblyscript(main)?,
+            "go" => self.execute_tinygo(main)?,
             _ => panic!("unsupported language"),
         }
+        if let Some(key) = &self.sign_key {
+            write_signature("target/applet.wasm", &read_signing_key(Some(key))?)?;
+        }
+        if let Some(max_size) = self.max_size.filter(|_| !dry_run()) {
+            let size = std::fs::metadata("target/applet.wasm")?.len();
+            anyhow::ensure!(size <= max_size, "applet size is {size} bytes but max is {max_size}");
+        }
+        Ok(())
     }
 
     fn execute_rust(&self, main: &MainOptions) -> Result<()> {
@@ -270,9 +530,12 @@ impl AppletOptions {
             sed.args(["-n", r#"s/^name = "\(.*\)"$/\1/p"#, "Cargo.toml"]);
             sed.current_dir(dir);
             let name = read_output_line(&mut sed)?;
-            (dir.to_string(), format!("{dir}/{}", wasm_target(&name)))
+            (dir.to_string(), format!("{dir}/{}", wasm_target(self.wasm_target, &name)))
         } else {
-            (format!("examples/{}/{}", self.lang, self.name), wasm_target(&self.name))
+            (
+                format!("examples/{}/{}", self.lang, self.name),
+                wasm_target(self.wasm_target, &self.name),
+            )
         };
         let mut cargo = Command::new("cargo");
         let mut rustflags = vec![
@@ -286,11 +549,13 @@ impl AppletOptions {
         if main.multivalue {
             rustflags.push("-C target-feature=+multivalue".to_string());
         }
-        cargo.args(["build", "--target=wasm32-unknown-unknown"]);
+        cargo.args(["build", &format!("--target={}", self.wasm_target)]);
         cargo.arg(format!("--profile={}", self.profile));
-        for features in &self.features {
-            cargo.arg(format!("--features={features}"));
+        if main.multivalue {
+            // The applet API must agree with the runner on the signatures of multivalue functions.
+            cargo.arg("--features=wasefire/multivalue");
         }
+        self.forward_features(&mut cargo);
         if main.release {
             cargo.args(["-Zbuild-std=core,alloc", "-Zbuild-std-features=panic_immediate_abort"]);
         } else {
@@ -302,10 +567,39 @@ impl AppletOptions {
         if copy_if_changed(&wasm, "target/applet.wasm")? {
             self.execute_wasm(main)?;
         }
-        Ok(())
+        let manifest = read_manifest(&format!("{dir}/Cargo.toml"))?;
+        write_manifest("target/applet.wasm", &manifest)
+    }
+
+    /// Returns the directory of a Rust applet.
+    fn rust_dir(&self) -> String {
+        match self.name.starts_with(['.', '/']) {
+            true => self.name.clone(),
+            false => format!("examples/{}/{}", self.lang, self.name),
+        }
+    }
+
+    fn execute_test(&self, args: &[String]) -> Result<()> {
+        anyhow::ensure!(self.lang == "rust", "only Rust applets have unit tests");
+        let mut cargo = Command::new("cargo");
+        cargo.args(["test", "--features=test"]);
+        self.forward_features(&mut cargo);
+        if !args.is_empty() {
+            cargo.arg("--");
+            cargo.args(args);
+        }
+        cargo.current_dir(self.rust_dir());
+        execute_command(&mut cargo)
+    }
+
+    fn forward_features(&self, cargo: &mut Command) {
+        for features in &self.features {
+            cargo.arg(format!("--features={features}"));
+        }
     }
 
     fn execute_assemblyscript(&self, main: &MainOptions) -> Result<()> {
+        anyhow::ensure!(!main.multivalue, "--multivalue is not supported for AssemblyScript");
         let dir = format!("examples/{}", self.lang);
         if !Path::new("examples/assemblyscript/node_modules/.bin/asc").exists() {
             ensure_command(&["npm"])?;
@@ -330,9 +624,24 @@ impl AppletOptions {
         self.execute_wasm(main)
     }
 
+    fn execute_tinygo(&self, main: &MainOptions) -> Result<()> {
+        anyhow::ensure!(!main.multivalue, "--multivalue is not supported for TinyGo");
+        ensure_command(&["tinygo"])?;
+        let mut tinygo = Command::new("../../scripts/wrapper.sh");
+        tinygo.args(["tinygo", "build", "-target=wasm-unknown", "-o", "../../target/applet.wasm"]);
+        if main.release {
+            tinygo.arg("-no-debug");
+        }
+        tinygo.arg(format!("./{}", self.name));
+        tinygo.current_dir(format!("examples/{}", self.lang));
+        execute_command(&mut tinygo)?;
+        self.execute_wasm(main)
+    }
+
     fn execute_wasm(&self, main: &MainOptions) -> Result<()> {
         let wasm = "target/applet.wasm";
-        if main.size {
+        let size = main.size && !dry_run();
+        if size {
             println!("Initial applet size: {}", std::fs::metadata(wasm)?.len());
         }
         if self.strip.get() {
@@ -340,7 +649,7 @@ impl AppletOptions {
             strip.arg("wasm-strip");
             strip.arg(wasm);
             execute_command(&mut strip)?;
-            if main.size {
+            if size {
                 println!("Stripped applet size: {}", std::fs::metadata(wasm)?.len());
             }
         }
@@ -357,7 +666,7 @@ impl AppletOptions {
             ]);
             opt.args([wasm, "-o", wasm]);
             execute_command(&mut opt)?;
-            if main.size {
+            if size {
                 println!("Optimized applet size: {}", std::fs::metadata(wasm)?.len());
             }
         }
@@ -382,14 +691,49 @@ impl AppletCommand {
                 wasm.map(|x| twiggy.arg(x));
                 execute_command(&mut twiggy)
             }
+            AppletCommand::Test { .. } => unreachable!("tests don't compile the applet"),
         }
     }
 }
 
 impl Runner {
     fn execute(&self, main: &MainOptions) -> Result<()> {
-        self.options.execute(main, false)?;
-        Ok(())
+        let mut runners = vec![self.options.clone()];
+        for name in &self.others {
+            runners.push(RunnerOptions { name: name.clone(), ..self.options.clone() });
+        }
+        execute_parallel(main.jobs, runners.into_iter().map(|x| move || x.execute(main, false)))
+    }
+}
+
+impl VerifyReproducible {
+    fn execute(&self, main: &MainOptions) -> Result<()> {
+        let root = std::env::current_dir()?.join("target/reproducible");
+        let mut elfs = Vec::new();
+        for build in ["first", "second"] {
+            let dir = root.join(build).to_str().context("non UTF-8 target directory")?.to_string();
+            if Path::new(&dir).exists() && !dry_run() {
+                std::fs::remove_dir_all(&dir)?;
+            }
+            let options = RunnerOptions { target_dir: Some(dir), ..self.options.clone() };
+            options.execute(main, false)?;
+            elfs.push(options.board_target());
+        }
+        if dry_run() {
+            return Ok(());
+        }
+        let first = std::fs::read(&elfs[0])?;
+        let second = std::fs::read(&elfs[1])?;
+        let first_hash = Sha256::digest(&first);
+        if first_hash == Sha256::digest(&second) {
+            println!("The {} runner is reproducible (sha256 {first_hash:x}).", self.options.name);
+            return Ok(());
+        }
+        let section = match differing_section(&first, &second)? {
+            Some(x) => format!("section {x}"),
+            None => "headers".to_string(),
+        };
+        anyhow::bail!("{} and {} differ in their {section}", elfs[0], elfs[1])
     }
 }
 
@@ -397,7 +741,8 @@ impl RunnerOptions {
     fn execute(&self, main: &MainOptions, run: bool) -> Result<()> {
         let mut cargo = Command::new("cargo");
         let mut rustflags = Vec::new();
-        if run && self.name == "host" {
+        // When spawned, the host runner is built and then run directly (see below).
+        if run && self.name == "host" && !self.spawn.get() {
             cargo.arg("run");
         } else {
             cargo.arg("build");
@@ -427,15 +772,32 @@ impl RunnerOptions {
                 rustflags.push("-C debuginfo=2".to_string());
             }
         }
-        rustflags.push(format!("-C opt-level={}", self.opt_level));
+        if self.opt_level_override.is_empty() {
+            rustflags.push(format!("-C opt-level={}", self.opt_level));
+        } else {
+            // RUSTFLAGS would apply to all crates, so we use the profile instead.
+            let profile = "profile.release";
+            cargo.arg(format!("--config={profile}.opt-level={}", self.opt_level.to_toml()));
+            for OptLevelOverride { name, level } in &self.opt_level_override {
+                let key = format!("{profile}.package.{name}.opt-level");
+                cargo.arg(format!("--config={key}={}", level.to_toml()));
+            }
+        }
         if main.release {
             cargo.arg("--features=release");
         } else {
             cargo.arg("--features=debug");
         }
-        if self.no_default_features {
+        if main.multivalue {
+            cargo.arg("--features=multivalue");
+        }
+        // The default features of the nordic runner select the chip.
+        if self.no_default_features || self.name == "nordic" {
             cargo.arg("--no-default-features");
         }
+        if self.name == "nordic" {
+            cargo.arg(format!("--features={}", self.chip_feature()?));
+        }
         for features in &self.features {
             cargo.arg(format!("--features={features}"));
         }
@@ -447,13 +809,27 @@ impl RunnerOptions {
             rustflags.push("-C link-arg=-Tstack-sizes.x".to_string());
         }
         cargo.env("RUSTFLAGS", rustflags.join(" "));
+        if let Some(dir) = &self.target_dir {
+            cargo.env("CARGO_TARGET_DIR", dir);
+        }
         cargo.current_dir(format!("crates/runner-{}", self.name));
         if run && self.name == "host" {
             let path = Path::new("target/storage.bin");
-            if self.erase_flash && path.exists() {
+            if self.erase_flash && path.exists() && !dry_run() {
                 std::fs::remove_file(path)?;
             }
-            replace_command(cargo);
+            anyhow::ensure!(self.erase_region.is_none(), "--erase-region is not supported on host");
+            if !self.spawn.get() {
+                return self.run_command(cargo);
+            }
+            // Killing `cargo run` would not kill the runner, so we spawn the runner ourselves.
+            execute_command(&mut cargo)?;
+            let mut runner = Command::new(std::env::current_dir()?.join(self.board_target()));
+            runner.current_dir("crates/runner-host");
+            if let Some(log) = &self.log {
+                runner.env(self.log_env(), log);
+            }
+            return self.run_command(runner);
         } else {
             execute_command(&mut cargo)?;
         }
@@ -486,7 +862,7 @@ impl RunnerOptions {
             size.arg(&elf);
             execute_command(&mut size)?;
         }
-        if let Some(stack_sizes) = self.stack_sizes {
+        if let Some(stack_sizes) = self.stack_sizes.filter(|_| !dry_run()) {
             let elf = std::fs::read(&elf)?;
             let symbols = stack_sizes::analyze_executable(&elf).unwrap();
             assert!(symbols.have_32_bit_addresses);
@@ -506,29 +882,54 @@ impl RunnerOptions {
                 }
             }
             while let Some((Reverse(stack), address, name)) = top_stack_sizes.pop() {
-                println!("{:#010x}\t{}\t{}", address, stack, demangle(name));
+                match self.stack_sizes_format {
+                    StackSizesFormat::Table => {
+                        println!("{:#010x}\t{}\t{}", address, stack, demangle(name))
+                    }
+                    // Semicolons separate frames in the folded format.
+                    StackSizesFormat::Folded => {
+                        println!("{} {}", demangle(name).to_string().replace(';', ","), stack)
+                    }
+                }
             }
         }
         if !run {
             return Ok(());
         }
-        let chip = match self.name.as_str() {
-            "nordic" => "nRF52840_xxAA",
-            "host" => unreachable!(),
-            _ => unimplemented!(),
-        };
-        if self.erase_flash {
+        let chip = self.chip();
+        if (self.erase_flash || self.erase_region.is_some()) && !dry_run() {
             let mut session = Session::auto_attach(
                 TargetSelector::Unspecified(chip.to_string()),
                 Permissions::default(),
             )?;
-            eprintln!("Erasing the flash of {}", session.target().name);
-            flashing::erase_all(&mut session, None)?;
+            if self.erase_flash {
+                eprintln!("Erasing the flash of {}", session.target().name);
+                flashing::erase_all(&mut session, None)?;
+            }
+            if let Some(region) = self.erase_region {
+                erase_region(&mut session, region)?;
+            }
         }
         if self.gdb {
             println!("Use the following 2 commands in different terminals:");
-            println!("JLinkGDBServer -device {chip} -if swd -speed 4000 -port 2331");
-            println!("gdb-multiarch -ex 'file {elf}' -ex 'target remote localhost:2331'");
+            println!("JLinkGDBServer -device {chip} -if swd -speed 4000 -port {GDB_PORT}");
+            println!("gdb-multiarch -ex 'file {elf}' -ex 'target remote localhost:{GDB_PORT}'");
+        }
+        if self.gdb_attach {
+            let mut server = Command::new("JLinkGDBServer");
+            server.args(["-device", chip, "-if", "swd", "-speed", "4000"]);
+            server.args(["-port", &GDB_PORT.to_string()]);
+            // The server exits when GDB disconnects.
+            server.arg("-singlerun");
+            eprintln!("{server:?}");
+            if !dry_run() {
+                let mut server = server.spawn()?;
+                wait_for_port(&mut server, GDB_PORT)?;
+            }
+            let mut gdb = Command::new("gdb-multiarch");
+            gdb.args(["-ex", &format!("file {elf}")]);
+            gdb.args(["-ex", &format!("target remote localhost:{GDB_PORT}")]);
+            return self.run_command(gdb);
         }
         let mut probe_run = Command::new("./scripts/wrapper.sh");
         probe_run.arg("probe-run");
@@ -539,8 +940,34 @@ impl RunnerOptions {
         if self.measure_stack {
             probe_run.arg("--measure-stack");
         }
+        if self.verify {
+            probe_run.arg("--verify");
+        }
         probe_run.arg(elf);
-        replace_command(probe_run);
+        self.run_command(probe_run)
+    }
+
+    /// Runs a command in place of xtask, unless it should be spawned.
+    fn run_command(&self, mut command: Command) -> Result<()> {
+        if !self.spawn.get() {
+            replace_command(command);
+        }
+        eprintln!("{command:?}");
+        if !dry_run() {
+            *self.child.lock().unwrap() = Some(command.spawn()?);
+        }
+        Ok(())
+    }
+
+    /// Stops the spawned runner, if any.
+    ///
+    /// The spawned command is the runner itself (or exec's into it), so killing it is enough.
+    fn stop(&self) -> Result<()> {
+        if let Some(mut child) = self.child.lock().unwrap().take() {
+            child.kill()?;
+            child.wait()?;
+        }
+        Ok(())
     }
 
     fn target(&self) -> &'static str {
@@ -570,26 +997,159 @@ impl RunnerOptions {
         }
     }
 
+    fn chip(&self) -> &str {
+        if let Some(chip) = &self.chip {
+            return chip;
+        }
+        match self.name.as_str() {
+            "nordic" => "nRF52840_xxAA",
+            "host" => unreachable!(),
+            _ => unimplemented!(),
+        }
+    }
+
+    fn chip_feature(&self) -> Result<&'static str> {
+        let chip = self.chip();
+        if chip.starts_with("nRF52840") {
+            Ok("nrf52840")
+        } else if chip.starts_with("nRF52833") {
+            Ok("nrf52833")
+        } else {
+            anyhow::bail!("unsupported chip {chip} for the {} runner", self.name)
+        }
+    }
+
     fn board_target(&self) -> String {
-        format!("target/{}/release/runner-{}", self.target(), self.name)
+        let dir = self.target_dir.as_deref().unwrap_or("target");
+        format!("{dir}/{}/release/runner-{}", self.target(), self.name)
     }
 }
 
-fn wasm_target(name: &str) -> String {
-    format!("target/wasm32-unknown-unknown/release/{name}.wasm")
+/// Waits until a server listens on a local port.
+fn wait_for_port(server: &mut Child, port: u16) -> Result<()> {
+    for _ in 0 .. 100 {
+        if let Some(status) = server.try_wait()? {
+            anyhow::bail!("GDB server exited with {status}");
+        }
+        // Binding fails once the server listens. We don't connect because the server would
+        // consider it as its single run.
+        if TcpListener::bind(("localhost", port)).is_err() {
+            return Ok(());
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    server.kill()?;
+    anyhow::bail!("GDB server did not listen on port {port}")
+}
+
+/// Erases the flash sectors of a region.
+fn erase_region(session: &mut Session, region: FlashRegion) -> Result<()> {
+    let end = region.start.checked_add(region.len).context("region overflows")?;
+    let (first, count) = {
+        let properties = session
+            .target()
+            .flash_algorithms
+            .iter()
+            .map(|x| &x.flash_properties)
+            .find(|x| x.address_range.contains(&region.start) && end <= x.address_range.end)
+            .with_context(|| format!("region {region} is not in flash"))?;
+        let [sector] = &properties.sectors[..] else {
+            anyhow::bail!("non-uniform flash sectors are not supported");
+        };
+        let offset = region.start - properties.address_range.start;
+        anyhow::ensure!(
+            offset % sector.size == 0 && region.len % sector.size == 0,
+            "region {region} is not aligned to {:#x} bytes sectors",
+            sector.size
+        );
+        (offset / sector.size, region.len / sector.size)
+    };
+    eprintln!("Erasing {count} sectors at {:#x} of {}", region.start, session.target().name);
+    flashing::erase_sectors(session, None, first as usize, count as usize)?;
+    Ok(())
+}
+
+fn wasm_target(target: WasmTarget, name: &str) -> String {
+    format!("target/{target}/release/{name}.wasm")
+}
+
+/// Exit code of a failed command.
+#[derive(Debug)]
+struct ExitCode(i32);
+
+impl Display for ExitCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "command failed with exit code {}", self.0)
+    }
+}
+
+impl std::error::Error for ExitCode {}
+
+/// Whether commands are printed instead of executed.
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+
+fn dry_run() -> bool {
+    DRY_RUN.load(Ordering::Relaxed)
 }
 
 fn execute_command(command: &mut Command) -> Result<()> {
     eprintln!("{command:?}");
+    if dry_run() {
+        return Ok(());
+    }
     let code = command.spawn()?.wait()?.code().expect("no error code");
     if code != 0 {
-        std::process::exit(code);
+        // This is not fatal when watching. Otherwise, xtask exits with the same code.
+        return Err(ExitCode(code).into());
     }
     Ok(())
 }
 
+/// Runs independent tasks with at most `jobs` of them in parallel.
+///
+/// No task is started after one fails, in which case the first error is returned once the running
+/// tasks complete.
+fn execute_parallel<T>(jobs: usize, tasks: impl Iterator<Item = T> + Send) -> Result<()>
+where T: FnOnce() -> Result<()> + Send {
+    let tasks = Mutex::new(tasks);
+    let error = Mutex::new(None);
+    std::thread::scope(|s| {
+        for _ in 0 .. jobs.max(1) {
+            s.spawn(|| {
+                while error.lock().unwrap().is_none() {
+                    let Some(task) = tasks.lock().unwrap().next() else { break };
+                    if let Err(e) = task() {
+                        error.lock().unwrap().get_or_insert(e);
+                    }
+                }
+            });
+        }
+    });
+    match error.into_inner().unwrap() {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+/// Waits until a source file changes and no other change happens for a short while.
+fn wait_for_changes(receiver: &Receiver<notify::Result<notify::Event>>) -> Result<()> {
+    // Build outputs are ignored, otherwise each build would trigger a rebuild.
+    let is_source = |path: &Path| !path.components().any(|x| x.as_os_str() == "target");
+    loop {
+        let event = receiver.recv()??;
+        if !event.kind.is_access() && event.paths.iter().any(|x| is_source(x)) {
+            break;
+        }
+    }
+    while receiver.recv_timeout(Duration::from_millis(200)).is_ok() {}
+    Ok(())
+}
+
 fn replace_command(mut command: Command) -> ! {
     eprintln!("{command:?}");
+    if dry_run() {
+        std::process::exit(0);
+    }
     panic!("{}", command.exec());
 }
 
@@ -608,10 +1168,212 @@ fn ensure_command(cmd: &[&str]) -> Result<()> {
     execute_command(&mut ensure_bloat)
 }
 
+/// Name of the custom section holding the applet manifest.
+const MANIFEST_SECTION: &str = "wasefire-manifest";
+
+/// Returns the applet manifest from the Cargo.toml of an applet.
+///
+/// The name and version come from the `[package]` table. The required board capabilities and the
+/// granted permissions come from the `capabilities` and `permissions` arrays of the
+/// `[package.metadata.wasefire]` table, which must fit on one line.
+fn read_manifest(cargo_toml: &str) -> Result<String> {
+    let unquote = |x: &str| -> Result<String> {
+        let x = x.strip_prefix('"').and_then(|x| x.strip_suffix('"'));
+        Ok(x.with_context(|| format!("expected a string in {cargo_toml}"))?.to_string())
+    };
+    let mut table = String::new();
+    let mut name = None;
+    let mut version = None;
+    let mut capabilities = Vec::new();
+    let mut permissions = Vec::new();
+    for line in std::fs::read_to_string(cargo_toml)?.lines() {
+        let line = line.trim();
+        if let Some(x) = line.strip_prefix('[').and_then(|x| x.strip_suffix(']')) {
+            table = x.to_string();
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else { continue };
+        match (table.as_str(), key.trim()) {
+            ("package", "name") => name = Some(unquote(value.trim())?),
+            ("package", "version") => version = Some(unquote(value.trim())?),
+            ("package.metadata.wasefire", key @ ("capabilities" | "permissions")) => {
+                let value = value.trim().strip_prefix('[').and_then(|x| x.strip_suffix(']'));
+                let value = value.with_context(|| format!("{key} must be an array on one line"))?;
+                let values = match key {
+                    "capabilities" => &mut capabilities,
+                    _ => &mut permissions,
+                };
+                for x in value.split(',').map(|x| x.trim()).filter(|x| !x.is_empty()) {
+                    values.push(unquote(x)?);
+                }
+            }
+            _ => (),
+        }
+    }
+    let name = name.with_context(|| format!("no package name in {cargo_toml}"))?;
+    let version = version.with_context(|| format!("no package version in {cargo_toml}"))?;
+    let capabilities = capabilities.join(",");
+    let permissions = permissions.join(",");
+    Ok(format!(
+        "name={name}\nversion={version}\ncapabilities={capabilities}\npermissions={permissions}\n"
+    ))
+}
+
+/// Writes the manifest in a custom section at the beginning of an applet.
+///
+/// A manifest previously written by this function is replaced.
+fn write_manifest(wasm: &str, manifest: &str) -> Result<()> {
+    let mut name = Vec::new();
+    write_leb128(MANIFEST_SECTION.len(), &mut name);
+    name.extend_from_slice(MANIFEST_SECTION.as_bytes());
+    let mut section = vec![0];
+    write_leb128(name.len() + manifest.len(), &mut section);
+    section.extend_from_slice(&name);
+    section.extend_from_slice(manifest.as_bytes());
+    if dry_run() {
+        return Ok(());
+    }
+    let mut content = std::fs::read(wasm)?;
+    anyhow::ensure!(content.starts_with(b"\0asm"), "{wasm} is not a WebAssembly module");
+    // Remove the previous manifest, if any.
+    if content.get(8) == Some(&0) {
+        if let Some((size, len)) = read_leb128(&content[9 ..]) {
+            let start = 9 + len;
+            if content.get(start ..).map_or(false, |x| x.starts_with(&name)) {
+                content.drain(8 .. start + size);
+            }
+        }
+    }
+    content.splice(8 .. 8, section);
+    std::fs::write(wasm, content)?;
+    Ok(())
+}
+
+/// Name of the custom section holding the applet signature.
+const SIGNATURE_SECTION: &str = "wasefire-signature";
+
+/// Reads an Ed25519 private key.
+///
+/// The key is read from a file (32 bytes) if provided, and otherwise from the `WASEFIRE_SIGN_KEY`
+/// environment variable (64 hexadecimal digits).
+fn read_signing_key(path: Option<&str>) -> Result<SigningKey> {
+    let key = match path {
+        Some(path) => std::fs::read(path)?,
+        None => {
+            let key = std::env::var("WASEFIRE_SIGN_KEY")
+                .context("WASEFIRE_SIGN_KEY must be set without --key")?;
+            let key: Option<Vec<u8>> = (0 .. key.len())
+                .step_by(2)
+                .map(|i| key.get(i .. i + 2).and_then(|x| u8::from_str_radix(x, 16).ok()))
+                .collect();
+            key.context("WASEFIRE_SIGN_KEY is not hexadecimal")?
+        }
+    };
+    let key: [u8; 32] =
+        key.try_into().map_err(|_| anyhow::anyhow!("the private key must be 32 bytes"))?;
+    Ok(SigningKey::from_bytes(&key))
+}
+
+/// Returns the header of the signature section (everything but the signature).
+fn signature_header() -> Vec<u8> {
+    let mut header = vec![0];
+    write_leb128(1 + SIGNATURE_SECTION.len() + SIGNATURE_LENGTH, &mut header);
+    write_leb128(SIGNATURE_SECTION.len(), &mut header);
+    header.extend_from_slice(SIGNATURE_SECTION.as_bytes());
+    header
+}
+
+/// Splits a signed applet into its signed bytes and its signature.
+///
+/// Returns `None` if the applet does not end with a signature section.
+fn split_signature(content: &[u8]) -> Option<(&[u8], &[u8; SIGNATURE_LENGTH])> {
+    let header = signature_header();
+    let start = content.len().checked_sub(header.len() + SIGNATURE_LENGTH)?;
+    let (message, section) = content.split_at(start);
+    let signature = section.strip_prefix(header.as_slice())?;
+    Some((message, signature.try_into().unwrap()))
+}
+
+/// Signs an applet, replacing its previous signature if any.
+///
+/// The signature covers all the preceding bytes of the applet, so this must be the last change to
+/// the applet.
+fn sign_applet(content: &[u8], key: &SigningKey) -> Vec<u8> {
+    let message = split_signature(content).map_or(content, |(x, _)| x);
+    let mut result = message.to_vec();
+    result.extend_from_slice(&signature_header());
+    result.extend_from_slice(&key.sign(message).to_bytes());
+    result
+}
+
+/// Writes the signature in a custom section at the end of an applet.
+///
+/// The public key is printed such that it can be pinned in the runner (see `WASEFIRE_APPLET_KEY`).
+fn write_signature(wasm: &str, key: &SigningKey) -> Result<()> {
+    if dry_run() {
+        return Ok(());
+    }
+    let content = std::fs::read(wasm)?;
+    anyhow::ensure!(content.starts_with(b"\0asm"), "{wasm} is not a WebAssembly module");
+    std::fs::write(wasm, sign_applet(&content, key))?;
+    let public: String =
+        key.verifying_key().as_bytes().iter().map(|x| format!("{x:02x}")).collect();
+    println!("Signed {wasm} with public key {public}.");
+    Ok(())
+}
+
+fn write_leb128(mut x: usize, output: &mut Vec<u8>) {
+    while 0x80 <= x {
+        output.push(0x80 | (x & 0x7f) as u8);
+        x >>= 7;
+    }
+    output.push(x as u8);
+}
+
+/// Returns the value and length of a LEB128 number.
+fn read_leb128(input: &[u8]) -> Option<(usize, usize)> {
+    let mut x = 0;
+    for (i, byte) in input.iter().enumerate().take(5) {
+        x |= ((byte & 0x7f) as usize) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some((x, i + 1));
+        }
+    }
+    None
+}
+
+/// Returns the name of the first section differing between 2 ELF files.
+///
+/// Returns `None` if all sections are identical (the difference is elsewhere).
+fn differing_section(first: &[u8], second: &[u8]) -> Result<Option<String>> {
+    let first = object::File::parse(first)?;
+    let second = object::File::parse(second)?;
+    for section in first.sections() {
+        let name = section.name()?;
+        let other = match second.section_by_name(name) {
+            Some(x) => x,
+            None => return Ok(Some(format!("{name} (missing in the second build)"))),
+        };
+        if section.address() != other.address() || section.data()? != other.data()? {
+            return Ok(Some(name.to_string()));
+        }
+    }
+    for section in second.sections() {
+        let name = section.name()?;
+        if first.section_by_name(name).is_none() {
+            return Ok(Some(format!("{name} (missing in the first build)")));
+        }
+    }
+    Ok(None)
+}
+
 /// Copies a file if its destination .hash changed.
 ///
-/// Returns whether the copy took place.
+/// Returns whether the copy took place (always in dry-run mode).
 fn copy_if_changed(src: &str, dst: &str) -> Result<bool> {
+    if dry_run() {
+        return Ok(true);
+    }
     let dst_file = format!("{dst}.hash");
     let src_hash = Sha256::digest(std::fs::read(src)?);
     let changed = !Path::new(dst).exists()
@@ -626,6 +1388,45 @@ fn copy_if_changed(src: &str, dst: &str) -> Result<bool> {
 
 fn main() -> Result<()> {
     env_logger::init_from_env(env_logger::Env::new().default_filter_or("warn"));
-    Flags::parse().execute()?;
+    if let Err(error) = Flags::parse().execute() {
+        if let Some(ExitCode(code)) = error.downcast_ref() {
+            std::process::exit(*code);
+        }
+        return Err(error);
+    }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::Signature;
+
+    use super::*;
+
+    #[test]
+    fn sign_verify_round_trip() {
+        let key = SigningKey::from_bytes(&[0x42; 32]);
+        // Module with an empty type section.
+        let applet = b"\0asm\x01\0\0\0\x01\x01\x00";
+        let signed = sign_applet(applet, &key);
+        let (message, signature) = split_signature(&signed).unwrap();
+        assert_eq!(message, applet);
+        let signature = Signature::from_bytes(signature);
+        assert!(key.verifying_key().verify_strict(message, &signature).is_ok());
+        assert!(key.verifying_key().verify_strict(b"\0asm\x01\0\0\0", &signature).is_err());
+        // Signing again replaces the signature.
+        assert_eq!(sign_applet(&signed, &key), signed);
+        assert_eq!(split_signature(applet), None);
+    }
+
+    #[test]
+    fn scheduler_finds_signature() {
+        use wasefire_scheduler::signature::Signed;
+        let key = SigningKey::from_bytes(&[0x42; 32]);
+        let applet = b"\0asm\x01\0\0\0\x01\x01\x00";
+        let signed = sign_applet(applet, &key);
+        let Signed { message, signature } = Signed::find(&signed).unwrap().unwrap();
+        assert_eq!(message, applet);
+        assert_eq!(signature, &key.sign(applet).to_bytes());
+    }
+}
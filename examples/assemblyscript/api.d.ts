@@ -0,0 +1,1533 @@
+// Copyright 2022 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+/**
+ * Version of the applet API (1).
+ */
+export declare const API_VERSION: number;
+
+/**
+ * Returns how many analog inputs are on the device.
+ *
+ * @returns How many analog inputs are on the device.
+ */
+export declare function adc_count(): number;
+
+/**
+ * Samples an analog input.
+ *
+ * @param adc Index of the analog input to sample.
+ * @returns The sample normalized to 16 bits, or a negative value on error.
+ *
+ * A sample of 0 is ground and 65535 is the reference voltage.
+ */
+export declare function adc_read(adc: number): number;
+
+/**
+ * Measures the supply voltage.
+ *
+ * @returns The supply voltage in millivolts, or a negative value on error.
+ */
+export declare function adc_read_vdd(): number;
+
+/**
+ * Describes the state of a button.
+ */
+export declare enum button_State {
+  /**
+   * The button is released.
+   */
+  Released,
+  /**
+   * The button is pressed.
+   */
+  Pressed,
+}
+
+/**
+ * Describes a button gesture.
+ */
+export declare enum button_Gesture {
+  /**
+   * The button was pressed and released once.
+   */
+  Single,
+  /**
+   * The button was held pressed for at least the long-press threshold.
+   */
+  Long,
+  /**
+   * The button was pressed twice within the double-press window.
+   */
+  Double,
+}
+
+/**
+ * Returns how many buttons are on the device.
+ *
+ * @returns How many buttons are on the device.
+ */
+export declare function button_count(): number;
+
+/**
+ * Register a handler for button events.
+ *
+ * @param button Index of the button to listen to.
+ * @param handler_func Function called on button events.
+ *
+ * The function takes its opaque `data` and the new button `state` as arguments.
+ * If gestures are configured for the button, the function is called with a
+ * `Gesture` instead of a `State`.
+ * @param handler_data The opaque data to use when calling the handler function.
+ */
+export declare function button_register(button: number, handler_func: number, handler_data: number): void;
+
+/**
+ * Configures gesture recognition for a button.
+ *
+ * When configured, the button handler is called on gestures instead of state changes.
+ *
+ * @param button Index of the button to configure.
+ * @param long Minimum duration in milliseconds of a long press.
+ *
+ * Zero disables gesture recognition for the button.
+ * @param double Maximum duration in milliseconds between a release and the next press for a
+ * double press.
+ *
+ * Zero disables double presses such that single presses are reported on release.
+ * @returns Zero on success, -1 if there are no timers available.
+ */
+export declare function button_gesture(button: number, long: number, double: number): number;
+
+/**
+ * Unregister handlers for button events.
+ *
+ * @param button Index of the button to stop listening to.
+ */
+export declare function button_unregister(button: number): void;
+
+/**
+ * Starts measuring the frequency and duty cycle of the signal on a GPIO.
+ *
+ * The measurement is executed by the board. Register a handler to be notified when
+ * the measurement is done.
+ *
+ * @param gpio Index of the GPIO.
+ * @returns Zero on success, negative on error.
+ *
+ * It is an error to start a measurement while another one is running.
+ */
+export declare function capture_start(gpio: number): number;
+
+/**
+ * Reads the result of the done measurement of a GPIO.
+ *
+ * @param gpio Index of the GPIO.
+ * @param ptr Pointer to the 8 bytes of the measurement.
+ *
+ * The period and the high time of the signal in nanoseconds as little-endian u32.
+ * @returns One if the signal was measured, zero if there is no signal, and negative on
+ * error.
+ *
+ * It is an error to read the result of a measurement that is not done.
+ */
+export declare function capture_result(gpio: number, ptr: number): number;
+
+/**
+ * Register a handler for input capture events.
+ *
+ * @param gpio Index of the GPIO to listen to.
+ * @param handler_func Function called when a measurement is done.
+ *
+ * The function takes its opaque `data` as argument.
+ * @param handler_data The opaque data to use when calling the handler function.
+ */
+export declare function capture_register(gpio: number, handler_func: number, handler_data: number): void;
+
+/**
+ * Unregister handlers for input capture events.
+ *
+ * @param gpio Index of the GPIO to stop listening to.
+ */
+export declare function capture_unregister(gpio: number): void;
+
+/**
+ * Whether a timer should periodically trigger.
+ */
+export declare enum clock_Mode {
+  /**
+   * The timer fires only once.
+   */
+  Oneshot,
+  /**
+   * The timer fires periodically.
+   */
+  Periodic,
+}
+
+/**
+ * Allocates a timer (initially stopped).
+ *
+ * @param handler_func Function called when the timer triggers.
+ * @param handler_data The opaque data to use when calling the handler function.
+ * @returns Identifier for this timer.
+ */
+export declare function clock_allocate(handler_func: number, handler_data: number): number;
+
+/**
+ * Starts a stopped timer given its id.
+ *
+ * @param id The identifier of the timer to start.
+ *
+ * It must come from an allocated timer that wasn't stopped.
+ * @param mode Whether the timer should periodically fire.
+ *
+ * Valid values are defined by [`Mode`](super::Mode).
+ * @param duration_ms How long until the timer triggers in milli-seconds.
+ */
+export declare function clock_start(id: number, mode: number, duration_ms: number): void;
+
+/**
+ * Stops a running timer given its id.
+ *
+ * Note that if the timer triggers while being stopped, the handler may still be
+ * called.
+ *
+ * @param id The identifier of the timer to start.
+ */
+export declare function clock_stop(id: number): void;
+
+/**
+ * Deallocates a stopped timer given its id.
+ *
+ * @param id The identifier of the timer to start.
+ */
+export declare function clock_free(id: number): void;
+
+/**
+ * Returns the time since the platform started.
+ *
+ * @param ptr Pointer to the 8 bytes of the uptime in micro-seconds (little-endian).
+ * @returns Zero on success, negative on error.
+ */
+export declare function clock_uptime(ptr: number): number;
+
+/**
+ * Describes errors on cryptographic operations.
+ */
+export declare enum crypto_Error {
+  /**
+   * A function pre-condition was broken.
+   */
+  InvalidArgument,
+  /**
+   * An operation is unsupported.
+   */
+  Unsupported,
+}
+
+/**
+ * Whether AES-CCM is supported.
+ *
+ * @returns 1 if supported, 0 otherwise.
+ */
+export declare function crypto_ccm_is_supported(): number;
+
+/**
+ * Encrypts a clear text given a key and IV.
+ *
+ * @param key The 16 bytes key to encrypt with.
+ * @param iv The 8 bytes IV to encrypt with.
+ * @param len Length in bytes of the `clear` text.
+ *
+ * This must be at most 251 bytes. The `cipher` length must be 4 bytes longer than
+ * this value.
+ * @param clear The clear text to encrypt from.
+ *
+ * Its length must be provided in the `len` field.
+ * @param cipher The cipher text to encrypt to.
+ *
+ * Its length must be `len + 4` bytes.
+ * @returns Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+ * otherwise.
+ */
+export declare function crypto_ccm_encrypt(key: number, iv: number, len: number, clear: number, cipher: number): number;
+
+/**
+ * Decrypts a cipher text given a key and IV.
+ *
+ * @param key The 16 bytes key to encrypt with.
+ * @param iv The 8 bytes IV to encrypt with.
+ * @param len Length in bytes of the `clear` text.
+ *
+ * This must be at most 251 bytes. The `cipher` length must be 4 bytes longer than
+ * this value.
+ * @param cipher The cipher text to encrypt from.
+ *
+ * Its length must be `len + 4` bytes.
+ * @param clear The clear text to encrypt to.
+ *
+ * Its length must be provided in the `len` field.
+ * @returns Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+ * otherwise.
+ */
+export declare function crypto_ccm_decrypt(key: number, iv: number, len: number, cipher: number, clear: number): number;
+
+/**
+ * Whether CRCs are supported.
+ *
+ * @returns 1 if supported, 0 otherwise.
+ */
+export declare function crypto_crc_is_supported(): number;
+
+/**
+ * Computes the CRC-32 of some data.
+ *
+ * @param data The pointer to the data.
+ * @param length The length of the data.
+ * @param crc The 4 bytes checksum.
+ * @returns Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+ * otherwise.
+ */
+export declare function crypto_crc_crc32(data: number, length: number, crc: number): number;
+
+/**
+ * Computes the CRC-16/CCITT of some data.
+ *
+ * @param data The pointer to the data.
+ * @param length The length of the data.
+ * @param crc The 2 bytes checksum.
+ * @returns Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+ * otherwise.
+ */
+export declare function crypto_crc_crc16_ccitt(data: number, length: number, crc: number): number;
+
+/**
+ * Whether ECDH over P-256 is supported.
+ *
+ * @returns 1 if supported, 0 otherwise.
+ */
+export declare function crypto_ecdh_is_supported(): number;
+
+/**
+ * Generates a random key pair.
+ *
+ * @param private The 32 bytes private key.
+ * @param public The 64 bytes public key.
+ * @returns Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+ * otherwise.
+ */
+export declare function crypto_ecdh_generate_keypair(private: number, public: number): number;
+
+/**
+ * Computes the shared secret of a private key and a peer public key.
+ *
+ * @param private The 32 bytes private key.
+ * @param public The 64 bytes public key of the peer.
+ * @param shared The 32 bytes shared secret.
+ * @returns Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+ * otherwise.
+ */
+export declare function crypto_ecdh_agree(private: number, public: number, shared: number): number;
+
+/**
+ * Whether AES-256-GCM is supported.
+ *
+ * @returns 1 if supported, 0 otherwise.
+ */
+export declare function crypto_gcm_is_supported(): number;
+
+/**
+ * Encrypts and authenticates a clear text with associated data given a key and IV.
+ *
+ * @param key The 32 bytes key.
+ * @param iv The 12 bytes IV.
+ * @param aad The additional authenticated data.
+ * @param aad_len The length of the additional authenticated data.
+ * @param length The length of the clear (and cipher) text.
+ * @param clear The clear text.
+ * @param cipher The cipher text.
+ * @param tag The 16 bytes authentication tag.
+ * @returns Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+ * otherwise.
+ */
+export declare function crypto_gcm_encrypt(key: number, iv: number, aad: number, aad_len: number, length: number, clear: number, cipher: number, tag: number): number;
+
+/**
+ * Decrypts and authenticates a cipher text with associated data given a key and IV.
+ *
+ * @param key The 32 bytes key.
+ * @param iv The 12 bytes IV.
+ * @param aad The additional authenticated data.
+ * @param aad_len The length of the additional authenticated data.
+ * @param tag The 16 bytes authentication tag.
+ * @param length The length of the cipher (and clear) text.
+ * @param cipher The cipher text.
+ * @param clear The clear text.
+ * @returns Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+ * otherwise.
+ */
+export declare function crypto_gcm_decrypt(key: number, iv: number, aad: number, aad_len: number, tag: number, length: number, cipher: number, clear: number): number;
+
+/**
+ * Hash algorithm.
+ */
+export declare enum crypto_hash_Algorithm {
+  /**
+   * SHA-256.
+   */
+  Sha256,
+}
+
+/**
+ * Whether the algorithm is supported.
+ *
+ * @param algorithm The hash algorithm.
+ * @returns 1 if supported, 0 otherwise.
+ */
+export declare function crypto_hash_is_supported(algorithm: number): number;
+
+/**
+ * Initializes a hash.
+ *
+ * @param algorithm The hash algorithm.
+ * @returns A non-negative identifier on success, bitwise complement of
+ * [`Error`](crate::crypto::Error) otherwise.
+ */
+export declare function crypto_hash_initialize(algorithm: number): number;
+
+/**
+ * Updates a hash.
+ *
+ * Errors are surfaced in the [`finalize()`] call.
+ *
+ * @param id The identifier returned by the associated [`initialize()`] call.
+ * @param data The pointer to the data to hash.
+ * @param length The length of the data to hash.
+ */
+export declare function crypto_hash_update(id: number, data: number, length: number): void;
+
+/**
+ * Finalizes a hash.
+ *
+ * @param id The identifier returned by the associated [`initialize()`] call.
+ *
+ * This is consumed and invalidated by this call regardless of the return value.
+ * @param digest The pointer to the buffer where the digest must be written.
+ *
+ * Its length is defined by the algorithm:
+ * - 32 bytes for SHA-256.
+ *
+ * The pointer may be null, in which case this function deallocates the identifier
+ * without computing the digest.
+ * @returns Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+ * otherwise.
+ */
+export declare function crypto_hash_finalize(id: number, digest: number): number;
+
+/**
+ * Prints a message to the debug output.
+ *
+ * If debug output is disabled then this is a no-op.
+ *
+ * @param ptr The message to print.
+ *
+ * Traps if the message is not valid UTF-8.
+ * @param len The length of the message in bytes.
+ */
+export declare function debug_println(ptr: number, len: number): void;
+
+/**
+ * Reads the heap statistics of the platform.
+ *
+ * @param ptr Pointer to the 12 bytes of statistics.
+ *
+ * The used, free, and high water bytes as little-endian u32.
+ * @returns Zero on success, negative on error.
+ */
+export declare function debug_heap_stats(ptr: number): number;
+
+/**
+ * Exits the platform with an error code.
+ *
+ * This is used by test applets to terminate the platform and propagate the test
+ * result.
+ *
+ * @param code 0 for success, 1 for failure
+ */
+export declare function debug_exit(code: number): void;
+
+/**
+ * Logging levels from the least to the most verbose.
+ */
+export declare enum debug_Level {
+  Error,
+  Warn,
+  Info,
+  Debug,
+  Trace,
+}
+
+/**
+ * Sets the most verbose level of the platform logs.
+ *
+ * Levels disabled when the platform was compiled can't be enabled.
+ *
+ * @param level The most verbose level to log.
+ *
+ * Valid values are defined by [`Level`](super::Level).
+ * @returns Zero on success, negative on error.
+ */
+export declare function debug_set_log_level(level: number): number;
+
+/**
+ * Logs a message at a given level in the platform logs.
+ *
+ * The message interleaves with the platform logs and is subject to the same level
+ * filter. If logging is disabled then this is a no-op.
+ *
+ * @param level The level of the message.
+ *
+ * Valid values are defined by [`Level`](super::Level).
+ * @param ptr The message to log.
+ *
+ * Traps if the message is not valid UTF-8.
+ * @param len The length of the message in bytes.
+ */
+export declare function debug_log(level: number, ptr: number, len: number): void;
+
+/**
+ * Returns how many encoders are on the device.
+ *
+ * @returns How many encoders are on the device.
+ */
+export declare function encoder_count(): number;
+
+/**
+ * Returns the current position of an encoder.
+ *
+ * @param encoder Index of the encoder.
+ * @returns Current position in steps.
+ */
+export declare function encoder_position(encoder: number): number;
+
+/**
+ * Starts homing an encoder.
+ *
+ * The position is zeroed at the next index pulse. The shaft must be rotated while
+ * homing. Register a handler to be notified when homing is done.
+ *
+ * @param encoder Index of the encoder.
+ * @returns Error code: 0 on success, -1 on error
+ */
+export declare function encoder_home(encoder: number): number;
+
+/**
+ * Register a handler for encoder events.
+ *
+ * @param encoder Index of the encoder to listen to.
+ * @param handler_func Function called when homing is done.
+ *
+ * The function takes its opaque `data` as argument.
+ * @param handler_data The opaque data to use when calling the handler function.
+ */
+export declare function encoder_register(encoder: number, handler_func: number, handler_data: number): void;
+
+/**
+ * Unregister handlers for encoder events.
+ *
+ * @param encoder Index of the encoder to stop listening to.
+ */
+export declare function encoder_unregister(encoder: number): void;
+
+/**
+ * Returns how many GPIOs are on the device.
+ *
+ * @returns How many GPIOs are on the device.
+ */
+export declare function gpio_count(): number;
+
+/**
+ * Describes the configuration of a GPIO.
+ */
+export declare enum gpio_Mode {
+  /**
+   * Input with an internal pull-up resistor.
+   */
+  InputPullUp,
+  /**
+   * Input with an internal pull-down resistor.
+   */
+  InputPullDown,
+  /**
+   * Output driving both levels.
+   */
+  OutputPushPull,
+  /**
+   * Output driving only the low level.
+   */
+  OutputOpenDrain,
+}
+
+/**
+ * Configures a GPIO.
+ *
+ * @param gpio Index of the GPIO to configure.
+ * @param mode The new mode of the GPIO.
+ *
+ * Valid values are defined by [`Mode`](super::Mode).
+ */
+export declare function gpio_configure(gpio: number, mode: number): void;
+
+/**
+ * Reads the level of a configured GPIO.
+ *
+ * @param gpio Index of the GPIO to read.
+ * @returns 0 for low and 1 for high.
+ */
+export declare function gpio_read(gpio: number): number;
+
+/**
+ * Writes the level of a GPIO configured as output.
+ *
+ * @param gpio Index of the GPIO to write.
+ * @param level 0 for low and 1 for high.
+ */
+export declare function gpio_write(gpio: number, level: number): void;
+
+/**
+ * Configures the bus.
+ *
+ * This must be called before any transfer.
+ *
+ * @param sda Index of the GPIO of the data line.
+ * @param scl Index of the GPIO of the clock line.
+ * @param frequency Clock rate in Hertz.
+ * @returns Zero on success, negative on error.
+ */
+export declare function i2c_sw_configure(sda: number, scl: number, frequency: number): number;
+
+/**
+ * Reads bytes from a device into a buffer.
+ *
+ * @param addr 7-bits address of the device.
+ * @param ptr Address of the buffer.
+ * @param len Length of the buffer in bytes.
+ * @returns Zero on success, negative on error.
+ */
+export declare function i2c_sw_read(addr: number, ptr: number, len: number): number;
+
+/**
+ * Writes bytes to a device from a buffer.
+ *
+ * @param addr 7-bits address of the device.
+ * @param ptr Address of the buffer.
+ * @param len Length of the buffer in bytes.
+ * @returns Zero on success, negative on error.
+ */
+export declare function i2c_sw_write(addr: number, ptr: number, len: number): number;
+
+/**
+ * Writes then reads bytes from a device with a repeated start condition.
+ *
+ * @param addr 7-bits address of the device.
+ * @param wptr Address of the buffer to write.
+ * @param wlen Length of the buffer to write in bytes.
+ * @param rptr Address of the buffer to read.
+ * @param rlen Length of the buffer to read in bytes.
+ * @returns Zero on success, negative on error.
+ */
+export declare function i2c_sw_write_read(addr: number, wptr: number, wlen: number, rptr: number, rlen: number): number;
+
+/**
+ * Returns how many LEDs are on the device.
+ *
+ * @returns How many LEDs are on the device.
+ */
+export declare function led_count(): number;
+
+/**
+ * Describes the state of a LED.
+ */
+export declare enum led_Status {
+  /**
+   * The LED is off.
+   */
+  Off,
+  /**
+   * The LED is on.
+   */
+  On,
+}
+
+/**
+ * Returns a LED status.
+ *
+ * @param led Index of the LED to set.
+ * @returns 0 for off and 1 for on.
+ */
+export declare function led_get(led: number): number;
+
+/**
+ * Sets a LED status.
+ *
+ * @param led Index of the LED to set.
+ * @param status 0 for off and 1 for on.
+ */
+export declare function led_set(led: number, status: number): void;
+
+/**
+ * Sets a LED brightness.
+ *
+ * LEDs without brightness support are on if the brightness is at least 128.
+ *
+ * @param led Index of the LED to set.
+ * @param brightness 0 for off and 255 for fully on.
+ */
+export declare function led_set_brightness(led: number, brightness: number): void;
+
+/**
+ * Animation patterns.
+ */
+export declare enum led_ring_Pattern {
+  /**
+   * A single lit pixel going around the ring.
+   */
+  Spinner,
+  /**
+   * An arc of lit pixels growing around the ring and restarting when full.
+   */
+  Progress,
+  /**
+   * A rainbow rotating around the ring (the color is ignored).
+   */
+  Rainbow,
+}
+
+/**
+ * Returns the number of pixels of the ring.
+ *
+ * @returns How many pixels are available (zero if there is no ring).
+ */
+export declare function led_ring_count(): number;
+
+/**
+ * Starts an animation, replacing the current one if any.
+ *
+ * @param pattern The animation pattern.
+ * @param speed The number of steps per second (zero freezes the first frame).
+ * @param color The color of the lit pixels as 0xRRGGBB.
+ * @returns Zero on success, negative on error.
+ */
+export declare function led_ring_animate(pattern: number, speed: number, color: number): number;
+
+/**
+ * Stops the current animation and turns all pixels off.
+ *
+ * @returns Zero on success, negative on error.
+ */
+export declare function led_ring_stop(): number;
+
+/**
+ * Whether a magnetometer is available.
+ *
+ * @returns 1 if supported, 0 otherwise.
+ */
+export declare function magnetometer_is_supported(): number;
+
+/**
+ * Reads the magnetic field along the X, Y, and Z axes.
+ *
+ * @param ptr Pointer to the 6 bytes of the raw field (X, Y, then Z as little-endian i16).
+ * @returns Zero on success, negative on error.
+ */
+export declare function magnetometer_read(ptr: number): number;
+
+/**
+ * Describes errors of platform operations.
+ */
+export declare enum platform_Error {
+  /**
+   * The operation is not supported.
+   */
+  Unsupported,
+  /**
+   * A function pre-condition was broken.
+   */
+  InvalidArgument,
+}
+
+/**
+ * Board capabilities that applets may query.
+ *
+ * Each capability is a bit in the result of `capabilities()`: bit `1 << c` is set if
+ * the board supports capability `c`.
+ */
+export declare enum platform_Capability {
+  /**
+   * Analog-to-digital converters.
+   */
+  Adc,
+  /**
+   * AES-128-CCM.
+   */
+  Aes128Ccm,
+  /**
+   * AES-256-GCM.
+   */
+  Aes256Gcm,
+  /**
+   * Buttons.
+   */
+  Button,
+  /**
+   * CRC.
+   */
+  Crc,
+  /**
+   * ECDH.
+   */
+  Ecdh,
+  /**
+   * Rotary encoders.
+   */
+  Encoder,
+  /**
+   * GPIOs.
+   */
+  Gpio,
+  /**
+   * LEDs.
+   */
+  Led,
+  /**
+   * LED rings.
+   */
+  LedRing,
+  /**
+   * Magnetometer.
+   */
+  Magnetometer,
+  /**
+   * PWM outputs.
+   */
+  Pwm,
+  /**
+   * Random number generator.
+   */
+  Rng,
+  /**
+   * SHA-256.
+   */
+  Sha256,
+  /**
+   * Stepper motors.
+   */
+  Stepper,
+  /**
+   * Temperature sensor.
+   */
+  Temp,
+  /**
+   * Timers.
+   */
+  Timer,
+  /**
+   * UARTs.
+   */
+  Uart,
+  /**
+   * USB serial.
+   */
+  UsbSerial,
+}
+
+/**
+ * Reboots the platform.
+ *
+ * Pending storage operations complete before the platform resets.
+ *
+ * @returns Complement of error number. Never returns on success.
+ */
+export declare function platform_reboot(): number;
+
+/**
+ * Wipes the storage and reboots the platform.
+ *
+ * The whole storage is physically erased, including the store of all applets. The
+ * applet manifest must grant the `factory-reset` permission and the applet must be
+ * signed with the applet key of the runner.
+ *
+ * @returns Complement of error number. Never returns on success.
+ */
+export declare function platform_factory_reset(): number;
+
+/**
+ * Returns the capabilities supported by the board.
+ *
+ * Applets may use it to gracefully degrade on boards missing some capabilities.
+ *
+ * @returns Bit set of the supported capabilities.
+ */
+export declare function platform_capabilities(): number;
+
+/**
+ * Returns the applet API version of the platform.
+ *
+ * Applets may compare it with the version they were compiled against to check
+ * compatibility.
+ *
+ * @returns The applet API version.
+ */
+export declare function platform_api_version(): number;
+
+/**
+ * Reads the unique ID of the device.
+ *
+ * The ID is stable across resets but is not a secret.
+ *
+ * @param ptr Pointer to the 8 bytes of the device ID.
+ * @returns Complement of error number.
+ */
+export declare function platform_device_id(ptr: number): number;
+
+/**
+ * Whether firmware updates are supported.
+ *
+ * @returns 1 if supported, 0 otherwise.
+ */
+export declare function platform_update_is_supported(): number;
+
+/**
+ * Starts a firmware update.
+ *
+ * Any update in progress is discarded and the staging bank is erased.
+ *
+ * @returns Zero on success, bitwise complement of [`Error`](crate::platform::Error)
+ * otherwise.
+ */
+export declare function platform_update_start(): number;
+
+/**
+ * Writes a chunk of the firmware at an offset of the staging bank.
+ *
+ * @param offset The offset in the staging bank.
+ * @param ptr The chunk.
+ * @param len The length of the chunk.
+ * @returns Zero on success, bitwise complement of [`Error`](crate::platform::Error)
+ * otherwise.
+ */
+export declare function platform_update_write(offset: number, ptr: number, len: number): number;
+
+/**
+ * Verifies the signature and version of the written firmware.
+ *
+ * The update must be started again if the signature is invalid or the version is
+ * older than the last applied firmware.
+ *
+ * @param signature The 64 bytes Ed25519 signature of the firmware.
+ * @returns Zero on success, bitwise complement of [`Error`](crate::platform::Error)
+ * otherwise.
+ */
+export declare function platform_update_finalize(signature: number): number;
+
+/**
+ * Applies the finalized firmware update and reboots the platform.
+ *
+ * @returns Complement of error number. Never returns on success.
+ */
+export declare function platform_update_apply(): number;
+
+/**
+ * Describes errors controlling peripheral power.
+ */
+export declare enum power_Error {
+  /**
+   * The peripheral is invalid or not supported.
+   */
+  Invalid,
+  /**
+   * The peripheral is in use.
+   */
+  Busy,
+}
+
+/**
+ * Kinds of peripherals.
+ */
+export declare enum power_Peripheral {
+  /**
+   * The analog-to-digital converters.
+   */
+  Adc,
+  /**
+   * The magnetometer.
+   */
+  Magnetometer,
+  /**
+   * The random number generator.
+   */
+  Rng,
+  /**
+   * A timer.
+   */
+  Timer,
+  /**
+   * The USB device.
+   */
+  Usb,
+}
+
+/**
+ * Disables a peripheral to save power.
+ *
+ * A disabled peripheral can't be used until enabled again.
+ *
+ * @param peripheral Kind of the peripheral.
+ * @param index Index of the peripheral.
+ * @returns Zero for success. Otherwise complement of error number.
+ */
+export declare function power_disable_peripheral(peripheral: number, index: number): number;
+
+/**
+ * Enables a peripheral.
+ *
+ * @param peripheral Kind of the peripheral.
+ * @param index Index of the peripheral.
+ * @returns Zero for success. Otherwise complement of error number.
+ */
+export declare function power_enable_peripheral(peripheral: number, index: number): number;
+
+/**
+ * Returns how many PWM outputs are on the device.
+ *
+ * @returns How many PWM outputs are on the device.
+ */
+export declare function pwm_count(): number;
+
+/**
+ * Configures and starts a PWM output.
+ *
+ * @param pwm Index of the PWM output to configure.
+ * @param freq_hz Frequency in Hz.
+ * @param duty_pct Duty cycle in percent (at most 100).
+ *
+ * A duty cycle of 0 (resp. 100) is a steady low (resp. high) output.
+ * @returns Zero on success, negative on error.
+ */
+export declare function pwm_configure(pwm: number, freq_hz: number, duty_pct: number): number;
+
+/**
+ * Stops a PWM output.
+ *
+ * The output is steady low afterwards.
+ *
+ * @param pwm Index of the PWM output to stop.
+ * @returns Zero on success, negative on error.
+ */
+export declare function pwm_stop(pwm: number): number;
+
+/**
+ * Fills a slice with random bytes.
+ *
+ * @param ptr The slice to fill.
+ * @param len The length of the slice.
+ * @returns Error code: 0 on success, -1 on error
+ *
+ * The buffer may be modified on error and should not be used.
+ */
+export declare function rng_fill_bytes(ptr: number, len: number): number;
+
+/**
+ * Waits until a callback is scheduled.
+ *
+ * This can be used as power management, since the CPU will sleep while waiting.
+ */
+export declare function scheduling_wait_for_callback(): void;
+
+/**
+ * Waits until the board produces an event.
+ *
+ * Unlike `wait_for_callback`, this returns after the next board event even if it
+ * doesn't schedule a callback. Pending callbacks are executed before returning. The
+ * CPU sleeps while waiting.
+ */
+export declare function scheduling_wait_for_event(): void;
+
+/**
+ * Returns how many callbacks are pending.
+ *
+ * @returns How many callbacks are pending.
+ */
+export declare function scheduling_num_pending_callbacks(): number;
+
+/**
+ * Returns how many stepper motors are on the device.
+ *
+ * @returns How many stepper motors are on the device.
+ */
+export declare function stepper_count(): number;
+
+/**
+ * Starts moving a stepper motor to an absolute position.
+ *
+ * The move follows a trapezoidal acceleration profile and is executed by the board.
+ * Register a handler to be notified when the move is done.
+ *
+ * @param stepper Index of the stepper motor to move.
+ * @param position Target position in steps.
+ * @param max_speed Maximum speed in steps per second.
+ * @param acceleration Acceleration in steps per second squared.
+ * @returns Error code: 0 on success, -1 on error
+ *
+ * It is an error to start a move while the motor is still moving.
+ */
+export declare function stepper_move_to(stepper: number, position: number, max_speed: number, acceleration: number): number;
+
+/**
+ * Returns the current position of a stepper motor.
+ *
+ * @param stepper Index of the stepper motor.
+ * @returns Current position in steps.
+ */
+export declare function stepper_position(stepper: number): number;
+
+/**
+ * Register a handler for stepper motor events.
+ *
+ * @param stepper Index of the stepper motor to listen to.
+ * @param handler_func Function called when a move is done.
+ *
+ * The function takes its opaque `data` as argument.
+ * @param handler_data The opaque data to use when calling the handler function.
+ */
+export declare function stepper_register(stepper: number, handler_func: number, handler_data: number): void;
+
+/**
+ * Unregister handlers for stepper motor events.
+ *
+ * @param stepper Index of the stepper motor to stop listening to.
+ */
+export declare function stepper_unregister(stepper: number): void;
+
+/**
+ * Describes errors interacting with the store.
+ */
+export declare enum store_Error {
+  /**
+   * A function pre-condition was broken.
+   */
+  InvalidArgument,
+  /**
+   * The store is full.
+   */
+  NoCapacity,
+  /**
+   * The store reached its end of life.
+   */
+  NoLifetime,
+  /**
+   * An operation to the underlying storage failed.
+   */
+  StorageError,
+  /**
+   * The underlying storage doesn't match the store invariant.
+   */
+  InvalidStorage,
+}
+
+/**
+ * Inserts an entry in the store.
+ *
+ * If an entry for that key was already present, it is overwritten.
+ *
+ * @param key Key of the entry.
+ *
+ * This must be smaller than 4032. Larger keys are reserved for the platform, but
+ * entries inserted there by older platforms can still be found and removed.
+ * @param ptr Value of the entry.
+ * @param len Length of the value.
+ * @returns Zero for success. Otherwise complement of error number.
+ */
+export declare function store_insert(key: number, ptr: number, len: number): number;
+
+/**
+ * Removes an entry from the store.
+ *
+ * This is not an error if no entry is present. This is simply a no-op in that case.
+ *
+ * @param key Key of the entry.
+ * @returns Zero for success. Otherwise complement of error number.
+ */
+export declare function store_remove(key: number): number;
+
+/**
+ * Finds an entry in the store, if any.
+ *
+ * @param key Key of the entry to find.
+ * @param ptr Where to write the value of the entry, if found.
+ *
+ * The (inner) pointer will be allocated by the callee and must be freed by the
+ * caller. It is thus owned by the caller when the function returns.
+ * @param len Where to write the length of the value, if found.
+ * @returns One if found. Zero if not found. Otherwise complement of error number.
+ */
+export declare function store_find(key: number, ptr: number, len: number): number;
+
+/**
+ * Inserts a fragmented entry in the store.
+ *
+ * If an entry for that range of keys was already present, it is overwritten.
+ *
+ * @param keys Range of keys.
+ *
+ * The first key is the lower 16 bits and the one past the last key is the upper 16
+ * bits. The range must be non-empty and the keys smaller than 4032.
+ * @param ptr Value of the entry.
+ * @param len Length of the value.
+ *
+ * This must fit in the range of keys.
+ * @returns Zero for success. Otherwise complement of error number.
+ */
+export declare function store_fragment_insert(keys: number, ptr: number, len: number): number;
+
+/**
+ * Removes a fragmented entry from the store.
+ *
+ * This is not an error if no entry is present. This is simply a no-op in that case.
+ *
+ * @param keys Range of keys (same encoding as for `insert`).
+ * @returns Zero for success. Otherwise complement of error number.
+ */
+export declare function store_fragment_remove(keys: number): number;
+
+/**
+ * Reads part of a fragmented entry from the store.
+ *
+ * The runner writes the bytes of the entry starting at `offset` directly into the
+ * buffer. Large entries may thus be read in a single call, or in a few calls at
+ * increasing offsets when the applet can't afford a buffer for the whole entry.
+ *
+ * @param keys Range of keys (same encoding as for `insert`).
+ * @param offset Offset in the entry of the first byte to read.
+ * @param ptr Where to write the bytes read.
+ * @param len Length of the buffer.
+ * @returns Number of bytes read. Otherwise complement of error number.
+ *
+ * This is smaller than the length of the buffer when the end of the entry is
+ * reached. It is zero if the entry is not found or the offset is past its end.
+ */
+export declare function store_fragment_read(keys: number, offset: number, ptr: number, len: number): number;
+
+/**
+ * Whether a temperature sensor is available.
+ *
+ * @returns 1 if supported, 0 otherwise.
+ */
+export declare function temp_is_supported(): number;
+
+/**
+ * Reads the temperature.
+ *
+ * @param ptr Pointer to the 4 bytes of the temperature in millidegrees Celsius (little-endian
+ * i32).
+ * @returns Zero on success, negative on error.
+ */
+export declare function temp_read(ptr: number): number;
+
+/**
+ * Returns the number of UARTs.
+ *
+ * @returns How many UARTs are available.
+ */
+export declare function uart_count(): number;
+
+/**
+ * Sets the baud rate of a UART.
+ *
+ * @param uart Index of the UART.
+ * @param baudrate Baud rate in bits per second.
+ * @returns Zero on success, -1 on error (e.g. unsupported baud rate).
+ */
+export declare function uart_set_baudrate(uart: number, baudrate: number): number;
+
+/**
+ * Reads from a UART into a buffer.
+ *
+ * @param uart Index of the UART.
+ * @param ptr Address of the buffer.
+ * @param len Length of the buffer in bytes.
+ * @returns Number of bytes read (or negative value for errors).
+ *
+ * This function does not block and may return zero.
+ */
+export declare function uart_read(uart: number, ptr: number, len: number): number;
+
+/**
+ * Writes to a UART from a buffer.
+ *
+ * @param uart Index of the UART.
+ * @param ptr Address of the buffer.
+ * @param len Length of the buffer in bytes.
+ * @returns Number of bytes written (or negative value for errors).
+ *
+ * This function does not block and may return zero.
+ */
+export declare function uart_write(uart: number, ptr: number, len: number): number;
+
+/**
+ * Flushes a UART.
+ *
+ * @param uart Index of the UART.
+ * @returns Zero on success, -1 on error.
+ */
+export declare function uart_flush(uart: number): number;
+
+/**
+ * UART events.
+ */
+export declare enum uart_Event {
+  /**
+   * Ready for read.
+   */
+  Read,
+  /**
+   * Ready for write.
+   */
+  Write,
+}
+
+/**
+ * Registers a callback when a UART is ready.
+ *
+ * It is possible that the callback is spuriously called.
+ *
+ * @param uart
+ * @param event
+ * @param handler_func
+ * @param handler_data
+ */
+export declare function uart_register(uart: number, event: number, handler_func: number, handler_data: number): void;
+
+/**
+ * Unregisters a callback.
+ *
+ * @param uart
+ * @param event
+ */
+export declare function uart_unregister(uart: number, event: number): void;
+
+/**
+ * Describes errors on USB operations.
+ */
+export declare enum usb_Error {
+  Unknown,
+}
+
+/**
+ * Reads a report from USB HID into a 64-bytes buffer.
+ *
+ * @param ptr Address of the 64-bytes buffer.
+ * @returns One if a report was read, zero if there is nothing to read, and negative value
+ * for errors.
+ *
+ * This function does not block.
+ */
+export declare function usb_hid_read(ptr: number): number;
+
+/**
+ * Writes a report to USB HID from a 64-bytes buffer.
+ *
+ * @param ptr Address of the 64-bytes buffer.
+ * @returns One if the report was written, zero if the other side is not ready, and
+ * negative value for errors.
+ *
+ * This function does not block.
+ */
+export declare function usb_hid_write(ptr: number): number;
+
+/**
+ * USB HID events.
+ */
+export declare enum usb_hid_Event {
+  /**
+   * Ready for read.
+   */
+  Read,
+  /**
+   * Ready for write.
+   */
+  Write,
+}
+
+/**
+ * Registers a callback when USB HID is ready.
+ *
+ * It is possible that the callback is spuriously called.
+ *
+ * @param event
+ * @param handler_func
+ * @param handler_data
+ */
+export declare function usb_hid_register(event: number, handler_func: number, handler_data: number): void;
+
+/**
+ * Unregisters a callback.
+ *
+ * @param event
+ */
+export declare function usb_hid_unregister(event: number): void;
+
+/**
+ * Returns the number of serial ports.
+ *
+ * @returns How many serial ports are available.
+ */
+export declare function usb_serial_count(): number;
+
+/**
+ * Reads from a USB serial port into a buffer.
+ *
+ * @param port Index of the serial port.
+ * @param ptr Address of the buffer.
+ * @param len Length of the buffer in bytes.
+ * @returns Number of bytes read (or negative value for errors).
+ *
+ * This function does not block and may return zero.
+ */
+export declare function usb_serial_read(port: number, ptr: number, len: number): number;
+
+/**
+ * Writes to a USB serial port from a buffer.
+ *
+ * @param port Index of the serial port.
+ * @param ptr Address of the buffer.
+ * @param len Length of the buffer in bytes.
+ * @returns Number of bytes written (or negative value for errors).
+ *
+ * This function does not block and may return zero.
+ */
+export declare function usb_serial_write(port: number, ptr: number, len: number): number;
+
+/**
+ * USB serial events.
+ */
+export declare enum usb_serial_Event {
+  /**
+   * Ready for read.
+   */
+  Read,
+  /**
+   * Ready for write.
+   */
+  Write,
+  /**
+   * Line state changed.
+   */
+  Control,
+  /**
+   * Line coding changed.
+   */
+  Coding,
+}
+
+/**
+ * Number of stop bits.
+ */
+export declare enum usb_serial_StopBits {
+  One,
+  OnePointFive,
+  Two,
+}
+
+/**
+ * Parity type.
+ */
+export declare enum usb_serial_Parity {
+  None,
+  Odd,
+  Even,
+  Mark,
+  Space,
+}
+
+/**
+ * Registers a callback when a USB serial port is ready.
+ *
+ * It is possible that the callback is spuriously called.
+ *
+ * @param port
+ * @param event
+ * @param handler_func
+ * @param handler_data
+ */
+export declare function usb_serial_register(port: number, event: number, handler_func: number, handler_data: number): void;
+
+/**
+ * Unregisters a callback.
+ *
+ * @param port
+ * @param event
+ */
+export declare function usb_serial_unregister(port: number, event: number): void;
+
+/**
+ * Flushs a USB serial port.
+ *
+ * @param port Index of the serial port.
+ * @returns Zero on success, -1 on error.
+ */
+export declare function usb_serial_flush(port: number): number;
+
+/**
+ * Flushes a USB serial port, waiting at most a given duration.
+ *
+ * @param port Index of the serial port.
+ * @param timeout_ms Maximum duration to wait in milliseconds.
+ * @returns Zero on success, -1 on error (including timeout).
+ */
+export declare function usb_serial_flush_timeout(port: number, timeout_ms: number): number;
+
+/**
+ * Returns the line state of a USB serial port.
+ *
+ * @param port Index of the serial port.
+ * @returns Bit 0 is DTR and bit 1 is RTS (or -1 on error).
+ */
+export declare function usb_serial_line_state(port: number): number;
+
+/**
+ * Returns the line coding of a USB serial port.
+ *
+ * @param port Index of the serial port.
+ * @param ptr Pointer to the 8 bytes of the line coding.
+ *
+ * The data rate in bits per second as little-endian u32, followed by the stop
+ * bits, the parity, the number of data bits, and a zero byte.
+ * @returns Zero on success, -1 on error.
+ */
+export declare function usb_serial_line_coding(port: number, ptr: number): number;
+
+/**
+ * Board-specific syscalls.
+ *
+ * Those calls are forwarded by the scheduler.
+ *
+ * @param x1
+ * @param x2
+ * @param x3
+ * @param x4
+ * @returns
+ */
+export declare function syscall(x1: number, x2: number, x3: number, x4: number): number;
@@ -12,6 +12,34 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+// Version of the applet API.
+export const API_VERSION: u32 = 1;
+
+// START OF MODULE adc
+// Analog-to-digital converter operations.
+  // Returns how many analog inputs are on the device.
+  @external("env", "ac")
+  export declare function adc_count(
+  // How many analog inputs are on the device.
+  ): usize
+
+  // Samples an analog input.
+  @external("env", "ar")
+  export declare function adc_read(
+    // Index of the analog input to sample.
+    adc: usize,
+  // The sample normalized to 16 bits, or a negative value on error.
+  //
+  // A sample of 0 is ground and 65535 is the reference voltage.
+  ): isize
+
+  // Measures the supply voltage.
+  @external("env", "av")
+  export declare function adc_read_vdd(
+  // The supply voltage in millivolts, or a negative value on error.
+  ): isize
+// END OF MODULE adc
+
 // START OF MODULE button
 // Button and touch operations.
   // Describes the state of a button.
@@ -23,6 +51,18 @@
     Pressed,
   }
 
+  // Describes a button gesture.
+  enum button_Gesture {
+    // The button was pressed and released once.
+    Single,
+
+    // The button was held pressed for at least the long-press threshold.
+    Long,
+
+    // The button was pressed twice within the double-press window.
+    Double,
+  }
+
   // Returns how many buttons are on the device.
   @external("env", "bc")
   export declare function button_count(
@@ -38,12 +78,35 @@
     // Function called on button events.
     //
     // The function takes its opaque `data` and the new button `state` as arguments.
+    // If gestures are configured for the button, the function is called with a
+    // `Gesture` instead of a `State`.
     handler_func: usize,
 
     // The opaque data to use when calling the handler function.
     handler_data: usize,
   ): void
 
+  // Configures gesture recognition for a button.
+  //
+  // When configured, the button handler is called on gestures instead of state changes.
+  @external("env", "bg")
+  export declare function button_gesture(
+    // Index of the button to configure.
+    button: usize,
+
+    // Minimum duration in milliseconds of a long press.
+    //
+    // Zero disables gesture recognition for the button.
+    long: usize,
+
+    // Maximum duration in milliseconds between a release and the next press for a
+    // double press.
+    //
+    // Zero disables double presses such that single presses are reported on release.
+    double: usize,
+  // Zero on success, -1 if there are no timers available.
+  ): isize
+
   // Unregister handlers for button events.
   @external("env", "bu")
   export declare function button_unregister(
@@ -52,6 +115,62 @@
   ): void
 // END OF MODULE button
 
+// START OF MODULE capture
+// Input capture operations.
+//
+// The GPIO must be configured as an input with the `gpio` module.
+  // Starts measuring the frequency and duty cycle of the signal on a GPIO.
+  //
+  // The measurement is executed by the board. Register a handler to be notified when
+  // the measurement is done.
+  @external("env", "cs")
+  export declare function capture_start(
+    // Index of the GPIO.
+    gpio: usize,
+  // Zero on success, negative on error.
+  //
+  // It is an error to start a measurement while another one is running.
+  ): isize
+
+  // Reads the result of the done measurement of a GPIO.
+  @external("env", "cr")
+  export declare function capture_result(
+    // Index of the GPIO.
+    gpio: usize,
+
+    // Pointer to the 8 bytes of the measurement.
+    //
+    // The period and the high time of the signal in nanoseconds as little-endian u32.
+    ptr: usize,
+  // One if the signal was measured, zero if there is no signal, and negative on
+  // error.
+  //
+  // It is an error to read the result of a measurement that is not done.
+  ): isize
+
+  // Register a handler for input capture events.
+  @external("env", "ce")
+  export declare function capture_register(
+    // Index of the GPIO to listen to.
+    gpio: usize,
+
+    // Function called when a measurement is done.
+    //
+    // The function takes its opaque `data` as argument.
+    handler_func: usize,
+
+    // The opaque data to use when calling the handler function.
+    handler_data: usize,
+  ): void
+
+  // Unregister handlers for input capture events.
+  @external("env", "cd")
+  export declare function capture_unregister(
+    // Index of the GPIO to stop listening to.
+    gpio: usize,
+  ): void
+// END OF MODULE capture
+
 // START OF MODULE clock
 // Clock and timer operations.
   // Whether a timer should periodically trigger.
@@ -107,6 +226,14 @@
     // The identifier of the timer to start.
     id: usize,
   ): void
+
+  // Returns the time since the platform started.
+  @external("env", "tu")
+  export declare function clock_uptime(
+    // Pointer to the 8 bytes of the uptime in micro-seconds (little-endian).
+    ptr: usize,
+  // Zero on success, negative on error.
+  ): isize
 // END OF MODULE clock
 
 // START OF MODULE crypto
@@ -185,6 +312,89 @@
     ): isize
   // END OF MODULE crypto_ccm
 
+  // START OF MODULE crypto_crc
+  // Cyclic redundancy checks.
+  //
+  // CRC-32 is the one used by Ethernet, zlib, and PNG (also known as CRC-32/ISO-HDLC).
+  // CRC-16/CCITT has initial value 0xffff and no final xor (also known as CRC-16/IBM-3740
+  // or CRC-16/CCITT-FALSE). Checksums are written in little-endian.
+    // Whether CRCs are supported.
+    @external("env", "crs")
+    export declare function crypto_crc_is_supported(
+    // 1 if supported, 0 otherwise.
+    ): usize
+
+    // Computes the CRC-32 of some data.
+    @external("env", "crw")
+    export declare function crypto_crc_crc32(
+      // The pointer to the data.
+      data: usize,
+
+      // The length of the data.
+      length: usize,
+
+      // The 4 bytes checksum.
+      crc: usize,
+    // Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+    // otherwise.
+    ): isize
+
+    // Computes the CRC-16/CCITT of some data.
+    @external("env", "crh")
+    export declare function crypto_crc_crc16_ccitt(
+      // The pointer to the data.
+      data: usize,
+
+      // The length of the data.
+      length: usize,
+
+      // The 2 bytes checksum.
+      crc: usize,
+    // Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+    // otherwise.
+    ): isize
+  // END OF MODULE crypto_crc
+
+  // START OF MODULE crypto_ecdh
+  // ECDH over P-256.
+  //
+  // Private keys are 32 bytes big-endian scalars. Public keys are 64 bytes uncompressed
+  // points (the big-endian X coordinate followed by the Y coordinate). Shared secrets are
+  // the 32 bytes X coordinate of the product point (as specified by SEC1).
+    // Whether ECDH over P-256 is supported.
+    @external("env", "ces")
+    export declare function crypto_ecdh_is_supported(
+    // 1 if supported, 0 otherwise.
+    ): usize
+
+    // Generates a random key pair.
+    @external("env", "ceg")
+    export declare function crypto_ecdh_generate_keypair(
+      // The 32 bytes private key.
+      private: usize,
+
+      // The 64 bytes public key.
+      public: usize,
+    // Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+    // otherwise.
+    ): isize
+
+    // Computes the shared secret of a private key and a peer public key.
+    @external("env", "cea")
+    export declare function crypto_ecdh_agree(
+      // The 32 bytes private key.
+      private: usize,
+
+      // The 64 bytes public key of the peer.
+      public: usize,
+
+      // The 32 bytes shared secret.
+      shared: usize,
+    // Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+    // otherwise.
+    ): isize
+  // END OF MODULE crypto_ecdh
+
   // START OF MODULE crypto_gcm
   // AES-256-GCM.
     // Whether AES-256-GCM is supported.
@@ -332,6 +542,16 @@
     len: usize,
   ): void
 
+  // Reads the heap statistics of the platform.
+  @external("env", "dh")
+  export declare function debug_heap_stats(
+    // Pointer to the 12 bytes of statistics.
+    //
+    // The used, free, and high water bytes as little-endian u32.
+    ptr: usize,
+  // Zero on success, negative on error.
+  ): isize
+
   // Exits the platform with an error code.
   //
   // This is used by test applets to terminate the platform and propagate the test
@@ -341,8 +561,227 @@
     // 0 for success, 1 for failure
     code: usize,
   ): void
+
+  // Logging levels from the least to the most verbose.
+  enum debug_Level {
+    Error,
+
+    Warn,
+
+    Info,
+
+    Debug,
+
+    Trace,
+  }
+
+  // Sets the most verbose level of the platform logs.
+  //
+  // Levels disabled when the platform was compiled can't be enabled.
+  @external("env", "dl")
+  export declare function debug_set_log_level(
+    // The most verbose level to log.
+    //
+    // Valid values are defined by [`Level`](super::Level).
+    level: usize,
+  // Zero on success, negative on error.
+  ): isize
+
+  // Logs a message at a given level in the platform logs.
+  //
+  // The message interleaves with the platform logs and is subject to the same level
+  // filter. If logging is disabled then this is a no-op.
+  @external("env", "dg")
+  export declare function debug_log(
+    // The level of the message.
+    //
+    // Valid values are defined by [`Level`](super::Level).
+    level: usize,
+
+    // The message to log.
+    //
+    // Traps if the message is not valid UTF-8.
+    ptr: usize,
+
+    // The length of the message in bytes.
+    len: usize,
+  ): void
 // END OF MODULE debug
 
+// START OF MODULE encoder
+// Quadrature encoder operations.
+  // Returns how many encoders are on the device.
+  @external("env", "ec")
+  export declare function encoder_count(
+  // How many encoders are on the device.
+  ): usize
+
+  // Returns the current position of an encoder.
+  @external("env", "ep")
+  export declare function encoder_position(
+    // Index of the encoder.
+    encoder: usize,
+  // Current position in steps.
+  ): isize
+
+  // Starts homing an encoder.
+  //
+  // The position is zeroed at the next index pulse. The shaft must be rotated while
+  // homing. Register a handler to be notified when homing is done.
+  @external("env", "eh")
+  export declare function encoder_home(
+    // Index of the encoder.
+    encoder: usize,
+  // Error code: 0 on success, -1 on error
+  ): isize
+
+  // Register a handler for encoder events.
+  @external("env", "er")
+  export declare function encoder_register(
+    // Index of the encoder to listen to.
+    encoder: usize,
+
+    // Function called when homing is done.
+    //
+    // The function takes its opaque `data` as argument.
+    handler_func: usize,
+
+    // The opaque data to use when calling the handler function.
+    handler_data: usize,
+  ): void
+
+  // Unregister handlers for encoder events.
+  @external("env", "eu")
+  export declare function encoder_unregister(
+    // Index of the encoder to stop listening to.
+    encoder: usize,
+  ): void
+// END OF MODULE encoder
+
+// START OF MODULE gpio
+// General-purpose input/output operations.
+  // Returns how many GPIOs are on the device.
+  @external("env", "gc")
+  export declare function gpio_count(
+  // How many GPIOs are on the device.
+  ): usize
+
+  // Describes the configuration of a GPIO.
+  enum gpio_Mode {
+    // Input with an internal pull-up resistor.
+    InputPullUp,
+
+    // Input with an internal pull-down resistor.
+    InputPullDown,
+
+    // Output driving both levels.
+    OutputPushPull,
+
+    // Output driving only the low level.
+    OutputOpenDrain,
+  }
+
+  // Configures a GPIO.
+  @external("env", "gf")
+  export declare function gpio_configure(
+    // Index of the GPIO to configure.
+    gpio: usize,
+
+    // The new mode of the GPIO.
+    //
+    // Valid values are defined by [`Mode`](super::Mode).
+    mode: usize,
+  ): void
+
+  // Reads the level of a configured GPIO.
+  @external("env", "gr")
+  export declare function gpio_read(
+    // Index of the GPIO to read.
+    gpio: usize,
+  // 0 for low and 1 for high.
+  ): usize
+
+  // Writes the level of a GPIO configured as output.
+  @external("env", "gw")
+  export declare function gpio_write(
+    // Index of the GPIO to write.
+    gpio: usize,
+
+    // 0 for low and 1 for high.
+    level: usize,
+  ): void
+// END OF MODULE gpio
+
+// START OF MODULE i2c_sw
+// Software I2C operations.
+//
+// The bus is bit-banged on 2 GPIOs. It is slower than a hardware bus and the CPU is busy
+// for the whole transfer.
+  // Configures the bus.
+  //
+  // This must be called before any transfer.
+  @external("env", "isc")
+  export declare function i2c_sw_configure(
+    // Index of the GPIO of the data line.
+    sda: usize,
+
+    // Index of the GPIO of the clock line.
+    scl: usize,
+
+    // Clock rate in Hertz.
+    frequency: usize,
+  // Zero on success, negative on error.
+  ): isize
+
+  // Reads bytes from a device into a buffer.
+  @external("env", "isr")
+  export declare function i2c_sw_read(
+    // 7-bits address of the device.
+    addr: usize,
+
+    // Address of the buffer.
+    ptr: usize,
+
+    // Length of the buffer in bytes.
+    len: usize,
+  // Zero on success, negative on error.
+  ): isize
+
+  // Writes bytes to a device from a buffer.
+  @external("env", "isw")
+  export declare function i2c_sw_write(
+    // 7-bits address of the device.
+    addr: usize,
+
+    // Address of the buffer.
+    ptr: usize,
+
+    // Length of the buffer in bytes.
+    len: usize,
+  // Zero on success, negative on error.
+  ): isize
+
+  // Writes then reads bytes from a device with a repeated start condition.
+  @external("env", "isx")
+  export declare function i2c_sw_write_read(
+    // 7-bits address of the device.
+    addr: usize,
+
+    // Address of the buffer to write.
+    wptr: usize,
+
+    // Length of the buffer to write in bytes.
+    wlen: usize,
+
+    // Address of the buffer to read.
+    rptr: usize,
+
+    // Length of the buffer to read in bytes.
+    rlen: usize,
+  // Zero on success, negative on error.
+  ): isize
+// END OF MODULE i2c_sw
+
 // START OF MODULE led
 // LED operations.
   // Returns how many LEDs are on the device.
@@ -377,8 +816,350 @@
     // 0 for off and 1 for on.
     status: usize,
   ): void
+
+  // Sets a LED brightness.
+  //
+  // LEDs without brightness support are on if the brightness is at least 128.
+  @external("env", "lb")
+  export declare function led_set_brightness(
+    // Index of the LED to set.
+    led: usize,
+
+    // 0 for off and 255 for fully on.
+    brightness: usize,
+  ): void
 // END OF MODULE led
 
+// START OF MODULE led_ring
+// Addressable LED ring operations.
+//
+// Animations are executed by the board, such that the applet only needs to select them.
+  // Animation patterns.
+  enum led_ring_Pattern {
+    // A single lit pixel going around the ring.
+    Spinner,
+
+    // An arc of lit pixels growing around the ring and restarting when full.
+    Progress,
+
+    // A rainbow rotating around the ring (the color is ignored).
+    Rainbow,
+  }
+
+  // Returns the number of pixels of the ring.
+  @external("env", "lrc")
+  export declare function led_ring_count(
+  // How many pixels are available (zero if there is no ring).
+  ): usize
+
+  // Starts an animation, replacing the current one if any.
+  @external("env", "lra")
+  export declare function led_ring_animate(
+    // The animation pattern.
+    pattern: usize,
+
+    // The number of steps per second (zero freezes the first frame).
+    speed: usize,
+
+    // The color of the lit pixels as 0xRRGGBB.
+    color: usize,
+  // Zero on success, negative on error.
+  ): isize
+
+  // Stops the current animation and turns all pixels off.
+  @external("env", "lrs")
+  export declare function led_ring_stop(
+  // Zero on success, negative on error.
+  ): isize
+// END OF MODULE led_ring
+
+// START OF MODULE magnetometer
+// Magnetometer operations.
+  // Whether a magnetometer is available.
+  @external("env", "fs")
+  export declare function magnetometer_is_supported(
+  // 1 if supported, 0 otherwise.
+  ): usize
+
+  // Reads the magnetic field along the X, Y, and Z axes.
+  @external("env", "fr")
+  export declare function magnetometer_read(
+    // Pointer to the 6 bytes of the raw field (X, Y, then Z as little-endian i16).
+    ptr: usize,
+  // Zero on success, negative on error.
+  ): isize
+// END OF MODULE magnetometer
+
+// START OF MODULE platform
+// Platform operations.
+  // Describes errors of platform operations.
+  enum platform_Error {
+    // The operation is not supported.
+    Unsupported,
+
+    // A function pre-condition was broken.
+    InvalidArgument,
+  }
+
+  // Board capabilities that applets may query.
+  //
+  // Each capability is a bit in the result of `capabilities()`: bit `1 << c` is set if
+  // the board supports capability `c`.
+  enum platform_Capability {
+    // Analog-to-digital converters.
+    Adc,
+
+    // AES-128-CCM.
+    Aes128Ccm,
+
+    // AES-256-GCM.
+    Aes256Gcm,
+
+    // Buttons.
+    Button,
+
+    // CRC.
+    Crc,
+
+    // ECDH.
+    Ecdh,
+
+    // Rotary encoders.
+    Encoder,
+
+    // GPIOs.
+    Gpio,
+
+    // LEDs.
+    Led,
+
+    // LED rings.
+    LedRing,
+
+    // Magnetometer.
+    Magnetometer,
+
+    // PWM outputs.
+    Pwm,
+
+    // Random number generator.
+    Rng,
+
+    // SHA-256.
+    Sha256,
+
+    // Stepper motors.
+    Stepper,
+
+    // Temperature sensor.
+    Temp,
+
+    // Timers.
+    Timer,
+
+    // UARTs.
+    Uart,
+
+    // USB serial.
+    UsbSerial,
+  }
+
+  // Reboots the platform.
+  //
+  // Pending storage operations complete before the platform resets.
+  @external("env", "pr")
+  export declare function platform_reboot(
+  // Complement of error number. Never returns on success.
+  ): isize
+
+  // Wipes the storage and reboots the platform.
+  //
+  // The whole storage is physically erased, including the store of all applets. The
+  // applet manifest must grant the `factory-reset` permission and the applet must be
+  // signed with the applet key of the runner.
+  @external("env", "pf")
+  export declare function platform_factory_reset(
+  // Complement of error number. Never returns on success.
+  ): isize
+
+  // Returns the capabilities supported by the board.
+  //
+  // Applets may use it to gracefully degrade on boards missing some capabilities.
+  @external("env", "pc")
+  export declare function platform_capabilities(
+  // Bit set of the supported capabilities.
+  ): usize
+
+  // Returns the applet API version of the platform.
+  //
+  // Applets may compare it with the version they were compiled against to check
+  // compatibility.
+  @external("env", "pv")
+  export declare function platform_api_version(
+  // The applet API version.
+  ): usize
+
+  // Reads the unique ID of the device.
+  //
+  // The ID is stable across resets but is not a secret.
+  @external("env", "pi")
+  export declare function platform_device_id(
+    // Pointer to the 8 bytes of the device ID.
+    ptr: usize,
+  // Complement of error number.
+  ): isize
+
+  // START OF MODULE platform_update
+  // Firmware updates.
+  //
+  // The new firmware is written to a staging bank, its Ed25519 signature is verified, and
+  // it is swapped in on the next reboot. The firmware ends with its version as a 32-bit
+  // little-endian integer, which must not be older than the last applied firmware.
+  //
+  // The applet manifest must grant the `update` permission and the applet must be signed
+  // with the applet key of the runner.
+    // Whether firmware updates are supported.
+    @external("env", "pus")
+    export declare function platform_update_is_supported(
+    // 1 if supported, 0 otherwise.
+    ): usize
+
+    // Starts a firmware update.
+    //
+    // Any update in progress is discarded and the staging bank is erased.
+    @external("env", "pub")
+    export declare function platform_update_start(
+    // Zero on success, bitwise complement of [`Error`](crate::platform::Error)
+    // otherwise.
+    ): isize
+
+    // Writes a chunk of the firmware at an offset of the staging bank.
+    @external("env", "puw")
+    export declare function platform_update_write(
+      // The offset in the staging bank.
+      offset: usize,
+
+      // The chunk.
+      ptr: usize,
+
+      // The length of the chunk.
+      len: usize,
+    // Zero on success, bitwise complement of [`Error`](crate::platform::Error)
+    // otherwise.
+    ): isize
+
+    // Verifies the signature and version of the written firmware.
+    //
+    // The update must be started again if the signature is invalid or the version is
+    // older than the last applied firmware.
+    @external("env", "puf")
+    export declare function platform_update_finalize(
+      // The 64 bytes Ed25519 signature of the firmware.
+      signature: usize,
+    // Zero on success, bitwise complement of [`Error`](crate::platform::Error)
+    // otherwise.
+    ): isize
+
+    // Applies the finalized firmware update and reboots the platform.
+    @external("env", "pua")
+    export declare function platform_update_apply(
+    // Complement of error number. Never returns on success.
+    ): isize
+  // END OF MODULE platform_update
+// END OF MODULE platform
+
+// START OF MODULE power
+// Peripheral power operations.
+//
+// Peripherals are identified by their kind and an index (only used for timers, zero
+// otherwise).
+  // Describes errors controlling peripheral power.
+  enum power_Error {
+    // The peripheral is invalid or not supported.
+    Invalid,
+
+    // The peripheral is in use.
+    Busy,
+  }
+
+  // Kinds of peripherals.
+  enum power_Peripheral {
+    // The analog-to-digital converters.
+    Adc,
+
+    // The magnetometer.
+    Magnetometer,
+
+    // The random number generator.
+    Rng,
+
+    // A timer.
+    Timer,
+
+    // The USB device.
+    Usb,
+  }
+
+  // Disables a peripheral to save power.
+  //
+  // A disabled peripheral can't be used until enabled again.
+  @external("env", "pd")
+  export declare function power_disable_peripheral(
+    // Kind of the peripheral.
+    peripheral: usize,
+
+    // Index of the peripheral.
+    index: usize,
+  // Zero for success. Otherwise complement of error number.
+  ): isize
+
+  // Enables a peripheral.
+  @external("env", "pe")
+  export declare function power_enable_peripheral(
+    // Kind of the peripheral.
+    peripheral: usize,
+
+    // Index of the peripheral.
+    index: usize,
+  // Zero for success. Otherwise complement of error number.
+  ): isize
+// END OF MODULE power
+
+// START OF MODULE pwm
+// PWM output operations.
+  // Returns how many PWM outputs are on the device.
+  @external("env", "wc")
+  export declare function pwm_count(
+  // How many PWM outputs are on the device.
+  ): usize
+
+  // Configures and starts a PWM output.
+  @external("env", "wg")
+  export declare function pwm_configure(
+    // Index of the PWM output to configure.
+    pwm: usize,
+
+    // Frequency in Hz.
+    freq_hz: usize,
+
+    // Duty cycle in percent (at most 100).
+    //
+    // A duty cycle of 0 (resp. 100) is a steady low (resp. high) output.
+    duty_pct: usize,
+  // Zero on success, negative on error.
+  ): isize
+
+  // Stops a PWM output.
+  //
+  // The output is steady low afterwards.
+  @external("env", "ws")
+  export declare function pwm_stop(
+    // Index of the PWM output to stop.
+    pwm: usize,
+  // Zero on success, negative on error.
+  ): isize
+// END OF MODULE pwm
+
 // START OF MODULE rng
 // Random number generators.
   // Fills a slice with random bytes.
@@ -403,6 +1184,15 @@
   export declare function scheduling_wait_for_callback(
   ): void
 
+  // Waits until the board produces an event.
+  //
+  // Unlike `wait_for_callback`, this returns after the next board event even if it
+  // doesn't schedule a callback. Pending callbacks are executed before returning. The
+  // CPU sleeps while waiting.
+  @external("env", "se")
+  export declare function scheduling_wait_for_event(
+  ): void
+
   // Returns how many callbacks are pending.
   @external("env", "sh")
   export declare function scheduling_num_pending_callbacks(
@@ -410,6 +1200,67 @@
   ): usize
 // END OF MODULE scheduling
 
+// START OF MODULE stepper
+// Stepper motor operations.
+  // Returns how many stepper motors are on the device.
+  @external("env", "mc")
+  export declare function stepper_count(
+  // How many stepper motors are on the device.
+  ): usize
+
+  // Starts moving a stepper motor to an absolute position.
+  //
+  // The move follows a trapezoidal acceleration profile and is executed by the board.
+  // Register a handler to be notified when the move is done.
+  @external("env", "mm")
+  export declare function stepper_move_to(
+    // Index of the stepper motor to move.
+    stepper: usize,
+
+    // Target position in steps.
+    position: isize,
+
+    // Maximum speed in steps per second.
+    max_speed: usize,
+
+    // Acceleration in steps per second squared.
+    acceleration: usize,
+  // Error code: 0 on success, -1 on error
+  //
+  // It is an error to start a move while the motor is still moving.
+  ): isize
+
+  // Returns the current position of a stepper motor.
+  @external("env", "mp")
+  export declare function stepper_position(
+    // Index of the stepper motor.
+    stepper: usize,
+  // Current position in steps.
+  ): isize
+
+  // Register a handler for stepper motor events.
+  @external("env", "mr")
+  export declare function stepper_register(
+    // Index of the stepper motor to listen to.
+    stepper: usize,
+
+    // Function called when a move is done.
+    //
+    // The function takes its opaque `data` as argument.
+    handler_func: usize,
+
+    // The opaque data to use when calling the handler function.
+    handler_data: usize,
+  ): void
+
+  // Unregister handlers for stepper motor events.
+  @external("env", "mu")
+  export declare function stepper_unregister(
+    // Index of the stepper motor to stop listening to.
+    stepper: usize,
+  ): void
+// END OF MODULE stepper
+
 // START OF MODULE store
 // Persistent storage operations.
   // Describes errors interacting with the store.
@@ -437,7 +1288,8 @@
   export declare function store_insert(
     // Key of the entry.
     //
-    // This must be smaller than 4096.
+    // This must be smaller than 4032. Larger keys are reserved for the platform, but
+    // entries inserted there by older platforms can still be found and removed.
     key: usize,
 
     // Value of the entry.
@@ -474,8 +1326,187 @@
     len: usize,
   // One if found. Zero if not found. Otherwise complement of error number.
   ): isize
+
+  // START OF MODULE store_fragment
+  // Support for fragmented entries.
+  //
+  // Fragmented entries span a range of keys and may thus be larger than the maximum value
+  // length of a single entry. They are meant for large buffers (e.g. images or firmware).
+  //
+  // The functions of this module transfer the whole buffer in a single call: the runner
+  // directly reads from (or writes to) the region of the applet linear memory described by
+  // the pointer and length. The region is only accessed for the duration of the call and the
+  // runner does not retain it afterwards. The applet must not assume anything about the
+  // content of the region if the call fails.
+    // Inserts a fragmented entry in the store.
+    //
+    // If an entry for that range of keys was already present, it is overwritten.
+    @external("env", "sfi")
+    export declare function store_fragment_insert(
+      // Range of keys.
+      //
+      // The first key is the lower 16 bits and the one past the last key is the upper 16
+      // bits. The range must be non-empty and the keys smaller than 4032.
+      keys: usize,
+
+      // Value of the entry.
+      ptr: usize,
+
+      // Length of the value.
+      //
+      // This must fit in the range of keys.
+      len: usize,
+    // Zero for success. Otherwise complement of error number.
+    ): isize
+
+    // Removes a fragmented entry from the store.
+    //
+    // This is not an error if no entry is present. This is simply a no-op in that case.
+    @external("env", "sfr")
+    export declare function store_fragment_remove(
+      // Range of keys (same encoding as for `insert`).
+      keys: usize,
+    // Zero for success. Otherwise complement of error number.
+    ): isize
+
+    // Reads part of a fragmented entry from the store.
+    //
+    // The runner writes the bytes of the entry starting at `offset` directly into the
+    // buffer. Large entries may thus be read in a single call, or in a few calls at
+    // increasing offsets when the applet can't afford a buffer for the whole entry.
+    @external("env", "sfo")
+    export declare function store_fragment_read(
+      // Range of keys (same encoding as for `insert`).
+      keys: usize,
+
+      // Offset in the entry of the first byte to read.
+      offset: usize,
+
+      // Where to write the bytes read.
+      ptr: usize,
+
+      // Length of the buffer.
+      len: usize,
+    // Number of bytes read. Otherwise complement of error number.
+    //
+    // This is smaller than the length of the buffer when the end of the entry is
+    // reached. It is zero if the entry is not found or the offset is past its end.
+    ): isize
+  // END OF MODULE store_fragment
 // END OF MODULE store
 
+// START OF MODULE temp
+// Temperature sensor operations.
+  // Whether a temperature sensor is available.
+  @external("env", "hs")
+  export declare function temp_is_supported(
+  // 1 if supported, 0 otherwise.
+  ): usize
+
+  // Reads the temperature.
+  @external("env", "hr")
+  export declare function temp_read(
+    // Pointer to the 4 bytes of the temperature in millidegrees Celsius (little-endian
+    // i32).
+    ptr: usize,
+  // Zero on success, negative on error.
+  ): isize
+// END OF MODULE temp
+
+// START OF MODULE uart
+// UART operations.
+//
+// These are serial ports on physical pins, distinct from the USB serial. A board may have
+// multiple UARTs. They are identified by their index, starting at zero.
+  // Returns the number of UARTs.
+  @external("env", "uac")
+  export declare function uart_count(
+  // How many UARTs are available.
+  ): usize
+
+  // Sets the baud rate of a UART.
+  @external("env", "uab")
+  export declare function uart_set_baudrate(
+    // Index of the UART.
+    uart: usize,
+
+    // Baud rate in bits per second.
+    baudrate: usize,
+  // Zero on success, -1 on error (e.g. unsupported baud rate).
+  ): isize
+
+  // Reads from a UART into a buffer.
+  @external("env", "uar")
+  export declare function uart_read(
+    // Index of the UART.
+    uart: usize,
+
+    // Address of the buffer.
+    ptr: usize,
+
+    // Length of the buffer in bytes.
+    len: usize,
+  // Number of bytes read (or negative value for errors).
+  //
+  // This function does not block and may return zero.
+  ): isize
+
+  // Writes to a UART from a buffer.
+  @external("env", "uaw")
+  export declare function uart_write(
+    // Index of the UART.
+    uart: usize,
+
+    // Address of the buffer.
+    ptr: usize,
+
+    // Length of the buffer in bytes.
+    len: usize,
+  // Number of bytes written (or negative value for errors).
+  //
+  // This function does not block and may return zero.
+  ): isize
+
+  // Flushes a UART.
+  @external("env", "uaf")
+  export declare function uart_flush(
+    // Index of the UART.
+    uart: usize,
+  // Zero on success, -1 on error.
+  ): isize
+
+  // UART events.
+  enum uart_Event {
+    // Ready for read.
+    Read,
+
+    // Ready for write.
+    Write,
+  }
+
+  // Registers a callback when a UART is ready.
+  //
+  // It is possible that the callback is spuriously called.
+  @external("env", "uae")
+  export declare function uart_register(
+    uart: usize,
+
+    event: usize,
+
+    handler_func: usize,
+
+    handler_data: usize,
+  ): void
+
+  // Unregisters a callback.
+  @external("env", "uad")
+  export declare function uart_unregister(
+    uart: usize,
+
+    event: usize,
+  ): void
+// END OF MODULE uart
+
 // START OF MODULE usb
 // USB operations.
   // Describes errors on USB operations.
@@ -483,10 +1514,77 @@
     Unknown,
   }
 
+  // START OF MODULE usb_hid
+  // USB HID operations.
+  //
+  // Reports are always 64 bytes long.
+    // Reads a report from USB HID into a 64-bytes buffer.
+    @external("env", "uhr")
+    export declare function usb_hid_read(
+      // Address of the 64-bytes buffer.
+      ptr: usize,
+    // One if a report was read, zero if there is nothing to read, and negative value
+    // for errors.
+    //
+    // This function does not block.
+    ): isize
+
+    // Writes a report to USB HID from a 64-bytes buffer.
+    @external("env", "uhw")
+    export declare function usb_hid_write(
+      // Address of the 64-bytes buffer.
+      ptr: usize,
+    // One if the report was written, zero if the other side is not ready, and
+    // negative value for errors.
+    //
+    // This function does not block.
+    ): isize
+
+    // USB HID events.
+    enum usb_hid_Event {
+      // Ready for read.
+      Read,
+
+      // Ready for write.
+      Write,
+    }
+
+    // Registers a callback when USB HID is ready.
+    //
+    // It is possible that the callback is spuriously called.
+    @external("env", "uhe")
+    export declare function usb_hid_register(
+      event: usize,
+
+      handler_func: usize,
+
+      handler_data: usize,
+    ): void
+
+    // Unregisters a callback.
+    @external("env", "uhd")
+    export declare function usb_hid_unregister(
+      event: usize,
+    ): void
+  // END OF MODULE usb_hid
+
   // START OF MODULE usb_serial
-    // Reads from USB serial into a buffer.
+  // USB serial operations.
+  //
+  // A board may have multiple serial ports. They are identified by their index, starting at
+  // zero.
+    // Returns the number of serial ports.
+    @external("env", "usc")
+    export declare function usb_serial_count(
+    // How many serial ports are available.
+    ): usize
+
+    // Reads from a USB serial port into a buffer.
     @external("env", "usr")
     export declare function usb_serial_read(
+      // Index of the serial port.
+      port: usize,
+
       // Address of the buffer.
       ptr: usize,
 
@@ -497,9 +1595,12 @@
     // This function does not block and may return zero.
     ): isize
 
-    // Writes to USB serial from a buffer.
+    // Writes to a USB serial port from a buffer.
     @external("env", "usw")
     export declare function usb_serial_write(
+      // Index of the serial port.
+      port: usize,
+
       // Address of the buffer.
       ptr: usize,
 
@@ -517,13 +1618,43 @@
 
       // Ready for write.
       Write,
+
+      // Line state changed.
+      Control,
+
+      // Line coding changed.
+      Coding,
+    }
+
+    // Number of stop bits.
+    enum usb_serial_StopBits {
+      One,
+
+      OnePointFive,
+
+      Two,
+    }
+
+    // Parity type.
+    enum usb_serial_Parity {
+      None,
+
+      Odd,
+
+      Even,
+
+      Mark,
+
+      Space,
     }
 
-    // Registers a callback when USB serial is ready.
+    // Registers a callback when a USB serial port is ready.
     //
     // It is possible that the callback is spuriously called.
     @external("env", "use")
     export declare function usb_serial_register(
+      port: usize,
+
       event: usize,
 
       handler_func: usize,
@@ -534,12 +1665,49 @@
     // Unregisters a callback.
     @external("env", "usd")
     export declare function usb_serial_unregister(
+      port: usize,
+
       event: usize,
     ): void
 
-    // Flushs the USB serial.
+    // Flushs a USB serial port.
     @external("env", "usf")
     export declare function usb_serial_flush(
+      // Index of the serial port.
+      port: usize,
+    // Zero on success, -1 on error.
+    ): isize
+
+    // Flushes a USB serial port, waiting at most a given duration.
+    @external("env", "ust")
+    export declare function usb_serial_flush_timeout(
+      // Index of the serial port.
+      port: usize,
+
+      // Maximum duration to wait in milliseconds.
+      timeout_ms: usize,
+    // Zero on success, -1 on error (including timeout).
+    ): isize
+
+    // Returns the line state of a USB serial port.
+    @external("env", "usl")
+    export declare function usb_serial_line_state(
+      // Index of the serial port.
+      port: usize,
+    // Bit 0 is DTR and bit 1 is RTS (or -1 on error).
+    ): isize
+
+    // Returns the line coding of a USB serial port.
+    @external("env", "usk")
+    export declare function usb_serial_line_coding(
+      // Index of the serial port.
+      port: usize,
+
+      // Pointer to the 8 bytes of the line coding.
+      //
+      // The data rate in bits per second as little-endian u32, followed by the stop
+      // bits, the parity, the number of data bits, and a zero byte.
+      ptr: usize,
     // Zero on success, -1 on error.
     ): isize
   // END OF MODULE usb_serial
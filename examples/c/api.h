@@ -0,0 +1,2291 @@
+// Copyright 2022 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#ifndef WASEFIRE_API_H
+#define WASEFIRE_API_H
+
+#include <stdint.h>
+
+#ifdef __wasm__
+#define WASEFIRE_IMPORT(name) \
+  __attribute__((import_module("env"), import_name(name)))
+#else
+#define WASEFIRE_IMPORT(name)
+#endif
+
+// Version of the applet API.
+#define WASEFIRE_API_VERSION 1
+
+// START OF MODULE adc
+// Analog-to-digital converter operations.
+
+// Results of adc_count().
+typedef struct {
+  // How many analog inputs are on the device.
+  uint32_t cnt;
+} adc_count_results_t;
+
+// Returns how many analog inputs are on the device.
+WASEFIRE_IMPORT("ac")
+extern uint32_t adc_count(void);
+
+// Parameters of adc_read().
+typedef struct {
+  // Index of the analog input to sample.
+  uint32_t adc;
+} adc_read_params_t;
+
+// Results of adc_read().
+typedef struct {
+  // The sample normalized to 16 bits, or a negative value on error.
+  //
+  // A sample of 0 is ground and 65535 is the reference voltage.
+  int32_t res;
+} adc_read_results_t;
+
+// Samples an analog input.
+WASEFIRE_IMPORT("ar")
+extern int32_t adc_read(uint32_t adc);
+
+// Results of adc_read_vdd().
+typedef struct {
+  // The supply voltage in millivolts, or a negative value on error.
+  int32_t res;
+} adc_read_vdd_results_t;
+
+// Measures the supply voltage.
+WASEFIRE_IMPORT("av")
+extern int32_t adc_read_vdd(void);
+
+// END OF MODULE adc
+
+// START OF MODULE button
+// Button and touch operations.
+
+// Describes the state of a button.
+enum button_state {
+  // The button is released.
+  BUTTON_STATE_RELEASED,
+  // The button is pressed.
+  BUTTON_STATE_PRESSED,
+};
+
+// Describes a button gesture.
+enum button_gesture {
+  // The button was pressed and released once.
+  BUTTON_GESTURE_SINGLE,
+  // The button was held pressed for at least the long-press threshold.
+  BUTTON_GESTURE_LONG,
+  // The button was pressed twice within the double-press window.
+  BUTTON_GESTURE_DOUBLE,
+};
+
+// Results of button_count().
+typedef struct {
+  // How many buttons are on the device.
+  uint32_t cnt;
+} button_count_results_t;
+
+// Returns how many buttons are on the device.
+WASEFIRE_IMPORT("bc")
+extern uint32_t button_count(void);
+
+// Parameters of button_register().
+typedef struct {
+  // Index of the button to listen to.
+  uint32_t button;
+  // Function called on button events.
+  //
+  // The function takes its opaque `data` and the new button `state` as arguments.
+  // If gestures are configured for the button, the function is called with a
+  // `Gesture` instead of a `State`.
+  void (*handler_func)(uint8_t *data, uint32_t state);
+  // The opaque data to use when calling the handler function.
+  uint8_t *handler_data;
+} button_register_params_t;
+
+// Register a handler for button events.
+WASEFIRE_IMPORT("br")
+extern void button_register(uint32_t button, void (*handler_func)(uint8_t *data, uint32_t state), uint8_t *handler_data);
+
+// Parameters of button_gesture().
+typedef struct {
+  // Index of the button to configure.
+  uint32_t button;
+  // Minimum duration in milliseconds of a long press.
+  //
+  // Zero disables gesture recognition for the button.
+  uint32_t long_;
+  // Maximum duration in milliseconds between a release and the next press for a
+  // double press.
+  //
+  // Zero disables double presses such that single presses are reported on release.
+  uint32_t double_;
+} button_gesture_params_t;
+
+// Results of button_gesture().
+typedef struct {
+  // Zero on success, -1 if there are no timers available.
+  int32_t res;
+} button_gesture_results_t;
+
+// Configures gesture recognition for a button.
+//
+// When configured, the button handler is called on gestures instead of state changes.
+WASEFIRE_IMPORT("bg")
+extern int32_t button_gesture(uint32_t button, uint32_t long_, uint32_t double_);
+
+// Parameters of button_unregister().
+typedef struct {
+  // Index of the button to stop listening to.
+  uint32_t button;
+} button_unregister_params_t;
+
+// Unregister handlers for button events.
+WASEFIRE_IMPORT("bu")
+extern void button_unregister(uint32_t button);
+
+// END OF MODULE button
+
+// START OF MODULE capture
+// Input capture operations.
+//
+// The GPIO must be configured as an input with the `gpio` module.
+
+// Parameters of capture_start().
+typedef struct {
+  // Index of the GPIO.
+  uint32_t gpio;
+} capture_start_params_t;
+
+// Results of capture_start().
+typedef struct {
+  // Zero on success, negative on error.
+  //
+  // It is an error to start a measurement while another one is running.
+  int32_t res;
+} capture_start_results_t;
+
+// Starts measuring the frequency and duty cycle of the signal on a GPIO.
+//
+// The measurement is executed by the board. Register a handler to be notified when
+// the measurement is done.
+WASEFIRE_IMPORT("cs")
+extern int32_t capture_start(uint32_t gpio);
+
+// Parameters of capture_result().
+typedef struct {
+  // Index of the GPIO.
+  uint32_t gpio;
+  // Pointer to the 8 bytes of the measurement.
+  //
+  // The period and the high time of the signal in nanoseconds as little-endian u32.
+  uint8_t *ptr;
+} capture_result_params_t;
+
+// Results of capture_result().
+typedef struct {
+  // One if the signal was measured, zero if there is no signal, and negative on
+  // error.
+  //
+  // It is an error to read the result of a measurement that is not done.
+  int32_t res;
+} capture_result_results_t;
+
+// Reads the result of the done measurement of a GPIO.
+WASEFIRE_IMPORT("cr")
+extern int32_t capture_result(uint32_t gpio, uint8_t *ptr);
+
+// Parameters of capture_register().
+typedef struct {
+  // Index of the GPIO to listen to.
+  uint32_t gpio;
+  // Function called when a measurement is done.
+  //
+  // The function takes its opaque `data` as argument.
+  void (*handler_func)(uint8_t *data);
+  // The opaque data to use when calling the handler function.
+  uint8_t *handler_data;
+} capture_register_params_t;
+
+// Register a handler for input capture events.
+WASEFIRE_IMPORT("ce")
+extern void capture_register(uint32_t gpio, void (*handler_func)(uint8_t *data), uint8_t *handler_data);
+
+// Parameters of capture_unregister().
+typedef struct {
+  // Index of the GPIO to stop listening to.
+  uint32_t gpio;
+} capture_unregister_params_t;
+
+// Unregister handlers for input capture events.
+WASEFIRE_IMPORT("cd")
+extern void capture_unregister(uint32_t gpio);
+
+// END OF MODULE capture
+
+// START OF MODULE clock
+// Clock and timer operations.
+
+// Whether a timer should periodically trigger.
+enum clock_mode {
+  // The timer fires only once.
+  CLOCK_MODE_ONESHOT,
+  // The timer fires periodically.
+  CLOCK_MODE_PERIODIC,
+};
+
+// Parameters of clock_allocate().
+typedef struct {
+  // Function called when the timer triggers.
+  void (*handler_func)(uint8_t *data);
+  // The opaque data to use when calling the handler function.
+  uint8_t *handler_data;
+} clock_allocate_params_t;
+
+// Results of clock_allocate().
+typedef struct {
+  // Identifier for this timer.
+  uint32_t id;
+} clock_allocate_results_t;
+
+// Allocates a timer (initially stopped).
+WASEFIRE_IMPORT("ta")
+extern uint32_t clock_allocate(void (*handler_func)(uint8_t *data), uint8_t *handler_data);
+
+// Parameters of clock_start().
+typedef struct {
+  // The identifier of the timer to start.
+  //
+  // It must come from an allocated timer that wasn't stopped.
+  uint32_t id;
+  // Whether the timer should periodically fire.
+  //
+  // Valid values are defined by [`Mode`](super::Mode).
+  uint32_t mode;
+  // How long until the timer triggers in milli-seconds.
+  uint32_t duration_ms;
+} clock_start_params_t;
+
+// Starts a stopped timer given its id.
+WASEFIRE_IMPORT("tb")
+extern void clock_start(uint32_t id, uint32_t mode, uint32_t duration_ms);
+
+// Parameters of clock_stop().
+typedef struct {
+  // The identifier of the timer to start.
+  uint32_t id;
+} clock_stop_params_t;
+
+// Stops a running timer given its id.
+//
+// Note that if the timer triggers while being stopped, the handler may still be
+// called.
+WASEFIRE_IMPORT("tc")
+extern void clock_stop(uint32_t id);
+
+// Parameters of clock_free().
+typedef struct {
+  // The identifier of the timer to start.
+  uint32_t id;
+} clock_free_params_t;
+
+// Deallocates a stopped timer given its id.
+WASEFIRE_IMPORT("td")
+extern void clock_free(uint32_t id);
+
+// Parameters of clock_uptime().
+typedef struct {
+  // Pointer to the 8 bytes of the uptime in micro-seconds (little-endian).
+  uint8_t *ptr;
+} clock_uptime_params_t;
+
+// Results of clock_uptime().
+typedef struct {
+  // Zero on success, negative on error.
+  int32_t res;
+} clock_uptime_results_t;
+
+// Returns the time since the platform started.
+WASEFIRE_IMPORT("tu")
+extern int32_t clock_uptime(uint8_t *ptr);
+
+// END OF MODULE clock
+
+// START OF MODULE crypto
+// Cryptographic operations.
+
+// Describes errors on cryptographic operations.
+enum crypto_error {
+  // A function pre-condition was broken.
+  CRYPTO_ERROR_INVALID_ARGUMENT,
+  // An operation is unsupported.
+  CRYPTO_ERROR_UNSUPPORTED,
+};
+
+// START OF MODULE crypto_ccm
+// AES-CCM according to Bluetooth.
+
+// Results of crypto_ccm_is_supported().
+typedef struct {
+  // 1 if supported, 0 otherwise.
+  uint32_t supported;
+} crypto_ccm_is_supported_results_t;
+
+// Whether AES-CCM is supported.
+WASEFIRE_IMPORT("ccs")
+extern uint32_t crypto_ccm_is_supported(void);
+
+// Parameters of crypto_ccm_encrypt().
+typedef struct {
+  // The 16 bytes key to encrypt with.
+  uint8_t const *key;
+  // The 8 bytes IV to encrypt with.
+  uint8_t const *iv;
+  // Length in bytes of the `clear` text.
+  //
+  // This must be at most 251 bytes. The `cipher` length must be 4 bytes longer than
+  // this value.
+  uint32_t len;
+  // The clear text to encrypt from.
+  //
+  // Its length must be provided in the `len` field.
+  uint8_t const *clear;
+  // The cipher text to encrypt to.
+  //
+  // Its length must be `len + 4` bytes.
+  uint8_t *cipher;
+} crypto_ccm_encrypt_params_t;
+
+// Results of crypto_ccm_encrypt().
+typedef struct {
+  // Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+  // otherwise.
+  int32_t res;
+} crypto_ccm_encrypt_results_t;
+
+// Encrypts a clear text given a key and IV.
+WASEFIRE_IMPORT("cce")
+extern int32_t crypto_ccm_encrypt(uint8_t const *key, uint8_t const *iv, uint32_t len, uint8_t const *clear, uint8_t *cipher);
+
+// Parameters of crypto_ccm_decrypt().
+typedef struct {
+  // The 16 bytes key to encrypt with.
+  uint8_t const *key;
+  // The 8 bytes IV to encrypt with.
+  uint8_t const *iv;
+  // Length in bytes of the `clear` text.
+  //
+  // This must be at most 251 bytes. The `cipher` length must be 4 bytes longer than
+  // this value.
+  uint32_t len;
+  // The cipher text to encrypt from.
+  //
+  // Its length must be `len + 4` bytes.
+  uint8_t const *cipher;
+  // The clear text to encrypt to.
+  //
+  // Its length must be provided in the `len` field.
+  uint8_t *clear;
+} crypto_ccm_decrypt_params_t;
+
+// Results of crypto_ccm_decrypt().
+typedef struct {
+  // Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+  // otherwise.
+  int32_t res;
+} crypto_ccm_decrypt_results_t;
+
+// Decrypts a cipher text given a key and IV.
+WASEFIRE_IMPORT("ccd")
+extern int32_t crypto_ccm_decrypt(uint8_t const *key, uint8_t const *iv, uint32_t len, uint8_t const *cipher, uint8_t *clear);
+
+// END OF MODULE crypto_ccm
+
+// START OF MODULE crypto_crc
+// Cyclic redundancy checks.
+//
+// CRC-32 is the one used by Ethernet, zlib, and PNG (also known as CRC-32/ISO-HDLC).
+// CRC-16/CCITT has initial value 0xffff and no final xor (also known as CRC-16/IBM-3740
+// or CRC-16/CCITT-FALSE). Checksums are written in little-endian.
+
+// Results of crypto_crc_is_supported().
+typedef struct {
+  // 1 if supported, 0 otherwise.
+  uint32_t supported;
+} crypto_crc_is_supported_results_t;
+
+// Whether CRCs are supported.
+WASEFIRE_IMPORT("crs")
+extern uint32_t crypto_crc_is_supported(void);
+
+// Parameters of crypto_crc_crc32().
+typedef struct {
+  // The pointer to the data.
+  uint8_t const *data;
+  // The length of the data.
+  uint32_t length;
+  // The 4 bytes checksum.
+  uint8_t *crc;
+} crypto_crc_crc32_params_t;
+
+// Results of crypto_crc_crc32().
+typedef struct {
+  // Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+  // otherwise.
+  int32_t res;
+} crypto_crc_crc32_results_t;
+
+// Computes the CRC-32 of some data.
+WASEFIRE_IMPORT("crw")
+extern int32_t crypto_crc_crc32(uint8_t const *data, uint32_t length, uint8_t *crc);
+
+// Parameters of crypto_crc_crc16_ccitt().
+typedef struct {
+  // The pointer to the data.
+  uint8_t const *data;
+  // The length of the data.
+  uint32_t length;
+  // The 2 bytes checksum.
+  uint8_t *crc;
+} crypto_crc_crc16_ccitt_params_t;
+
+// Results of crypto_crc_crc16_ccitt().
+typedef struct {
+  // Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+  // otherwise.
+  int32_t res;
+} crypto_crc_crc16_ccitt_results_t;
+
+// Computes the CRC-16/CCITT of some data.
+WASEFIRE_IMPORT("crh")
+extern int32_t crypto_crc_crc16_ccitt(uint8_t const *data, uint32_t length, uint8_t *crc);
+
+// END OF MODULE crypto_crc
+
+// START OF MODULE crypto_ecdh
+// ECDH over P-256.
+//
+// Private keys are 32 bytes big-endian scalars. Public keys are 64 bytes uncompressed
+// points (the big-endian X coordinate followed by the Y coordinate). Shared secrets are
+// the 32 bytes X coordinate of the product point (as specified by SEC1).
+
+// Results of crypto_ecdh_is_supported().
+typedef struct {
+  // 1 if supported, 0 otherwise.
+  uint32_t supported;
+} crypto_ecdh_is_supported_results_t;
+
+// Whether ECDH over P-256 is supported.
+WASEFIRE_IMPORT("ces")
+extern uint32_t crypto_ecdh_is_supported(void);
+
+// Parameters of crypto_ecdh_generate_keypair().
+typedef struct {
+  // The 32 bytes private key.
+  uint8_t *private;
+  // The 64 bytes public key.
+  uint8_t *public;
+} crypto_ecdh_generate_keypair_params_t;
+
+// Results of crypto_ecdh_generate_keypair().
+typedef struct {
+  // Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+  // otherwise.
+  int32_t res;
+} crypto_ecdh_generate_keypair_results_t;
+
+// Generates a random key pair.
+WASEFIRE_IMPORT("ceg")
+extern int32_t crypto_ecdh_generate_keypair(uint8_t *private, uint8_t *public);
+
+// Parameters of crypto_ecdh_agree().
+typedef struct {
+  // The 32 bytes private key.
+  uint8_t const *private;
+  // The 64 bytes public key of the peer.
+  uint8_t const *public;
+  // The 32 bytes shared secret.
+  uint8_t *shared;
+} crypto_ecdh_agree_params_t;
+
+// Results of crypto_ecdh_agree().
+typedef struct {
+  // Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+  // otherwise.
+  int32_t res;
+} crypto_ecdh_agree_results_t;
+
+// Computes the shared secret of a private key and a peer public key.
+WASEFIRE_IMPORT("cea")
+extern int32_t crypto_ecdh_agree(uint8_t const *private, uint8_t const *public, uint8_t *shared);
+
+// END OF MODULE crypto_ecdh
+
+// START OF MODULE crypto_gcm
+// AES-256-GCM.
+
+// Results of crypto_gcm_is_supported().
+typedef struct {
+  // 1 if supported, 0 otherwise.
+  uint32_t supported;
+} crypto_gcm_is_supported_results_t;
+
+// Whether AES-256-GCM is supported.
+WASEFIRE_IMPORT("cgs")
+extern uint32_t crypto_gcm_is_supported(void);
+
+// Parameters of crypto_gcm_encrypt().
+typedef struct {
+  // The 32 bytes key.
+  uint8_t const *key;
+  // The 12 bytes IV.
+  uint8_t const *iv;
+  // The additional authenticated data.
+  uint8_t const *aad;
+  // The length of the additional authenticated data.
+  uint32_t aad_len;
+  // The length of the clear (and cipher) text.
+  uint32_t length;
+  // The clear text.
+  uint8_t const *clear;
+  // The cipher text.
+  uint8_t *cipher;
+  // The 16 bytes authentication tag.
+  uint8_t *tag;
+} crypto_gcm_encrypt_params_t;
+
+// Results of crypto_gcm_encrypt().
+typedef struct {
+  // Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+  // otherwise.
+  int32_t res;
+} crypto_gcm_encrypt_results_t;
+
+// Encrypts and authenticates a clear text with associated data given a key and IV.
+WASEFIRE_IMPORT("cge")
+extern int32_t crypto_gcm_encrypt(uint8_t const *key, uint8_t const *iv, uint8_t const *aad, uint32_t aad_len, uint32_t length, uint8_t const *clear, uint8_t *cipher, uint8_t *tag);
+
+// Parameters of crypto_gcm_decrypt().
+typedef struct {
+  // The 32 bytes key.
+  uint8_t const *key;
+  // The 12 bytes IV.
+  uint8_t const *iv;
+  // The additional authenticated data.
+  uint8_t const *aad;
+  // The length of the additional authenticated data.
+  uint32_t aad_len;
+  // The 16 bytes authentication tag.
+  uint8_t const *tag;
+  // The length of the cipher (and clear) text.
+  uint32_t length;
+  // The cipher text.
+  uint8_t const *cipher;
+  // The clear text.
+  uint8_t *clear;
+} crypto_gcm_decrypt_params_t;
+
+// Results of crypto_gcm_decrypt().
+typedef struct {
+  // Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+  // otherwise.
+  int32_t res;
+} crypto_gcm_decrypt_results_t;
+
+// Decrypts and authenticates a cipher text with associated data given a key and IV.
+WASEFIRE_IMPORT("cgd")
+extern int32_t crypto_gcm_decrypt(uint8_t const *key, uint8_t const *iv, uint8_t const *aad, uint32_t aad_len, uint8_t const *tag, uint32_t length, uint8_t const *cipher, uint8_t *clear);
+
+// END OF MODULE crypto_gcm
+
+// START OF MODULE crypto_hash
+// Hash functions.
+
+// Hash algorithm.
+enum crypto_hash_algorithm {
+  // SHA-256.
+  CRYPTO_HASH_ALGORITHM_SHA256,
+};
+
+// Parameters of crypto_hash_is_supported().
+typedef struct {
+  // The hash algorithm.
+  uint32_t algorithm;
+} crypto_hash_is_supported_params_t;
+
+// Results of crypto_hash_is_supported().
+typedef struct {
+  // 1 if supported, 0 otherwise.
+  uint32_t supported;
+} crypto_hash_is_supported_results_t;
+
+// Whether the algorithm is supported.
+WASEFIRE_IMPORT("chs")
+extern uint32_t crypto_hash_is_supported(uint32_t algorithm);
+
+// Parameters of crypto_hash_initialize().
+typedef struct {
+  // The hash algorithm.
+  uint32_t algorithm;
+} crypto_hash_initialize_params_t;
+
+// Results of crypto_hash_initialize().
+typedef struct {
+  // A non-negative identifier on success, bitwise complement of
+  // [`Error`](crate::crypto::Error) otherwise.
+  int32_t id;
+} crypto_hash_initialize_results_t;
+
+// Initializes a hash.
+WASEFIRE_IMPORT("chi")
+extern int32_t crypto_hash_initialize(uint32_t algorithm);
+
+// Parameters of crypto_hash_update().
+typedef struct {
+  // The identifier returned by the associated [`initialize()`] call.
+  uint32_t id;
+  // The pointer to the data to hash.
+  uint8_t const *data;
+  // The length of the data to hash.
+  uint32_t length;
+} crypto_hash_update_params_t;
+
+// Updates a hash.
+//
+// Errors are surfaced in the [`finalize()`] call.
+WASEFIRE_IMPORT("chu")
+extern void crypto_hash_update(uint32_t id, uint8_t const *data, uint32_t length);
+
+// Parameters of crypto_hash_finalize().
+typedef struct {
+  // The identifier returned by the associated [`initialize()`] call.
+  //
+  // This is consumed and invalidated by this call regardless of the return value.
+  uint32_t id;
+  // The pointer to the buffer where the digest must be written.
+  //
+  // Its length is defined by the algorithm:
+  // - 32 bytes for SHA-256.
+  //
+  // The pointer may be null, in which case this function deallocates the identifier
+  // without computing the digest.
+  uint8_t *digest;
+} crypto_hash_finalize_params_t;
+
+// Results of crypto_hash_finalize().
+typedef struct {
+  // Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+  // otherwise.
+  int32_t res;
+} crypto_hash_finalize_results_t;
+
+// Finalizes a hash.
+WASEFIRE_IMPORT("chf")
+extern int32_t crypto_hash_finalize(uint32_t id, uint8_t *digest);
+
+// END OF MODULE crypto_hash
+
+// END OF MODULE crypto
+
+// START OF MODULE debug
+// Debugging operations.
+
+// Parameters of debug_println().
+typedef struct {
+  // The message to print.
+  //
+  // Traps if the message is not valid UTF-8.
+  uint8_t const *ptr;
+  // The length of the message in bytes.
+  uint32_t len;
+} debug_println_params_t;
+
+// Prints a message to the debug output.
+//
+// If debug output is disabled then this is a no-op.
+WASEFIRE_IMPORT("dp")
+extern void debug_println(uint8_t const *ptr, uint32_t len);
+
+// Parameters of debug_heap_stats().
+typedef struct {
+  // Pointer to the 12 bytes of statistics.
+  //
+  // The used, free, and high water bytes as little-endian u32.
+  uint8_t *ptr;
+} debug_heap_stats_params_t;
+
+// Results of debug_heap_stats().
+typedef struct {
+  // Zero on success, negative on error.
+  int32_t res;
+} debug_heap_stats_results_t;
+
+// Reads the heap statistics of the platform.
+WASEFIRE_IMPORT("dh")
+extern int32_t debug_heap_stats(uint8_t *ptr);
+
+// Parameters of debug_exit().
+typedef struct {
+  // 0 for success, 1 for failure
+  uint32_t code;
+} debug_exit_params_t;
+
+// Exits the platform with an error code.
+//
+// This is used by test applets to terminate the platform and propagate the test
+// result.
+WASEFIRE_IMPORT("de")
+extern void debug_exit(uint32_t code);
+
+// Logging levels from the least to the most verbose.
+enum debug_level {
+  DEBUG_LEVEL_ERROR,
+  DEBUG_LEVEL_WARN,
+  DEBUG_LEVEL_INFO,
+  DEBUG_LEVEL_DEBUG,
+  DEBUG_LEVEL_TRACE,
+};
+
+// Parameters of debug_set_log_level().
+typedef struct {
+  // The most verbose level to log.
+  //
+  // Valid values are defined by [`Level`](super::Level).
+  uint32_t level;
+} debug_set_log_level_params_t;
+
+// Results of debug_set_log_level().
+typedef struct {
+  // Zero on success, negative on error.
+  int32_t res;
+} debug_set_log_level_results_t;
+
+// Sets the most verbose level of the platform logs.
+//
+// Levels disabled when the platform was compiled can't be enabled.
+WASEFIRE_IMPORT("dl")
+extern int32_t debug_set_log_level(uint32_t level);
+
+// Parameters of debug_log().
+typedef struct {
+  // The level of the message.
+  //
+  // Valid values are defined by [`Level`](super::Level).
+  uint32_t level;
+  // The message to log.
+  //
+  // Traps if the message is not valid UTF-8.
+  uint8_t const *ptr;
+  // The length of the message in bytes.
+  uint32_t len;
+} debug_log_params_t;
+
+// Logs a message at a given level in the platform logs.
+//
+// The message interleaves with the platform logs and is subject to the same level
+// filter. If logging is disabled then this is a no-op.
+WASEFIRE_IMPORT("dg")
+extern void debug_log(uint32_t level, uint8_t const *ptr, uint32_t len);
+
+// END OF MODULE debug
+
+// START OF MODULE encoder
+// Quadrature encoder operations.
+
+// Results of encoder_count().
+typedef struct {
+  // How many encoders are on the device.
+  uint32_t cnt;
+} encoder_count_results_t;
+
+// Returns how many encoders are on the device.
+WASEFIRE_IMPORT("ec")
+extern uint32_t encoder_count(void);
+
+// Parameters of encoder_position().
+typedef struct {
+  // Index of the encoder.
+  uint32_t encoder;
+} encoder_position_params_t;
+
+// Results of encoder_position().
+typedef struct {
+  // Current position in steps.
+  int32_t position;
+} encoder_position_results_t;
+
+// Returns the current position of an encoder.
+WASEFIRE_IMPORT("ep")
+extern int32_t encoder_position(uint32_t encoder);
+
+// Parameters of encoder_home().
+typedef struct {
+  // Index of the encoder.
+  uint32_t encoder;
+} encoder_home_params_t;
+
+// Results of encoder_home().
+typedef struct {
+  // Error code: 0 on success, -1 on error
+  int32_t res;
+} encoder_home_results_t;
+
+// Starts homing an encoder.
+//
+// The position is zeroed at the next index pulse. The shaft must be rotated while
+// homing. Register a handler to be notified when homing is done.
+WASEFIRE_IMPORT("eh")
+extern int32_t encoder_home(uint32_t encoder);
+
+// Parameters of encoder_register().
+typedef struct {
+  // Index of the encoder to listen to.
+  uint32_t encoder;
+  // Function called when homing is done.
+  //
+  // The function takes its opaque `data` as argument.
+  void (*handler_func)(uint8_t *data);
+  // The opaque data to use when calling the handler function.
+  uint8_t *handler_data;
+} encoder_register_params_t;
+
+// Register a handler for encoder events.
+WASEFIRE_IMPORT("er")
+extern void encoder_register(uint32_t encoder, void (*handler_func)(uint8_t *data), uint8_t *handler_data);
+
+// Parameters of encoder_unregister().
+typedef struct {
+  // Index of the encoder to stop listening to.
+  uint32_t encoder;
+} encoder_unregister_params_t;
+
+// Unregister handlers for encoder events.
+WASEFIRE_IMPORT("eu")
+extern void encoder_unregister(uint32_t encoder);
+
+// END OF MODULE encoder
+
+// START OF MODULE gpio
+// General-purpose input/output operations.
+
+// Results of gpio_count().
+typedef struct {
+  // How many GPIOs are on the device.
+  uint32_t cnt;
+} gpio_count_results_t;
+
+// Returns how many GPIOs are on the device.
+WASEFIRE_IMPORT("gc")
+extern uint32_t gpio_count(void);
+
+// Describes the configuration of a GPIO.
+enum gpio_mode {
+  // Input with an internal pull-up resistor.
+  GPIO_MODE_INPUT_PULL_UP,
+  // Input with an internal pull-down resistor.
+  GPIO_MODE_INPUT_PULL_DOWN,
+  // Output driving both levels.
+  GPIO_MODE_OUTPUT_PUSH_PULL,
+  // Output driving only the low level.
+  GPIO_MODE_OUTPUT_OPEN_DRAIN,
+};
+
+// Parameters of gpio_configure().
+typedef struct {
+  // Index of the GPIO to configure.
+  uint32_t gpio;
+  // The new mode of the GPIO.
+  //
+  // Valid values are defined by [`Mode`](super::Mode).
+  uint32_t mode;
+} gpio_configure_params_t;
+
+// Configures a GPIO.
+WASEFIRE_IMPORT("gf")
+extern void gpio_configure(uint32_t gpio, uint32_t mode);
+
+// Parameters of gpio_read().
+typedef struct {
+  // Index of the GPIO to read.
+  uint32_t gpio;
+} gpio_read_params_t;
+
+// Results of gpio_read().
+typedef struct {
+  // 0 for low and 1 for high.
+  uint32_t level;
+} gpio_read_results_t;
+
+// Reads the level of a configured GPIO.
+WASEFIRE_IMPORT("gr")
+extern uint32_t gpio_read(uint32_t gpio);
+
+// Parameters of gpio_write().
+typedef struct {
+  // Index of the GPIO to write.
+  uint32_t gpio;
+  // 0 for low and 1 for high.
+  uint32_t level;
+} gpio_write_params_t;
+
+// Writes the level of a GPIO configured as output.
+WASEFIRE_IMPORT("gw")
+extern void gpio_write(uint32_t gpio, uint32_t level);
+
+// END OF MODULE gpio
+
+// START OF MODULE i2c_sw
+// Software I2C operations.
+//
+// The bus is bit-banged on 2 GPIOs. It is slower than a hardware bus and the CPU is busy
+// for the whole transfer.
+
+// Parameters of i2c_sw_configure().
+typedef struct {
+  // Index of the GPIO of the data line.
+  uint32_t sda;
+  // Index of the GPIO of the clock line.
+  uint32_t scl;
+  // Clock rate in Hertz.
+  uint32_t frequency;
+} i2c_sw_configure_params_t;
+
+// Results of i2c_sw_configure().
+typedef struct {
+  // Zero on success, negative on error.
+  int32_t res;
+} i2c_sw_configure_results_t;
+
+// Configures the bus.
+//
+// This must be called before any transfer.
+WASEFIRE_IMPORT("isc")
+extern int32_t i2c_sw_configure(uint32_t sda, uint32_t scl, uint32_t frequency);
+
+// Parameters of i2c_sw_read().
+typedef struct {
+  // 7-bits address of the device.
+  uint32_t addr;
+  // Address of the buffer.
+  uint8_t *ptr;
+  // Length of the buffer in bytes.
+  uint32_t len;
+} i2c_sw_read_params_t;
+
+// Results of i2c_sw_read().
+typedef struct {
+  // Zero on success, negative on error.
+  int32_t res;
+} i2c_sw_read_results_t;
+
+// Reads bytes from a device into a buffer.
+WASEFIRE_IMPORT("isr")
+extern int32_t i2c_sw_read(uint32_t addr, uint8_t *ptr, uint32_t len);
+
+// Parameters of i2c_sw_write().
+typedef struct {
+  // 7-bits address of the device.
+  uint32_t addr;
+  // Address of the buffer.
+  uint8_t const *ptr;
+  // Length of the buffer in bytes.
+  uint32_t len;
+} i2c_sw_write_params_t;
+
+// Results of i2c_sw_write().
+typedef struct {
+  // Zero on success, negative on error.
+  int32_t res;
+} i2c_sw_write_results_t;
+
+// Writes bytes to a device from a buffer.
+WASEFIRE_IMPORT("isw")
+extern int32_t i2c_sw_write(uint32_t addr, uint8_t const *ptr, uint32_t len);
+
+// Parameters of i2c_sw_write_read().
+typedef struct {
+  // 7-bits address of the device.
+  uint32_t addr;
+  // Address of the buffer to write.
+  uint8_t const *wptr;
+  // Length of the buffer to write in bytes.
+  uint32_t wlen;
+  // Address of the buffer to read.
+  uint8_t *rptr;
+  // Length of the buffer to read in bytes.
+  uint32_t rlen;
+} i2c_sw_write_read_params_t;
+
+// Results of i2c_sw_write_read().
+typedef struct {
+  // Zero on success, negative on error.
+  int32_t res;
+} i2c_sw_write_read_results_t;
+
+// Writes then reads bytes from a device with a repeated start condition.
+WASEFIRE_IMPORT("isx")
+extern int32_t i2c_sw_write_read(uint32_t addr, uint8_t const *wptr, uint32_t wlen, uint8_t *rptr, uint32_t rlen);
+
+// END OF MODULE i2c_sw
+
+// START OF MODULE led
+// LED operations.
+
+// Results of led_count().
+typedef struct {
+  // How many LEDs are on the device.
+  uint32_t cnt;
+} led_count_results_t;
+
+// Returns how many LEDs are on the device.
+WASEFIRE_IMPORT("lc")
+extern uint32_t led_count(void);
+
+// Describes the state of a LED.
+enum led_status {
+  // The LED is off.
+  LED_STATUS_OFF,
+  // The LED is on.
+  LED_STATUS_ON,
+};
+
+// Parameters of led_get().
+typedef struct {
+  // Index of the LED to set.
+  uint32_t led;
+} led_get_params_t;
+
+// Results of led_get().
+typedef struct {
+  // 0 for off and 1 for on.
+  uint32_t status;
+} led_get_results_t;
+
+// Returns a LED status.
+WASEFIRE_IMPORT("lg")
+extern uint32_t led_get(uint32_t led);
+
+// Parameters of led_set().
+typedef struct {
+  // Index of the LED to set.
+  uint32_t led;
+  // 0 for off and 1 for on.
+  uint32_t status;
+} led_set_params_t;
+
+// Sets a LED status.
+WASEFIRE_IMPORT("ls")
+extern void led_set(uint32_t led, uint32_t status);
+
+// Parameters of led_set_brightness().
+typedef struct {
+  // Index of the LED to set.
+  uint32_t led;
+  // 0 for off and 255 for fully on.
+  uint32_t brightness;
+} led_set_brightness_params_t;
+
+// Sets a LED brightness.
+//
+// LEDs without brightness support are on if the brightness is at least 128.
+WASEFIRE_IMPORT("lb")
+extern void led_set_brightness(uint32_t led, uint32_t brightness);
+
+// END OF MODULE led
+
+// START OF MODULE led_ring
+// Addressable LED ring operations.
+//
+// Animations are executed by the board, such that the applet only needs to select them.
+
+// Animation patterns.
+enum led_ring_pattern {
+  // A single lit pixel going around the ring.
+  LED_RING_PATTERN_SPINNER,
+  // An arc of lit pixels growing around the ring and restarting when full.
+  LED_RING_PATTERN_PROGRESS,
+  // A rainbow rotating around the ring (the color is ignored).
+  LED_RING_PATTERN_RAINBOW,
+};
+
+// Results of led_ring_count().
+typedef struct {
+  // How many pixels are available (zero if there is no ring).
+  uint32_t cnt;
+} led_ring_count_results_t;
+
+// Returns the number of pixels of the ring.
+WASEFIRE_IMPORT("lrc")
+extern uint32_t led_ring_count(void);
+
+// Parameters of led_ring_animate().
+typedef struct {
+  // The animation pattern.
+  uint32_t pattern;
+  // The number of steps per second (zero freezes the first frame).
+  uint32_t speed;
+  // The color of the lit pixels as 0xRRGGBB.
+  uint32_t color;
+} led_ring_animate_params_t;
+
+// Results of led_ring_animate().
+typedef struct {
+  // Zero on success, negative on error.
+  int32_t res;
+} led_ring_animate_results_t;
+
+// Starts an animation, replacing the current one if any.
+WASEFIRE_IMPORT("lra")
+extern int32_t led_ring_animate(uint32_t pattern, uint32_t speed, uint32_t color);
+
+// Results of led_ring_stop().
+typedef struct {
+  // Zero on success, negative on error.
+  int32_t res;
+} led_ring_stop_results_t;
+
+// Stops the current animation and turns all pixels off.
+WASEFIRE_IMPORT("lrs")
+extern int32_t led_ring_stop(void);
+
+// END OF MODULE led_ring
+
+// START OF MODULE magnetometer
+// Magnetometer operations.
+
+// Results of magnetometer_is_supported().
+typedef struct {
+  // 1 if supported, 0 otherwise.
+  uint32_t supported;
+} magnetometer_is_supported_results_t;
+
+// Whether a magnetometer is available.
+WASEFIRE_IMPORT("fs")
+extern uint32_t magnetometer_is_supported(void);
+
+// Parameters of magnetometer_read().
+typedef struct {
+  // Pointer to the 6 bytes of the raw field (X, Y, then Z as little-endian i16).
+  uint8_t *ptr;
+} magnetometer_read_params_t;
+
+// Results of magnetometer_read().
+typedef struct {
+  // Zero on success, negative on error.
+  int32_t res;
+} magnetometer_read_results_t;
+
+// Reads the magnetic field along the X, Y, and Z axes.
+WASEFIRE_IMPORT("fr")
+extern int32_t magnetometer_read(uint8_t *ptr);
+
+// END OF MODULE magnetometer
+
+// START OF MODULE platform
+// Platform operations.
+
+// Describes errors of platform operations.
+enum platform_error {
+  // The operation is not supported.
+  PLATFORM_ERROR_UNSUPPORTED,
+  // A function pre-condition was broken.
+  PLATFORM_ERROR_INVALID_ARGUMENT,
+};
+
+// Board capabilities that applets may query.
+//
+// Each capability is a bit in the result of `capabilities()`: bit `1 << c` is set if
+// the board supports capability `c`.
+enum platform_capability {
+  // Analog-to-digital converters.
+  PLATFORM_CAPABILITY_ADC,
+  // AES-128-CCM.
+  PLATFORM_CAPABILITY_AES128_CCM,
+  // AES-256-GCM.
+  PLATFORM_CAPABILITY_AES256_GCM,
+  // Buttons.
+  PLATFORM_CAPABILITY_BUTTON,
+  // CRC.
+  PLATFORM_CAPABILITY_CRC,
+  // ECDH.
+  PLATFORM_CAPABILITY_ECDH,
+  // Rotary encoders.
+  PLATFORM_CAPABILITY_ENCODER,
+  // GPIOs.
+  PLATFORM_CAPABILITY_GPIO,
+  // LEDs.
+  PLATFORM_CAPABILITY_LED,
+  // LED rings.
+  PLATFORM_CAPABILITY_LED_RING,
+  // Magnetometer.
+  PLATFORM_CAPABILITY_MAGNETOMETER,
+  // PWM outputs.
+  PLATFORM_CAPABILITY_PWM,
+  // Random number generator.
+  PLATFORM_CAPABILITY_RNG,
+  // SHA-256.
+  PLATFORM_CAPABILITY_SHA256,
+  // Stepper motors.
+  PLATFORM_CAPABILITY_STEPPER,
+  // Temperature sensor.
+  PLATFORM_CAPABILITY_TEMP,
+  // Timers.
+  PLATFORM_CAPABILITY_TIMER,
+  // UARTs.
+  PLATFORM_CAPABILITY_UART,
+  // USB serial.
+  PLATFORM_CAPABILITY_USB_SERIAL,
+};
+
+// Results of platform_reboot().
+typedef struct {
+  // Complement of error number. Never returns on success.
+  int32_t res;
+} platform_reboot_results_t;
+
+// Reboots the platform.
+//
+// Pending storage operations complete before the platform resets.
+WASEFIRE_IMPORT("pr")
+extern int32_t platform_reboot(void);
+
+// Results of platform_factory_reset().
+typedef struct {
+  // Complement of error number. Never returns on success.
+  int32_t res;
+} platform_factory_reset_results_t;
+
+// Wipes the storage and reboots the platform.
+//
+// The whole storage is physically erased, including the store of all applets. The
+// applet manifest must grant the `factory-reset` permission and the applet must be
+// signed with the applet key of the runner.
+WASEFIRE_IMPORT("pf")
+extern int32_t platform_factory_reset(void);
+
+// Results of platform_capabilities().
+typedef struct {
+  // Bit set of the supported capabilities.
+  uint32_t capabilities;
+} platform_capabilities_results_t;
+
+// Returns the capabilities supported by the board.
+//
+// Applets may use it to gracefully degrade on boards missing some capabilities.
+WASEFIRE_IMPORT("pc")
+extern uint32_t platform_capabilities(void);
+
+// Results of platform_api_version().
+typedef struct {
+  // The applet API version.
+  uint32_t version;
+} platform_api_version_results_t;
+
+// Returns the applet API version of the platform.
+//
+// Applets may compare it with the version they were compiled against to check
+// compatibility.
+WASEFIRE_IMPORT("pv")
+extern uint32_t platform_api_version(void);
+
+// Parameters of platform_device_id().
+typedef struct {
+  // Pointer to the 8 bytes of the device ID.
+  uint8_t *ptr;
+} platform_device_id_params_t;
+
+// Results of platform_device_id().
+typedef struct {
+  // Complement of error number.
+  int32_t res;
+} platform_device_id_results_t;
+
+// Reads the unique ID of the device.
+//
+// The ID is stable across resets but is not a secret.
+WASEFIRE_IMPORT("pi")
+extern int32_t platform_device_id(uint8_t *ptr);
+
+// START OF MODULE platform_update
+// Firmware updates.
+//
+// The new firmware is written to a staging bank, its Ed25519 signature is verified, and
+// it is swapped in on the next reboot. The firmware ends with its version as a 32-bit
+// little-endian integer, which must not be older than the last applied firmware.
+//
+// The applet manifest must grant the `update` permission and the applet must be signed
+// with the applet key of the runner.
+
+// Results of platform_update_is_supported().
+typedef struct {
+  // 1 if supported, 0 otherwise.
+  uint32_t supported;
+} platform_update_is_supported_results_t;
+
+// Whether firmware updates are supported.
+WASEFIRE_IMPORT("pus")
+extern uint32_t platform_update_is_supported(void);
+
+// Results of platform_update_start().
+typedef struct {
+  // Zero on success, bitwise complement of [`Error`](crate::platform::Error)
+  // otherwise.
+  int32_t res;
+} platform_update_start_results_t;
+
+// Starts a firmware update.
+//
+// Any update in progress is discarded and the staging bank is erased.
+WASEFIRE_IMPORT("pub")
+extern int32_t platform_update_start(void);
+
+// Parameters of platform_update_write().
+typedef struct {
+  // The offset in the staging bank.
+  uint32_t offset;
+  // The chunk.
+  uint8_t const *ptr;
+  // The length of the chunk.
+  uint32_t len;
+} platform_update_write_params_t;
+
+// Results of platform_update_write().
+typedef struct {
+  // Zero on success, bitwise complement of [`Error`](crate::platform::Error)
+  // otherwise.
+  int32_t res;
+} platform_update_write_results_t;
+
+// Writes a chunk of the firmware at an offset of the staging bank.
+WASEFIRE_IMPORT("puw")
+extern int32_t platform_update_write(uint32_t offset, uint8_t const *ptr, uint32_t len);
+
+// Parameters of platform_update_finalize().
+typedef struct {
+  // The 64 bytes Ed25519 signature of the firmware.
+  uint8_t const *signature;
+} platform_update_finalize_params_t;
+
+// Results of platform_update_finalize().
+typedef struct {
+  // Zero on success, bitwise complement of [`Error`](crate::platform::Error)
+  // otherwise.
+  int32_t res;
+} platform_update_finalize_results_t;
+
+// Verifies the signature and version of the written firmware.
+//
+// The update must be started again if the signature is invalid or the version is
+// older than the last applied firmware.
+WASEFIRE_IMPORT("puf")
+extern int32_t platform_update_finalize(uint8_t const *signature);
+
+// Results of platform_update_apply().
+typedef struct {
+  // Complement of error number. Never returns on success.
+  int32_t res;
+} platform_update_apply_results_t;
+
+// Applies the finalized firmware update and reboots the platform.
+WASEFIRE_IMPORT("pua")
+extern int32_t platform_update_apply(void);
+
+// END OF MODULE platform_update
+
+// END OF MODULE platform
+
+// START OF MODULE power
+// Peripheral power operations.
+//
+// Peripherals are identified by their kind and an index (only used for timers, zero
+// otherwise).
+
+// Describes errors controlling peripheral power.
+enum power_error {
+  // The peripheral is invalid or not supported.
+  POWER_ERROR_INVALID,
+  // The peripheral is in use.
+  POWER_ERROR_BUSY,
+};
+
+// Kinds of peripherals.
+enum power_peripheral {
+  // The analog-to-digital converters.
+  POWER_PERIPHERAL_ADC,
+  // The magnetometer.
+  POWER_PERIPHERAL_MAGNETOMETER,
+  // The random number generator.
+  POWER_PERIPHERAL_RNG,
+  // A timer.
+  POWER_PERIPHERAL_TIMER,
+  // The USB device.
+  POWER_PERIPHERAL_USB,
+};
+
+// Parameters of power_disable_peripheral().
+typedef struct {
+  // Kind of the peripheral.
+  uint32_t peripheral;
+  // Index of the peripheral.
+  uint32_t index;
+} power_disable_peripheral_params_t;
+
+// Results of power_disable_peripheral().
+typedef struct {
+  // Zero for success. Otherwise complement of error number.
+  int32_t res;
+} power_disable_peripheral_results_t;
+
+// Disables a peripheral to save power.
+//
+// A disabled peripheral can't be used until enabled again.
+WASEFIRE_IMPORT("pd")
+extern int32_t power_disable_peripheral(uint32_t peripheral, uint32_t index);
+
+// Parameters of power_enable_peripheral().
+typedef struct {
+  // Kind of the peripheral.
+  uint32_t peripheral;
+  // Index of the peripheral.
+  uint32_t index;
+} power_enable_peripheral_params_t;
+
+// Results of power_enable_peripheral().
+typedef struct {
+  // Zero for success. Otherwise complement of error number.
+  int32_t res;
+} power_enable_peripheral_results_t;
+
+// Enables a peripheral.
+WASEFIRE_IMPORT("pe")
+extern int32_t power_enable_peripheral(uint32_t peripheral, uint32_t index);
+
+// END OF MODULE power
+
+// START OF MODULE pwm
+// PWM output operations.
+
+// Results of pwm_count().
+typedef struct {
+  // How many PWM outputs are on the device.
+  uint32_t cnt;
+} pwm_count_results_t;
+
+// Returns how many PWM outputs are on the device.
+WASEFIRE_IMPORT("wc")
+extern uint32_t pwm_count(void);
+
+// Parameters of pwm_configure().
+typedef struct {
+  // Index of the PWM output to configure.
+  uint32_t pwm;
+  // Frequency in Hz.
+  uint32_t freq_hz;
+  // Duty cycle in percent (at most 100).
+  //
+  // A duty cycle of 0 (resp. 100) is a steady low (resp. high) output.
+  uint32_t duty_pct;
+} pwm_configure_params_t;
+
+// Results of pwm_configure().
+typedef struct {
+  // Zero on success, negative on error.
+  int32_t res;
+} pwm_configure_results_t;
+
+// Configures and starts a PWM output.
+WASEFIRE_IMPORT("wg")
+extern int32_t pwm_configure(uint32_t pwm, uint32_t freq_hz, uint32_t duty_pct);
+
+// Parameters of pwm_stop().
+typedef struct {
+  // Index of the PWM output to stop.
+  uint32_t pwm;
+} pwm_stop_params_t;
+
+// Results of pwm_stop().
+typedef struct {
+  // Zero on success, negative on error.
+  int32_t res;
+} pwm_stop_results_t;
+
+// Stops a PWM output.
+//
+// The output is steady low afterwards.
+WASEFIRE_IMPORT("ws")
+extern int32_t pwm_stop(uint32_t pwm);
+
+// END OF MODULE pwm
+
+// START OF MODULE rng
+// Random number generators.
+
+// Parameters of rng_fill_bytes().
+typedef struct {
+  // The slice to fill.
+  uint8_t *ptr;
+  // The length of the slice.
+  uint32_t len;
+} rng_fill_bytes_params_t;
+
+// Results of rng_fill_bytes().
+typedef struct {
+  // Error code: 0 on success, -1 on error
+  //
+  // The buffer may be modified on error and should not be used.
+  int32_t res;
+} rng_fill_bytes_results_t;
+
+// Fills a slice with random bytes.
+WASEFIRE_IMPORT("rb")
+extern int32_t rng_fill_bytes(uint8_t *ptr, uint32_t len);
+
+// END OF MODULE rng
+
+// START OF MODULE scheduling
+
+// Waits until a callback is scheduled.
+//
+// This can be used as power management, since the CPU will sleep while waiting.
+WASEFIRE_IMPORT("sw")
+extern void scheduling_wait_for_callback(void);
+
+// Waits until the board produces an event.
+//
+// Unlike `wait_for_callback`, this returns after the next board event even if it
+// doesn't schedule a callback. Pending callbacks are executed before returning. The
+// CPU sleeps while waiting.
+WASEFIRE_IMPORT("se")
+extern void scheduling_wait_for_event(void);
+
+// Results of scheduling_num_pending_callbacks().
+typedef struct {
+  // How many callbacks are pending.
+  uint32_t count;
+} scheduling_num_pending_callbacks_results_t;
+
+// Returns how many callbacks are pending.
+WASEFIRE_IMPORT("sh")
+extern uint32_t scheduling_num_pending_callbacks(void);
+
+// END OF MODULE scheduling
+
+// START OF MODULE stepper
+// Stepper motor operations.
+
+// Results of stepper_count().
+typedef struct {
+  // How many stepper motors are on the device.
+  uint32_t cnt;
+} stepper_count_results_t;
+
+// Returns how many stepper motors are on the device.
+WASEFIRE_IMPORT("mc")
+extern uint32_t stepper_count(void);
+
+// Parameters of stepper_move_to().
+typedef struct {
+  // Index of the stepper motor to move.
+  uint32_t stepper;
+  // Target position in steps.
+  int32_t position;
+  // Maximum speed in steps per second.
+  uint32_t max_speed;
+  // Acceleration in steps per second squared.
+  uint32_t acceleration;
+} stepper_move_to_params_t;
+
+// Results of stepper_move_to().
+typedef struct {
+  // Error code: 0 on success, -1 on error
+  //
+  // It is an error to start a move while the motor is still moving.
+  int32_t res;
+} stepper_move_to_results_t;
+
+// Starts moving a stepper motor to an absolute position.
+//
+// The move follows a trapezoidal acceleration profile and is executed by the board.
+// Register a handler to be notified when the move is done.
+WASEFIRE_IMPORT("mm")
+extern int32_t stepper_move_to(uint32_t stepper, int32_t position, uint32_t max_speed, uint32_t acceleration);
+
+// Parameters of stepper_position().
+typedef struct {
+  // Index of the stepper motor.
+  uint32_t stepper;
+} stepper_position_params_t;
+
+// Results of stepper_position().
+typedef struct {
+  // Current position in steps.
+  int32_t position;
+} stepper_position_results_t;
+
+// Returns the current position of a stepper motor.
+WASEFIRE_IMPORT("mp")
+extern int32_t stepper_position(uint32_t stepper);
+
+// Parameters of stepper_register().
+typedef struct {
+  // Index of the stepper motor to listen to.
+  uint32_t stepper;
+  // Function called when a move is done.
+  //
+  // The function takes its opaque `data` as argument.
+  void (*handler_func)(uint8_t *data);
+  // The opaque data to use when calling the handler function.
+  uint8_t *handler_data;
+} stepper_register_params_t;
+
+// Register a handler for stepper motor events.
+WASEFIRE_IMPORT("mr")
+extern void stepper_register(uint32_t stepper, void (*handler_func)(uint8_t *data), uint8_t *handler_data);
+
+// Parameters of stepper_unregister().
+typedef struct {
+  // Index of the stepper motor to stop listening to.
+  uint32_t stepper;
+} stepper_unregister_params_t;
+
+// Unregister handlers for stepper motor events.
+WASEFIRE_IMPORT("mu")
+extern void stepper_unregister(uint32_t stepper);
+
+// END OF MODULE stepper
+
+// START OF MODULE store
+// Persistent storage operations.
+
+// Describes errors interacting with the store.
+enum store_error {
+  // A function pre-condition was broken.
+  STORE_ERROR_INVALID_ARGUMENT,
+  // The store is full.
+  STORE_ERROR_NO_CAPACITY,
+  // The store reached its end of life.
+  STORE_ERROR_NO_LIFETIME,
+  // An operation to the underlying storage failed.
+  STORE_ERROR_STORAGE_ERROR,
+  // The underlying storage doesn't match the store invariant.
+  STORE_ERROR_INVALID_STORAGE,
+};
+
+// Parameters of store_insert().
+typedef struct {
+  // Key of the entry.
+  //
+  // This must be smaller than 4032. Larger keys are reserved for the platform, but
+  // entries inserted there by older platforms can still be found and removed.
+  uint32_t key;
+  // Value of the entry.
+  uint8_t const *ptr;
+  // Length of the value.
+  uint32_t len;
+} store_insert_params_t;
+
+// Results of store_insert().
+typedef struct {
+  // Zero for success. Otherwise complement of error number.
+  int32_t res;
+} store_insert_results_t;
+
+// Inserts an entry in the store.
+//
+// If an entry for that key was already present, it is overwritten.
+WASEFIRE_IMPORT("si")
+extern int32_t store_insert(uint32_t key, uint8_t const *ptr, uint32_t len);
+
+// Parameters of store_remove().
+typedef struct {
+  // Key of the entry.
+  uint32_t key;
+} store_remove_params_t;
+
+// Results of store_remove().
+typedef struct {
+  // Zero for success. Otherwise complement of error number.
+  int32_t res;
+} store_remove_results_t;
+
+// Removes an entry from the store.
+//
+// This is not an error if no entry is present. This is simply a no-op in that case.
+WASEFIRE_IMPORT("sr")
+extern int32_t store_remove(uint32_t key);
+
+// Parameters of store_find().
+typedef struct {
+  // Key of the entry to find.
+  uint32_t key;
+  // Where to write the value of the entry, if found.
+  //
+  // The (inner) pointer will be allocated by the callee and must be freed by the
+  // caller. It is thus owned by the caller when the function returns.
+  uint8_t **ptr;
+  // Where to write the length of the value, if found.
+  uint32_t *len;
+} store_find_params_t;
+
+// Results of store_find().
+typedef struct {
+  // One if found. Zero if not found. Otherwise complement of error number.
+  int32_t res;
+} store_find_results_t;
+
+// Finds an entry in the store, if any.
+WASEFIRE_IMPORT("sf")
+extern int32_t store_find(uint32_t key, uint8_t **ptr, uint32_t *len);
+
+// START OF MODULE store_fragment
+// Support for fragmented entries.
+//
+// Fragmented entries span a range of keys and may thus be larger than the maximum value
+// length of a single entry. They are meant for large buffers (e.g. images or firmware).
+//
+// The functions of this module transfer the whole buffer in a single call: the runner
+// directly reads from (or writes to) the region of the applet linear memory described by
+// the pointer and length. The region is only accessed for the duration of the call and the
+// runner does not retain it afterwards. The applet must not assume anything about the
+// content of the region if the call fails.
+
+// Parameters of store_fragment_insert().
+typedef struct {
+  // Range of keys.
+  //
+  // The first key is the lower 16 bits and the one past the last key is the upper 16
+  // bits. The range must be non-empty and the keys smaller than 4032.
+  uint32_t keys;
+  // Value of the entry.
+  uint8_t const *ptr;
+  // Length of the value.
+  //
+  // This must fit in the range of keys.
+  uint32_t len;
+} store_fragment_insert_params_t;
+
+// Results of store_fragment_insert().
+typedef struct {
+  // Zero for success. Otherwise complement of error number.
+  int32_t res;
+} store_fragment_insert_results_t;
+
+// Inserts a fragmented entry in the store.
+//
+// If an entry for that range of keys was already present, it is overwritten.
+WASEFIRE_IMPORT("sfi")
+extern int32_t store_fragment_insert(uint32_t keys, uint8_t const *ptr, uint32_t len);
+
+// Parameters of store_fragment_remove().
+typedef struct {
+  // Range of keys (same encoding as for `insert`).
+  uint32_t keys;
+} store_fragment_remove_params_t;
+
+// Results of store_fragment_remove().
+typedef struct {
+  // Zero for success. Otherwise complement of error number.
+  int32_t res;
+} store_fragment_remove_results_t;
+
+// Removes a fragmented entry from the store.
+//
+// This is not an error if no entry is present. This is simply a no-op in that case.
+WASEFIRE_IMPORT("sfr")
+extern int32_t store_fragment_remove(uint32_t keys);
+
+// Parameters of store_fragment_read().
+typedef struct {
+  // Range of keys (same encoding as for `insert`).
+  uint32_t keys;
+  // Offset in the entry of the first byte to read.
+  uint32_t offset;
+  // Where to write the bytes read.
+  uint8_t *ptr;
+  // Length of the buffer.
+  uint32_t len;
+} store_fragment_read_params_t;
+
+// Results of store_fragment_read().
+typedef struct {
+  // Number of bytes read. Otherwise complement of error number.
+  //
+  // This is smaller than the length of the buffer when the end of the entry is
+  // reached. It is zero if the entry is not found or the offset is past its end.
+  int32_t res;
+} store_fragment_read_results_t;
+
+// Reads part of a fragmented entry from the store.
+//
+// The runner writes the bytes of the entry starting at `offset` directly into the
+// buffer. Large entries may thus be read in a single call, or in a few calls at
+// increasing offsets when the applet can't afford a buffer for the whole entry.
+WASEFIRE_IMPORT("sfo")
+extern int32_t store_fragment_read(uint32_t keys, uint32_t offset, uint8_t *ptr, uint32_t len);
+
+// END OF MODULE store_fragment
+
+// END OF MODULE store
+
+// START OF MODULE temp
+// Temperature sensor operations.
+
+// Results of temp_is_supported().
+typedef struct {
+  // 1 if supported, 0 otherwise.
+  uint32_t supported;
+} temp_is_supported_results_t;
+
+// Whether a temperature sensor is available.
+WASEFIRE_IMPORT("hs")
+extern uint32_t temp_is_supported(void);
+
+// Parameters of temp_read().
+typedef struct {
+  // Pointer to the 4 bytes of the temperature in millidegrees Celsius (little-endian
+  // i32).
+  uint8_t *ptr;
+} temp_read_params_t;
+
+// Results of temp_read().
+typedef struct {
+  // Zero on success, negative on error.
+  int32_t res;
+} temp_read_results_t;
+
+// Reads the temperature.
+WASEFIRE_IMPORT("hr")
+extern int32_t temp_read(uint8_t *ptr);
+
+// END OF MODULE temp
+
+// START OF MODULE uart
+// UART operations.
+//
+// These are serial ports on physical pins, distinct from the USB serial. A board may have
+// multiple UARTs. They are identified by their index, starting at zero.
+
+// Results of uart_count().
+typedef struct {
+  // How many UARTs are available.
+  uint32_t cnt;
+} uart_count_results_t;
+
+// Returns the number of UARTs.
+WASEFIRE_IMPORT("uac")
+extern uint32_t uart_count(void);
+
+// Parameters of uart_set_baudrate().
+typedef struct {
+  // Index of the UART.
+  uint32_t uart;
+  // Baud rate in bits per second.
+  uint32_t baudrate;
+} uart_set_baudrate_params_t;
+
+// Results of uart_set_baudrate().
+typedef struct {
+  // Zero on success, -1 on error (e.g. unsupported baud rate).
+  int32_t res;
+} uart_set_baudrate_results_t;
+
+// Sets the baud rate of a UART.
+WASEFIRE_IMPORT("uab")
+extern int32_t uart_set_baudrate(uint32_t uart, uint32_t baudrate);
+
+// Parameters of uart_read().
+typedef struct {
+  // Index of the UART.
+  uint32_t uart;
+  // Address of the buffer.
+  uint8_t *ptr;
+  // Length of the buffer in bytes.
+  uint32_t len;
+} uart_read_params_t;
+
+// Results of uart_read().
+typedef struct {
+  // Number of bytes read (or negative value for errors).
+  //
+  // This function does not block and may return zero.
+  int32_t len;
+} uart_read_results_t;
+
+// Reads from a UART into a buffer.
+WASEFIRE_IMPORT("uar")
+extern int32_t uart_read(uint32_t uart, uint8_t *ptr, uint32_t len);
+
+// Parameters of uart_write().
+typedef struct {
+  // Index of the UART.
+  uint32_t uart;
+  // Address of the buffer.
+  uint8_t const *ptr;
+  // Length of the buffer in bytes.
+  uint32_t len;
+} uart_write_params_t;
+
+// Results of uart_write().
+typedef struct {
+  // Number of bytes written (or negative value for errors).
+  //
+  // This function does not block and may return zero.
+  int32_t len;
+} uart_write_results_t;
+
+// Writes to a UART from a buffer.
+WASEFIRE_IMPORT("uaw")
+extern int32_t uart_write(uint32_t uart, uint8_t const *ptr, uint32_t len);
+
+// Parameters of uart_flush().
+typedef struct {
+  // Index of the UART.
+  uint32_t uart;
+} uart_flush_params_t;
+
+// Results of uart_flush().
+typedef struct {
+  // Zero on success, -1 on error.
+  int32_t res;
+} uart_flush_results_t;
+
+// Flushes a UART.
+WASEFIRE_IMPORT("uaf")
+extern int32_t uart_flush(uint32_t uart);
+
+// UART events.
+enum uart_event {
+  // Ready for read.
+  UART_EVENT_READ,
+  // Ready for write.
+  UART_EVENT_WRITE,
+};
+
+// Parameters of uart_register().
+typedef struct {
+  uint32_t uart;
+  uint32_t event;
+  void (*handler_func)(uint8_t *data);
+  uint8_t *handler_data;
+} uart_register_params_t;
+
+// Registers a callback when a UART is ready.
+//
+// It is possible that the callback is spuriously called.
+WASEFIRE_IMPORT("uae")
+extern void uart_register(uint32_t uart, uint32_t event, void (*handler_func)(uint8_t *data), uint8_t *handler_data);
+
+// Parameters of uart_unregister().
+typedef struct {
+  uint32_t uart;
+  uint32_t event;
+} uart_unregister_params_t;
+
+// Unregisters a callback.
+WASEFIRE_IMPORT("uad")
+extern void uart_unregister(uint32_t uart, uint32_t event);
+
+// END OF MODULE uart
+
+// START OF MODULE usb
+// USB operations.
+
+// Describes errors on USB operations.
+enum usb_error {
+  USB_ERROR_UNKNOWN,
+};
+
+// START OF MODULE usb_hid
+// USB HID operations.
+//
+// Reports are always 64 bytes long.
+
+// Parameters of usb_hid_read().
+typedef struct {
+  // Address of the 64-bytes buffer.
+  uint8_t *ptr;
+} usb_hid_read_params_t;
+
+// Results of usb_hid_read().
+typedef struct {
+  // One if a report was read, zero if there is nothing to read, and negative value
+  // for errors.
+  //
+  // This function does not block.
+  int32_t res;
+} usb_hid_read_results_t;
+
+// Reads a report from USB HID into a 64-bytes buffer.
+WASEFIRE_IMPORT("uhr")
+extern int32_t usb_hid_read(uint8_t *ptr);
+
+// Parameters of usb_hid_write().
+typedef struct {
+  // Address of the 64-bytes buffer.
+  uint8_t const *ptr;
+} usb_hid_write_params_t;
+
+// Results of usb_hid_write().
+typedef struct {
+  // One if the report was written, zero if the other side is not ready, and
+  // negative value for errors.
+  //
+  // This function does not block.
+  int32_t res;
+} usb_hid_write_results_t;
+
+// Writes a report to USB HID from a 64-bytes buffer.
+WASEFIRE_IMPORT("uhw")
+extern int32_t usb_hid_write(uint8_t const *ptr);
+
+// USB HID events.
+enum usb_hid_event {
+  // Ready for read.
+  USB_HID_EVENT_READ,
+  // Ready for write.
+  USB_HID_EVENT_WRITE,
+};
+
+// Parameters of usb_hid_register().
+typedef struct {
+  uint32_t event;
+  void (*handler_func)(uint8_t *data);
+  uint8_t *handler_data;
+} usb_hid_register_params_t;
+
+// Registers a callback when USB HID is ready.
+//
+// It is possible that the callback is spuriously called.
+WASEFIRE_IMPORT("uhe")
+extern void usb_hid_register(uint32_t event, void (*handler_func)(uint8_t *data), uint8_t *handler_data);
+
+// Parameters of usb_hid_unregister().
+typedef struct {
+  uint32_t event;
+} usb_hid_unregister_params_t;
+
+// Unregisters a callback.
+WASEFIRE_IMPORT("uhd")
+extern void usb_hid_unregister(uint32_t event);
+
+// END OF MODULE usb_hid
+
+// START OF MODULE usb_serial
+// USB serial operations.
+//
+// A board may have multiple serial ports. They are identified by their index, starting at
+// zero.
+
+// Results of usb_serial_count().
+typedef struct {
+  // How many serial ports are available.
+  uint32_t cnt;
+} usb_serial_count_results_t;
+
+// Returns the number of serial ports.
+WASEFIRE_IMPORT("usc")
+extern uint32_t usb_serial_count(void);
+
+// Parameters of usb_serial_read().
+typedef struct {
+  // Index of the serial port.
+  uint32_t port;
+  // Address of the buffer.
+  uint8_t *ptr;
+  // Length of the buffer in bytes.
+  uint32_t len;
+} usb_serial_read_params_t;
+
+// Results of usb_serial_read().
+typedef struct {
+  // Number of bytes read (or negative value for errors).
+  //
+  // This function does not block and may return zero.
+  int32_t len;
+} usb_serial_read_results_t;
+
+// Reads from a USB serial port into a buffer.
+WASEFIRE_IMPORT("usr")
+extern int32_t usb_serial_read(uint32_t port, uint8_t *ptr, uint32_t len);
+
+// Parameters of usb_serial_write().
+typedef struct {
+  // Index of the serial port.
+  uint32_t port;
+  // Address of the buffer.
+  uint8_t const *ptr;
+  // Length of the buffer in bytes.
+  uint32_t len;
+} usb_serial_write_params_t;
+
+// Results of usb_serial_write().
+typedef struct {
+  // Number of bytes written (or negative value for errors).
+  //
+  // This function does not block and may return zero.
+  int32_t len;
+} usb_serial_write_results_t;
+
+// Writes to a USB serial port from a buffer.
+WASEFIRE_IMPORT("usw")
+extern int32_t usb_serial_write(uint32_t port, uint8_t const *ptr, uint32_t len);
+
+// USB serial events.
+enum usb_serial_event {
+  // Ready for read.
+  USB_SERIAL_EVENT_READ,
+  // Ready for write.
+  USB_SERIAL_EVENT_WRITE,
+  // Line state changed.
+  USB_SERIAL_EVENT_CONTROL,
+  // Line coding changed.
+  USB_SERIAL_EVENT_CODING,
+};
+
+// Number of stop bits.
+enum usb_serial_stop_bits {
+  USB_SERIAL_STOP_BITS_ONE,
+  USB_SERIAL_STOP_BITS_ONE_POINT_FIVE,
+  USB_SERIAL_STOP_BITS_TWO,
+};
+
+// Parity type.
+enum usb_serial_parity {
+  USB_SERIAL_PARITY_NONE,
+  USB_SERIAL_PARITY_ODD,
+  USB_SERIAL_PARITY_EVEN,
+  USB_SERIAL_PARITY_MARK,
+  USB_SERIAL_PARITY_SPACE,
+};
+
+// Parameters of usb_serial_register().
+typedef struct {
+  uint32_t port;
+  uint32_t event;
+  void (*handler_func)(uint8_t *data);
+  uint8_t *handler_data;
+} usb_serial_register_params_t;
+
+// Registers a callback when a USB serial port is ready.
+//
+// It is possible that the callback is spuriously called.
+WASEFIRE_IMPORT("use")
+extern void usb_serial_register(uint32_t port, uint32_t event, void (*handler_func)(uint8_t *data), uint8_t *handler_data);
+
+// Parameters of usb_serial_unregister().
+typedef struct {
+  uint32_t port;
+  uint32_t event;
+} usb_serial_unregister_params_t;
+
+// Unregisters a callback.
+WASEFIRE_IMPORT("usd")
+extern void usb_serial_unregister(uint32_t port, uint32_t event);
+
+// Parameters of usb_serial_flush().
+typedef struct {
+  // Index of the serial port.
+  uint32_t port;
+} usb_serial_flush_params_t;
+
+// Results of usb_serial_flush().
+typedef struct {
+  // Zero on success, -1 on error.
+  int32_t res;
+} usb_serial_flush_results_t;
+
+// Flushs a USB serial port.
+WASEFIRE_IMPORT("usf")
+extern int32_t usb_serial_flush(uint32_t port);
+
+// Parameters of usb_serial_flush_timeout().
+typedef struct {
+  // Index of the serial port.
+  uint32_t port;
+  // Maximum duration to wait in milliseconds.
+  uint32_t timeout_ms;
+} usb_serial_flush_timeout_params_t;
+
+// Results of usb_serial_flush_timeout().
+typedef struct {
+  // Zero on success, -1 on error (including timeout).
+  int32_t res;
+} usb_serial_flush_timeout_results_t;
+
+// Flushes a USB serial port, waiting at most a given duration.
+WASEFIRE_IMPORT("ust")
+extern int32_t usb_serial_flush_timeout(uint32_t port, uint32_t timeout_ms);
+
+// Parameters of usb_serial_line_state().
+typedef struct {
+  // Index of the serial port.
+  uint32_t port;
+} usb_serial_line_state_params_t;
+
+// Results of usb_serial_line_state().
+typedef struct {
+  // Bit 0 is DTR and bit 1 is RTS (or -1 on error).
+  int32_t res;
+} usb_serial_line_state_results_t;
+
+// Returns the line state of a USB serial port.
+WASEFIRE_IMPORT("usl")
+extern int32_t usb_serial_line_state(uint32_t port);
+
+// Parameters of usb_serial_line_coding().
+typedef struct {
+  // Index of the serial port.
+  uint32_t port;
+  // Pointer to the 8 bytes of the line coding.
+  //
+  // The data rate in bits per second as little-endian u32, followed by the stop
+  // bits, the parity, the number of data bits, and a zero byte.
+  uint8_t *ptr;
+} usb_serial_line_coding_params_t;
+
+// Results of usb_serial_line_coding().
+typedef struct {
+  // Zero on success, -1 on error.
+  int32_t res;
+} usb_serial_line_coding_results_t;
+
+// Returns the line coding of a USB serial port.
+WASEFIRE_IMPORT("usk")
+extern int32_t usb_serial_line_coding(uint32_t port, uint8_t *ptr);
+
+// END OF MODULE usb_serial
+
+// END OF MODULE usb
+
+// Parameters of syscall().
+typedef struct {
+  uint32_t x1;
+  uint32_t x2;
+  uint32_t x3;
+  uint32_t x4;
+} syscall_params_t;
+
+// Results of syscall().
+typedef struct {
+  int32_t res;
+} syscall_results_t;
+
+// Board-specific syscalls.
+//
+// Those calls are forwarded by the scheduler.
+WASEFIRE_IMPORT("s")
+extern int32_t syscall(uint32_t x1, uint32_t x2, uint32_t x3, uint32_t x4);
+
+#endif  // WASEFIRE_API_H
@@ -0,0 +1,1924 @@
+// Copyright 2022 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+package api
+
+// Version of the applet API.
+const ApiVersion uint32 = 1
+
+// START OF MODULE adc
+// Analog-to-digital converter operations.
+
+// Returns how many analog inputs are on the device.
+//
+//go:wasmimport env ac
+func AdcCount() (
+	// How many analog inputs are on the device.
+	cnt uint32,
+)
+
+// Samples an analog input.
+//
+//go:wasmimport env ar
+func AdcRead(
+	// Index of the analog input to sample.
+	adc uint32,
+) (
+	// The sample normalized to 16 bits, or a negative value on error.
+	//
+	// A sample of 0 is ground and 65535 is the reference voltage.
+	res int32,
+)
+
+// Measures the supply voltage.
+//
+//go:wasmimport env av
+func AdcReadVdd() (
+	// The supply voltage in millivolts, or a negative value on error.
+	res int32,
+)
+
+// END OF MODULE adc
+
+// START OF MODULE button
+// Button and touch operations.
+
+// Describes the state of a button.
+type ButtonState uint32
+
+const (
+	// The button is released.
+	ButtonStateReleased ButtonState = iota
+	// The button is pressed.
+	ButtonStatePressed
+)
+
+// Describes a button gesture.
+type ButtonGesture uint32
+
+const (
+	// The button was pressed and released once.
+	ButtonGestureSingle ButtonGesture = iota
+	// The button was held pressed for at least the long-press threshold.
+	ButtonGestureLong
+	// The button was pressed twice within the double-press window.
+	ButtonGestureDouble
+)
+
+// Returns how many buttons are on the device.
+//
+//go:wasmimport env bc
+func ButtonCount() (
+	// How many buttons are on the device.
+	cnt uint32,
+)
+
+// Register a handler for button events.
+//
+//go:wasmimport env br
+func ButtonRegister(
+	// Index of the button to listen to.
+	button uint32,
+	// Function called on button events.
+	//
+	// The function takes its opaque `data` and the new button `state` as arguments.
+	// If gestures are configured for the button, the function is called with a
+	// `Gesture` instead of a `State`.
+	handler_func uintptr,
+	// The opaque data to use when calling the handler function.
+	handler_data uintptr,
+)
+
+// Configures gesture recognition for a button.
+//
+// When configured, the button handler is called on gestures instead of state changes.
+//
+//go:wasmimport env bg
+func ButtonGestureFn(
+	// Index of the button to configure.
+	button uint32,
+	// Minimum duration in milliseconds of a long press.
+	//
+	// Zero disables gesture recognition for the button.
+	long uint32,
+	// Maximum duration in milliseconds between a release and the next press for a
+	// double press.
+	//
+	// Zero disables double presses such that single presses are reported on release.
+	double uint32,
+) (
+	// Zero on success, -1 if there are no timers available.
+	res int32,
+)
+
+// Unregister handlers for button events.
+//
+//go:wasmimport env bu
+func ButtonUnregister(
+	// Index of the button to stop listening to.
+	button uint32,
+)
+
+// END OF MODULE button
+
+// START OF MODULE capture
+// Input capture operations.
+//
+// The GPIO must be configured as an input with the `gpio` module.
+
+// Starts measuring the frequency and duty cycle of the signal on a GPIO.
+//
+// The measurement is executed by the board. Register a handler to be notified when
+// the measurement is done.
+//
+//go:wasmimport env cs
+func CaptureStart(
+	// Index of the GPIO.
+	gpio uint32,
+) (
+	// Zero on success, negative on error.
+	//
+	// It is an error to start a measurement while another one is running.
+	res int32,
+)
+
+// Reads the result of the done measurement of a GPIO.
+//
+//go:wasmimport env cr
+func CaptureResult(
+	// Index of the GPIO.
+	gpio uint32,
+	// Pointer to the 8 bytes of the measurement.
+	//
+	// The period and the high time of the signal in nanoseconds as little-endian u32.
+	ptr uintptr,
+) (
+	// One if the signal was measured, zero if there is no signal, and negative on
+	// error.
+	//
+	// It is an error to read the result of a measurement that is not done.
+	res int32,
+)
+
+// Register a handler for input capture events.
+//
+//go:wasmimport env ce
+func CaptureRegister(
+	// Index of the GPIO to listen to.
+	gpio uint32,
+	// Function called when a measurement is done.
+	//
+	// The function takes its opaque `data` as argument.
+	handler_func uintptr,
+	// The opaque data to use when calling the handler function.
+	handler_data uintptr,
+)
+
+// Unregister handlers for input capture events.
+//
+//go:wasmimport env cd
+func CaptureUnregister(
+	// Index of the GPIO to stop listening to.
+	gpio uint32,
+)
+
+// END OF MODULE capture
+
+// START OF MODULE clock
+// Clock and timer operations.
+
+// Whether a timer should periodically trigger.
+type ClockMode uint32
+
+const (
+	// The timer fires only once.
+	ClockModeOneshot ClockMode = iota
+	// The timer fires periodically.
+	ClockModePeriodic
+)
+
+// Allocates a timer (initially stopped).
+//
+//go:wasmimport env ta
+func ClockAllocate(
+	// Function called when the timer triggers.
+	handler_func uintptr,
+	// The opaque data to use when calling the handler function.
+	handler_data uintptr,
+) (
+	// Identifier for this timer.
+	id uint32,
+)
+
+// Starts a stopped timer given its id.
+//
+//go:wasmimport env tb
+func ClockStart(
+	// The identifier of the timer to start.
+	//
+	// It must come from an allocated timer that wasn't stopped.
+	id uint32,
+	// Whether the timer should periodically fire.
+	//
+	// Valid values are defined by [`Mode`](super::Mode).
+	mode uint32,
+	// How long until the timer triggers in milli-seconds.
+	duration_ms uint32,
+)
+
+// Stops a running timer given its id.
+//
+// Note that if the timer triggers while being stopped, the handler may still be
+// called.
+//
+//go:wasmimport env tc
+func ClockStop(
+	// The identifier of the timer to start.
+	id uint32,
+)
+
+// Deallocates a stopped timer given its id.
+//
+//go:wasmimport env td
+func ClockFree(
+	// The identifier of the timer to start.
+	id uint32,
+)
+
+// Returns the time since the platform started.
+//
+//go:wasmimport env tu
+func ClockUptime(
+	// Pointer to the 8 bytes of the uptime in micro-seconds (little-endian).
+	ptr uintptr,
+) (
+	// Zero on success, negative on error.
+	res int32,
+)
+
+// END OF MODULE clock
+
+// START OF MODULE crypto
+// Cryptographic operations.
+
+// Describes errors on cryptographic operations.
+type CryptoError uint32
+
+const (
+	// A function pre-condition was broken.
+	CryptoErrorInvalidArgument CryptoError = iota
+	// An operation is unsupported.
+	CryptoErrorUnsupported
+)
+
+// START OF MODULE crypto_ccm
+// AES-CCM according to Bluetooth.
+
+// Whether AES-CCM is supported.
+//
+//go:wasmimport env ccs
+func CryptoCcmIsSupported() (
+	// 1 if supported, 0 otherwise.
+	supported uint32,
+)
+
+// Encrypts a clear text given a key and IV.
+//
+//go:wasmimport env cce
+func CryptoCcmEncrypt(
+	// The 16 bytes key to encrypt with.
+	key uintptr,
+	// The 8 bytes IV to encrypt with.
+	iv uintptr,
+	// Length in bytes of the `clear` text.
+	//
+	// This must be at most 251 bytes. The `cipher` length must be 4 bytes longer than
+	// this value.
+	len uint32,
+	// The clear text to encrypt from.
+	//
+	// Its length must be provided in the `len` field.
+	clear uintptr,
+	// The cipher text to encrypt to.
+	//
+	// Its length must be `len + 4` bytes.
+	cipher uintptr,
+) (
+	// Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+	// otherwise.
+	res int32,
+)
+
+// Decrypts a cipher text given a key and IV.
+//
+//go:wasmimport env ccd
+func CryptoCcmDecrypt(
+	// The 16 bytes key to encrypt with.
+	key uintptr,
+	// The 8 bytes IV to encrypt with.
+	iv uintptr,
+	// Length in bytes of the `clear` text.
+	//
+	// This must be at most 251 bytes. The `cipher` length must be 4 bytes longer than
+	// this value.
+	len uint32,
+	// The cipher text to encrypt from.
+	//
+	// Its length must be `len + 4` bytes.
+	cipher uintptr,
+	// The clear text to encrypt to.
+	//
+	// Its length must be provided in the `len` field.
+	clear uintptr,
+) (
+	// Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+	// otherwise.
+	res int32,
+)
+
+// END OF MODULE crypto_ccm
+
+// START OF MODULE crypto_crc
+// Cyclic redundancy checks.
+//
+// CRC-32 is the one used by Ethernet, zlib, and PNG (also known as CRC-32/ISO-HDLC).
+// CRC-16/CCITT has initial value 0xffff and no final xor (also known as CRC-16/IBM-3740
+// or CRC-16/CCITT-FALSE). Checksums are written in little-endian.
+
+// Whether CRCs are supported.
+//
+//go:wasmimport env crs
+func CryptoCrcIsSupported() (
+	// 1 if supported, 0 otherwise.
+	supported uint32,
+)
+
+// Computes the CRC-32 of some data.
+//
+//go:wasmimport env crw
+func CryptoCrcCrc32(
+	// The pointer to the data.
+	data uintptr,
+	// The length of the data.
+	length uint32,
+	// The 4 bytes checksum.
+	crc uintptr,
+) (
+	// Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+	// otherwise.
+	res int32,
+)
+
+// Computes the CRC-16/CCITT of some data.
+//
+//go:wasmimport env crh
+func CryptoCrcCrc16Ccitt(
+	// The pointer to the data.
+	data uintptr,
+	// The length of the data.
+	length uint32,
+	// The 2 bytes checksum.
+	crc uintptr,
+) (
+	// Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+	// otherwise.
+	res int32,
+)
+
+// END OF MODULE crypto_crc
+
+// START OF MODULE crypto_ecdh
+// ECDH over P-256.
+//
+// Private keys are 32 bytes big-endian scalars. Public keys are 64 bytes uncompressed
+// points (the big-endian X coordinate followed by the Y coordinate). Shared secrets are
+// the 32 bytes X coordinate of the product point (as specified by SEC1).
+
+// Whether ECDH over P-256 is supported.
+//
+//go:wasmimport env ces
+func CryptoEcdhIsSupported() (
+	// 1 if supported, 0 otherwise.
+	supported uint32,
+)
+
+// Generates a random key pair.
+//
+//go:wasmimport env ceg
+func CryptoEcdhGenerateKeypair(
+	// The 32 bytes private key.
+	private uintptr,
+	// The 64 bytes public key.
+	public uintptr,
+) (
+	// Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+	// otherwise.
+	res int32,
+)
+
+// Computes the shared secret of a private key and a peer public key.
+//
+//go:wasmimport env cea
+func CryptoEcdhAgree(
+	// The 32 bytes private key.
+	private uintptr,
+	// The 64 bytes public key of the peer.
+	public uintptr,
+	// The 32 bytes shared secret.
+	shared uintptr,
+) (
+	// Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+	// otherwise.
+	res int32,
+)
+
+// END OF MODULE crypto_ecdh
+
+// START OF MODULE crypto_gcm
+// AES-256-GCM.
+
+// Whether AES-256-GCM is supported.
+//
+//go:wasmimport env cgs
+func CryptoGcmIsSupported() (
+	// 1 if supported, 0 otherwise.
+	supported uint32,
+)
+
+// Encrypts and authenticates a clear text with associated data given a key and IV.
+//
+//go:wasmimport env cge
+func CryptoGcmEncrypt(
+	// The 32 bytes key.
+	key uintptr,
+	// The 12 bytes IV.
+	iv uintptr,
+	// The additional authenticated data.
+	aad uintptr,
+	// The length of the additional authenticated data.
+	aad_len uint32,
+	// The length of the clear (and cipher) text.
+	length uint32,
+	// The clear text.
+	clear uintptr,
+	// The cipher text.
+	cipher uintptr,
+	// The 16 bytes authentication tag.
+	tag uintptr,
+) (
+	// Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+	// otherwise.
+	res int32,
+)
+
+// Decrypts and authenticates a cipher text with associated data given a key and IV.
+//
+//go:wasmimport env cgd
+func CryptoGcmDecrypt(
+	// The 32 bytes key.
+	key uintptr,
+	// The 12 bytes IV.
+	iv uintptr,
+	// The additional authenticated data.
+	aad uintptr,
+	// The length of the additional authenticated data.
+	aad_len uint32,
+	// The 16 bytes authentication tag.
+	tag uintptr,
+	// The length of the cipher (and clear) text.
+	length uint32,
+	// The cipher text.
+	cipher uintptr,
+	// The clear text.
+	clear uintptr,
+) (
+	// Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+	// otherwise.
+	res int32,
+)
+
+// END OF MODULE crypto_gcm
+
+// START OF MODULE crypto_hash
+// Hash functions.
+
+// Hash algorithm.
+type CryptoHashAlgorithm uint32
+
+const (
+	// SHA-256.
+	CryptoHashAlgorithmSha256 CryptoHashAlgorithm = iota
+)
+
+// Whether the algorithm is supported.
+//
+//go:wasmimport env chs
+func CryptoHashIsSupported(
+	// The hash algorithm.
+	algorithm uint32,
+) (
+	// 1 if supported, 0 otherwise.
+	supported uint32,
+)
+
+// Initializes a hash.
+//
+//go:wasmimport env chi
+func CryptoHashInitialize(
+	// The hash algorithm.
+	algorithm uint32,
+) (
+	// A non-negative identifier on success, bitwise complement of
+	// [`Error`](crate::crypto::Error) otherwise.
+	id int32,
+)
+
+// Updates a hash.
+//
+// Errors are surfaced in the [`finalize()`] call.
+//
+//go:wasmimport env chu
+func CryptoHashUpdate(
+	// The identifier returned by the associated [`initialize()`] call.
+	id uint32,
+	// The pointer to the data to hash.
+	data uintptr,
+	// The length of the data to hash.
+	length uint32,
+)
+
+// Finalizes a hash.
+//
+//go:wasmimport env chf
+func CryptoHashFinalize(
+	// The identifier returned by the associated [`initialize()`] call.
+	//
+	// This is consumed and invalidated by this call regardless of the return value.
+	id uint32,
+	// The pointer to the buffer where the digest must be written.
+	//
+	// Its length is defined by the algorithm:
+	// - 32 bytes for SHA-256.
+	//
+	// The pointer may be null, in which case this function deallocates the identifier
+	// without computing the digest.
+	digest uintptr,
+) (
+	// Zero on success, bitwise complement of [`Error`](crate::crypto::Error)
+	// otherwise.
+	res int32,
+)
+
+// END OF MODULE crypto_hash
+
+// END OF MODULE crypto
+
+// START OF MODULE debug
+// Debugging operations.
+
+// Prints a message to the debug output.
+//
+// If debug output is disabled then this is a no-op.
+//
+//go:wasmimport env dp
+func DebugPrintln(
+	// The message to print.
+	//
+	// Traps if the message is not valid UTF-8.
+	ptr uintptr,
+	// The length of the message in bytes.
+	len uint32,
+)
+
+// Reads the heap statistics of the platform.
+//
+//go:wasmimport env dh
+func DebugHeapStats(
+	// Pointer to the 12 bytes of statistics.
+	//
+	// The used, free, and high water bytes as little-endian u32.
+	ptr uintptr,
+) (
+	// Zero on success, negative on error.
+	res int32,
+)
+
+// Exits the platform with an error code.
+//
+// This is used by test applets to terminate the platform and propagate the test
+// result.
+//
+//go:wasmimport env de
+func DebugExit(
+	// 0 for success, 1 for failure
+	code uint32,
+)
+
+// Logging levels from the least to the most verbose.
+type DebugLevel uint32
+
+const (
+	DebugLevelError DebugLevel = iota
+	DebugLevelWarn
+	DebugLevelInfo
+	DebugLevelDebug
+	DebugLevelTrace
+)
+
+// Sets the most verbose level of the platform logs.
+//
+// Levels disabled when the platform was compiled can't be enabled.
+//
+//go:wasmimport env dl
+func DebugSetLogLevel(
+	// The most verbose level to log.
+	//
+	// Valid values are defined by [`Level`](super::Level).
+	level uint32,
+) (
+	// Zero on success, negative on error.
+	res int32,
+)
+
+// Logs a message at a given level in the platform logs.
+//
+// The message interleaves with the platform logs and is subject to the same level
+// filter. If logging is disabled then this is a no-op.
+//
+//go:wasmimport env dg
+func DebugLog(
+	// The level of the message.
+	//
+	// Valid values are defined by [`Level`](super::Level).
+	level uint32,
+	// The message to log.
+	//
+	// Traps if the message is not valid UTF-8.
+	ptr uintptr,
+	// The length of the message in bytes.
+	len uint32,
+)
+
+// END OF MODULE debug
+
+// START OF MODULE encoder
+// Quadrature encoder operations.
+
+// Returns how many encoders are on the device.
+//
+//go:wasmimport env ec
+func EncoderCount() (
+	// How many encoders are on the device.
+	cnt uint32,
+)
+
+// Returns the current position of an encoder.
+//
+//go:wasmimport env ep
+func EncoderPosition(
+	// Index of the encoder.
+	encoder uint32,
+) (
+	// Current position in steps.
+	position int32,
+)
+
+// Starts homing an encoder.
+//
+// The position is zeroed at the next index pulse. The shaft must be rotated while
+// homing. Register a handler to be notified when homing is done.
+//
+//go:wasmimport env eh
+func EncoderHome(
+	// Index of the encoder.
+	encoder uint32,
+) (
+	// Error code: 0 on success, -1 on error
+	res int32,
+)
+
+// Register a handler for encoder events.
+//
+//go:wasmimport env er
+func EncoderRegister(
+	// Index of the encoder to listen to.
+	encoder uint32,
+	// Function called when homing is done.
+	//
+	// The function takes its opaque `data` as argument.
+	handler_func uintptr,
+	// The opaque data to use when calling the handler function.
+	handler_data uintptr,
+)
+
+// Unregister handlers for encoder events.
+//
+//go:wasmimport env eu
+func EncoderUnregister(
+	// Index of the encoder to stop listening to.
+	encoder uint32,
+)
+
+// END OF MODULE encoder
+
+// START OF MODULE gpio
+// General-purpose input/output operations.
+
+// Returns how many GPIOs are on the device.
+//
+//go:wasmimport env gc
+func GpioCount() (
+	// How many GPIOs are on the device.
+	cnt uint32,
+)
+
+// Describes the configuration of a GPIO.
+type GpioMode uint32
+
+const (
+	// Input with an internal pull-up resistor.
+	GpioModeInputPullUp GpioMode = iota
+	// Input with an internal pull-down resistor.
+	GpioModeInputPullDown
+	// Output driving both levels.
+	GpioModeOutputPushPull
+	// Output driving only the low level.
+	GpioModeOutputOpenDrain
+)
+
+// Configures a GPIO.
+//
+//go:wasmimport env gf
+func GpioConfigure(
+	// Index of the GPIO to configure.
+	gpio uint32,
+	// The new mode of the GPIO.
+	//
+	// Valid values are defined by [`Mode`](super::Mode).
+	mode uint32,
+)
+
+// Reads the level of a configured GPIO.
+//
+//go:wasmimport env gr
+func GpioRead(
+	// Index of the GPIO to read.
+	gpio uint32,
+) (
+	// 0 for low and 1 for high.
+	level uint32,
+)
+
+// Writes the level of a GPIO configured as output.
+//
+//go:wasmimport env gw
+func GpioWrite(
+	// Index of the GPIO to write.
+	gpio uint32,
+	// 0 for low and 1 for high.
+	level uint32,
+)
+
+// END OF MODULE gpio
+
+// START OF MODULE i2c_sw
+// Software I2C operations.
+//
+// The bus is bit-banged on 2 GPIOs. It is slower than a hardware bus and the CPU is busy
+// for the whole transfer.
+
+// Configures the bus.
+//
+// This must be called before any transfer.
+//
+//go:wasmimport env isc
+func I2cSwConfigure(
+	// Index of the GPIO of the data line.
+	sda uint32,
+	// Index of the GPIO of the clock line.
+	scl uint32,
+	// Clock rate in Hertz.
+	frequency uint32,
+) (
+	// Zero on success, negative on error.
+	res int32,
+)
+
+// Reads bytes from a device into a buffer.
+//
+//go:wasmimport env isr
+func I2cSwRead(
+	// 7-bits address of the device.
+	addr uint32,
+	// Address of the buffer.
+	ptr uintptr,
+	// Length of the buffer in bytes.
+	len uint32,
+) (
+	// Zero on success, negative on error.
+	res int32,
+)
+
+// Writes bytes to a device from a buffer.
+//
+//go:wasmimport env isw
+func I2cSwWrite(
+	// 7-bits address of the device.
+	addr uint32,
+	// Address of the buffer.
+	ptr uintptr,
+	// Length of the buffer in bytes.
+	len uint32,
+) (
+	// Zero on success, negative on error.
+	res int32,
+)
+
+// Writes then reads bytes from a device with a repeated start condition.
+//
+//go:wasmimport env isx
+func I2cSwWriteRead(
+	// 7-bits address of the device.
+	addr uint32,
+	// Address of the buffer to write.
+	wptr uintptr,
+	// Length of the buffer to write in bytes.
+	wlen uint32,
+	// Address of the buffer to read.
+	rptr uintptr,
+	// Length of the buffer to read in bytes.
+	rlen uint32,
+) (
+	// Zero on success, negative on error.
+	res int32,
+)
+
+// END OF MODULE i2c_sw
+
+// START OF MODULE led
+// LED operations.
+
+// Returns how many LEDs are on the device.
+//
+//go:wasmimport env lc
+func LedCount() (
+	// How many LEDs are on the device.
+	cnt uint32,
+)
+
+// Describes the state of a LED.
+type LedStatus uint32
+
+const (
+	// The LED is off.
+	LedStatusOff LedStatus = iota
+	// The LED is on.
+	LedStatusOn
+)
+
+// Returns a LED status.
+//
+//go:wasmimport env lg
+func LedGet(
+	// Index of the LED to set.
+	led uint32,
+) (
+	// 0 for off and 1 for on.
+	status uint32,
+)
+
+// Sets a LED status.
+//
+//go:wasmimport env ls
+func LedSet(
+	// Index of the LED to set.
+	led uint32,
+	// 0 for off and 1 for on.
+	status uint32,
+)
+
+// Sets a LED brightness.
+//
+// LEDs without brightness support are on if the brightness is at least 128.
+//
+//go:wasmimport env lb
+func LedSetBrightness(
+	// Index of the LED to set.
+	led uint32,
+	// 0 for off and 255 for fully on.
+	brightness uint32,
+)
+
+// END OF MODULE led
+
+// START OF MODULE led_ring
+// Addressable LED ring operations.
+//
+// Animations are executed by the board, such that the applet only needs to select them.
+
+// Animation patterns.
+type LedRingPattern uint32
+
+const (
+	// A single lit pixel going around the ring.
+	LedRingPatternSpinner LedRingPattern = iota
+	// An arc of lit pixels growing around the ring and restarting when full.
+	LedRingPatternProgress
+	// A rainbow rotating around the ring (the color is ignored).
+	LedRingPatternRainbow
+)
+
+// Returns the number of pixels of the ring.
+//
+//go:wasmimport env lrc
+func LedRingCount() (
+	// How many pixels are available (zero if there is no ring).
+	cnt uint32,
+)
+
+// Starts an animation, replacing the current one if any.
+//
+//go:wasmimport env lra
+func LedRingAnimate(
+	// The animation pattern.
+	pattern uint32,
+	// The number of steps per second (zero freezes the first frame).
+	speed uint32,
+	// The color of the lit pixels as 0xRRGGBB.
+	color uint32,
+) (
+	// Zero on success, negative on error.
+	res int32,
+)
+
+// Stops the current animation and turns all pixels off.
+//
+//go:wasmimport env lrs
+func LedRingStop() (
+	// Zero on success, negative on error.
+	res int32,
+)
+
+// END OF MODULE led_ring
+
+// START OF MODULE magnetometer
+// Magnetometer operations.
+
+// Whether a magnetometer is available.
+//
+//go:wasmimport env fs
+func MagnetometerIsSupported() (
+	// 1 if supported, 0 otherwise.
+	supported uint32,
+)
+
+// Reads the magnetic field along the X, Y, and Z axes.
+//
+//go:wasmimport env fr
+func MagnetometerRead(
+	// Pointer to the 6 bytes of the raw field (X, Y, then Z as little-endian i16).
+	ptr uintptr,
+) (
+	// Zero on success, negative on error.
+	res int32,
+)
+
+// END OF MODULE magnetometer
+
+// START OF MODULE platform
+// Platform operations.
+
+// Describes errors of platform operations.
+type PlatformError uint32
+
+const (
+	// The operation is not supported.
+	PlatformErrorUnsupported PlatformError = iota
+	// A function pre-condition was broken.
+	PlatformErrorInvalidArgument
+)
+
+// Board capabilities that applets may query.
+//
+// Each capability is a bit in the result of `capabilities()`: bit `1 << c` is set if
+// the board supports capability `c`.
+type PlatformCapability uint32
+
+const (
+	// Analog-to-digital converters.
+	PlatformCapabilityAdc PlatformCapability = iota
+	// AES-128-CCM.
+	PlatformCapabilityAes128Ccm
+	// AES-256-GCM.
+	PlatformCapabilityAes256Gcm
+	// Buttons.
+	PlatformCapabilityButton
+	// CRC.
+	PlatformCapabilityCrc
+	// ECDH.
+	PlatformCapabilityEcdh
+	// Rotary encoders.
+	PlatformCapabilityEncoder
+	// GPIOs.
+	PlatformCapabilityGpio
+	// LEDs.
+	PlatformCapabilityLed
+	// LED rings.
+	PlatformCapabilityLedRing
+	// Magnetometer.
+	PlatformCapabilityMagnetometer
+	// PWM outputs.
+	PlatformCapabilityPwm
+	// Random number generator.
+	PlatformCapabilityRng
+	// SHA-256.
+	PlatformCapabilitySha256
+	// Stepper motors.
+	PlatformCapabilityStepper
+	// Temperature sensor.
+	PlatformCapabilityTemp
+	// Timers.
+	PlatformCapabilityTimer
+	// UARTs.
+	PlatformCapabilityUart
+	// USB serial.
+	PlatformCapabilityUsbSerial
+)
+
+// Reboots the platform.
+//
+// Pending storage operations complete before the platform resets.
+//
+//go:wasmimport env pr
+func PlatformReboot() (
+	// Complement of error number. Never returns on success.
+	res int32,
+)
+
+// Wipes the storage and reboots the platform.
+//
+// The whole storage is physically erased, including the store of all applets. The
+// applet manifest must grant the `factory-reset` permission and the applet must be
+// signed with the applet key of the runner.
+//
+//go:wasmimport env pf
+func PlatformFactoryReset() (
+	// Complement of error number. Never returns on success.
+	res int32,
+)
+
+// Returns the capabilities supported by the board.
+//
+// Applets may use it to gracefully degrade on boards missing some capabilities.
+//
+//go:wasmimport env pc
+func PlatformCapabilities() (
+	// Bit set of the supported capabilities.
+	capabilities uint32,
+)
+
+// Returns the applet API version of the platform.
+//
+// Applets may compare it with the version they were compiled against to check
+// compatibility.
+//
+//go:wasmimport env pv
+func PlatformApiVersion() (
+	// The applet API version.
+	version uint32,
+)
+
+// Reads the unique ID of the device.
+//
+// The ID is stable across resets but is not a secret.
+//
+//go:wasmimport env pi
+func PlatformDeviceId(
+	// Pointer to the 8 bytes of the device ID.
+	ptr uintptr,
+) (
+	// Complement of error number.
+	res int32,
+)
+
+// START OF MODULE platform_update
+// Firmware updates.
+//
+// The new firmware is written to a staging bank, its Ed25519 signature is verified, and
+// it is swapped in on the next reboot. The firmware ends with its version as a 32-bit
+// little-endian integer, which must not be older than the last applied firmware.
+//
+// The applet manifest must grant the `update` permission and the applet must be signed
+// with the applet key of the runner.
+
+// Whether firmware updates are supported.
+//
+//go:wasmimport env pus
+func PlatformUpdateIsSupported() (
+	// 1 if supported, 0 otherwise.
+	supported uint32,
+)
+
+// Starts a firmware update.
+//
+// Any update in progress is discarded and the staging bank is erased.
+//
+//go:wasmimport env pub
+func PlatformUpdateStart() (
+	// Zero on success, bitwise complement of [`Error`](crate::platform::Error)
+	// otherwise.
+	res int32,
+)
+
+// Writes a chunk of the firmware at an offset of the staging bank.
+//
+//go:wasmimport env puw
+func PlatformUpdateWrite(
+	// The offset in the staging bank.
+	offset uint32,
+	// The chunk.
+	ptr uintptr,
+	// The length of the chunk.
+	len uint32,
+) (
+	// Zero on success, bitwise complement of [`Error`](crate::platform::Error)
+	// otherwise.
+	res int32,
+)
+
+// Verifies the signature and version of the written firmware.
+//
+// The update must be started again if the signature is invalid or the version is
+// older than the last applied firmware.
+//
+//go:wasmimport env puf
+func PlatformUpdateFinalize(
+	// The 64 bytes Ed25519 signature of the firmware.
+	signature uintptr,
+) (
+	// Zero on success, bitwise complement of [`Error`](crate::platform::Error)
+	// otherwise.
+	res int32,
+)
+
+// Applies the finalized firmware update and reboots the platform.
+//
+//go:wasmimport env pua
+func PlatformUpdateApply() (
+	// Complement of error number. Never returns on success.
+	res int32,
+)
+
+// END OF MODULE platform_update
+
+// END OF MODULE platform
+
+// START OF MODULE power
+// Peripheral power operations.
+//
+// Peripherals are identified by their kind and an index (only used for timers, zero
+// otherwise).
+
+// Describes errors controlling peripheral power.
+type PowerError uint32
+
+const (
+	// The peripheral is invalid or not supported.
+	PowerErrorInvalid PowerError = iota
+	// The peripheral is in use.
+	PowerErrorBusy
+)
+
+// Kinds of peripherals.
+type PowerPeripheral uint32
+
+const (
+	// The analog-to-digital converters.
+	PowerPeripheralAdc PowerPeripheral = iota
+	// The magnetometer.
+	PowerPeripheralMagnetometer
+	// The random number generator.
+	PowerPeripheralRng
+	// A timer.
+	PowerPeripheralTimer
+	// The USB device.
+	PowerPeripheralUsb
+)
+
+// Disables a peripheral to save power.
+//
+// A disabled peripheral can't be used until enabled again.
+//
+//go:wasmimport env pd
+func PowerDisablePeripheral(
+	// Kind of the peripheral.
+	peripheral uint32,
+	// Index of the peripheral.
+	index uint32,
+) (
+	// Zero for success. Otherwise complement of error number.
+	res int32,
+)
+
+// Enables a peripheral.
+//
+//go:wasmimport env pe
+func PowerEnablePeripheral(
+	// Kind of the peripheral.
+	peripheral uint32,
+	// Index of the peripheral.
+	index uint32,
+) (
+	// Zero for success. Otherwise complement of error number.
+	res int32,
+)
+
+// END OF MODULE power
+
+// START OF MODULE pwm
+// PWM output operations.
+
+// Returns how many PWM outputs are on the device.
+//
+//go:wasmimport env wc
+func PwmCount() (
+	// How many PWM outputs are on the device.
+	cnt uint32,
+)
+
+// Configures and starts a PWM output.
+//
+//go:wasmimport env wg
+func PwmConfigure(
+	// Index of the PWM output to configure.
+	pwm uint32,
+	// Frequency in Hz.
+	freq_hz uint32,
+	// Duty cycle in percent (at most 100).
+	//
+	// A duty cycle of 0 (resp. 100) is a steady low (resp. high) output.
+	duty_pct uint32,
+) (
+	// Zero on success, negative on error.
+	res int32,
+)
+
+// Stops a PWM output.
+//
+// The output is steady low afterwards.
+//
+//go:wasmimport env ws
+func PwmStop(
+	// Index of the PWM output to stop.
+	pwm uint32,
+) (
+	// Zero on success, negative on error.
+	res int32,
+)
+
+// END OF MODULE pwm
+
+// START OF MODULE rng
+// Random number generators.
+
+// Fills a slice with random bytes.
+//
+//go:wasmimport env rb
+func RngFillBytes(
+	// The slice to fill.
+	ptr uintptr,
+	// The length of the slice.
+	len uint32,
+) (
+	// Error code: 0 on success, -1 on error
+	//
+	// The buffer may be modified on error and should not be used.
+	res int32,
+)
+
+// END OF MODULE rng
+
+// START OF MODULE scheduling
+
+// Waits until a callback is scheduled.
+//
+// This can be used as power management, since the CPU will sleep while waiting.
+//
+//go:wasmimport env sw
+func SchedulingWaitForCallback()
+
+// Waits until the board produces an event.
+//
+// Unlike `wait_for_callback`, this returns after the next board event even if it
+// doesn't schedule a callback. Pending callbacks are executed before returning. The
+// CPU sleeps while waiting.
+//
+//go:wasmimport env se
+func SchedulingWaitForEvent()
+
+// Returns how many callbacks are pending.
+//
+//go:wasmimport env sh
+func SchedulingNumPendingCallbacks() (
+	// How many callbacks are pending.
+	count uint32,
+)
+
+// END OF MODULE scheduling
+
+// START OF MODULE stepper
+// Stepper motor operations.
+
+// Returns how many stepper motors are on the device.
+//
+//go:wasmimport env mc
+func StepperCount() (
+	// How many stepper motors are on the device.
+	cnt uint32,
+)
+
+// Starts moving a stepper motor to an absolute position.
+//
+// The move follows a trapezoidal acceleration profile and is executed by the board.
+// Register a handler to be notified when the move is done.
+//
+//go:wasmimport env mm
+func StepperMoveTo(
+	// Index of the stepper motor to move.
+	stepper uint32,
+	// Target position in steps.
+	position int32,
+	// Maximum speed in steps per second.
+	max_speed uint32,
+	// Acceleration in steps per second squared.
+	acceleration uint32,
+) (
+	// Error code: 0 on success, -1 on error
+	//
+	// It is an error to start a move while the motor is still moving.
+	res int32,
+)
+
+// Returns the current position of a stepper motor.
+//
+//go:wasmimport env mp
+func StepperPosition(
+	// Index of the stepper motor.
+	stepper uint32,
+) (
+	// Current position in steps.
+	position int32,
+)
+
+// Register a handler for stepper motor events.
+//
+//go:wasmimport env mr
+func StepperRegister(
+	// Index of the stepper motor to listen to.
+	stepper uint32,
+	// Function called when a move is done.
+	//
+	// The function takes its opaque `data` as argument.
+	handler_func uintptr,
+	// The opaque data to use when calling the handler function.
+	handler_data uintptr,
+)
+
+// Unregister handlers for stepper motor events.
+//
+//go:wasmimport env mu
+func StepperUnregister(
+	// Index of the stepper motor to stop listening to.
+	stepper uint32,
+)
+
+// END OF MODULE stepper
+
+// START OF MODULE store
+// Persistent storage operations.
+
+// Describes errors interacting with the store.
+type StoreError uint32
+
+const (
+	// A function pre-condition was broken.
+	StoreErrorInvalidArgument StoreError = iota
+	// The store is full.
+	StoreErrorNoCapacity
+	// The store reached its end of life.
+	StoreErrorNoLifetime
+	// An operation to the underlying storage failed.
+	StoreErrorStorageError
+	// The underlying storage doesn't match the store invariant.
+	StoreErrorInvalidStorage
+)
+
+// Inserts an entry in the store.
+//
+// If an entry for that key was already present, it is overwritten.
+//
+//go:wasmimport env si
+func StoreInsert(
+	// Key of the entry.
+	//
+	// This must be smaller than 4032. Larger keys are reserved for the platform, but
+	// entries inserted there by older platforms can still be found and removed.
+	key uint32,
+	// Value of the entry.
+	ptr uintptr,
+	// Length of the value.
+	len uint32,
+) (
+	// Zero for success. Otherwise complement of error number.
+	res int32,
+)
+
+// Removes an entry from the store.
+//
+// This is not an error if no entry is present. This is simply a no-op in that case.
+//
+//go:wasmimport env sr
+func StoreRemove(
+	// Key of the entry.
+	key uint32,
+) (
+	// Zero for success. Otherwise complement of error number.
+	res int32,
+)
+
+// Finds an entry in the store, if any.
+//
+//go:wasmimport env sf
+func StoreFind(
+	// Key of the entry to find.
+	key uint32,
+	// Where to write the value of the entry, if found.
+	//
+	// The (inner) pointer will be allocated by the callee and must be freed by the
+	// caller. It is thus owned by the caller when the function returns.
+	ptr uintptr,
+	// Where to write the length of the value, if found.
+	len uintptr,
+) (
+	// One if found. Zero if not found. Otherwise complement of error number.
+	res int32,
+)
+
+// START OF MODULE store_fragment
+// Support for fragmented entries.
+//
+// Fragmented entries span a range of keys and may thus be larger than the maximum value
+// length of a single entry. They are meant for large buffers (e.g. images or firmware).
+//
+// The functions of this module transfer the whole buffer in a single call: the runner
+// directly reads from (or writes to) the region of the applet linear memory described by
+// the pointer and length. The region is only accessed for the duration of the call and the
+// runner does not retain it afterwards. The applet must not assume anything about the
+// content of the region if the call fails.
+
+// Inserts a fragmented entry in the store.
+//
+// If an entry for that range of keys was already present, it is overwritten.
+//
+//go:wasmimport env sfi
+func StoreFragmentInsert(
+	// Range of keys.
+	//
+	// The first key is the lower 16 bits and the one past the last key is the upper 16
+	// bits. The range must be non-empty and the keys smaller than 4032.
+	keys uint32,
+	// Value of the entry.
+	ptr uintptr,
+	// Length of the value.
+	//
+	// This must fit in the range of keys.
+	len uint32,
+) (
+	// Zero for success. Otherwise complement of error number.
+	res int32,
+)
+
+// Removes a fragmented entry from the store.
+//
+// This is not an error if no entry is present. This is simply a no-op in that case.
+//
+//go:wasmimport env sfr
+func StoreFragmentRemove(
+	// Range of keys (same encoding as for `insert`).
+	keys uint32,
+) (
+	// Zero for success. Otherwise complement of error number.
+	res int32,
+)
+
+// Reads part of a fragmented entry from the store.
+//
+// The runner writes the bytes of the entry starting at `offset` directly into the
+// buffer. Large entries may thus be read in a single call, or in a few calls at
+// increasing offsets when the applet can't afford a buffer for the whole entry.
+//
+//go:wasmimport env sfo
+func StoreFragmentRead(
+	// Range of keys (same encoding as for `insert`).
+	keys uint32,
+	// Offset in the entry of the first byte to read.
+	offset uint32,
+	// Where to write the bytes read.
+	ptr uintptr,
+	// Length of the buffer.
+	len uint32,
+) (
+	// Number of bytes read. Otherwise complement of error number.
+	//
+	// This is smaller than the length of the buffer when the end of the entry is
+	// reached. It is zero if the entry is not found or the offset is past its end.
+	res int32,
+)
+
+// END OF MODULE store_fragment
+
+// END OF MODULE store
+
+// START OF MODULE temp
+// Temperature sensor operations.
+
+// Whether a temperature sensor is available.
+//
+//go:wasmimport env hs
+func TempIsSupported() (
+	// 1 if supported, 0 otherwise.
+	supported uint32,
+)
+
+// Reads the temperature.
+//
+//go:wasmimport env hr
+func TempRead(
+	// Pointer to the 4 bytes of the temperature in millidegrees Celsius (little-endian
+	// i32).
+	ptr uintptr,
+) (
+	// Zero on success, negative on error.
+	res int32,
+)
+
+// END OF MODULE temp
+
+// START OF MODULE uart
+// UART operations.
+//
+// These are serial ports on physical pins, distinct from the USB serial. A board may have
+// multiple UARTs. They are identified by their index, starting at zero.
+
+// Returns the number of UARTs.
+//
+//go:wasmimport env uac
+func UartCount() (
+	// How many UARTs are available.
+	cnt uint32,
+)
+
+// Sets the baud rate of a UART.
+//
+//go:wasmimport env uab
+func UartSetBaudrate(
+	// Index of the UART.
+	uart uint32,
+	// Baud rate in bits per second.
+	baudrate uint32,
+) (
+	// Zero on success, -1 on error (e.g. unsupported baud rate).
+	res int32,
+)
+
+// Reads from a UART into a buffer.
+//
+//go:wasmimport env uar
+func UartRead(
+	// Index of the UART.
+	uart uint32,
+	// Address of the buffer.
+	ptr uintptr,
+	// Length of the buffer in bytes.
+	len uint32,
+) (
+	// Number of bytes read (or negative value for errors).
+	//
+	// This function does not block and may return zero.
+	len int32,
+)
+
+// Writes to a UART from a buffer.
+//
+//go:wasmimport env uaw
+func UartWrite(
+	// Index of the UART.
+	uart uint32,
+	// Address of the buffer.
+	ptr uintptr,
+	// Length of the buffer in bytes.
+	len uint32,
+) (
+	// Number of bytes written (or negative value for errors).
+	//
+	// This function does not block and may return zero.
+	len int32,
+)
+
+// Flushes a UART.
+//
+//go:wasmimport env uaf
+func UartFlush(
+	// Index of the UART.
+	uart uint32,
+) (
+	// Zero on success, -1 on error.
+	res int32,
+)
+
+// UART events.
+type UartEvent uint32
+
+const (
+	// Ready for read.
+	UartEventRead UartEvent = iota
+	// Ready for write.
+	UartEventWrite
+)
+
+// Registers a callback when a UART is ready.
+//
+// It is possible that the callback is spuriously called.
+//
+//go:wasmimport env uae
+func UartRegister(
+	uart uint32,
+	event uint32,
+	handler_func uintptr,
+	handler_data uintptr,
+)
+
+// Unregisters a callback.
+//
+//go:wasmimport env uad
+func UartUnregister(
+	uart uint32,
+	event uint32,
+)
+
+// END OF MODULE uart
+
+// START OF MODULE usb
+// USB operations.
+
+// Describes errors on USB operations.
+type UsbError uint32
+
+const (
+	UsbErrorUnknown UsbError = iota
+)
+
+// START OF MODULE usb_hid
+// USB HID operations.
+//
+// Reports are always 64 bytes long.
+
+// Reads a report from USB HID into a 64-bytes buffer.
+//
+//go:wasmimport env uhr
+func UsbHidRead(
+	// Address of the 64-bytes buffer.
+	ptr uintptr,
+) (
+	// One if a report was read, zero if there is nothing to read, and negative value
+	// for errors.
+	//
+	// This function does not block.
+	res int32,
+)
+
+// Writes a report to USB HID from a 64-bytes buffer.
+//
+//go:wasmimport env uhw
+func UsbHidWrite(
+	// Address of the 64-bytes buffer.
+	ptr uintptr,
+) (
+	// One if the report was written, zero if the other side is not ready, and
+	// negative value for errors.
+	//
+	// This function does not block.
+	res int32,
+)
+
+// USB HID events.
+type UsbHidEvent uint32
+
+const (
+	// Ready for read.
+	UsbHidEventRead UsbHidEvent = iota
+	// Ready for write.
+	UsbHidEventWrite
+)
+
+// Registers a callback when USB HID is ready.
+//
+// It is possible that the callback is spuriously called.
+//
+//go:wasmimport env uhe
+func UsbHidRegister(
+	event uint32,
+	handler_func uintptr,
+	handler_data uintptr,
+)
+
+// Unregisters a callback.
+//
+//go:wasmimport env uhd
+func UsbHidUnregister(
+	event uint32,
+)
+
+// END OF MODULE usb_hid
+
+// START OF MODULE usb_serial
+// USB serial operations.
+//
+// A board may have multiple serial ports. They are identified by their index, starting at
+// zero.
+
+// Returns the number of serial ports.
+//
+//go:wasmimport env usc
+func UsbSerialCount() (
+	// How many serial ports are available.
+	cnt uint32,
+)
+
+// Reads from a USB serial port into a buffer.
+//
+//go:wasmimport env usr
+func UsbSerialRead(
+	// Index of the serial port.
+	port uint32,
+	// Address of the buffer.
+	ptr uintptr,
+	// Length of the buffer in bytes.
+	len uint32,
+) (
+	// Number of bytes read (or negative value for errors).
+	//
+	// This function does not block and may return zero.
+	len int32,
+)
+
+// Writes to a USB serial port from a buffer.
+//
+//go:wasmimport env usw
+func UsbSerialWrite(
+	// Index of the serial port.
+	port uint32,
+	// Address of the buffer.
+	ptr uintptr,
+	// Length of the buffer in bytes.
+	len uint32,
+) (
+	// Number of bytes written (or negative value for errors).
+	//
+	// This function does not block and may return zero.
+	len int32,
+)
+
+// USB serial events.
+type UsbSerialEvent uint32
+
+const (
+	// Ready for read.
+	UsbSerialEventRead UsbSerialEvent = iota
+	// Ready for write.
+	UsbSerialEventWrite
+	// Line state changed.
+	UsbSerialEventControl
+	// Line coding changed.
+	UsbSerialEventCoding
+)
+
+// Number of stop bits.
+type UsbSerialStopBits uint32
+
+const (
+	UsbSerialStopBitsOne UsbSerialStopBits = iota
+	UsbSerialStopBitsOnePointFive
+	UsbSerialStopBitsTwo
+)
+
+// Parity type.
+type UsbSerialParity uint32
+
+const (
+	UsbSerialParityNone UsbSerialParity = iota
+	UsbSerialParityOdd
+	UsbSerialParityEven
+	UsbSerialParityMark
+	UsbSerialParitySpace
+)
+
+// Registers a callback when a USB serial port is ready.
+//
+// It is possible that the callback is spuriously called.
+//
+//go:wasmimport env use
+func UsbSerialRegister(
+	port uint32,
+	event uint32,
+	handler_func uintptr,
+	handler_data uintptr,
+)
+
+// Unregisters a callback.
+//
+//go:wasmimport env usd
+func UsbSerialUnregister(
+	port uint32,
+	event uint32,
+)
+
+// Flushs a USB serial port.
+//
+//go:wasmimport env usf
+func UsbSerialFlush(
+	// Index of the serial port.
+	port uint32,
+) (
+	// Zero on success, -1 on error.
+	res int32,
+)
+
+// Flushes a USB serial port, waiting at most a given duration.
+//
+//go:wasmimport env ust
+func UsbSerialFlushTimeout(
+	// Index of the serial port.
+	port uint32,
+	// Maximum duration to wait in milliseconds.
+	timeout_ms uint32,
+) (
+	// Zero on success, -1 on error (including timeout).
+	res int32,
+)
+
+// Returns the line state of a USB serial port.
+//
+//go:wasmimport env usl
+func UsbSerialLineState(
+	// Index of the serial port.
+	port uint32,
+) (
+	// Bit 0 is DTR and bit 1 is RTS (or -1 on error).
+	res int32,
+)
+
+// Returns the line coding of a USB serial port.
+//
+//go:wasmimport env usk
+func UsbSerialLineCoding(
+	// Index of the serial port.
+	port uint32,
+	// Pointer to the 8 bytes of the line coding.
+	//
+	// The data rate in bits per second as little-endian u32, followed by the stop
+	// bits, the parity, the number of data bits, and a zero byte.
+	ptr uintptr,
+) (
+	// Zero on success, -1 on error.
+	res int32,
+)
+
+// END OF MODULE usb_serial
+
+// END OF MODULE usb
+
+// Board-specific syscalls.
+//
+// Those calls are forwarded by the scheduler.
+//
+//go:wasmimport env s
+func Syscall(
+	x1 uint32,
+	x2 uint32,
+	x3 uint32,
+	x4 uint32,
+) (
+	res int32,
+)
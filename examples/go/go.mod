@@ -0,0 +1,3 @@
+module github.com/google/wasefire/examples/go
+
+go 1.20
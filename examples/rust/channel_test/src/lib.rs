@@ -0,0 +1,80 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests that HMAC and authenticated channels are working properly.
+
+#![no_std]
+wasefire::applet!();
+
+use wasefire::crypto::channel::{Channel, Error, StoreCounter};
+use wasefire::crypto::hash::{is_supported, Algorithm};
+use wasefire::crypto::hmac::hmac_sha256;
+
+fn main() {
+    if !is_supported(Algorithm::Sha256) {
+        debug!("SHA-256 is not supported.");
+        debug::exit(true);
+    }
+    test_hmac();
+    test_replay();
+    debug::exit(true);
+}
+
+fn test_hmac() {
+    debug!("test_hmac(): Compute the MAC of test vectors.");
+    // Those test vectors are taken from RFC 4231 (test cases 2 and 6).
+    let mac = hmac_sha256(b"Jefe", b"what do ya want for nothing?").unwrap();
+    debug::assert_eq(
+        &mac[..],
+        &[
+            0x5b, 0xdc, 0xc1, 0x46, 0xbf, 0x60, 0x75, 0x4e, 0x6a, 0x04, 0x24, 0x26, 0x08, 0x95,
+            0x75, 0xc7, 0x5a, 0x00, 0x3f, 0x08, 0x9d, 0x27, 0x39, 0x83, 0x9d, 0xec, 0x58, 0xb9,
+            0x64, 0xec, 0x38, 0x43,
+        ],
+    );
+    let mac = hmac_sha256(&[0xaa; 131], b"Test Using Larger Than Block-Size Key - Hash Key First")
+        .unwrap();
+    debug::assert_eq(
+        &mac[..],
+        &[
+            0x60, 0xe4, 0x31, 0x59, 0x1e, 0xe0, 0xb6, 0x7f, 0x0d, 0x8a, 0x26, 0xaa, 0xcb, 0xf5,
+            0xb7, 0x7f, 0x8e, 0x0b, 0xc6, 0x21, 0x37, 0x28, 0xc5, 0x14, 0x05, 0x46, 0x04, 0x0f,
+            0x0e, 0xe3, 0x7f, 0x54,
+        ],
+    );
+}
+
+fn test_replay() {
+    debug!("test_replay(): Replayed messages are rejected.");
+    const KEY: &[u8] = b"channel test key";
+    for key in [0, 1] {
+        store::remove(key).unwrap();
+    }
+    let mut sender = Channel::new(KEY, StoreCounter::new(0));
+    let mut receiver = Channel::new(KEY, StoreCounter::new(1));
+    let first = sender.seal(b"first").unwrap();
+    let second = sender.seal(b"second").unwrap();
+    debug!("- fresh message is accepted");
+    debug::assert_eq(&receiver.open(&first), &Ok(&b"first"[..]));
+    debug!("- replayed message is rejected");
+    debug::assert_eq(&receiver.open(&first), &Err(Error::Replayed));
+    debug!("- tampered message is rejected");
+    let mut tampered = second.clone();
+    tampered[8] ^= 1;
+    debug::assert_eq(&receiver.open(&tampered), &Err(Error::Invalid));
+    debug!("- next fresh message is accepted");
+    debug::assert_eq(&receiver.open(&second), &Ok(&b"second"[..]));
+    debug!("- out-of-order message is rejected");
+    debug::assert_eq(&receiver.open(&first), &Err(Error::Replayed));
+}
@@ -15,10 +15,10 @@
 //! Demonstrates simple USB serial usage.
 //!
 //! The applet simply echoes its input, except for alphabetic ASCII characters. Their case is
-//! toggled first.
+//! toggled first. The applet doesn't need an allocator.
 
 #![no_std]
-wasefire::applet!();
+wasefire::applet!(no_alloc);
 
 fn main() {
     loop {
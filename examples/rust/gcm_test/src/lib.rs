@@ -21,6 +21,7 @@ fn main() {
     if crypto::gcm::is_supported() {
         test_encrypt();
         test_decrypt();
+        test_tampered();
     }
     debug::exit(true);
 }
@@ -45,6 +46,21 @@ fn test_decrypt() {
     }
 }
 
+fn test_tampered() {
+    debug!("test_tampered(): Rejects tampered tags and cipher texts.");
+    for &Vector { key, iv, aad, cipher, tag, .. } in TEST_VECTORS {
+        debug!("- {} bytes", cipher.len());
+        let mut tampered = crypto::gcm::Cipher { text: cipher.to_vec(), tag: *tag };
+        tampered.tag[0] ^= 1;
+        debug::assert(crypto::gcm::decrypt(key, iv, aad, &tampered).is_err());
+        if !cipher.is_empty() {
+            let mut tampered = crypto::gcm::Cipher { text: cipher.to_vec(), tag: *tag };
+            tampered.text[0] ^= 1;
+            debug::assert(crypto::gcm::decrypt(key, iv, aad, &tampered).is_err());
+        }
+    }
+}
+
 struct Vector {
     key: &'static [u8; 32],
     iv: &'static [u8; 12],
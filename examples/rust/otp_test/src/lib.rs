@@ -0,0 +1,88 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests that one-time passwords are working properly.
+
+#![no_std]
+wasefire::applet!();
+
+use wasefire::crypto::hash;
+use wasefire::crypto::otp::{Algorithm, Otp};
+
+fn main() {
+    test_totp_sha1();
+    test_totp_sha256();
+    test_invalid();
+    debug::exit(true);
+}
+
+fn test_totp_sha1() {
+    debug!("test_totp_sha1(): Compute the codes of RFC 6238 test vectors.");
+    let otp = Otp { algorithm: Algorithm::Sha1, digits: 8 };
+    for &(time, code) in TOTP_SHA1_VECTORS {
+        debug!("- {time}");
+        debug::assert_eq(&otp.totp(SECRET_SHA1, time, 30).unwrap(), &code);
+    }
+    // The default is 6 digits with SHA-1, which is the suffix of the 8-digit code.
+    debug::assert_eq(&crypto::otp::totp(SECRET_SHA1, 59, 30).unwrap(), &287082);
+    // TOTP is HOTP with the time step as counter.
+    debug::assert_eq(&otp.hotp(SECRET_SHA1, 1).unwrap(), &94287082);
+}
+
+fn test_totp_sha256() {
+    debug!("test_totp_sha256(): Compute the codes of RFC 6238 test vectors.");
+    if !hash::is_supported(hash::Algorithm::Sha256) {
+        debug!("- not supported");
+        return;
+    }
+    let otp = Otp { algorithm: Algorithm::Sha256, digits: 8 };
+    for &(time, code) in TOTP_SHA256_VECTORS {
+        debug!("- {time}");
+        debug::assert_eq(&otp.totp(SECRET_SHA256, time, 30).unwrap(), &code);
+    }
+    // TOTP is HOTP with the time step as counter.
+    debug::assert_eq(&otp.hotp(SECRET_SHA256, 1).unwrap(), &46119246);
+}
+
+fn test_invalid() {
+    debug!("test_invalid(): Check invalid parameters are rejected.");
+    let otp = Otp { digits: 9, ..Otp::default() };
+    debug::assert_eq(&otp.hotp(SECRET_SHA256, 0), &Err(crypto::Error::InvalidArgument));
+    debug::assert_eq(
+        &crypto::otp::totp(SECRET_SHA256, 59, 0),
+        &Err(crypto::Error::InvalidArgument),
+    );
+}
+
+const SECRET_SHA1: &[u8] = b"12345678901234567890";
+const SECRET_SHA256: &[u8] = b"12345678901234567890123456789012";
+
+// Those test vectors are taken from RFC 6238 appendix B.
+const TOTP_SHA1_VECTORS: &[(u64, u32)] = &[
+    (59, 94287082),
+    (1111111109, 7081804),
+    (1111111111, 14050471),
+    (1234567890, 89005924),
+    (2000000000, 69279037),
+    (20000000000, 65353130),
+];
+
+const TOTP_SHA256_VECTORS: &[(u64, u32)] = &[
+    (59, 46119246),
+    (1111111109, 68084774),
+    (1111111111, 67062674),
+    (1234567890, 91819424),
+    (2000000000, 90698825),
+    (20000000000, 77737706),
+];
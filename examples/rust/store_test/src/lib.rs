@@ -23,6 +23,7 @@ fn main() {
     test_insert();
     test_remove();
     test_find();
+    test_fragment();
     debug::exit(true);
 }
 
@@ -67,17 +68,41 @@ fn test_find() {
     }
 }
 
+fn test_fragment() {
+    debug!("test_fragment(): Transfers a large entry in single calls.");
+    let keys = 300 .. 310;
+    let value: Vec<u8> = (0 .. 3000).map(|i| i as u8).collect();
+    debug!("- Insert {:?} with {} bytes", keys, value.len());
+    store::fragment::insert(keys.clone(), &value).unwrap();
+    debug!("- Read the whole entry");
+    let mut actual = alloc::vec![0; value.len()];
+    let len = store::fragment::read(keys.clone(), 0, &mut actual).unwrap();
+    debug::assert_eq(&actual[.. len], &value[..]);
+    debug!("- Read across a fragment boundary");
+    let mut actual = [0; 100];
+    let len = store::fragment::read(keys.clone(), 1000, &mut actual).unwrap();
+    debug::assert_eq(&actual[.. len], &value[1000 .. 1100]);
+    debug!("- Read past the end");
+    debug::assert_eq(&store::fragment::read(keys.clone(), 3000, &mut actual), &Ok(0));
+    debug!("- Remove {:?}", keys);
+    store::fragment::remove(keys.clone()).unwrap();
+    debug::assert_eq(&store::fragment::read(keys, 0, &mut actual), &Ok(0));
+}
+
 const INSERTED: &[usize] = &[0, 1, 2, 3, 100, 500, 1000, 2000];
 const REMOVED: &[usize] = &[2, 500];
 
+/// Last key available to applets (the next ones are reserved for the platform).
+const LAST_KEY: usize = 4031;
+
 fn reverse(key: usize) -> usize {
-    4095 - key
+    LAST_KEY - key
 }
 
 fn value(mut key: usize) -> Vec<u8> {
-    let reversed = key & 0x800 != 0;
+    let reversed = LAST_KEY / 2 < key;
     if reversed {
-        key = 4095 - key;
+        key = reverse(key);
     }
     let val = key as u8;
     let mut len = val as usize;
@@ -36,4 +36,14 @@ mod tests {
         // We want to make sure types in core and alloc also work in tests.
         assert_eq!(make().get(), 18);
     }
+
+    #[test]
+    fn test_zeroize() {
+        let mut buf = [0x5a; 16];
+        crypto::zeroize(&mut buf);
+        assert_eq!(buf, [0; 16]);
+        let mut buf = [0x5a; 16];
+        drop(crypto::Zeroizing::new(&mut buf[..]));
+        assert_eq!(buf, [0; 16]);
+    }
 }
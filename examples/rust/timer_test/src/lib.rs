@@ -64,9 +64,14 @@ fn test_oneshot() {
         }
     });
     debug!("+ start timer");
+    let start = clock::uptime();
     timer.start_ms(clock::Oneshot, 1000);
     scheduling::wait_until(|| done.get());
     debug::assert(done.get());
+    // The uptime clock and the timers may use different hardware with different precisions.
+    let elapsed = clock::uptime() - start;
+    debug!("- elapsed {:?}", elapsed);
+    debug::assert(core::time::Duration::from_millis(990) <= elapsed);
 }
 
 fn test_oneshot_cancel() {
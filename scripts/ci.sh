@@ -22,6 +22,10 @@ for lang in $(ls examples); do
   for name in $(ls examples/$lang); do
     [ $lang = assemblyscript -a $name = node_modules ] && continue
     [ $lang = assemblyscript -a $name = api.ts ] && continue
+    [ $lang = assemblyscript -a $name = api.d.ts ] && continue
+    [ $lang = c -a $name = api.h ] && continue
+    [ $lang = go -a $name = api ] && continue
+    [ $lang = go -a $name = go.mod ] && continue
     x cargo xtask applet $lang $name
     x cargo xtask --release applet $lang $name
   done
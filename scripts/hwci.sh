@@ -31,3 +31,5 @@ done
 for name in $(list); do
   x cargo xtask --release applet rust $name runner "$@"
 done
+# The store test calls store::find() which returns multiple values with multivalue.
+x cargo xtask --multivalue applet rust store_test runner "$@"
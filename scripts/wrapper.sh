@@ -69,6 +69,7 @@ ensure_bin() {
 
 case "$1" in
   npm) ensure_bin npm ;;
+  tinygo) e 'Install TinyGo from https://tinygo.org/getting-started/install' ;;
   wasm-opt) ensure_bin binaryen ;;
   wasm-strip) ensure_bin wabt ;;
   *) e "Wrapper does not support '$1'" ;;